@@ -120,6 +120,28 @@ pub(crate) mod function {
                 }
                 Ok(())
             }
+            Status::ChangeViaDumbHttp {
+                update_refs,
+                write_pack_bundles,
+                loose_objects,
+            } => {
+                print_updates(
+                    &repo,
+                    &Default::default(),
+                    update_refs,
+                    ref_specs,
+                    res.ref_map,
+                    &mut out,
+                    err,
+                )?;
+                for write_pack_bundle in write_pack_bundles {
+                    if let Some(data_path) = write_pack_bundle.data_path {
+                        writeln!(out, "pack  file: \"{}\"", data_path.display()).ok();
+                    }
+                }
+                writeln!(out, "received {loose_objects} loose object(s) via dumb HTTP").ok();
+                Ok(())
+            }
         }?;
         if dry_run {
             writeln!(out, "DRY-RUN: No ref was updated and no pack was received.").ok();
@@ -1,36 +1,209 @@
-use anyhow::Context;
-use gix::{objs::Kind, ObjectId};
+use std::{path::Path, sync::atomic::AtomicBool};
 
-pub fn function(mut repo: gix::Repository, spec: Option<String>, mut out: impl std::io::Write) -> anyhow::Result<()> {
-    let spec = spec.unwrap_or("HEAD".into());
+use anyhow::{bail, Context};
+use gix::{
+    config::tree::{Fsck, Key},
+    objs::Kind,
+    ObjectId,
+};
+use gix_fsck::{
+    full,
+    message::{self, Severity},
+};
 
+pub struct Options {
+    /// If set, only check connectivity starting from this revision, instead of all references, their reflogs and the index.
+    pub spec: Option<String>,
+    /// Print objects that aren't reachable.
+    pub unreachable: bool,
+    /// Print objects that are unreachable and not referenced by any other object.
+    pub dangling: bool,
+    /// Write dangling objects into `.git/lost-found`.
+    pub lost_found: bool,
+}
+
+pub fn function(
+    mut repo: gix::Repository,
+    Options {
+        spec,
+        unreachable,
+        dangling,
+        lost_found,
+    }: Options,
+    mut out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
     // We expect to be finding a bunch of non-existent objects here - never refresh the ODB
     repo.objects.refresh_never();
 
-    let id = repo
-        .rev_parse_single(spec.as_str())
-        .context("Only single revisions are supported")?;
-    let commits: gix::revision::Walk<'_> = id
-        .object()?
-        .peel_to_kind(gix::object::Kind::Commit)
-        .context("Need committish as starting point")?
-        .id()
-        .ancestors()
-        .all()?;
-
-    let on_missing = |oid: &ObjectId, kind: Kind| {
-        writeln!(out, "{oid}: {kind}").expect("failed to write output");
+    let tips = match spec {
+        Some(spec) => vec![repo
+            .rev_parse_single(spec.as_str())
+            .context("Only single revisions are supported")?
+            .detach()],
+        None => starting_points(&repo)?,
     };
+    let options = options(&repo)?;
+    let objects = repo.objects.iter()?.filter_map(Result::ok);
+    let outcome = full::check(&repo.objects, objects, tips, &options, should_interrupt)?;
+
+    for problem in &outcome.problems {
+        let severity = if problem.severity.is_error() {
+            "error"
+        } else {
+            "warning"
+        };
+        writeln!(
+            out,
+            "{severity} in {kind} {id}: {message}: {description}",
+            kind = problem.kind,
+            id = problem.id,
+            message = problem.message,
+            description = problem.description
+        )?;
+    }
+    for mismatch in &outcome.hash_mismatches {
+        writeln!(
+            out,
+            "error: hash mismatch for {kind} {expected} (actual {actual})",
+            kind = mismatch.kind,
+            expected = mismatch.expected,
+            actual = mismatch.actual
+        )?;
+    }
+    for (id, err) in &outcome.corrupt {
+        writeln!(out, "error: {id}: object corrupt or unreadable: {err}")?;
+    }
+    for missing in &outcome.missing {
+        let kind = missing.kind.map_or("object".into(), |kind| kind.to_string());
+        if let Some(referenced_by) = missing.referenced_by {
+            writeln!(
+                out,
+                "broken link from {referenced_by}\n              to {kind} {}",
+                missing.id
+            )?;
+        }
+        writeln!(out, "missing {kind} {}", missing.id)?;
+    }
+    if unreachable {
+        for (id, kind) in &outcome.unreachable {
+            writeln!(out, "unreachable {kind} {id}")?;
+        }
+    }
+    if dangling {
+        for (id, kind) in &outcome.dangling {
+            writeln!(out, "dangling {kind} {id}")?;
+        }
+    }
+    if lost_found {
+        write_lost_found(&repo, &outcome.dangling)?;
+    }
+
+    if outcome.has_errors() {
+        bail!("Found errors in the object database")
+    }
+    Ok(())
+}
+
+/// Collect all objects referenced by `HEAD`, references and their reflogs as well as the index.
+fn starting_points(repo: &gix::Repository) -> anyhow::Result<Vec<ObjectId>> {
+    let mut tips = Vec::new();
+    let add_reflog =
+        |tips: &mut Vec<ObjectId>, mut log: gix::refs::file::log::iter::Platform<'_, '_>| -> anyhow::Result<()> {
+            if let Some(lines) = log.all()? {
+                for line in lines {
+                    let line = line?;
+                    tips.extend(
+                        [line.previous_oid(), line.new_oid()]
+                            .into_iter()
+                            .filter(|id| !id.is_null()),
+                    );
+                }
+            }
+            Ok(())
+        };
+
+    let head = repo.head()?;
+    tips.extend(head.id().map(gix::Id::detach));
+    add_reflog(&mut tips, head.log_iter())?;
+    for reference in repo.references()?.all()? {
+        let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+        tips.extend(reference.try_id().map(gix::Id::detach));
+        add_reflog(&mut tips, reference.log_iter())?;
+    }
+
+    if let Some(index) = repo.try_index()? {
+        tips.extend(
+            index
+                .entries()
+                .iter()
+                .filter(|entry| entry.mode != gix::index::entry::Mode::COMMIT)
+                .map(|entry| entry.id),
+        );
+        let mut trees: Vec<_> = index.tree().into_iter().collect();
+        while let Some(tree) = trees.pop() {
+            if tree.num_entries.is_some() {
+                tips.push(tree.id);
+            }
+            trees.extend(tree.children.iter());
+        }
+    }
+    Ok(tips)
+}
+
+/// Obtain all options from the `fsck` section of the configuration.
+fn options(repo: &gix::Repository) -> anyhow::Result<full::Options> {
+    let config = repo.config_snapshot();
+    let mut options = full::Options::default();
+    options.object.strict = config.boolean(Fsck::STRICT.logical_name().as_str()).unwrap_or(false);
+
+    if let Some(sections) = config.plumbing().sections_by_name("fsck") {
+        for section in sections {
+            for key in section.keys() {
+                let Some(id) = message::Id::from_bytes(key.as_ref().as_bytes()) else {
+                    continue;
+                };
+                let value = section.value(key).unwrap_or_default();
+                let severity = Severity::from_bytes(value.as_ref())
+                    .with_context(|| format!("Invalid severity '{value}' for fsck.{key}"))?;
+                options.object.severities.insert(id, severity);
+            }
+        }
+    }
+
+    if let Some(path) = config.trusted_path(Fsck::SKIP_LIST.logical_name().as_str()) {
+        let path = path?;
+        let content =
+            std::fs::read(&path).with_context(|| format!("Could not read skip list at '{}'", path.display()))?;
+        options.object.skip_list = message::parse_skip_list(&content)
+            .map_err(|line| anyhow::anyhow!("Invalid object id '{line}' in skip list at '{}'", path.display()))?
+            .into_iter()
+            .collect();
+    }
+
+    if let Some(shallow) = repo.shallow_commits()? {
+        options.shallow_commits = shallow.iter().copied().collect();
+    }
+    Ok(options)
+}
 
-    let mut check = gix_fsck::Connectivity::new(&repo.objects, on_missing);
-    // Walk all commits, checking each one for connectivity
-    for commit in commits {
-        let commit = commit?;
-        check.check_commit(&commit.id)?;
-        // Note that we leave parent-iteration to the commits iterator, as it will
-        // correctly handle shallow repositories which are expected to have the commits
-        // along the shallow boundary missing.
+/// Write `dangling` commits into `lost-found/commit` and all other objects into `lost-found/other`, just like `git` does.
+/// Blobs are written with their content, everything else by id.
+fn write_lost_found(repo: &gix::Repository, dangling: &[(ObjectId, Kind)]) -> anyhow::Result<()> {
+    let base = repo.git_dir().join("lost-found");
+    let (commits, other) = (base.join("commit"), base.join("other"));
+    let create =
+        |dir: &Path| std::fs::create_dir_all(dir).with_context(|| format!("Could not create '{}'", dir.display()));
+    create(&commits)?;
+    create(&other)?;
+    for (id, kind) in dangling {
+        let (dir, content) = match kind {
+            Kind::Commit => (&commits, format!("{id}\n").into_bytes()),
+            Kind::Blob => (&other, repo.find_object(*id)?.detach().data),
+            Kind::Tree | Kind::Tag => (&other, format!("{id}\n").into_bytes()),
+        };
+        std::fs::write(dir.join(id.to_hex().to_string()), content)?;
     }
     Ok(())
 }
@@ -110,6 +110,7 @@ pub fn show(
         index.write(gix::index::write::Options {
             extensions: Default::default(),
             skip_hash: false, // TODO: make this based on configuration
+            ..Default::default()
         })?;
     }
 
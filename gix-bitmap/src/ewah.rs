@@ -51,10 +51,120 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+mod init {
+    use super::{Vec, RLW_LARGEST_LITERAL_COUNT, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
+
+    impl Vec {
+        /// Create a new compressed bitmap with only the bits at the given `indices` set, which must be sorted in ascending order.
+        ///
+        /// The amount of bits it holds is one more than the highest index, or `0` if there is no index.
+        pub fn from_set_bits(indices: impl IntoIterator<Item = usize>) -> Self {
+            let mut out = Vec {
+                num_bits: 0,
+                bits: std::vec::Vec::new(),
+                rlw: 0,
+            };
+            let mut current_word_index = 0usize;
+            let mut current_word = 0u64;
+            for index in indices {
+                let word_index = index / 64;
+                assert!(
+                    word_index >= current_word_index,
+                    "BUG: indices must be sorted in ascending order"
+                );
+                if word_index != current_word_index {
+                    out.push_literal_word(current_word);
+                    out.push_empty_words(word_index - current_word_index - 1);
+                    current_word_index = word_index;
+                    current_word = 0;
+                }
+                current_word |= 1 << (index % 64);
+                out.num_bits = u32::try_from(index + 1).expect("bitmaps are not larger than 4 billion bits");
+            }
+            if out.num_bits != 0 {
+                out.push_literal_word(current_word);
+            }
+            if out.bits.is_empty() {
+                out.bits.push(0);
+            }
+            out
+        }
+
+        fn current_rlw(&mut self) -> &mut u64 {
+            if self.bits.is_empty() {
+                self.bits.push(0);
+                self.rlw = 0;
+            }
+            &mut self.bits[self.rlw as usize]
+        }
+
+        fn start_new_rlw(&mut self) {
+            self.rlw = self.bits.len() as u64;
+            self.bits.push(0);
+        }
+
+        fn push_empty_words(&mut self, mut count: usize) {
+            while count != 0 {
+                let rlw = *self.current_rlw();
+                if rlw >> (1 + RLW_RUNNING_BITS) != 0
+                    || (rlw >> 1) & RLW_LARGEST_RUNNING_COUNT == RLW_LARGEST_RUNNING_COUNT
+                {
+                    self.start_new_rlw();
+                    continue;
+                }
+                let run_len = (rlw >> 1) & RLW_LARGEST_RUNNING_COUNT;
+                let added = (RLW_LARGEST_RUNNING_COUNT - run_len).min(count as u64);
+                *self.current_rlw() = (rlw & !(RLW_LARGEST_RUNNING_COUNT << 1)) | ((run_len + added) << 1);
+                count -= added as usize;
+            }
+        }
+
+        fn push_literal_word(&mut self, word: u64) {
+            if word == 0 {
+                self.push_empty_words(1);
+                return;
+            }
+            let mut rlw = *self.current_rlw();
+            if rlw >> (1 + RLW_RUNNING_BITS) == RLW_LARGEST_LITERAL_COUNT {
+                self.start_new_rlw();
+                rlw = 0;
+            }
+            let literal_count = rlw >> (1 + RLW_RUNNING_BITS);
+            *self.current_rlw() =
+                (rlw & ((1 << (1 + RLW_RUNNING_BITS)) - 1)) | ((literal_count + 1) << (1 + RLW_RUNNING_BITS));
+            self.bits.push(word);
+        }
+    }
+}
+
+mod write {
+    use super::Vec;
+
+    impl Vec {
+        /// Serialize this bitmap to `out` in the format understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(
+                &u32::try_from(self.bits.len())
+                    .expect("not more than 4 billion words")
+                    .to_be_bytes(),
+            )?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(
+                &u32::try_from(self.rlw)
+                    .expect("rlw position fits into 32 bits")
+                    .to_be_bytes(),
+            )
+        }
+    }
+}
+
 mod access {
     use std::convert::{TryFrom, TryInto};
 
-    use super::Vec;
+    use super::{Vec, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
 
     impl Vec {
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
@@ -115,13 +225,13 @@ mod access {
     fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
-
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
 }
 
+const RLW_RUNNING_BITS: u64 = 4 * 8;
+const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
+
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct Vec {
     num_bits: u32,
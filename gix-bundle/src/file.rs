@@ -0,0 +1,136 @@
+use std::{
+    io::{BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_features::progress::DynNestedProgress;
+
+use crate::{File, Header};
+
+///
+pub mod init {
+    use std::path::PathBuf;
+
+    /// The error returned by [`File::at()`](crate::File::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bundle at '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not decode the header of the bundle at '{}'", path.display())]
+        Header {
+            source: crate::header::decode::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+///
+pub mod unpack {
+    use gix_hash::ObjectId;
+
+    /// The error returned by [`File::unpack()`](crate::File::unpack()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The bundle uses {actual} objects, but {expected} was expected")]
+        ObjectHashMismatch {
+            actual: gix_hash::Kind,
+            expected: gix_hash::Kind,
+        },
+        #[error("The bundle requires the following commits which are not present: {}", ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+        MissingPrerequisites { ids: Vec<ObjectId> },
+        #[error("Could not open the pack contained in the bundle")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        WritePack(#[from] gix_pack::bundle::write::Error),
+    }
+}
+
+/// Initialization
+impl File {
+    /// Open the bundle at `path` and decode its header.
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, init::Error> {
+        let path = path.into();
+        let io_err = |source| init::Error::Io {
+            source,
+            path: path.clone(),
+        };
+        let mut read = BufReader::new(std::fs::File::open(&path).map_err(io_err)?);
+        let header = Header::from_read(&mut read).map_err(|source| init::Error::Header {
+            source,
+            path: path.clone(),
+        })?;
+        let pack_offset = read.stream_position().map_err(io_err)?;
+        Ok(File {
+            header,
+            path,
+            pack_offset,
+        })
+    }
+}
+
+/// Access
+impl File {
+    /// Return the decoded header of the bundle.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Return the path the bundle was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the offset into the bundle file at which the pack starts.
+    pub fn pack_offset(&self) -> u64 {
+        self.pack_offset
+    }
+
+    /// Open the bundle file once more and return a reader that is positioned at the beginning of its pack.
+    pub fn pack_reader(&self) -> std::io::Result<BufReader<std::fs::File>> {
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pack_offset))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+/// Unbundling
+impl File {
+    /// Write the pack of this bundle and its index into `directory`, or only validate it if `None`, similar to `git bundle unbundle`.
+    ///
+    /// `objects` is used to assure all prerequisites are present and to resolve delta bases outside of the pack.
+    /// `progress` and `should_interrupt` are passed to the pack writer, which is configured with `options`.
+    pub fn unpack<T>(
+        &self,
+        directory: Option<&Path>,
+        objects: T,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: gix_pack::bundle::write::Options,
+    ) -> Result<gix_pack::bundle::write::Outcome, unpack::Error>
+    where
+        T: gix_object::Find + gix_object::Exists,
+    {
+        if self.header.object_hash != options.object_hash {
+            return Err(unpack::Error::ObjectHashMismatch {
+                actual: self.header.object_hash,
+                expected: options.object_hash,
+            });
+        }
+        let missing = self.header.missing_prerequisites(&objects);
+        if !missing.is_empty() {
+            return Err(unpack::Error::MissingPrerequisites { ids: missing });
+        }
+        let mut pack = self.pack_reader()?;
+        Ok(gix_pack::Bundle::write_to_directory(
+            &mut pack,
+            directory,
+            progress,
+            should_interrupt,
+            Some(objects),
+            options,
+        )?)
+    }
+}
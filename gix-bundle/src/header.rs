@@ -0,0 +1,215 @@
+use std::io::{BufRead, Write};
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Header, Prerequisite, Ref, Version};
+
+const SIGNATURE_V2: &[u8] = b"# v2 git bundle";
+const SIGNATURE_V3: &[u8] = b"# v3 git bundle";
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`Header::from_read()`](crate::Header::from_read()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the bundle header")]
+        Io(#[from] std::io::Error),
+        #[error("The bundle signature {line:?} is unknown")]
+        UnknownSignature { line: BString },
+        #[error("The bundle ended before the end of the header was reached")]
+        UnexpectedEof,
+        #[error("Capabilities are not supported in bundles of version 2, found {line:?}")]
+        CapabilityInV2 { line: BString },
+        #[error("Capabilities must come before prerequisites and references, found {line:?}")]
+        CapabilityOutOfOrder { line: BString },
+        #[error("The bundle capability {name:?} is not supported")]
+        UnsupportedCapability { name: BString },
+        #[error("The capability {name:?} needs a value")]
+        MissingCapabilityValue { name: BString },
+        #[error("The object format {name:?} is not supported")]
+        UnsupportedObjectFormat { name: BString },
+        #[error("Could not parse {line:?} as prerequisite or reference")]
+        MalformedLine { line: BString },
+        #[error(transparent)]
+        Id(#[from] gix_hash::decode::Error),
+    }
+}
+
+/// Return the version of the bundle if `line` is a known bundle signature, with or without trailing newline.
+pub(crate) fn parse_signature(line: &[u8]) -> Option<Version> {
+    match line.strip_suffix(b"\n").unwrap_or(line) {
+        SIGNATURE_V2 => Some(Version::V2),
+        SIGNATURE_V3 => Some(Version::V3),
+        _ => None,
+    }
+}
+
+impl Header {
+    /// Read a bundle header from `read`, which is left positioned at the first byte of the pack once this method returns.
+    pub fn from_read(read: &mut dyn BufRead) -> Result<Self, decode::Error> {
+        let mut line = Vec::new();
+        let mut next_line = |line: &mut Vec<u8>| -> Result<(), decode::Error> {
+            line.clear();
+            if read.read_until(b'\n', line)? == 0 || line.last() != Some(&b'\n') {
+                return Err(decode::Error::UnexpectedEof);
+            }
+            line.pop();
+            Ok(())
+        };
+
+        next_line(&mut line)?;
+        let version = parse_signature(&line).ok_or_else(|| decode::Error::UnknownSignature {
+            line: line.as_bstr().into(),
+        })?;
+        let mut header = Header {
+            version,
+            object_hash: gix_hash::Kind::Sha1,
+            filter: None,
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        };
+        loop {
+            next_line(&mut line)?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                if version == Version::V2 {
+                    return Err(decode::Error::CapabilityInV2 {
+                        line: line.as_bstr().into(),
+                    });
+                }
+                if !header.prerequisites.is_empty() || !header.refs.is_empty() {
+                    return Err(decode::Error::CapabilityOutOfOrder {
+                        line: line.as_bstr().into(),
+                    });
+                }
+                header.parse_capability(capability)?;
+            } else if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let (id, comment) = parse_id_and_rest(prerequisite, header.object_hash, &line)?;
+                header.prerequisites.push(Prerequisite {
+                    id,
+                    comment: comment.unwrap_or_default().into(),
+                });
+            } else {
+                let (id, name) = parse_id_and_rest(&line, header.object_hash, &line)?;
+                header.refs.push(Ref {
+                    name: name
+                        .filter(|name| !name.is_empty())
+                        .ok_or_else(|| decode::Error::MalformedLine {
+                            line: line.as_bstr().into(),
+                        })?
+                        .into(),
+                    id,
+                });
+            }
+        }
+        Ok(header)
+    }
+
+    fn parse_capability(&mut self, capability: &[u8]) -> Result<(), decode::Error> {
+        let (name, value) = match capability.find_byte(b'=') {
+            Some(pos) => (&capability[..pos], Some(&capability[pos + 1..])),
+            None => (capability, None),
+        };
+        let value = || {
+            value.ok_or_else(|| decode::Error::MissingCapabilityValue {
+                name: name.as_bstr().into(),
+            })
+        };
+        match name {
+            b"object-format" => {
+                let value = value()?;
+                self.object_hash = value
+                    .to_str()
+                    .ok()
+                    .and_then(|name| name.parse().ok())
+                    .ok_or_else(|| decode::Error::UnsupportedObjectFormat {
+                        name: value.as_bstr().into(),
+                    })?;
+            }
+            b"filter" => self.filter = Some(value()?.as_bstr().into()),
+            _ => {
+                return Err(decode::Error::UnsupportedCapability {
+                    name: name.as_bstr().into(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Write this header to `out` so that it can be followed by a pack, automatically using [`Version::V3`] if
+    /// capabilities are needed to represent it.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let version = if self.filter.is_some() || self.object_hash != gix_hash::Kind::Sha1 {
+            Version::V3
+        } else {
+            self.version
+        };
+        match version {
+            Version::V2 => out.write_all(SIGNATURE_V2)?,
+            Version::V3 => {
+                out.write_all(SIGNATURE_V3)?;
+                out.write_all(b"\n@object-format=")?;
+                out.write_all(object_format_name(self.object_hash).as_bytes())?;
+                if let Some(filter) = &self.filter {
+                    out.write_all(b"\n@filter=")?;
+                    out.write_all(filter)?;
+                }
+            }
+        }
+        out.write_all(b"\n")?;
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for reference in &self.refs {
+            write!(out, "{} ", reference.id)?;
+            out.write_all(&reference.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+
+    /// Return the ids of all prerequisites which don't exist in `objects`.
+    pub fn missing_prerequisites(&self, objects: &dyn gix_object::Exists) -> Vec<ObjectId> {
+        self.prerequisites
+            .iter()
+            .filter(|p| !objects.exists(&p.id))
+            .map(|p| p.id)
+            .collect()
+    }
+}
+
+fn object_format_name(kind: gix_hash::Kind) -> &'static str {
+    match kind {
+        gix_hash::Kind::Sha1 => "sha1",
+    }
+}
+
+/// Parse `<hex-id>[ <rest>]` from `data`, using `line` for error messages.
+fn parse_id_and_rest<'a>(
+    data: &'a [u8],
+    object_hash: gix_hash::Kind,
+    line: &[u8],
+) -> Result<(ObjectId, Option<&'a [u8]>), decode::Error> {
+    let hex_len = object_hash.len_in_hex();
+    let malformed = || decode::Error::MalformedLine {
+        line: BString::from(line),
+    };
+    let id = ObjectId::from_hex(data.get(..hex_len).ok_or_else(malformed)?)?;
+    let rest = match data.get(hex_len..).unwrap_or_default() {
+        [] => None,
+        [b' ', rest @ ..] => Some(rest),
+        _ => return Err(malformed()),
+    };
+    Ok((id, rest))
+}
@@ -0,0 +1,89 @@
+//! Read and write [git bundles](https://git-scm.com/docs/gitformat-bundle), which are files containing a pack along with
+//! the references pointing into it and the commits the receiver is expected to have already.
+//!
+//! ## Deviation
+//!
+//! Bundles are always written with a complete pack that doesn't refer to objects outside of it, even though `git` would
+//! produce a thin pack that may use prerequisite objects as delta bases.
+//! Prerequisites are only checked for existence, not for being connected to all of their ancestors.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The version of the bundle format.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Version {
+    /// The original format without capabilities, which implies the use of SHA1.
+    #[default]
+    V2,
+    /// The format that supports capabilities, like `object-format` or `filter` for partial bundles.
+    V3,
+}
+
+/// A commit that the receiver of a bundle must have for the bundle's pack to be complete.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Prerequisite {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// A comment that is informational only, typically the commit's subject line, which may be empty.
+    pub comment: BString,
+}
+
+/// A reference contained in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ref {
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+    /// The object the reference points to, which is contained in the pack of the bundle.
+    pub id: ObjectId,
+}
+
+/// The header of a bundle, which is followed by the pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The version of the bundle format.
+    pub version: Version,
+    /// The hash used for all objects in the bundle, as configured by the `object-format` capability.
+    pub object_hash: gix_hash::Kind,
+    /// The object filter that was used to create a partial bundle, like `blob:none`, as configured by the `filter` capability.
+    pub filter: Option<BString>,
+    /// The commits the receiver must have.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references whose objects are contained in the bundle.
+    pub refs: Vec<Ref>,
+}
+
+/// A bundle file on disk, along with its parsed header.
+#[derive(Debug, Clone)]
+pub struct File {
+    header: Header,
+    path: PathBuf,
+    pack_offset: u64,
+}
+
+/// Return `true` if the file at `path` starts with the signature of a bundle of any supported version.
+pub fn is_bundle(path: &std::path::Path) -> bool {
+    use std::io::{BufRead, Read};
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut line = Vec::new();
+    std::io::BufReader::with_capacity(64, file)
+        .take(64)
+        .read_until(b'\n', &mut line)
+        .map_or(false, |_| header::parse_signature(&line).is_some())
+}
+
+///
+pub mod header;
+
+///
+pub mod file;
+
+///
+pub mod write;
+pub use write::function::write;
@@ -0,0 +1,273 @@
+/// Options for use in [`write()`](crate::write()).
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// The version of the bundle to write, which is upgraded to [`Version::V3`](crate::Version::V3) automatically
+    /// if the header can't be represented otherwise.
+    pub version: crate::Version,
+    /// The amount of threads to use when creating the pack, or `None` to use all available cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [`write()`](crate::write()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The header that was written in front of the pack.
+    pub header: crate::Header,
+    /// The amount of objects in the pack.
+    pub num_objects: usize,
+}
+
+/// The error returned by [`write()`](crate::write()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot create a bundle without references")]
+    NoRefs,
+    #[error("Refusing to create an empty bundle as all references are reachable from excluded commits")]
+    Empty,
+    #[error(transparent)]
+    FindExisting(#[from] gix_object::find::existing::Error),
+    #[error(transparent)]
+    FindExistingIter(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error("Could not write the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    CreateEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+pub(crate) mod function {
+    use std::{
+        collections::VecDeque,
+        io::Write,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use gix_features::progress::{DynNestedProgress, Progress};
+    use gix_hash::ObjectId;
+    use gix_hashtable::HashSet;
+    use gix_object::{tree::EntryKind, CommitRef, Kind, TagRefIter};
+    use gix_pack::{data::output, FindExt};
+
+    use super::{Error, Options, Outcome};
+    use crate::{Header, Prerequisite, Ref};
+
+    /// Write a bundle to `out` which contains `refs` along with all objects reachable from them, similar to `git bundle create`.
+    ///
+    /// All commits reachable from `exclude` are omitted, which turns the excluded commits that are parents of included commits into
+    /// prerequisites. Use `objects` to traverse the commit graph and to create the pack,
+    /// while `progress` and `should_interrupt` are used to report progress and to stop the operation, respectively.
+    ///
+    /// Trees and blobs that are reachable from prerequisites are omitted from the pack as the receiver is expected to have them.
+    pub fn write<T>(
+        refs: Vec<Ref>,
+        exclude: &[ObjectId],
+        objects: T,
+        out: &mut dyn Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options { version, thread_limit }: Options,
+    ) -> Result<Outcome, Error>
+    where
+        T: gix_pack::Find + Clone + Send + 'static,
+    {
+        let object_hash = refs.first().ok_or(Error::NoRefs)?.id.kind();
+        let mut buf = Vec::new();
+
+        let mut hidden = HashSet::default();
+        let mut queue: VecDeque<_> = exclude
+            .iter()
+            .map(|id| peel_to_commit(&objects, *id, &mut buf, &mut Vec::new()))
+            .filter_map(Result::transpose)
+            .collect::<Result<_, _>>()?;
+        while let Some(id) = queue.pop_front() {
+            if !hidden.insert(id) {
+                continue;
+            }
+            let (data, _) = objects.find_commit_iter(&id, &mut buf)?;
+            queue.extend(data.parent_ids().filter(|id| !hidden.contains(id)));
+        }
+
+        let (mut tags, mut commits, mut others) = (Vec::new(), Vec::new(), Vec::new());
+        let mut seen = HashSet::default();
+        let mut boundary = Vec::new();
+        for tip in &refs {
+            let mut peeled_tags = Vec::new();
+            match peel_to_commit(&objects, tip.id, &mut buf, &mut peeled_tags)? {
+                Some(commit) => queue.push_back(commit),
+                None => {
+                    let peeled = peeled_tags.pop().expect("the peeled object is always the last one");
+                    others.push(peeled);
+                }
+            }
+            for (tag, _) in peeled_tags {
+                if seen.insert(tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        while let Some(id) = queue.pop_front() {
+            if hidden.contains(&id) || !seen.insert(id) {
+                continue;
+            }
+            commits.push(id);
+            let (data, _) = objects.find_commit_iter(&id, &mut buf)?;
+            for parent in data.parent_ids() {
+                if hidden.contains(&parent) {
+                    if seen.insert(parent) {
+                        boundary.push(parent);
+                    }
+                } else {
+                    queue.push_back(parent);
+                }
+            }
+        }
+        if commits.is_empty() && others.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let mut prerequisites = Vec::with_capacity(boundary.len());
+        let mut known = HashSet::default();
+        for id in boundary {
+            let (data, _) = objects.find(&id, &mut buf)?;
+            let tree = {
+                let commit = CommitRef::from_bytes(data.data)?;
+                prerequisites.push(Prerequisite {
+                    id,
+                    comment: commit.message_summary().into_owned(),
+                });
+                commit.tree()
+            };
+            collect_tree(&objects, tree, &mut known, &mut Vec::new(), &mut buf)?;
+        }
+
+        let mut ids = tags;
+        for id in commits {
+            let (mut data, _) = objects.find_commit_iter(&id, &mut buf)?;
+            let tree = data.tree_id()?;
+            ids.push(id);
+            collect_tree(&objects, tree, &mut known, &mut ids, &mut buf)?;
+        }
+        for (id, kind) in others {
+            match kind {
+                Kind::Tree => collect_tree(&objects, id, &mut known, &mut ids, &mut buf)?,
+                _ => {
+                    if known.insert(id) {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+
+        let header = Header {
+            version,
+            object_hash,
+            filter: None,
+            prerequisites,
+            refs,
+        };
+        header.write_to(out)?;
+
+        let num_objects = ids.len();
+        let (counts, _) = {
+            let mut counting = progress.add_child("counting".into());
+            counting.init(Some(num_objects), gix_features::progress::count("objects"));
+            output::count::objects_unthreaded(
+                &objects,
+                &mut ids.into_iter().map(Ok),
+                &counting,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?
+        };
+        let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            objects,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+        let mut writer = output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            object_hash,
+        );
+        for written in writer.by_ref() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            written?;
+        }
+        Ok(Outcome { header, num_objects })
+    }
+
+    /// Follow the tag chain starting at `id` and return the commit it ultimately points to, or `None` if it doesn't
+    /// point to a commit. All tags are pushed to `peeled`, along with the final object if it isn't a commit.
+    fn peel_to_commit(
+        objects: &dyn gix_pack::Find,
+        mut id: ObjectId,
+        buf: &mut Vec<u8>,
+        peeled: &mut Vec<(ObjectId, Kind)>,
+    ) -> Result<Option<ObjectId>, Error> {
+        loop {
+            let (data, _) = objects.find(&id, buf)?;
+            match data.kind {
+                Kind::Commit => return Ok(Some(id)),
+                Kind::Tag => {
+                    peeled.push((id, Kind::Tag));
+                    id = TagRefIter::from_bytes(data.data).target_id()?;
+                }
+                kind => {
+                    peeled.push((id, kind));
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Push `tree` and all objects reachable from it to `out` unless they are `known` already.
+    /// Submodule commits are ignored.
+    fn collect_tree(
+        objects: &dyn gix_pack::Find,
+        tree: ObjectId,
+        known: &mut HashSet,
+        out: &mut Vec<ObjectId>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let mut trees = vec![tree];
+        while let Some(tree) = trees.pop() {
+            if !known.insert(tree) {
+                continue;
+            }
+            out.push(tree);
+            let (entries, _) = objects.find_tree_iter(&tree, buf)?;
+            for entry in entries {
+                let entry = entry?;
+                match entry.mode.kind() {
+                    EntryKind::Tree => trees.push(entry.oid.to_owned()),
+                    EntryKind::Commit => {}
+                    EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => {
+                        if known.insert(entry.oid.to_owned()) {
+                            out.push(entry.oid.to_owned());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+pub use gix_testtools::Result;
+
+fn fixture() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_bundles.sh")
+}
+
+fn repo_objects() -> Result<gix_odb::HandleArc> {
+    let mut objects = gix_odb::at(fixture()?.join("repo/.git/objects"))?.into_arc()?;
+    objects.prevent_pack_unload();
+    Ok(objects)
+}
+
+fn head_id() -> Result<ObjectId> {
+    let hex = std::fs::read(fixture()?.join("repo/.git/refs/heads/main"))?;
+    Ok(ObjectId::from_hex(hex.strip_suffix(b"\n").unwrap_or(&hex))?)
+}
+
+mod file;
+mod header;
+mod write;
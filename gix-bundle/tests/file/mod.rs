@@ -0,0 +1,60 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_bundle::file::unpack;
+use gix_features::progress;
+
+use crate::{fixture, repo_objects};
+
+#[test]
+fn unpack_complete_bundle_without_local_objects() -> crate::Result {
+    let bundle = gix_bundle::File::at(fixture()?.join("all.bundle"))?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let outcome = bundle.unpack(
+        Some(tmp.path()),
+        gix_object::find::Never,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.index.num_objects, 11, "3 commits, 4 trees, 3 blobs and a tag");
+    assert!(outcome.data_path.expect("written").is_file());
+    Ok(())
+}
+
+#[test]
+fn unpack_fails_if_prerequisites_are_missing() -> crate::Result {
+    let bundle = gix_bundle::File::at(fixture()?.join("incremental.bundle"))?;
+    let err = bundle
+        .unpack(
+            None,
+            gix_object::find::Never,
+            &mut progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )
+        .unwrap_err();
+    match err {
+        unpack::Error::MissingPrerequisites { ids } => {
+            assert_eq!(ids, [bundle.header().prerequisites[0].id]);
+        }
+        err => panic!("unexpected error: {err}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn unpack_with_prerequisites() -> crate::Result {
+    let bundle = gix_bundle::File::at(fixture()?.join("incremental.bundle"))?;
+    let outcome = bundle.unpack(
+        None,
+        repo_objects()?,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.index.num_objects, 4,
+        "the new commit, its trees and the new blob, as the pack isn't thin"
+    );
+    Ok(())
+}
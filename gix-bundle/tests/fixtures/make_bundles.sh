@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git symbolic-ref HEAD refs/heads/main
+
+  echo a > a
+  git add a
+  git commit -q -m "first"
+  git tag -a -m "annotated" v1
+
+  echo b > b
+  git add b
+  git commit -q -m "second"
+
+  mkdir dir
+  echo c > dir/c
+  git add dir
+  git commit -q -m "third"
+
+  git bundle create -q ../all.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main
+  git bundle create -q --version=3 ../v3.bundle main
+)
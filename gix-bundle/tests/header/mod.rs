@@ -0,0 +1,129 @@
+use std::io::Read;
+
+use gix_bundle::{header::decode, Header, Version};
+
+use crate::{fixture, head_id};
+
+fn header_at(name: &str) -> crate::Result<Header> {
+    Ok(gix_bundle::File::at(fixture()?.join(name))?.header().clone())
+}
+
+#[test]
+fn v2_with_refs_and_tags() -> crate::Result {
+    let header = header_at("all.bundle")?;
+    assert_eq!(header.version, Version::V2);
+    assert_eq!(header.object_hash, gix_hash::Kind::Sha1);
+    assert_eq!(header.filter, None);
+    assert!(header.prerequisites.is_empty());
+    assert_eq!(
+        header.refs.iter().map(|r| r.name.to_string()).collect::<Vec<_>>(),
+        ["refs/heads/main", "refs/tags/v1", "HEAD"]
+    );
+    assert_eq!(header.refs[0].id, head_id()?);
+    assert_eq!(header.refs[2].id, head_id()?);
+    assert_ne!(header.refs[1].id, head_id()?, "annotated tags are not peeled");
+    Ok(())
+}
+
+#[test]
+fn v2_with_prerequisites() -> crate::Result {
+    let header = header_at("incremental.bundle")?;
+    assert_eq!(header.version, Version::V2);
+    assert_eq!(header.prerequisites.len(), 1);
+    assert_eq!(header.prerequisites[0].comment, "second");
+    assert_eq!(header.refs.len(), 1);
+    assert_eq!(header.refs[0].name, "refs/heads/main");
+    Ok(())
+}
+
+#[test]
+fn v3_with_capabilities() -> crate::Result {
+    let header = header_at("v3.bundle")?;
+    assert_eq!(header.version, Version::V3);
+    assert_eq!(header.object_hash, gix_hash::Kind::Sha1);
+    assert_eq!(header.refs.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn v3_with_filter_and_trailing_pack() -> crate::Result {
+    let mut input: &[u8] = b"# v3 git bundle\n@object-format=sha1\n@filter=blob:none\n-6395b73ac81ba5e816eb41836246b612b0d03060\n5a04e7e6bede64110a4c18ec0132496a71636472 refs/heads/main\n\nPACK";
+    let header = Header::from_read(&mut input)?;
+    assert_eq!(header.filter.as_ref().map(ToString::to_string).as_deref(), Some("blob:none"));
+    assert_eq!(header.prerequisites[0].comment, "", "comments are optional");
+    let mut rest = Vec::new();
+    input.read_to_end(&mut rest)?;
+    assert_eq!(rest, b"PACK", "the reader is positioned at the start of the pack");
+    Ok(())
+}
+
+#[test]
+fn write_to_roundtrips() -> crate::Result {
+    for name in ["all.bundle", "incremental.bundle", "v3.bundle"] {
+        let header = header_at(name)?;
+        let mut buf = Vec::new();
+        header.write_to(&mut buf)?;
+        assert_eq!(Header::from_read(&mut buf.as_slice())?, header, "{name}");
+        let original = std::fs::read(fixture()?.join(name))?;
+        assert_eq!(
+            buf,
+            original[..buf.len()],
+            "{name}: the header is written exactly like git does"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn write_to_upgrades_to_v3_for_capabilities() -> crate::Result {
+    let mut header = header_at("all.bundle")?;
+    header.filter = Some("blob:none".into());
+    let mut buf = Vec::new();
+    header.write_to(&mut buf)?;
+    let decoded = Header::from_read(&mut buf.as_slice())?;
+    assert_eq!(decoded.version, Version::V3);
+    assert_eq!(decoded.filter, header.filter);
+    Ok(())
+}
+
+#[test]
+fn is_bundle() -> crate::Result {
+    let dir = fixture()?;
+    for name in ["all.bundle", "incremental.bundle", "v3.bundle"] {
+        assert!(gix_bundle::is_bundle(&dir.join(name)), "{name}");
+    }
+    assert!(!gix_bundle::is_bundle(&dir.join("repo/a")));
+    assert!(!gix_bundle::is_bundle(&dir.join("does-not-exist")));
+    Ok(())
+}
+
+#[test]
+fn decode_errors() {
+    let id = "5a04e7e6bede64110a4c18ec0132496a71636472";
+    for (input, expected) in [
+        ("# v4 git bundle\n\n".to_string(), "UnknownSignature"),
+        (format!("# v2 git bundle\n@object-format=sha1\n{id} HEAD\n\n"), "CapabilityInV2"),
+        (format!("# v3 git bundle\n{id} HEAD\n@object-format=sha1\n\n"), "CapabilityOutOfOrder"),
+        ("# v3 git bundle\n@unknown\n\n".into(), "UnsupportedCapability"),
+        ("# v3 git bundle\n@filter\n\n".into(), "MissingCapabilityValue"),
+        ("# v3 git bundle\n@object-format=sha256\n\n".into(), "UnsupportedObjectFormat"),
+        (format!("# v2 git bundle\n{id}\n\n"), "MalformedLine"),
+        (format!("# v2 git bundle\n{id}HEAD\n\n"), "MalformedLine"),
+        ("# v2 git bundle\nnot-a-hash HEAD\n\n".into(), "MalformedLine"),
+        (format!("# v2 git bundle\n{id} HEAD\n"), "UnexpectedEof"),
+    ] {
+        let err = Header::from_read(&mut input.as_bytes()).expect_err("invalid input");
+        let actual = match err {
+            decode::Error::UnknownSignature { .. } => "UnknownSignature",
+            decode::Error::CapabilityInV2 { .. } => "CapabilityInV2",
+            decode::Error::CapabilityOutOfOrder { .. } => "CapabilityOutOfOrder",
+            decode::Error::UnsupportedCapability { .. } => "UnsupportedCapability",
+            decode::Error::MissingCapabilityValue { .. } => "MissingCapabilityValue",
+            decode::Error::UnsupportedObjectFormat { .. } => "UnsupportedObjectFormat",
+            decode::Error::MalformedLine { .. } => "MalformedLine",
+            decode::Error::UnexpectedEof => "UnexpectedEof",
+            decode::Error::Io(_) | decode::Error::Id(_) => "other",
+        };
+        assert_eq!(actual, expected, "{input:?}");
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_bundle::{write, Header, Ref};
+use gix_features::progress;
+
+use crate::{fixture, head_id, repo_objects};
+
+fn main_ref() -> crate::Result<Ref> {
+    Ok(Ref {
+        name: "refs/heads/main".into(),
+        id: head_id()?,
+    })
+}
+
+#[test]
+fn incremental_bundle_from_range() -> crate::Result {
+    let prerequisite = gix_bundle::File::at(fixture()?.join("incremental.bundle"))?
+        .header()
+        .prerequisites[0]
+        .clone();
+    let mut buf = Vec::new();
+    let outcome = gix_bundle::write(
+        vec![main_ref()?],
+        &[prerequisite.id],
+        repo_objects()?,
+        &mut buf,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.num_objects, 4, "trees and blobs of prerequisites are excluded");
+    assert_eq!(outcome.header.prerequisites, [prerequisite]);
+
+    let mut input = buf.as_slice();
+    assert_eq!(Header::from_read(&mut input)?, outcome.header);
+    assert!(input.starts_with(b"PACK"));
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("written.bundle");
+    std::fs::write(&path, &buf)?;
+    let unpacked = gix_bundle::File::at(path)?.unpack(
+        None,
+        repo_objects()?,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(unpacked.index.num_objects, 4);
+    Ok(())
+}
+
+#[test]
+fn complete_bundle_with_annotated_tag() -> crate::Result {
+    let tag = gix_bundle::File::at(fixture()?.join("all.bundle"))?.header().refs[1].clone();
+    let mut buf = Vec::new();
+    let outcome = gix_bundle::write(
+        vec![main_ref()?, tag],
+        &[],
+        repo_objects()?,
+        &mut buf,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        write::Options {
+            version: gix_bundle::Version::V3,
+            thread_limit: Some(1),
+        },
+    )?;
+    assert_eq!(outcome.num_objects, 11);
+    assert!(outcome.header.prerequisites.is_empty());
+    assert_eq!(Header::from_read(&mut buf.as_slice())?.version, gix_bundle::Version::V3);
+    Ok(())
+}
+
+#[test]
+fn empty_bundles_are_refused() -> crate::Result {
+    let err = gix_bundle::write(
+        vec![main_ref()?],
+        &[head_id()?],
+        repo_objects()?,
+        &mut Vec::new(),
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::Empty));
+
+    let err = gix_bundle::write(
+        Vec::new(),
+        &[],
+        repo_objects()?,
+        &mut Vec::new(),
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::NoRefs));
+    Ok(())
+}
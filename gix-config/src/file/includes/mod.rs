@@ -28,6 +28,9 @@ impl File<'static> {
     ///   technically 'splitting' the section. This can only make a difference if the `include` section also has values
     ///   which later overwrite portions of the included file, which seems unusual as these would be related to `includes`.
     ///   We can fix this by 'splitting' the include section if needed so the included sections are put into the right place.
+    /// - `includeIf.hasconfig:remote.*.url:<pattern>` conditions are evaluated after all other includes were resolved,
+    ///   and only see the remote URLs of this instance. Thus all files that may define remote URLs should be
+    ///   [appended](File::append()) before calling this method.
     pub fn resolve_includes(&mut self, options: init::Options<'_>) -> Result<(), Error> {
         if options.includes.max_depth == 0 {
             return Ok(());
@@ -38,12 +41,89 @@ impl File<'static> {
 }
 
 pub(crate) fn resolve(config: &mut File<'static>, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<(), Error> {
-    resolve_includes_recursive(config, 0, buf, options)
+    resolve_includes_recursive(config, 0, None, buf, options)?;
+    resolve_hasconfig_includes(config, buf, options)
 }
 
+/// Follow `includeIf.hasconfig:remote.*.url:<pattern>` includes if `pattern` matches any of the remote URLs in `config`,
+/// which has all other includes resolved already, similar to how `git` collects the remote URLs in a separate pass.
+///
+/// As the remote URLs are known at this point, all includes of the included files are resolved as well,
+/// but none of them may define remote URLs.
+fn resolve_hasconfig_includes(
+    config: &mut File<'static>,
+    buf: &mut Vec<u8>,
+    options: init::Options<'_>,
+) -> Result<(), Error> {
+    let mut section_ids_and_include_paths = Vec::new();
+    let remote_urls = collect_remote_urls(config);
+    for (id, section) in config.section_order.iter().map(|id| (*id, &config.sections[id])) {
+        let header = &section.header;
+        if header.name.as_ref() != "includeIf" {
+            continue;
+        }
+        if let Some(pattern) = header
+            .subsection_name
+            .as_ref()
+            .and_then(|condition| condition.strip_prefix(HASCONFIG_REMOTE_URL))
+        {
+            if remote_url_matches(pattern.as_bstr(), &remote_urls) {
+                detach_include_paths(&mut section_ids_and_include_paths, section, id)
+            }
+        }
+    }
+
+    for (section_id, config_path) in section_ids_and_include_paths {
+        let depth = config.sections[&section_id].meta.level;
+        if depth >= options.includes.max_depth {
+            if options.includes.err_on_max_depth_exceeded {
+                return Err(Error::IncludeDepthExceeded {
+                    max_depth: options.includes.max_depth,
+                });
+            }
+            continue;
+        }
+        append_followed_includes_recursively(
+            vec![(section_id, config_path)],
+            config,
+            depth,
+            Some(&remote_urls),
+            options,
+            buf,
+        )?;
+    }
+    Ok(())
+}
+
+const HASCONFIG_REMOTE_URL: &[u8] = b"hasconfig:remote.*.url:";
+
+/// Return all values of `remote.<name>.url`.
+fn collect_remote_urls(config: &File<'_>) -> Vec<BString> {
+    config
+        .sections_by_name("remote")
+        .into_iter()
+        .flatten()
+        .flat_map(|section| section.values("url"))
+        .map(Cow::into_owned)
+        .collect()
+}
+
+fn remote_url_matches(pattern: &BStr, remote_urls: &[BString]) -> bool {
+    remote_urls.iter().any(|url| {
+        gix_glob::wildmatch(
+            pattern,
+            url.as_bstr(),
+            gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+        )
+    })
+}
+
+/// Resolve all includes in `target_config` recursively, and if `remote_urls` is set, evaluate `hasconfig:remote.*.url`
+/// conditions with them, while assuring that none of the included files define remote URLs.
 fn resolve_includes_recursive(
     target_config: &mut File<'static>,
     depth: u8,
+    remote_urls: Option<&[BString]>,
     buf: &mut Vec<u8>,
     options: init::Options<'_>,
 ) -> Result<(), Error> {
@@ -70,20 +150,28 @@ fn resolve_includes_recursive(
         } else if header_name == "includeIf" {
             if let Some(condition) = &header.subsection_name {
                 let target_config_path = section.meta.path.as_deref();
-                if include_condition_match(condition.as_ref(), target_config_path, options.includes)? {
+                if include_condition_match(condition.as_ref(), target_config_path, remote_urls, options.includes)? {
                     detach_include_paths(&mut section_ids_and_include_paths, section, id)
                 }
             }
         }
     }
 
-    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, options, buf)
+    append_followed_includes_recursively(
+        section_ids_and_include_paths,
+        target_config,
+        depth,
+        remote_urls,
+        options,
+        buf,
+    )
 }
 
 fn append_followed_includes_recursively(
     section_ids_and_include_paths: Vec<(SectionId, crate::Path<'_>)>,
     target_config: &mut File<'static>,
     depth: u8,
+    remote_urls: Option<&[BString]>,
     options: init::Options<'_>,
     buf: &mut Vec<u8>,
 ) -> Result<(), Error> {
@@ -117,7 +205,16 @@ fn append_followed_includes_recursively(
                 init::Error::Interpolate(err) => Error::Interpolate(err),
                 init::Error::Includes(_) => unreachable!("BUG: {:?} not possible due to no-follow options", err),
             })?;
-        resolve_includes_recursive(&mut include_config, depth + 1, buf, options)?;
+        resolve_includes_recursive(&mut include_config, depth + 1, remote_urls, buf, options)?;
+        if remote_urls.is_some() && !collect_remote_urls(&include_config).is_empty() {
+            return Err(Error::RemoteUrlInHasconfigInclude {
+                path: include_config
+                    .meta()
+                    .path
+                    .clone()
+                    .expect("included files always have a path"),
+            });
+        }
 
         target_config.append_or_insert(include_config, Some(section_id));
     }
@@ -141,6 +238,7 @@ fn detach_include_paths(
 fn include_condition_match(
     condition: &BStr,
     target_config_path: Option<&Path>,
+    remote_urls: Option<&[BString]>,
     options: Options<'_>,
 ) -> Result<bool, Error> {
     let mut tokens = condition.splitn(2, |b| *b == b':');
@@ -163,6 +261,12 @@ fn include_condition_match(
             gix_glob::wildmatch::Mode::IGNORE_CASE,
         ),
         b"onbranch" => Ok(onbranch_matches(condition, options.conditional).is_some()),
+        b"hasconfig" => Ok(condition
+            .strip_prefix(&HASCONFIG_REMOTE_URL[b"hasconfig:".len()..])
+            .zip(remote_urls)
+            .map_or(false, |(pattern, remote_urls)| {
+                remote_url_matches(pattern.as_bstr(), remote_urls)
+            })),
         _ => Ok(false),
     }
 }
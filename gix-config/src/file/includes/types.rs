@@ -18,6 +18,8 @@ pub enum Error {
     MissingGitDir,
     #[error(transparent)]
     Realpath(#[from] gix_path::realpath::Error),
+    #[error("Remote URLs must not be configured in {path:?} or any file it includes, as it's included by 'includeIf.hasconfig:remote.*.url'")]
+    RemoteUrlInHasconfigInclude { path: std::path::PathBuf },
 }
 
 /// Options to handle includes, like `include.path` or `includeIf.<condition>.path`,
@@ -60,6 +62,8 @@ impl<'a> Options<'a> {
     }
     /// Provide options to follow includes like git does, provided the required `conditional` and `interpolate` contexts
     /// to support `gitdir` and `onbranch` based `includeIf` directives as well as standard `include.path` resolution.
+    /// `hasconfig:remote.*.url` conditions don't need any context as they are matched against the remote URLs
+    /// in the configuration itself.
     /// Note that the follow-mode is `git`-style, following at most 10 indirections while
     /// producing an error if the depth is exceeded.
     pub fn follow(interpolate: interpolate::Context<'a>, conditional: conditional::Context<'a>) -> Self {
@@ -83,7 +83,7 @@ impl File<'static> {
     /// Includes will be resolved within limits as some information like the git installation directory is missing to interpolate
     /// paths with as well as git repository information like the branch name.
     pub fn from_git_dir(dir: std::path::PathBuf) -> Result<File<'static>, from_git_dir::Error> {
-        let (mut local, git_dir) = {
+        let (local, git_dir) = {
             let source = Source::Local;
             let mut path = dir;
             path.push(
@@ -126,14 +126,12 @@ impl File<'static> {
         };
 
         let mut globals = Self::from_globals()?;
-        globals.resolve_includes(options)?;
-        local.resolve_includes(options)?;
-
         globals.append(local);
-        if let Some(mut worktree) = worktree {
-            worktree.resolve_includes(options)?;
+        if let Some(worktree) = worktree {
             globals.append(worktree);
         }
+        // Resolve includes only once all files are known, so `hasconfig:remote.*.url` conditions see all remote URLs.
+        globals.resolve_includes(options)?;
         globals.append(Self::from_environment_overrides()?);
 
         Ok(globals)
@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use crate::{
-    file::{init, init::Options, Metadata},
+    file::{includes, init, init::Options, Metadata},
     File,
 };
 
@@ -78,6 +78,10 @@ impl File<'static> {
         options: Options<'_>,
     ) -> Result<Option<Self>, Error> {
         let mut target = None;
+        let no_follow_options = Options {
+            includes: includes::Options::no_follow(),
+            ..options
+        };
         let mut seen = BTreeSet::default();
         for (path, mut meta) in path_meta.filter_map(|mut meta| meta.path.take().map(|p| (p, meta))) {
             if !seen.insert(path.clone()) {
@@ -119,7 +123,7 @@ impl File<'static> {
             };
             meta.path = Some(path);
 
-            let config = Self::from_bytes_owned(buf, meta, options)?;
+            let config = Self::from_bytes_owned(buf, meta, no_follow_options)?;
             match &mut target {
                 None => {
                     target = Some(config);
@@ -129,6 +133,10 @@ impl File<'static> {
                 }
             }
         }
+        // Resolve includes only once all files are known, so `hasconfig:remote.*.url` conditions see all remote URLs.
+        if let Some(target) = target.as_mut() {
+            includes::resolve(target, buf, options).map_err(init::Error::from)?;
+        }
         Ok(target)
     }
 }
@@ -0,0 +1,180 @@
+use std::{fs, io::Write, path::Path};
+
+use bstr::{BString, ByteSlice};
+use gix_config::file::{includes, init};
+use gix_testtools::tempfile::tempdir;
+
+use crate::file::{
+    cow_str,
+    init::from_paths::{escape_backslashes, includes::conditional::git_init},
+};
+
+type Result = crate::Result;
+
+#[test]
+fn literal_urls_match() -> Result {
+    assert_section_value("https://example.com/repo.git", "https://example.com/repo.git", true)
+}
+
+#[test]
+fn non_matching_urls_do_not_include() -> Result {
+    assert_section_value("https://example.com/other.git", "https://example.com/repo.git", false)
+}
+
+#[test]
+fn simple_globs_do_not_cross_component_boundary() -> Result {
+    assert_section_value("https://example.com/*", "https://example.com/repo.git", true)?;
+    assert_section_value("https://*/repo.git", "https://example.com/repo.git", true)?;
+    assert_section_value("https://*", "https://example.com/repo.git", false)
+}
+
+#[test]
+fn double_star_globs_cross_component_boundaries() -> Result {
+    assert_section_value("https://**", "https://example.com/org/repo.git", true)?;
+    assert_section_value("**/repo.git", "https://example.com/org/repo.git", true)?;
+    assert_section_value("**/other.git", "https://example.com/org/repo.git", false)
+}
+
+#[test]
+fn remote_urls_defined_after_the_condition_are_seen() -> Result {
+    let dir = tempdir()?;
+    let include_path = dir.path().join("include");
+    fs::write(&include_path, "[section]\n  value = override-by-include")?;
+    let config_path = dir.path().join("config");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[section]
+  value = base-value
+[includeIf "hasconfig:remote.*.url:https://example.com/**"]
+  path = {}
+[remote "origin"]
+  url = https://example.com/repo.git"#,
+            escape_backslashes(&include_path),
+        ),
+    )?;
+
+    let config = gix_config::File::from_paths_metadata(Some(metadata(&config_path)?), options())?.expect("non-empty");
+    assert_eq!(
+        config.string("section", None, "value"),
+        Some(cow_str("override-by-include"))
+    );
+    Ok(())
+}
+
+#[test]
+fn included_files_must_not_define_remote_urls() -> Result {
+    let dir = tempdir()?;
+    let nested_include_path = dir.path().join("nested-include");
+    fs::write(
+        &nested_include_path,
+        "[remote \"other\"]\n  url = https://example.com/other.git",
+    )?;
+    let include_path = dir.path().join("include");
+    fs::write(
+        &include_path,
+        format!("[include]\n  path = {}", escape_backslashes(&nested_include_path)),
+    )?;
+    let config_path = dir.path().join("config");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[remote "origin"]
+  url = https://example.com/repo.git
+[includeIf "hasconfig:remote.*.url:https://example.com/**"]
+  path = {}"#,
+            escape_backslashes(&include_path),
+        ),
+    )?;
+
+    let err = gix_config::File::from_paths_metadata(Some(metadata(&config_path)?), options()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            init::from_paths::Error::Init(init::Error::Includes(
+                includes::Error::RemoteUrlInHasconfigInclude { ref path }
+            )) if *path == nested_include_path
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+/// Place the `hasconfig` condition with `pattern` into the global configuration and define a remote with `url`
+/// in the local configuration of a repository, to assure it includes a file only if `pattern` matches `url`.
+fn assert_section_value(pattern: &str, url: &str, expect_include: bool) -> Result {
+    let dir = tempdir()?;
+    let include_path = dir.path().join("include");
+    fs::write(&include_path, "[section]\n  value = override-by-include")?;
+
+    let global_config_path = dir.path().join(".gitconfig");
+    fs::write(
+        &global_config_path,
+        format!(
+            r#"
+[section]
+  value = base-value
+[includeIf "hasconfig:remote.*.url:{pattern}"]
+  path = {}"#,
+            escape_backslashes(&include_path),
+        ),
+    )?;
+
+    let git_dir = dir.path().join("repo.git");
+    git_init(&git_dir, true)?;
+    let local_config_path = git_dir.join("config");
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&local_config_path)?
+        .write_all(format!("[remote \"origin\"]\n  url = {url}\n").as_bytes())?;
+
+    let config = gix_config::File::from_paths_metadata(
+        [metadata(&global_config_path)?, metadata(&local_config_path)?],
+        options(),
+    )?
+    .expect("non-empty");
+    let expected = if expect_include {
+        "override-by-include"
+    } else {
+        "base-value"
+    };
+    assert_eq!(
+        config.string("section", None, "value"),
+        Some(cow_str(expected)),
+        "pattern {pattern:?} should match {url:?}: {expect_include}"
+    );
+    assure_git_agrees(expected, dir.path(), &git_dir)
+}
+
+fn assure_git_agrees(expected: &str, home: &Path, git_dir: &Path) -> Result {
+    let output = std::process::Command::new("git")
+        .args(["config", "--get", "section.value"])
+        .env("GIT_DIR", git_dir)
+        .env("HOME", home)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env_remove("GIT_CONFIG_COUNT")
+        .env_remove("XDG_CONFIG_HOME")
+        .current_dir(git_dir)
+        .output()?;
+
+    assert!(output.status.success(), "{output:?}");
+    let git_output: BString = output.stdout.trim_end().into();
+    assert_eq!(git_output, expected, "git disagrees with gix-config");
+    Ok(())
+}
+
+fn metadata(path: &Path) -> crate::Result<gix_config::file::Metadata> {
+    Ok(gix_config::file::Metadata::try_from_path(
+        path,
+        gix_config::Source::Api,
+    )?)
+}
+
+fn options() -> init::Options<'static> {
+    init::Options {
+        includes: includes::Options::follow(Default::default(), Default::default()),
+        ..Default::default()
+    }
+}
@@ -1,6 +1,9 @@
 use std::io::Read;
 
-use crate::helper::{Action, Context, Error, NextAction, Outcome, Result};
+use crate::{
+    helper::{Action, Context, Error, NextAction, Outcome, Result},
+    program::{main, Kind},
+};
 
 impl Action {
     /// Send ourselves to the given `write` which is expected to be credentials-helper compatible
@@ -40,6 +43,9 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
 }
 
 pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    if matches!(helper.kind, Kind::Store(_) | Kind::Cache(_)) {
+        return in_process(&helper.kind, action);
+    }
     let (mut stdin, stdout) = helper.start(action)?;
     if let (Action::Get(_), None) = (&action, &stdout) {
         panic!("BUG: `Helper` impls must return an output handle to read output from if Action::Get is provided")
@@ -66,3 +72,27 @@ pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::
         Some(stdout) => Ok(Some(stdout)),
     }
 }
+
+/// Perform `action` with the in-process helper of the given `kind`, producing the output of a helper program.
+fn in_process(kind: &Kind, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    let (main_action, mut ctx) = match action {
+        Action::Get(ctx) => (main::Action::Get, ctx.clone()),
+        Action::Store(payload) => (main::Action::Store, Context::from_bytes(payload)?),
+        Action::Erase(payload) => (main::Action::Erase, Context::from_bytes(payload)?),
+    };
+    if ctx.url.is_some() && ctx.protocol.is_none() {
+        ctx.destructure_url_in_place(true)
+            .map_err(|err| Error::CredentialsHelperFailed {
+                source: std::io::Error::new(std::io::ErrorKind::InvalidInput, err),
+            })?;
+    }
+    let outcome = match kind {
+        Kind::Store(options) => options.invoke(main_action, ctx),
+        Kind::Cache(options) => options.invoke(main_action, ctx),
+        _ => unreachable!("BUG: only called for in-process helpers"),
+    }
+    .map_err(|err| Error::CredentialsHelperFailed { source: err })?;
+    Ok(action
+        .expects_output()
+        .then(|| outcome.map(|ctx| ctx.to_bstring().into()).unwrap_or_default()))
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::{Read, Write},
+    net::Shutdown,
+    os::unix::{net::UnixStream, process::CommandExt},
+    path::Path,
+    process::{Command, Stdio},
+    sync::atomic::Ordering,
+};
+
+use crate::{
+    program::{
+        cache::{daemon, Options},
+        main::Action,
+    },
+    protocol::Context,
+};
+
+pub(super) fn invoke(options: &Options, action: Action, ctx: Context) -> std::io::Result<Option<Context>> {
+    let socket = options.socket_path().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not determine the path to the credential cache socket",
+        )
+    })?;
+    let mut request = format!("action={}\ntimeout={}\n", action.as_str(), options.timeout.as_secs()).into_bytes();
+    ctx.write_to(&mut request)?;
+
+    let mut stream = match UnixStream::connect(&socket) {
+        Ok(stream) => stream,
+        Err(err)
+            if matches!(
+                err.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            if !matches!(action, Action::Store) {
+                return Ok(None);
+            }
+            spawn_daemon(&socket)?;
+            UnixStream::connect(&socket)?
+        }
+        Err(err) => return Err(err),
+    };
+    stream.write_all(&request)?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    match action {
+        Action::Get if !response.is_empty() => Context::from_bytes(&response)
+            .map(Some)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+        Action::Get | Action::Store | Action::Erase => Ok(None),
+    }
+}
+
+/// Start the current executable as [daemon](super::daemon::serve_if_requested()) listening on `socket` and wait until it's ready.
+///
+/// It runs in its own process group to outlive the current process, even if the terminal interrupts it.
+fn spawn_daemon(socket: &Path) -> std::io::Result<()> {
+    if !daemon::CAN_SPAWN.load(Ordering::SeqCst) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "The credential cache daemon can only be started by applications that call `daemon::serve_if_requested()` on startup",
+        ));
+    }
+    let mut child = Command::new(std::env::current_exe()?)
+        .env(daemon::SOCKET_ENV, socket)
+        .process_group(0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    gix_trace::debug!(socket = ?socket, pid = child.id(), "started credential cache daemon");
+    let mut ready = [0; 3];
+    let res = child
+        .stdout
+        .take()
+        .expect("stdout is configured")
+        .read_exact(&mut ready);
+    if res.is_err() || &ready != b"ok\n" {
+        child.kill().ok();
+        child.wait()?;
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "The credential cache daemon did not start",
+        ));
+    }
+    Ok(())
+}
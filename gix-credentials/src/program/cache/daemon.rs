@@ -0,0 +1,219 @@
+use std::{
+    io::{Read, Write},
+    os::unix::{
+        fs::DirBuilderExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use bstr::ByteSlice;
+
+use crate::protocol::Context;
+
+/// A daemon to keep credentials in memory until they expire, which is compatible with `git credential-cache--daemon`.
+pub struct Daemon {
+    listener: UnixListener,
+    socket: PathBuf,
+}
+
+/// The amount of time to wait for the first credential to be stored before shutting down.
+const WAIT_FOR_FIRST_ENTRY: Duration = Duration::from_secs(30);
+
+/// The environment variable holding the socket to serve when the current executable is started as daemon.
+pub(super) const SOCKET_ENV: &str = "GIX_CREDENTIAL_CACHE_DAEMON_SOCKET";
+
+/// Set if the current executable [can run the daemon](serve_if_requested()), which is when it may be started as one.
+pub(super) static CAN_SPAWN: AtomicBool = AtomicBool::new(false);
+
+/// Serve credentials if the current process was started as daemon by the [cache helper](super::Options::invoke()),
+/// or return `None` if it should continue as usual.
+///
+/// As the cache helper starts the current executable again to run the daemon in the background, applications that want
+/// cached credentials to outlive them have to call this first thing in `main()` and exit once a result is returned.
+/// Without it, storing credentials in the cache fails if no daemon is running yet.
+pub fn serve_if_requested() -> Option<std::io::Result<()>> {
+    let Some(socket) = std::env::var_os(SOCKET_ENV) else {
+        CAN_SPAWN.store(true, Ordering::SeqCst);
+        return None;
+    };
+    Some(Daemon::bind(PathBuf::from(socket)).and_then(|daemon| {
+        // Tell the client that it can connect now.
+        let mut stdout = std::io::stdout();
+        stdout.write_all(b"ok\n")?;
+        stdout.flush()?;
+        daemon.serve()
+    }))
+}
+
+struct Entry {
+    credential: Context,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    entries: Vec<Entry>,
+    exit: bool,
+}
+
+type Shared = (Mutex<State>, Condvar);
+
+impl Daemon {
+    /// Listen on `socket`, replacing a stale socket and creating its directory with access for the current user only if needed.
+    pub fn bind(socket: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let socket = socket.into();
+        if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+        match std::fs::remove_file(&socket) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        Ok(Daemon {
+            listener: UnixListener::bind(&socket)?,
+            socket,
+        })
+    }
+
+    /// Serve requests until all stored credentials expired or a client asks us to `exit`, and remove the socket afterwards.
+    ///
+    /// If no credential is stored within the first 30 seconds, the daemon shuts down as well.
+    pub fn serve(self) -> std::io::Result<()> {
+        let state: Arc<Shared> = Default::default();
+        let expire = std::thread::spawn({
+            let state = Arc::clone(&state);
+            let socket = self.socket.clone();
+            move || expire_entries(&state, &socket)
+        });
+
+        let (lock, cvar) = &*state;
+        for stream in self.listener.incoming() {
+            if lock.lock().expect("no panic while holding the lock").exit {
+                break;
+            }
+            match stream.and_then(|stream| handle_request(stream, &state)) {
+                Ok(Request::Exit) => break,
+                Ok(Request::Continue) => {}
+                #[allow(unused_variables)]
+                Err(err) => gix_trace::warn!("Could not handle credential cache request: {}", err),
+            }
+        }
+
+        lock.lock().expect("no panic while holding the lock").exit = true;
+        cvar.notify_all();
+        expire.join().ok();
+        std::fs::remove_file(&self.socket).ok();
+        Ok(())
+    }
+}
+
+enum Request {
+    Continue,
+    Exit,
+}
+
+fn handle_request(mut stream: UnixStream, state: &Shared) -> std::io::Result<Request> {
+    let mut request = Vec::new();
+    stream.read_to_end(&mut request)?;
+    let mut lines = request.splitn(3, |b| *b == b'\n');
+    let (Some(action), Some(timeout)) = (
+        lines.next().and_then(|line| line.strip_prefix(b"action=")),
+        lines
+            .next()
+            .and_then(|line| line.strip_prefix(b"timeout="))
+            .and_then(|timeout| timeout.to_str().ok()?.parse::<i64>().ok()),
+    ) else {
+        return Ok(Request::Continue);
+    };
+    let mut ctx = Context::from_bytes(lines.next().unwrap_or_default())
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    if ctx.url.is_some() && ctx.protocol.is_none() {
+        ctx.destructure_url_in_place(true)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    }
+    ctx.url = None;
+    ctx.quit = None;
+
+    let (lock, cvar) = state;
+    let mut state = lock.lock().expect("no panic while holding the lock");
+    let now = Instant::now();
+    match action {
+        b"exit" => return Ok(Request::Exit),
+        b"get" => {
+            if let Some(entry) = state
+                .entries
+                .iter()
+                .find(|entry| entry.expires_at > now && ctx.matches(&entry.credential, false))
+            {
+                let credential = Context {
+                    username: entry.credential.username.clone(),
+                    password: entry.credential.password.clone(),
+                    ..Default::default()
+                };
+                drop(state);
+                let mut out = Vec::new();
+                credential.write_to(&mut out)?;
+                stream.write_all(&out)?;
+            }
+        }
+        b"erase" => state.entries.retain(|entry| !ctx.matches(&entry.credential, true)),
+        b"store" => {
+            if timeout < 0 || ctx.username.is_none() || ctx.password.is_none() {
+                gix_trace::warn!("The credential cache client provided a partial credential or no timeout");
+            } else {
+                state.entries.retain(|entry| !ctx.matches(&entry.credential, false));
+                state.entries.push(Entry {
+                    credential: ctx,
+                    expires_at: now + Duration::from_secs(timeout as u64),
+                });
+                cvar.notify_all();
+            }
+        }
+        #[allow(unused_variables)]
+        unknown => gix_trace::warn!(
+            "The credential cache client sent unknown action: {:?}",
+            unknown.as_bstr()
+        ),
+    }
+    Ok(Request::Continue)
+}
+
+/// Remove entries as they expire and shut down the daemon listening on `socket` once none are left.
+fn expire_entries((lock, cvar): &Shared, socket: &Path) {
+    let wait_for_first_entry_until = Instant::now() + WAIT_FOR_FIRST_ENTRY;
+    let mut state = lock.lock().expect("no panic while holding the lock");
+    loop {
+        if state.exit {
+            return;
+        }
+        let now = Instant::now();
+        state.entries.retain(|entry| entry.expires_at > now);
+        let next_expiry = state
+            .entries
+            .iter()
+            .map(|entry| entry.expires_at)
+            .min()
+            .or_else(|| (wait_for_first_entry_until > now).then_some(wait_for_first_entry_until));
+        match next_expiry {
+            Some(next_expiry) => {
+                state = cvar
+                    .wait_timeout(state, next_expiry - now)
+                    .expect("no panic while holding the lock")
+                    .0;
+            }
+            None => {
+                state.exit = true;
+                drop(state);
+                // Wake up the listener so it can observe that it should exit.
+                UnixStream::connect(socket).ok();
+                return;
+            }
+        }
+    }
+}
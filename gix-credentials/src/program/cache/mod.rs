@@ -0,0 +1,71 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{program::main::Action, protocol::Context};
+
+/// Options for the in-process `cache` helper, which behaves like `git credential-cache` and keeps credentials in the memory
+/// of a [daemon](daemon::Daemon) for a limited time, talking to it through a unix socket.
+///
+/// The protocol spoken with the daemon is the one of `git credential-cache--daemon`, so either one can be used by the other.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// The path to the unix socket of the daemon, like `--socket=<path>`.
+    ///
+    /// If `None`, `~/.git-credential-cache/socket` is used if its directory exists, or `$XDG_CACHE_HOME/git/credential/socket`.
+    pub socket: Option<PathBuf>,
+    /// The amount of time credentials are cached for once they are stored, like `--timeout=<seconds>`.
+    pub timeout: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            socket: None,
+            timeout: Duration::from_secs(900),
+        }
+    }
+}
+
+impl Options {
+    /// Return the path to the socket to connect to, or `None` if no home directory could be found to derive it from.
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.socket.clone().or_else(|| {
+            let home = gix_path::env::home_dir();
+            match home.as_ref().map(|home| home.join(".git-credential-cache")) {
+                Some(dir) if dir.is_dir() => Some(dir.join("socket")),
+                _ => gix_path::env::var("XDG_CACHE_HOME")
+                    .map(PathBuf::from)
+                    .or_else(|| home.map(|home| home.join(".cache")))
+                    .map(|cache| cache.join("git").join("credential").join("socket")),
+            }
+        })
+    }
+
+    /// Perform `action` with `ctx` by sending it to the cache daemon, returning the credentials found for [`Action::Get`].
+    ///
+    /// If no daemon is running when storing credentials, the current executable is started again as daemon in the background
+    /// so the credentials outlive the current process, which requires it to call [`daemon::serve_if_requested()`] on startup.
+    /// Alternatively, applications may [serve](daemon::Daemon::serve()) a [daemon](daemon::Daemon) on the socket themselves.
+    ///
+    /// Note that this is only supported on unix, and fails on all other platforms.
+    pub fn invoke(&self, action: Action, ctx: Context) -> std::io::Result<Option<Context>> {
+        #[cfg(unix)]
+        {
+            client::invoke(self, action, ctx)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (action, ctx);
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "The credential cache needs unix sockets",
+            ))
+        }
+    }
+}
+
+#[cfg(unix)]
+mod client;
+
+///
+#[cfg(unix)]
+pub mod daemon;
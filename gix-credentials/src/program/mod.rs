@@ -1,6 +1,10 @@
-use std::process::{Command, Stdio};
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::Duration,
+};
 
-use bstr::{BString, ByteSlice, ByteVec};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 
 use crate::{helper, Program};
 
@@ -24,6 +28,10 @@ pub enum Kind {
     },
     /// A script to execute with `sh`.
     ExternalShellScript(BString),
+    /// The `store` helper, implemented in-process to behave like `git credential-store`.
+    Store(store::Options),
+    /// The `cache` helper, implemented in-process to behave like `git credential-cache`.
+    Cache(cache::Options),
 }
 
 /// Initialization
@@ -39,9 +47,14 @@ impl Program {
 
     /// Parse the given input as per the custom helper definition, supporting `!<script>`, `name` and `/absolute/name`, the latter two
     /// also support arguments which are ignored here.
+    ///
+    /// The names `store` and `cache` along with the arguments they understand are turned into their [in-process](Kind::Store)
+    /// [implementations](Kind::Cache), so they work even if `git` isn't installed.
     pub fn from_custom_definition(input: impl Into<BString>) -> Self {
         fn from_custom_definition_inner(mut input: BString) -> Program {
-            let kind = if input.starts_with(b"!") {
+            let kind = if let Some(kind) = in_process_kind(input.as_ref()) {
+                kind
+            } else if input.starts_with(b"!") {
                 input.remove(0);
                 Kind::ExternalShellScript(input)
             } else {
@@ -92,6 +105,25 @@ impl Program {
                 .with_shell()
                 .arg(action.as_arg(true))
                 .into(),
+            Kind::Store(store::Options { file }) => {
+                let mut cmd = Command::new(git_program);
+                cmd.arg("credential-store");
+                if let Some(file) = file {
+                    cmd.arg("--file").arg(file);
+                }
+                cmd.arg(action.as_arg(true));
+                cmd
+            }
+            Kind::Cache(cache::Options { socket, timeout }) => {
+                let mut cmd = Command::new(git_program);
+                cmd.arg("credential-cache")
+                    .arg(format!("--timeout={}", timeout.as_secs()));
+                if let Some(socket) = socket {
+                    cmd.arg("--socket").arg(socket);
+                }
+                cmd.arg(action.as_arg(true));
+                cmd
+            }
         };
         cmd.stdin(Stdio::piped())
             .stdout(if action.expects_output() {
@@ -104,6 +136,54 @@ impl Program {
     }
 }
 
+/// Parse `input` as `store` or `cache` helper with the arguments they support, or return `None` if it's something else
+/// or uses arguments or shell syntax we don't understand.
+fn in_process_kind(input: &BStr) -> Option<Kind> {
+    fn path(arg: &[u8]) -> Option<PathBuf> {
+        if arg.iter().any(|b| b"\"'$`\\".contains(b)) {
+            return None;
+        }
+        match arg.strip_prefix(b"~/") {
+            Some(rest) => Some(gix_path::env::home_dir()?.join(gix_path::from_byte_slice(rest))),
+            None => Some(gix_path::from_byte_slice(arg).to_owned()),
+        }
+    }
+    let mut args = input.fields();
+    let name = args.next()?;
+    let mut flags = Vec::new();
+    while let Some(arg) = args.next() {
+        let arg = arg.strip_prefix(b"--")?;
+        flags.push(match arg.find_byte(b'=') {
+            Some(pos) => (&arg[..pos], &arg[pos + 1..]),
+            None => (arg, args.next()?),
+        });
+    }
+    match name {
+        b"store" => {
+            let mut options = store::Options::default();
+            for (flag, value) in flags {
+                match flag {
+                    b"file" => options.file = Some(path(value)?),
+                    _ => return None,
+                }
+            }
+            Some(Kind::Store(options))
+        }
+        b"cache" => {
+            let mut options = cache::Options::default();
+            for (flag, value) in flags {
+                match flag {
+                    b"timeout" => options.timeout = Duration::from_secs(value.to_str().ok()?.parse().ok()?),
+                    b"socket" => options.socket = Some(path(value)?),
+                    _ => return None,
+                }
+            }
+            Some(Kind::Cache(options))
+        }
+        _ => None,
+    }
+}
+
 /// Builder
 impl Program {
     /// By default `stderr` of programs is inherited and typically displayed in the terminal.
@@ -146,3 +226,8 @@ impl Program {
 ///
 pub mod main;
 pub use main::function::main;
+
+///
+pub mod cache;
+///
+pub mod store;
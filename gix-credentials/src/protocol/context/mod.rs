@@ -34,6 +34,20 @@ mod access {
             }
             buf.into()
         }
+        /// Return `true` if all of the protocol, host, path and username that are set in this instance are set to the
+        /// same value in `other`, like `git` does when looking for credentials matching the ones it wants.
+        /// The password is compared the same way if `match_password` is `true`.
+        pub fn matches(&self, other: &Context, match_password: bool) -> bool {
+            fn field_matches<T: PartialEq>(want: &Option<T>, have: &Option<T>) -> bool {
+                want.as_ref().map_or(true, |want| have.as_ref() == Some(want))
+            }
+            field_matches(&self.protocol, &other.protocol)
+                && field_matches(&self.host, &other.host)
+                && field_matches(&self.path, &other.path)
+                && field_matches(&self.username, &other.username)
+                && (!match_password || field_matches(&self.password, &other.password))
+        }
+
         /// Compute a prompt to obtain the given value.
         pub fn to_prompt(&self, field: &str) -> String {
             match self.to_url() {
@@ -0,0 +1,76 @@
+//! The credential cache helper starts the current executable as daemon, which is why this test needs its own `main()`.
+
+#[cfg(unix)]
+fn main() -> gix_testtools::Result {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixStream,
+        time::{Duration, Instant},
+    };
+
+    use gix_credentials::{
+        program::{cache, cache::daemon, main::Action},
+        protocol::Context,
+    };
+
+    if let Some(res) = daemon::serve_if_requested() {
+        return Ok(res?);
+    }
+
+    let want = Context {
+        protocol: Some("https".into()),
+        host: Some("example.com".into()),
+        ..Default::default()
+    };
+    const SOCKET: &str = "GIX_CREDENTIALS_TEST_CACHE_SOCKET";
+    if let Some(socket) = std::env::var_os(SOCKET) {
+        let options = cache::Options {
+            socket: Some(socket.into()),
+            ..Default::default()
+        };
+        options.invoke(
+            Action::Store,
+            Context {
+                username: Some("user".into()),
+                password: Some("pass".into()),
+                ..want
+            },
+        )?;
+        return Ok(());
+    }
+
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("cache").join("socket");
+    let status = std::process::Command::new(std::env::current_exe()?)
+        .env(SOCKET, &socket)
+        .status()?;
+    assert!(status.success(), "the child process stored the credentials");
+
+    let options = cache::Options {
+        socket: Some(socket.clone()),
+        ..Default::default()
+    };
+    assert_eq!(
+        options.invoke(Action::Get, want)?,
+        Some(Context {
+            username: Some("user".into()),
+            password: Some("pass".into()),
+            ..Default::default()
+        }),
+        "the daemon started by the child process is still running"
+    );
+
+    let mut stream = UnixStream::connect(&socket)?;
+    stream.write_all(b"action=exit\ntimeout=0\n")?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    stream.read_to_end(&mut Vec::new())?;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while socket.exists() {
+        assert!(Instant::now() < deadline, "the daemon removes its socket when exiting");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() {}
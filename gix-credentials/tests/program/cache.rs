@@ -0,0 +1,178 @@
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bstr::ByteSlice;
+use gix_credentials::{
+    program::{cache, cache::daemon::Daemon, main::Action},
+    protocol::Context,
+};
+
+#[test]
+fn credentials_can_be_stored_retrieved_and_erased() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let (options, daemon) = start_daemon(dir.path())?;
+
+    assert_eq!(options.invoke(Action::Get, want("example.com"))?, None);
+    options.invoke(Action::Store, complete_context("example.com", "user", "pass"))?;
+    options.invoke(Action::Store, complete_context("other.org", "user", "other"))?;
+    assert_eq!(
+        options.invoke(Action::Get, want("example.com"))?,
+        Some(identity("user", "pass"))
+    );
+
+    options.invoke(Action::Store, complete_context("example.com", "user", "new"))?;
+    assert_eq!(
+        options.invoke(Action::Get, want("example.com"))?,
+        Some(identity("user", "new")),
+        "matching credentials are replaced"
+    );
+
+    options.invoke(Action::Erase, complete_context("example.com", "user", "wrong"))?;
+    assert_eq!(
+        options.invoke(Action::Get, want("example.com"))?,
+        Some(identity("user", "new")),
+        "passwords have to match when erasing"
+    );
+    options.invoke(Action::Erase, want("example.com"))?;
+    assert_eq!(options.invoke(Action::Get, want("example.com"))?, None);
+    assert_eq!(
+        options.invoke(Action::Get, want("other.org"))?,
+        Some(identity("user", "other"))
+    );
+
+    stop_daemon(&options, daemon)
+}
+
+#[test]
+fn credentials_expire_after_the_timeout() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let (mut options, daemon) = start_daemon(dir.path())?;
+    options.timeout = Duration::ZERO;
+    options.invoke(Action::Store, complete_context("example.com", "user", "pass"))?;
+    assert_eq!(options.invoke(Action::Get, want("example.com"))?, None);
+    stop_daemon(&options, daemon)
+}
+
+#[test]
+fn git_credential_cache_can_use_our_daemon() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let (options, daemon) = start_daemon(dir.path())?;
+    let socket = options.socket.clone().expect("set");
+
+    git_credential_cache(
+        &socket,
+        "store",
+        "protocol=https\nhost=example.com\nusername=git-user\npassword=git-pass\n",
+    )?;
+    assert_eq!(
+        options.invoke(Action::Get, want("example.com"))?,
+        Some(identity("git-user", "git-pass"))
+    );
+
+    options.invoke(Action::Store, complete_context("other.org", "user", "pass"))?;
+    assert_eq!(
+        git_credential_cache(&socket, "get", "protocol=https\nhost=other.org\n")?.as_bstr(),
+        "username=user\npassword=pass\n"
+    );
+
+    git_credential_cache(&socket, "exit", "")?;
+    daemon.join().expect("no panic")?;
+    assert!(!socket.exists(), "the socket is removed when the daemon exits");
+    Ok(())
+}
+
+#[test]
+fn nothing_happens_without_daemon_unless_storing() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let options = cache::Options {
+        socket: Some(dir.path().join("socket")),
+        ..Default::default()
+    };
+    assert_eq!(options.invoke(Action::Get, want("example.com"))?, None);
+    options.invoke(Action::Erase, want("example.com"))?;
+    assert!(!dir.path().join("socket").exists(), "no daemon was started");
+    Ok(())
+}
+
+#[test]
+fn storing_without_daemon_fails_if_the_executable_cannot_serve_as_one() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let options = cache::Options {
+        socket: Some(dir.path().join("socket")),
+        ..Default::default()
+    };
+    let err = options
+        .invoke(Action::Store, complete_context("example.com", "user", "pass"))
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        std::io::ErrorKind::Unsupported,
+        "`daemon::serve_if_requested()` wasn't called, so this executable can't be started as daemon"
+    );
+    Ok(())
+}
+
+fn start_daemon(dir: &Path) -> std::io::Result<(cache::Options, std::thread::JoinHandle<std::io::Result<()>>)> {
+    let socket = dir.join("cache").join("socket");
+    let daemon = Daemon::bind(&socket)?;
+    Ok((
+        cache::Options {
+            socket: Some(socket),
+            ..Default::default()
+        },
+        std::thread::spawn(move || daemon.serve()),
+    ))
+}
+
+fn stop_daemon(options: &cache::Options, daemon: std::thread::JoinHandle<std::io::Result<()>>) -> crate::Result {
+    let socket: PathBuf = options.socket.clone().expect("set");
+    let mut stream = UnixStream::connect(&socket)?;
+    stream.write_all(b"action=exit\ntimeout=0\n")?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    stream.read_to_end(&mut Vec::new())?;
+    daemon.join().expect("no panic")?;
+    assert!(!socket.exists(), "the socket is removed when the daemon exits");
+    Ok(())
+}
+
+fn want(host: &str) -> Context {
+    Context {
+        protocol: Some("https".into()),
+        host: Some(host.into()),
+        ..Default::default()
+    }
+}
+
+fn complete_context(host: &str, username: &str, password: &str) -> Context {
+    Context {
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..want(host)
+    }
+}
+
+fn identity(username: &str, password: &str) -> Context {
+    Context {
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..Default::default()
+    }
+}
+
+fn git_credential_cache(socket: &Path, action: &str, input: &str) -> crate::Result<Vec<u8>> {
+    let mut child = std::process::Command::new("git")
+        .args(["credential-cache", "--socket"])
+        .arg(socket)
+        .arg(action)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    child.stdin.take().expect("configured").write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success(), "{output:?}");
+    Ok(output.stdout)
+}
@@ -1,4 +1,10 @@
-use gix_credentials::{helper, program::Kind, Program};
+use std::time::Duration;
+
+use gix_credentials::{
+    helper,
+    program::{cache, store, Kind},
+    Program,
+};
 
 #[cfg(windows)]
 const GIT: &str = "git.exe";
@@ -108,3 +114,61 @@ fn path_with_simple_args() {
         "a shell is used as there are arguments, and it's generally more flexible, but on windows we split ourselves"
     );
 }
+
+#[test]
+fn store_is_in_process() {
+    let prog = Program::from_custom_definition("store");
+    assert_eq!(prog.kind, Kind::Store(store::Options::default()));
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{GIT}" "credential-store" "store""#),
+        "the command is what git would run"
+    );
+
+    for input in ["store --file=/path/to/file", "store --file /path/to/file"] {
+        let prog = Program::from_custom_definition(input);
+        assert_eq!(
+            prog.kind,
+            Kind::Store(store::Options {
+                file: Some("/path/to/file".into())
+            })
+        );
+    }
+}
+
+#[test]
+fn cache_is_in_process() {
+    let prog = Program::from_custom_definition("cache");
+    assert_eq!(prog.kind, Kind::Cache(cache::Options::default()));
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{GIT}" "credential-cache" "--timeout=900" "store""#),
+    );
+
+    let prog = Program::from_custom_definition("cache --timeout=60 --socket /path/to/socket");
+    assert_eq!(
+        prog.kind,
+        Kind::Cache(cache::Options {
+            socket: Some("/path/to/socket".into()),
+            timeout: Duration::from_secs(60),
+        })
+    );
+}
+
+#[test]
+fn store_and_cache_with_unknown_arguments_or_shell_syntax_are_external() {
+    for input in [
+        "store --unknown",
+        "store --file",
+        "store --file \"/path with spaces\"",
+        "store --file=$HOME/file",
+        "cache --timeout=forever",
+        "cache --socket",
+    ] {
+        let prog = Program::from_custom_definition(input);
+        assert!(
+            matches!(&prog.kind, Kind::ExternalName { name_and_args } if name_and_args == input),
+            "{input}"
+        );
+    }
+}
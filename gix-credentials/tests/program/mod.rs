@@ -1 +1,4 @@
+#[cfg(unix)]
+mod cache;
 mod from_custom_definition;
+mod store;
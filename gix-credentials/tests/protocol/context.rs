@@ -173,3 +173,39 @@ mod to_url {
         );
     }
 }
+
+mod matches {
+    use gix_credentials::protocol::Context;
+
+    fn ctx(protocol: &str, host: &str, username: Option<&str>, password: Option<&str>) -> Context {
+        Context {
+            protocol: Some(protocol.into()),
+            host: Some(host.into()),
+            username: username.map(Into::into),
+            password: password.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_fields_set_in_self_are_compared() {
+        let have = ctx("https", "example.com", Some("user"), Some("pass"));
+        assert!(Context::default().matches(&have, true), "nothing is wanted");
+        assert!(ctx("https", "example.com", None, None).matches(&have, true));
+        assert!(ctx("https", "example.com", Some("user"), None).matches(&have, true));
+        assert!(!ctx("https", "example.com", Some("other"), None).matches(&have, true));
+        assert!(!ctx("http", "example.com", None, None).matches(&have, true));
+        assert!(
+            !have.matches(&ctx("https", "example.com", None, None), false),
+            "fields wanted by self must be set in other"
+        );
+    }
+
+    #[test]
+    fn passwords_are_only_compared_if_desired() {
+        let have = ctx("https", "example.com", Some("user"), Some("pass"));
+        let want = ctx("https", "example.com", Some("user"), Some("other"));
+        assert!(want.matches(&have, false));
+        assert!(!want.matches(&have, true));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{
+    parse::{
+        strict::match_multi_number,
+        tm::{at, local_offset, match_string, strtoul, Tm, MONTH_NAMES, WEEKDAY_NAMES},
+        Error,
+    },
+    SecondsSinceUnixEpoch,
+};
+
+/// Parse `input` as date relative to `now` by picking up whatever can be understood, or return `None` if nothing was.
+///
+/// Words like `yesterday`, `noon`, `last friday`, `3 days ago` or month names are understood along with numbers,
+/// where anything that isn't a known word or number is skipped, so `3.days.ago` works as well.
+/// Fields of the date that aren't mentioned are taken from `now`.
+pub(super) fn parse(input: &[u8], now: SecondsSinceUnixEpoch) -> Result<Option<SecondsSinceUnixEpoch>, Error> {
+    let offset = local_offset(now);
+    let now_tm = Tm::from_seconds(now, offset).ok_or(Error::RelativeTimeConversion)?;
+    let mut tm = Tm {
+        year: -1,
+        mon: -1,
+        mday: -1,
+        ..now_tm
+    };
+    let mut state = State {
+        now: &now_tm,
+        offset,
+        number: 0,
+        touched: false,
+    };
+
+    let mut pos = 0;
+    while pos < input.len() && input[pos] != 0 {
+        let c = input[pos];
+        if c.is_ascii_digit() {
+            pending_number(&mut tm, &mut state.number);
+            pos = approxidate_digit(input, pos, &mut tm, &mut state.number, now);
+            state.touched = true;
+        } else if c.is_ascii_alphabetic() {
+            pos = state.approxidate_alpha(input, pos, &mut tm)?;
+        } else {
+            pos += 1;
+        }
+    }
+    pending_number(&mut tm, &mut state.number);
+    let seconds = state.update_tm(&mut tm, 0)?;
+    Ok(state.touched.then_some(seconds))
+}
+
+struct State<'a> {
+    /// The time that relative dates refer to.
+    now: &'a Tm,
+    /// The offset of the local timezone in seconds.
+    offset: i32,
+    /// The number we have seen last, and which wasn't used yet, or `0`.
+    number: i64,
+    /// If `true`, we have understood something.
+    touched: bool,
+}
+
+impl State<'_> {
+    /// Fill in the date fields of `tm` that are unknown from the current time, and go back `seconds` in time.
+    ///
+    /// Return the resulting time after updating all fields in `tm` with it.
+    fn update_tm(&self, tm: &mut Tm, seconds: i64) -> Result<SecondsSinceUnixEpoch, Error> {
+        if tm.mday < 0 {
+            tm.mday = self.now.mday;
+        }
+        if tm.mon < 0 {
+            tm.mon = self.now.mon;
+        }
+        if tm.year < 0 {
+            tm.year = self.now.year;
+            if tm.mon > self.now.mon {
+                tm.year -= 1;
+            }
+        }
+
+        let time = tm
+            .to_seconds(self.offset)
+            .and_then(|time| time.checked_sub(seconds))
+            .ok_or(Error::RelativeTimeConversion)?;
+        *tm = Tm::from_seconds(time, self.offset).ok_or(Error::RelativeTimeConversion)?;
+        Ok(time)
+    }
+
+    /// Set `tm` to the previous occurrence of `hour`, which is today or yesterday.
+    fn date_time(&self, tm: &mut Tm, hour: i64) -> Result<(), Error> {
+        if tm.hour < hour {
+            self.update_tm(tm, 24 * 60 * 60)?;
+        }
+        tm.hour = hour;
+        tm.min = 0;
+        tm.sec = 0;
+        Ok(())
+    }
+
+    /// Handle the word starting at `pos` in `input` and return the position past its end.
+    fn approxidate_alpha(&mut self, input: &[u8], pos: usize, tm: &mut Tm) -> Result<usize, Error> {
+        let word = &input[pos..];
+        let mut end = pos + 1;
+        while at(input, end).is_ascii_alphabetic() {
+            end += 1;
+        }
+
+        for (month, name) in MONTH_NAMES.iter().enumerate() {
+            if match_string(word, name) >= 3 {
+                tm.mon = month as i64;
+                self.touched = true;
+                return Ok(end);
+            }
+        }
+
+        if let Some(special) = SPECIALS.iter().find(|s| match_string(word, s.name()) == s.name().len()) {
+            self.special(*special, tm)?;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        if self.number == 0 {
+            if let Some(number) = (1..NUMBER_NAMES.len()).find(|n| {
+                let name = NUMBER_NAMES[*n];
+                match_string(word, name) == name.len()
+            }) {
+                self.number = number as i64;
+                self.touched = true;
+            } else if match_string(word, "last") == 4 {
+                self.number = 1;
+                self.touched = true;
+            }
+            return Ok(end);
+        }
+
+        for (name, seconds) in TYPE_LENGTHS {
+            if match_string(word, name) >= name.len() - 1 {
+                let seconds = seconds.checked_mul(self.number).ok_or(Error::RelativeTimeConversion)?;
+                self.update_tm(tm, seconds)?;
+                self.number = 0;
+                self.touched = true;
+                return Ok(end);
+            }
+        }
+
+        for (weekday, name) in WEEKDAY_NAMES.iter().enumerate() {
+            if match_string(word, name) >= 3 {
+                let mut weeks = self.number - 1;
+                self.number = 0;
+                let mut days = tm.wday - weekday as i64;
+                if days <= 0 {
+                    weeks += 1;
+                }
+                days = weeks
+                    .checked_mul(7)
+                    .and_then(|weeks| weeks.checked_add(days))
+                    .and_then(|days| days.checked_mul(24 * 60 * 60))
+                    .ok_or(Error::RelativeTimeConversion)?;
+                self.update_tm(tm, days)?;
+                self.touched = true;
+                return Ok(end);
+            }
+        }
+
+        if match_string(word, "months") >= 5 {
+            self.update_tm(tm, 0)?;
+            let month = tm.mon.checked_sub(self.number).ok_or(Error::RelativeTimeConversion)?;
+            tm.year += month.div_euclid(12);
+            tm.mon = month.rem_euclid(12);
+            self.number = 0;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        if match_string(word, "years") >= 4 {
+            self.update_tm(tm, 0)?;
+            tm.year = tm.year.checked_sub(self.number).ok_or(Error::RelativeTimeConversion)?;
+            self.number = 0;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        Ok(end)
+    }
+
+    fn special(&mut self, special: Special, tm: &mut Tm) -> Result<(), Error> {
+        match special {
+            Special::Yesterday => {
+                self.number = 0;
+                self.update_tm(tm, 24 * 60 * 60)?;
+            }
+            Special::Noon | Special::Midnight | Special::Tea => {
+                pending_number(tm, &mut self.number);
+                let hour = match special {
+                    Special::Noon => 12,
+                    Special::Midnight => 0,
+                    _ => 17,
+                };
+                self.date_time(tm, hour)?;
+            }
+            Special::Pm | Special::Am => {
+                let mut hour = tm.hour;
+                if self.number != 0 {
+                    hour = self.number;
+                    tm.min = 0;
+                    tm.sec = 0;
+                }
+                self.number = 0;
+                tm.hour = (hour % 12) + if special == Special::Pm { 12 } else { 0 };
+            }
+            Special::Never => {
+                *tm = Tm::from_seconds(0, self.offset).ok_or(Error::RelativeTimeConversion)?;
+                self.number = 0;
+            }
+            Special::Now => {
+                self.number = 0;
+                self.update_tm(tm, 0)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Words with a special meaning, in the order in which they are matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Special {
+    Yesterday,
+    Noon,
+    Midnight,
+    Tea,
+    Pm,
+    Am,
+    Never,
+    Now,
+}
+
+const SPECIALS: [Special; 8] = [
+    Special::Yesterday,
+    Special::Noon,
+    Special::Midnight,
+    Special::Tea,
+    Special::Pm,
+    Special::Am,
+    Special::Never,
+    Special::Now,
+];
+
+impl Special {
+    fn name(&self) -> &'static str {
+        match self {
+            Special::Yesterday => "yesterday",
+            Special::Noon => "noon",
+            Special::Midnight => "midnight",
+            Special::Tea => "tea",
+            Special::Pm => "PM",
+            Special::Am => "AM",
+            Special::Never => "never",
+            Special::Now => "now",
+        }
+    }
+}
+
+const NUMBER_NAMES: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// Units of time along with their length in seconds, which may also be written without the trailing `s`.
+const TYPE_LENGTHS: [(&str, i64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("weeks", 7 * 24 * 60 * 60),
+];
+
+/// Handle the number starting at `pos` in `input` and return the position past its end.
+fn approxidate_digit(input: &[u8], pos: usize, tm: &mut Tm, number: &mut i64, now: SecondsSinceUnixEpoch) -> usize {
+    let (num, end) = strtoul(input, pos);
+    if let sep @ (b':' | b'.' | b'/' | b'-') = at(input, end) {
+        if at(input, end + 1).is_ascii_digit() {
+            let consumed = match_multi_number(num, sep, &input[pos..], end - pos, tm, now);
+            if consumed != 0 {
+                return pos + consumed;
+            }
+        }
+    }
+
+    // Accept zero-padding only for small numbers, like in `Dec 02`, but never `Dec 0002`.
+    if input[pos] != b'0' || end - pos <= 2 {
+        *number = num;
+    }
+    end
+}
+
+/// If there is a number that wasn't used yet, assume it's the day of the month as in `Dec 6, 1992`,
+/// or the month or the year if these are known already.
+fn pending_number(tm: &mut Tm, number: &mut i64) {
+    let num = *number;
+    if num == 0 {
+        return;
+    }
+    *number = 0;
+    if tm.mday < 0 && num < 32 {
+        tm.mday = num;
+    } else if tm.mon < 0 && num < 13 {
+        tm.mon = num - 1;
+    } else if tm.year < 0 {
+        if num > 1969 && num < 2100 {
+            tm.year = num - 1900;
+        } else if num > 69 && num < 100 {
+            tm.year = num;
+        } else if num < 38 {
+            tm.year = 100 + num;
+        }
+    }
+}
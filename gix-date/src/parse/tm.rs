@@ -0,0 +1,207 @@
+use crate::SecondsSinceUnixEpoch;
+
+/// The equivalent of `struct tm` as used by `git`, with each field being negative if it's unknown.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Tm {
+    /// Years since 1900.
+    pub year: i64,
+    /// Month of the year, starting at 0 for January.
+    pub mon: i64,
+    /// Day of the month, starting at 1.
+    pub mday: i64,
+    pub hour: i64,
+    pub min: i64,
+    pub sec: i64,
+    /// Day of the week, starting at 0 for Sunday.
+    pub wday: i64,
+}
+
+/// Years outside of this range can't be represented by the `time` crate, which we use to break down timestamps.
+const MAX_YEAR_DISTANCE: i64 = 10_000;
+
+impl Tm {
+    /// Return an instance with all fields unknown.
+    pub fn unknown() -> Self {
+        Tm {
+            year: -1,
+            mon: -1,
+            mday: -1,
+            hour: -1,
+            min: -1,
+            sec: -1,
+            wday: -1,
+        }
+    }
+
+    /// Break down `seconds` into their components as seen in a timezone `offset` seconds east of UTC,
+    /// like `gmtime_r()` or `localtime_r()`, or return `None` if it's out of range.
+    pub fn from_seconds(seconds: SecondsSinceUnixEpoch, offset: i32) -> Option<Self> {
+        let time = time::OffsetDateTime::from_unix_timestamp(seconds.checked_add(offset.into())?).ok()?;
+        Some(Tm {
+            year: i64::from(time.year()) - 1900,
+            mon: i64::from(u8::from(time.month())) - 1,
+            mday: time.day().into(),
+            hour: time.hour().into(),
+            min: time.minute().into(),
+            sec: time.second().into(),
+            wday: time.weekday().number_days_from_sunday().into(),
+        })
+    }
+
+    /// Turn this instance into seconds since the unix epoch, assuming it's in a timezone `offset` seconds east of UTC,
+    /// like `mktime()` would. Fields that are out of range are normalized, so the 32nd of January is the 1st of February.
+    pub fn to_seconds(self, offset: i32) -> Option<SecondsSinceUnixEpoch> {
+        let year = self.year.checked_add(1900)?.checked_add(self.mon.div_euclid(12))?;
+        if !(-MAX_YEAR_DISTANCE..=MAX_YEAR_DISTANCE).contains(&year) {
+            return None;
+        }
+        let days = days_from_civil(year, self.mon.rem_euclid(12) + 1, 1).checked_add(self.mday.checked_sub(1)?)?;
+        days.checked_mul(24 * 60 * 60)?
+            .checked_add(self.hour.checked_mul(60 * 60)?)?
+            .checked_add(self.min.checked_mul(60)?)?
+            .checked_add(self.sec)?
+            .checked_sub(offset.into())
+    }
+
+    /// Turn this instance into seconds since the unix epoch in UTC without normalization, or return `None` if
+    /// the date isn't fully known. Only years from 1970 to 2099 are supported, like `tm_to_time_t()` in `git`.
+    pub fn to_seconds_strict(self) -> Option<SecondsSinceUnixEpoch> {
+        const MDAYS: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+        let year = self.year - 70;
+        if !(0..=129).contains(&year) || !(0..=11).contains(&self.mon) {
+            return None;
+        }
+        let mut day = self.mday;
+        if self.mon < 2 || (year + 2) % 4 != 0 {
+            day -= 1;
+        }
+        if self.hour < 0 || self.min < 0 || self.sec < 0 {
+            return None;
+        }
+        Some(
+            (year * 365 + (year + 1) / 4 + MDAYS[self.mon as usize] + day) * 24 * 60 * 60
+                + self.hour * 60 * 60
+                + self.min * 60
+                + self.sec,
+        )
+    }
+
+    /// Return `true` if year, month and day are known.
+    pub fn is_date_known(&self) -> bool {
+        self.year != -1 && self.mon != -1 && self.mday != -1
+    }
+
+    /// Return `true` if no field that makes up a date or time is known yet.
+    pub fn is_unset(&self) -> bool {
+        (self.year & self.mon & self.mday & self.hour & self.min & self.sec) < 0
+    }
+}
+
+/// Return the offset of the local timezone in seconds at `seconds` since the unix epoch, or `0` if it can't be determined.
+pub(super) fn local_offset(seconds: SecondsSinceUnixEpoch) -> i32 {
+    time::OffsetDateTime::from_unix_timestamp(seconds)
+        .ok()
+        .and_then(|time| time::UtcOffset::local_offset_at(time).ok())
+        .map_or(0, time::UtcOffset::whole_seconds)
+}
+
+/// Return the amount of days since the unix epoch for the given date in the proleptic gregorian calendar,
+/// with `month` and `day` starting at 1.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Return the byte at `pos` in `input`, or `0` if it's out of bounds, just like reading the terminating null byte in C.
+pub(super) fn at(input: &[u8], pos: usize) -> u8 {
+    input.get(pos).copied().unwrap_or(0)
+}
+
+/// Parse a number like `strtol()`, starting at `pos` in `input`, and return it along with the position past its end.
+///
+/// Leading whitespace and a sign are skipped, values that are too large saturate and if there are no digits,
+/// `0` is returned along with `pos`.
+pub(super) fn strtol(input: &[u8], pos: usize) -> (i64, usize) {
+    let mut cursor = pos;
+    while matches!(at(input, cursor), b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') {
+        cursor += 1;
+    }
+    let negative = match at(input, cursor) {
+        b'-' => {
+            cursor += 1;
+            true
+        }
+        b'+' => {
+            cursor += 1;
+            false
+        }
+        _ => false,
+    };
+    let digits_start = cursor;
+    let mut value: i64 = 0;
+    while at(input, cursor).is_ascii_digit() {
+        value = value
+            .saturating_mul(10)
+            .saturating_add(i64::from(at(input, cursor) - b'0'));
+        cursor += 1;
+    }
+    if cursor == digits_start {
+        return (0, pos);
+    }
+    (if negative { -value } else { value }, cursor)
+}
+
+/// Like [`strtol()`], but negative numbers turn into the largest possible value as `strtoul()` would wrap them around.
+pub(super) fn strtoul(input: &[u8], pos: usize) -> (i64, usize) {
+    let (value, end) = strtol(input, pos);
+    (if value < 0 { i64::MAX } else { value }, end)
+}
+
+/// Return the length of the case-insensitive match of `input` with `word`, or `0` if `input` continues with
+/// an alphanumeric character that doesn't match. This is `match_string()` in `git`.
+pub(super) fn match_string(input: &[u8], word: &str) -> usize {
+    let word = word.as_bytes();
+    let mut pos = 0;
+    while pos < input.len() && input[pos] != 0 {
+        let (actual, expected) = (input[pos], at(word, pos));
+        if actual.eq_ignore_ascii_case(&expected) {
+            pos += 1;
+            continue;
+        }
+        if !actual.is_ascii_alphanumeric() {
+            break;
+        }
+        return 0;
+    }
+    pos
+}
+
+pub(super) const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+pub(super) const WEEKDAY_NAMES: [&str; 7] = [
+    "Sundays",
+    "Mondays",
+    "Tuesdays",
+    "Wednesdays",
+    "Thursdays",
+    "Fridays",
+    "Saturdays",
+];
@@ -3,6 +3,11 @@ set -eu -o pipefail
 
 git init;
 
+# Relative dates are resolved against this fixed point in time, Tue Jul 26 12:05:30 UTC 2022,
+# and the timezone is fixed as well as dates without explicit one are assumed to be local.
+export GIT_TEST_DATE_NOW=1658837130
+export TZ=UTC
+
 function baseline() {
     local test_date=$1 # first argument is the date to test
     local test_name=$2 # second argument is the format name for re-formatting
@@ -26,8 +31,7 @@ function baseline() {
 # date formats following to https://git-scm.com/docs/git-log#Documentation/git-log.txt---dateltformatgt
 
 # short
-# ODO
-#baseline '2022-08-22'
+baseline '2022-08-22' ''
 # rfc2822
 baseline 'Thu, 18 Aug 2022 12:45:06 +0800' 'RFC2822'
 # iso8601
@@ -42,5 +46,58 @@ baseline '1234567890' 'UNIX'
 # raw
 baseline '1660874655 +0800' 'RAW'
 
+# more absolute dates, similar to what `git` accepts in `GIT_AUTHOR_DATE`
+baseline '@1660874655 +0800' ''
+baseline '@42 +0030' ''
+baseline '1979-02-26 18:31:00' ''
+baseline '2022-08-17 22:04:58 -0000' ''
+baseline '17.08.2022 12:00' ''
+baseline '08/17/2022 12:00:00 +0200' ''
+baseline '20220817T120000Z' ''
+baseline 'Aug 17 2022 10:30pm' ''
+baseline 'Wed Aug 17 22:04:58 CEST 2022' ''
+baseline '2022-08-17 22:04:58.123 +0200' ''
+
+# approximate dates, relative to GIT_TEST_DATE_NOW
+baseline '2 weeks ago' ''
+baseline '2.weeks.ago' ''
+baseline '3.days.ago' ''
+baseline '5 hours ago' ''
+baseline '1.second.ago' ''
+baseline '10 minutes ago' ''
+baseline '1 month ago' ''
+baseline '8 months ago' ''
+baseline '2 years ago' ''
+baseline 'last year' ''
+baseline 'last week' ''
+baseline 'three days ago' ''
+baseline 'yesterday' ''
+baseline 'noon' ''
+baseline 'midnight' ''
+baseline 'tea' ''
+baseline 'noon yesterday' ''
+baseline 'yesterday noon' ''
+baseline '6am yesterday' ''
+baseline '10am' ''
+baseline '3pm' ''
+baseline '3:15pm' ''
+baseline '12:30' ''
+baseline 'friday' ''
+baseline 'last friday' ''
+baseline 'last tuesday' ''
+baseline '2 fridays ago' ''
+baseline 'Aug 1' ''
+baseline '1 Aug' ''
+baseline 'Dec 02' ''
+baseline 'Dec 0002' ''
+baseline 'june' ''
+baseline '2022-08-17' ''
+baseline '17.08.2022' ''
+baseline 'July 4th 2021' ''
+
+# failure
+baseline 'foobar' ''
+baseline 'ago' ''
+
 # Note that we can't necessarily put 64bit dates here yet as `git` on the system might not yet support it.
 
@@ -7,6 +7,9 @@ use gix_date::{
 use gix_testtools::Result;
 use once_cell::sync::Lazy;
 
+/// The time that relative dates in the baseline refer to, as set with `GIT_TEST_DATE_NOW`.
+const NOW: SecondsSinceUnixEpoch = 1658837130;
+
 struct Sample {
     format_name: Option<String>,
     exit_code: usize,
@@ -53,7 +56,8 @@ fn parse_compare_format() {
         },
     ) in BASELINE.iter()
     {
-        let res = gix_date::parse(pattern.as_str(), Some(SystemTime::now()));
+        let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(NOW as u64);
+        let res = gix_date::parse(pattern.as_str(), Some(now));
         assert_eq!(
             res.is_ok(),
             *exit_code == 0,
@@ -14,17 +14,71 @@ fn special_time_is_ok_for_now() {
     );
 }
 
+#[test]
+fn object_header_date_with_at_prefix() {
+    assert_eq!(
+        gix_date::parse("@42 +0030", None).unwrap(),
+        Time {
+            seconds: 42,
+            offset: 1800,
+            sign: Sign::Plus,
+        }
+    );
+    assert_eq!(
+        gix_date::parse("@42 -0000", None).unwrap(),
+        Time {
+            seconds: 42,
+            offset: 0,
+            sign: Sign::Minus,
+        },
+        "the sign of a zero offset is retained"
+    );
+}
+
+#[test]
+fn iso8601_without_timezone_is_local_time() {
+    let time = gix_date::parse("1979-02-26 18:31:00", None).unwrap();
+    assert_eq!(
+        time.seconds + i64::from(time.offset),
+        288901860,
+        "the offset is the one of the local timezone, but the time of day is always as written"
+    );
+}
+
+#[test]
+fn various_absolute_dates() {
+    for (input, seconds, offset) in [
+        ("2022-08-17 22:04:58 +0200", 1660766698, 7200),
+        ("2022-08-17T22:04:58+02:00", 1660766698, 7200),
+        ("Wed Aug 17 22:04:58 CEST 2022", 1660766698, 7200),
+        ("17.08.2022 22:04:58 +0200", 1660766698, 7200),
+        ("08/17/2022 22:04:58 +0200", 1660766698, 7200),
+        ("20220817T200458Z", 1660766698, 0),
+        ("Aug 17 2022 10:04:58pm +0200", 1660766698, 7200),
+        ("2022-08-17 22:04:58.123 +0200", 1660766698, 7200),
+        ("@1660766698 +0200", 1660766698, 7200),
+    ] {
+        let time = gix_date::parse(input, None).unwrap_or_else(|err| panic!("{input:?} should parse: {err}"));
+        assert_eq!((time.seconds, time.offset), (seconds, offset), "{input:?}");
+    }
+}
+
 #[test]
 fn short() {
+    let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1658837130);
     assert_eq!(
-        gix_date::parse("1979-02-26", Some(SystemTime::now())).unwrap(),
+        gix_date::parse("1979-02-26", Some(now)).unwrap(),
         Time {
-            seconds: 288835200,
+            seconds: 288835200 + 12 * 3600 + 5 * 60 + 30,
             offset: 0,
             sign: Sign::Plus,
         },
-        "could not parse with SHORT format"
+        "like in git, the time of day is the one of `now`"
     );
+    assert!(matches!(
+        gix_date::parse("1979-02-26", None).unwrap_err(),
+        gix_date::parse::Error::MissingCurrentTime
+    ));
 }
 
 #[test]
@@ -161,14 +215,56 @@ mod relative {
             "relative times differ"
         );
     }
+    #[test]
+    fn words_and_units() {
+        // Tue Jul 26 12:05:30 UTC 2022
+        let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1658837130);
+        let day = 24 * 60 * 60;
+        for (input, expected) in [
+            ("now", 1658837130),
+            ("3.days.ago", 1658837130 - 3 * day),
+            ("three days ago", 1658837130 - 3 * day),
+            ("last week", 1658837130 - 7 * day),
+            ("yesterday", 1658837130 - day),
+            ("noon", 1658837130 - 5 * 60 - 30),
+            ("tea", 1658837130 - day + 5 * 3600 - 5 * 60 - 30),
+            ("noon yesterday", 1658837130 - day - 5 * 60 - 30),
+            ("last friday", 1658837130 - 4 * day),
+            ("last tuesday", 1658837130 - 7 * day),
+            ("1 month ago", 1658837130 - 30 * day),
+            ("2 years ago", 1658837130 - 730 * day),
+            ("Aug 1", 1627819530),
+        ] {
+            let time = gix_date::parse(input, Some(now)).unwrap_or_else(|err| panic!("{input:?} should parse: {err}"));
+            assert_eq!(time.seconds, expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn nothing_understood_is_an_error() {
+        let now = SystemTime::now();
+        for input in ["foobar", "ago", ""] {
+            assert!(matches!(
+                gix_date::parse(input, Some(now)).unwrap_err(),
+                gix_date::parse::Error::InvalidDateString { .. }
+            ));
+        }
+    }
 }
 
 /// Various cases the fuzzer found
 mod fuzz {
     #[test]
     fn invalid_but_does_not_cause_panic() {
-        for input in ["7	-𬞋", "5 ڜ-09", "-4 week ago Z", "8960609 day ago"] {
+        for input in ["8960609 day ago", "99999999999999 hours ago"] {
             let _ = gix_date::parse(input, Some(std::time::UNIX_EPOCH)).unwrap_err();
         }
     }
+
+    #[test]
+    fn approximately_valid_and_does_not_cause_panic() {
+        for input in ["7	-𬞋", "5 ڜ-09", "-4 week ago Z", "9999999999999999999 months ago"] {
+            let _ = gix_date::parse(input, Some(std::time::UNIX_EPOCH)).expect("numbers are picked up, like git does");
+        }
+    }
 }
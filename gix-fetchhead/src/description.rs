@@ -0,0 +1,107 @@
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{Description, Kind};
+
+/// Kinds that are written with a prefix, along with the prefix of their reference names.
+/// Note that the `'` is part of the written prefix as the name follows right after.
+const KINDS: [(Kind, &str, &str); 4] = [
+    (Kind::Branch, "branch '", "refs/heads/"),
+    (Kind::Tag, "tag '", "refs/tags/"),
+    (Kind::RemoteTrackingBranch, "remote-tracking branch '", "refs/remotes/"),
+    (Kind::Other, "'", ""),
+];
+
+/// Separates the name of the reference from the URL. As reference names can't contain spaces, it's unambiguous.
+const NAME_END: &str = "' of ";
+
+impl<'a> Description<'a> {
+    /// Describe the remote reference with full `name`, like `refs/heads/main` or `HEAD`, as fetched from `url`,
+    /// the way `git` does it.
+    ///
+    /// This shortens the `url` by removing trailing slashes and the `.git` suffix, so `https://host/repo.git/`
+    /// becomes `https://host/repo`.
+    pub fn from_ref_name(name: &'a BStr, url: &'a BStr) -> Self {
+        let (kind, name) = if name == "HEAD" {
+            (Kind::Head, "".into())
+        } else {
+            KINDS
+                .iter()
+                .find_map(|(kind, _, ref_prefix)| name.strip_prefix(ref_prefix.as_bytes()).map(|name| (*kind, name)))
+                .map(|(kind, name)| (kind, name.as_bstr()))
+                .expect("the last kind matches everything")
+        };
+        Description {
+            kind: if name.is_empty() { Kind::Head } else { kind },
+            name,
+            url: shorten_url(url),
+        }
+    }
+
+    /// Return the full name of the remote reference, like `refs/heads/main` for a [branch](Kind::Branch),
+    /// or `HEAD` for [`Kind::Head`].
+    pub fn ref_name(&self) -> BString {
+        match self.kind {
+            Kind::Head => "HEAD".into(),
+            kind => {
+                let ref_prefix = KINDS
+                    .iter()
+                    .find_map(|(k, _, ref_prefix)| (*k == kind).then_some(*ref_prefix))
+                    .expect("all kinds but head are known");
+                let mut name = BString::from(ref_prefix);
+                name.extend_from_slice(self.name);
+                name
+            }
+        }
+    }
+
+    /// Parse `description` as written by `git`, falling back to a [`Kind::Head`] description with `description`
+    /// as URL if it can't be understood otherwise.
+    pub(crate) fn from_bytes(description: &'a BStr) -> Self {
+        KINDS
+            .iter()
+            .find_map(|(kind, prefix, _)| {
+                let rest = description.strip_prefix(prefix.as_bytes())?;
+                let (name, url) = rest.split_once_str(NAME_END)?;
+                Some(Description {
+                    kind: *kind,
+                    name: name.as_bstr(),
+                    url: url.as_bstr(),
+                })
+            })
+            .unwrap_or(Description {
+                kind: Kind::Head,
+                name: "".into(),
+                url: description,
+            })
+    }
+
+    /// Write this instance to `out` like `git` would, escaping newlines in the URL.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        if !self.name.is_empty() {
+            if let Some((_, prefix, _)) = KINDS.iter().find(|(kind, _, _)| *kind == self.kind) {
+                out.write_all(prefix.as_bytes())?;
+                out.write_all(self.name)?;
+                out.write_all(NAME_END.as_bytes())?;
+            }
+        }
+        let mut url = self.url.as_bytes();
+        while let Some(pos) = url.find_byte(b'\n') {
+            out.write_all(&url[..pos])?;
+            out.write_all(b"\\n")?;
+            url = &url[pos + 1..];
+        }
+        out.write_all(url)
+    }
+}
+
+/// Remove trailing slashes from `url` along with the `.git` suffix, like `git` does when writing `FETCH_HEAD`.
+fn shorten_url(url: &BStr) -> &BStr {
+    let url = match url.iter().rposition(|b| *b != b'/') {
+        Some(last) => &url[..=last],
+        None => &url[..0],
+    };
+    match url.strip_suffix(b".git") {
+        Some(stripped) if url.len() > 5 => stripped.as_bstr(),
+        _ => url.as_bstr(),
+    }
+}
@@ -1,2 +1,66 @@
-#![deny(rust_2018_idioms)]
+//! [Parse][parse()] and [write][Entry::write_to()] the `FETCH_HEAD` file, which `git fetch` uses to remember
+//! all objects it fetched along with the remote references they came from.
+//!
+//! Each line of the file is an [`Entry`], which looks like `<id>\t[not-for-merge]\t<description>`.
+//! Entries without the `not-for-merge` marker are the ones that `git pull` merges into the current branch.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::BStr;
+use gix_hash::ObjectId;
+
+///
+pub mod parse;
+
+/// Parse the given `buf` of bytes line by line into [entries][Entry].
+///
+/// Errors may occur per line, but it's up to the caller to stop iteration when
+/// one is encountered.
+pub fn parse(buf: &[u8]) -> parse::Lines<'_> {
+    parse::Lines::new(buf)
+}
+
+mod description;
+mod write;
+
+/// A line in the `FETCH_HEAD` file, representing one object that was obtained from a remote.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Entry<'a> {
+    /// The object the remote reference pointed to, which isn't peeled, so it may be an annotated tag.
+    pub id: ObjectId,
+    /// If `true`, the object isn't meant to be merged by `git pull`, which only merges the objects
+    /// of the remote references that the current branch is configured to merge with.
+    pub not_for_merge: bool,
+    /// Information about where the object was fetched from.
+    pub description: Description<'a>,
+}
+
+/// The kind of the remote reference an [`Entry`] was fetched from, which affects how its [`Description`] is written.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Kind {
+    /// The `HEAD` reference, or one without a name, which is described by the URL alone, like `<url>`.
+    Head,
+    /// A branch in `refs/heads/`, described like `branch 'main' of <url>`.
+    Branch,
+    /// A tag in `refs/tags/`, described like `tag 'v1.0' of <url>`.
+    Tag,
+    /// A remote tracking branch in `refs/remotes/` of the remote, described like `remote-tracking branch 'origin/main' of <url>`.
+    RemoteTrackingBranch,
+    /// Any other reference, which is described by its full name, like `'refs/pull/1/head' of <url>`.
+    Other,
+}
+
+/// The human-readable part of an [`Entry`], like `branch 'main' of https://github.com/Byron/gitoxide`.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Description<'a> {
+    /// The kind of remote reference the object was fetched from.
+    pub kind: Kind,
+    /// The name of the remote reference without the prefix implied by `kind`, like `main` for `refs/heads/main`.
+    ///
+    /// It's the full reference name for [`Kind::Other`], and empty for [`Kind::Head`].
+    pub name: &'a BStr,
+    /// The URL of the remote, which is usually shortened by removing trailing slashes and the `.git` suffix.
+    ///
+    /// Newlines are escaped as `\n`, just like they are in the file.
+    pub url: &'a BStr,
+}
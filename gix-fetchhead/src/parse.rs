@@ -0,0 +1,76 @@
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`parse()`][crate::parse()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Line {line_number} doesn't start with a valid object id: {line:?}")]
+        ObjectId {
+            line_number: usize,
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("Line {line_number} lacks the tab-separated merge marker: {line:?}")]
+        MergeMarker { line_number: usize, line: BString },
+    }
+}
+
+use bstr::{BStr, ByteSlice};
+pub use error::Error;
+
+use crate::{Description, Entry};
+
+/// An iterator to parse `FETCH_HEAD` lines on-demand.
+pub struct Lines<'a> {
+    lines: bstr::Lines<'a>,
+    line_no: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Lines {
+            lines: input.as_bstr().lines(),
+            line_no: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Entry<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line_no += 1;
+            if line.is_empty() {
+                continue;
+            }
+            return parse_line(line.as_bstr(), self.line_no).into();
+        }
+        None
+    }
+}
+
+fn parse_line(line: &BStr, line_number: usize) -> Result<Entry<'_>, Error> {
+    let (hex, rest) = line.split_once_str(b"\t").unwrap_or((line, b""));
+    let id = gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::ObjectId {
+        line_number,
+        line: line.into(),
+        source,
+    })?;
+    let (not_for_merge, description) = if let Some(description) = rest.strip_prefix(b"not-for-merge\t") {
+        (true, description)
+    } else if let Some(description) = rest.strip_prefix(b"\t") {
+        (false, description)
+    } else {
+        return Err(Error::MergeMarker {
+            line_number,
+            line: line.into(),
+        });
+    };
+    Ok(Entry {
+        id,
+        not_for_merge,
+        description: Description::from_bytes(description.as_bstr()),
+    })
+}
@@ -0,0 +1,14 @@
+use crate::Entry;
+
+impl Entry<'_> {
+    /// Write this entry as a single line to `out`, including the trailing newline, the way `git` writes it to `FETCH_HEAD`.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(out, "{}\t", self.id)?;
+        if self.not_for_merge {
+            out.write_all(b"not-for-merge")?;
+        }
+        out.write_all(b"\t")?;
+        self.description.write_to(out)?;
+        out.write_all(b"\n")
+    }
+}
@@ -0,0 +1,13 @@
+pub use gix_testtools::Result;
+
+fn fixture(name: &str) -> Result<Vec<u8>> {
+    let dir = gix_testtools::scripted_fixture_read_only("make_fetch_heads.sh")?;
+    Ok(std::fs::read(dir.join(name))?)
+}
+
+fn hex_to_id(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
+}
+
+mod parse;
+mod write;
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git symbolic-ref HEAD refs/heads/main
+  git commit -q --allow-empty -m "first"
+  git branch feature
+  git update-ref refs/pull/1/head HEAD
+)
+git clone -q --mirror remote remote.git
+
+git clone -q remote.git/ clone
+(cd clone
+  (cd ../remote.git
+    git commit-tree -p HEAD -m "second" HEAD^{tree} > ../second
+    git update-ref refs/heads/main "$(cat ../second)"
+    git tag -a -m "annotated" v1 main
+  )
+
+  git fetch -q
+  cp .git/FETCH_HEAD ../configured
+
+  git fetch -q origin refs/pull/1/head HEAD feature
+  cp .git/FETCH_HEAD ../explicit
+
+  git fetch -q --tags
+  cp .git/FETCH_HEAD ../tags
+)
@@ -0,0 +1,123 @@
+use gix_fetchhead::{Description, Entry, Kind};
+
+use crate::{fixture, hex_to_id};
+
+#[test]
+fn configured_refspecs_mark_the_merge_branch() -> crate::Result {
+    let buf = fixture("configured")?;
+    let entries = gix_fetchhead::parse(&buf).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries
+            .iter()
+            .map(|e| (e.not_for_merge, e.description.kind, e.description.name.to_string()))
+            .collect::<Vec<_>>(),
+        [
+            (false, Kind::Branch, "main".into()),
+            (true, Kind::Branch, "feature".into()),
+            (true, Kind::Tag, "v1".into()),
+        ],
+        "the branch to merge with comes first, followed by all other branches and tags that were fetched"
+    );
+    for entry in &entries {
+        assert!(
+            entry.description.url.ends_with(b"/remote"),
+            "trailing slashes and the '.git' suffix were removed: {}",
+            entry.description.url
+        );
+    }
+    assert_eq!(entries[0].description.ref_name(), "refs/heads/main");
+    assert_eq!(entries[2].description.ref_name(), "refs/tags/v1");
+    Ok(())
+}
+
+#[test]
+fn explicit_refspecs_are_all_for_merge() -> crate::Result {
+    let buf = fixture("explicit")?;
+    let entries = gix_fetchhead::parse(&buf).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries
+            .iter()
+            .map(|e| (
+                e.not_for_merge,
+                e.description.kind,
+                e.description.ref_name().to_string()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (false, Kind::Other, "refs/pull/1/head".into()),
+            (false, Kind::Head, "HEAD".into()),
+            (false, Kind::Branch, "refs/heads/feature".into()),
+        ]
+    );
+    assert_eq!(entries[0].description.name, "refs/pull/1/head");
+    assert_eq!(entries[1].description.name, "", "HEAD is described by the URL alone");
+    assert_eq!(
+        entries[0].id, entries[2].id,
+        "the pull request points to the feature branch"
+    );
+    Ok(())
+}
+
+#[test]
+fn empty_lines_are_skipped() -> crate::Result {
+    let entries = gix_fetchhead::parse(
+        b"\n0000000000000000000000000000000000000000\tnot-for-merge\tremote-tracking branch 'origin/main' of host:repo\n\n",
+    )
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries,
+        [Entry {
+            id: hex_to_id("0000000000000000000000000000000000000000"),
+            not_for_merge: true,
+            description: Description {
+                kind: Kind::RemoteTrackingBranch,
+                name: "origin/main".into(),
+                url: "host:repo".into(),
+            },
+        }]
+    );
+    assert_eq!(entries[0].description.ref_name(), "refs/remotes/origin/main");
+    Ok(())
+}
+
+#[test]
+fn unknown_descriptions_are_treated_as_url() -> crate::Result {
+    let entry = gix_fetchhead::parse(b"0000000000000000000000000000000000000000\t\tbranch 'unterminated of url")
+        .next()
+        .expect("one line")?;
+    assert_eq!(
+        entry.description,
+        Description {
+            kind: Kind::Head,
+            name: "".into(),
+            url: "branch 'unterminated of url".into(),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_lines_yield_errors_without_stopping_iteration() {
+    let input = b"not-a-hash\t\turl\n0000000000000000000000000000000000000000 no-tab url\n0000000000000000000000000000000000000000\t\turl";
+    let mut lines = gix_fetchhead::parse(input);
+    assert!(matches!(
+        lines.next(),
+        Some(Err(gix_fetchhead::parse::Error::ObjectId { line_number: 1, .. }))
+    ));
+    assert!(matches!(
+        lines.next(),
+        Some(Err(gix_fetchhead::parse::Error::ObjectId { line_number: 2, .. }))
+    ));
+    assert!(matches!(lines.next(), Some(Ok(_))));
+    assert!(lines.next().is_none());
+
+    let err = gix_fetchhead::parse(b"0000000000000000000000000000000000000000\tfor-merge\turl")
+        .next()
+        .expect("one line")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix_fetchhead::parse::Error::MergeMarker { line_number: 1, .. }
+    ));
+}
@@ -0,0 +1,92 @@
+use bstr::ByteSlice;
+use gix_fetchhead::{Description, Entry, Kind};
+
+use crate::{fixture, hex_to_id};
+
+#[test]
+fn round_trips_what_git_wrote() -> crate::Result {
+    for name in ["configured", "explicit", "tags"] {
+        let buf = fixture(name)?;
+        let mut out = Vec::new();
+        for entry in gix_fetchhead::parse(&buf) {
+            entry?.write_to(&mut out)?;
+        }
+        assert_eq!(out.as_bstr(), buf.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn descriptions_from_ref_names_match_git() -> crate::Result {
+    let buf = fixture("explicit")?;
+    let mut out = Vec::new();
+    for entry in gix_fetchhead::parse(&buf) {
+        let entry = entry?;
+        let ref_name = entry.description.ref_name();
+        let url = format!("{}.git/", entry.description.url);
+        Entry {
+            description: Description::from_ref_name(ref_name.as_ref(), url.as_str().into()),
+            ..entry
+        }
+        .write_to(&mut out)?;
+    }
+    assert_eq!(
+        out.as_bstr(),
+        buf.as_bstr(),
+        "the URL is shortened and names are described just like git does it"
+    );
+    Ok(())
+}
+
+#[test]
+fn urls_are_shortened() {
+    for (url, expected) in [
+        ("https://host/repo.git/", "https://host/repo"),
+        ("https://host/repo//", "https://host/repo"),
+        ("host:repo.git", "host:repo"),
+        ("a.git", "a.git"),
+        (".git", ".git"),
+        ("///", ""),
+    ] {
+        assert_eq!(
+            Description::from_ref_name("refs/heads/main".into(), url.into()).url,
+            expected,
+            "{url}"
+        );
+    }
+}
+
+#[test]
+fn kinds_are_derived_from_ref_names() {
+    for (ref_name, kind, name) in [
+        ("HEAD", Kind::Head, ""),
+        ("refs/heads/main", Kind::Branch, "main"),
+        ("refs/tags/v1", Kind::Tag, "v1"),
+        ("refs/remotes/origin/main", Kind::RemoteTrackingBranch, "origin/main"),
+        ("refs/notes/commits", Kind::Other, "refs/notes/commits"),
+        ("refs/heads/", Kind::Head, ""),
+    ] {
+        let desc = Description::from_ref_name(ref_name.into(), "url".into());
+        assert_eq!(
+            (desc.kind, desc.name.to_str_lossy().as_ref()),
+            (kind, name),
+            "{ref_name}"
+        );
+    }
+}
+
+#[test]
+fn newlines_in_urls_are_escaped() -> std::io::Result<()> {
+    let mut out = Vec::new();
+    Entry {
+        id: hex_to_id("0000000000000000000000000000000000000000"),
+        not_for_merge: true,
+        description: Description::from_ref_name("refs/tags/v1".into(), "a\nb\n".into()),
+    }
+    .write_to(&mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "0000000000000000000000000000000000000000\tnot-for-merge\ttag 'v1' of a\\nb\\n\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,226 @@
+//! A complete check of all objects in an object database, verifying their hashes and syntax, their connectivity
+//! when starting from a set of tips, and finding objects that aren't reachable from any of them.
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::Kind;
+
+use crate::object;
+
+/// Options for use in [`check()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Options to control how the syntax of individual objects is checked.
+    pub object: object::Options,
+    /// The commits at the shallow boundary of the repository, whose parents are expected to be missing.
+    pub shallow_commits: HashSet,
+}
+
+/// The error returned by [`check()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// An object whose actual hash doesn't match the id it's stored under.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HashMismatch {
+    /// The id the object was stored under.
+    pub expected: ObjectId,
+    /// The id computed from the object's data.
+    pub actual: ObjectId,
+    /// The kind of the object.
+    pub kind: Kind,
+}
+
+/// An object that is reachable but missing from the object database.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Missing {
+    /// The id of the missing object.
+    pub id: ObjectId,
+    /// The kind the object is expected to have, or `None` if it was a tip and its kind is unknown.
+    pub kind: Option<Kind>,
+    /// The object that refers to the missing object, or `None` if it was a tip.
+    pub referenced_by: Option<ObjectId>,
+}
+
+/// The outcome of [`check()`].
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The amount of objects that were read and checked.
+    pub objects: usize,
+    /// Problems with the syntax of objects.
+    pub problems: Vec<object::Problem>,
+    /// Objects whose data doesn't hash to the id they are stored under.
+    pub hash_mismatches: Vec<HashMismatch>,
+    /// Objects that couldn't be read, along with the reason for it.
+    pub corrupt: Vec<(ObjectId, gix_object::find::Error)>,
+    /// Objects that are reachable from the tips but not present.
+    pub missing: Vec<Missing>,
+    /// Objects which are present but not reachable from any tip, sorted by id.
+    pub unreachable: Vec<(ObjectId, Kind)>,
+    /// Unreachable objects that aren't referenced by any other object, sorted by id.
+    ///
+    /// These are the objects that have to be kept alive to retain all unreachable objects.
+    pub dangling: Vec<(ObjectId, Kind)>,
+}
+
+impl Outcome {
+    /// Return `true` if there was at least one problem that is considered an error, i.e. that should cause `git fsck`
+    /// to fail.
+    ///
+    /// Note that neither unreachable nor dangling objects are errors.
+    pub fn has_errors(&self) -> bool {
+        !(self.hash_mismatches.is_empty() && self.corrupt.is_empty() && self.missing.is_empty())
+            || self.problems.iter().any(|p| p.severity.is_error())
+    }
+}
+
+/// Check all `objects` in `db`, which typically are all objects contained in it, along with their connectivity
+/// when starting from `tips`, configured with `options`.
+///
+/// `tips` are the starting points of the connectivity check, typically all references and their reflogs, `HEAD`
+/// and all objects referenced by the index.
+/// Objects reachable from `tips` that aren't contained in `objects` will still be read from `db`.
+///
+/// ### Algorithm
+///
+/// First, every object is read, its hash is recomputed and its syntax is [checked](object::check()). All objects it
+/// refers to are remembered as being used.
+/// Then the graph is traversed from all `tips` to find missing objects, which leaves all objects that weren't traversed
+/// as unreachable. These are dangling if they aren't used by any other object.
+pub fn check(
+    db: impl gix_object::Find,
+    objects: impl IntoIterator<Item = ObjectId>,
+    tips: impl IntoIterator<Item = ObjectId>,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error> {
+    let mut out = Outcome::default();
+    let mut buf = Vec::new();
+    let mut refs = Vec::new();
+    let mut kinds = HashMap::<ObjectId, Kind>::default();
+    let mut used = HashSet::default();
+
+    for id in objects {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        if kinds.contains_key(&id) {
+            continue;
+        }
+        let data = match db.try_find(&id, &mut buf) {
+            Ok(Some(data)) => data,
+            Ok(None) => continue,
+            Err(err) => {
+                out.corrupt.push((id, err));
+                continue;
+            }
+        };
+        out.objects += 1;
+        kinds.insert(id, data.kind);
+
+        let actual = gix_object::compute_hash(id.kind(), data.kind, data.data);
+        if actual != id {
+            out.hash_mismatches.push(HashMismatch {
+                expected: id,
+                actual,
+                kind: data.kind,
+            });
+        }
+        object::check(&id, data.kind, data.data, &options.object, |problem| {
+            out.problems.push(problem)
+        });
+
+        refs.clear();
+        references(data.kind, data.data, true, &mut refs);
+        used.extend(refs.iter().map(|(id, _)| *id));
+    }
+
+    let mut reachable = HashSet::default();
+    let mut queue: VecDeque<_> = tips.into_iter().map(|id| (id, None, None)).collect();
+    while let Some((id, kind, referenced_by)) = queue.pop_front() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        if !reachable.insert(id) {
+            continue;
+        }
+        let data = match db.try_find(&id, &mut buf) {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                out.missing.push(Missing {
+                    id,
+                    kind,
+                    referenced_by,
+                });
+                continue;
+            }
+            Err(err) => {
+                if !kinds.contains_key(&id) {
+                    out.corrupt.push((id, err));
+                }
+                continue;
+            }
+        };
+        refs.clear();
+        let follow_parents = !options.shallow_commits.contains(&id);
+        references(data.kind, data.data, follow_parents, &mut refs);
+        queue.extend(
+            refs.iter()
+                .map(|(ref_id, ref_kind)| (*ref_id, Some(*ref_kind), Some(id))),
+        );
+    }
+
+    let mut unreachable: Vec<_> = kinds.into_iter().filter(|(id, _)| !reachable.contains(id)).collect();
+    unreachable.sort_by_key(|(id, _)| *id);
+    out.dangling = unreachable
+        .iter()
+        .filter(|(id, _)| !used.contains(id))
+        .copied()
+        .collect();
+    out.unreachable = unreachable;
+    Ok(out)
+}
+
+/// Put all objects referenced by the object of `kind` with `data` into `out`, along with their expected kind.
+/// The parents of commits are only added if `follow_parents` is `true`.
+fn references(kind: Kind, data: &[u8], follow_parents: bool, out: &mut Vec<(ObjectId, Kind)>) {
+    match kind {
+        Kind::Commit => {
+            let Ok(commit) = gix_object::CommitRef::from_bytes(data) else {
+                return;
+            };
+            out.push((commit.tree(), Kind::Tree));
+            if follow_parents {
+                out.extend(commit.parents().map(|id| (id, Kind::Commit)));
+            }
+        }
+        Kind::Tag => {
+            let Ok(tag) = gix_object::TagRef::from_bytes(data) else {
+                return;
+            };
+            out.push((tag.target(), tag.target_kind));
+        }
+        Kind::Tree => {
+            let Ok(tree) = gix_object::TreeRef::from_bytes(data) else {
+                return;
+            };
+            out.extend(tree.entries.iter().filter_map(|entry| {
+                let kind = match entry.mode.kind() {
+                    gix_object::tree::EntryKind::Tree => Kind::Tree,
+                    gix_object::tree::EntryKind::Commit => return None,
+                    _ => Kind::Blob,
+                };
+                Some((entry.oid.to_owned(), kind))
+            }));
+        }
+        Kind::Blob => {}
+    }
+}
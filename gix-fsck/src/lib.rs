@@ -7,6 +7,10 @@ use gix_hash::ObjectId;
 use gix_hashtable::HashSet;
 use gix_object::{tree::EntryKind, Exists, FindExt, Kind};
 
+pub mod full;
+pub mod message;
+pub mod object;
+
 /// Perform a connectivity check.
 pub struct Connectivity<T, F>
 where
@@ -57,15 +61,48 @@ where
             let commit = self.db.find_commit(oid, &mut self.buf)?;
             commit.tree()
         };
+        self.check_trees(tree_id);
+        Ok(())
+    }
+
+    /// Run the connectivity check on the provided tag `oid`, following it to the object it points to.
+    ///
+    /// Tags pointing to commits or other tags are checked just like [commits](Self::check_commit()), so
+    /// these have to be present or an error is returned.
+    /// Trees and blobs that tags point to are reported using `missing_cb` if they are missing.
+    pub fn check_tag(&mut self, oid: &ObjectId) -> Result<(), gix_object::find::existing_object::Error> {
+        if !self.seen.insert(*oid) {
+            return Ok(());
+        }
+        let (target_id, target_kind) = {
+            let tag = self.db.find_tag(oid, &mut self.buf)?;
+            (tag.target(), tag.target_kind)
+        };
+        match target_kind {
+            Kind::Commit => self.check_commit(&target_id)?,
+            Kind::Tag => self.check_tag(&target_id)?,
+            Kind::Tree => {
+                if !self.seen.contains(&target_id) {
+                    self.check_trees(target_id);
+                }
+            }
+            Kind::Blob => {
+                if self.seen.insert(target_id) {
+                    check_blob(&self.db, &target_id, &mut self.missing_cb);
+                }
+            }
+        }
+        Ok(())
+    }
 
+    /// Check the tree with `tree_id` and all trees and blobs reachable from it.
+    fn check_trees(&mut self, tree_id: ObjectId) {
         let mut tree_ids = VecDeque::from_iter(Some(tree_id));
         while let Some(tree_id) = tree_ids.pop_front() {
             if self.seen.insert(tree_id) {
                 self.check_tree(&tree_id, &mut tree_ids);
             }
         }
-
-        Ok(())
     }
 
     /// Blobs are checked right away, trees are stored in `tree_ids` for the parent to iterate them, and only
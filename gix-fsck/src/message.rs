@@ -0,0 +1,143 @@
+//! Identifiers and severities of the messages produced when checking objects, equivalent to the
+//! [fsck messages](https://git-scm.com/docs/git-fsck#_fsck_messages) of `git`.
+use gix_object::bstr::ByteSlice;
+
+/// The severity of a message, which determines if it's an error or if it should be ignored.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// The message is not reported at all.
+    Ignore,
+    /// The message is informational and is reported like a warning, but will never turn into an error.
+    Info,
+    /// The message is a warning, which becomes an error in `strict` mode.
+    Warn,
+    /// The message is an error.
+    Error,
+    /// The object is so broken that it can't be checked any further, which is reported as error.
+    Fatal,
+}
+
+impl Severity {
+    /// Parse the value of a `fsck.<msg-id>` configuration key, one of `error`, `warn` or `ignore`.
+    pub fn from_bytes(value: &[u8]) -> Option<Self> {
+        Some(match value {
+            b"error" => Severity::Error,
+            b"warn" => Severity::Warn,
+            b"ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+
+    /// Return `true` if this severity makes a message count as error.
+    pub fn is_error(&self) -> bool {
+        matches!(self, Severity::Error | Severity::Fatal)
+    }
+}
+
+macro_rules! ids {
+    ($($variant:ident => $name:literal, $severity:ident, $doc:literal;)*) => {
+        /// The identifier of a message produced when checking an object.
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub enum Id {
+            $(
+                #[doc = $doc]
+                $variant,
+            )*
+        }
+
+        impl Id {
+            /// All known message identifiers.
+            pub const ALL: &'static [Id] = &[$(Id::$variant),*];
+
+            /// Return the camel-cased name of this identifier as used in `fsck.<msg-id>` configuration keys.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Id::$variant => $name),*
+                }
+            }
+
+            /// Return the severity this message has if it isn't configured otherwise.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(Id::$variant => Severity::$severity),*
+                }
+            }
+        }
+    };
+}
+
+ids! {
+    NulInHeader => "nulInHeader", Fatal, "A NUL byte was found in the object header.";
+    UnterminatedHeader => "unterminatedHeader", Fatal, "The object header isn't terminated with an empty line.";
+    BadDate => "badDate", Error, "The date of an identity can't be parsed.";
+    BadDateOverflow => "badDateOverflow", Error, "The date of an identity is too large to be represented.";
+    BadEmail => "badEmail", Error, "The email of an identity isn't terminated with `>`.";
+    BadName => "badName", Error, "The name of an identity contains a `>`.";
+    BadObjectSha1 => "badObjectSha1", Error, "The `object` header of a tag doesn't contain a valid hash.";
+    BadParentSha1 => "badParentSha1", Error, "A `parent` header of a commit doesn't contain a valid hash.";
+    BadTimezone => "badTimezone", Error, "The timezone of an identity is malformed.";
+    BadTree => "badTree", Error, "A tree can't be parsed.";
+    BadTreeSha1 => "badTreeSha1", Error, "The `tree` header of a commit doesn't contain a valid hash.";
+    BadType => "badType", Error, "The `type` header of a tag doesn't name a known object kind.";
+    DuplicateEntries => "duplicateEntries", Error, "A tree contains the same name more than once.";
+    MissingAuthor => "missingAuthor", Error, "A commit has no `author` header.";
+    MissingCommitter => "missingCommitter", Error, "A commit has no `committer` header.";
+    MissingEmail => "missingEmail", Error, "An identity lacks an email.";
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error, "An identity lacks a name before its email.";
+    MissingObject => "missingObject", Error, "A tag has no `object` header.";
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error, "An identity lacks the space between email and date.";
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error, "An identity lacks the space between name and email.";
+    MissingTag => "missingTag", Error, "The `tag` header of a tag isn't terminated.";
+    MissingTagEntry => "missingTagEntry", Error, "A tag has no `tag` header.";
+    MissingTree => "missingTree", Error, "A commit has no `tree` header.";
+    MissingType => "missingType", Error, "The `type` header of a tag isn't terminated.";
+    MissingTypeEntry => "missingTypeEntry", Error, "A tag has no `type` header.";
+    MultipleAuthors => "multipleAuthors", Error, "A commit has more than one `author` header.";
+    TreeNotSorted => "treeNotSorted", Error, "The entries of a tree aren't sorted correctly.";
+    ZeroPaddedDate => "zeroPaddedDate", Error, "The date of an identity has leading zeroes.";
+    BadFilemode => "badFilemode", Warn, "A tree entry has a mode that isn't supported.";
+    EmptyName => "emptyName", Warn, "A tree entry has an empty name.";
+    FullPathname => "fullPathname", Warn, "A tree entry name contains a slash.";
+    HasDot => "hasDot", Warn, "A tree entry is named `.`.";
+    HasDotdot => "hasDotdot", Warn, "A tree entry is named `..`.";
+    HasDotgit => "hasDotgit", Warn, "A tree entry is named `.git`, or something that a filesystem treats as `.git`.";
+    NullSha1 => "nullSha1", Warn, "A tree entry points to the null hash.";
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warn, "A tree entry has a mode with leading zeroes.";
+    NulInCommit => "nulInCommit", Warn, "A NUL byte was found in the commit message.";
+    BadTagName => "badTagName", Info, "The name in the `tag` header of a tag isn't a valid reference name.";
+    MissingTaggerEntry => "missingTaggerEntry", Info, "A tag has no `tagger` header.";
+    ExtraHeaderEntry => "extraHeaderEntry", Info, "A tag has unknown headers after the `tagger` header.";
+}
+
+impl Id {
+    /// Find the identifier named `name`, compared case-insensitively just like configuration keys are.
+    ///
+    /// Both the camel-cased `fsck.<msg-id>` spelling as well as the upper-cased one with underscores
+    /// used by `git` in its output are supported.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        let name: Vec<u8> = name.iter().copied().filter(|b| *b != b'_').collect();
+        Id::ALL
+            .iter()
+            .find(|id| id.as_str().as_bytes().eq_ignore_ascii_case(&name))
+            .copied()
+    }
+}
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse the content of a `fsck.skipList` file, which contains one hexadecimal object id per line, with
+/// empty lines and everything after `#` being ignored.
+///
+/// Return the list of object ids, or the first line that isn't a valid object id.
+pub fn parse_skip_list(content: &[u8]) -> Result<Vec<gix_hash::ObjectId>, gix_object::bstr::BString> {
+    content
+        .lines()
+        .map(|line| line.split_str("#").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| gix_hash::ObjectId::from_hex(line).map_err(|_| line.into()))
+        .collect()
+}
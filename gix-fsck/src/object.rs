@@ -0,0 +1,425 @@
+//! Validate the syntax of individual objects, similar to what `git fsck` does for each object it encounters.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::{
+    bstr::{BStr, BString, ByteSlice},
+    Kind,
+};
+
+use crate::message::{Id, Severity};
+
+/// Options to control which problems are reported, and how.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// If `true`, warnings are turned into errors and unusual but permitted file modes are reported, similar to `fsck.strict`.
+    pub strict: bool,
+    /// Severities that override the [default severity](Id::default_severity()) of messages, typically from `fsck.<msg-id>`.
+    pub severities: BTreeMap<Id, Severity>,
+    /// Objects known to be broken which won't be reported at all, typically from `fsck.skipList`.
+    pub skip_list: HashSet,
+}
+
+impl Options {
+    /// Return the severity to use for messages with `id`, taking overrides and `strict` mode into account.
+    pub fn severity(&self, id: Id) -> Severity {
+        match self.severities.get(&id) {
+            Some(severity) => *severity,
+            None => match id.default_severity() {
+                Severity::Warn if self.strict => Severity::Error,
+                severity => severity,
+            },
+        }
+    }
+}
+
+/// A problem found with an object.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Problem {
+    /// The id of the object with the problem.
+    pub id: ObjectId,
+    /// The kind of the object with the problem.
+    pub kind: Kind,
+    /// Identifies the problem.
+    pub message: Id,
+    /// The severity of the problem, which is never [`Severity::Ignore`].
+    pub severity: Severity,
+    /// A human-readable description of the problem.
+    pub description: BString,
+}
+
+/// Check the syntax of the object with `id` of `kind` and with `data`, and call `on_problem` for each problem found,
+/// as long as it's not ignored by `options`.
+///
+/// Return `true` if at least one problem was an error.
+///
+/// Note that checking stops at the first problem that is an error, just like in `git`.
+/// The hash of the object isn't verified here, and blobs are never checked.
+pub fn check(id: &ObjectId, kind: Kind, data: &[u8], options: &Options, mut on_problem: impl FnMut(Problem)) -> bool {
+    if options.skip_list.contains(id) {
+        return false;
+    }
+    let mut report = Report {
+        id,
+        kind,
+        options,
+        on_problem: &mut on_problem,
+        has_error: false,
+    };
+    let hash_len = id.kind().len_in_hex();
+    let _ = match kind {
+        Kind::Commit => check_commit(data, hash_len, &mut report),
+        Kind::Tag => check_tag(data, hash_len, &mut report),
+        Kind::Tree => check_tree(data, id.kind().len_in_bytes(), options.strict, &mut report),
+        Kind::Blob => Ok(()),
+    };
+    report.has_error
+}
+
+/// Indicates that checking stopped due to an error.
+struct Stop;
+
+struct Report<'a> {
+    id: &'a ObjectId,
+    kind: Kind,
+    options: &'a Options,
+    on_problem: &'a mut dyn FnMut(Problem),
+    has_error: bool,
+}
+
+impl Report<'_> {
+    /// Report `message`, and return `Err` if it's an error.
+    fn message(&mut self, message: Id, description: &str) -> Result<(), Stop> {
+        let severity = self.options.severity(message);
+        if severity == Severity::Ignore {
+            return Ok(());
+        }
+        (self.on_problem)(Problem {
+            id: *self.id,
+            kind: self.kind,
+            message,
+            severity,
+            description: description.into(),
+        });
+        if severity.is_error() {
+            self.has_error = true;
+            Err(Stop)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Assure the header of a commit or tag doesn't contain NUL bytes and is terminated.
+fn check_header(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    let header = match data.find(b"\n\n") {
+        Some(pos) => &data[..pos],
+        None => data,
+    };
+    if header.contains(&0) {
+        return report.message(Id::NulInHeader, "unterminated header: NUL at offset");
+    }
+    if header.len() == data.len() && data.last() != Some(&b'\n') {
+        return report.message(Id::UnterminatedHeader, "unterminated header");
+    }
+    Ok(())
+}
+
+/// Parse a hexadecimal hash of `hash_len` characters followed by a newline, and return the remaining data.
+fn parse_hash_line(data: &[u8], hash_len: usize) -> Option<&[u8]> {
+    let hex = data.get(..hash_len)?;
+    ObjectId::from_hex(hex).ok()?;
+    data.get(hash_len..)?.strip_prefix(b"\n")
+}
+
+fn check_commit(data: &[u8], hash_len: usize, report: &mut Report<'_>) -> Result<(), Stop> {
+    check_header(data, report)?;
+    let Some(mut buf) = data.strip_prefix(b"tree ") else {
+        return report.message(Id::MissingTree, "invalid format - expected 'tree' line");
+    };
+    buf = match parse_hash_line(buf, hash_len) {
+        Some(buf) => buf,
+        None => return report.message(Id::BadTreeSha1, "invalid 'tree' line format - bad sha1"),
+    };
+    while let Some(parent) = buf.strip_prefix(b"parent ") {
+        buf = match parse_hash_line(parent, hash_len) {
+            Some(buf) => buf,
+            None => return report.message(Id::BadParentSha1, "invalid 'parent' line format - bad sha1"),
+        };
+    }
+    let mut authors = 0;
+    while let Some(author) = buf.strip_prefix(b"author ") {
+        authors += 1;
+        buf = check_ident(author, report)?;
+    }
+    match authors {
+        0 => report.message(Id::MissingAuthor, "invalid format - expected 'author' line")?,
+        1 => {}
+        _ => report.message(Id::MultipleAuthors, "invalid format - multiple 'author' lines")?,
+    }
+    let Some(committer) = buf.strip_prefix(b"committer ") else {
+        return report.message(Id::MissingCommitter, "invalid format - expected 'committer' line");
+    };
+    check_ident(committer, report)?;
+    if data.contains(&0) {
+        report.message(Id::NulInCommit, "NUL byte in the commit object body")?;
+    }
+    Ok(())
+}
+
+fn check_tag(data: &[u8], hash_len: usize, report: &mut Report<'_>) -> Result<(), Stop> {
+    check_header(data, report)?;
+    let Some(mut buf) = data.strip_prefix(b"object ") else {
+        return report.message(Id::MissingObject, "invalid format - expected 'object' line");
+    };
+    buf = match parse_hash_line(buf, hash_len) {
+        Some(buf) => buf,
+        None => return report.message(Id::BadObjectSha1, "invalid 'object' line format - bad sha1"),
+    };
+
+    let Some(kind) = buf.strip_prefix(b"type ") else {
+        return report.message(Id::MissingTypeEntry, "invalid format - expected 'type' line");
+    };
+    let Some(eol) = kind.find_byte(b'\n') else {
+        return report.message(Id::MissingType, "invalid format - unexpected end after 'type' line");
+    };
+    if Kind::from_bytes(&kind[..eol]).is_err() {
+        report.message(Id::BadType, "invalid 'type' value")?;
+    }
+    buf = &kind[eol + 1..];
+
+    let Some(name) = buf.strip_prefix(b"tag ") else {
+        return report.message(Id::MissingTagEntry, "invalid format - expected 'tag' line");
+    };
+    let Some(eol) = name.find_byte(b'\n') else {
+        return report.message(Id::MissingTag, "invalid format - unexpected end after 'tag' line");
+    };
+    let mut full_name = BString::from("refs/tags/");
+    full_name.extend_from_slice(&name[..eol]);
+    if gix_validate::reference::name(full_name.as_ref()).is_err() {
+        report.message(
+            Id::BadTagName,
+            &format!("invalid 'tag' name: {}", name[..eol].as_bstr()),
+        )?;
+    }
+    buf = &name[eol + 1..];
+
+    match buf.strip_prefix(b"tagger ") {
+        Some(tagger) => buf = check_ident(tagger, report)?,
+        None => report.message(Id::MissingTaggerEntry, "invalid format - expected 'tagger' line")?,
+    }
+    if !buf.is_empty() && !buf.starts_with(b"\n") {
+        report.message(Id::ExtraHeaderEntry, "invalid format - extra header(s) after 'tagger'")?;
+    }
+    Ok(())
+}
+
+/// Check the identity in `data` up to and including the newline, and return the data past it.
+fn check_ident<'a>(data: &'a [u8], report: &mut Report<'_>) -> Result<&'a [u8], Stop> {
+    let eol = data.find_byte(b'\n').unwrap_or(data.len());
+    let rest = data.get(eol + 1..).unwrap_or_default();
+    let line = &data[..eol];
+
+    if line.first() == Some(&b'<') {
+        report.message(
+            Id::MissingNameBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        )?;
+        return Ok(rest);
+    }
+    let start = line.find_byteset(b"<>").unwrap_or(line.len());
+    match line.get(start) {
+        Some(b'>') => {
+            report.message(Id::BadName, "invalid author/committer line - bad name")?;
+            return Ok(rest);
+        }
+        Some(b'<') => {}
+        _ => {
+            report.message(Id::MissingEmail, "invalid author/committer line - missing email")?;
+            return Ok(rest);
+        }
+    }
+    if start > 0 && line[start - 1] != b' ' {
+        report.message(
+            Id::MissingSpaceBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        )?;
+        return Ok(rest);
+    }
+    let email_end = start + 1 + line[start + 1..].find_byteset(b"<>").unwrap_or(line.len() - start - 1);
+    if line.get(email_end) != Some(&b'>') {
+        report.message(Id::BadEmail, "invalid author/committer line - bad email")?;
+        return Ok(rest);
+    }
+    let Some(date) = line[email_end + 1..].strip_prefix(b" ") else {
+        report.message(
+            Id::MissingSpaceBeforeDate,
+            "invalid author/committer line - missing space before date",
+        )?;
+        return Ok(rest);
+    };
+    if date.first() == Some(&b'0') && date.get(1) != Some(&b' ') {
+        report.message(Id::ZeroPaddedDate, "invalid author/committer line - zero-padded date")?;
+        return Ok(rest);
+    }
+    let digits = date.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 || date.get(digits) != Some(&b' ') {
+        report.message(Id::BadDate, "invalid author/committer line - bad date")?;
+        return Ok(rest);
+    }
+    if date[..digits]
+        .to_str()
+        .ok()
+        .and_then(|d| d.parse::<u64>().ok())
+        .is_none()
+    {
+        report.message(
+            Id::BadDateOverflow,
+            "invalid author/committer line - date causes integer overflow",
+        )?;
+        return Ok(rest);
+    }
+    let tz = &date[digits + 1..];
+    if tz.len() != 5 || !matches!(tz[0], b'+' | b'-') || !tz[1..].iter().all(u8::is_ascii_digit) {
+        report.message(Id::BadTimezone, "invalid author/committer line - bad time zone")?;
+    }
+    Ok(rest)
+}
+
+fn check_tree(data: &[u8], hash_len: usize, strict: bool, report: &mut Report<'_>) -> Result<(), Stop> {
+    #[derive(Default)]
+    struct Found {
+        null_sha1: bool,
+        full_path: bool,
+        empty_name: bool,
+        dot: bool,
+        dotdot: bool,
+        dotgit: bool,
+        zero_pad: bool,
+        bad_modes: bool,
+        duplicates: bool,
+        unordered: bool,
+    }
+    let mut found = Found::default();
+    let mut previous: Option<(u32, &BStr)> = None;
+    let mut buf = data;
+    while !buf.is_empty() {
+        let Some((TreeEntry { mode, name, id }, rest)) = parse_tree_entry(buf, hash_len) else {
+            return report.message(Id::BadTree, "cannot be parsed as a tree");
+        };
+        buf = rest;
+
+        found.null_sha1 |= id.iter().all(|b| *b == 0);
+        found.full_path |= name.contains(&b'/');
+        found.empty_name |= name.is_empty();
+        found.dot |= name == ".";
+        found.dotdot |= name == "..";
+        found.dotgit |= is_dotgit(name);
+        found.zero_pad |= mode.first() == Some(&b'0');
+
+        let Some(mode) = std::str::from_utf8(mode)
+            .ok()
+            .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+        else {
+            return report.message(Id::BadTree, "cannot be parsed as a tree");
+        };
+        found.bad_modes |= match mode {
+            0o100755 | 0o100644 | 0o120000 | 0o40000 | 0o160000 => false,
+            0o100664 => strict,
+            _ => true,
+        };
+
+        if let Some((previous_mode, previous_name)) = previous {
+            match verify_ordered(previous_mode, previous_name, mode, name) {
+                Ordering::Ok => {}
+                Ordering::Duplicate => found.duplicates = true,
+                Ordering::Unordered => found.unordered = true,
+            }
+        }
+        previous = Some((mode, name));
+    }
+
+    for (is_set, message, description) in [
+        (found.null_sha1, Id::NullSha1, "contains entries pointing to null sha1"),
+        (found.full_path, Id::FullPathname, "contains full pathnames"),
+        (found.empty_name, Id::EmptyName, "contains empty pathname"),
+        (found.dot, Id::HasDot, "contains '.'"),
+        (found.dotdot, Id::HasDotdot, "contains '..'"),
+        (found.dotgit, Id::HasDotgit, "contains '.git'"),
+        (
+            found.zero_pad,
+            Id::ZeroPaddedFilemode,
+            "contains zero-padded file modes",
+        ),
+        (found.bad_modes, Id::BadFilemode, "contains bad file modes"),
+        (
+            found.duplicates,
+            Id::DuplicateEntries,
+            "contains duplicate file entries",
+        ),
+        (found.unordered, Id::TreeNotSorted, "not properly sorted"),
+    ] {
+        if is_set {
+            report.message(message, description)?;
+        }
+    }
+    Ok(())
+}
+
+struct TreeEntry<'a> {
+    mode: &'a [u8],
+    name: &'a BStr,
+    id: &'a [u8],
+}
+
+/// Parse `<mode> <name>\0<hash>` and return it along with the remaining data.
+fn parse_tree_entry(data: &[u8], hash_len: usize) -> Option<(TreeEntry<'_>, &[u8])> {
+    let space = data.find_byte(b' ')?;
+    let mode = &data[..space];
+    if mode.is_empty() || !mode.iter().all(|b| (b'0'..=b'7').contains(b)) {
+        return None;
+    }
+    let data = &data[space + 1..];
+    let nul = data.find_byte(0)?;
+    let name = data[..nul].as_bstr();
+    let data = &data[nul + 1..];
+    let id = data.get(..hash_len)?;
+    Some((TreeEntry { mode, name, id }, &data[hash_len..]))
+}
+
+/// Return `true` if `name` is `.git`, or would be treated as such on case-insensitive filesystems
+/// or by NTFS, which ignores trailing dots and spaces and knows `git~1` as short name.
+fn is_dotgit(name: &BStr) -> bool {
+    let trimmed = name.trim_end_with(|c| c == '.' || c == ' ');
+    trimmed.eq_ignore_ascii_case(b".git") || trimmed.eq_ignore_ascii_case(b"git~1")
+}
+
+enum Ordering {
+    Ok,
+    Duplicate,
+    Unordered,
+}
+
+/// Check that the entry with `mode2` and `name2` may follow the one with `mode1` and `name1`, with directories
+/// sorting as if their name had a trailing slash.
+fn verify_ordered(mode1: u32, name1: &BStr, mode2: u32, name2: &BStr) -> Ordering {
+    let len = name1.len().min(name2.len());
+    match name1[..len].cmp(&name2[..len]) {
+        std::cmp::Ordering::Less => return Ordering::Ok,
+        std::cmp::Ordering::Greater => return Ordering::Unordered,
+        std::cmp::Ordering::Equal => {}
+    }
+    let is_dir = |mode: u32| mode & 0o170000 == 0o40000;
+    let (c1, c2) = (name1.get(len).copied(), name2.get(len).copied());
+    if c1.is_none() && c2.is_none() {
+        return Ordering::Duplicate;
+    }
+    let c1 = c1.unwrap_or(if is_dir(mode1) { b'/' } else { 0 });
+    let c2 = c2.unwrap_or(if is_dir(mode2) { b'/' } else { 0 });
+    if c1 < c2 {
+        Ordering::Ok
+    } else {
+        Ordering::Unordered
+    }
+}
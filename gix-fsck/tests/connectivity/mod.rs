@@ -76,3 +76,31 @@ fn missing_trees() {
     );
     assert_eq!(check_missing("treeless", all_commits()), expected);
 }
+
+#[test]
+fn tags_are_followed_to_their_target() {
+    let repo = gix_testtools::scripted_fixture_read_only("make_fsck_repo.sh").expect("fixture path");
+    let mut db = gix_odb::at(repo.join(".git").join("objects")).expect("valid odb");
+    db.refresh_never();
+    let rev_parse = |spec: &str| {
+        let output = std::process::Command::new("git")
+            .args(["rev-parse", spec])
+            .current_dir(&repo)
+            .output()
+            .expect("git is available");
+        hex_to_id(std::str::from_utf8(&output.stdout).expect("ascii").trim())
+    };
+
+    let mut missing: HashMap<ObjectId, Kind> = HashMap::default();
+    let mut check = Connectivity::new(&db, |oid: &ObjectId, kind: Kind| {
+        missing.insert(*oid, kind);
+    });
+    check.check_tag(&rev_parse("v1")).expect("tag and commit are present");
+    check.check_commit(&rev_parse("HEAD")).expect("commit is present");
+    drop(check);
+    assert_eq!(
+        missing,
+        hex_to_objects([rev_parse("HEAD:b").to_string().as_str()], Kind::Blob),
+        "the blob deleted by the fixture is missing"
+    );
+}
@@ -0,0 +1,40 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.compression 0
+
+echo a > a
+git add a
+git commit -q -m "first"
+git tag -a -m "annotated" v1
+
+echo b > b
+git add b
+git commit -q -m "second"
+
+git checkout -q -b unreachable
+echo c > c
+git add c
+git commit -q -m "unreachable"
+git rev-parse HEAD > unreachable-commit
+git checkout -q main 2>/dev/null || git checkout -q master
+git branch -D -q unreachable
+
+echo dangling | git hash-object -w --stdin > dangling-blob
+
+# store a blob under a hash that doesn't match its content
+blob=$(echo "mismatch" | git hash-object -w --stdin)
+wrong=$(echo "other" | git hash-object --stdin)
+mkdir -p .git/objects/${wrong:0:2}
+mv .git/objects/${blob:0:2}/${blob:2} .git/objects/${wrong:0:2}/${wrong:2}
+echo $wrong > mismatched-blob
+
+# a commit without author
+tree=$(git rev-parse HEAD^{tree})
+printf 'tree %s\ncommitter C <c@example.com> 1 +0000\n\nno author\n' $tree | git hash-object -t commit -w --stdin --literally > broken-commit
+
+# delete the blob of 'b' which is reachable
+blob=$(git rev-parse HEAD:b)
+rm .git/objects/${blob:0:2}/${blob:2}
+echo $blob > missing-blob
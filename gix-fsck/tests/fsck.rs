@@ -5,3 +5,5 @@ pub fn hex_to_id(hex: &str) -> ObjectId {
 }
 
 mod connectivity;
+mod full;
+mod object;
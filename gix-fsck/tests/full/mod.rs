@@ -0,0 +1,132 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_fsck::{full, message::Id};
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::hex_to_id;
+
+fn fixture() -> PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_fsck_repo.sh").expect("fixture path")
+}
+
+fn id_in(file: &str) -> ObjectId {
+    let hex = std::fs::read_to_string(fixture().join(file)).expect("file written by fixture");
+    hex_to_id(hex.trim())
+}
+
+fn db() -> gix_odb::Handle {
+    let mut db = gix_odb::at(fixture().join(".git").join("objects")).expect("valid odb");
+    db.refresh_never();
+    db
+}
+
+fn run(tips: impl IntoIterator<Item = ObjectId>, options: &full::Options) -> full::Outcome {
+    let db = db();
+    let objects: Vec<_> = db.iter().expect("iterable").map(|id| id.expect("valid")).collect();
+    full::check(&db, objects, tips, options, &AtomicBool::default()).expect("not interrupted")
+}
+
+fn tips() -> Vec<ObjectId> {
+    let repo = fixture();
+    ["HEAD", "refs/tags/v1"]
+        .iter()
+        .map(|name| {
+            let output = std::process::Command::new("git")
+                .args(["rev-parse", name])
+                .current_dir(&repo)
+                .output()
+                .expect("git is available");
+            hex_to_id(std::str::from_utf8(&output.stdout).expect("ascii").trim())
+        })
+        .collect()
+}
+
+#[test]
+fn finds_all_problems() {
+    let out = run(tips(), &full::Options::default());
+    assert!(out.has_errors());
+    assert!(out.corrupt.is_empty());
+
+    assert_eq!(out.hash_mismatches.len(), 1);
+    assert_eq!(out.hash_mismatches[0].expected, id_in("mismatched-blob"));
+    assert_eq!(out.hash_mismatches[0].kind, Kind::Blob);
+
+    assert_eq!(
+        out.problems.iter().map(|p| (p.id, p.message)).collect::<Vec<_>>(),
+        [(id_in("broken-commit"), Id::MissingAuthor)]
+    );
+
+    assert_eq!(out.missing.len(), 1);
+    assert_eq!(out.missing[0].id, id_in("missing-blob"));
+    assert_eq!(out.missing[0].kind, Some(Kind::Blob));
+    assert!(out.missing[0].referenced_by.is_some(), "the tree refers to it");
+
+    let unreachable_commit = id_in("unreachable-commit");
+    let mut dangling: Vec<_> = [
+        (unreachable_commit, Kind::Commit),
+        (id_in("broken-commit"), Kind::Commit),
+        (id_in("dangling-blob"), Kind::Blob),
+        (id_in("mismatched-blob"), Kind::Blob),
+    ]
+    .into();
+    dangling.sort();
+    assert_eq!(out.dangling, dangling);
+    assert_eq!(
+        out.unreachable.len(),
+        dangling.len() + 2,
+        "the tree and blob of the unreachable commit are unreachable, but not dangling"
+    );
+}
+
+#[test]
+fn unreachable_objects_become_reachable_as_tips() {
+    let mut tips = tips();
+    tips.push(id_in("unreachable-commit"));
+    let out = run(tips, &full::Options::default());
+    assert_eq!(out.unreachable.len(), 3);
+    assert_eq!(out.dangling.len(), 3);
+}
+
+#[test]
+fn missing_tips_are_reported() {
+    let missing = hex_to_id("0000000000000000000000000000000000000001");
+    let out = run(Some(missing), &full::Options::default());
+    assert_eq!(
+        out.missing,
+        [full::Missing {
+            id: missing,
+            kind: None,
+            referenced_by: None
+        }]
+    );
+}
+
+#[test]
+fn skip_list_and_shallow_commits() {
+    let head = tips()[0];
+    let mut options = full::Options::default();
+    options.object.skip_list.insert(id_in("broken-commit"));
+    options.shallow_commits.insert(head);
+    let out = run(Some(head), &options);
+    assert!(out.problems.is_empty(), "skipped objects aren't reported");
+    assert_eq!(out.missing.len(), 1, "only the blob is missing");
+    assert!(
+        out.unreachable.iter().any(|(_, kind)| *kind == Kind::Tag),
+        "parents of shallow commits aren't followed, and the tag isn't a tip"
+    );
+}
+
+#[test]
+fn interruption() {
+    let db = db();
+    let err = full::check(
+        &db,
+        Some(id_in("dangling-blob")),
+        None,
+        &full::Options::default(),
+        &AtomicBool::new(true),
+    )
+    .unwrap_err();
+    assert!(matches!(err, full::Error::Interrupted));
+}
@@ -0,0 +1,273 @@
+use gix_fsck::{
+    message::{Id, Severity},
+    object::{check, Options},
+};
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+fn problems(kind: Kind, data: &[u8], options: &Options) -> Vec<(Id, Severity)> {
+    let mut out = Vec::new();
+    let id = ObjectId::null(gix_hash::Kind::Sha1);
+    let has_error = check(&id, kind, data, options, |p| out.push((p.message, p.severity)));
+    assert_eq!(
+        has_error,
+        out.iter().any(|(_, severity)| severity.is_error()),
+        "the return value indicates errors"
+    );
+    out
+}
+
+const TREE: &str = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n";
+const IDENT: &str = "A U Thor <author@example.com> 1700000000 +0100\n";
+
+fn commit(headers: &str) -> Vec<u8> {
+    format!("{headers}\nmessage\n").into_bytes()
+}
+
+fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name) in entries {
+        out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+        out.extend_from_slice(&[1; 20]);
+    }
+    out
+}
+
+mod commit {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let data = commit(&format!("{TREE}author {IDENT}committer {IDENT}"));
+        assert_eq!(problems(Kind::Commit, &data, &Options::default()), []);
+    }
+
+    #[test]
+    fn missing_author() {
+        let data = commit(&format!("{TREE}committer {IDENT}"));
+        assert_eq!(
+            problems(Kind::Commit, &data, &Options::default()),
+            [(Id::MissingAuthor, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn bad_tree_hash() {
+        let data = commit(&format!("tree 1234\nauthor {IDENT}committer {IDENT}"));
+        assert_eq!(
+            problems(Kind::Commit, &data, &Options::default()),
+            [(Id::BadTreeSha1, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn bad_identities() {
+        for (ident, expected) in [
+            ("<a@example.com> 1 +0000", Id::MissingNameBeforeEmail),
+            ("A>a <a@example.com> 1 +0000", Id::BadName),
+            ("A 1 +0000", Id::MissingEmail),
+            ("A<a@example.com> 1 +0000", Id::MissingSpaceBeforeEmail),
+            ("A <a@example.com 1 +0000", Id::BadEmail),
+            ("A <a@example.com>1 +0000", Id::MissingSpaceBeforeDate),
+            ("A <a@example.com> 01 +0000", Id::ZeroPaddedDate),
+            ("A <a@example.com> x +0000", Id::BadDate),
+            ("A <a@example.com> 99999999999999999999999 +0000", Id::BadDateOverflow),
+            ("A <a@example.com> 1 0000", Id::BadTimezone),
+        ] {
+            let data = commit(&format!("{TREE}author {ident}\ncommitter {IDENT}"));
+            assert_eq!(
+                problems(Kind::Commit, &data, &Options::default()),
+                [(expected, Severity::Error)],
+                "{ident}"
+            );
+        }
+    }
+
+    #[test]
+    fn nul_in_header_is_fatal() {
+        let data = commit(&format!("{TREE}author A <a\0@example.com> 1 +0000\ncommitter {IDENT}"));
+        assert_eq!(
+            problems(Kind::Commit, &data, &Options::default()),
+            [(Id::NulInHeader, Severity::Fatal)]
+        );
+    }
+
+    #[test]
+    fn severity_overrides_allow_checking_to_continue() {
+        let data = commit(&format!("{TREE}author {IDENT}author {IDENT}"));
+        let mut options = Options::default();
+        options.severities.insert(Id::MultipleAuthors, Severity::Warn);
+        assert_eq!(
+            problems(Kind::Commit, &data, &options),
+            [
+                (Id::MultipleAuthors, Severity::Warn),
+                (Id::MissingCommitter, Severity::Error)
+            ]
+        );
+
+        options.severities.insert(Id::MissingCommitter, Severity::Ignore);
+        assert_eq!(
+            problems(Kind::Commit, &data, &options),
+            [(Id::MultipleAuthors, Severity::Warn)]
+        );
+    }
+}
+
+mod tag {
+    use super::*;
+
+    fn tag(headers: &str) -> Vec<u8> {
+        format!("object 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n{headers}\nmessage\n").into_bytes()
+    }
+
+    #[test]
+    fn valid() {
+        let data = tag(&format!("type tree\ntag v1\ntagger {IDENT}"));
+        assert_eq!(problems(Kind::Tag, &data, &Options::default()), []);
+    }
+
+    #[test]
+    fn informational_messages_are_no_errors() {
+        let data = tag("type tree\ntag v1..2\n");
+        assert_eq!(
+            problems(Kind::Tag, &data, &Options::default()),
+            [
+                (Id::BadTagName, Severity::Info),
+                (Id::MissingTaggerEntry, Severity::Info)
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_type() {
+        let data = tag(&format!("type nothing\ntag v1\ntagger {IDENT}"));
+        assert_eq!(
+            problems(Kind::Tag, &data, &Options::default()),
+            [(Id::BadType, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn missing_type() {
+        let data = tag(&format!("tag v1\ntagger {IDENT}"));
+        assert_eq!(
+            problems(Kind::Tag, &data, &Options::default()),
+            [(Id::MissingTypeEntry, Severity::Error)]
+        );
+    }
+}
+
+mod tree {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let data = tree(&[
+            ("100644", "a"),
+            ("40000", "a-dir"),
+            ("100644", "b.txt"),
+            ("40000", "b"),
+            ("120000", "link"),
+            ("160000", "submodule"),
+            ("100755", "x"),
+        ]);
+        assert_eq!(problems(Kind::Tree, &data, &Options::default()), []);
+    }
+
+    #[test]
+    fn directories_sort_with_implicit_slash() {
+        let data = tree(&[("100644", "a.txt"), ("40000", "a")]);
+        assert_eq!(problems(Kind::Tree, &data, &Options::default()), []);
+
+        let data = tree(&[("40000", "a"), ("100644", "a.txt")]);
+        assert_eq!(
+            problems(Kind::Tree, &data, &Options::default()),
+            [(Id::TreeNotSorted, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let data = tree(&[("100644", "a"), ("40000", "a")]);
+        assert_eq!(
+            problems(Kind::Tree, &data, &Options::default()),
+            [(Id::DuplicateEntries, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn warnings_become_errors_in_strict_mode() {
+        let data = tree(&[("100664", ".GIT"), ("040000", "b"), ("100644", "c/d"), ("100644", "..")]);
+        assert_eq!(
+            problems(Kind::Tree, &data, &Options::default()),
+            [
+                (Id::FullPathname, Severity::Warn),
+                (Id::HasDotdot, Severity::Warn),
+                (Id::HasDotgit, Severity::Warn),
+                (Id::ZeroPaddedFilemode, Severity::Warn),
+                (Id::TreeNotSorted, Severity::Error)
+            ]
+        );
+
+        let strict = Options {
+            strict: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            problems(Kind::Tree, &data, &strict),
+            [(Id::FullPathname, Severity::Error)],
+            "checking stops at the first error"
+        );
+    }
+
+    #[test]
+    fn group_writable_files_are_bad_only_in_strict_mode() {
+        let data = tree(&[("100664", "a")]);
+        assert_eq!(problems(Kind::Tree, &data, &Options::default()), []);
+        let strict = Options {
+            strict: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            problems(Kind::Tree, &data, &strict),
+            [(Id::BadFilemode, Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn unparsable() {
+        assert_eq!(
+            problems(Kind::Tree, b"100644 a\0short", &Options::default()),
+            [(Id::BadTree, Severity::Error)]
+        );
+    }
+}
+
+#[test]
+fn skip_list_silences_all_messages() {
+    let data = commit(TREE);
+    let mut options = Options::default();
+    options.skip_list.insert(ObjectId::null(gix_hash::Kind::Sha1));
+    assert_eq!(problems(Kind::Commit, &data, &options), []);
+}
+
+#[test]
+fn message_ids_are_matched_case_insensitively() {
+    assert_eq!(Id::from_bytes(b"zeroPaddedFilemode"), Some(Id::ZeroPaddedFilemode));
+    assert_eq!(Id::from_bytes(b"ZERO_PADDED_FILEMODE"), Some(Id::ZeroPaddedFilemode));
+    assert_eq!(Id::from_bytes(b"zeropaddedfilemode"), Some(Id::ZeroPaddedFilemode));
+    assert_eq!(Id::from_bytes(b"unknown"), None);
+    for id in Id::ALL {
+        assert_eq!(Id::from_bytes(id.as_str().as_bytes()), Some(*id));
+    }
+}
+
+#[test]
+fn skip_list_parsing() {
+    let list = gix_fsck::message::parse_skip_list(
+        b"# comment\n4b825dc642cb6eb9a060e54bf8d69288fbee4904 # the empty tree\n\n  e69de29bb2d1d6434b8b29ae775ad8c2e48c5391\n",
+    )
+    .expect("valid");
+    assert_eq!(list.len(), 2);
+    assert_eq!(gix_fsck::message::parse_skip_list(b"invalid\n").unwrap_err(), "invalid");
+}
@@ -1,14 +1,10 @@
 use std::{cmp::Ordering, ops::Range};
 
-use bstr::{BStr, ByteSlice, ByteVec};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use filetime::FileTime;
 
 use crate::{entry, extension, DirectoryKind, Entry, PathStorage, PathStorageRef, State, Version};
 
-// TODO: integrate this somehow, somewhere, depending on later usage.
-#[allow(dead_code)]
-mod sparse;
-
 /// General information and entries
 impl State {
     /// Return the version used to store this state's information on disk.
@@ -586,6 +582,128 @@ impl State {
         });
     }
 
+    /// Insert a new entry with `stat`, `id`, `flags`, `mode` and `path` at its sorted position, or replace the entry with the
+    /// same `path` and stage, and return its index in [`entries()`](Self::entries()). The stage is taken from `flags`.
+    ///
+    /// If the entry is at stage 0, all other stages of `path` are removed as the conflict is considered resolved.
+    /// Entries which would conflict with `path` as they are its parent directory, or as `path` is their parent directory,
+    /// are removed as well. Thus, adding `a/b` removes `a`, and adding `a` removes `a/b`.
+    ///
+    /// The [`tree` extension](extension::Tree) is invalidated along `path` as it doesn't represent the entries anymore.
+    ///
+    /// Note that [sparse directory entries](entry::Mode::DIR) aren't considered, so sparse indices should be
+    /// [expanded](Self::expand_sparse_directories()) first, and that the memory used for the paths of removed entries
+    /// is not freed, as it's append-only.
+    pub fn upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        let stage = flags.stage();
+        self.invalidate_tree_at(path);
+        if stage == 0 {
+            if let Some(mut range) = self.entry_range(path) {
+                if self.entries[range.start].stage() == 0 {
+                    range.start += 1;
+                }
+                self.entries.drain(range);
+            }
+        }
+        let mut dir = path;
+        while let Some(pos) = dir.rfind_byte(b'/') {
+            dir = dir[..pos].as_bstr();
+            if let Some(range) = self.entry_range(dir) {
+                self.entries.drain(range);
+            }
+        }
+        let mut dir_prefix = BString::from(path);
+        dir_prefix.push(b'/');
+        if let Some(range) = self.prefixed_entries_range(dir_prefix.as_ref()) {
+            self.entries.drain(range);
+        }
+
+        let paths = &self.path_backing;
+        match self
+            .entries
+            .binary_search_by(|e| Entry::cmp_filepaths(e.path_in(paths), path).then_with(|| e.stage().cmp(&stage)))
+        {
+            Ok(idx) => {
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                idx
+            }
+            Err(idx) => {
+                let path_start = self.path_backing.len();
+                self.path_backing.push_str(path);
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        stat,
+                        id,
+                        flags,
+                        mode,
+                        path: path_start..self.path_backing.len(),
+                    },
+                );
+                idx
+            }
+        }
+    }
+
+    /// Remove the entries at `path` in all stages and return the amount of removed entries.
+    ///
+    /// The [`tree` extension](extension::Tree) is invalidated along `path` if at least one entry was removed.
+    pub fn remove_entries_by_path(&mut self, path: &BStr) -> usize {
+        match self.entry_range(path) {
+            Some(range) => {
+                self.invalidate_tree_at(path);
+                self.entries.drain(range).len()
+            }
+            None => 0,
+        }
+    }
+
+    /// Mark all trees of the [`tree` extension](extension::Tree) leading to `path` as invalid, and remove the tree at `path`
+    /// if there is one, to indicate that they don't represent the entries anymore.
+    ///
+    /// This is called automatically by [`upsert_entry()`](Self::upsert_entry()) and
+    /// [`remove_entries_by_path()`](Self::remove_entries_by_path()), but needs to be called when altering the
+    /// [`id`](Entry::id) or [`mode`](Entry::mode) of entries [directly](Self::entries_mut()).
+    pub fn invalidate_tree_at(&mut self, path: &BStr) {
+        let mut tree = match self.tree.as_mut() {
+            Some(tree) => tree,
+            None => return,
+        };
+        let mut path = path;
+        loop {
+            tree.num_entries = None;
+            let (name, rest) = match path.find_byte(b'/') {
+                Some(pos) => (&path[..pos], Some(path[pos + 1..].as_bstr())),
+                None => (path, None),
+            };
+            let pos = match tree.children.binary_search_by(|child| child.name.as_slice().cmp(name)) {
+                Ok(pos) => pos,
+                Err(_) => break,
+            };
+            match rest {
+                Some(rest) => {
+                    tree = &mut tree.children[pos];
+                    path = rest;
+                }
+                None => {
+                    tree.children.remove(pos);
+                    break;
+                }
+            }
+        }
+    }
+
     /// Unconditionally sort entries as needed to perform lookups quickly.
     pub fn sort_entries(&mut self) {
         let path_backing = &self.path_backing;
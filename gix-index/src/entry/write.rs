@@ -1,10 +1,39 @@
 use std::convert::TryInto;
 
+use bstr::BStr;
+
 use crate::{entry, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
     pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_header_to(&mut out, path)?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` with path access via `state` as done in index V4, with our path stored
+    /// as difference to `previous_path`, which is empty for the first entry.
+    pub(crate) fn write_v4_to(
+        &self,
+        mut out: impl std::io::Write,
+        state: &State,
+        previous_path: &BStr,
+    ) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_header_to(&mut out, path)?;
+        let common_prefix_len = previous_path
+            .iter()
+            .zip(path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        crate::util::write_var_int(&mut out, (previous_path.len() - common_prefix_len) as u64)?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_header_to(&self, out: &mut impl std::io::Write, path: &BStr) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -17,7 +46,6 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
         let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
@@ -33,7 +61,6 @@ impl Entry {
                     .to_be_bytes(),
             )?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
     }
 }
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use bstr::BString;
 
 use crate::{
@@ -5,15 +7,51 @@ use crate::{
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
+/// The token of the filesystem monitor which identifies the point in time up to which changes are known.
 #[derive(Clone)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of the version 1 hook protocol.
+    V1 {
+        /// The time of the last query.
+        nanos_since_1970: u64,
+    },
+    /// The opaque token of the version 2 hook protocol.
+    V2 {
+        /// The token as provided by the filesystem monitor.
+        token: BString,
+    },
 }
 
+/// The signature of the filesystem monitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
-pub fn decode(data: &[u8]) -> Option<FsMonitor> {
+impl FsMonitor {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut entry_dirty = Vec::new();
+        self.entry_dirty.write_to(&mut entry_dirty)?;
+        data.extend_from_slice(&(u32::try_from(entry_dirty.len()).expect("less than 4GB bitmap")).to_be_bytes());
+        data.extend_from_slice(&entry_dirty);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB fsmonitor extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+pub(crate) fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
         1 => {
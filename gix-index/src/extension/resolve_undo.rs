@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use bstr::BString;
 use gix_hash::ObjectId;
 
@@ -6,9 +8,10 @@ use crate::{
     util::{split_at_byte_exclusive, split_at_pos},
 };
 
+/// All paths of the resolve-undo extension.
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
+/// The stages of a conflicting path before it was resolved, allowing to undo the resolution.
 #[derive(Clone)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
@@ -18,16 +21,37 @@ pub struct ResolvePath {
     stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
+/// A single stage of a conflicting path.
 #[derive(Clone, Copy)]
 pub struct Stage {
     mode: u32,
     id: ObjectId,
 }
 
+/// The signature of the resolve-undo extension.
 pub const SIGNATURE: Signature = *b"REUC";
 
-pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
+pub(crate) fn write_to(mut out: impl std::io::Write, paths: &Paths) -> Result<(), std::io::Error> {
+    let mut data = Vec::new();
+    for path in paths {
+        data.extend_from_slice(&path.name);
+        data.push(0);
+        for stage in &path.stages {
+            let mode = stage.map_or(0, |stage| stage.mode);
+            data.extend_from_slice(format!("{mode:o}").as_bytes());
+            data.push(0);
+        }
+        for stage in path.stages.iter().flatten() {
+            data.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(data.len()).expect("less than 4GB resolve-undo extension")).to_be_bytes())?;
+    out.write_all(&data)
+}
+
+pub(crate) fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     let hash_len = object_hash.len_in_bytes();
     let mut out = Vec::new();
 
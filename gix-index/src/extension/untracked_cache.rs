@@ -1,4 +1,4 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 use bstr::BString;
 use gix_hash::ObjectId;
@@ -6,7 +6,7 @@ use gix_hash::ObjectId;
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
-    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int, write_var_int},
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
@@ -58,8 +58,8 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
 
     let mut res = UntrackedCache {
         identifier: identifier.into(),
-        info_exclude: (!info_exclude.id.is_null()).then_some(info_exclude),
-        excludes_file: (!excludes_file.id.is_null()).then_some(excludes_file),
+        info_exclude: info_exclude.is_set().then_some(info_exclude),
+        excludes_file: excludes_file.is_set().then_some(excludes_file),
         exclude_filename_per_dir: exclude_filename_per_dir.into(),
         dir_flags,
         directories: Vec::new(),
@@ -111,6 +111,89 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
     res.into()
 }
 
+impl UntrackedCache {
+    /// Serialize this instance to `out`, assuming object hashes are of type `object_hash`.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: gix_hash::Kind) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        write_var_int(&mut data, self.identifier.len() as u64)?;
+        data.extend_from_slice(&self.identifier);
+        for oid_stat in [&self.info_exclude, &self.excludes_file] {
+            match oid_stat {
+                Some(oid_stat) => {
+                    write_stat(&mut data, &oid_stat.stat);
+                    data.extend_from_slice(oid_stat.id.as_bytes());
+                }
+                None => {
+                    write_stat(&mut data, &Default::default());
+                    data.extend_from_slice(object_hash.null().as_bytes());
+                }
+            }
+        }
+        data.extend_from_slice(&self.dir_flags.to_be_bytes());
+        data.extend_from_slice(&self.exclude_filename_per_dir);
+        data.push(0);
+
+        write_var_int(&mut data, self.directories.len() as u64)?;
+        if !self.directories.is_empty() {
+            write_directory_block(&mut data, &self.directories, 0)?;
+            let bitmap = |predicate: &dyn Fn(&Directory) -> bool| {
+                gix_bitmap::ewah::Vec::from_set_bits(
+                    self.directories
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, dir)| predicate(dir).then_some(index)),
+                )
+            };
+            bitmap(&|dir| dir.stat.is_some()).write_to(&mut data)?;
+            bitmap(&|dir| dir.check_only).write_to(&mut data)?;
+            bitmap(&|dir| dir.exclude_file_oid.is_some()).write_to(&mut data)?;
+            for stat in self.directories.iter().filter_map(|dir| dir.stat.as_ref()) {
+                write_stat(&mut data, stat);
+            }
+            for id in self.directories.iter().filter_map(|dir| dir.exclude_file_oid.as_ref()) {
+                data.extend_from_slice(id.as_bytes());
+            }
+        }
+        data.push(0);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB untracked cache extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+fn write_directory_block(out: &mut Vec<u8>, directories: &[Directory], index: usize) -> Result<(), std::io::Error> {
+    let dir = &directories[index];
+    write_var_int(&mut *out, dir.untracked_entries.len() as u64)?;
+    write_var_int(&mut *out, dir.sub_directories.len() as u64)?;
+    out.extend_from_slice(&dir.name);
+    out.push(0);
+    for name in &dir.untracked_entries {
+        out.extend_from_slice(name);
+        out.push(0);
+    }
+    for sub_directory in &dir.sub_directories {
+        write_directory_block(out, directories, *sub_directory)?;
+    }
+    Ok(())
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: &entry::Stat) {
+    for value in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
 fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>) -> Option<&'a [u8]> {
     let (num_untracked, data) = var_int(data)?;
     let (num_dirs, data) = var_int(data)?;
@@ -143,6 +226,13 @@ fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>)
     data.into()
 }
 
+impl OidStat {
+    /// Git writes the stat of exclude files even if their id is null, so only consider it unset if both are empty.
+    fn is_set(&self) -> bool {
+        !self.id.is_null() || self.stat != entry::Stat::default()
+    }
+}
+
 fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
     let (stat, data) = crate::decode::stat(data)?;
     let (hash, data) = split_at_pos(data, hash_len)?;
@@ -63,6 +63,12 @@ mod access {
         pub fn checksum(&self) -> Option<gix_hash::ObjectId> {
             self.checksum
         }
+
+        /// The shared index that was merged into this one when it was read as split index, or that was written along with it
+        /// when [writing][File::write()] a split index.
+        pub fn shared_index(&self) -> Option<&File> {
+            self.shared_index.as_deref()
+        }
     }
 }
 
@@ -1,6 +1,6 @@
 use gix_features::hash;
 
-use crate::{write, File, Version};
+use crate::{write, File, State, Version};
 
 /// The error produced by [`File::write()`].
 #[derive(Debug, thiserror::Error)]
@@ -19,40 +19,298 @@ impl File {
     /// to retain all information of this index.
     pub fn write_to(
         &self,
-        mut out: impl std::io::Write,
+        out: impl std::io::Write,
         options: write::Options,
     ) -> std::io::Result<(Version, gix_hash::ObjectId)> {
         let _span = gix_features::trace::detail!("gix_index::File::write_to()", skip_hash = options.skip_hash);
-        let (version, hash) = if options.skip_hash {
-            let out: &mut dyn std::io::Write = &mut out;
-            let version = self.state.write_to(out, options)?;
-            (version, self.state.object_hash.null())
-        } else {
-            let mut hasher = hash::Write::new(&mut out, self.state.object_hash);
-            let out: &mut dyn std::io::Write = &mut hasher;
-            let version = self.state.write_to(out, options)?;
-            (version, gix_hash::ObjectId::from(hasher.hash.digest()))
-        };
-        out.write_all(hash.as_slice())?;
-        Ok((version, hash))
+        write_state(&self.state, out, options)
     }
 
     /// Write ourselves to the path we were read from after acquiring a lock, using `options`.
     ///
+    /// If [`options.split_index`](write::Options::split_index) is set, a split index is written which refers to a shared index
+    /// file next to it, which is created as needed. Sparse indices are always written as regular index as they can't be split.
+    ///
     /// Note that the hash produced will be stored which is why we need to be mutable.
     pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write()", path = ?self.path);
-        let mut lock = std::io::BufWriter::with_capacity(
-            64 * 1024,
-            gix_lock::File::acquire_to_update_resource(&self.path, gix_lock::acquire::Fail::Immediately, None)?,
-        );
-        let (version, digest) = self.write_to(&mut lock, options)?;
-        match lock.into_inner() {
-            Ok(lock) => lock.commit()?,
-            Err(err) => return Err(err.into_error().into()),
+        let (version, digest) = match options.split_index {
+            Some(split_index) if !self.state.is_sparse() => self.write_split(options, split_index)?,
+            _ => write_locked(&self.path, &self.state, options)?,
         };
         self.state.version = version;
         self.checksum = Some(digest);
         Ok(())
     }
+
+    fn write_split(
+        &mut self,
+        options: write::Options,
+        split_index: write::SplitIndex,
+    ) -> Result<(Version, gix_hash::ObjectId), Error> {
+        let delta = self.shared_index.as_deref().and_then(|shared_index| {
+            if shared_index.path.parent() != self.path.parent() {
+                return None;
+            }
+            let delta = split::Delta::compute(&shared_index.state, &self.state);
+            (!delta.needs_new_shared_index(split_index.max_percent_change)).then_some(delta)
+        });
+        let (split_state, new_shared_index) = match delta {
+            Some(delta) => {
+                let shared_index = self.shared_index.as_deref().expect("present if there is a delta");
+                // Keep the shared index from expiring while it's still in use.
+                filetime::set_file_mtime(&shared_index.path, filetime::FileTime::now()).ok();
+                let checksum = shared_index.checksum.expect("shared indices are read or written");
+                (delta.into_state(&self.state, checksum), None)
+            }
+            None => {
+                let shared_index = split::write_shared_index(&self.path, &self.state)?;
+                let checksum = shared_index.checksum.expect("just written");
+                (
+                    split::Delta::default().into_state(&self.state, checksum),
+                    Some(shared_index),
+                )
+            }
+        };
+
+        let out = write_locked(&self.path, &split_state, options)?;
+        if let Some(shared_index) = new_shared_index {
+            if let Some(expiry) = split_index.shared_index_expiry {
+                split::remove_expired_shared_indices(&shared_index.path, expiry);
+            }
+            self.shared_index = Some(Box::new(shared_index));
+        }
+        Ok(out)
+    }
+}
+
+fn write_state(
+    state: &State,
+    mut out: impl std::io::Write,
+    options: write::Options,
+) -> std::io::Result<(Version, gix_hash::ObjectId)> {
+    let (version, hash) = if options.skip_hash {
+        let out: &mut dyn std::io::Write = &mut out;
+        let version = state.write_to(out, options)?;
+        (version, state.object_hash.null())
+    } else {
+        let mut hasher = hash::Write::new(&mut out, state.object_hash);
+        let out: &mut dyn std::io::Write = &mut hasher;
+        let version = state.write_to(out, options)?;
+        (version, gix_hash::ObjectId::from(hasher.hash.digest()))
+    };
+    out.write_all(hash.as_slice())?;
+    Ok((version, hash))
+}
+
+fn write_locked(
+    path: &std::path::Path,
+    state: &State,
+    options: write::Options,
+) -> Result<(Version, gix_hash::ObjectId), Error> {
+    let mut lock = std::io::BufWriter::with_capacity(
+        64 * 1024,
+        gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?,
+    );
+    let out = write_state(state, &mut lock, options)?;
+    match lock.into_inner() {
+        Ok(lock) => lock.commit()?,
+        Err(err) => return Err(err.into_error().into()),
+    };
+    Ok(out)
+}
+
+mod split {
+    use std::path::Path;
+
+    use crate::{entry, extension, write, File, State};
+
+    /// Information about how the entries of an index differ from the ones in its shared index.
+    #[derive(Default)]
+    pub(super) struct Delta {
+        /// The positions of entries in the shared index that aren't present anymore.
+        deleted: Vec<usize>,
+        /// Pairs of positions in the shared index and the positions of the entries that replace them.
+        replaced: Vec<(usize, usize)>,
+        /// The positions of entries that don't exist in the shared index.
+        added: Vec<usize>,
+        /// The amount of entries in the index.
+        num_entries: usize,
+    }
+
+    impl Delta {
+        pub(super) fn compute(shared: &State, state: &State) -> Self {
+            let mut out = Delta::default();
+            let mut is_used = vec![false; shared.entries.len()];
+            for (index, entry) in state.entries.iter().enumerate() {
+                if entry.flags.contains(entry::Flags::REMOVE) {
+                    continue;
+                }
+                out.num_entries += 1;
+                match shared.entry_index_by_path_and_stage(entry.path(state), entry.stage()) {
+                    Some(shared_index) => {
+                        is_used[shared_index] = true;
+                        let shared_entry = &shared.entries[shared_index];
+                        if shared_entry.stat != entry.stat
+                            || shared_entry.id != entry.id
+                            || shared_entry.mode != entry.mode
+                            || stored_flags(shared_entry.flags) != stored_flags(entry.flags)
+                        {
+                            out.replaced.push((shared_index, index));
+                        }
+                    }
+                    None => out.added.push(index),
+                }
+            }
+            out.deleted = is_used
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, is_used)| (!is_used).then_some(index))
+                .collect();
+            out
+        }
+
+        pub(super) fn needs_new_shared_index(&self, max_percent_change: u8) -> bool {
+            match max_percent_change {
+                0 => true,
+                100.. => false,
+                max_percent_change => {
+                    let not_shared = self.replaced.len() + self.added.len();
+                    self.num_entries * usize::from(max_percent_change) < not_shared * 100
+                }
+            }
+        }
+
+        /// Create a state to write as split index, with replaced entries first and without a path, followed by all
+        /// new entries, while keeping all extensions of `state`.
+        pub(super) fn into_state(self, state: &State, shared_index_checksum: gix_hash::ObjectId) -> State {
+            let mut entries = Vec::with_capacity(self.replaced.len() + self.added.len());
+            let mut path_backing = Vec::new();
+            for &(_, index) in &self.replaced {
+                let mut entry = state.entries[index].clone();
+                entry.path = 0..0;
+                entries.push(entry);
+            }
+            for index in self.added {
+                let mut entry = state.entries[index].clone();
+                let path = entry.path(state);
+                entry.path = path_backing.len()..path_backing.len() + path.len();
+                path_backing.extend_from_slice(path);
+                entries.push(entry);
+            }
+
+            State {
+                object_hash: state.object_hash,
+                timestamp: state.timestamp,
+                version: state.version,
+                entries,
+                path_backing,
+                is_sparse: false,
+                tree: state.tree.clone(),
+                link: Some(extension::Link {
+                    shared_index_checksum,
+                    bitmaps: Some(extension::link::Bitmaps {
+                        delete: gix_bitmap::ewah::Vec::from_set_bits(self.deleted),
+                        replace: gix_bitmap::ewah::Vec::from_set_bits(
+                            self.replaced.into_iter().map(|(shared_index, _)| shared_index),
+                        ),
+                    }),
+                }),
+                resolve_undo: state.resolve_undo.clone(),
+                untracked: state.untracked.clone(),
+                fs_monitor: state.fs_monitor.clone(),
+            }
+        }
+    }
+
+    /// Only the flags that are persisted, as in-memory flags don't constitute a change.
+    fn stored_flags(flags: entry::Flags) -> entry::Flags {
+        flags
+            & (entry::Flags::STAGE_MASK
+                | entry::Flags::EXTENDED
+                | entry::Flags::ASSUME_VALID
+                | entry::Flags::INTENT_TO_ADD
+                | entry::Flags::SKIP_WORKTREE)
+    }
+
+    /// Write all entries of `state` into a new shared index next to `index_path`, without extensions.
+    pub(super) fn write_shared_index(index_path: &Path, state: &State) -> Result<File, super::Error> {
+        let shared_state = State {
+            object_hash: state.object_hash,
+            timestamp: state.timestamp,
+            version: state.version,
+            entries: state
+                .entries
+                .iter()
+                .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                .cloned()
+                .collect(),
+            path_backing: state.path_backing.clone(),
+            is_sparse: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        };
+        let mut buf = Vec::new();
+        let (version, checksum) = super::write_state(
+            &shared_state,
+            &mut buf,
+            write::Options {
+                extensions: write::Extensions::None,
+                version: None,
+                skip_hash: false,
+                split_index: None,
+            },
+        )?;
+
+        let path = index_path
+            .parent()
+            .expect("index files are always in a directory")
+            .join(format!("sharedindex.{checksum}"));
+        if path.is_file() {
+            filetime::set_file_mtime(&path, filetime::FileTime::now()).ok();
+        } else {
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+            std::io::Write::write_all(&mut lock, &buf)?;
+            lock.commit()?;
+        }
+
+        Ok(File {
+            state: State {
+                version,
+                ..shared_state
+            },
+            path,
+            checksum: Some(checksum),
+            shared_index: None,
+        })
+    }
+
+    /// Remove all shared indices next to the one at `shared_index_path` which weren't modified since `expiry`.
+    /// Errors are ignored, as shared indices that can't be removed now may be removed another time.
+    pub(super) fn remove_expired_shared_indices(shared_index_path: &Path, expiry: std::time::SystemTime) {
+        let Some(dir) = shared_index_path.parent() else { return };
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path == shared_index_path
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("sharedindex."))
+            {
+                continue;
+            }
+            let is_expired = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .map_or(false, |modified| modified < expiry);
+            if is_expired {
+                std::fs::remove_file(path).ok();
+            }
+        }
+    }
 }
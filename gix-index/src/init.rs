@@ -1,3 +1,5 @@
+pub(crate) use from_tree::CollectEntries;
+
 mod from_tree {
     use std::collections::VecDeque;
 
@@ -47,6 +49,7 @@ mod from_tree {
                 path_backing,
                 path: _,
                 path_deque: _,
+                flags: _,
             } = delegate;
 
             entries.sort_by(|a, b| Entry::cmp_filepaths(a.path_in(&path_backing), b.path_in(&path_backing)));
@@ -67,11 +70,12 @@ mod from_tree {
         }
     }
 
-    struct CollectEntries {
-        entries: Vec<Entry>,
-        path_backing: PathStorage,
+    pub(crate) struct CollectEntries {
+        pub(crate) entries: Vec<Entry>,
+        pub(crate) path_backing: PathStorage,
         path: BString,
         path_deque: VecDeque<BString>,
+        flags: Flags,
     }
 
     impl CollectEntries {
@@ -81,6 +85,18 @@ mod from_tree {
                 path_backing: Vec::new(),
                 path: BString::default(),
                 path_deque: VecDeque::new(),
+                flags: Flags::empty(),
+            }
+        }
+
+        /// Collect entries below the directory at `prefix` into `path_backing`, with each entry receiving `flags`.
+        pub(crate) fn with_prefix(prefix: &BStr, flags: Flags, path_backing: PathStorage) -> CollectEntries {
+            CollectEntries {
+                entries: Vec::new(),
+                path_backing,
+                path: prefix.to_owned(),
+                path_deque: VecDeque::new(),
+                flags,
             }
         }
 
@@ -106,7 +122,7 @@ mod from_tree {
             let new_entry = Entry {
                 stat: Stat::default(),
                 id: entry.oid.into(),
-                flags: Flags::empty(),
+                flags: self.flags,
                 mode,
                 path: path_start..self.path_backing.len(),
             };
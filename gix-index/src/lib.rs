@@ -24,6 +24,9 @@ mod access;
 
 mod init;
 
+///
+pub mod sparse;
+
 ///
 pub mod decode;
 
@@ -88,6 +91,9 @@ pub struct File {
     pub(crate) path: PathBuf,
     /// The checksum of all bytes prior to the checksum itself.
     pub(crate) checksum: Option<gix_hash::ObjectId>,
+    /// The shared index that was merged into `state` when reading a split index, or that was written alongside it,
+    /// as it's needed to write the split index again.
+    pub(crate) shared_index: Option<Box<File>>,
 }
 
 /// The type to use and store paths to all entries.
@@ -170,6 +176,23 @@ pub(crate) mod util {
         (num, data).into()
     }
 
+    /// Write `n` as variable int in the same format understood by [`var_int()`].
+    pub fn write_var_int(mut out: impl std::io::Write, mut n: u64) -> std::io::Result<()> {
+        let mut buf = [0u8; 10];
+        let mut bytes_written = 1;
+        buf[buf.len() - 1] = n as u8 & 0b0111_1111;
+        for out in buf.iter_mut().rev().skip(1) {
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+            n -= 1;
+            *out = 0b1000_0000 | (n as u8 & 0b0111_1111);
+            bytes_written += 1;
+        }
+        out.write_all(&buf[buf.len() - bytes_written..])
+    }
+
     #[inline]
     pub fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
         split_at_pos(data, 4).map(|(num, data)| (u32::from_be_bytes(num.try_into().unwrap()), data))
@@ -0,0 +1,311 @@
+/// Configuration related to sparse indexes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If true, certain entries in the index will be excluded / skipped for certain operations,
+    /// based on the ignore patterns in the `.git/info/sparse-checkout` file. These entries will
+    /// carry the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This typically is the value of `core.sparseCheckout` in the git configuration.
+    pub sparse_checkout: bool,
+
+    /// Interpret the `.git/info/sparse-checkout` file using _cone mode_.
+    ///
+    /// If true, _cone mode_ is active and entire directories will be included in the checkout, as well as files in the root
+    /// of the repository.
+    /// If false, non-cone mode is active and entries to _include_ will be matched with patterns like those found in `.gitignore` files.
+    ///
+    /// This typically is the value of `core.sparseCheckoutCone` in the git configuration.
+    pub directory_patterns_only: bool,
+
+    /// If true, will attempt to write a sparse index file which only works in cone mode.
+    ///
+    /// A sparse index has [`DIR` entries][crate::entry::Mode::DIR] that represent entire directories to be skipped
+    /// during checkout and other operations due to the added presence of
+    /// the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This is typically the value of `index.sparse` in the git configuration.
+    pub write_sparse_index: bool,
+}
+
+impl Options {
+    /// Derive a valid mode from all parameters that affect the 'sparseness' of the index.
+    ///
+    /// Some combinations of them degenerate to one particular mode.
+    pub fn sparse_mode(&self) -> Mode {
+        match (
+            self.sparse_checkout,
+            self.directory_patterns_only,
+            self.write_sparse_index,
+        ) {
+            (true, true, true) => Mode::IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+            (true, true, false) => Mode::IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+            (true, false, _) => Mode::IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+            (false, _, _) => Mode::Disabled,
+        }
+    }
+}
+
+/// Describes the configuration how a sparse index should be written, or if one should be written at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// index with DIR entries for exclusion and included entries, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+    /// index with all file entries and skip worktree flags for exclusion, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+    /// index with all file entries and skip-worktree flags for exclusion, `ignore` patterns to include entries in `.git/info/sparse-checkout` file.
+    IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+    /// index with all entries, non is excluded, `.git/info/sparse-checkout` file is not considered, a regular index.
+    Disabled,
+}
+
+mod function {
+    use bstr::{BStr, BString, ByteSlice, ByteVec};
+    use gix_object::FindExt;
+    use gix_traverse::tree::breadthfirst;
+
+    use crate::{entry, extension, init::CollectEntries, Entry, State};
+
+    /// Sparse directories
+    impl State {
+        /// Replace each [sparse directory entry](entry::Mode::DIR) with all entries of the tree it points to, recursively,
+        /// using `objects` to lookup trees, and return the amount of sparse directories that were expanded.
+        ///
+        /// Each new entry is marked with [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE) just like the sparse directory it
+        /// replaces, and the resulting index is not sparse anymore. This is what `git` calls a _full index_.
+        ///
+        /// The [`tree` extension](extension::Tree), if present, is updated to contain the trees of all expanded directories
+        /// so that [collapsing](Self::collapse_sparse_directories()) them again is possible.
+        pub fn expand_sparse_directories<Find>(&mut self, objects: Find) -> Result<usize, breadthfirst::Error>
+        where
+            Find: gix_object::Find,
+        {
+            if !self.is_sparse {
+                return Ok(0);
+            }
+            let _span = gix_features::trace::coarse!("gix_index::State::expand_sparse_directories()");
+            let mut path_backing = std::mem::take(&mut self.path_backing);
+            let mut entries = Vec::with_capacity(self.entries.len());
+            let mut buf = Vec::new();
+            let mut num_expanded = 0;
+            let mut res = Ok(());
+            for entry in std::mem::take(&mut self.entries) {
+                if !entry.mode.is_sparse() || res.is_err() {
+                    entries.push(entry);
+                    continue;
+                }
+                let dir = entry.path_in(&path_backing);
+                let dir = dir.strip_suffix(b"/").unwrap_or(dir).as_bstr().to_owned();
+                let mut delegate = CollectEntries::with_prefix(
+                    dir.as_ref(),
+                    entry.flags | entry::Flags::EXTENDED,
+                    std::mem::take(&mut path_backing),
+                );
+                res = objects
+                    .find_tree_iter(&entry.id, &mut buf)
+                    .map_err(Into::into)
+                    .and_then(|root| breadthfirst(root, breadthfirst::State::default(), &objects, &mut delegate));
+                path_backing = delegate.path_backing;
+                if res.is_ok() {
+                    entries.extend(delegate.entries);
+                    num_expanded += 1;
+                    if let Some(tree) = self.tree.as_mut() {
+                        let is_valid = tree_extension_of(&entry.id, &objects, &mut buf).map_or(false, |mut node| {
+                            node.name = dir.rsplit_str("/").next().unwrap_or_default().into();
+                            insert_expanded_tree(tree, dir.as_ref(), node)
+                        });
+                        if !is_valid {
+                            self.tree = None;
+                        }
+                    }
+                } else {
+                    entries.push(entry);
+                }
+            }
+            self.path_backing = path_backing;
+            self.entries = entries;
+            self.sort_entries();
+            self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+            res.map(|_| num_expanded)
+        }
+
+        /// Replace all entries below directories for which `is_outside_cone(dir)` returns `true` with a single
+        /// [sparse directory entry](entry::Mode::DIR), and return the amount of created sparse directory entries.
+        ///
+        /// `dir` is the path to a directory relative to the root of the repository, without trailing slash.
+        /// This is the inverse of [`expand_sparse_directories()`](Self::expand_sparse_directories()).
+        ///
+        /// Directories are only collapsed if their [`tree` extension](extension::Tree) is present and valid, and if all of their
+        /// entries are unconflicted and marked with [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE). Thus, if there is no
+        /// tree extension, nothing will be collapsed.
+        pub fn collapse_sparse_directories(&mut self, mut is_outside_cone: impl FnMut(&BStr) -> bool) -> usize {
+            let Some(mut tree) = self.tree.take() else {
+                return 0;
+            };
+            let _span = gix_features::trace::coarse!("gix_index::State::collapse_sparse_directories()");
+            let mut collapsed = Vec::new();
+            let mut path = BString::default();
+            self.find_collapsible_directories(&tree, &mut path, &mut is_outside_cone, &mut collapsed);
+            if collapsed.is_empty() {
+                self.tree = Some(tree);
+                return 0;
+            }
+            collapsed.sort_by_key(|(_, range, _)| range.start);
+
+            let mut entries = Vec::with_capacity(self.entries.len());
+            let mut cursor = 0;
+            for (dir, range, id) in &collapsed {
+                entries.extend_from_slice(&self.entries[cursor..range.start]);
+                let path_start = self.path_backing.len();
+                self.path_backing.push_str(dir);
+                self.path_backing.push(b'/');
+                entries.push(Entry {
+                    stat: Default::default(),
+                    id: *id,
+                    flags: entry::Flags::SKIP_WORKTREE | entry::Flags::EXTENDED,
+                    mode: entry::Mode::DIR,
+                    path: path_start..self.path_backing.len(),
+                });
+                cursor = range.end;
+            }
+            entries.extend_from_slice(&self.entries[cursor..]);
+            self.entries = entries;
+            self.is_sparse = true;
+
+            remove_collapsed_trees(&mut tree, &mut BString::default(), &collapsed);
+            self.tree = Some(tree);
+            collapsed.len()
+        }
+
+        fn find_collapsible_directories(
+            &self,
+            tree: &extension::Tree,
+            path: &mut BString,
+            is_outside_cone: &mut dyn FnMut(&BStr) -> bool,
+            out: &mut Vec<(BString, std::ops::Range<usize>, gix_hash::ObjectId)>,
+        ) {
+            for child in &tree.children {
+                let prev_len = path.len();
+                if !path.is_empty() {
+                    path.push(b'/');
+                }
+                path.extend_from_slice(&child.name);
+
+                let collapsible_range =
+                    child
+                        .num_entries
+                        .filter(|_| is_outside_cone(path.as_ref()))
+                        .and_then(|num_entries| {
+                            path.push(b'/');
+                            let range = self.prefixed_entries_range(path.as_ref());
+                            path.pop();
+                            range.filter(|range| {
+                                range.len() == num_entries as usize
+                                    && self.entries[range.clone()].iter().all(|e| {
+                                        e.stage() == 0
+                                            && !e.mode.is_sparse()
+                                            && e.flags.contains(entry::Flags::SKIP_WORKTREE)
+                                    })
+                            })
+                        });
+                match collapsible_range {
+                    Some(range) => out.push((path.clone(), range, child.id)),
+                    None => self.find_collapsible_directories(child, path, is_outside_cone, out),
+                }
+                path.truncate(prev_len);
+            }
+        }
+    }
+
+    /// Build the tree extension of the tree with `id`, recursively, leaving its name empty.
+    fn tree_extension_of(
+        id: &gix_hash::oid,
+        objects: &impl gix_object::Find,
+        buf: &mut Vec<u8>,
+    ) -> Result<extension::Tree, breadthfirst::Error> {
+        let mut subtrees = Vec::new();
+        let mut num_entries = 0;
+        for entry in objects.find_tree_iter(id, buf)? {
+            let entry = entry?;
+            if entry.mode.is_tree() {
+                subtrees.push((entry.filename.to_owned(), entry.oid.to_owned()));
+            } else {
+                num_entries += 1;
+            }
+        }
+        let mut children = Vec::with_capacity(subtrees.len());
+        for (name, id) in subtrees {
+            let mut child = tree_extension_of(&id, objects, buf)?;
+            child.name = name.as_slice().into();
+            num_entries += child.num_entries.unwrap_or_default();
+            children.push(child);
+        }
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(extension::Tree {
+            name: Default::default(),
+            id: id.to_owned(),
+            num_entries: Some(num_entries),
+            children,
+        })
+    }
+
+    /// Place `node` at `dir` within `root` in place of a sparse directory entry, and adjust the entry count of all of its
+    /// parents. Return `false` if a parent of `dir` wasn't found, which means `root` doesn't match the index anymore.
+    fn insert_expanded_tree(root: &mut extension::Tree, dir: &BStr, node: extension::Tree) -> bool {
+        let added_entries = node.num_entries.unwrap_or_default().saturating_sub(1);
+        let mut tree = root;
+        let mut components = dir.split_str("/").peekable();
+        while let Some(component) = components.next() {
+            if let Some(num_entries) = tree.num_entries.as_mut() {
+                *num_entries += added_entries;
+            }
+            let pos = tree
+                .children
+                .binary_search_by(|child| child.name.as_slice().cmp(component));
+            if components.peek().is_none() {
+                match pos {
+                    Ok(pos) => tree.children[pos] = node,
+                    Err(pos) => tree.children.insert(pos, node),
+                }
+                return true;
+            }
+            match pos {
+                Ok(pos) => tree = &mut tree.children[pos],
+                Err(_) => return false,
+            }
+        }
+        false
+    }
+
+    /// Remove all trees that were turned into sparse directories and adjust the entry count of their parents,
+    /// returning the amount of entries that were removed.
+    fn remove_collapsed_trees(
+        tree: &mut extension::Tree,
+        path: &mut BString,
+        collapsed: &[(BString, std::ops::Range<usize>, gix_hash::ObjectId)],
+    ) -> u32 {
+        let mut removed = 0;
+        tree.children.retain_mut(|child| {
+            let prev_len = path.len();
+            if !path.is_empty() {
+                path.push(b'/');
+            }
+            path.extend_from_slice(&child.name);
+            let keep = match collapsed.iter().find(|(dir, _, _)| dir == path) {
+                Some((_, range, _)) => {
+                    removed += range.len() as u32 - 1;
+                    false
+                }
+                None => {
+                    removed += remove_collapsed_trees(child, path, collapsed);
+                    true
+                }
+            };
+            path.truncate(prev_len);
+            keep
+        });
+        if let Some(num_entries) = tree.num_entries.as_mut() {
+            *num_entries -= removed;
+        }
+        removed
+    }
+}
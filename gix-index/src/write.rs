@@ -17,6 +17,12 @@ pub enum Extensions {
     Given {
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
     },
@@ -32,9 +38,15 @@ impl Extensions {
             Extensions::All => Some(signature),
             Extensions::Given {
                 tree_cache,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
                 _ => &false,
             }
@@ -45,17 +57,52 @@ impl Extensions {
 
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options write either index V2 or V3 depending on the content of the entries, even if the
+/// index was read as V4.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write.
     pub extensions: Extensions,
+    /// The version to write, or `None` to write V2 or V3 depending on the content of the entries.
+    ///
+    /// [`Version::V4`] is always written as is, while [`Version::V2`] is upgraded to [`Version::V3`] and vice versa
+    /// depending on whether entries have extended flags or not, to avoid losing information.
+    pub version: Option<Version>,
     /// Set the trailing hash of the produced index to all zeroes to save some time.
     ///
     /// This value is typically controlled by `index.skipHash` and is respected when the index is written
     /// via [`File::write()`](crate::File::write()) and [`File::write_to()`](crate::File::write_to()).
     /// Note that
     pub skip_hash: bool,
+    /// If set, write a split index with most entries stored in a shared index file next to it, like `core.splitIndex` would.
+    ///
+    /// This is only respected by [`File::write()`](crate::File::write()) as it needs to know where to place the shared index.
+    pub split_index: Option<SplitIndex>,
+}
+
+/// Configure how a split index is written by [`File::write()`](crate::File::write()).
+#[derive(Debug, Clone, Copy)]
+pub struct SplitIndex {
+    /// The maximum percentage of entries that may be stored in the split index itself instead of the shared index
+    /// before a new shared index is written, like `splitIndex.maxPercentChange`.
+    ///
+    /// With `0` a new shared index will be written each time, and with `100` only if there is no shared index yet.
+    pub max_percent_change: u8,
+    /// Once a new shared index was written, remove all other shared index files which weren't modified since the given time,
+    /// like `splitIndex.sharedIndexExpire`. If `None`, they are never removed.
+    pub shared_index_expiry: Option<std::time::SystemTime>,
+}
+
+impl Default for SplitIndex {
+    /// Use the same defaults as `git`, which writes a new shared index once 20% of the entries changed, and removes
+    /// shared indices that weren't used in the last two weeks.
+    fn default() -> Self {
+        SplitIndex {
+            max_percent_change: 20,
+            shared_index_expiry: std::time::SystemTime::now()
+                .checked_sub(std::time::Duration::from_secs(60 * 60 * 24 * 14)),
+        }
+    }
 }
 
 impl State {
@@ -65,11 +112,16 @@ impl State {
         out: impl std::io::Write,
         Options {
             extensions,
+            version,
             skip_hash: _,
+            split_index: _,
         }: Options,
     ) -> std::io::Result<Version> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
-        let version = self.detect_required_version();
+        let version = match version.unwrap_or(Version::V2) {
+            Version::V4 => Version::V4,
+            Version::V2 | Version::V3 => self.detect_required_version(),
+        };
 
         let mut write = CountBytes::new(out);
         let num_entries: u32 = self
@@ -86,7 +138,7 @@ impl State {
             .expect("definitely not too many entries");
 
         let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
+        let offset_to_extensions = entries(&mut write, self, version, offset_to_entries)?;
         let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
 
         if num_entries > 0
@@ -112,11 +164,39 @@ impl State {
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                self.link()
+                    .map(|link| link.write_to(write).map(|_| extension::link::SIGNATURE))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(write, paths).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|untracked| untracked.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor()
+                            .map(|fs_monitor| fs_monitor.write_to(write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -164,11 +244,23 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    version: Version,
+    header_size: u32,
+) -> Result<u32, std::io::Error> {
+    let mut previous_path: &bstr::BStr = Default::default();
     for entry in state.entries() {
         if entry.flags.contains(entry::Flags::REMOVE) {
             continue;
         }
+        if version == Version::V4 {
+            let path = entry.path(state);
+            entry.write_v4_to(&mut *out, state, previous_path)?;
+            previous_path = path;
+            continue;
+        }
         entry.write_to(&mut *out, state)?;
         match (out.count - header_size) % 8 {
             0 => {}
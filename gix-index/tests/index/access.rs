@@ -271,6 +271,97 @@ fn remove_entries() {
     file.remove_entries(|_, _, _| unreachable!("should not be called"));
 }
 
+#[test]
+fn upsert_entry_inserts_in_order_or_replaces() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    let entry = file.entry(0).clone();
+    assert!(file.tree().expect("present").num_entries.is_some());
+
+    let idx = file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "d/aa".into());
+    assert_eq!(file.entry(idx).path(&file), "d/aa");
+    assert_eq!(file.entries().len(), num_entries + 1);
+    file.verify_entries()?;
+    let tree = file.tree().expect("still present");
+    assert_eq!(tree.num_entries, None, "the root tree is invalidated");
+    let d = &tree.children[0];
+    assert_eq!(
+        (d.name.as_slice(), d.num_entries),
+        (&b"d"[..], None),
+        "as is the parent tree"
+    );
+    assert!(d.children[0].num_entries.is_some(), "but unrelated trees remain valid");
+
+    let new_id = gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1);
+    let idx = file.upsert_entry(entry.stat, new_id, entry.flags, entry.mode, "x".into());
+    assert_eq!(file.entries().len(), num_entries + 1, "existing entries are replaced");
+    assert_eq!(file.entry(idx).id, new_id);
+    assert_eq!(
+        file.entry_by_path("x".into()).expect("present").id,
+        new_id,
+        "lookups work as entries are still sorted"
+    );
+    Ok(())
+}
+
+#[test]
+fn upsert_entry_removes_conflicting_entries() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    let entry = file.entry(0).clone();
+
+    file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "a/b".into());
+    assert_eq!(
+        file.entries().len(),
+        num_entries,
+        "the file 'a' is replaced with a directory"
+    );
+    assert!(file.entry_by_path("a".into()).is_none());
+    file.verify_entries()?;
+
+    file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "d".into());
+    assert_eq!(
+        file.entries().len(),
+        num_entries - 5,
+        "the directory 'd' with 6 files is replaced with a file"
+    );
+    assert!(file.prefixed_entries("d/".into()).is_none());
+    assert!(
+        file.tree().expect("present").children.is_empty(),
+        "the tree of 'd' doesn't exist anymore"
+    );
+    file.verify_entries()?;
+
+    let mut file = Fixture::Loose("conflicting-file").open();
+    let entry = file.entry(0).clone();
+    assert_eq!(entry.stage(), 1);
+    let flags = entry.flags - gix_index::entry::Flags::STAGE_MASK;
+    file.upsert_entry(entry.stat, entry.id, flags, entry.mode, "file".into());
+    assert_eq!(file.entries().len(), 1, "adding stage 0 resolves the conflict");
+    assert_eq!(file.entry(0).stage(), 0);
+    Ok(())
+}
+
+#[test]
+fn remove_entries_by_path() {
+    let mut file = Fixture::Loose("conflicting-file").open();
+    assert_eq!(file.remove_entries_by_path("does-not-exist".into()), 0);
+    assert_eq!(file.remove_entries_by_path("file".into()), 3, "all stages are removed");
+    assert_eq!(file.entries().len(), 0);
+
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    assert_eq!(file.remove_entries_by_path("d/last/6".into()), 1);
+    assert_eq!(file.entries().len(), num_entries - 1);
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None);
+    assert_eq!(tree.children[0].num_entries, None);
+    assert_eq!(
+        tree.children[0].children[0].num_entries, None,
+        "'last' is invalidated too"
+    );
+}
+
 #[test]
 fn sort_entries() {
     let mut file = Fixture::Generated("v4_more_files_IEOT").open();
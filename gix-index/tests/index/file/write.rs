@@ -14,12 +14,19 @@ fn roundtrips() -> crate::Result {
             Generated("v2"),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: true,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: true,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Loose("REUC"), all_ext_but_eoie()),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
     ];
 
     for (fixture, options) in input {
@@ -48,6 +55,7 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: false,
+        ..Default::default()
     })?;
 
     let actual = gix_index::File::at(
@@ -65,6 +73,7 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: true,
+        ..Default::default()
     })?;
 
     let actual = gix_index::File::at(
@@ -133,9 +142,7 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this fails because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required version, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files_IEOT"),
         Generated("v3_skip_worktree"),
         Generated("v3_added_files"),
         Generated("v3_sparse_index_non_cone"),
@@ -148,15 +155,25 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: true,
+                fs_monitor: false,
                 end_of_index_entry: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
+                resolve_undo: true,
+                untracked_cache: false,
+                fs_monitor: true,
                 end_of_index_entry: true,
             }),
         ] {
             let expected = fixture.open();
             let fixture = fixture.to_name();
+            let options = Options {
+                version: Some(expected.version()),
+                ..options
+            };
 
             let mut out = Vec::<u8>::new();
             let (actual_version, _digest) = expected.write_to(&mut out, options).unwrap();
@@ -168,6 +185,52 @@ fn state_comparisons_with_various_extension_configurations() {
     }
 }
 
+#[test]
+fn v4_is_written_with_path_prefix_compression_if_requested() -> crate::Result {
+    let expected = Generated("v2_more_files").open();
+    assert_eq!(expected.version(), Version::V2);
+
+    let mut v2 = Vec::new();
+    expected.write_to(&mut v2, Default::default())?;
+    let mut v4 = Vec::new();
+    let (actual_version, _digest) = expected.write_to(
+        &mut v4,
+        Options {
+            version: Some(Version::V4),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(actual_version, Version::V4);
+    assert!(
+        v4.len() < v2.len(),
+        "paths are prefix-compressed and entries aren't padded"
+    );
+
+    let (actual, _) = State::from_bytes(&v4, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(actual.version(), Version::V4);
+    assert_eq!(actual.entries(), expected.entries());
+    assert_eq!(actual.path_backing(), expected.path_backing());
+    Ok(())
+}
+
+#[test]
+fn v4_can_be_read_by_git() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let index_path = tmp.path().join(".git").join("index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    index.write(Options {
+        version: Some(Version::V4),
+        ..Default::default()
+    })?;
+    assert_eq!(index.version(), Version::V4);
+    assert_eq!(
+        git_ls_files(tmp.path()),
+        paths_and_ids(&index),
+        "git can read our V4 index"
+    );
+    Ok(())
+}
+
 #[test]
 fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crate::Result {
     let mut expected = Generated("v2").open();
@@ -210,6 +273,194 @@ fn remove_flag_is_respected() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn split_index_is_written_as_delta_to_its_shared_index() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")?;
+    let worktree = dir.path().join("split");
+    let index_path = worktree.join(".git/index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    let shared_index_checksum = index.shared_index().expect("read from split index").checksum();
+    assert_eq!(shared_indices(&index_path).len(), 1);
+
+    let id_of_z = index.entry_by_path_and_stage("z".into(), 0).expect("present").id;
+    let b = index.entry_index_by_path_and_stage("b".into(), 0).expect("present");
+    index.entries_mut()[b].id = id_of_z;
+    let y = index.entry_index_by_path_and_stage("y".into(), 0).expect("present");
+    index.entries_mut()[y].flags.insert(entry::Flags::REMOVE);
+    index.upsert_entry(
+        Default::default(),
+        id_of_z,
+        entry::Flags::empty(),
+        entry::Mode::FILE,
+        "new".into(),
+    );
+
+    index.write(Options {
+        split_index: Some(write::SplitIndex {
+            max_percent_change: 100,
+            shared_index_expiry: None,
+        }),
+        ..Default::default()
+    })?;
+    assert_eq!(
+        index.shared_index().expect("still present").checksum(),
+        shared_index_checksum,
+        "the shared index is reused"
+    );
+    assert_eq!(shared_indices(&index_path).len(), 1);
+
+    let (split, _) = State::from_bytes(
+        &std::fs::read(&index_path)?,
+        FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert_eq!(
+        split.entries().len(),
+        4,
+        "the replaced entry 'b' and the entries not in the shared index, 'd', 'e' and 'new', are stored in the split index"
+    );
+    let link = split.link().expect("link extension written");
+    assert_eq!(Some(link.shared_index_checksum), shared_index_checksum);
+    let bitmaps = link.bitmaps.as_ref().expect("always written");
+    assert_eq!(set_bits(&bitmaps.replace), [1], "'b' follows 'a' in the shared index");
+    assert_eq!(
+        set_bits(&bitmaps.delete),
+        [0, 2, 3, 4],
+        "'a', 'c', 'x' and 'y' aren't present anymore"
+    );
+
+    let actual = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(paths_and_ids(&actual), paths_and_ids(&index));
+    assert_eq!(
+        git_ls_files(&worktree),
+        paths_and_ids(&index),
+        "git reads the split index in the same way"
+    );
+    Ok(())
+}
+
+#[test]
+fn split_index_with_too_many_changes_gets_a_new_shared_index() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")?;
+    let worktree = dir.path().join("split");
+    let index_path = worktree.join(".git/index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    let previous_shared_indices = shared_indices(&index_path);
+
+    index.entries_mut()[0].stat.size += 1;
+    index.write(Options {
+        split_index: Some(write::SplitIndex {
+            max_percent_change: 0,
+            shared_index_expiry: Some(std::time::SystemTime::now() + std::time::Duration::from_secs(60)),
+        }),
+        ..Default::default()
+    })?;
+
+    let shared_index = index.shared_index().expect("written");
+    let shared_index_name = format!("sharedindex.{}", shared_index.checksum().expect("always set"));
+    assert_eq!(
+        shared_indices(&index_path),
+        [shared_index_name.as_str()],
+        "all other shared indices expired"
+    );
+    assert_ne!(previous_shared_indices, [shared_index_name]);
+
+    let (split, _) = State::from_bytes(
+        &std::fs::read(&index_path)?,
+        FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert_eq!(split.entries().len(), 0, "all entries are in the shared index");
+    assert_eq!(git_ls_files(&worktree), paths_and_ids(&index));
+    Ok(())
+}
+
+#[test]
+fn regular_index_can_be_written_as_split_index() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")?;
+    let worktree = dir.path().join("regular");
+    let index_path = worktree.join(".git/index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert!(index.shared_index().is_none());
+    assert!(shared_indices(&index_path).is_empty());
+
+    let options = Options {
+        split_index: Some(Default::default()),
+        ..Default::default()
+    };
+    index.write(options)?;
+    let shared_index_checksum = index.shared_index().expect("written").checksum();
+    assert_eq!(shared_indices(&index_path).len(), 1);
+    assert_eq!(git_ls_files(&worktree), paths_and_ids(&index));
+
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    index.write(options)?;
+    assert_eq!(
+        index.shared_index().expect("read").checksum(),
+        shared_index_checksum,
+        "without changes, the shared index is reused"
+    );
+    assert_eq!(git_ls_files(&worktree), paths_and_ids(&index));
+
+    index.write(Default::default())?;
+    let (state, _) = State::from_bytes(
+        &std::fs::read(&index_path)?,
+        FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert!(
+        state.link().is_none(),
+        "without split index options, a regular index is written"
+    );
+    assert_eq!(git_ls_files(&worktree), paths_and_ids(&index));
+    Ok(())
+}
+
+fn shared_indices(index_path: &std::path::Path) -> Vec<String> {
+    let mut out: Vec<_> = std::fs::read_dir(index_path.parent().expect("in .git"))
+        .expect("readable")
+        .map(|e| e.expect("valid").file_name().into_string().expect("valid UTF-8"))
+        .filter(|name| name.starts_with("sharedindex."))
+        .collect();
+    out.sort();
+    out
+}
+
+fn set_bits(bitmap: &gix_bitmap::ewah::Vec) -> Vec<usize> {
+    let mut out = Vec::new();
+    bitmap.for_each_set_bit(|index| {
+        out.push(index);
+        Some(())
+    });
+    out
+}
+
+fn paths_and_ids(state: &State) -> Vec<String> {
+    state
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .map(|e| format!("{} {}", e.id, e.path(state)))
+        .collect()
+}
+
+fn git_ls_files(worktree: &std::path::Path) -> Vec<String> {
+    let out = std::process::Command::new("git")
+        .args(["ls-files", "--format=%(objectname) %(path)"])
+        .current_dir(worktree)
+        .output()
+        .expect("git can be run");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout)
+        .expect("valid UTF-8")
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -244,6 +495,34 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
         "tree extension mismatch, actual vs option in {fixture:?}"
     );
 
+    assert_eq!(
+        actual.resolve_undo().is_some(),
+        options
+            .extensions
+            .should_write(extension::resolve_undo::SIGNATURE)
+            .and_then(|_| expected.resolve_undo())
+            .is_some(),
+        "resolve-undo extension mismatch, actual vs option in {fixture:?}"
+    );
+    assert_eq!(
+        actual.untracked().is_some(),
+        options
+            .extensions
+            .should_write(extension::untracked_cache::SIGNATURE)
+            .and_then(|_| expected.untracked())
+            .is_some(),
+        "untracked-cache extension mismatch, actual vs option in {fixture:?}"
+    );
+    assert_eq!(
+        actual.fs_monitor().is_some(),
+        options
+            .extensions
+            .should_write(extension::fs_monitor::SIGNATURE)
+            .and_then(|_| expected.fs_monitor())
+            .is_some(),
+        "fs-monitor extension mismatch, actual vs option in {fixture:?}"
+    );
+
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing against baseline
     assert_eq!(
@@ -293,14 +572,29 @@ fn only_tree_ext() -> Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
             tree_cache: true,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
         },
         skip_hash: false,
+        ..Default::default()
     }
 }
 
+fn all_ext_but_eoie() -> Options {
+    options_with(write::Extensions::Given {
+        end_of_index_entry: false,
+        tree_cache: true,
+        resolve_undo: true,
+        untracked_cache: true,
+        fs_monitor: true,
+    })
+}
+
 fn options_with(extensions: write::Extensions) -> Options {
     Options {
         extensions,
         skip_hash: false,
+        ..Default::default()
     }
 }
@@ -0,0 +1,87 @@
+use gix_index::entry::{Flags, Mode};
+
+use crate::index::Fixture;
+
+#[test]
+fn expand_sparse_directories_yields_full_index() -> crate::Result {
+    let repo = gix::open(gix_testtools::scripted_fixture_read_only_standalone(
+        "make_index/v3_sparse_index.sh",
+    )?)?;
+    let mut actual = repo.open_index()?.into_parts().0;
+    assert!(actual.is_sparse());
+
+    let num_expanded = actual.expand_sparse_directories(&repo.objects)?;
+    assert_eq!(num_expanded, 2, "c1/c3 and d");
+    assert!(!actual.is_sparse());
+    actual.verify_entries()?;
+    actual.verify_extensions(true, &repo.objects)?;
+
+    let expected = Fixture::Generated("v3_skip_worktree").open();
+    assert_entries_eq(&actual, &expected);
+    assert_eq!(
+        actual.tree(),
+        expected.tree(),
+        "the tree cache contains the expanded trees, allowing them to be collapsed again"
+    );
+
+    assert_eq!(
+        actual.expand_sparse_directories(&repo.objects)?,
+        0,
+        "full indices don't need expansion"
+    );
+    Ok(())
+}
+
+#[test]
+fn collapse_sparse_directories_yields_sparse_index() -> crate::Result {
+    let mut actual = Fixture::Generated("v3_skip_worktree").open().into_parts().0;
+    let num_entries = actual.entries().len();
+    assert!(actual.tree().is_some(), "the tree cache is required for collapsing");
+
+    let num_collapsed = actual.collapse_sparse_directories(|dir| dir != "c1" && dir != "c1/c2");
+    assert_eq!(num_collapsed, 2, "c1/c3 and d, but not d/c4 as it's already contained");
+    assert!(actual.is_sparse());
+    actual.verify_entries()?;
+    actual.verify_extensions(false, gix::objs::find::Never)?;
+    assert_eq!(
+        actual.tree().expect("still present").num_entries,
+        Some(num_entries as u32 - 7 + 2),
+        "the collapsed directories are counted as one entry each"
+    );
+
+    let expected = Fixture::Generated("v3_sparse_index").open();
+    assert_entries_eq(&actual, &expected);
+    Ok(())
+}
+
+#[test]
+fn collapse_sparse_directories_ignores_directories_with_entries_in_the_worktree() {
+    let mut actual = Fixture::Generated("v3_skip_worktree").open().into_parts().0;
+    let expected = actual.clone();
+
+    assert_eq!(
+        actual.collapse_sparse_directories(|dir| dir == "c1"),
+        0,
+        "c1 has entries that aren't skipped"
+    );
+    assert!(!actual.is_sparse());
+    assert_entries_eq(&actual, &expected);
+}
+
+fn assert_entries_eq(actual: &gix_index::State, expected: &gix_index::State) {
+    assert_eq!(actual.entries().len(), expected.entries().len());
+    for (a, e) in actual.entries().iter().zip(expected.entries()) {
+        assert_eq!(a.path(actual), e.path(expected));
+        assert_eq!(a.id, e.id, "{}", a.path(actual));
+        assert_eq!(a.mode, e.mode, "{}", a.path(actual));
+        assert_eq!(
+            a.flags & (Flags::SKIP_WORKTREE | Flags::EXTENDED),
+            e.flags & (Flags::SKIP_WORKTREE | Flags::EXTENDED),
+            "{}",
+            a.path(actual)
+        );
+        if a.mode == Mode::DIR {
+            assert!(a.path(actual).ends_with(b"/"), "sparse directories end with a slash");
+        }
+    }
+}
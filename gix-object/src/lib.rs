@@ -149,6 +149,8 @@ pub struct TagRef<'a> {
     /// The message describing this release.
     pub message: &'a BStr,
     /// A cryptographic signature over the entire content of the serialized tag object thus far.
+    ///
+    /// Despite its name, it can also be an SSH or X.509 signature.
     pub pgp_signature: Option<&'a BStr>,
 }
 
@@ -175,6 +177,8 @@ pub struct Tag {
     /// The message describing the tag.
     pub message: BString,
     /// A pgp signature over all bytes of the encoded tag, excluding the pgp signature itself.
+    ///
+    /// Despite its name, it can also be an SSH or X.509 signature.
     pub pgp_signature: Option<BString>,
 }
 
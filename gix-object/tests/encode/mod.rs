@@ -66,7 +66,8 @@ mod tag {
         "tag/no-tagger.txt",
         "tag/whitespace.txt",
         "tag/with-newlines.txt",
-        "tag/signed.txt"
+        "tag/signed.txt",
+        "tag/signed-ssh.txt"
     );
 }
 
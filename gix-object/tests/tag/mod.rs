@@ -150,6 +150,25 @@ mod from_bytes {
         Ok(())
     }
 
+    #[test]
+    fn signed_with_ssh() -> crate::Result {
+        let fixture = fixture_name("tag", "signed-ssh.txt");
+        let tag = TagRef::from_bytes(&fixture)?;
+        assert_eq!(tag.message, "for the signature");
+        assert_eq!(
+            tag.pgp_signature.expect("present"),
+            "-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAgGtFLLmuovkGF4HthTLm2zblsA8
+IR2HJyquXPTVcSmrUAAAADZ2l0AAAAAAAAAAZzaGE1MTIAAABTAAAAC3NzaC1lZDI1NTE5
+AAAAQLQmYvtYBajc2iXeQx2vWTJ9/Dv6DF8hh8b10vlvuHiamoNDQ1BL6GO+k6nexLKmxE
+Qv9vuz2Dyt4cu1h8VgOwA=
+-----END SSH SIGNATURE-----
+",
+            "SSH signatures are recognized just like PGP signatures"
+        );
+        Ok(())
+    }
+
     #[test]
     fn empty() -> crate::Result {
         assert_eq!(
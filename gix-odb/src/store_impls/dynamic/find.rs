@@ -26,6 +26,13 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("Could not obtain the missing object {id} from the promisor remote")]
+        Promisor {
+            /// The object that couldn't be found
+            id: gix_hash::ObjectId,
+            #[source]
+            source: Box<dyn std::error::Error + Send + Sync + 'static>,
+        },
         #[error("The base object {} could not be found but is required to decode {}", .base_id, .id)]
         DeltaBaseMissing {
             /// the id of the base object which failed to lookup
@@ -109,6 +116,7 @@ where
             }
         }
 
+        let mut promised = recursion.is_some();
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -297,11 +305,39 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
+                None if self.fetch_from_promisor(id, snapshot, &mut promised)? => {}
                 None => return Ok(None),
             }
         }
     }
 
+    /// Obtain the missing object `id` through our promisor if there is one and if that wasn't tried yet as tracked by `promised`,
+    /// and update `snapshot` to see the new objects. Return `true` if the object should be looked up once more.
+    pub(crate) fn fetch_from_promisor(
+        &self,
+        id: &gix_hash::oid,
+        snapshot: &mut load_index::Snapshot,
+        promised: &mut bool,
+    ) -> Result<bool, Error> {
+        let promisor = match self.promisor.as_ref() {
+            Some(promisor) if !*promised => promisor,
+            _ => return Ok(false),
+        };
+        *promised = true;
+        promisor.fetch(&[id.to_owned()]).map_err(|source| Error::Promisor {
+            id: id.to_owned(),
+            source,
+        })?;
+        if let Some(new_snapshot) = self
+            .store
+            .load_one_index(super::RefreshMode::AfterAllIndicesLoaded, snapshot.marker)?
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+        Ok(true)
+    }
+
     pub(crate) fn clear_cache(&self) {
         self.packed_object_count.borrow_mut().take();
     }
@@ -37,6 +37,7 @@ where
             }
         }
 
+        let mut promised = recursion.is_some();
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -172,6 +173,7 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
+                None if self.fetch_from_promisor(id, snapshot, &mut promised)? => {}
                 None => return Ok(None),
             }
         }
@@ -69,6 +69,15 @@ impl super::Store {
         Ok(snapshot)
     }
 
+    /// Consolidate our view of packs and indices with what's currently on disk, without waiting for an object lookup to fail first.
+    ///
+    /// Use this after adding or removing packs, for example to consolidate objects, so that all handles pick up the new state
+    /// the next time they access the store.
+    pub fn refresh(&self) -> Result<(), Error> {
+        self.consolidate_with_disk_state(false /* needs init */, false /* load new index */)?;
+        Ok(())
+    }
+
     /// If `None` is returned, there is new indices and the caller should give up. This is a possibility even if it's allowed to refresh
     /// as here might be no change to pick up.
     pub(crate) fn load_one_index(
@@ -23,6 +23,9 @@ where
 
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
+    /// If set, objects that can't be found will be obtained through it once before giving up, which is what
+    /// makes partial clones usable.
+    pub promisor: Option<std::sync::Arc<dyn Promisor>>,
 
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
@@ -43,6 +46,13 @@ pub enum RefreshMode {
     Never,
 }
 
+/// A way to obtain objects that are missing in the object database, typically from the *promisor remote* of a partial clone.
+pub trait Promisor: Send + Sync {
+    /// Obtain all objects identified by `ids` and write them into the object database on disk so that they can be
+    /// found after the next refresh.
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
 impl RefreshMode {
     /// Set this refresh mode to never refresh.
     pub fn never(&mut self) {
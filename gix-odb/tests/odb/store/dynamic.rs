@@ -776,6 +776,116 @@ fn missing_objects_triggers_everything_is_loaded() {
     );
 }
 
+mod promisor {
+    use std::{
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use gix_hash::ObjectId;
+    use gix_object::{Exists, Find, FindExt};
+    use gix_odb::Header;
+    use gix_testtools::fixture_path_standalone;
+
+    use crate::hex_to_id;
+
+    /// A promisor that makes a hidden pack visible when asked, which is as if it was fetched.
+    struct UnhidePack {
+        stem: PathBuf,
+        calls: AtomicUsize,
+    }
+
+    impl gix_odb::store::Promisor for UnhidePack {
+        fn fetch(&self, ids: &[ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            assert_eq!(ids.len(), 1, "one object at a time");
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.stem.with_extension("idx.bak").is_file() {
+                std::fs::rename(self.stem.with_extension("pack.bak"), self.stem.with_extension("pack"))?;
+                std::fs::rename(self.stem.with_extension("idx.bak"), self.stem.with_extension("idx"))?;
+            }
+            Ok(())
+        }
+    }
+
+    struct Unavailable;
+
+    impl gix_odb::store::Promisor for Unavailable {
+        fn fetch(&self, _ids: &[ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Err("the promisor remote is unreachable".into())
+        }
+    }
+
+    fn objects_with_hidden_pack() -> crate::Result<(gix_testtools::tempfile::TempDir, PathBuf)> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let pack_dir = tmp.path().join("pack");
+        std::fs::create_dir(&pack_dir)?;
+        gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects/pack"), &pack_dir)?;
+        let stem = pack_dir.join("pack-c0438c19fb16422b6bbcce24387b3264416d485b");
+        std::fs::rename(stem.with_extension("idx"), stem.with_extension("idx.bak"))?;
+        std::fs::rename(stem.with_extension("pack"), stem.with_extension("pack.bak"))?;
+        Ok((tmp, stem))
+    }
+
+    const IN_HIDDEN_PACK: &str = "dd25c539efbb0ab018caa4cda2d133285634e9b5";
+
+    #[test]
+    fn missing_objects_are_obtained_through_the_promisor_once() -> crate::Result {
+        let (tmp, stem) = objects_with_hidden_pack()?;
+        let promisor = Arc::new(UnhidePack {
+            stem,
+            calls: AtomicUsize::default(),
+        });
+        let mut handle = gix_odb::at(tmp.path())?;
+        handle.refresh_never();
+        handle.promisor = Some(promisor.clone());
+
+        let id = hex_to_id(IN_HIDDEN_PACK);
+        assert!(!handle.exists(&id), "checking for existence never fetches");
+        assert_eq!(promisor.calls.load(Ordering::SeqCst), 0);
+
+        let mut buf = Vec::new();
+        handle.find(&id, &mut buf)?;
+        assert_eq!(
+            promisor.calls.load(Ordering::SeqCst),
+            1,
+            "the object was fetched, and is found afterwards even though the handle never refreshes"
+        );
+        assert!(handle.exists(&id));
+
+        let missing = ObjectId::null(gix_hash::Kind::Sha1);
+        assert!(
+            handle.try_find(&missing, &mut buf)?.is_none(),
+            "objects the promisor can't provide are still missing"
+        );
+        assert!(handle.try_header(&missing)?.is_none());
+        assert_eq!(
+            promisor.calls.load(Ordering::SeqCst),
+            3,
+            "each lookup asks only once before giving up"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn promisor_errors_are_propagated() -> crate::Result {
+        let (tmp, _stem) = objects_with_hidden_pack()?;
+        let mut handle = gix_odb::at(tmp.path())?;
+        handle.promisor = Some(Arc::new(Unavailable));
+
+        let err = handle
+            .try_header(&hex_to_id(IN_HIDDEN_PACK))
+            .expect_err("the promisor fails");
+        assert_eq!(
+            err.to_string(),
+            "Could not obtain the missing object dd25c539efbb0ab018caa4cda2d133285634e9b5 from the promisor remote"
+        );
+        Ok(())
+    }
+}
+
 #[test]
 fn iterate_over_a_bunch_of_loose_and_packed_objects() -> crate::Result {
     let (db, _tmp) = db_with_all_object_sources()?;
@@ -0,0 +1,116 @@
+use std::io::Write;
+
+use crate::index;
+
+mod error {
+    /// The error returned by [`index::File::write_bitmap_to()`][crate::index::File::write_bitmap_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Object {id} isn't contained in the pack")]
+        ObjectNotInPack { id: gix_hash::ObjectId },
+        #[error("The kind of object {id} is unknown")]
+        UnknownKind { id: gix_hash::ObjectId },
+        #[error("Cannot write more than {} bitmaps", u32::MAX)]
+        TooManyCommits,
+    }
+}
+pub use error::Error;
+
+/// A commit to write a reachability bitmap for, for use in [`index::File::write_bitmap_to()`].
+#[derive(Debug, Clone)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: gix_hash::ObjectId,
+    /// The ids of all objects reachable from the commit, including the commit itself, in any order.
+    pub reachable: Vec<gix_hash::ObjectId>,
+}
+
+impl index::File {
+    pub(crate) const BITMAP_SIGNATURE: &'static [u8] = b"BITM";
+    pub(crate) const BITMAP_VERSION: u16 = 1;
+    /// Every object reachable from a commit with a bitmap is contained in the pack.
+    pub(crate) const BITMAP_OPT_FULL_DAG: u16 = 0x1;
+
+    /// Write a reachability bitmap for the pack of this index to `out`, as stored in `.bitmap` files next to the index, and
+    /// return the checksum of the written file.
+    ///
+    /// `commits` are the commits to write bitmaps for, and every object reachable from them must be contained in the pack.
+    /// `kind_of(id)` must return the kind of each object in the pack, which typically is known from traversing it.
+    ///
+    /// Bits are assigned to objects in the order in which they appear in the pack, and only version 1 of the format is written,
+    /// without the optional name-hash cache or lookup table.
+    pub fn write_bitmap_to(
+        &self,
+        commits: &[Commit],
+        kind_of: &mut dyn FnMut(&gix_hash::oid) -> Option<gix_object::Kind>,
+        out: &mut dyn std::io::Write,
+    ) -> Result<gix_hash::ObjectId, Error> {
+        let pack_position = {
+            let mut by_offset: Vec<_> = self
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| (entry.pack_offset, index))
+                .collect();
+            by_offset.sort_unstable();
+            let mut pack_position = vec![0; by_offset.len()];
+            for (position, (_offset, index)) in by_offset.into_iter().enumerate() {
+                pack_position[index] = position;
+            }
+            pack_position
+        };
+        let position_of = |id: &gix_hash::oid| -> Result<usize, Error> {
+            self.lookup(id)
+                .map(|index| pack_position[index as usize])
+                .ok_or_else(|| Error::ObjectNotInPack { id: id.to_owned() })
+        };
+
+        let mut by_kind = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for (index, position) in pack_position.iter().enumerate() {
+            let id = self.oid_at_index(index as index::EntryIndex);
+            let slot = match kind_of(id).ok_or_else(|| Error::UnknownKind { id: id.to_owned() })? {
+                gix_object::Kind::Commit => 0,
+                gix_object::Kind::Tree => 1,
+                gix_object::Kind::Blob => 2,
+                gix_object::Kind::Tag => 3,
+            };
+            by_kind[slot].push(*position);
+        }
+
+        let mut out = gix_features::hash::Write::new(out, self.object_hash);
+        out.write_all(Self::BITMAP_SIGNATURE)?;
+        out.write_all(&Self::BITMAP_VERSION.to_be_bytes())?;
+        out.write_all(&Self::BITMAP_OPT_FULL_DAG.to_be_bytes())?;
+        out.write_all(
+            &u32::try_from(commits.len())
+                .map_err(|_| Error::TooManyCommits)?
+                .to_be_bytes(),
+        )?;
+        out.write_all(self.pack_checksum().as_slice())?;
+
+        for mut positions in by_kind {
+            positions.sort_unstable();
+            gix_bitmap::ewah::Vec::from_set_bits(positions).write_to(&mut out)?;
+        }
+        for commit in commits {
+            let index = self.lookup(commit.id).ok_or(Error::ObjectNotInPack { id: commit.id })?;
+            let mut positions = commit
+                .reachable
+                .iter()
+                .map(|id| position_of(id))
+                .collect::<Result<Vec<_>, _>>()?;
+            positions.sort_unstable();
+            positions.dedup();
+
+            out.write_all(&index.to_be_bytes())?;
+            out.write_all(&[0 /* xor-offset */, 0 /* flags */])?;
+            gix_bitmap::ewah::Vec::from_set_bits(positions).write_to(&mut out)?;
+        }
+
+        let checksum: gix_hash::ObjectId = out.hash.digest().into();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
@@ -141,6 +141,9 @@ pub mod init;
 pub(crate) mod access;
 pub use access::Entry;
 
+///
+pub mod bitmap;
+
 pub(crate) mod encode;
 ///
 pub mod traverse;
@@ -0,0 +1,227 @@
+//! Support for the V2 `bundle-uri` command, which advertises bundles to download before fetching the remainder.
+use bstr::{BStr, BString, ByteSlice};
+
+/// How the bundles of a [`List`] relate to each other.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// All bundles are needed to obtain the full set of objects.
+    #[default]
+    All,
+    /// Any bundle contains the full set of objects, so only one of them needs to be downloaded.
+    Any,
+}
+
+/// The heuristic advertised along with a [`List`] to help with incremental downloads.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heuristic {
+    /// Bundles have a [creation token](Bundle::creation_token) and should be applied in ascending order.
+    CreationToken,
+}
+
+/// A single bundle as advertised in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bundle {
+    /// The identifier of the bundle, which is only used to group its keys.
+    pub id: BString,
+    /// The URI to download the bundle from, which may also be relative to the URI the list was obtained from.
+    pub uri: BString,
+    /// A value to sort bundles by to apply them in order, with lower values to be applied first.
+    pub creation_token: Option<u64>,
+    /// The object filter the bundle was created with, if any.
+    pub filter: Option<BString>,
+}
+
+/// A list of bundles as received by the `bundle-uri` command, or as read from a bundle list file.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// The heuristic to use to order the bundles, if one was advertised and is known to us.
+    pub heuristic: Option<Heuristic>,
+    /// All bundles in the order they were advertised.
+    pub bundles: Vec<Bundle>,
+}
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_lines()`](super::List::from_lines()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Expected a line of the form `bundle.<key>=<value>`, got {line:?}")]
+        MalformedLine { line: BString },
+        #[error("The bundle list didn't specify `bundle.version`")]
+        MissingVersion,
+        #[error("Bundle list version {version:?} is not supported")]
+        UnsupportedVersion { version: BString },
+        #[error("Bundle list mode {mode:?} is neither 'all' nor 'any'")]
+        InvalidMode { mode: BString },
+        #[error("The creation token {value:?} of bundle {id:?} isn't an unsigned integer")]
+        InvalidCreationToken { id: BString, value: BString },
+        #[error("Bundle {id:?} didn't specify an uri")]
+        MissingUri { id: BString },
+    }
+}
+
+impl List {
+    /// Parse a bundle list from `lines` of the form `bundle.<key>=<value>` as sent in response to the `bundle-uri` command.
+    ///
+    /// Keys we don't know are ignored to be compatible with future extensions, just like `git` does.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, decode::Error> {
+        let mut list = List::default();
+        let mut version = None;
+        let mut bundles = Vec::<(BString, Option<BString>, Option<u64>, Option<BString>)>::new();
+        for line in lines {
+            let line = line.trim_end_with(|c| c == '\n');
+            let malformed = || decode::Error::MalformedLine { line: line.into() };
+            let (key, value) = line.split_once_str(b"=").ok_or_else(malformed)?;
+            let key = key
+                .strip_prefix(b"bundle.")
+                .filter(|key| !key.is_empty())
+                .ok_or_else(malformed)?;
+            let value = value.as_bstr();
+            match key.rfind_byte(b'.') {
+                None => {
+                    if key.eq_ignore_ascii_case(b"version") {
+                        version = Some(value);
+                    } else if key.eq_ignore_ascii_case(b"mode") {
+                        list.mode = match value.as_bytes() {
+                            b"all" => Mode::All,
+                            b"any" => Mode::Any,
+                            _ => return Err(decode::Error::InvalidMode { mode: value.into() }),
+                        };
+                    } else if key.eq_ignore_ascii_case(b"heuristic") {
+                        list.heuristic = (value == "creationToken").then_some(Heuristic::CreationToken);
+                    }
+                }
+                Some(pos) => {
+                    let (id, key) = (key[..pos].as_bstr(), &key[pos + 1..]);
+                    let idx = match bundles.iter().position(|b| b.0 == id) {
+                        Some(idx) => idx,
+                        None => {
+                            bundles.push((id.into(), None, None, None));
+                            bundles.len() - 1
+                        }
+                    };
+                    let bundle = &mut bundles[idx];
+                    if key.eq_ignore_ascii_case(b"uri") {
+                        bundle.1 = Some(value.into());
+                    } else if key.eq_ignore_ascii_case(b"creationToken") {
+                        bundle.2 = Some(value.to_str().ok().and_then(|v| v.parse().ok()).ok_or_else(|| {
+                            decode::Error::InvalidCreationToken {
+                                id: id.into(),
+                                value: value.into(),
+                            }
+                        })?);
+                    } else if key.eq_ignore_ascii_case(b"filter") {
+                        bundle.3 = Some(value.into());
+                    }
+                }
+            }
+        }
+
+        match version {
+            None => return Err(decode::Error::MissingVersion),
+            Some(version) if version != "1" => {
+                return Err(decode::Error::UnsupportedVersion {
+                    version: version.into(),
+                })
+            }
+            Some(_) => {}
+        }
+        list.bundles = bundles
+            .into_iter()
+            .map(|(id, uri, creation_token, filter)| match uri {
+                Some(uri) => Ok(Bundle {
+                    id,
+                    uri,
+                    creation_token,
+                    filter,
+                }),
+                None => Err(decode::Error::MissingUri { id }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(list)
+    }
+}
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+mod error {
+    /// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The server didn't advertise the 'bundle-uri' capability")]
+        MissingServerCapability,
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        PacketlineDecode(#[from] gix_transport::packetline::decode::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        Decode(#[from] super::decode::Error),
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use error::Error;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub(crate) mod function {
+    use bstr::BString;
+    use gix_features::progress::Progress;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, List};
+    use crate::{command::Feature, Command};
+
+    /// Invoke the `bundle-uri` V2 command on `transport`, which requires a prior handshake that yielded server `capabilities`
+    /// which advertise it, and return the list of bundles the server suggests to download.
+    /// `features` are sent along with the command, and typically contain the `agent`. `progress` is used to provide feedback.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        features: Vec<Feature>,
+        progress: &mut impl Progress,
+        trace: bool,
+    ) -> Result<List, Error> {
+        let _span = gix_features::trace::detail!("gix_protocol::bundle_uri()", capabilities = ?capabilities);
+        if !capabilities.contains(Command::BundleUri.as_str()) {
+            return Err(Error::MissingServerCapability);
+        }
+        progress.step();
+        progress.set_name("bundle uri".into());
+        let mut reader = transport
+            .invoke(
+                Command::BundleUri.as_str(),
+                features.into_iter(),
+                Some(std::iter::empty::<BString>()),
+                trace,
+            )
+            .await?;
+        let mut lines = Vec::new();
+        while let Some(line) = reader.readline().await.transpose()?.transpose()?.and_then(|l| l.as_bstr()) {
+            lines.push(line.to_owned());
+        }
+        Ok(List::from_lines(lines.iter().map(AsRef::as_ref))?)
+    }
+}
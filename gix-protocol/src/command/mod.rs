@@ -12,6 +12,7 @@ impl Command {
         match self {
             Command::LsRefs => "ls-refs",
             Command::Fetch => "fetch",
+            Command::BundleUri => "bundle-uri",
         }
     }
 }
@@ -27,6 +28,7 @@ mod with_io {
         /// Only V2
         fn all_argument_prefixes(&self) -> &'static [&'static str] {
             match self {
+                Command::BundleUri => &[],
                 Command::LsRefs => &["symrefs", "peel", "ref-prefix ", "unborn"],
                 Command::Fetch => &[
                     "want ", // hex oid
@@ -58,7 +60,7 @@ mod with_io {
 
         fn all_features(&self, version: gix_transport::Protocol) -> &'static [&'static str] {
             match self {
-                Command::LsRefs => &[],
+                Command::LsRefs | Command::BundleUri => &[],
                 Command::Fetch => match version {
                     gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => &[
                         "multi_ack",
@@ -108,6 +110,7 @@ mod with_io {
                     )
                     .collect(),
                 Command::LsRefs => vec![b"symrefs".as_bstr().to_owned(), b"peel".as_bstr().to_owned()],
+                Command::BundleUri => Vec::new(),
             }
         }
 
@@ -154,7 +157,7 @@ mod with_io {
                             .collect()
                     }
                 },
-                Command::LsRefs => vec![],
+                Command::LsRefs | Command::BundleUri => vec![],
             }
         }
         /// Panics if the given arguments and features don't match what's statically known. It's considered a bug in the delegate.
@@ -19,6 +19,8 @@ pub struct Arguments {
     deepen_relative: bool,
     ref_in_want: bool,
     supports_include_tag: bool,
+    packfile_uris: bool,
+    no_done: bool,
 
     features_for_first_want: Option<Vec<String>>,
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -80,6 +82,18 @@ impl Arguments {
     pub fn can_use_include_tag(&self) -> bool {
         self.supports_include_tag
     }
+    /// Return true if the 'packfile-uris' capability is supported.
+    ///
+    /// This is only ever the case in protocol V2.
+    pub fn can_use_packfile_uris(&self) -> bool {
+        self.packfile_uris
+    }
+    /// Return true if the server sends the pack as soon as it signalled that it is `ready`, without waiting for `done`.
+    ///
+    /// This is always the case in protocol V2, and needs the 'no-done' capability in V1.
+    pub fn can_use_no_done(&self) -> bool {
+        self.no_done
+    }
     /// Return true if we will use a stateless mode of operation, which can be decided in conjunction with `transport_is_stateless`.
     ///
     /// * we are always stateless if the transport is stateless, i.e. doesn't support multiple interactions with a single connection.
@@ -171,6 +185,17 @@ impl Arguments {
         }
     }
 
+    /// Allow the server to send parts of the pack as separate packfiles to download from URIs with one of the given
+    /// `protocols`, like `https`, instead of sending them inline.
+    ///
+    /// The URIs are then listed in [`Response::packfile_uris()`](crate::fetch::Response::packfile_uris()).
+    pub fn use_packfile_uris(&mut self, protocols: &[&str]) {
+        debug_assert!(self.packfile_uris, "'packfile-uris' feature required");
+        if self.packfile_uris && !protocols.is_empty() {
+            self.prefixed("packfile-uris ", protocols.join(","));
+        }
+    }
+
     /// Add the given `feature`, unconditionally.
     ///
     /// Note that sending an unknown or unsupported feature may cause the remote to terminate
@@ -207,6 +232,8 @@ impl Arguments {
         let mut deepen_since = shallow;
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
+        let packfile_uris = version == gix_transport::Protocol::V2 && has("packfile-uris");
+        let no_done = version == gix_transport::Protocol::V2 || has("no-done");
         let supports_include_tag;
         let (initial_arguments, features_for_first_want) = match version {
             gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => {
@@ -240,6 +267,8 @@ impl Arguments {
             filter,
             shallow,
             supports_include_tag,
+            packfile_uris,
+            no_done,
             deepen_not,
             deepen_relative,
             ref_in_want,
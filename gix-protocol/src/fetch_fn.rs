@@ -72,6 +72,7 @@ where
         server_protocol_version: protocol_version,
         refs,
         capabilities,
+        ..
     } = crate::fetch::handshake(
         &mut transport,
         authenticate,
@@ -131,17 +132,13 @@ where
         progress.set_name(format!("negotiate (round {round})"));
         round += 1;
         let action = delegate.negotiate(&refs, &mut arguments, previous_response.as_ref())?;
-        let mut reader = arguments.send(&mut transport, action == Action::Cancel).await?;
+        let is_done = action == Action::Cancel;
+        let client_expects_pack = is_done || arguments.can_use_no_done();
+        let mut reader = arguments.send(&mut transport, is_done).await?;
         if sideband_all {
             setup_remote_progress(&mut progress, &mut reader);
         }
-        let response = Response::from_line_reader(
-            protocol_version,
-            &mut reader,
-            true,  /* hack, telling us we don't want this delegate approach anymore */
-            false, /* just as much of a hack which causes us to expect a pack immediately */
-        )
-        .await?;
+        let response = Response::from_line_reader(protocol_version, &mut reader, client_expects_pack, !is_done).await?;
         previous_response = if response.has_pack() {
             progress.step();
             progress.set_name("receiving pack".into());
@@ -12,18 +12,40 @@ use crate::{credentials, handshake::refs};
 #[allow(clippy::result_large_err)]
 #[maybe_async]
 pub async fn handshake<AuthFn, T>(
+    transport: T,
+    service: Service,
+    authenticate: AuthFn,
+    extra_parameters: Vec<(String, Option<String>)>,
+    progress: &mut impl Progress,
+) -> Result<Outcome, Error>
+where
+    AuthFn: FnMut(credentials::helper::Action) -> credentials::protocol::Result,
+    T: client::Transport,
+{
+    handshake_with_shallow_commits(transport, service, authenticate, extra_parameters, progress)
+        .await
+        .map(|(outcome, _shallow_commits)| outcome)
+}
+
+/// Like [`handshake()`], but also return the commits at which the remote repository is shallow, as reported as part of the
+/// Protocol::V1 handshake.
+///
+/// They are always empty in V2, which sends this information along with the pack instead.
+#[allow(clippy::result_large_err)]
+#[maybe_async]
+pub async fn handshake_with_shallow_commits<AuthFn, T>(
     mut transport: T,
     service: Service,
     mut authenticate: AuthFn,
     extra_parameters: Vec<(String, Option<String>)>,
     progress: &mut impl Progress,
-) -> Result<Outcome, Error>
+) -> Result<(Outcome, Vec<gix_hash::ObjectId>), Error>
 where
     AuthFn: FnMut(credentials::helper::Action) -> credentials::protocol::Result,
     T: client::Transport,
 {
     let _span = gix_features::trace::detail!("gix_protocol::handshake()", service = ?service, extra_parameters = ?extra_parameters);
-    let (server_protocol_version, refs, capabilities) = {
+    let (server_protocol_version, refs, shallow_commits, capabilities) = {
         progress.init(None, progress::steps());
         progress.set_name("handshake".into());
         progress.step();
@@ -76,7 +98,7 @@ where
             });
         }
 
-        let parsed_refs = match refs {
+        let (parsed_refs, shallow_commits) = match refs {
             Some(mut refs) => {
                 assert!(
                     matches!(
@@ -85,19 +107,25 @@ where
                     ),
                     "Only V(0|1) auto-responds with refs"
                 );
-                Some(
-                    refs::from_v1_refs_received_as_part_of_handshake_and_capabilities(&mut refs, capabilities.iter())
-                        .await?,
-                )
+                let (refs, shallow_commits) =
+                    refs::from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities(
+                        &mut refs,
+                        capabilities.iter(),
+                    )
+                    .await?;
+                (Some(refs), shallow_commits)
             }
-            None => None,
+            None => (None, Vec::new()),
         };
-        (actual_protocol, parsed_refs, capabilities)
+        (actual_protocol, parsed_refs, shallow_commits, capabilities)
     }; // this scope is needed, see https://github.com/rust-lang/rust/issues/76149
 
-    Ok(Outcome {
-        server_protocol_version,
-        refs,
-        capabilities,
-    })
+    Ok((
+        Outcome {
+            server_protocol_version,
+            refs,
+            capabilities,
+        },
+        shallow_commits,
+    ))
 }
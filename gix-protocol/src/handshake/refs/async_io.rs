@@ -23,12 +23,33 @@ pub async fn from_v2_refs(in_refs: &mut dyn gix_transport::client::ReadlineBufRe
 ///
 /// Symbolic refs are shoe-horned into server capabilities whereas refs (without symbolic ones) are sent automatically as
 /// part of the handshake. Both symbolic and peeled refs need to be combined to fit into the [`Ref`] type provided here.
+/// The commits at which shallow remotes are shallow are ignored, use
+/// [`from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities()`] to obtain them as well.
 pub async fn from_v1_refs_received_as_part_of_handshake_and_capabilities<'a>(
     in_refs: &mut dyn gix_transport::client::ReadlineBufRead,
     capabilities: impl Iterator<Item = gix_transport::client::capabilities::Capability<'a>>,
 ) -> Result<Vec<Ref>, refs::parse::Error> {
+    from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities(in_refs, capabilities)
+        .await
+        .map(|(refs, _shallow_commits)| refs)
+}
+
+/// Parse refs from the return stream of the handshake as well as the server capabilities, also received as part of the
+/// handshake.
+/// Together they form a complete set of refs, which is returned along with the commits at which the remote repository
+/// is shallow, if it is shallow at all.
+///
+/// # Note
+///
+/// Symbolic refs are shoe-horned into server capabilities whereas refs (without symbolic ones) are sent automatically as
+/// part of the handshake. Both symbolic and peeled refs need to be combined to fit into the [`Ref`] type provided here.
+pub async fn from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities<'a>(
+    in_refs: &mut dyn gix_transport::client::ReadlineBufRead,
+    capabilities: impl Iterator<Item = gix_transport::client::capabilities::Capability<'a>>,
+) -> Result<(Vec<Ref>, Vec<gix_hash::ObjectId>), refs::parse::Error> {
     let mut out_refs = refs::shared::from_capabilities(capabilities)?;
     let number_of_possible_symbolic_refs_for_lookup = out_refs.len();
+    let mut out_shallow = Vec::new();
 
     while let Some(line) = in_refs
         .readline()
@@ -37,7 +58,12 @@ pub async fn from_v1_refs_received_as_part_of_handshake_and_capabilities<'a>(
         .transpose()?
         .and_then(|l| l.as_bstr())
     {
-        refs::shared::parse_v1(number_of_possible_symbolic_refs_for_lookup, &mut out_refs, line)?;
+        refs::shared::parse_v1(
+            number_of_possible_symbolic_refs_for_lookup,
+            &mut out_refs,
+            &mut out_shallow,
+            line,
+        )?;
     }
-    Ok(out_refs.into_iter().map(Into::into).collect())
+    Ok((out_refs.into_iter().map(Into::into).collect(), out_shallow))
 }
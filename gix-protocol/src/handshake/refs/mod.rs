@@ -68,12 +68,18 @@ pub(crate) mod shared;
 #[cfg(feature = "async-client")]
 mod async_io;
 #[cfg(feature = "async-client")]
-pub use async_io::{from_v1_refs_received_as_part_of_handshake_and_capabilities, from_v2_refs};
+pub use async_io::{
+    from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities,
+    from_v1_refs_received_as_part_of_handshake_and_capabilities, from_v2_refs,
+};
 
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
 #[cfg(feature = "blocking-client")]
-pub use blocking_io::{from_v1_refs_received_as_part_of_handshake_and_capabilities, from_v2_refs};
+pub use blocking_io::{
+    from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities,
+    from_v1_refs_received_as_part_of_handshake_and_capabilities, from_v2_refs,
+};
 
 #[cfg(test)]
 mod tests;
@@ -123,9 +123,15 @@ pub(crate) fn from_capabilities<'a>(
 pub(in crate::handshake::refs) fn parse_v1(
     num_initial_out_refs: usize,
     out_refs: &mut Vec<InternalRef>,
+    out_shallow: &mut Vec<gix_hash::ObjectId>,
     line: &BStr,
 ) -> Result<(), Error> {
     let trimmed = line.trim_end();
+    if let Some(hex_hash) = trimmed.strip_prefix(b"shallow ") {
+        // Shallow remotes advertise the commits they are shallow at after all refs.
+        out_shallow.push(gix_hash::ObjectId::from_hex(hex_hash)?);
+        return Ok(());
+    }
     let (hex_hash, path) = trimmed.split_at(
         trimmed
             .find(b" ")
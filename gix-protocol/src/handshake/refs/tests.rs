@@ -118,6 +118,49 @@ dce0ea858eef7ff61ad345cc5cdac62203fb3c10 refs/tags/gix-commitgraph-v0.0.0
     )
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn extract_shallow_commits_from_v1_refs() {
+    let input = &mut Fixture(
+        "73a6868963993a3328e7d8fe94e5a6ac5078a944 HEAD
+73a6868963993a3328e7d8fe94e5a6ac5078a944 refs/heads/main
+shallow 21c9b7500cb144b3169a6537961ec2b9e865be81
+shallow 8e472f9ccc7d745927426cbb2d9d077de545aa4e"
+            .as_bytes(),
+    );
+    let (out, shallow) = refs::from_v1_refs_and_shallow_commits_received_as_part_of_handshake_and_capabilities(
+        input,
+        Capabilities::from_bytes(b"\0symref=HEAD:refs/heads/main")
+            .expect("valid capabilities")
+            .0
+            .iter(),
+    )
+    .await
+    .expect("no failure from valid input");
+    assert_eq!(
+        out,
+        vec![
+            Ref::Symbolic {
+                full_ref_name: "HEAD".into(),
+                target: "refs/heads/main".into(),
+                tag: None,
+                object: oid("73a6868963993a3328e7d8fe94e5a6ac5078a944")
+            },
+            Ref::Direct {
+                full_ref_name: "refs/heads/main".into(),
+                object: oid("73a6868963993a3328e7d8fe94e5a6ac5078a944")
+            },
+        ]
+    );
+    assert_eq!(
+        shallow,
+        vec![
+            oid("21c9b7500cb144b3169a6537961ec2b9e865be81"),
+            oid("8e472f9ccc7d745927426cbb2d9d077de545aa4e")
+        ],
+        "shallow lines follow the refs and are returned separately"
+    );
+}
+
 #[test]
 fn extract_symbolic_references_from_capabilities() -> Result<(), client::Error> {
     let caps = client::Capabilities::from_bytes(
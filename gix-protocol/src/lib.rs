@@ -17,6 +17,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// Obtain a list of bundles to download before fetching.
+    BundleUri,
 }
 pub mod command;
 
@@ -40,6 +42,11 @@ mod fetch_fn;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use fetch_fn::{fetch, FetchConnection};
 
+///
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::bundle_uri;
+
 mod remote_progress;
 pub use remote_progress::RemoteProgress;
 
@@ -50,7 +57,7 @@ compile_error!("Cannot set both 'blocking-client' and 'async-client' features as
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub mod handshake;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
-pub use handshake::function::handshake;
+pub use handshake::function::{handshake, handshake_with_shallow_commits};
 
 ///
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
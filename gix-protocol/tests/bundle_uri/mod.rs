@@ -0,0 +1,118 @@
+use bstr::{BStr, ByteSlice};
+use gix_protocol::bundle_uri::{decode, Bundle, Heuristic, List, Mode};
+
+fn parse(input: &str) -> Result<List, decode::Error> {
+    List::from_lines(input.lines().map(|l| l.as_bytes().as_bstr()))
+}
+
+#[test]
+fn list_with_all_keys() -> crate::Result {
+    let list = parse(
+        "bundle.version=1
+bundle.mode=any
+bundle.heuristic=creationToken
+bundle.unknown=ignored
+bundle.one.uri=https://example.com/one.bundle
+bundle.one.creationToken=42
+bundle.two.URI=two.bundle
+bundle.two.filter=blob:none
+bundle.two.unknown=ignored",
+    )?;
+    assert_eq!(
+        list,
+        List {
+            mode: Mode::Any,
+            heuristic: Some(Heuristic::CreationToken),
+            bundles: vec![
+                Bundle {
+                    id: "one".into(),
+                    uri: "https://example.com/one.bundle".into(),
+                    creation_token: Some(42),
+                    filter: None,
+                },
+                Bundle {
+                    id: "two".into(),
+                    uri: "two.bundle".into(),
+                    creation_token: None,
+                    filter: Some("blob:none".into()),
+                }
+            ]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn minimal_list() -> crate::Result {
+    let list = parse("bundle.version=1\nbundle.heuristic=unknown")?;
+    assert_eq!(list.mode, Mode::All, "the default mode");
+    assert_eq!(list.heuristic, None, "unknown heuristics are ignored");
+    assert!(list.bundles.is_empty());
+    Ok(())
+}
+
+#[test]
+fn decode_errors() {
+    for (input, expected) in [
+        ("bundle.mode=all", "MissingVersion"),
+        ("bundle.version=2", "UnsupportedVersion"),
+        ("bundle.version=1\nbundle.mode=some", "InvalidMode"),
+        ("bundle.version=1\nbundle.a.uri=a\nbundle.a.creationToken=-1", "InvalidCreationToken"),
+        ("bundle.version=1\nbundle.a.creationToken=1", "MissingUri"),
+        ("bundle.version", "MalformedLine"),
+        ("remote.version=1", "MalformedLine"),
+        ("bundle.=1", "MalformedLine"),
+    ] {
+        let actual = match parse(input).expect_err("invalid input") {
+            decode::Error::MalformedLine { .. } => "MalformedLine",
+            decode::Error::MissingVersion => "MissingVersion",
+            decode::Error::UnsupportedVersion { .. } => "UnsupportedVersion",
+            decode::Error::InvalidMode { .. } => "InvalidMode",
+            decode::Error::InvalidCreationToken { .. } => "InvalidCreationToken",
+            decode::Error::MissingUri { .. } => "MissingUri",
+        };
+        assert_eq!(actual, expected, "{input:?}");
+    }
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn invoke_command() -> crate::Result {
+    use gix_transport::client::Transport;
+
+    let mut transport = crate::fetch::transport(
+        Vec::new(),
+        "v2/bundle-uri.response",
+        gix_transport::Protocol::V2,
+        gix_transport::client::git::ConnectMode::Daemon,
+    );
+    let capabilities = transport
+        .handshake(gix_transport::Service::UploadPack, &[])
+        .await?
+        .capabilities;
+    let list = gix_protocol::bundle_uri(
+        &mut transport,
+        &capabilities,
+        vec![("agent", Some("agent".into()))],
+        &mut gix_features::progress::Discard,
+        false,
+    )
+    .await?;
+    assert_eq!(list.mode, Mode::All);
+    assert_eq!(list.heuristic, Some(Heuristic::CreationToken));
+    assert_eq!(
+        list.bundles
+            .iter()
+            .map(|b| (b.uri.as_bstr(), b.creation_token))
+            .collect::<Vec<_>>(),
+        [
+            (BStr::new("https://example.com/base.bundle"), Some(1)),
+            (BStr::new("incremental.bundle"), Some(2))
+        ]
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        "002fgit-upload-pack does/not/matter\0\0version=2\x000017command=bundle-uri\n0010agent=agent\n00010000",
+        "the command doesn't take any arguments, but still sends the delimiter"
+    );
+    Ok(())
+}
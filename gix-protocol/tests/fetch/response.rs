@@ -117,6 +117,48 @@ mod v1 {
             Ok(())
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_with_multi_ack_continue() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-multi-ack.response");
+            let r =
+                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false, true)
+                    .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Common(id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Nak,
+                ],
+                "`continue` is what `multi_ack` servers send instead of `common`"
+            );
+            assert!(!r.has_pack());
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_ready_without_no_done_stops_at_nak() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch.response");
+            let r =
+                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false, true)
+                    .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
+                    Acknowledgement::Common(id("fe17165c392110d1305674c06e4aec35728bfab7")),
+                    Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    Acknowledgement::Ready,
+                    Acknowledgement::Nak,
+                ]
+            );
+            assert!(
+                !r.has_pack(),
+                "without `no-done` the server waits for `done` after `ready`, so we must not read past the `NAK` of a stateful connection"
+            );
+            Ok(())
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch.response");
@@ -175,6 +217,7 @@ mod v1 {
                 "V2 only feature, and we initialize capabilities with V1 for convenience"
             );
             assert!(args.is_empty());
+            assert!(!args.can_use_no_done(), "the server doesn't advertise 'no-done'");
 
             args.shallow(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de"));
             args.deepen(1);
@@ -197,6 +240,35 @@ mod v1 {
             assert_eq!(out.as_slice().as_bstr(), "009ewant aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa thin-pack side-band-64k ofs-delta shallow deepen-since deepen-not deepen-relative multi_ack_detailed filter\n000ddeepen 1\n0014deepen-relative\n0018deepen-since 123456\n0013deepen-not tag\n0035shallow 97c5a932b3940a09683e924ef6a92b31a6f7c6de\n00000032have bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n0009done\n");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn deepen_relative_as_feature_of_first_want() -> crate::Result {
+            let (caps, _) = Capabilities::from_bytes(&b"7814e8a05a59c0cf5fb186661d1551c75d1299b5 HEAD\0multi_ack thin-pack filter side-band side-band-64k ofs-delta shallow deepen-since deepen-not deepen-relative no-progress include-tag multi_ack_detailed symref=HEAD:refs/heads/master object-format=sha1 agent=git/2.28.0"[..])?;
+            let mut features = Command::Fetch.default_features(Protocol::V1, &caps);
+            features.retain(|(n, _)| *n != "deepen-relative");
+            let mut args = fetch::Arguments::new(Protocol::V1, features, false);
+
+            args.shallow(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de"));
+            args.deepen(1);
+            args.add_feature("deepen-relative");
+            args.deepen_since(123456);
+            args.deepen_not("tag".into());
+            args.want(id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+            args.have(id("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"));
+
+            let mut out = Vec::new();
+            let mut transport = transport(
+                &mut out,
+                "v1/clone.response",
+                Protocol::V2,
+                gix_transport::client::git::ConnectMode::Daemon,
+            );
+
+            let _response = args.send(&mut transport, true).await?;
+            drop(_response);
+            assert_eq!(out.as_slice().as_bstr(), "009ewant aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa thin-pack side-band-64k ofs-delta shallow deepen-since deepen-not multi_ack_detailed filter deepen-relative\n000ddeepen 1\n0018deepen-since 123456\n0013deepen-not tag\n0035shallow 97c5a932b3940a09683e924ef6a92b31a6f7c6de\n00000032have bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n0009done\n");
+            Ok(())
+        }
     }
 }
 mod v2 {
@@ -209,7 +281,7 @@ mod v2 {
         use gix_packetline::read::ProgressAction;
         use gix_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, PackfileUri, ShallowUpdate},
         };
         use gix_transport::Protocol;
 
@@ -237,6 +309,28 @@ mod v2 {
             Ok(())
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn clone_with_packfile_uris() -> crate::Result {
+            let mut provider = mock_reader("v2/clone-packfile-uris.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
+            assert_eq!(
+                r.packfile_uris(),
+                &[PackfileUri {
+                    pack_hash: id("9ac6ba06ef05b4d8fa9bd1bbb8c4ae3386fbd9ad"),
+                    uri: "https://example.com/pack-9ac6ba06ef05b4d8fa9bd1bbb8c4ae3386fbd9ad.pack".into()
+                }]
+            );
+            assert!(r.has_pack(), "the remaining objects are still sent as pack");
+            let mut pack = Vec::new();
+            reader.read_to_end(&mut pack).await?;
+            assert!(
+                pack.ends_with(b"PACK"),
+                "we read the pack with the sideband channel intact"
+            );
+            Ok(())
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-1.response");
@@ -402,6 +496,7 @@ mod v2 {
                 "V2 only feature, and we initialize capabilities with V1 for convenience"
             );
             assert!(args.is_empty());
+            assert!(args.can_use_no_done(), "V2 always sends the pack right after 'ready'");
 
             args.shallow(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de"));
             args.deepen(1);
@@ -230,6 +230,9 @@ pub mod create_or_update {
     use crate::FullNameRef;
 }
 
+///
+pub mod expire;
+
 mod error {
     /// The error returned by [`crate::file::Store::reflog_iter()`].
     #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,114 @@
+use std::io::Write;
+
+use gix_object::bstr::ByteSlice;
+
+use crate::{
+    store_impl::{file, file::log},
+    FullNameRef,
+};
+
+/// Options for use in [`file::Store::reflog_expire()`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// Remove all entries that were created before this time, or keep them all if `None`.
+    ///
+    /// This is typically configured with `gc.reflogExpire`.
+    pub expire: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// Remove all entries that were created before this time if their previous or new object isn't reachable from the tip
+    /// of the reference, or keep them all if `None`.
+    ///
+    /// This is typically configured with `gc.reflogExpireUnreachable`.
+    pub expire_unreachable: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// How to deal with locks that are held by others when locking the reference and its reflog.
+    pub lock_fail_mode: gix_lock::acquire::Fail,
+}
+
+/// The outcome of [`file::Store::reflog_expire()`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of entries that were removed.
+    pub removed: usize,
+    /// The amount of entries that remain in the reflog.
+    pub retained: usize,
+}
+
+/// The error returned by [`file::Store::reflog_expire()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not lock the reference or its reflog")]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error("Could not read the reflog")]
+    Read(#[source] std::io::Error),
+    #[error("Could not write the reflog")]
+    Write(#[source] std::io::Error),
+    #[error("Could not commit the rewritten reflog")]
+    Commit(#[from] gix_lock::commit::Error<gix_lock::File>),
+}
+
+impl file::Store {
+    /// Remove entries from the reflog of the reference `name` according to `options`, similar to `git reflog expire`.
+    /// `is_reachable(id)` is called for the previous and new objects of entries that are older than
+    /// [`expire_unreachable`](Options::expire_unreachable) to determine if they are reachable from the tip of the reference,
+    /// and it's up to the caller to determine what the tip is.
+    ///
+    /// The reference itself is locked while its reflog is rewritten, so that concurrent updates can't append to the log.
+    /// Entries which can't be parsed are retained, and nothing is written if no entry was removed.
+    /// If there is no reflog, nothing happens.
+    pub fn reflog_expire(
+        &self,
+        name: &FullNameRef,
+        options: Options,
+        is_reachable: &mut dyn FnMut(&gix_hash::oid) -> bool,
+    ) -> Result<Outcome, Error> {
+        let reflog_path = self.reflog_path(name);
+        if !reflog_path.is_file() {
+            return Ok(Outcome::default());
+        }
+        let (base, relative_path) = self.reference_path_with_base(name);
+        let _ref_lock = gix_lock::Marker::acquire_to_hold_resource(
+            base.join(relative_path.as_ref()),
+            options.lock_fail_mode,
+            Some(base.clone().into_owned()),
+        )?;
+        let buf = match std::fs::read(&reflog_path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Outcome::default()),
+            Err(err) => return Err(Error::Read(err)),
+        };
+
+        let mut out = Outcome::default();
+        let mut retained_lines = Vec::new();
+        for line in buf.lines() {
+            let keep = log::LineRef::from_bytes(line).map_or(true, |entry| {
+                let time = entry.signature.time.seconds;
+                if options.expire.map_or(false, |expire| time < expire) {
+                    return false;
+                }
+                if options.expire_unreachable.map_or(false, |expire| time < expire) {
+                    let mut reachable = |id: gix_hash::ObjectId| id.is_null() || is_reachable(id.as_ref());
+                    return reachable(entry.previous_oid()) && reachable(entry.new_oid());
+                }
+                true
+            });
+            if keep {
+                out.retained += 1;
+                retained_lines.push(line);
+            } else {
+                out.removed += 1;
+            }
+        }
+        if out.removed == 0 {
+            return Ok(out);
+        }
+
+        let mut lock = gix_lock::File::acquire_to_update_resource(&reflog_path, options.lock_fail_mode, None)?;
+        for line in retained_lines {
+            lock.write_all(line)
+                .and_then(|_| lock.write_all(b"\n"))
+                .map_err(Error::Write)?;
+        }
+        lock.commit()?;
+        Ok(out)
+    }
+}
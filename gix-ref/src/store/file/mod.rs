@@ -120,5 +120,8 @@ pub mod transaction;
 ///
 pub mod packed;
 
+///
+pub mod pack_refs;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
@@ -0,0 +1,138 @@
+use gix_object::bstr::ByteSlice;
+
+use crate::{
+    file,
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+/// Options for use in [`file::Store::pack_refs()`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// If `true`, pack all loose references below `refs/`, which is like `git pack-refs --all`.
+    /// Otherwise, only tags and references that are already present in `packed-refs` are packed.
+    pub all: bool,
+    /// If `true`, remove the loose references that were packed, along with their empty parent directories.
+    pub prune: bool,
+    /// How to deal with locks that are held by others when obtaining locks on `packed-refs` and loose references.
+    pub lock_fail_mode: gix_lock::acquire::Fail,
+}
+
+/// The outcome of [`file::Store::pack_refs()`].
+#[derive(Default, Debug, Clone)]
+pub struct Outcome {
+    /// The names of all loose references that were written into `packed-refs`, sorted by name.
+    pub packed: Vec<FullName>,
+}
+
+/// The error returned by [`file::Store::pack_refs()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not traverse loose references")]
+    Traverse(#[from] std::io::Error),
+    #[error(transparent)]
+    Iter(#[from] file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    PackedOpen(#[from] crate::packed::buffer::open::Error),
+    #[error(transparent)]
+    PackedFind(#[from] crate::packed::find::Error),
+    #[error(transparent)]
+    Prepare(#[from] file::transaction::prepare::Error),
+    #[error(transparent)]
+    Commit(#[from] file::transaction::commit::Error),
+}
+
+impl file::Store {
+    /// Write loose references into the `packed-refs` file, similar to `git pack-refs`, and configured by `options`.
+    /// Use `objects` to peel annotated tags so their peeled value can be stored alongside them.
+    ///
+    /// Only references below `refs/` that point to an object are packed, while symbolic references and worktree-private
+    /// references stay loose.
+    /// All references are packed as seen from the root of the store, ignoring the [namespace](file::Store::namespace).
+    ///
+    /// The transaction holds the lock on `packed-refs` while it's written, and loose references are only removed once
+    /// `packed-refs` was updated successfully.
+    pub fn pack_refs<'a>(
+        &self,
+        objects: impl gix_object::Find + 'a,
+        Options {
+            all,
+            prune,
+            lock_fail_mode,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let mut store = self.clone();
+        store.namespace = None;
+        let packed = store.cached_packed_buffer()?;
+
+        let mut names = Vec::new();
+        for reference in store.loose_iter()? {
+            let reference = reference?;
+            let Target::Peeled(id) = reference.target else {
+                continue;
+            };
+            let name = reference.name.as_ref();
+            if !name.as_bstr().starts_with(b"refs/") || name.category().map_or(false, |c| c.is_worktree_private()) {
+                continue;
+            }
+            let is_packable = all
+                || name.category() == Some(crate::Category::Tag)
+                || packed
+                    .as_ref()
+                    .map(|packed| packed.try_find(name).map(|r| r.is_some()))
+                    .transpose()?
+                    .unwrap_or(false);
+            if !is_packable {
+                continue;
+            }
+            names.push((reference.name, id));
+        }
+        if names.is_empty() {
+            return Ok(Outcome::default());
+        }
+
+        let edits = names.iter().map(|(name, id)| RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: Default::default(),
+                },
+                expected: PreviousValue::MustExistAndMatch(Target::Peeled(*id)),
+                new: Target::Peeled(*id),
+            },
+            name: name.clone(),
+            deref: false,
+        });
+        let objects: Box<dyn gix_object::Find + 'a> = Box::new(objects);
+        store
+            .transaction()
+            .packed_refs(if prune {
+                PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(objects)
+            } else {
+                PackedRefs::DeletionsAndNonSymbolicUpdates(objects)
+            })
+            .prepare(edits, lock_fail_mode, lock_fail_mode)?
+            .commit(None)?;
+
+        if prune {
+            let refs_dir = store.common_dir_resolved().join("refs");
+            for (name, _) in &names {
+                // Like git, keep top-level directories like `refs/heads`.
+                let Some(top_level) = name.as_bstr()["refs/".len()..].split_str("/").next() else {
+                    continue;
+                };
+                let boundary = refs_dir.join(gix_path::from_bstr(top_level.as_bstr()));
+                let path = store.reference_path(name.as_ref());
+                if let Some(parent) = path.parent().filter(|parent| parent.starts_with(&boundary)) {
+                    gix_tempfile::remove_dir::empty_upward_until_boundary(parent, &boundary).ok();
+                }
+            }
+        }
+        Ok(Outcome {
+            packed: names.into_iter().map(|(name, _)| name).collect(),
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use gix_ref::file::pack_refs::Options;
+
+use crate::file::{store_writable, EmptyCommit};
+
+fn loose_names(store: &crate::file::Store) -> crate::Result<Vec<String>> {
+    Ok(store
+        .loose_iter()?
+        .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+        .collect::<Result<_, _>>()?)
+}
+
+fn packed_names(store: &crate::file::Store) -> crate::Result<Vec<String>> {
+    let packed = store.open_packed_buffer()?.expect("packed refs exist");
+    Ok(packed
+        .iter()?
+        .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn tags_and_already_packed_refs_by_default() -> crate::Result {
+    let (_tmp, store) = store_writable("make_repo_for_pack_refs.sh")?;
+    let main_id = store.find("main")?.target.into_id();
+    let out = store.pack_refs(
+        EmptyCommit,
+        Options {
+            prune: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.packed.iter().map(|n| n.as_bstr().to_string()).collect::<Vec<_>>(),
+        ["refs/heads/main", "refs/tags/loose-tag"]
+    );
+    assert_eq!(
+        loose_names(&store)?,
+        [
+            "refs/bisect/bad",
+            "refs/heads/loose-only",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main"
+        ]
+    );
+    assert_eq!(
+        store.find("main")?.target.into_id(),
+        main_id,
+        "the packed ref was updated to the value of the loose one"
+    );
+    assert!(
+        store.git_dir().join("refs").join("tags").is_dir(),
+        "top-level directories are kept even if empty"
+    );
+    Ok(())
+}
+
+#[test]
+fn all_refs_without_pruning() -> crate::Result {
+    let (_tmp, store) = store_writable("make_repo_for_pack_refs.sh")?;
+    let loose_before = loose_names(&store)?;
+    let out = store.pack_refs(
+        EmptyCommit,
+        Options {
+            all: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.packed.iter().map(|n| n.as_bstr().to_string()).collect::<Vec<_>>(),
+        [
+            "refs/heads/loose-only",
+            "refs/heads/main",
+            "refs/remotes/origin/main",
+            "refs/tags/loose-tag"
+        ],
+        "symbolic and worktree-private refs are never packed"
+    );
+    assert_eq!(loose_names(&store)?, loose_before, "nothing was removed");
+    assert_eq!(
+        packed_names(&store)?,
+        [
+            "refs/heads/feature/a",
+            "refs/heads/feature/b",
+            "refs/heads/loose-only",
+            "refs/heads/main",
+            "refs/remotes/origin/main",
+            "refs/tags/annotated",
+            "refs/tags/lightweight",
+            "refs/tags/loose-tag"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn all_refs_with_pruning() -> crate::Result {
+    let (_tmp, store) = store_writable("make_repo_for_pack_refs.sh")?;
+    let out = store.pack_refs(
+        EmptyCommit,
+        Options {
+            all: true,
+            prune: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.packed.len(), 4);
+    assert_eq!(loose_names(&store)?, ["refs/bisect/bad", "refs/remotes/origin/HEAD"]);
+    assert_eq!(
+        store
+            .find("origin/HEAD")?
+            .target
+            .try_name()
+            .expect("symbolic")
+            .as_bstr(),
+        "refs/remotes/origin/main"
+    );
+    assert!(
+        store.try_find("origin/main")?.is_some(),
+        "the target of the symbolic ref is now packed"
+    );
+
+    let out = store.pack_refs(
+        EmptyCommit,
+        Options {
+            all: true,
+            prune: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(out.packed.is_empty(), "nothing left to pack");
+    Ok(())
+}
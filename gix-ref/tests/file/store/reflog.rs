@@ -66,3 +66,122 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod expire {
+    use gix_ref::file::log::expire::{Options, Outcome};
+
+    use crate::{file::store_writable, hex_to_id};
+
+    const LOG: &str = "\
+0000000000000000000000000000000000000000 1111111111111111111111111111111111111111 committer <committer@example.com> 100 +0000	first
+1111111111111111111111111111111111111111 2222222222222222222222222222222222222222 committer <committer@example.com> 200 +0000	second
+this line is unparseable
+2222222222222222222222222222222222222222 3333333333333333333333333333333333333333 committer <committer@example.com> 300 +0000	third
+3333333333333333333333333333333333333333 4444444444444444444444444444444444444444 committer <committer@example.com> 400 +0000	fourth
+";
+
+    fn store_with_log() -> crate::Result<(gix_testtools::tempfile::TempDir, crate::file::Store, std::path::PathBuf)> {
+        let (tmp, store) = store_writable("make_repo_for_pack_refs.sh")?;
+        let path = store.git_dir().join("logs").join("refs").join("heads").join("main");
+        std::fs::write(&path, LOG)?;
+        Ok((tmp, store, path))
+    }
+
+    fn messages(path: &std::path::Path) -> crate::Result<Vec<String>> {
+        Ok(std::fs::read_to_string(path)?
+            .lines()
+            .map(|line| line.rsplit('\t').next().unwrap_or(line).to_owned())
+            .collect())
+    }
+
+    #[test]
+    fn by_age() -> crate::Result {
+        let (_tmp, store, path) = store_with_log()?;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire: Some(250),
+                ..Default::default()
+            },
+            &mut |_| unreachable!("only called when expiring unreachable entries"),
+        )?;
+        assert_eq!(
+            out,
+            Outcome {
+                removed: 2,
+                retained: 3
+            }
+        );
+        assert_eq!(
+            messages(&path)?,
+            ["this line is unparseable", "third", "fourth"],
+            "unparseable lines are kept"
+        );
+        assert!(
+            !store.git_dir().join("refs/heads/main.lock").exists(),
+            "the reference lock is released"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn by_reachability() -> crate::Result {
+        let (_tmp, store, path) = store_with_log()?;
+        let unreachable = hex_to_id("2222222222222222222222222222222222222222");
+        let mut calls = 0;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire_unreachable: Some(350),
+                ..Default::default()
+            },
+            &mut |id| {
+                calls += 1;
+                id != unreachable
+            },
+        )?;
+        assert_eq!(
+            out,
+            Outcome {
+                removed: 2,
+                retained: 3
+            }
+        );
+        assert_eq!(calls, 4, "null ids are always reachable, and evaluation stops at the first unreachable id");
+        assert_eq!(messages(&path)?, ["first", "this line is unparseable", "fourth"]);
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_expire_or_no_log() -> crate::Result {
+        let (_tmp, store, path) = store_with_log()?;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire: Some(50),
+                expire_unreachable: None,
+                ..Default::default()
+            },
+            &mut |_| true,
+        )?;
+        assert_eq!(
+            out,
+            Outcome {
+                removed: 0,
+                retained: 5
+            }
+        );
+        assert_eq!(std::fs::read_to_string(&path)?, LOG, "the log is untouched");
+
+        let out = store.reflog_expire(
+            "refs/heads/does-not-exist".try_into()?,
+            Options {
+                expire: Some(50),
+                ..Default::default()
+            },
+            &mut |_| true,
+        )?;
+        assert_eq!(out, Outcome::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git branch feature/a
+git branch feature/b
+
+git tag lightweight
+git tag -m "annotated" annotated
+
+git pack-refs --all --prune
+git commit -q --allow-empty -m c2
+git tag loose-tag
+
+git branch loose-only
+git update-ref refs/remotes/origin/main HEAD
+git symbolic-ref refs/remotes/origin/HEAD refs/remotes/origin/main
+git update-ref refs/bisect/bad HEAD
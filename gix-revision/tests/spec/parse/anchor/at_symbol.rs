@@ -47,6 +47,30 @@ fn reflog_by_date_for_current_branch() {
     assert_eq!(rec.calls, 1);
 }
 
+#[test]
+fn reflog_by_date_with_explicit_offset_for_current_branch() {
+    let rec = parse("@{1979-02-26 18:30:00 +0030}");
+
+    assert!(rec.kind.is_none());
+    assert_eq!(rec.find_ref[0], None,);
+    assert_eq!(rec.current_branch_reflog_entry[0], Some("288900000 +0030".to_string()));
+    assert_eq!(rec.calls, 1);
+}
+
+#[test]
+fn reflog_by_relative_date_for_current_branch() {
+    for spec in [
+        "@{2.weeks.ago}",
+        "@{yesterday}",
+        "@{last friday}",
+        "@{1 month 3 days ago}",
+    ] {
+        let rec = parse(spec);
+        assert!(rec.current_branch_reflog_entry[0].is_some(), "{spec}");
+        assert_eq!(rec.calls, 1);
+    }
+}
+
 #[test]
 fn reflog_by_date_with_date_parse_failure() {
     let err = try_parse("@{foo}").unwrap_err();
@@ -48,6 +48,18 @@ fn refname_head() {
     assert_eq!(rec.get_ref(0), "HEAD");
 }
 
+#[test]
+fn refname_fetch_head() {
+    let rec = parse("FETCH_HEAD");
+    assert!(rec.kind.is_none());
+    assert_eq!(rec.get_ref(0), "FETCH_HEAD", "it's resolved like any other ref");
+
+    let rec = parse("HEAD..FETCH_HEAD");
+    assert_eq!(rec.kind, Some(gix_revision::spec::Kind::RangeBetween));
+    assert_eq!(rec.get_ref(0), "HEAD");
+    assert_eq!(rec.get_ref(1), "FETCH_HEAD");
+}
+
 #[test]
 fn refname_tag() {
     let spec = "v1.2.3.4-beta.1";
@@ -9,7 +9,8 @@ pub(crate) mod function {
     /// A general purpose connector connecting to a repository identified by the given `url`.
     ///
     /// This includes connections to
-    /// [git daemons][crate::client::git::connect()] only at the moment.
+    /// [git daemons][crate::client::git::connect()] and `http(s)` servers if the `async-http-client-reqwest` feature is enabled,
+    /// which requires a `tokio` runtime.
     ///
     /// Use `options` to further control specifics of the transport resulting from the connection.
     pub async fn connect<Url, E>(
@@ -42,6 +43,12 @@ pub(crate) mod function {
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
+            #[cfg(not(feature = "async-http-client-reqwest"))]
+            gix_url::Scheme::Https | gix_url::Scheme::Http => return Err(Error::CompiledWithoutHttp(url.scheme)),
+            #[cfg(feature = "async-http-client-reqwest")]
+            gix_url::Scheme::Https | gix_url::Scheme::Http => {
+                Box::new(crate::client::http::connect(url, options.version, options.trace))
+            }
             scheme => return Err(Error::UnsupportedScheme(scheme)),
         })
     }
@@ -0,0 +1,48 @@
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+use crate::client::http::options::{AuthenticateFn, FollowRedirects};
+
+/// An implementation for HTTP requests via the async version of `reqwest`.
+///
+/// Each request is performed by a task spawned onto the current `tokio` runtime, which is also required by `reqwest` itself.
+///
+/// The following [options][crate::client::http::Options] are supported:
+///
+/// * `extra_headers`, `follow_redirects`, `user_agent` and `connect_timeout`.
+/// * `proxy` and `no_proxy`, with credentials for the proxy either in its URL or provided by `proxy_authenticate`, which
+///   only works with basic authentication.
+/// * `backend` with [`Options`].
+///
+/// All others are ignored, particularly the ones related to TLS which is configured by enabling one of the TLS features
+/// of `reqwest`.
+pub struct Remote {
+    /// The client to perform requests with, created from `config` when it's first needed.
+    client: Option<reqwest::Client>,
+    /// If `true`, the client follows redirects in the next request.
+    allow_redirects: Arc<AtomicBool>,
+    /// How to handle redirects, which is decided when making the first request.
+    follow: Option<FollowRedirects>,
+    /// The base url after following redirects, to be used in all subsequent requests.
+    redirected_base_url: Arc<Mutex<Option<String>>>,
+    /// A way to store or erase the proxy credentials the `client` was created with, once we know if they work.
+    proxy_auth: Option<(gix_credentials::helper::NextAction, Arc<Mutex<AuthenticateFn>>)>,
+    /// A mechanism for configuring the remote.
+    config: crate::client::http::Options,
+}
+
+/// A function to configure a single request prior to sending it, support most complex configuration beyond what's possible with
+/// basic `git` http configuration.
+pub type ConfigureRequestFn = dyn FnMut(&mut reqwest::Request) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+    + Send
+    + Sync
+    + 'static;
+
+/// Options to configure the reqwest HTTP handler.
+#[derive(Default)]
+pub struct Options {
+    /// A function to configure the request that is about to be made.
+    pub configure_request: Option<Box<ConfigureRequestFn>>,
+}
+
+///
+pub mod remote;
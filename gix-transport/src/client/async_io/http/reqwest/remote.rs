@@ -0,0 +1,490 @@
+use std::{
+    any::Any,
+    convert::TryFrom,
+    io,
+    pin::Pin,
+    str::FromStr,
+    sync::{atomic, Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use async_trait::async_trait;
+use bytes::{Buf, Bytes};
+use futures_channel::{mpsc, oneshot};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use futures_lite::{ready, Future, StreamExt};
+
+use crate::client::http::{
+    self,
+    options::{FollowRedirects, ProxyAuthMethod},
+    redirect,
+    reqwest::Remote,
+    PostBodyDataKind,
+};
+
+/// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Request configuration failed")]
+    ConfigureRequest(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Redirect(#[from] redirect::Error),
+    #[error("The proxy authentication method {method:?} is not supported as only basic authentication is available")]
+    UnsupportedProxyAuthMethod { method: ProxyAuthMethod },
+    #[error(transparent)]
+    Authenticate(#[from] Box<gix_credentials::protocol::Error>),
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Reqwest(err) => {
+                err.is_timeout() || err.is_connect() || err.status().map_or(false, |status| status.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Remote {
+    fn default() -> Self {
+        Remote {
+            client: None,
+            allow_redirects: Arc::new(atomic::AtomicBool::new(false)),
+            follow: None,
+            redirected_base_url: Arc::new(Mutex::new(None)),
+            proxy_auth: None,
+            config: http::Options::default(),
+        }
+    }
+}
+
+/// utilities
+impl Remote {
+    fn client(&mut self) -> Result<reqwest::Client, Error> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(
+                self.config
+                    .connect_timeout
+                    .unwrap_or(std::time::Duration::from_secs(20)),
+            )
+            .http1_title_case_headers()
+            .redirect(reqwest::redirect::Policy::custom({
+                let allow_redirects = self.allow_redirects.clone();
+                move |attempt| {
+                    if allow_redirects.load(atomic::Ordering::Relaxed) {
+                        let curr_url = attempt.url();
+                        let prev_urls = attempt.previous();
+
+                        match prev_urls.first() {
+                            Some(prev_url) if prev_url.host_str() != curr_url.host_str() => {
+                                // git does not want to be redirected to a different host.
+                                attempt.stop()
+                            }
+                            _ => {
+                                // emulate default git behaviour which relies on curl default behaviour apparently.
+                                const CURL_DEFAULT_REDIRS: usize = 50;
+                                if prev_urls.len() >= CURL_DEFAULT_REDIRS {
+                                    attempt.error("too many redirects")
+                                } else {
+                                    attempt.follow()
+                                }
+                            }
+                        }
+                    } else {
+                        attempt.stop()
+                    }
+                }
+            }));
+        if let Some(user_agent) = &self.config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        builder = builder.default_headers(header_map(&self.config.extra_headers));
+
+        self.proxy_auth = None;
+        match self.config.proxy.as_deref().map(str::trim) {
+            Some("") => builder = builder.no_proxy(),
+            Some(proxy) => {
+                let mut proxy = reqwest::Proxy::all(proxy)?;
+                if let Some(no_proxy) = &self.config.no_proxy {
+                    proxy = proxy.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+                }
+                if let Some((obtain_creds_action, authenticate)) = &self.config.proxy_authenticate {
+                    match self.config.proxy_auth_method {
+                        ProxyAuthMethod::AnyAuth | ProxyAuthMethod::Basic => {}
+                        method => return Err(Error::UnsupportedProxyAuthMethod { method }),
+                    }
+                    let creds = authenticate.lock().expect("no panics in other threads")(obtain_creds_action.clone())
+                        .map_err(Box::new)?
+                        .expect("action to fetch credentials");
+                    proxy = proxy.basic_auth(&creds.identity.username, &creds.identity.password);
+                    self.proxy_auth = Some((creds.next, authenticate.clone()));
+                }
+                builder = builder.proxy(proxy);
+            }
+            None => {}
+        }
+
+        let client = builder.build()?;
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+
+    fn make_request(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        upload_body_kind: Option<PostBodyDataKind>,
+    ) -> Result<http::PostResponse<Headers, Body, PostBody>, http::Error> {
+        let client = self
+            .client()
+            .map_err(|err| http::Error::InitHttpClient { source: Box::new(err) })?;
+        let effective_url = redirect::swap_tails(
+            self.redirected_base_url
+                .lock()
+                .expect("no panics in other threads")
+                .as_deref(),
+            base_url,
+            url.to_owned(),
+        );
+        let req_builder = if upload_body_kind.is_some() {
+            client.post(&effective_url)
+        } else {
+            client.get(&effective_url)
+        }
+        .headers(header_map(headers));
+
+        let follow = self.follow.get_or_insert(self.config.follow_redirects);
+        self.allow_redirects.store(
+            matches!(follow, FollowRedirects::Initial | FollowRedirects::All),
+            atomic::Ordering::Relaxed,
+        );
+        if *follow == FollowRedirects::Initial {
+            *follow = FollowRedirects::None;
+        }
+
+        let (post_body_tx, post_body_rx) = mpsc::channel(0);
+        let (headers_tx, headers_rx) = oneshot::channel();
+        let (mut response_body_tx, response_body_rx) = mpsc::channel(0);
+        let request = Request {
+            client,
+            req_builder,
+            upload_body_kind,
+            upload_body: post_body_rx,
+            backend: self.config.backend.clone(),
+        };
+        let redirected_base_url = self.redirected_base_url.clone();
+        let base_url = base_url.to_owned();
+        let url = url.to_owned();
+        let proxy_auth = self.proxy_auth.take();
+        tokio::spawn(async move {
+            let res = request.execute().await;
+            if let Some((action, authenticate)) = proxy_auth {
+                let is_success =
+                    matches!(&res, Ok(res) if res.status() != reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED);
+                authenticate.lock().expect("no panics in other threads")(if is_success {
+                    action.store()
+                } else {
+                    action.erase()
+                })
+                .ok();
+            }
+
+            let mut res = match res.and_then(|res| res.error_for_status().map_err(Into::into)) {
+                Ok(res) => res,
+                Err(err) => {
+                    headers_tx.send(Err(into_io_error(err))).ok();
+                    return;
+                }
+            };
+
+            let actual_url = res.url().as_str();
+            if actual_url != effective_url.as_str() {
+                match redirect::base_url(actual_url, &base_url, url) {
+                    Ok(base_url) => {
+                        *redirected_base_url.lock().expect("no panics in other threads") = Some(base_url);
+                    }
+                    Err(err) => {
+                        headers_tx.send(Err(into_io_error(err.into()))).ok();
+                        return;
+                    }
+                }
+            }
+
+            let mut headers = Vec::new();
+            for (name, value) in res.headers() {
+                headers.extend_from_slice(name.as_str().as_bytes());
+                headers.push(b':');
+                headers.extend_from_slice(value.as_bytes());
+                headers.push(b'\n');
+            }
+            // The receiver might have decided not to read headers at all, which is fine with us.
+            headers_tx.send(Ok(headers)).ok();
+
+            // Reading the response body is streaming and may fail for many reasons. If so, we send the error over the response
+            // body channel and that's all we can do.
+            loop {
+                let chunk = match res.chunk().await {
+                    Ok(Some(chunk)) => Ok(chunk),
+                    Ok(None) => break,
+                    Err(err) => Err(io::Error::new(io::ErrorKind::Other, err)),
+                };
+                let is_err = chunk.is_err();
+                if send(&mut response_body_tx, chunk).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+
+        Ok(http::PostResponse {
+            post_body: PostBody {
+                channel: Some(post_body_tx),
+            },
+            headers: Headers {
+                channel: Some(headers_rx),
+                buf: Vec::new(),
+                pos: 0,
+            },
+            body: Body {
+                channel: response_body_rx,
+                chunk: Bytes::new(),
+            },
+        })
+    }
+}
+
+/// Turn `headers` with lines like `name: value` into a header map, skipping the ones that are invalid.
+fn header_map(headers: impl IntoIterator<Item = impl AsRef<str>>) -> reqwest::header::HeaderMap {
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for header_line in headers {
+        let header_line = header_line.as_ref();
+        let Some((header_name, value)) = header_line.split_once(':') else {
+            continue;
+        };
+        if let Some((key, val)) = reqwest::header::HeaderName::from_str(header_name)
+            .ok()
+            .zip(reqwest::header::HeaderValue::try_from(value.trim()).ok())
+        {
+            header_map.append(key, val);
+        }
+    }
+    header_map
+}
+
+fn into_io_error(err: Error) -> io::Error {
+    match err {
+        Error::Reqwest(err) => match err.status() {
+            Some(status) => {
+                let kind = if status == reqwest::StatusCode::UNAUTHORIZED {
+                    io::ErrorKind::PermissionDenied
+                } else if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+                    io::ErrorKind::NotFound
+                } else if status.is_server_error() {
+                    io::ErrorKind::ConnectionAborted
+                } else {
+                    io::ErrorKind::Other
+                };
+                io::Error::new(kind, format!("Received HTTP status {}", status.as_str()))
+            }
+            None => io::Error::new(io::ErrorKind::Other, err),
+        },
+        err => io::Error::new(io::ErrorKind::Other, err),
+    }
+}
+
+/// Send `item` through `channel` once it has capacity.
+async fn send<T>(channel: &mut mpsc::Sender<T>, item: T) -> Result<(), mpsc::SendError> {
+    futures_lite::future::poll_fn(|cx| channel.poll_ready(cx)).await?;
+    channel.start_send(item)
+}
+
+/// A request that is ready to be sent once its body is known.
+struct Request {
+    client: reqwest::Client,
+    req_builder: reqwest::RequestBuilder,
+    upload_body_kind: Option<PostBodyDataKind>,
+    upload_body: mpsc::Receiver<Vec<u8>>,
+    backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
+}
+
+impl Request {
+    async fn execute(self) -> Result<reqwest::Response, Error> {
+        let Request {
+            client,
+            req_builder,
+            upload_body_kind,
+            upload_body,
+            backend,
+        } = self;
+        let req_builder = match upload_body_kind {
+            Some(PostBodyDataKind::BoundedAndFitsIntoMemory) => {
+                let buf = upload_body
+                    .fold(Vec::<u8>::with_capacity(512), |mut buf, data| {
+                        buf.extend_from_slice(&data);
+                        buf
+                    })
+                    .await;
+                req_builder.body(buf)
+            }
+            Some(PostBodyDataKind::Unbounded) => {
+                req_builder.body(reqwest::Body::wrap_stream(upload_body.map(Ok::<_, io::Error>)))
+            }
+            None => req_builder,
+        };
+        let mut req = req_builder.build()?;
+        if let Some(ref mut request_options) = backend.as_ref().and_then(|backend| backend.lock().ok()) {
+            if let Some(options) = request_options.downcast_mut::<super::Options>() {
+                if let Some(configure_request) = &mut options.configure_request {
+                    configure_request(&mut req)?;
+                }
+            }
+        }
+        Ok(client.execute(req).await?)
+    }
+}
+
+/// The headers of a response, one `name:value` pair per line, which are available once the response was received.
+pub struct Headers {
+    channel: Option<oneshot::Receiver<io::Result<Vec<u8>>>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl AsyncBufRead for Headers {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if let Some(channel) = this.channel.as_mut() {
+            let headers = ready!(Pin::new(channel).poll(cx));
+            this.channel = None;
+            match headers {
+                Ok(Ok(headers)) => this.buf = headers,
+                Ok(Err(err)) => return Poll::Ready(Err(err)),
+                Err(oneshot::Canceled) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "The request was aborted before receiving a response",
+                    )))
+                }
+            }
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.pos = (this.pos + amt).min(this.buf.len());
+    }
+}
+
+impl AsyncRead for Headers {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let data = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let amt = data.len().min(buf.len());
+        buf[..amt].copy_from_slice(&data[..amt]);
+        self.consume(amt);
+        Poll::Ready(Ok(amt))
+    }
+}
+
+/// The body of a response, which is streamed as it's received.
+pub struct Body {
+    channel: mpsc::Receiver<io::Result<Bytes>>,
+    chunk: Bytes,
+}
+
+impl AsyncBufRead for Body {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.chunk.is_empty() {
+            match ready!(this.channel.poll_next(cx)) {
+                Some(Ok(chunk)) => this.chunk = chunk,
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(&this.chunk))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().chunk.advance(amt);
+    }
+}
+
+impl AsyncRead for Body {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let data = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let amt = data.len().min(buf.len());
+        buf[..amt].copy_from_slice(&data[..amt]);
+        self.consume(amt);
+        Poll::Ready(Ok(amt))
+    }
+}
+
+/// The body to post, which is sent once it's complete if its size is bounded, or streamed otherwise.
+pub struct PostBody {
+    channel: Option<mpsc::Sender<Vec<u8>>>,
+}
+
+impl AsyncWrite for PostBody {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let Some(channel) = self.get_mut().channel.as_mut() else {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        };
+        // If the request isn't interested in the body anymore, it failed or the server responded early,
+        // which is more interesting than the rest of the body and is communicated as part of the response.
+        if ready!(channel.poll_ready(cx)).is_ok() {
+            channel.start_send(buf.to_vec()).ok();
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().channel = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[async_trait(?Send)]
+impl http::Http for Remote {
+    type Headers = Headers;
+    type ResponseBody = Body;
+    type PostBody = PostBody;
+
+    async fn get(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        self.make_request(url, base_url, headers, None).map(Into::into)
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        post_body_kind: PostBodyDataKind,
+    ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        self.make_request(url, base_url, headers, Some(post_body_kind))
+    }
+
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(config) = config.downcast_ref::<http::Options>() {
+            self.config = config.clone();
+            self.client = None;
+        }
+        Ok(())
+    }
+}
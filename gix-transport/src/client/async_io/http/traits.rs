@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+
+use crate::client::http::{Error, GetResponse, PostBodyDataKind, PostResponse};
+
+/// A trait to abstract the HTTP operations needed to power all git interactions: read via GET and write via POST.
+/// Note that 401 must be turned into `std::io::Error(PermissionDenied)`, 404 and 410 into `std::io::Error(NotFound)`
+/// to indicate missing files, and other non-success http statuses must be transformed
+/// into `std::io::Error(Other)` when reading the headers.
+#[allow(clippy::type_complexity)]
+#[async_trait(?Send)]
+pub trait Http {
+    /// A type providing headers line by line.
+    type Headers: futures_io::AsyncBufRead + Unpin;
+    /// A type providing the response.
+    type ResponseBody: futures_io::AsyncBufRead + Unpin;
+    /// A type allowing to write the content to post.
+    type PostBody: futures_io::AsyncWrite + Unpin;
+
+    /// Initiate a `GET` request to `url` provided the given `headers`, where `base_url` is so that `base_url + tail == url`.
+    ///
+    /// The `base_url` helps to validate redirects and to swap it with the effective base after a redirect.
+    ///
+    /// The `headers` are provided verbatim and include both the key as well as the value.
+    async fn get(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<GetResponse<Self::Headers, Self::ResponseBody>, Error>;
+
+    /// Initiate a `POST` request to `url` providing with the given `headers`, where `base_url` is so that `base_url + tail == url`.
+    ///
+    /// The `base_url` helps to validate redirects and to swap it with the effective base after a redirect.
+    ///
+    /// The `headers` are provided verbatim and include both the key as well as the value.
+    /// Note that the [`PostResponse`] contains the [`post_body`][PostResponse::post_body] field which implements
+    /// [`AsyncWrite`][futures_io::AsyncWrite] and is expected to receive the body to post to the server.
+    ///
+    /// As this method is called from [`request()`][crate::client::TransportWithoutIO::request()] which can't perform IO,
+    /// implementations must not block, but start the request once data is written to the `post_body`, or once the response
+    /// is read. The `post_body` is dropped before the response is read.
+    fn post(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        body: PostBodyDataKind,
+    ) -> Result<PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, Error>;
+
+    /// Pass `config` which can deserialize in the implementation's configuration, as documented separately.
+    ///
+    /// The caller must know how that `config` data looks like for the intended implementation.
+    fn configure(
+        &mut self,
+        config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
@@ -9,5 +9,9 @@ pub use traits::{SetServiceResponse, Transport, TransportV2Ext};
 
 ///
 pub mod connect;
+
+///
+#[cfg(feature = "async-http-client")]
+pub mod http;
 #[cfg(feature = "async-std")]
 pub use connect::function::connect;
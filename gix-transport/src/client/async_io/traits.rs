@@ -2,6 +2,7 @@ use std::ops::DerefMut;
 
 use async_trait::async_trait;
 use bstr::BString;
+use futures_io::AsyncBufRead;
 use futures_lite::io::AsyncWriteExt;
 
 use crate::{
@@ -42,6 +43,18 @@ pub trait Transport: TransportWithoutIO {
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<SetServiceResponse<'_>, Error>;
+
+    /// Obtain the file at `path` relative to the location of the remote repository, or `None` if it doesn't exist.
+    ///
+    /// This is how servers are accessed that only support the 'dumb' HTTP protocol, as indicated by
+    /// [`handshake()`][Transport::handshake()] failing with [`Error::DumbHttpServer`].
+    /// Transports that can't provide files fail with an IO error of kind [`Unsupported`][std::io::ErrorKind::Unsupported].
+    async fn get_file(&mut self, path: &str) -> Result<Option<Box<dyn AsyncBufRead + Unpin + '_>>, Error> {
+        Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Cannot obtain file {path:?} as the transport doesn't support accessing files"),
+        )))
+    }
 }
 
 // Would be nice if the box implementation could auto-forward to all implemented traits.
@@ -54,6 +67,10 @@ impl<T: Transport + ?Sized> Transport for Box<T> {
     ) -> Result<SetServiceResponse<'_>, Error> {
         self.deref_mut().handshake(service, extra_parameters).await
     }
+
+    async fn get_file(&mut self, path: &str) -> Result<Option<Box<dyn AsyncBufRead + Unpin + '_>>, Error> {
+        self.deref_mut().get_file(path).await
+    }
 }
 
 // Would be nice if the box implementation could auto-forward to all implemented traits.
@@ -66,6 +83,10 @@ impl<T: Transport + ?Sized> Transport for &mut T {
     ) -> Result<SetServiceResponse<'_>, Error> {
         self.deref_mut().handshake(service, extra_parameters).await
     }
+
+    async fn get_file(&mut self, path: &str) -> Result<Option<Box<dyn AsyncBufRead + Unpin + '_>>, Error> {
+        self.deref_mut().get_file(path).await
+    }
 }
 
 /// An extension trait to add more methods to everything implementing [`Transport`].
@@ -34,6 +34,25 @@ pub(crate) mod function {
                         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
+            #[cfg(feature = "ssh-client-native")]
+            gix_url::Scheme::Ssh if options.ssh.native => Box::new({
+                let ssh_options = options
+                    .ssh
+                    .command
+                    .as_deref()
+                    .map(|command| {
+                        crate::client::blocking_io::ssh::native::connect::Options::from_ssh_command(
+                            &command.to_string_lossy(),
+                        )
+                    })
+                    .transpose()
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+                    .unwrap_or_default();
+                crate::client::blocking_io::ssh::native::connect(url, options.version, ssh_options, options.trace)
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+            }),
+            #[cfg(not(feature = "ssh-client-native"))]
+            gix_url::Scheme::Ssh if options.ssh.native => return Err(Error::CompiledWithoutNativeSsh),
             gix_url::Scheme::Ssh => Box::new({
                 crate::client::blocking_io::ssh::connect(url, options.version, options.ssh, options.trace)
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
@@ -8,7 +8,7 @@ use std::{
 
 use gix_features::io::pipe;
 
-use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, traits::PostBodyDataKind};
+use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, PostBodyDataKind};
 
 /// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
 #[derive(Debug, thiserror::Error)]
@@ -146,6 +146,10 @@ impl Default for Remote {
                             Some(status) => {
                                 let kind = if status == reqwest::StatusCode::UNAUTHORIZED {
                                     std::io::ErrorKind::PermissionDenied
+                                } else if status == reqwest::StatusCode::NOT_FOUND
+                                    || status == reqwest::StatusCode::GONE
+                                {
+                                    std::io::ErrorKind::NotFound
                                 } else if status.is_server_error() {
                                     std::io::ErrorKind::ConnectionAborted
                                 } else {
@@ -33,6 +33,10 @@ pub enum ProgramKind {
 
 mod program_kind;
 
+///
+#[cfg(feature = "ssh-client-native")]
+pub mod native;
+
 ///
 pub mod invocation {
     use std::ffi::OsString;
@@ -73,6 +77,11 @@ pub mod connect {
         /// when invoking the program.
         /// If unset, the `program` basename determines the variant, or an invocation of the `command` itself.
         pub kind: Option<ProgramKind>,
+        /// If `true`, use the native ssh client instead of invoking `command`, whose arguments are then used to configure it
+        /// as described in [`native::connect::Options::from_ssh_command()`][crate::client::ssh::native::connect::Options::from_ssh_command()].
+        ///
+        /// This requires the `ssh-client-native` feature.
+        pub native: bool,
     }
 
     impl Options {
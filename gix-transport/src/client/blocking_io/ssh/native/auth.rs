@@ -0,0 +1,92 @@
+//! Public key authentication with keys from an ssh agent or from identity files.
+use std::path::{Path, PathBuf};
+
+use super::{connect::IdentityAgent, Error};
+
+/// Authenticate as `user` on `session` using the keys of the agent selected by `agent`, followed by the keys in `identity_files`.
+///
+/// If `identities_only` is set, only keys of the agent that are also in `identity_files` are used.
+pub(crate) fn authenticate(
+    session: &ssh2::Session,
+    user: &str,
+    agent: IdentityAgent,
+    identity_files: &[PathBuf],
+    identities_only: bool,
+) -> Result<(), Error> {
+    let methods = session.auth_methods(user)?;
+    if session.authenticated() {
+        return Ok(());
+    }
+
+    let file_keys: Vec<_> = identity_files
+        .iter()
+        .filter_map(|path| match ssh_key::PrivateKey::read_openssh_file(path) {
+            Ok(key) => Some((path.as_path(), key.public_key().to_bytes().ok()?, key.is_encrypted())),
+            Err(ssh_key::Error::Io(std::io::ErrorKind::NotFound)) => None,
+            #[allow(unused_variables)]
+            Err(err) => {
+                gix_features::trace::warn!("Could not read identity file at {path:?}: {err}");
+                None
+            }
+        })
+        .collect();
+
+    let mut agent_keys = Vec::new();
+    if agent == IdentityAgent::FromEnvironment {
+        match authenticate_with_agent(session, user, |blob| {
+            agent_keys.push(blob.to_owned());
+            !identities_only || file_keys.iter().any(|(_, file_blob, _)| file_blob == blob)
+        }) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            #[allow(unused_variables)]
+            Err(err) => gix_features::trace::warn!("Could not use the ssh agent: {err}"),
+        }
+    }
+
+    for (path, blob, is_encrypted) in file_keys {
+        if agent_keys.contains(&blob) {
+            continue;
+        }
+        if is_encrypted {
+            gix_features::trace::warn!("Skipping encrypted identity file at {path:?} as the agent doesn't have it");
+            continue;
+        }
+        if authenticate_with_file(session, user, path) {
+            return Ok(());
+        }
+    }
+    Err(Error::AuthenticationFailed {
+        user: user.to_owned(),
+        methods: methods.split(',').map(ToOwned::to_owned).collect(),
+    })
+}
+
+/// Try all keys of the agent for which `use_key(public_key_blob)` returns `true`, and return `true` if one of them was accepted.
+fn authenticate_with_agent(
+    session: &ssh2::Session,
+    user: &str,
+    mut use_key: impl FnMut(&[u8]) -> bool,
+) -> Result<bool, ssh2::Error> {
+    let mut agent = session.agent()?;
+    agent.connect()?;
+    agent.list_identities()?;
+    for identity in agent.identities()? {
+        if use_key(identity.blob()) && agent.userauth(user, &identity).is_ok() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Return `true` if the key in the identity file at `path` was accepted.
+fn authenticate_with_file(session: &ssh2::Session, user: &str, path: &Path) -> bool {
+    match session.userauth_pubkey_file(user, None, path, None) {
+        Ok(()) => true,
+        #[allow(unused_variables)]
+        Err(err) => {
+            gix_features::trace::debug!("The key in {path:?} wasn't accepted: {err}");
+            false
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::{path::PathBuf, time::Duration};
+
+/// The error returned by [`Options::from_ssh_command()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The ssh command {command:?} contains unbalanced quotes")]
+    UnbalancedQuotes { command: String },
+    #[error("The ssh option {option:?} needs a value")]
+    MissingValue { option: String },
+    #[error("{value:?} is not a valid value for the ssh option {option:?}")]
+    InvalidValue { option: String, value: String },
+}
+
+/// How to deal with hosts whose key isn't known yet, like the `StrictHostKeyChecking` option of `ssh_config(5)`.
+///
+/// Connections to hosts whose key differs from the known one are always refused.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum StrictHostKeyChecking {
+    /// Refuse to connect to hosts with unknown keys, which is also used instead of `ask` as we can't ask.
+    #[default]
+    Yes,
+    /// Add the keys of unknown hosts to the first known hosts file of the user.
+    AcceptNew,
+    /// Like [`AcceptNew`][StrictHostKeyChecking::AcceptNew], as it's the closest we can get to what `ssh` does.
+    No,
+}
+
+/// The ssh agent to use for authentication.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum IdentityAgent {
+    /// Use the agent listening at the path in the `SSH_AUTH_SOCK` environment variable, if it is set,
+    /// or the agent of `OpenSSH` or `Pageant` on Windows.
+    #[default]
+    FromEnvironment,
+    /// Do not use an agent.
+    None,
+}
+
+/// The options for use when [connecting][super::connect()] with the native ssh client.
+///
+/// These mirror the options of the same name in `ssh_config(5)`, and can be obtained from a `core.sshCommand`
+/// using [`from_ssh_command()`][Options::from_ssh_command()].
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// The port to connect to if the URL doesn't specify one, defaulting to 22.
+    pub port: Option<u16>,
+    /// The user to authenticate as if the URL doesn't specify one, defaulting to the current user.
+    pub user: Option<String>,
+    /// The files with private keys to try after the keys of the agent.
+    /// If empty, `~/.ssh/id_ed25519`, `~/.ssh/id_ecdsa` and `~/.ssh/id_rsa` are used.
+    ///
+    /// Encrypted keys can only be used if the agent holds them.
+    pub identity_files: Vec<PathBuf>,
+    /// If `true`, only use the keys of the agent that are also in `identity_files`.
+    pub identities_only: bool,
+    /// The agent to ask for keys and signatures.
+    pub identity_agent: IdentityAgent,
+    /// The files with known host keys, the first of which receives new host keys.
+    /// If empty, `~/.ssh/known_hosts` and `~/.ssh/known_hosts2` are used.
+    ///
+    /// The global `/etc/ssh/ssh_known_hosts` file is always used as well.
+    pub user_known_hosts_files: Vec<PathBuf>,
+    /// How to deal with hosts whose key isn't known yet.
+    pub strict_host_key_checking: StrictHostKeyChecking,
+    /// The time after which to stop trying to establish a connection to the host, or `None` to use the system default.
+    pub connect_timeout: Option<Duration>,
+}
+
+impl Options {
+    /// Obtain options from the arguments of `command`, a command-line as configured in `core.sshCommand` or `GIT_SSH_COMMAND`
+    /// which is split like a shell would.
+    ///
+    /// The `-i`, `-l` and `-p` flags are supported along with `-o Option=value` for the options of `ssh_config(5)` that
+    /// correspond to our fields, and all other flags and options are ignored.
+    pub fn from_ssh_command(command: &str) -> Result<Self, Error> {
+        let words = split_words(command)?;
+        let mut words = words.iter().skip_while(|word| is_env_assignment(word)).skip(1);
+        let mut out = Options::default();
+        while let Some(word) = words.next() {
+            let Some(flags) = word.strip_prefix('-') else {
+                continue;
+            };
+            for (pos, flag) in flags.char_indices() {
+                if !FLAGS_WITH_ARGUMENT.contains(flag) {
+                    continue;
+                }
+                let attached = &flags[pos + flag.len_utf8()..];
+                let value = if attached.is_empty() {
+                    words.next().map(String::as_str).ok_or_else(|| Error::MissingValue {
+                        option: format!("-{flag}"),
+                    })?
+                } else {
+                    attached
+                };
+                match flag {
+                    'i' => out.set_option("IdentityFile", value)?,
+                    'l' => out.set_option("User", value)?,
+                    'p' => out.set_option("Port", value)?,
+                    'o' => {
+                        let (name, value) = value
+                            .split_once(|c: char| c == '=' || c.is_ascii_whitespace())
+                            .map(|(name, value)| {
+                                (
+                                    name,
+                                    value.trim_start_matches(|c: char| c == '=' || c.is_ascii_whitespace()),
+                                )
+                            })
+                            .ok_or_else(|| Error::MissingValue { option: value.into() })?;
+                        out.set_option(name, value)?;
+                    }
+                    _ => {}
+                }
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let invalid = || Error::InvalidValue {
+            option: name.into(),
+            value: value.into(),
+        };
+        let yes_or_no = || match value.to_ascii_lowercase().as_str() {
+            "yes" | "true" => Ok(true),
+            "no" | "false" => Ok(false),
+            _ => Err(invalid()),
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "identityfile" => self.identity_files.push(expand_tilde(value)),
+            "identitiesonly" => self.identities_only = yes_or_no()?,
+            "identityagent" => match value {
+                "none" => self.identity_agent = IdentityAgent::None,
+                "SSH_AUTH_SOCK" | "$SSH_AUTH_SOCK" => self.identity_agent = IdentityAgent::FromEnvironment,
+                _ => gix_features::trace::debug!(
+                    "Ignoring agent at {value:?} as only the one in SSH_AUTH_SOCK can be used"
+                ),
+            },
+            "userknownhostsfile" => {
+                self.user_known_hosts_files = value.split_ascii_whitespace().map(expand_tilde).collect();
+            }
+            "stricthostkeychecking" => {
+                self.strict_host_key_checking = match value.to_ascii_lowercase().as_str() {
+                    "yes" | "ask" => StrictHostKeyChecking::Yes,
+                    "accept-new" => StrictHostKeyChecking::AcceptNew,
+                    "no" | "off" => StrictHostKeyChecking::No,
+                    _ => return Err(invalid()),
+                }
+            }
+            "port" => self.port = Some(value.parse().map_err(|_| invalid())?),
+            "user" => self.user = Some(value.into()),
+            "connecttimeout" => {
+                self.connect_timeout = match value {
+                    "none" => None,
+                    secs => Some(Duration::from_secs(secs.parse().map_err(|_| invalid())?)),
+                }
+            }
+            _ => gix_features::trace::debug!("Ignoring unsupported ssh option {name:?}"),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn identity_files_or_default(&self) -> Vec<PathBuf> {
+        if self.identity_files.is_empty() {
+            in_ssh_dir(&["id_ed25519", "id_ecdsa", "id_rsa"])
+        } else {
+            self.identity_files.clone()
+        }
+    }
+
+    pub(crate) fn user_known_hosts_files_or_default(&self) -> Vec<PathBuf> {
+        if self.user_known_hosts_files.is_empty() {
+            in_ssh_dir(&["known_hosts", "known_hosts2"])
+        } else {
+            self.user_known_hosts_files.clone()
+        }
+    }
+}
+
+/// The flags of `ssh(1)` that take an argument.
+const FLAGS_WITH_ARGUMENT: &str = "BbcDEeFIiJLlmOopPQRSWw";
+
+fn in_ssh_dir(names: &[&str]) -> Vec<PathBuf> {
+    gix_path::env::home_dir()
+        .map(|home| names.iter().map(|name| home.join(".ssh").join(name)).collect())
+        .unwrap_or_default()
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), gix_path::env::home_dir()) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => path.into(),
+    }
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').map_or(false, |(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Split `command` into words like a POSIX shell would, handling quotes and backslashes.
+fn split_words(command: &str) -> Result<Vec<String>, Error> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut chars = command.chars();
+    let unbalanced = || Error::UnbalancedQuotes {
+        command: command.into(),
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_ascii_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or_else(unbalanced)? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or_else(unbalanced)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(unbalanced)? {
+                            c @ ('"' | '\\' | '$' | '`') => word.push(c),
+                            '\n' => {}
+                            c => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                let word = word.get_or_insert_with(String::new);
+                match chars.next() {
+                    Some('\n') => {}
+                    Some(c) => word.push(c),
+                    None => word.push('\\'),
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
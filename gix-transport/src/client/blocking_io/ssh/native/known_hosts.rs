@@ -0,0 +1,165 @@
+//! Verification of host keys against `known_hosts` files in the format described in `sshd(8)`.
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use hmac::Mac;
+use ssh_key::known_hosts::{Entry, HostPatterns, Marker};
+
+use super::{connect::StrictHostKeyChecking, Error};
+
+/// The name under which the host key of `host` at `port` is stored.
+pub(crate) fn host_name(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_owned()
+    } else {
+        format!("[{host}]:{port}")
+    }
+}
+
+/// Verify that `key` is the known key of the host called `name`, as produced by [`host_name()`], by looking at all `files` in order.
+///
+/// Depending on `strictness`, unknown keys are either rejected or appended to `add_to`.
+pub(crate) fn verify(
+    name: &str,
+    key: &ssh_key::PublicKey,
+    files: &[PathBuf],
+    add_to: Option<&Path>,
+    strictness: StrictHostKeyChecking,
+) -> Result<(), Error> {
+    let fingerprint = || key.fingerprint(ssh_key::HashAlg::Sha256).to_string();
+    let mut is_known = false;
+    let mut mismatch = None;
+    for path in files {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        for (line_number, entry) in entries(&content) {
+            if !host_matches(name, entry.host_patterns()) {
+                continue;
+            }
+            let same_key = entry.public_key().key_data() == key.key_data();
+            match entry.marker() {
+                Some(Marker::Revoked) if same_key => {
+                    return Err(Error::RevokedHostKey {
+                        host: name.to_owned(),
+                        fingerprint: fingerprint(),
+                        path: path.to_owned(),
+                    })
+                }
+                Some(_) => {}
+                None if same_key => is_known = true,
+                None if entry.public_key().algorithm() == key.algorithm() && mismatch.is_none() => {
+                    mismatch = Some((path.to_owned(), line_number));
+                }
+                None => {}
+            }
+        }
+    }
+    if is_known {
+        return Ok(());
+    }
+    if let Some((path, line)) = mismatch {
+        return Err(Error::HostKeyMismatch {
+            host: name.to_owned(),
+            fingerprint: fingerprint(),
+            path,
+            line,
+        });
+    }
+    match (strictness, add_to) {
+        (StrictHostKeyChecking::Yes, _) | (_, None) => Err(Error::UnknownHostKey {
+            host: name.to_owned(),
+            fingerprint: fingerprint(),
+        }),
+        (StrictHostKeyChecking::AcceptNew | StrictHostKeyChecking::No, Some(path)) => {
+            gix_features::trace::info!(
+                "Permanently adding {name} with key {} to the list of known hosts at {path:?}",
+                fingerprint()
+            );
+            add(name, key, path).map_err(|source| Error::AddHostKey {
+                host: name.to_owned(),
+                path: path.to_owned(),
+                source,
+            })
+        }
+    }
+}
+
+/// Return all valid entries in `content` along with their one-based line number.
+fn entries(content: &str) -> impl Iterator<Item = (usize, Entry)> + '_ {
+    content.lines().enumerate().filter_map(|(idx, line)| {
+        let line = line.split_once('#').map_or(line, |(line, _comment)| line).trim();
+        (!line.is_empty())
+            .then(|| Entry::from_str(line).ok())
+            .flatten()
+            .map(|entry| (idx + 1, entry))
+    })
+}
+
+fn add(name: &str, key: &ssh_key::PublicKey, path: &Path) -> std::io::Result<()> {
+    let key = ssh_key::PublicKey::new(key.key_data().clone(), "")
+        .to_openssh()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{name} {key}")
+}
+
+fn host_matches(name: &str, patterns: &HostPatterns) -> bool {
+    match patterns {
+        HostPatterns::Patterns(patterns) => {
+            let mut matched = false;
+            for pattern in patterns {
+                match pattern.strip_prefix('!') {
+                    Some(negated) if wildmatch(negated.as_bytes(), name.as_bytes()) => return false,
+                    Some(_) => {}
+                    None => matched |= wildmatch(pattern.as_bytes(), name.as_bytes()),
+                }
+            }
+            matched
+        }
+        HostPatterns::HashedName { salt, hash } => {
+            let mut mac = hmac::Hmac::<sha1::Sha1>::new_from_slice(salt).expect("any key length works");
+            mac.update(name.as_bytes());
+            mac.verify_slice(hash).is_ok()
+        }
+    }
+}
+
+/// Match `value` against `pattern` with `*` and `?` wildcards, ignoring case just like host names are compared.
+///
+/// On mismatch, only the most recent `*` is retried to consume one more byte, which keeps matching linear in practice.
+pub(super) fn wildmatch(pattern: &[u8], value: &[u8]) -> bool {
+    let (mut p, mut v) = (0, 0);
+    let mut last_star = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                last_star = Some((p, v));
+                p += 1;
+            }
+            Some(b'?') => {
+                p += 1;
+                v += 1;
+            }
+            Some(c) if c.eq_ignore_ascii_case(&value[v]) => {
+                p += 1;
+                v += 1;
+            }
+            _ => match last_star {
+                Some((star, star_value)) => {
+                    last_star = Some((star, star_value + 1));
+                    p = star + 1;
+                    v = star_value + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|b| *b == b'*')
+}
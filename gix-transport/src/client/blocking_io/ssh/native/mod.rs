@@ -0,0 +1,307 @@
+//! A native ssh client which connects to the server using `libssh2` instead of invoking the `ssh` program.
+//!
+//! It authenticates with the keys of an ssh agent or from identity files, verifies host keys using `known_hosts` files
+//! and executes the git service on the server, forwarding the desired protocol version in the `GIT_PROTOCOL` environment
+//! variable.
+use std::{
+    any::Any,
+    borrow::Cow,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    client::{self, git, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    Protocol, Service,
+};
+
+mod auth;
+mod known_hosts;
+
+///
+pub mod connect;
+
+/// The error used in [`connect()`] and when talking to the server.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The URL \"{0}\" is not usable for an ssh connection")]
+    UnsupportedScheme(BString),
+    #[error("Could not connect to {host}:{port}")]
+    Connect {
+        host: String,
+        port: u16,
+        source: std::io::Error,
+    },
+    #[error("An IO error occurred when talking to the ssh server")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Ssh(#[from] ssh2::Error),
+    #[error("The ssh server didn't provide a host key")]
+    MissingHostKey,
+    #[error("Host key verification failed as {host} with key {fingerprint} isn't in any known hosts file")]
+    UnknownHostKey { host: String, fingerprint: String },
+    #[error("The key {fingerprint} of {host} doesn't match the one in {}:{line}", path.display())]
+    HostKeyMismatch {
+        host: String,
+        fingerprint: String,
+        path: PathBuf,
+        line: usize,
+    },
+    #[error("The key {fingerprint} of {host} is marked as revoked in {}", path.display())]
+    RevokedHostKey {
+        host: String,
+        fingerprint: String,
+        path: PathBuf,
+    },
+    #[error("Could not add the key of {host} to {}", path.display())]
+    AddHostKey {
+        host: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not determine the user to authenticate as")]
+    MissingUser,
+    #[error("None of the available keys could authenticate {user:?}, with the server accepting {}", methods.join(", "))]
+    AuthenticationFailed { user: String, methods: Vec<String> },
+    #[error("The command {command:?} can't be executed on the ssh server as it isn't valid UTF-8")]
+    NonUtf8Command { command: BString },
+    #[error(transparent)]
+    Key(#[from] ssh_key::Error),
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) | Error::Connect { source: err, .. } => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// A transport that connects to the ssh server on the first [handshake][client::Transport::handshake()] to
+/// execute the service there.
+///
+/// It can only be instantiated using [`connect()`].
+pub struct Connection {
+    url: gix_url::Url,
+    path: BString,
+    options: connect::Options,
+    desired_version: Protocol,
+    trace: bool,
+    connection: Option<git::Connection<Reader, Writer>>,
+}
+
+impl Connection {
+    fn open_channel(&self, service: Service) -> Result<ssh2::Channel, Error> {
+        let host = self.url.host().expect("validated in connect()");
+        let host = host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(host);
+        let port = self.url.port.or(self.options.port).unwrap_or(22);
+        let user = self
+            .url
+            .user()
+            .map(ToOwned::to_owned)
+            .or_else(|| self.options.user.clone())
+            .or_else(current_user)
+            .ok_or(Error::MissingUser)?;
+
+        let stream = tcp_connect(host, port, self.options.connect_timeout).map_err(|source| Error::Connect {
+            host: host.to_owned(),
+            port,
+            source,
+        })?;
+        let mut session = ssh2::Session::new()?;
+        session.set_tcp_stream(stream);
+        session.handshake()?;
+        {
+            let (host_key, _) = session.host_key().ok_or(Error::MissingHostKey)?;
+            let host_key = ssh_key::PublicKey::from_bytes(host_key)?;
+            let user_files = self.options.user_known_hosts_files_or_default();
+            let files: Vec<_> = user_files
+                .iter()
+                .cloned()
+                .chain(Some(PathBuf::from("/etc/ssh/ssh_known_hosts")))
+                .collect();
+            known_hosts::verify(
+                &known_hosts::host_name(host, port),
+                &host_key,
+                &files,
+                user_files.first().map(PathBuf::as_path),
+                self.options.strict_host_key_checking,
+            )?;
+        }
+        auth::authenticate(
+            &session,
+            &user,
+            self.options.identity_agent,
+            &self.options.identity_files_or_default(),
+            self.options.identities_only,
+        )?;
+
+        let mut channel = session.channel_session()?;
+        if self.desired_version != Protocol::V1 {
+            channel.setenv("GIT_PROTOCOL", &format!("version={}", self.desired_version as usize))?;
+        }
+        let mut command = BString::from(service.as_str());
+        command.push(b' ');
+        command.extend_from_slice(&gix_quote::single(self.path.as_ref()));
+        gix_features::trace::debug!(command = ?command, host, port, user, "gix_transport::ssh::native");
+        channel.exec(command.to_str().map_err(|_| Error::NonUtf8Command {
+            command: command.clone(),
+        })?)?;
+        Ok(channel)
+    }
+}
+
+impl client::TransportWithoutIO for Connection {
+    fn set_identity(&mut self, identity: gix_sec::identity::Account) -> Result<(), client::Error> {
+        self.url
+            .set_user((!identity.username.is_empty()).then_some(identity.username));
+        Ok(())
+    }
+
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.connection
+            .as_mut()
+            .expect("handshake() to have been called first")
+            .request(write_mode, on_into_read, trace)
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(self.url.to_bstring())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl client::Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if self.path.trim().first() == Some(&b'-') {
+            return Err(client::Error::AmbiguousPath {
+                path: self.path.clone(),
+            });
+        }
+        let channel = self.open_channel(service).map_err(client::Error::NativeSsh)?;
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            Reader(channel.clone()),
+            Writer(channel),
+            self.desired_version,
+            self.path.clone(),
+            self.trace,
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+}
+
+/// The standard output of the remote command, which forwards its standard error to ours once all output was read.
+struct Reader(ssh2::Channel);
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes_read = self.0.read(buf)?;
+        if bytes_read == 0 && !buf.is_empty() {
+            std::io::copy(&mut self.0.stderr(), &mut std::io::stderr()).ok();
+        }
+        Ok(bytes_read)
+    }
+}
+
+/// The standard input of the remote command.
+struct Writer(ssh2::Channel);
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    /// Writes are sent right away, and flushing a channel in `libssh2` discards the output that wasn't read yet instead.
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // Let the server know we are done, as dropping the session just closes the connection.
+        self.0.send_eof().ok();
+        self.0.close().ok();
+    }
+}
+
+fn current_user() -> Option<String> {
+    ["USER", "USERNAME", "LOGNAME"]
+        .into_iter()
+        .find_map(|name| gix_path::env::var(name)?.into_string().ok())
+        .filter(|user| !user.is_empty())
+}
+
+fn tcp_connect(host: &str, port: u16, timeout: Option<std::time::Duration>) -> std::io::Result<TcpStream> {
+    let Some(timeout) = timeout else {
+        return TcpStream::connect((host, port));
+    };
+    let mut last_err = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "host name didn't resolve to any address")
+    }))
+}
+
+/// Connect to the host in `url` with the native ssh client to obtain data from the repository at the path of `url`.
+///
+/// The connection is established only on the first handshake, and uses `options` to learn how to authenticate and
+/// verify the host.
+///
+/// The `desired_version` is the preferred protocol version when establishing the connection, but note that it can be
+/// downgraded by servers not supporting it.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+#[allow(clippy::result_large_err)]
+pub fn connect(
+    url: gix_url::Url,
+    desired_version: Protocol,
+    options: connect::Options,
+    trace: bool,
+) -> Result<Connection, Error> {
+    if url.scheme != gix_url::Scheme::Ssh || url.host().is_none() {
+        return Err(Error::UnsupportedScheme(url.to_bstring()));
+    }
+    Ok(Connection {
+        path: gix_url::expand_path::for_shell(url.path.clone()),
+        url,
+        options,
+        desired_version,
+        trace,
+        connection: None,
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,466 @@
+mod server;
+
+mod connect {
+    use std::path::{Path, PathBuf};
+
+    use bstr::ByteSlice;
+
+    use super::server;
+    use crate::{
+        client::{
+            self,
+            ssh::native::{
+                connect,
+                connect::{IdentityAgent, Options, StrictHostKeyChecking},
+                Connection, Error,
+            },
+            Transport, TransportV2Ext,
+        },
+        Protocol, Service,
+    };
+
+    type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+    fn ed25519_key() -> ssh_key::PrivateKey {
+        ssh_key::PrivateKey::random(&mut rand_core::OsRng, ssh_key::Algorithm::Ed25519).expect("can generate keys")
+    }
+
+    fn fixture_bytes(path: &str) -> Vec<u8> {
+        std::fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)).expect("fixture exists")
+    }
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        identity_file: PathBuf,
+        known_hosts: PathBuf,
+        host_key: ssh_key::PrivateKey,
+        user_key: ssh_key::PrivateKey,
+    }
+
+    impl Fixture {
+        /// Create an identity file and a `known_hosts` file which knows `host_key` for the server at `port`.
+        fn new() -> Self {
+            let dir = tempfile::tempdir().expect("can create temp dir");
+            let user_key = ed25519_key();
+            let identity_file = dir.path().join("id_ed25519");
+            user_key
+                .write_openssh_file(&identity_file, ssh_key::LineEnding::LF)
+                .expect("can write key");
+            Fixture {
+                identity_file,
+                known_hosts: dir.path().join("known_hosts"),
+                _dir: dir,
+                host_key: ed25519_key(),
+                user_key,
+            }
+        }
+
+        fn know_host(&self, port: u16, key: &ssh_key::PublicKey) {
+            std::fs::write(
+                &self.known_hosts,
+                format!("# a comment\n[127.0.0.1]:{port} {}\n", key.to_openssh().unwrap()),
+            )
+            .unwrap();
+        }
+
+        fn options(&self) -> Options {
+            Options {
+                identity_files: vec![self.identity_file.clone()],
+                identity_agent: IdentityAgent::None,
+                user_known_hosts_files: vec![self.known_hosts.clone()],
+                ..Default::default()
+            }
+        }
+    }
+
+    fn url(port: u16) -> gix_url::Url {
+        gix_url::parse(format!("ssh://git@127.0.0.1:{port}/repo.git").as_str().into()).expect("valid")
+    }
+
+    fn handshake_error(transport: &mut Connection) -> Error {
+        match transport.handshake(Service::UploadPack, &[]) {
+            Ok(_) => panic!("the handshake should fail"),
+            Err(client::Error::NativeSsh(err)) => err,
+            Err(err) => panic!("expected a native ssh error, got {err:?}"),
+        }
+    }
+
+    #[test]
+    fn handshake_v2_and_ls_refs() -> Result {
+        let fixture = Fixture::new();
+        let (port, server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![fixture.user_key.public_key().clone()],
+            fixture_bytes("v2/clone.response"),
+        );
+        fixture.know_host(port, fixture.host_key.public_key());
+
+        let mut transport = connect(url(port), Protocol::V2, fixture.options(), false)?;
+        let res = transport.handshake(Service::UploadPack, &[])?;
+        assert_eq!(res.actual_protocol, Protocol::V2);
+        assert!(res.capabilities.contains("ls-refs"));
+        drop(res);
+
+        let reader = transport.invoke(
+            "ls-refs",
+            [("agent", Some("git/2.28.0"))].iter().copied(),
+            Some(
+                ["symrefs", "ref-prefix refs/heads/"]
+                    .iter()
+                    .map(|s| s.as_bytes().as_bstr().to_owned()),
+            ),
+            false,
+        )?;
+        let refs: Vec<_> = std::io::BufRead::lines(reader).collect::<std::result::Result<_, _>>()?;
+        assert_eq!(
+            refs,
+            [
+                "808e50d724f604f69ab93c6da2919c014667bedb HEAD symref-target:refs/heads/master",
+                "808e50d724f604f69ab93c6da2919c014667bedb refs/heads/master"
+            ]
+        );
+        drop(transport);
+
+        let record = server.join().expect("no panic")?;
+        assert_eq!(record.user, "git", "the user of the URL is used");
+        assert_eq!(
+            record.env,
+            [("GIT_PROTOCOL".to_owned(), "version=2".to_owned())],
+            "the protocol version is forwarded"
+        );
+        assert_eq!(record.command, "git-upload-pack '/repo.git'");
+        assert_eq!(
+            record.stdin.as_bstr(),
+            "0014command=ls-refs\n0015agent=git/2.28.0\n0001000csymrefs\n001bref-prefix refs/heads/\n0000",
+            "the request arrives in full despite the small window"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_v1_does_not_set_git_protocol() -> Result {
+        let fixture = Fixture::new();
+        let (port, server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![fixture.user_key.public_key().clone()],
+            fixture_bytes("v1/clone.response"),
+        );
+        fixture.know_host(port, fixture.host_key.public_key());
+
+        let mut transport = connect(
+            url(port),
+            Protocol::V1,
+            Options {
+                user: Some("ignored-as-the-url-has-a-user".into()),
+                ..fixture.options()
+            },
+            false,
+        )?;
+        let res = transport.handshake(Service::ReceivePack, &[])?;
+        assert_eq!(res.actual_protocol, Protocol::V1);
+        assert!(res.refs.is_some(), "V1 sends refs right away");
+        drop(res);
+        drop(transport);
+
+        let record = server.join().expect("no panic")?;
+        assert!(record.env.is_empty());
+        assert_eq!(record.command, "git-receive-pack '/repo.git'");
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_host_keys_are_rejected_by_default() -> Result {
+        let fixture = Fixture::new();
+        let (port, _server) = server::spawn(fixture.host_key.clone(), vec![], Vec::new());
+        std::fs::write(&fixture.known_hosts, "")?;
+
+        let mut transport = connect(url(port), Protocol::V2, fixture.options(), false)?;
+        let err = handshake_error(&mut transport);
+        assert!(
+            matches!(&err, Error::UnknownHostKey { host, .. } if *host == format!("[127.0.0.1]:{port}")),
+            "{err:?}"
+        );
+        assert_eq!(std::fs::read(&fixture.known_hosts)?, b"", "nothing was added");
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_host_keys_are_added_with_accept_new() -> Result {
+        let fixture = Fixture::new();
+        let (port, server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![fixture.user_key.public_key().clone()],
+            fixture_bytes("v2/clone.response"),
+        );
+        let mut transport = connect(
+            url(port),
+            Protocol::V2,
+            Options {
+                strict_host_key_checking: StrictHostKeyChecking::AcceptNew,
+                ..fixture.options()
+            },
+            false,
+        )?;
+        transport.handshake(Service::UploadPack, &[])?;
+        drop(transport);
+        server.join().expect("no panic")?;
+
+        let host_key = ssh_key::PublicKey::new(fixture.host_key.public_key().key_data().clone(), "");
+        assert_eq!(
+            std::fs::read_to_string(&fixture.known_hosts)?,
+            format!("[127.0.0.1]:{port} {}\n", host_key.to_openssh()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn changed_host_keys_are_always_rejected() -> Result {
+        let fixture = Fixture::new();
+        let (port, _server) = server::spawn(fixture.host_key.clone(), vec![], Vec::new());
+        fixture.know_host(port, ed25519_key().public_key());
+
+        let mut transport = connect(
+            url(port),
+            Protocol::V2,
+            Options {
+                strict_host_key_checking: StrictHostKeyChecking::No,
+                ..fixture.options()
+            },
+            false,
+        )?;
+        let err = handshake_error(&mut transport);
+        assert!(
+            matches!(&err, Error::HostKeyMismatch { line: 2, path, .. } if *path == fixture.known_hosts),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn authentication_fails_without_authorized_key() -> Result {
+        let fixture = Fixture::new();
+        let (port, _server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![ed25519_key().public_key().clone()],
+            Vec::new(),
+        );
+        fixture.know_host(port, fixture.host_key.public_key());
+
+        let mut transport = connect(url(port), Protocol::V2, fixture.options(), false)?;
+        let err = handshake_error(&mut transport);
+        assert!(
+            matches!(&err, Error::AuthenticationFailed { user, methods } if user == "git" && methods == &["publickey"]),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn agent_keys_are_used() -> Result {
+        let fixture = Fixture::new();
+        let agent_key = ed25519_key();
+        let (port, server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![agent_key.public_key().clone()],
+            fixture_bytes("v2/clone.response"),
+        );
+        fixture.know_host(port, fixture.host_key.public_key());
+        let agent_path = fixture.known_hosts.with_file_name("agent.sock");
+        let agent = agent::spawn(&agent_path, agent_key);
+        // This is the only test that uses an agent, so it can't affect other tests.
+        std::env::set_var("SSH_AUTH_SOCK", &agent_path);
+
+        let mut transport = connect(
+            url(port),
+            Protocol::V2,
+            Options {
+                identity_agent: IdentityAgent::FromEnvironment,
+                ..fixture.options()
+            },
+            false,
+        )?;
+        transport.handshake(Service::UploadPack, &[])?;
+        drop(transport);
+        server.join().expect("no panic")?;
+        assert_eq!(agent.join().expect("no panic")?, 1, "the agent signed once");
+        Ok(())
+    }
+
+    #[test]
+    fn ecdsa_keys_are_supported() -> Result {
+        let mut fixture = Fixture::new();
+        let ecdsa_key = || {
+            ssh_key::PrivateKey::random(
+                &mut rand_core::OsRng,
+                ssh_key::Algorithm::Ecdsa {
+                    curve: ssh_key::EcdsaCurve::NistP256,
+                },
+            )
+            .expect("can generate keys")
+        };
+        fixture.host_key = ecdsa_key();
+        fixture.user_key = ecdsa_key();
+        fixture
+            .user_key
+            .write_openssh_file(&fixture.identity_file, ssh_key::LineEnding::LF)?;
+        let (port, server) = server::spawn(
+            fixture.host_key.clone(),
+            vec![fixture.user_key.public_key().clone()],
+            fixture_bytes("v2/clone.response"),
+        );
+        fixture.know_host(port, fixture.host_key.public_key());
+
+        let mut transport = connect(url(port), Protocol::V2, fixture.options(), false)?;
+        transport.handshake(Service::UploadPack, &[])?;
+        drop(transport);
+        server.join().expect("no panic")?;
+        Ok(())
+    }
+
+    #[test]
+    fn non_ssh_urls_are_rejected() {
+        let url = gix_url::parse("git://host/repo".into()).unwrap();
+        assert!(matches!(
+            connect(url, Protocol::V2, Default::default(), false),
+            Err(Error::UnsupportedScheme(_))
+        ));
+    }
+
+    /// A fake ssh agent holding a single key.
+    #[cfg(unix)]
+    mod agent {
+        use std::{
+            io::{Read, Write},
+            os::unix::net::UnixListener,
+            path::Path,
+            thread::JoinHandle,
+        };
+
+        use signature::Signer;
+
+        use super::super::server::wire::{Decoder, Encode};
+
+        /// Serve a single connection and return how many signatures were made.
+        pub fn spawn(path: &Path, key: ssh_key::PrivateKey) -> JoinHandle<std::io::Result<usize>> {
+            let listener = UnixListener::bind(path).expect("can bind");
+            std::thread::spawn(move || {
+                let (mut stream, _) = listener.accept()?;
+                let mut signatures = 0;
+                loop {
+                    let mut len = [0u8; 4];
+                    if stream.read_exact(&mut len).is_err() {
+                        return Ok(signatures);
+                    }
+                    let mut request = vec![0; u32::from_be_bytes(len) as usize];
+                    stream.read_exact(&mut request)?;
+                    let mut response = Vec::new();
+                    match request[0] {
+                        11 => {
+                            response.push(12);
+                            response
+                                .put_u32(1)
+                                .put_string(key.public_key().to_bytes().unwrap())
+                                .put_string("comment");
+                        }
+                        13 => {
+                            let mut d = Decoder::new(&request[1..]);
+                            assert_eq!(d.string().unwrap(), key.public_key().to_bytes().unwrap());
+                            let signature: ssh_key::Signature = key.try_sign(d.string().unwrap()).unwrap();
+                            response.push(14);
+                            response.put_string(Vec::<u8>::try_from(signature).unwrap());
+                            signatures += 1;
+                        }
+                        _ => response.push(5),
+                    }
+                    let mut message = Vec::new();
+                    message.put_string(response);
+                    stream.write_all(&message)?;
+                }
+            })
+        }
+    }
+}
+
+mod options {
+    use std::{path::PathBuf, time::Duration};
+
+    use crate::client::ssh::native::connect::{IdentityAgent, Options, StrictHostKeyChecking};
+
+    #[test]
+    fn from_ssh_command() {
+        let opts = Options::from_ssh_command(
+            r#"GIT_TRACE=1 ssh -v -i /a/key -p2222 -lme -o "UserKnownHostsFile=/kh1 /kh2" -oIdentitiesOnly=yes -o StrictHostKeyChecking=accept-new -o 'ConnectTimeout 5' -o IdentityAgent=none -o Unknown=value -F /ignored/config -T"#,
+        )
+        .unwrap();
+        assert_eq!(opts.identity_files, [PathBuf::from("/a/key")]);
+        assert_eq!(opts.port, Some(2222));
+        assert_eq!(opts.user.as_deref(), Some("me"));
+        assert_eq!(
+            opts.user_known_hosts_files,
+            [PathBuf::from("/kh1"), PathBuf::from("/kh2")]
+        );
+        assert!(opts.identities_only);
+        assert_eq!(opts.strict_host_key_checking, StrictHostKeyChecking::AcceptNew);
+        assert_eq!(opts.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(opts.identity_agent, IdentityAgent::None);
+    }
+
+    #[test]
+    fn from_plain_program() {
+        let opts = Options::from_ssh_command("/usr/bin/ssh").unwrap();
+        assert!(opts.identity_files.is_empty());
+        assert_eq!(opts.identity_agent, IdentityAgent::FromEnvironment);
+        assert_eq!(opts.strict_host_key_checking, StrictHostKeyChecking::Yes);
+    }
+
+    #[test]
+    fn home_is_expanded() {
+        let opts = Options::from_ssh_command(r"ssh -i ~/.ssh/other\ key").unwrap();
+        let home = gix_path::env::home_dir().expect("tests have a home");
+        assert_eq!(opts.identity_files, [home.join(".ssh/other key")]);
+    }
+
+    #[test]
+    fn invalid_values_and_quotes_are_errors() {
+        assert!(Options::from_ssh_command("ssh -p nan").is_err());
+        assert!(Options::from_ssh_command("ssh -o StrictHostKeyChecking=maybe").is_err());
+        assert!(Options::from_ssh_command("ssh -i").is_err());
+        assert!(Options::from_ssh_command("ssh -o 'Port=22").is_err());
+    }
+}
+
+mod known_hosts {
+    use crate::client::blocking_io::ssh::native::known_hosts::wildmatch;
+
+    #[test]
+    fn wildcards_match_like_in_openssh() {
+        for (pattern, value, expected) in [
+            ("*", "", true),
+            ("*", "example.com", true),
+            ("*.example.com", "git.EXAMPLE.com", true),
+            ("*.example.com", "example.com", false),
+            ("git?.example.com", "git1.example.com", true),
+            ("git?.example.com", "git.example.com", false),
+            ("*git*", "xgitx", true),
+            ("a*b*c", "abbbc", true),
+            ("a*b*c", "abcb", false),
+            ("**", "a", true),
+            ("", "", true),
+            ("", "a", false),
+        ] {
+            assert_eq!(
+                wildmatch(pattern.as_bytes(), value.as_bytes()),
+                expected,
+                "{pattern} {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn many_stars_do_not_backtrack_exponentially() {
+        let pattern = "*a".repeat(64) + "b";
+        let value = "a".repeat(4096);
+        assert!(!wildmatch(pattern.as_bytes(), value.as_bytes()));
+    }
+}
@@ -0,0 +1,247 @@
+//! Algorithm negotiation and key derivation for the key exchange of [RFC 4253, section 7](https://www.rfc-editor.org/rfc/rfc4253#section-7),
+//! using the `curve25519-sha256` method of [RFC 8731](https://www.rfc-editor.org/rfc/rfc8731) as the only one.
+use rand_core::RngCore;
+use sha2::Digest;
+
+use super::{
+    packet,
+    wire::{msg, Decoder, Encode},
+    Error,
+};
+
+/// The key exchange methods we support, in order of preference.
+const KEX_ALGORITHMS: &[&str] = &["curve25519-sha256", "curve25519-sha256@libssh.org"];
+/// The host key algorithms we support.
+const HOST_KEY_ALGORITHMS: &[&str] = &["ssh-ed25519", "ecdsa-sha2-nistp256"];
+
+/// The pseudo-algorithms indicating support for the strict key exchange of OpenSSH.
+const STRICT_KEX_CLIENT: &str = "kex-strict-c-v00@openssh.com";
+const STRICT_KEX_SERVER: &str = "kex-strict-s-v00@openssh.com";
+
+/// Create the payload of our `KEXINIT` message, offering the `host_key_algorithm` of our host key.
+pub fn init_message(host_key_algorithm: &str) -> Vec<u8> {
+    let mut cookie = [0u8; 16];
+    rand_core::OsRng.fill_bytes(&mut cookie);
+    let mut kex_algorithms = KEX_ALGORITHMS.to_vec();
+    kex_algorithms.push(STRICT_KEX_SERVER);
+
+    let mut out = vec![msg::KEXINIT];
+    out.extend_from_slice(&cookie);
+    out.put_name_list(&kex_algorithms)
+        .put_name_list(&[host_key_algorithm])
+        .put_name_list(packet::CIPHERS)
+        .put_name_list(packet::CIPHERS)
+        .put_name_list(packet::MACS)
+        .put_name_list(packet::MACS)
+        .put_name_list(&["none"])
+        .put_name_list(&["none"])
+        .put_name_list(&[])
+        .put_name_list(&[])
+        .put_bool(false)
+        .put_u32(0);
+    out
+}
+
+/// The algorithms both sides agreed on.
+pub struct Negotiated {
+    pub cipher_client_to_server: &'static str,
+    pub cipher_server_to_client: &'static str,
+    pub mac_client_to_server: Option<&'static str>,
+    pub mac_server_to_client: Option<&'static str>,
+    /// If `true`, both sides support the strict key exchange mode.
+    pub strict: bool,
+    /// If `true`, the peer sent a guessed key exchange packet which must be ignored as the guess was wrong.
+    pub ignore_guessed_packet: bool,
+}
+
+struct InitMessage<'a> {
+    kex: Vec<&'a str>,
+    host_key: Vec<&'a str>,
+    cipher_client_to_server: Vec<&'a str>,
+    cipher_server_to_client: Vec<&'a str>,
+    mac_client_to_server: Vec<&'a str>,
+    mac_server_to_client: Vec<&'a str>,
+    compression_client_to_server: Vec<&'a str>,
+    compression_server_to_client: Vec<&'a str>,
+    first_kex_packet_follows: bool,
+}
+
+impl<'a> InitMessage<'a> {
+    fn decode(payload: &'a [u8]) -> Result<Self, Error> {
+        let mut d = Decoder::new(payload);
+        if d.u8()? != msg::KEXINIT {
+            return Err(Error::InvalidMessage("expected KEXINIT"));
+        }
+        d.skip(16)?;
+        let kex = d.name_list()?;
+        let host_key = d.name_list()?;
+        let cipher_client_to_server = d.name_list()?;
+        let cipher_server_to_client = d.name_list()?;
+        let mac_client_to_server = d.name_list()?;
+        let mac_server_to_client = d.name_list()?;
+        let compression_client_to_server = d.name_list()?;
+        let compression_server_to_client = d.name_list()?;
+        d.name_list()?;
+        d.name_list()?;
+        let first_kex_packet_follows = d.bool()?;
+        Ok(InitMessage {
+            kex,
+            host_key,
+            cipher_client_to_server,
+            cipher_server_to_client,
+            mac_client_to_server,
+            mac_server_to_client,
+            compression_client_to_server,
+            compression_server_to_client,
+            first_kex_packet_follows,
+        })
+    }
+}
+
+/// Pick the first algorithm of the `client` that the `server` supports, as long as it's one of the ones we know in `ours`.
+fn choose(kind: &'static str, client: &[&str], server: &[&str], ours: &[&'static str]) -> Result<&'static str, Error> {
+    client
+        .iter()
+        .find(|name| server.contains(name))
+        .and_then(|name| ours.iter().find(|ours| *ours == name).copied())
+        .ok_or(Error::NoCommonAlgorithm { kind })
+}
+
+/// Negotiate the algorithms to use given the `KEXINIT` payloads of the `client` and the `server`.
+pub fn negotiate(client: &[u8], server: &[u8]) -> Result<Negotiated, Error> {
+    let client = InitMessage::decode(client)?;
+    let server = InitMessage::decode(server)?;
+
+    let kex = choose("key exchange", &client.kex, &server.kex, KEX_ALGORITHMS)?;
+    let host_key = choose("host key", &client.host_key, &server.host_key, HOST_KEY_ALGORITHMS)?;
+    let cipher_client_to_server = choose(
+        "cipher",
+        &client.cipher_client_to_server,
+        &server.cipher_client_to_server,
+        packet::CIPHERS,
+    )?;
+    let cipher_server_to_client = choose(
+        "cipher",
+        &client.cipher_server_to_client,
+        &server.cipher_server_to_client,
+        packet::CIPHERS,
+    )?;
+    let mac = |cipher: &str, client: &[&str], server: &[&str]| -> Result<Option<&'static str>, Error> {
+        (!packet::cipher_is_aead(cipher))
+            .then(|| choose("mac", client, server, packet::MACS))
+            .transpose()
+    };
+    let mac_client_to_server = mac(
+        cipher_client_to_server,
+        &client.mac_client_to_server,
+        &server.mac_client_to_server,
+    )?;
+    let mac_server_to_client = mac(
+        cipher_server_to_client,
+        &client.mac_server_to_client,
+        &server.mac_server_to_client,
+    )?;
+    choose(
+        "compression",
+        &client.compression_client_to_server,
+        &server.compression_client_to_server,
+        &["none"],
+    )?;
+    choose(
+        "compression",
+        &client.compression_server_to_client,
+        &server.compression_server_to_client,
+        &["none"],
+    )?;
+
+    let guess_was_wrong =
+        |init: &InitMessage<'_>| init.kex.first() != Some(&kex) || init.host_key.first() != Some(&host_key);
+    Ok(Negotiated {
+        cipher_client_to_server,
+        cipher_server_to_client,
+        mac_client_to_server,
+        mac_server_to_client,
+        strict: client.kex.contains(&STRICT_KEX_CLIENT) && server.kex.contains(&STRICT_KEX_SERVER),
+        ignore_guessed_packet: (client.first_kex_packet_follows && guess_was_wrong(&client))
+            || (server.first_kex_packet_follows && guess_was_wrong(&server)),
+    })
+}
+
+/// The inputs to the exchange hash `H`, in the order in which they are hashed.
+pub struct ExchangeHashInput<'a> {
+    pub client_version: &'a [u8],
+    pub server_version: &'a [u8],
+    pub client_init: &'a [u8],
+    pub server_init: &'a [u8],
+    pub host_key: &'a [u8],
+    pub client_ephemeral: &'a [u8],
+    pub server_ephemeral: &'a [u8],
+    pub shared_secret: &'a [u8],
+}
+
+impl ExchangeHashInput<'_> {
+    pub fn hash(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.put_string(self.client_version)
+            .put_string(self.server_version)
+            .put_string(self.client_init)
+            .put_string(self.server_init)
+            .put_string(self.host_key)
+            .put_string(self.client_ephemeral)
+            .put_string(self.server_ephemeral)
+            .put_mpint(self.shared_secret);
+        sha2::Sha256::digest(&buf).to_vec()
+    }
+}
+
+/// Derive the keys for both directions as described in [RFC 4253, section 7.2](https://www.rfc-editor.org/rfc/rfc4253#section-7.2),
+/// returning `(client_to_server, server_to_client)`.
+pub fn derive_keys(
+    negotiated: &Negotiated,
+    shared_secret: &[u8],
+    exchange_hash: &[u8],
+    session_id: &[u8],
+) -> (packet::Keys, packet::Keys) {
+    let mut encoded_secret = Vec::new();
+    encoded_secret.put_mpint(shared_secret);
+    let derive = |letter: u8, len: usize| -> Vec<u8> {
+        let mut key = sha2::Sha256::new()
+            .chain_update(&encoded_secret)
+            .chain_update(exchange_hash)
+            .chain_update([letter])
+            .chain_update(session_id)
+            .finalize()
+            .to_vec();
+        while key.len() < len {
+            let more = sha2::Sha256::new()
+                .chain_update(&encoded_secret)
+                .chain_update(exchange_hash)
+                .chain_update(&key)
+                .finalize();
+            key.extend_from_slice(&more);
+        }
+        key.truncate(len);
+        key
+    };
+    let keys = |cipher: &'static str, mac: Option<&'static str>, letters: [u8; 3]| {
+        let (key_len, iv_len) = packet::cipher_key_and_iv_len(cipher);
+        packet::Keys {
+            cipher,
+            iv: derive(letters[0], iv_len),
+            key: derive(letters[1], key_len),
+            mac: mac.map(|mac| (mac, derive(letters[2], packet::mac_key_len(mac)))),
+        }
+    };
+    (
+        keys(
+            negotiated.cipher_client_to_server,
+            negotiated.mac_client_to_server,
+            *b"ACE",
+        ),
+        keys(
+            negotiated.cipher_server_to_client,
+            negotiated.mac_server_to_client,
+            *b"BDF",
+        ),
+    )
+}
@@ -0,0 +1,195 @@
+//! A minimal in-process ssh server to test the client against, accepting a single connection to pretend to run a git service.
+//!
+//! It only implements what's needed to talk to `libssh2`, and isn't hardened in any way.
+use std::{net::TcpListener, thread::JoinHandle};
+
+use bstr::BString;
+
+use session::Session;
+use wire::{msg, Decoder, Encode};
+
+mod kex;
+mod packet;
+mod session;
+pub mod wire;
+
+/// The error produced by the server.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("An IO error occurred when talking to the ssh client")]
+    Io(#[from] std::io::Error),
+    #[error("The ssh client sent an invalid message: {0}")]
+    InvalidMessage(&'static str),
+    #[error("The ssh client sent the unexpected message {0}")]
+    UnexpectedMessage(u8),
+    #[error("Could not agree on a {kind} algorithm with the ssh client")]
+    NoCommonAlgorithm { kind: &'static str },
+    #[error("The ssh client disconnected")]
+    Disconnected,
+    #[error(transparent)]
+    Key(#[from] ssh_key::Error),
+    #[error(transparent)]
+    Signature(#[from] signature::Error),
+}
+
+/// What the server observed.
+#[derive(Debug, Default)]
+pub struct Record {
+    pub user: String,
+    pub env: Vec<(String, String)>,
+    pub command: BString,
+    pub stdin: Vec<u8>,
+}
+
+/// The window we grant the client, which is small to exercise window adjustments.
+const WINDOW_SIZE: u32 = 64;
+
+/// Spawn a server identifying with `host_key` that lets in users with any of the `authorized_keys`, and which sends
+/// `response` as output of any command.
+pub fn spawn(
+    host_key: ssh_key::PrivateKey,
+    authorized_keys: Vec<ssh_key::PublicKey>,
+    response: Vec<u8>,
+) -> (u16, JoinHandle<Result<Record, Error>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("can bind to a free port");
+    let port = listener.local_addr().expect("bound").port();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept()?;
+        let mut session = Session::establish(stream, host_key)?;
+        let mut record = Record::default();
+        serve(&mut session, &authorized_keys, &response, &mut record)?;
+        Ok(record)
+    });
+    (port, handle)
+}
+
+fn serve(
+    session: &mut Session,
+    authorized_keys: &[ssh_key::PublicKey],
+    response: &[u8],
+    record: &mut Record,
+) -> Result<(), Error> {
+    let request = session.recv()?;
+    assert_eq!(request[0], msg::SERVICE_REQUEST);
+    let mut accept = vec![msg::SERVICE_ACCEPT];
+    accept.put_string("ssh-userauth");
+    session.send(&accept)?;
+
+    loop {
+        let request = session.recv()?;
+        assert_eq!(request[0], msg::USERAUTH_REQUEST);
+        if authenticate(session, &request, authorized_keys, record)? {
+            break;
+        }
+        let mut failure = vec![msg::USERAUTH_FAILURE];
+        failure.put_name_list(&["publickey"]).put_bool(false);
+        session.send(&failure)?;
+    }
+
+    let mut client_id = None;
+    loop {
+        let message = session.recv()?;
+        let mut d = Decoder::new(&message[1..]);
+        match message[0] {
+            msg::CHANNEL_OPEN => {
+                assert_eq!(d.utf8()?, "session");
+                client_id = Some(d.u32()?);
+                let mut confirmation = vec![msg::CHANNEL_OPEN_CONFIRMATION];
+                confirmation
+                    .put_u32(client_id.unwrap())
+                    .put_u32(42)
+                    .put_u32(WINDOW_SIZE)
+                    .put_u32(32);
+                session.send(&confirmation)?;
+            }
+            msg::CHANNEL_REQUEST => {
+                assert_eq!(d.u32()?, 42);
+                let kind = d.utf8()?;
+                let want_reply = d.bool()?;
+                match kind {
+                    "env" => {
+                        record.env.push((d.utf8()?.into(), d.utf8()?.into()));
+                        if want_reply {
+                            let mut success = vec![msg::CHANNEL_SUCCESS];
+                            success.put_u32(client_id.expect("channel is open"));
+                            session.send(&success)?;
+                        }
+                    }
+                    "exec" => {
+                        record.command = d.string()?.into();
+                        assert!(want_reply, "we need to know if the command could be executed");
+                        let client_id = client_id.expect("channel is open");
+                        let mut success = vec![msg::CHANNEL_SUCCESS];
+                        success.put_u32(client_id);
+                        session.send(&success)?;
+                        for chunk in response.chunks(1000) {
+                            let mut data = vec![msg::CHANNEL_DATA];
+                            data.put_u32(client_id).put_string(chunk);
+                            session.send(&data)?;
+                        }
+                    }
+                    _ => unreachable!("unexpected channel request {kind}"),
+                }
+            }
+            msg::CHANNEL_DATA => {
+                assert_eq!(d.u32()?, 42);
+                let data = d.string()?;
+                assert!(data.len() <= 32, "the client respects our maximum packet size");
+                record.stdin.extend_from_slice(data);
+                let mut adjust = vec![msg::CHANNEL_WINDOW_ADJUST];
+                adjust.put_u32(client_id.expect("open")).put_u32(data.len() as u32);
+                // The client may already have closed the connection after sending its last message.
+                session.send(&adjust).ok();
+            }
+            msg::CHANNEL_WINDOW_ADJUST | msg::CHANNEL_EOF => {}
+            msg::CHANNEL_CLOSE => {
+                let mut close = vec![msg::CHANNEL_CLOSE];
+                close.put_u32(client_id.expect("open"));
+                session.send(&close).ok();
+                return Ok(());
+            }
+            other => unreachable!("unexpected message {other}"),
+        }
+    }
+}
+
+/// Return `true` if the user is authenticated, or `false` if authentication failed. Queries for keys are answered directly.
+fn authenticate(
+    session: &mut Session,
+    request: &[u8],
+    authorized_keys: &[ssh_key::PublicKey],
+    record: &mut Record,
+) -> Result<bool, Error> {
+    let mut d = Decoder::new(&request[1..]);
+    record.user = d.utf8()?.into();
+    assert_eq!(d.utf8()?, "ssh-connection");
+    if d.utf8()? != "publickey" {
+        return Ok(false);
+    }
+    let has_signature = d.bool()?;
+    let algorithm = d.utf8()?;
+    let blob = d.string()?;
+    let key = ssh_key::PublicKey::from_bytes(blob)?;
+    if !authorized_keys.iter().any(|k| k.key_data() == key.key_data()) {
+        return Ok(false);
+    }
+    if !has_signature {
+        let mut ok = vec![msg::USERAUTH_PK_OK];
+        ok.put_string(algorithm).put_string(blob);
+        session.send(&ok)?;
+        let request = session.recv()?;
+        return authenticate(session, &request, authorized_keys, record);
+    }
+    let signature_blob = d.string()?;
+    let signature = ssh_key::Signature::try_from(signature_blob)?;
+    assert_eq!(signature.algorithm().as_str(), algorithm);
+
+    let mut signed_data = Vec::new();
+    signed_data.put_string(session.session_id());
+    signed_data.extend_from_slice(&request[..request.len() - 4 - signature_blob.len()]);
+    let is_valid = signature::Verifier::verify(&key, &signed_data, &signature).is_ok();
+    if is_valid {
+        session.send(&[msg::USERAUTH_SUCCESS])?;
+    }
+    Ok(is_valid)
+}
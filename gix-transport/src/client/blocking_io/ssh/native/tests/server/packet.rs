@@ -0,0 +1,315 @@
+//! The binary packet protocol of [RFC 4253, section 6](https://www.rfc-editor.org/rfc/rfc4253#section-6), including encryption
+//! and message authentication.
+use std::io::{Read, Write};
+
+use aes::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use hmac::Mac as _;
+use poly1305::universal_hash::KeyInit;
+use rand_core::RngCore;
+
+use super::Error;
+
+/// The ciphers we support, in order of preference.
+pub(crate) const CIPHERS: &[&str] = &["chacha20-poly1305@openssh.com", "aes256-ctr", "aes128-ctr"];
+/// The message authentication codes we support for ciphers that need one, in order of preference.
+pub(crate) const MACS: &[&str] = &["hmac-sha2-256", "hmac-sha2-512"];
+
+/// The largest packet we are willing to receive, as 256KiB is what OpenSSH accepts as well.
+const MAX_PACKET_LEN: usize = 256 * 1024;
+
+/// Return the `(key_len, iv_len)` of `cipher`.
+pub(crate) fn cipher_key_and_iv_len(cipher: &str) -> (usize, usize) {
+    match cipher {
+        "chacha20-poly1305@openssh.com" => (64, 0),
+        "aes256-ctr" => (32, 16),
+        "aes128-ctr" => (16, 16),
+        _ => unreachable!("BUG: cipher {cipher} wasn't negotiated"),
+    }
+}
+
+/// Return `true` if `cipher` authenticates messages itself, so no MAC needs to be negotiated.
+pub(crate) fn cipher_is_aead(cipher: &str) -> bool {
+    cipher == "chacha20-poly1305@openssh.com"
+}
+
+/// Return the length of the key for `mac`.
+pub(crate) fn mac_key_len(mac: &str) -> usize {
+    match mac {
+        "hmac-sha2-256" => 32,
+        "hmac-sha2-512" => 64,
+        _ => unreachable!("BUG: mac {mac} wasn't negotiated"),
+    }
+}
+
+/// The keys for one direction, as derived from a key exchange.
+pub(crate) struct Keys {
+    pub cipher: &'static str,
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+    /// The MAC algorithm and its key, unless `cipher` is an AEAD cipher.
+    pub mac: Option<(&'static str, Vec<u8>)>,
+}
+
+enum Ctr {
+    Aes128(Box<ctr::Ctr128BE<aes::Aes128>>),
+    Aes256(Box<ctr::Ctr128BE<aes::Aes256>>),
+}
+
+impl Ctr {
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        match self {
+            Ctr::Aes128(c) => c.apply_keystream(buf),
+            Ctr::Aes256(c) => c.apply_keystream(buf),
+        }
+    }
+}
+
+enum Mac {
+    Sha256(Vec<u8>),
+    Sha512(Vec<u8>),
+}
+
+impl Mac {
+    fn len(&self) -> usize {
+        match self {
+            Mac::Sha256(_) => 32,
+            Mac::Sha512(_) => 64,
+        }
+    }
+
+    fn compute(&self, seq: u32, packet: &[u8]) -> Vec<u8> {
+        match self {
+            Mac::Sha256(key) => {
+                let mut mac =
+                    <hmac::Hmac<sha2::Sha256> as hmac::Mac>::new_from_slice(key).expect("any key length works");
+                mac.update(&seq.to_be_bytes());
+                mac.update(packet);
+                mac.finalize().into_bytes().to_vec()
+            }
+            Mac::Sha512(key) => {
+                let mut mac =
+                    <hmac::Hmac<sha2::Sha512> as hmac::Mac>::new_from_slice(key).expect("any key length works");
+                mac.update(&seq.to_be_bytes());
+                mac.update(packet);
+                mac.finalize().into_bytes().to_vec()
+            }
+        }
+    }
+}
+
+enum Cipher {
+    /// No encryption, as used before the first key exchange completed.
+    None,
+    /// `chacha20-poly1305@openssh.com`, as specified in OpenSSH's `PROTOCOL.chacha20poly1305`.
+    ChaCha20Poly1305 {
+        /// The key for the payload, `K_2`.
+        main_key: [u8; 32],
+        /// The key for the packet length, `K_1`.
+        header_key: [u8; 32],
+    },
+    AesCtr {
+        ctr: Ctr,
+        mac: Mac,
+    },
+}
+
+impl Cipher {
+    fn new(keys: Keys) -> Self {
+        let Keys { cipher, key, iv, mac } = keys;
+        match cipher {
+            "chacha20-poly1305@openssh.com" => Cipher::ChaCha20Poly1305 {
+                main_key: key[..32].try_into().expect("64 byte key"),
+                header_key: key[32..64].try_into().expect("64 byte key"),
+            },
+            "aes256-ctr" | "aes128-ctr" => {
+                let ctr = if cipher == "aes256-ctr" {
+                    Ctr::Aes256(Box::new(
+                        ctr::Ctr128BE::new_from_slices(&key, &iv).expect("correct key and iv length"),
+                    ))
+                } else {
+                    Ctr::Aes128(Box::new(
+                        ctr::Ctr128BE::new_from_slices(&key, &iv).expect("correct key and iv length"),
+                    ))
+                };
+                let mac = match mac.expect("non-AEAD ciphers have a MAC") {
+                    ("hmac-sha2-256", key) => Mac::Sha256(key),
+                    ("hmac-sha2-512", key) => Mac::Sha512(key),
+                    (name, _) => unreachable!("BUG: mac {name} wasn't negotiated"),
+                };
+                Cipher::AesCtr { ctr, mac }
+            }
+            _ => unreachable!("BUG: cipher {cipher} wasn't negotiated"),
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        match self {
+            Cipher::None | Cipher::ChaCha20Poly1305 { .. } => 8,
+            Cipher::AesCtr { .. } => 16,
+        }
+    }
+}
+
+fn chacha(key: &[u8; 32], seq: u32) -> chacha20::ChaCha20Legacy {
+    let nonce = u64::from(seq).to_be_bytes();
+    chacha20::ChaCha20Legacy::new(key.into(), &nonce.into())
+}
+
+fn poly1305_key(main_key: &[u8; 32], seq: u32) -> poly1305::Key {
+    let mut key = poly1305::Key::default();
+    chacha(main_key, seq).apply_keystream(&mut key);
+    key
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// The state of one direction of the binary packet protocol.
+pub(crate) struct Direction {
+    cipher: Cipher,
+    /// The sequence number of the next packet.
+    seq: u32,
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Direction {
+            cipher: Cipher::None,
+            seq: 0,
+        }
+    }
+}
+
+impl Direction {
+    /// Use `keys` for all subsequent packets.
+    pub fn set_keys(&mut self, keys: Keys) {
+        self.cipher = Cipher::new(keys);
+    }
+
+    /// Start counting packets from zero, as needed in strict key exchange mode after each `NEWKEYS` message.
+    pub fn reset_sequence_number(&mut self) {
+        self.seq = 0;
+    }
+
+    /// Return the sequence number of the next packet.
+    pub fn sequence_number(&self) -> u32 {
+        self.seq
+    }
+
+    /// Write `payload` as packet to `out`.
+    pub fn write_packet(&mut self, out: &mut impl Write, payload: &[u8]) -> Result<(), Error> {
+        let block_size = self.cipher.block_size();
+        let is_aead = matches!(self.cipher, Cipher::ChaCha20Poly1305 { .. });
+        // With AEAD ciphers, the length isn't part of what's encrypted in blocks.
+        let unpadded_len = 1 + payload.len() + if is_aead { 0 } else { 4 };
+        let mut padding_len = block_size - unpadded_len % block_size;
+        if padding_len < 4 {
+            padding_len += block_size;
+        }
+        let packet_len = 1 + payload.len() + padding_len;
+
+        let mut packet = Vec::with_capacity(4 + packet_len + 64);
+        packet.extend_from_slice(&(packet_len as u32).to_be_bytes());
+        packet.push(padding_len as u8);
+        packet.extend_from_slice(payload);
+        let padding_start = packet.len();
+        packet.resize(padding_start + padding_len, 0);
+        rand_core::OsRng.fill_bytes(&mut packet[padding_start..]);
+
+        match &mut self.cipher {
+            Cipher::None => {}
+            Cipher::ChaCha20Poly1305 { main_key, header_key } => {
+                let poly_key = poly1305_key(main_key, self.seq);
+                chacha(header_key, self.seq).apply_keystream(&mut packet[..4]);
+                let mut payload_cipher = chacha(main_key, self.seq);
+                payload_cipher.seek(64);
+                payload_cipher.apply_keystream(&mut packet[4..]);
+                let tag = poly1305::Poly1305::new(&poly_key).compute_unpadded(&packet);
+                packet.extend_from_slice(&tag);
+            }
+            Cipher::AesCtr { ctr, mac } => {
+                let tag = mac.compute(self.seq, &packet);
+                ctr.apply_keystream(&mut packet);
+                packet.extend_from_slice(&tag);
+            }
+        }
+        out.write_all(&packet)?;
+        self.seq = self.seq.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Read the next packet from `input` and return its payload.
+    pub fn read_packet(&mut self, input: &mut impl Read) -> Result<Vec<u8>, Error> {
+        let block_size = self.cipher.block_size();
+        let mut packet = vec![0; block_size];
+        input.read_exact(&mut packet[..4])?;
+
+        let packet_len_of = |header: &[u8]| -> Result<usize, Error> {
+            let len = u32::from_be_bytes(header[..4].try_into().expect("4 bytes")) as usize;
+            if !(5..=MAX_PACKET_LEN).contains(&len) {
+                return Err(Error::InvalidMessage("packet length out of bounds"));
+            }
+            Ok(len)
+        };
+
+        let payload_range = match &mut self.cipher {
+            Cipher::None => {
+                let packet_len = packet_len_of(&packet)?;
+                packet.resize(4 + packet_len, 0);
+                input.read_exact(&mut packet[4..])?;
+                4..packet.len()
+            }
+            Cipher::ChaCha20Poly1305 { main_key, header_key } => {
+                let mut len = [0u8; 4];
+                len.copy_from_slice(&packet[..4]);
+                chacha(header_key, self.seq).apply_keystream(&mut len);
+                let packet_len = packet_len_of(&len)?;
+                packet.resize(4 + packet_len + 16, 0);
+                input.read_exact(&mut packet[4..])?;
+
+                let (ciphertext, tag) = packet.split_at(4 + packet_len);
+                let expected_tag =
+                    poly1305::Poly1305::new(&poly1305_key(main_key, self.seq)).compute_unpadded(ciphertext);
+                if !constant_time_eq(&expected_tag, tag) {
+                    return Err(Error::InvalidMessage("message authentication failed"));
+                }
+                packet.truncate(4 + packet_len);
+                let mut payload_cipher = chacha(main_key, self.seq);
+                payload_cipher.seek(64);
+                payload_cipher.apply_keystream(&mut packet[4..]);
+                4..packet.len()
+            }
+            Cipher::AesCtr { ctr, mac } => {
+                input.read_exact(&mut packet[4..])?;
+                ctr.apply_keystream(&mut packet);
+                let packet_len = packet_len_of(&packet)?;
+                if (4 + packet_len) % block_size != 0 {
+                    return Err(Error::InvalidMessage("packet isn't aligned to the cipher block size"));
+                }
+                packet.resize(4 + packet_len + mac.len(), 0);
+                input.read_exact(&mut packet[block_size..])?;
+                ctr.apply_keystream(&mut packet[block_size..4 + packet_len]);
+
+                let (plaintext, tag) = packet.split_at(4 + packet_len);
+                if !constant_time_eq(&mac.compute(self.seq, plaintext), tag) {
+                    return Err(Error::InvalidMessage("message authentication failed"));
+                }
+                packet.truncate(4 + packet_len);
+                4..packet.len()
+            }
+        };
+        self.seq = self.seq.wrapping_add(1);
+
+        let body = &packet[payload_range];
+        let padding_len = body[0] as usize;
+        if padding_len < 4 || 1 + padding_len > body.len() {
+            return Err(Error::InvalidMessage("invalid padding length"));
+        }
+        let payload = &body[1..body.len() - padding_len];
+        if payload.is_empty() {
+            return Err(Error::InvalidMessage("empty packet"));
+        }
+        Ok(payload.to_vec())
+    }
+}
@@ -0,0 +1,189 @@
+//! The server side of the ssh transport layer of [RFC 4253](https://www.rfc-editor.org/rfc/rfc4253), which sends and
+//! receives encrypted messages after exchanging keys.
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+};
+
+use super::{
+    kex, packet,
+    wire::{msg, Decoder, Encode},
+    Error,
+};
+
+/// An established ssh connection, which has negotiated keys and thus can send and receive messages of the higher-level protocols.
+pub struct Session {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    incoming: packet::Direction,
+    outgoing: packet::Direction,
+    host_key: ssh_key::PrivateKey,
+    local_version: Vec<u8>,
+    remote_version: Vec<u8>,
+    session_id: Vec<u8>,
+    strict_kex: bool,
+}
+
+const VERSION: &str = "SSH-2.0-gix_transport_test_server";
+
+impl Session {
+    /// Exchange versions with the client on `stream`, negotiate keys while identifying with `host_key` and return the
+    /// encrypted session.
+    pub fn establish(stream: TcpStream, host_key: ssh_key::PrivateKey) -> Result<Self, Error> {
+        stream.set_nodelay(true)?;
+        let mut writer = stream.try_clone()?;
+        writer.write_all(format!("{VERSION}\r\n").as_bytes())?;
+        let mut reader = BufReader::new(stream);
+        let remote_version = read_version(&mut reader)?;
+        let mut session = Session {
+            reader,
+            writer,
+            incoming: Default::default(),
+            outgoing: Default::default(),
+            host_key,
+            local_version: VERSION.as_bytes().to_vec(),
+            remote_version,
+            session_id: Vec::new(),
+            strict_kex: false,
+        };
+        session.key_exchange(None)?;
+        Ok(session)
+    }
+
+    /// The session identifier, which is the exchange hash of the first key exchange.
+    pub fn session_id(&self) -> &[u8] {
+        &self.session_id
+    }
+
+    /// Send a message with the given `payload`.
+    pub fn send(&mut self, payload: &[u8]) -> Result<(), Error> {
+        self.outgoing.write_packet(&mut self.writer, payload)
+    }
+
+    /// Receive the next message that isn't handled by the transport layer itself.
+    ///
+    /// Key re-exchanges initiated by the client are performed transparently, and a disconnect is turned into an error.
+    pub fn recv(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            let payload = self.incoming.read_packet(&mut self.reader)?;
+            match payload[0] {
+                msg::DISCONNECT => return Err(Error::Disconnected),
+                msg::IGNORE | msg::DEBUG | msg::UNIMPLEMENTED => continue,
+                msg::KEXINIT => self.key_exchange(Some(payload))?,
+                _ => return Ok(payload),
+            }
+        }
+    }
+
+    /// Receive a message that is part of the key exchange, which is the only kind of message allowed during the key exchange.
+    fn recv_kex_message(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            let payload = self.incoming.read_packet(&mut self.reader)?;
+            match payload[0] {
+                msg::DISCONNECT => return Err(Error::Disconnected),
+                msg::IGNORE | msg::DEBUG | msg::UNIMPLEMENTED if !self.strict_kex => continue,
+                _ => return Ok(payload),
+            }
+        }
+    }
+
+    fn expect_kex_message(&mut self, expected: u8) -> Result<Vec<u8>, Error> {
+        let payload = self.recv_kex_message()?;
+        if payload[0] != expected {
+            return Err(Error::UnexpectedMessage(payload[0]));
+        }
+        Ok(payload)
+    }
+
+    /// Perform a key exchange, either initially or because the client sent `client_init`, its `KEXINIT` payload.
+    fn key_exchange(&mut self, client_init: Option<Vec<u8>>) -> Result<(), Error> {
+        let is_initial = self.session_id.is_empty();
+        let server_init = kex::init_message(self.host_key.algorithm().as_str());
+        self.send(&server_init)?;
+        let client_init = match client_init {
+            Some(init) => init,
+            None => self.expect_kex_message(msg::KEXINIT)?,
+        };
+        let negotiated = kex::negotiate(&client_init, &server_init)?;
+        if is_initial {
+            self.strict_kex = negotiated.strict;
+            if self.strict_kex && self.incoming.sequence_number() != 1 {
+                return Err(Error::InvalidMessage(
+                    "KEXINIT must be the first message in strict key exchange mode",
+                ));
+            }
+        }
+        if negotiated.ignore_guessed_packet {
+            self.recv_kex_message()?;
+        }
+
+        let init = self.expect_kex_message(msg::KEX_ECDH_INIT)?;
+        let client_ephemeral = Decoder::new(&init[1..]).string()?;
+        let client_ephemeral_key: [u8; 32] = client_ephemeral
+            .try_into()
+            .map_err(|_| Error::InvalidMessage("ephemeral keys must have 32 bytes"))?;
+        let secret = x25519_dalek::EphemeralSecret::random_from_rng(rand_core::OsRng);
+        let ephemeral = x25519_dalek::PublicKey::from(&secret);
+        let shared_secret = secret.diffie_hellman(&client_ephemeral_key.into());
+        let host_key_blob = self.host_key.public_key().to_bytes()?;
+        let exchange_hash = kex::ExchangeHashInput {
+            client_version: &self.remote_version,
+            server_version: &self.local_version,
+            client_init: &client_init,
+            server_init: &server_init,
+            host_key: &host_key_blob,
+            client_ephemeral,
+            server_ephemeral: ephemeral.as_bytes(),
+            shared_secret: shared_secret.as_bytes(),
+        }
+        .hash();
+        let signature: ssh_key::Signature = signature::Signer::try_sign(&self.host_key, &exchange_hash)?;
+
+        let mut reply = vec![msg::KEX_ECDH_REPLY];
+        reply
+            .put_string(&host_key_blob)
+            .put_string(ephemeral.as_bytes())
+            .put_string(Vec::<u8>::try_from(signature)?);
+        self.send(&reply)?;
+        if is_initial {
+            self.session_id = exchange_hash.clone();
+        }
+
+        let (client_to_server, server_to_client) =
+            kex::derive_keys(&negotiated, shared_secret.as_bytes(), &exchange_hash, &self.session_id);
+        self.send(&[msg::NEWKEYS])?;
+        self.outgoing.set_keys(server_to_client);
+        if self.strict_kex {
+            self.outgoing.reset_sequence_number();
+        }
+        self.expect_kex_message(msg::NEWKEYS)?;
+        self.incoming.set_keys(client_to_server);
+        if self.strict_kex {
+            self.incoming.reset_sequence_number();
+        }
+        Ok(())
+    }
+}
+
+/// Read the identification string of the client, skipping all lines before it as allowed by RFC 4253.
+fn read_version(reader: &mut BufReader<TcpStream>) -> Result<Vec<u8>, Error> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.by_ref().take(8192).read_until(b'\n', &mut line)? == 0 {
+            return Err(Error::InvalidMessage("connection closed before receiving the version"));
+        }
+        if !line.starts_with(b"SSH-") {
+            continue;
+        }
+        while matches!(line.last(), Some(b'\n' | b'\r')) {
+            line.pop();
+        }
+        if !line.starts_with(b"SSH-2.0-") {
+            return Err(Error::InvalidMessage(
+                "the client doesn't support ssh protocol version 2",
+            ));
+        }
+        return Ok(line);
+    }
+}
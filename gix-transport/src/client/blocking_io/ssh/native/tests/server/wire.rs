@@ -0,0 +1,133 @@
+//! Encoding and decoding of the data types used in ssh messages, as described in [RFC 4251, section 5](https://www.rfc-editor.org/rfc/rfc4251#section-5).
+use super::Error;
+
+/// The message numbers defined in RFC 4250 which the server uses.
+pub(crate) mod msg {
+    pub const DISCONNECT: u8 = 1;
+    pub const IGNORE: u8 = 2;
+    pub const UNIMPLEMENTED: u8 = 3;
+    pub const DEBUG: u8 = 4;
+    pub const SERVICE_REQUEST: u8 = 5;
+    pub const SERVICE_ACCEPT: u8 = 6;
+    pub const KEXINIT: u8 = 20;
+    pub const NEWKEYS: u8 = 21;
+    pub const KEX_ECDH_INIT: u8 = 30;
+    pub const KEX_ECDH_REPLY: u8 = 31;
+    pub const USERAUTH_REQUEST: u8 = 50;
+    pub const USERAUTH_FAILURE: u8 = 51;
+    pub const USERAUTH_SUCCESS: u8 = 52;
+    pub const USERAUTH_PK_OK: u8 = 60;
+    pub const CHANNEL_OPEN: u8 = 90;
+    pub const CHANNEL_OPEN_CONFIRMATION: u8 = 91;
+    pub const CHANNEL_WINDOW_ADJUST: u8 = 93;
+    pub const CHANNEL_DATA: u8 = 94;
+    pub const CHANNEL_EOF: u8 = 96;
+    pub const CHANNEL_CLOSE: u8 = 97;
+    pub const CHANNEL_REQUEST: u8 = 98;
+    pub const CHANNEL_SUCCESS: u8 = 99;
+}
+
+/// A way to append ssh data types to a message.
+pub(crate) trait Encode {
+    fn put_u8(&mut self, value: u8) -> &mut Self;
+    fn put_bool(&mut self, value: bool) -> &mut Self;
+    fn put_u32(&mut self, value: u32) -> &mut Self;
+    fn put_string(&mut self, value: impl AsRef<[u8]>) -> &mut Self;
+    fn put_name_list(&mut self, names: &[&str]) -> &mut Self;
+    /// Put `value`, an unsigned big-endian number, as `mpint`.
+    fn put_mpint(&mut self, value: &[u8]) -> &mut Self;
+}
+
+impl Encode for Vec<u8> {
+    fn put_u8(&mut self, value: u8) -> &mut Self {
+        self.push(value);
+        self
+    }
+
+    fn put_bool(&mut self, value: bool) -> &mut Self {
+        self.put_u8(value.into())
+    }
+
+    fn put_u32(&mut self, value: u32) -> &mut Self {
+        self.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn put_string(&mut self, value: impl AsRef<[u8]>) -> &mut Self {
+        let value = value.as_ref();
+        self.put_u32(value.len() as u32);
+        self.extend_from_slice(value);
+        self
+    }
+
+    fn put_name_list(&mut self, names: &[&str]) -> &mut Self {
+        self.put_string(names.join(","))
+    }
+
+    fn put_mpint(&mut self, value: &[u8]) -> &mut Self {
+        let start = value.iter().position(|b| *b != 0).unwrap_or(value.len());
+        let value = &value[start..];
+        let needs_sign_byte = value.first().map_or(false, |b| b & 0x80 != 0);
+        self.put_u32((value.len() + usize::from(needs_sign_byte)) as u32);
+        if needs_sign_byte {
+            self.push(0);
+        }
+        self.extend_from_slice(value);
+        self
+    }
+}
+
+/// Decode ssh data types from a message.
+pub(crate) struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Decoder { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::InvalidMessage("message ended unexpectedly"));
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")))
+    }
+
+    pub fn string(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub fn utf8(&mut self) -> Result<&'a str, Error> {
+        std::str::from_utf8(self.string()?).map_err(|_| Error::InvalidMessage("a string wasn't valid UTF-8"))
+    }
+
+    pub fn name_list(&mut self) -> Result<Vec<&'a str>, Error> {
+        let names = self.utf8()?;
+        Ok(if names.is_empty() {
+            Vec::new()
+        } else {
+            names.split(',').collect()
+        })
+    }
+
+    /// Skip `len` bytes.
+    pub fn skip(&mut self, len: usize) -> Result<(), Error> {
+        self.take(len).map(|_| ())
+    }
+}
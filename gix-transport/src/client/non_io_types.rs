@@ -61,11 +61,20 @@ pub(crate) mod connect {
         },
         #[error("The '{0}' protocol is currently unsupported")]
         UnsupportedScheme(gix_url::Scheme),
-        #[cfg(not(any(feature = "http-client-curl", feature = "http-client-reqwest")))]
+        #[cfg(all(
+            not(feature = "async-client"),
+            not(any(feature = "http-client-curl", feature = "http-client-reqwest"))
+        ))]
         #[error(
             "'{0}' is not compiled in. Compile with the 'http-client-curl' or 'http-client-reqwest' cargo feature"
         )]
         CompiledWithoutHttp(gix_url::Scheme),
+        #[cfg(all(feature = "async-client", not(feature = "async-http-client-reqwest")))]
+        #[error("'{0}' is not compiled in. Compile with the 'async-http-client-reqwest' cargo feature")]
+        CompiledWithoutHttp(gix_url::Scheme),
+        #[cfg(all(feature = "blocking-client", not(feature = "ssh-client-native")))]
+        #[error("The native ssh client is not compiled in. Compile with the 'ssh-client-native' cargo feature")]
+        CompiledWithoutNativeSsh,
     }
 
     // TODO: maybe fix this workaround: want `IsSpuriousError`  in `Connection(…)`
@@ -94,19 +103,23 @@ mod error {
     use bstr::BString;
 
     use crate::client::capabilities;
-    #[cfg(feature = "http-client")]
+    #[cfg(any(feature = "http-client", feature = "async-http-client"))]
     use crate::client::http;
     #[cfg(feature = "blocking-client")]
     use crate::client::ssh;
 
-    #[cfg(feature = "http-client")]
+    #[cfg(any(feature = "http-client", feature = "async-http-client"))]
     type HttpError = http::Error;
     #[cfg(feature = "blocking-client")]
     type SshInvocationError = ssh::invocation::Error;
-    #[cfg(not(feature = "http-client"))]
+    #[cfg(not(any(feature = "http-client", feature = "async-http-client")))]
     type HttpError = std::convert::Infallible;
     #[cfg(not(feature = "blocking-client"))]
     type SshInvocationError = std::convert::Infallible;
+    #[cfg(feature = "ssh-client-native")]
+    type NativeSshError = ssh::native::Error;
+    #[cfg(not(feature = "ssh-client-native"))]
+    type NativeSshError = std::convert::Infallible;
 
     /// The error used in most methods of the [`client`][crate::client] module
     #[derive(thiserror::Error, Debug)]
@@ -140,8 +153,12 @@ mod error {
         Http(#[from] HttpError),
         #[error(transparent)]
         SshInvocation(SshInvocationError),
+        #[error(transparent)]
+        NativeSsh(NativeSshError),
         #[error("The repository path '{path}' could be mistaken for a command-line argument")]
         AmbiguousPath { path: BString },
+        #[error("The server only supports the 'dumb' HTTP protocol as it didn't respond with a 'smart' service advertisement")]
+        DumbHttpServer,
     }
 
     impl crate::IsSpuriousError for Error {
@@ -149,6 +166,7 @@ mod error {
             match self {
                 Error::Io(err) => err.is_spurious(),
                 Error::Http(err) => err.is_spurious(),
+                Error::NativeSsh(err) => err.is_spurious(),
                 _ => false,
             }
         }
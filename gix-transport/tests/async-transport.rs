@@ -10,5 +10,9 @@ pub fn fixture_bytes(path: &str) -> Vec<u8> {
     std::fs::read(fixture_path(path)).expect("fixture to be present and readable")
 }
 
-#[cfg(not(any(feature = "blocking-client", feature = "http-client-curl")))]
+#[cfg(not(any(
+    feature = "blocking-client",
+    feature = "http-client-curl",
+    feature = "async-http-client-reqwest"
+)))]
 mod client;
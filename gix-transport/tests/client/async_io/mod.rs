@@ -0,0 +1,2 @@
+#[cfg(feature = "async-http-client-reqwest")]
+mod http;
@@ -1,4 +1,12 @@
+#[cfg(feature = "async-client")]
+mod async_io;
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
 mod capabilities;
 mod git;
+#[cfg(any(
+    feature = "http-client-curl",
+    feature = "http-client-reqwest",
+    feature = "async-http-client-reqwest"
+))]
+mod mock;
@@ -24,6 +24,12 @@ pub mod parse;
 
 /// Parse the given `bytes` as a [git url](Url).
 ///
+/// Inputs are classified just like `git` does it: everything containing `://` is a URL, paths without a colon
+/// or with a slash before the first colon are local paths, and everything else is an scp-like target like `host:path`.
+/// `ssh`, `git` and `file` URLs are percent-decoded, and `~` at the beginning of `ssh` and `git` paths refers
+/// to a home directory, just as in `git`.
+/// Hosts and ports are kept as given, so upper-case hosts aren't lower-cased and default ports like `:443` aren't dropped.
+///
 /// # Note
 ///
 /// We cannot and should never have to deal with UTF-16 encoded windows strings, so bytes input is acceptable.
@@ -36,7 +42,7 @@ pub fn parse(input: &BStr) -> Result<Url, parse::Error> {
             parse::file_url(input, protocol_end)
         }
         InputScheme::Url { protocol_end } => parse::url(input, protocol_end),
-        InputScheme::Scp { colon } => parse::scp(input, colon),
+        InputScheme::Scp => parse::scp(input),
     }
 }
 
@@ -83,6 +89,8 @@ pub struct Url {
     pub port: Option<u16>,
     /// The path portion of the URL, usually the location of the git repository.
     ///
+    /// Paths of `ssh` and `git` URLs don't begin with a slash if they are relative to a home directory, like `~/repo` or `~user/repo`.
+    ///
     /// # Security-Warning
     ///
     /// URLs allow paths to start with `-` which makes it possible to mask command-line arguments as path which then leads to
@@ -190,13 +198,12 @@ impl Url {
     }
 
     /// Return the path of this URL *and* if it can't be mistaken for a command-line argument.
-    /// Note that it always begins with a slash, which is ignored for this comparison.
+    /// Note that a leading slash is ignored for this comparison.
     ///
     /// Use this method if the path is going to be passed to a command-line application.
     pub fn path_argument_safe(&self) -> Option<&BStr> {
-        self.path
-            .get(1..)
-            .and_then(|truncated| (!looks_like_argument(truncated)).then_some(self.path.as_ref()))
+        let path = self.path.strip_prefix(b"/").unwrap_or(&self.path);
+        (!looks_like_argument(path)).then_some(self.path.as_ref())
     }
 
     /// Returns true if the path portion of the url is `/`.
@@ -238,33 +245,64 @@ impl Url {
 impl Url {
     /// Write this URL losslessly to `out`, ready to be parsed again.
     pub fn write_to(&self, mut out: &mut dyn std::io::Write) -> std::io::Result<()> {
-        if !(self.serialize_alternative_form && (self.scheme == Scheme::File || self.scheme == Scheme::Ssh)) {
+        let alternative_form =
+            self.serialize_alternative_form && (self.scheme == Scheme::File || self.scheme == Scheme::Ssh);
+        if !alternative_form {
             out.write_all(self.scheme.as_str().as_bytes())?;
             out.write_all(b"://")?;
         }
-        match (&self.user, &self.host) {
-            (Some(user), Some(host)) => {
-                out.write_all(user.as_bytes())?;
-                if let Some(password) = &self.password {
-                    out.write_all(&[b':'])?;
-                    out.write_all(password.as_bytes())?;
+        // These URLs are percent-decoded when parsed, just like git does before connecting, so `%` has to be escaped.
+        let escape_percent = !alternative_form && matches!(self.scheme, Scheme::File | Scheme::Ssh | Scheme::Git);
+        let write = |out: &mut dyn std::io::Write, bytes: &[u8]| -> std::io::Result<()> {
+            if escape_percent {
+                for chunk in bytes.split_inclusive(|b| *b == b'%') {
+                    out.write_all(chunk)?;
+                    if chunk.ends_with(b"%") {
+                        out.write_all(b"25")?;
+                    }
                 }
-                out.write_all(&[b'@'])?;
-                out.write_all(host.as_bytes())?;
-            }
-            (None, Some(host)) => {
-                out.write_all(host.as_bytes())?;
+                Ok(())
+            } else {
+                out.write_all(bytes)
             }
-            (None, None) => {}
-            (Some(_user), None) => unreachable!("BUG: should not be possible to have a user but no host"),
         };
+        // scp-like URLs can only have a port if the host is in brackets, like `[host:22]:path`.
+        let scp_with_port = alternative_form && self.scheme == Scheme::Ssh && self.port.is_some();
+        if scp_with_port {
+            out.write_all(b"[")?;
+        }
+        if let Some(user) = &self.user {
+            write(out, user.as_bytes())?;
+            if let Some(password) = &self.password {
+                out.write_all(b":")?;
+                write(out, password.as_bytes())?;
+            }
+            out.write_all(b"@")?;
+        }
+        if let Some(host) = &self.host {
+            let needs_brackets = !scp_with_port && self.scheme != Scheme::File && host.contains([':', '/']);
+            if needs_brackets {
+                out.write_all(b"[")?;
+            }
+            write(out, host.as_bytes())?;
+            if needs_brackets {
+                out.write_all(b"]")?;
+            }
+        }
         if let Some(port) = &self.port {
             write!(&mut out, ":{port}")?;
         }
+        if scp_with_port {
+            out.write_all(b"]")?;
+        }
         if self.serialize_alternative_form && self.scheme == Scheme::Ssh {
             out.write_all(b":")?;
+        } else if !alternative_form && matches!(self.scheme, Scheme::Ssh | Scheme::Git) && !self.path.starts_with(b"/")
+        {
+            // Paths relative to a home directory, like `~/repo`, are written as `/~/repo`.
+            out.write_all(b"/")?;
         }
-        out.write_all(&self.path)?;
+        write(out, &self.path)?;
         Ok(())
     }
 
@@ -1,4 +1,4 @@
-use std::convert::Infallible;
+use std::{borrow::Cow, convert::Infallible};
 
 use bstr::{BStr, BString, ByteSlice};
 
@@ -14,13 +14,10 @@ pub enum Error {
         kind: UrlKind,
         source: std::str::Utf8Error,
     },
-    #[error("{} {url:?} can not be parsed as valid URL", kind.as_str())]
-    Url {
-        url: String,
-        kind: UrlKind,
-        source: url::ParseError,
-    },
-
+    #[error("URL \"{url}\" has an invalid port number: \"{port}\"")]
+    InvalidPort { url: BString, port: BString },
+    #[error("URL \"{url}\" does not specify a host")]
+    MissingHost { url: BString },
     #[error("The host portion of the following URL is too long ({} bytes, {len} bytes total): {truncated_url:?}", truncated_url.len())]
     TooLong { truncated_url: BString, len: usize },
     #[error("{} \"{url}\" does not specify a path to a repository", kind.as_str())]
@@ -58,29 +55,43 @@ impl UrlKind {
 
 pub(crate) enum InputScheme {
     Url { protocol_end: usize },
-    Scp { colon: usize },
+    Scp,
     Local,
 }
 
+/// Classify `input` just like `parse_connect_url()` in git's `connect.c` does.
 pub(crate) fn find_scheme(input: &BStr) -> InputScheme {
-    // TODO: url's may only contain `:/`, we should additionally check if the characters used for
-    //       protocol are all valid
+    // Like git, anything containing `://` is a URL, even though only valid schemes are accepted later.
     if let Some(protocol_end) = input.find("://") {
         return InputScheme::Url { protocol_end };
     }
 
-    if let Some(colon) = input.find_byte(b':') {
-        // allow user to select files containing a `:` by passing them as absolute or relative path
-        // this is behavior explicitly mentioned by the scp and git manuals
-        let explicitly_local = &input[..colon].contains(&b'/');
-        let dos_driver_letter = cfg!(windows) && input[..colon].len() == 1;
+    if is_local_not_ssh(input) {
+        InputScheme::Local
+    } else {
+        InputScheme::Scp
+    }
+}
 
-        if !explicitly_local && !dos_driver_letter {
-            return InputScheme::Scp { colon };
-        }
+/// Mirrors `url_is_local_not_ssh()` of git: files containing a `:` can be selected by passing them as absolute or
+/// relative path, as explicitly mentioned by the scp and git manuals. On Windows, drive letters are local as well.
+fn is_local_not_ssh(input: &[u8]) -> bool {
+    match input.find_byte(b':') {
+        Some(colon) => input[..colon].contains(&b'/') || (cfg!(windows) && has_dos_drive_prefix(input)),
+        None => true,
     }
+}
 
-    InputScheme::Local
+fn has_dos_drive_prefix(input: &[u8]) -> bool {
+    input.len() >= 2 && input[0].is_ascii_alphabetic() && input[1] == b':'
+}
+
+/// Mirrors `is_urlschemechar()` of git.
+fn is_valid_scheme(scheme: &[u8]) -> bool {
+    matches!(scheme.first(), Some(b) if b.is_ascii_alphabetic())
+        && scheme[1..]
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
 }
 
 pub(crate) fn url(input: &BStr, protocol_end: usize) -> Result<crate::Url, Error> {
@@ -97,85 +108,275 @@ pub(crate) fn url(input: &BStr, protocol_end: usize) -> Result<crate::Url, Error
             len: input.len(),
         });
     }
-    let (input, url) = input_to_utf8_and_url(input, UrlKind::Url)?;
-    let scheme = url.scheme().into();
 
-    if matches!(scheme, Scheme::Git | Scheme::Ssh) && url.path().is_empty() {
+    let scheme = &input[..protocol_end];
+    if !is_valid_scheme(scheme) {
+        return Err(Error::RelativeUrl {
+            url: input.to_str_lossy().into_owned(),
+        });
+    }
+    let scheme = Scheme::from(scheme.to_ascii_lowercase().to_str().expect("valid schemes are ASCII"));
+    let rest = &input[protocol_end + "://".len()..];
+    match scheme {
+        Scheme::Ssh | Scheme::Git => connect_url(input, scheme, percent_decode(rest).as_ref()),
+        _ => generic_url(input, scheme, rest),
+    }
+}
+
+/// Parse `rest` of `ssh` and `git` URLs after the `://`, which git decodes before it connects.
+fn connect_url(input: &BStr, scheme: Scheme, rest: &BStr) -> Result<crate::Url, Error> {
+    let host_end = find_brackets(rest, b'/').map_or(0, |(_open, close)| close);
+    let Some(path_start) = rest[host_end..].find_byte(b'/').map(|pos| pos + host_end) else {
         return Err(Error::MissingRepositoryPath {
             url: input.into(),
             kind: UrlKind::Url,
         });
-    }
+    };
+    let (user, password, host, port) =
+        user_host_and_port(input, &rest[..path_start], scheme == Scheme::Ssh, UrlKind::Url)?;
+    Ok(crate::Url {
+        serialize_alternative_form: false,
+        scheme,
+        user,
+        password,
+        host,
+        port,
+        path: without_slash_before_tilde(&rest[path_start..]).into(),
+    })
+}
 
-    if url.cannot_be_a_base() {
-        return Err(Error::RelativeUrl { url: input.to_owned() });
+/// Parse `rest` of URLs after the `://` which git hands to other programs, like `curl` for `http` and `https`, without
+/// decoding or normalizing them. The port is validated like `url_normalize()` in git's `urlmatch.c` does it.
+fn generic_url(input: &BStr, scheme: Scheme, rest: &[u8]) -> Result<crate::Url, Error> {
+    let authority_end = rest.find_byteset(b"/?#").unwrap_or(rest.len());
+    let authority = &rest[..authority_end];
+    let (user_info, host_and_port) = match authority.find_byte(b'@') {
+        Some(at) => (Some(&authority[..at]), &authority[at + 1..]),
+        None => (None, authority),
+    };
+    let (host, port) = match host_and_port
+        .rfind_byte(b':')
+        .filter(|colon| !host_and_port[*colon..].contains(&b']'))
+    {
+        Some(colon) => {
+            let port = &host_and_port[colon + 1..];
+            let parsed_port = (!port.is_empty())
+                .then(|| {
+                    port.iter()
+                        .all(u8::is_ascii_digit)
+                        .then(|| port.to_str().ok()?.parse::<u16>().ok())
+                        .flatten()
+                        .filter(|port| *port != 0)
+                        .ok_or_else(|| Error::InvalidPort {
+                            url: input.into(),
+                            port: port.into(),
+                        })
+                })
+                .transpose()?;
+            (&host_and_port[..colon], parsed_port)
+        }
+        None => (host_and_port, None),
+    };
+    let host = host
+        .strip_prefix(b"[")
+        .and_then(|host| host.strip_suffix(b"]"))
+        .unwrap_or(host);
+    if host.is_empty() && matches!(scheme, Scheme::Http | Scheme::Https) {
+        return Err(Error::MissingHost { url: input.into() });
+    }
+    let (user, password) = user_and_password(input, user_info, UrlKind::Url)?;
+    let mut path = BString::from(&rest[authority_end..]);
+    if matches!(scheme, Scheme::Http | Scheme::Https) && !path.starts_with(b"/") {
+        // Like `curl` does, request the root of the server if there is no path.
+        path.insert(0, b'/');
     }
-
     Ok(crate::Url {
         serialize_alternative_form: false,
         scheme,
-        user: url_user(&url),
-        password: url.password().map(Into::into),
-        host: url.host_str().map(Into::into),
-        port: url.port(),
-        path: url.path().into(),
+        user,
+        password,
+        host: non_empty_string(input, host, UrlKind::Url)?,
+        port,
+        path,
     })
 }
 
-pub(crate) fn scp(input: &BStr, colon: usize) -> Result<crate::Url, Error> {
-    let input = input_to_utf8(input, UrlKind::Scp)?;
-
-    // TODO: this incorrectly splits at IPv6 addresses, check for `[]` before splitting
-    let (host, path) = input.split_at(colon);
-    debug_assert_eq!(path.get(..1), Some(":"), "{path} should start with :");
-    let path = &path[1..];
-
-    if path.is_empty() {
+/// Parse `[user@]host:path`, with the host optionally in brackets to allow it to contain colons, like git does.
+pub(crate) fn scp(input: &BStr) -> Result<crate::Url, Error> {
+    let host_end = find_brackets(input, b':').map_or(0, |(_open, close)| close);
+    let Some(colon) = input[host_end..].find_byte(b':').map(|pos| pos + host_end) else {
         return Err(Error::MissingRepositoryPath {
-            url: input.to_owned().into(),
+            url: input.into(),
             kind: UrlKind::Scp,
         });
+    };
+    let (user, password, host, port) = user_host_and_port(input, &input[..colon], true, UrlKind::Scp)?;
+    Ok(crate::Url {
+        serialize_alternative_form: true,
+        scheme: Scheme::Ssh,
+        user,
+        password,
+        host,
+        port,
+        path: without_slash_before_tilde(&input[colon + 1..]).into(),
+    })
+}
+
+type UserPasswordHostPort = (Option<String>, Option<String>, Option<String>, Option<u16>);
+
+/// Split `user[:password]@host[:port]` like git does before connecting with `ssh` or to a `git` daemon, see `get_host_and_port()`
+/// in its `connect.c`. Brackets around the host, which are needed for IPv6 addresses, are removed, and a port is only split off
+/// if it's a valid port number. With `port_in_brackets`, the port may also be inside the brackets, like in `[host:22]`, which
+/// git only supports for `ssh`.
+fn user_host_and_port(
+    input: &BStr,
+    user_host_and_port: &[u8],
+    port_in_brackets: bool,
+    kind: UrlKind,
+) -> Result<UserPasswordHostPort, Error> {
+    let (user_info, host, port) = match find_brackets(user_host_and_port, b'/') {
+        Some((open, close)) => {
+            let (user_info, mut host) = match open.checked_sub(1) {
+                Some(at) => (Some(&user_host_and_port[..at]), &user_host_and_port[open + 1..close]),
+                None => split_user_info(&user_host_and_port[open + 1..close]),
+            };
+            let after_host = &user_host_and_port[close + 1..];
+            let mut port = after_host
+                .find_byte(b':')
+                .and_then(|colon| parse_port(&after_host[colon + 1..]));
+            if port.is_none() && port_in_brackets {
+                if let Some((colon, host_port)) = host
+                    .find_byte(b':')
+                    .and_then(|colon| parse_port(&host[colon + 1..]).map(|port| (colon, port)))
+                {
+                    port = Some(host_port);
+                    host = &host[..colon];
+                }
+            }
+            (user_info, host, port)
+        }
+        None => {
+            let (user_info, host_and_port) = split_user_info(user_host_and_port);
+            match host_and_port.find_byte(b':') {
+                Some(colon) => match parse_port(&host_and_port[colon + 1..]) {
+                    Some(port) => (user_info, &host_and_port[..colon], Some(port)),
+                    None if colon + 1 == host_and_port.len() => (user_info, &host_and_port[..colon], None),
+                    None => (user_info, host_and_port, None),
+                },
+                None => (user_info, host_and_port, None),
+            }
+        }
+    };
+    let (user, password) = user_and_password(input, user_info, kind)?;
+    Ok((user, password, non_empty_string(input, host, kind)?, port))
+}
+
+/// Return the positions of the opening and closing brackets if `input` starts with a host in brackets, optionally preceded by
+/// `user@`, like `host_end()` in git's `connect.c`. Only the portion before `stop` is searched for `@[`.
+fn find_brackets(input: &[u8], stop: u8) -> Option<(usize, usize)> {
+    let before_stop = &input[..input.find_byte(stop).unwrap_or(input.len())];
+    let open = before_stop.find("@[").map_or(0, |at| at + 1);
+    if input.get(open) != Some(&b'[') {
+        return None;
     }
+    let close = input[open + 1..].find_byte(b']')? + open + 1;
+    Some((open, close))
+}
 
-    // The path returned by the parsed url often has the wrong number of leading `/` characters but
-    // should never differ in any other way (ssh URLs should not contain a query or fragment part).
-    // To avoid the various off-by-one errors caused by the `/` characters, we keep using the path
-    // determined above and can therefore skip parsing it here as well.
-    let url = url::Url::parse(&format!("ssh://{host}")).map_err(|source| Error::Url {
-        url: input.to_owned(),
-        kind: UrlKind::Scp,
-        source,
-    })?;
+/// Split `user@host` at the last `@`, just like `ssh` does.
+fn split_user_info(input: &[u8]) -> (Option<&[u8]>, &[u8]) {
+    match input.rfind_byte(b'@') {
+        Some(at) => (Some(&input[..at]), &input[at + 1..]),
+        None => (None, input),
+    }
+}
 
-    Ok(crate::Url {
-        serialize_alternative_form: true,
-        scheme: url.scheme().into(),
-        user: url_user(&url),
-        password: url.password().map(Into::into),
-        host: url.host_str().map(Into::into),
-        port: url.port(),
-        path: path.into(),
+fn user_and_password(
+    input: &BStr,
+    user_info: Option<&[u8]>,
+    kind: UrlKind,
+) -> Result<(Option<String>, Option<String>), Error> {
+    let Some(user_info) = user_info else {
+        return Ok((None, None));
+    };
+    let (user, password) = match user_info.find_byte(b':') {
+        Some(colon) => (
+            &user_info[..colon],
+            non_empty_string(input, &user_info[colon + 1..], kind)?,
+        ),
+        None => (user_info, None),
+    };
+    Ok(if user.is_empty() && password.is_none() {
+        (None, None)
+    } else {
+        (Some(to_string(input, user, kind)?), password)
     })
 }
 
-fn url_user(url: &url::Url) -> Option<String> {
-    if url.username().is_empty() && url.password().is_none() {
-        None
+/// Parse `port` like `strtol()` would and return it if it's a valid port number, which is what git does.
+fn parse_port(port: &[u8]) -> Option<u16> {
+    let port = &port[port.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+    let digits = port.strip_prefix(b"+").unwrap_or(port);
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    digits.to_str().ok()?.parse().ok()
+}
+
+/// Like git, don't treat `~` in paths like `/~user/repo` as part of the path, but as marker for the home directory on the remote.
+/// Note that git checks the second character of scp-like paths as well, even if the first one isn't a `/`.
+fn without_slash_before_tilde(path: &[u8]) -> &[u8] {
+    if path.get(1) == Some(&b'~') {
+        &path[1..]
     } else {
-        Some(url.username().into())
+        path
     }
 }
 
-pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url, Error> {
-    let input = input_to_utf8(input, UrlKind::Url)?;
-    let input_after_protocol = &input[protocol_colon + "://".len()..];
+/// Decode `%XX` sequences like `url_decode()` in git, which leaves invalid sequences and those that would decode into `NUL` as is.
+pub fn percent_decode(input: &[u8]) -> Cow<'_, BStr> {
+    if !input.contains(&b'%') {
+        return Cow::Borrowed(input.as_bstr());
+    }
+    fn hex_value(b: u8) -> Option<u8> {
+        (b as char).to_digit(16).map(|v| v as u8)
+    }
+    let mut out = Vec::with_capacity(input.len());
+    let mut pos = 0;
+    while pos < input.len() {
+        if input[pos] == b'%' {
+            if let Some(byte) = input
+                .get(pos + 1..pos + 3)
+                .and_then(|hex| Some(hex_value(hex[0])? << 4 | hex_value(hex[1])?))
+                .filter(|byte| *byte != 0)
+            {
+                out.push(byte);
+                pos += 3;
+                continue;
+            }
+        }
+        out.push(input[pos]);
+        pos += 1;
+    }
+    Cow::Owned(out.into())
+}
 
-    let Some(first_slash) = input_after_protocol
-        .find('/')
-        .or_else(|| cfg!(windows).then(|| input_after_protocol.find('\\')).flatten())
+pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url, Error> {
+    let input_after_protocol = percent_decode(&input[protocol_colon + "://".len()..]);
+    let input_after_protocol = input_after_protocol.as_ref();
+
+    let host_end = find_brackets(input_after_protocol, b'/').map_or(0, |(_open, close)| close);
+    let Some(first_slash) = input_after_protocol[host_end..]
+        .find_byte(b'/')
+        .or_else(|| {
+            cfg!(windows)
+                .then(|| input_after_protocol[host_end..].find_byte(b'\\'))
+                .flatten()
+        })
+        .map(|pos| pos + host_end)
     else {
         return Err(Error::MissingRepositoryPath {
-            url: input.to_owned().into(),
+            url: input.to_owned(),
             kind: UrlKind::Url,
         });
     };
@@ -197,7 +398,7 @@ pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url
             input_after_protocol
         };
         // parse `file://x:/path/to/git` as explained above
-        if input_after_protocol.chars().nth(1) == Some(':') {
+        if input_after_protocol.get(1) == Some(&b':') {
             Some(input_after_protocol)
         } else {
             None
@@ -211,7 +412,7 @@ pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url
         None
     } else {
         // `file://host/path/to/git`
-        Some(&input_after_protocol[..first_slash])
+        Some(to_string(input, &input_after_protocol[..first_slash], UrlKind::Url)?)
     };
 
     // default behavior on Unix platforms and if no Windows special case was triggered
@@ -219,8 +420,8 @@ pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url
 
     Ok(crate::Url {
         serialize_alternative_form: false,
-        host: host.map(Into::into),
-        ..local(path.into())?
+        host,
+        ..local(path)?
     })
 }
 
@@ -243,19 +444,18 @@ pub(crate) fn local(input: &BStr) -> Result<crate::Url, Error> {
     })
 }
 
-fn input_to_utf8(input: &BStr, kind: UrlKind) -> Result<&str, Error> {
-    std::str::from_utf8(input).map_err(|source| Error::Utf8 {
-        url: input.to_owned(),
-        kind,
-        source,
-    })
+fn non_empty_string(input: &BStr, value: &[u8], kind: UrlKind) -> Result<Option<String>, Error> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        to_string(input, value, kind).map(Some)
+    }
 }
 
-fn input_to_utf8_and_url(input: &BStr, kind: UrlKind) -> Result<(&str, url::Url), Error> {
-    let input = input_to_utf8(input, kind)?;
-    url::Url::parse(input)
-        .map(|url| (input, url))
-        .map_err(|source| Error::Url {
+fn to_string(input: &BStr, value: &[u8], kind: UrlKind) -> Result<String, Error> {
+    std::str::from_utf8(value)
+        .map(ToOwned::to_owned)
+        .map_err(|source| Error::Utf8 {
             url: input.to_owned(),
             kind,
             source,
@@ -52,6 +52,27 @@ done
 tests_windows+=("file://c:/repo")
 tests_windows+=("c:repo")
 
+# scp-like urls with brackets and ports, partially from git's t/t5601-clone.sh
+for url in "myhost:src" "[myhost:123]:src" "[myhost]:src" "[::1]:repo" "user@[::1]:repo" "[user@::1]:repo" \
+           "[user@myhost:22]:repo" "host:" "user@host:~/repo"; do
+  tests+=("$url")
+done
+
+# hosts in brackets, ports as parsed by strtol(), empty hosts, and special characters in paths
+for url in "ssh://[::1]:22/repo" "ssh://[myhost:123]/repo" "ssh://[::1]x/repo" "git://[host:22]/repo" \
+           "ssh://host:022/repo" "ssh://host:+22/repo" "git://host:+22/repo" "ssh://host:99999/repo" \
+           "ssh://host:abc/repo" "ssh://h:o:s:t:22/repo" "ssh://:22/repo" "ssh:///repo" "git:///repo" \
+           "ssh://host/" "ssh://host/repo?a#b"; do
+  tests+=("$url")
+done
+
+# percent-encoded urls, which git decodes before connecting
+for url in "ssh://host/%7erepo" "ssh://us%40er@host/repo" "ssh://[fe80::1%25eth0]/repo" "git://host/%00x" \
+           "ssh://host/re%25po" "git://host/re%20po%zz"; do
+  tests+=("$url")
+done
+tests_unix+=("file://host/re%20po" "file:///re%25po")
+
 tests_unix+=("${tests[@]}")
 tests_windows+=("${tests[@]}")
 
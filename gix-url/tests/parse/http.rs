@@ -74,3 +74,15 @@ fn http_missing_path() -> crate::Result {
     assert_url("http://host.xz", url(Scheme::Http, None, "host.xz", None, b"/"))?;
     Ok(())
 }
+
+#[test]
+fn host_case_and_default_ports_are_kept_like_in_git() -> crate::Result {
+    assert_url_roundtrip(
+        "https://EXAMPLE.com:443/repo",
+        url(Scheme::Https, None, "EXAMPLE.com", 443, b"/repo"),
+    )?;
+    assert_url_roundtrip(
+        "http://example.COM:80/repo",
+        url(Scheme::Http, None, "example.COM", 80, b"/repo"),
+    )
+}
@@ -39,3 +39,14 @@ fn file_missing_host_path_separator() {
 fn missing_port_despite_indication() {
     assert_matches!(parse("ssh://host.xz:"), Err(MissingRepositoryPath { .. }))
 }
+
+#[test]
+fn http_with_invalid_port() {
+    assert_matches!(parse("https://host.xz:99999/repo"), Err(InvalidPort { .. }));
+    assert_matches!(parse("https://host.xz:abc/repo"), Err(InvalidPort { .. }));
+}
+
+#[test]
+fn http_missing_host() {
+    assert_matches!(parse("https:///repo"), Err(MissingHost { .. }));
+}
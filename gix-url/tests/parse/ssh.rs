@@ -27,7 +27,7 @@ fn host_is_ipv4() -> crate::Result {
 fn username_expansion_with_username() -> crate::Result {
     assert_url_roundtrip(
         "ssh://example.com/~byron/hello/git",
-        url(Scheme::Ssh, None, "example.com", None, b"/~byron/hello/git"),
+        url(Scheme::Ssh, None, "example.com", None, b"~byron/hello/git"),
     )
 }
 
@@ -35,7 +35,7 @@ fn username_expansion_with_username() -> crate::Result {
 fn username_expansion_without_username() -> crate::Result {
     assert_url_roundtrip(
         "ssh://example.com/~/hello/git",
-        url(Scheme::Ssh, None, "example.com", None, b"/~/hello/git"),
+        url(Scheme::Ssh, None, "example.com", None, b"~/hello/git"),
     )
 }
 
@@ -193,3 +193,49 @@ fn strange_scp_like_with_host_named_file() -> crate::Result {
     assert_eq!(url.to_bstring(), "file:..");
     Ok(())
 }
+
+#[test]
+fn host_is_ipv6_in_brackets() -> crate::Result {
+    assert_url_roundtrip(
+        "ssh://user@[::1]:22/repo",
+        url(Scheme::Ssh, "user", "::1", 22, b"/repo"),
+    )
+}
+
+#[test]
+fn scp_like_with_port_in_brackets() -> crate::Result {
+    assert_url_roundtrip(
+        "[user@host.xz:2222]:repo",
+        url_alternate(Scheme::Ssh, "user", "host.xz", 2222, b"repo"),
+    )?;
+    assert_url_roundtrip("[::1]:repo", url_alternate(Scheme::Ssh, None, "::1", None, b"repo"))
+}
+
+#[test]
+fn invalid_ports_are_part_of_the_host_like_in_git() -> crate::Result {
+    let actual = assert_url(
+        "ssh://host.xz:99999/repo",
+        url(Scheme::Ssh, None, "host.xz:99999", None, b"/repo"),
+    )?;
+    assert_eq!(
+        actual.to_bstring(),
+        "ssh://[host.xz:99999]/repo",
+        "hosts with colons are put into brackets"
+    );
+    assert_url("ssh://host.xz:/repo", url(Scheme::Ssh, None, "host.xz", None, b"/repo"))?;
+    Ok(())
+}
+
+#[test]
+fn percent_encoding_is_decoded_like_in_git() -> crate::Result {
+    let url = assert_url(
+        "ssh://us%40er@host.xz/%7e/re%25po",
+        url(Scheme::Ssh, "us@er", "host.xz", None, b"~/re%po"),
+    )?;
+    assert_eq!(
+        url.to_bstring(),
+        "ssh://us@er@host.xz/~/re%25po",
+        "only `%` needs to be encoded to be parsed the same way again"
+    );
+    Ok(())
+}
@@ -11,6 +11,10 @@ use crate::checkout::chunk;
 /// operation will abort.
 /// `options` provide a lot of context on how to perform the operation.
 ///
+/// Entries marked with [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) are not written, which includes
+/// all sparse directory entries. Use [`Options::sparse_checkout`](crate::checkout::Options::sparse_checkout) to
+/// set these flags according to the patterns of a `sparse-checkout` file.
+///
 /// ### Handling the return value
 ///
 /// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
@@ -28,6 +32,9 @@ pub fn checkout<Find>(
 where
     Find: gix_object::Find + Send + Clone,
 {
+    if let Some(patterns) = options.sparse_checkout.as_ref() {
+        patterns.apply_to_index(index);
+    }
     let paths = index.take_path_backing();
     let res = checkout_inner(index, &paths, dir, objects, files, bytes, should_interrupt, options);
     index.return_path_backing(paths);
@@ -66,6 +66,12 @@ pub struct Options {
     pub filters: gix_filter::Pipeline,
     /// Control how long-running processes may use the 'delay' capability.
     pub filter_process_delay: gix_filter::driver::apply::Delay,
+    /// If set, the patterns of the `sparse-checkout` file are used to set or clear the
+    /// [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag of all entries before checking them out,
+    /// so that only the included entries are written to the worktree.
+    ///
+    /// If `None`, the skip-worktree flags of all entries are used as they are.
+    pub sparse_checkout: Option<crate::sparse::Patterns>,
 }
 
 /// The error returned by the [checkout()][crate::checkout()] function.
@@ -0,0 +1,275 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_glob::search::pattern;
+use gix_ignore::search::Ignore;
+use gix_index::entry::Flags;
+
+/// The patterns of a `.git/info/sparse-checkout` file which determine which index entries are present in the worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Patterns {
+    /// Patterns in _cone mode_, which include entire directories along with all files in their parent directories.
+    ///
+    /// This is the mode used if `core.sparseCheckoutCone` is `true`.
+    Cone(Cone),
+    /// Patterns like the ones found in `.gitignore` files, with the difference that matching paths are _included_.
+    NonCone(pattern::List<Ignore>),
+}
+
+/// The directories to include in _cone mode_.
+///
+/// Files in the root of the worktree are always included, as are files directly inside of the parent directories
+/// of included directories.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cone {
+    /// If `true`, all directories are included as the file only contains the `/*` pattern.
+    all: bool,
+    /// Directories whose content is included recursively.
+    recursive: BTreeSet<BString>,
+    /// Directories whose files are included, as they are the parent of one of the `recursive` directories.
+    parents: BTreeSet<BString>,
+}
+
+/// Instantiation
+impl Patterns {
+    /// Parse the content of a `sparse-checkout` file at `source` from `bytes`, and in _cone mode_ if `cone` is `true`.
+    ///
+    /// Note that like `git`, patterns that aren't valid in cone mode cause the patterns to be interpreted in non-cone mode.
+    pub fn from_bytes(bytes: &[u8], source: impl Into<PathBuf>, cone: bool) -> Self {
+        match cone.then(|| Cone::from_bytes(bytes)).flatten() {
+            Some(cone) => Patterns::Cone(cone),
+            None => Patterns::NonCone(pattern::List::from_bytes(bytes, source.into(), None)),
+        }
+    }
+
+    /// Read the `sparse-checkout` file at `path` and parse it in _cone mode_ if `cone` is `true`,
+    /// or return `None` if it doesn't exist.
+    pub fn from_file(path: impl Into<PathBuf>, cone: bool) -> std::io::Result<Option<Self>> {
+        let path = path.into();
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(Some(Self::from_bytes(&bytes, path, cone))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Access
+impl Patterns {
+    /// Return `true` if the entry at the repository-relative `path` should be present in the worktree.
+    /// `is_dir` is `true` if `path` is a directory.
+    pub fn is_included(&self, path: &BStr, is_dir: bool) -> bool {
+        match self {
+            Patterns::Cone(cone) => cone.is_included(path, is_dir),
+            Patterns::NonCone(list) => {
+                let mut is_dir = is_dir;
+                let mut path = path;
+                loop {
+                    if let Some(m) = gix_ignore::search::pattern_matching_relative_path(
+                        list,
+                        path,
+                        path.rfind_byte(b'/').map(|pos| pos + 1),
+                        Some(is_dir),
+                        gix_glob::pattern::Case::Sensitive,
+                    ) {
+                        return !m.pattern.is_negative();
+                    }
+                    match path.rfind_byte(b'/') {
+                        Some(pos) => {
+                            path = path[..pos].as_bstr();
+                            is_dir = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return `true` if the directory at the repository-relative `dir` and all of its content is excluded from the worktree
+    /// in _cone mode_, which makes it suitable to be represented as sparse directory entry in the index.
+    ///
+    /// In non-cone mode, this is always `false`.
+    pub fn is_directory_outside_cone(&self, dir: &BStr) -> bool {
+        match self {
+            Patterns::Cone(cone) => !cone.is_included(dir, true),
+            Patterns::NonCone(_) => false,
+        }
+    }
+
+    /// Set the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag on all unconflicted entries in `index` that are excluded
+    /// by these patterns, and clear it on all others, and return the amount of entries that are marked to be skipped.
+    ///
+    /// [Sparse directory entries](gix_index::entry::Mode::DIR) are left untouched.
+    pub fn apply_to_index(&self, index: &mut gix_index::State) -> usize {
+        let mut num_skipped = 0;
+        for (entry, path) in index.entries_mut_with_paths() {
+            if entry.mode.is_sparse() {
+                num_skipped += 1;
+                continue;
+            }
+            if entry.stage() == 0 && !self.is_included(path, false) {
+                entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+                num_skipped += 1;
+            } else {
+                entry.flags.remove(Flags::SKIP_WORKTREE);
+                if !entry.flags.intersects(Flags::INTENT_TO_ADD) {
+                    entry.flags.remove(Flags::EXTENDED);
+                }
+            }
+        }
+        num_skipped
+    }
+}
+
+impl Cone {
+    /// Create a new instance which includes all `directories` recursively, which are relative to the root of the repository.
+    pub fn from_directories(directories: impl IntoIterator<Item = impl AsRef<BStr>>) -> Self {
+        let mut out = Cone::default();
+        out.add_directories(directories);
+        out
+    }
+
+    /// Add all `directories` to be included recursively.
+    ///
+    /// Directories that are already contained in an included directory are ignored.
+    pub fn add_directories(&mut self, directories: impl IntoIterator<Item = impl AsRef<BStr>>) {
+        for dir in directories {
+            let dir = dir.as_ref().trim_with(|c| c == '/').as_bstr();
+            if dir.is_empty() {
+                self.all = true;
+                continue;
+            }
+            if self.recursive.contains(dir) || self.has_recursive_parent(dir) {
+                continue;
+            }
+            let is_contained = |path: &BString| path.starts_with(dir) && path.get(dir.len()) == Some(&b'/');
+            self.recursive.retain(|path| !is_contained(path));
+            self.parents.retain(|path| path != dir && !is_contained(path));
+            let mut parent = dir;
+            while let Some(pos) = parent.rfind_byte(b'/') {
+                parent = parent[..pos].as_bstr();
+                self.parents.insert(parent.to_owned());
+            }
+            self.recursive.insert(dir.to_owned());
+        }
+    }
+
+    /// Parse `bytes` as written by `git sparse-checkout` in cone mode, or return `None` if a pattern was encountered
+    /// that isn't valid in cone mode.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut out = Cone::default();
+        for line in bytes.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            match line {
+                b"/*" => out.all = true,
+                b"!/*/" => out.all = false,
+                _ => {
+                    let (is_negative, line) = match line.strip_prefix(b"!") {
+                        Some(line) => (true, line),
+                        None => (false, line),
+                    };
+                    let dir = line.strip_prefix(b"/")?.strip_suffix(b"/")?;
+                    match (is_negative, dir.strip_suffix(b"/*")) {
+                        (true, Some(dir)) => {
+                            let dir = unescape(dir);
+                            if !out.recursive.remove(&dir) {
+                                return None;
+                            }
+                            out.parents.insert(dir);
+                        }
+                        (false, None) => {
+                            let dir = unescape(dir);
+                            if out.parents.contains(&dir) {
+                                return None;
+                            }
+                            out.recursive.insert(dir);
+                        }
+                        _ => return None,
+                    }
+                }
+            }
+        }
+        Some(out)
+    }
+
+    /// Return all directories that are included recursively, without the ones that are contained in another included directory.
+    pub fn directories(&self) -> impl Iterator<Item = &BStr> + '_ {
+        self.recursive
+            .iter()
+            .filter(|dir| !self.has_recursive_parent(dir.as_bstr()))
+            .map(|dir| dir.as_bstr())
+    }
+
+    /// Write ourselves to `out` in the format used by `git` for `sparse-checkout` files in cone mode.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        if self.all {
+            return out.write_all(b"/*\n");
+        }
+        out.write_all(b"/*\n!/*/\n")?;
+        for parent in self
+            .parents
+            .iter()
+            .filter(|dir| !self.recursive.contains(*dir) && !self.has_recursive_parent(dir.as_bstr()))
+        {
+            let parent = escape(parent.as_bstr());
+            writeln!(out, "/{parent}/\n!/{parent}/*/")?;
+        }
+        for dir in self.directories() {
+            writeln!(out, "/{}/", escape(dir))?;
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the entry at the repository-relative `path` is included, with `is_dir` being `true` if it's a directory.
+    pub fn is_included(&self, path: &BStr, is_dir: bool) -> bool {
+        if self.all || self.recursive.contains(path) || self.has_recursive_parent(path) {
+            return true;
+        }
+        if is_dir {
+            self.parents.contains(path)
+        } else {
+            match path.rfind_byte(b'/') {
+                None => true,
+                Some(pos) => self.parents.contains(path[..pos].as_bstr()),
+            }
+        }
+    }
+
+    fn has_recursive_parent(&self, path: &BStr) -> bool {
+        let mut parent = path;
+        while let Some(pos) = parent.rfind_byte(b'/') {
+            parent = parent[..pos].as_bstr();
+            if self.recursive.contains(parent) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn unescape(dir: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut bytes = dir.iter();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\\' => out.extend(bytes.next().copied()),
+            b => out.push(*b),
+        }
+    }
+    out
+}
+
+fn escape(dir: &BStr) -> BString {
+    let mut out = BString::default();
+    for b in dir.iter() {
+        if matches!(b, b'\\' | b'*' | b'?' | b'[') {
+            out.push(b'\\');
+        }
+        out.push(*b);
+    }
+    out
+}
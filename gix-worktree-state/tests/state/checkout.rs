@@ -8,9 +8,13 @@ use std::{
 };
 
 use gix_features::progress;
+use gix_index::entry::Flags;
 use gix_object::{bstr::ByteSlice, Data};
 use gix_testtools::tempfile::TempDir;
-use gix_worktree_state::checkout::Collision;
+use gix_worktree_state::{
+    checkout::Collision,
+    sparse::{Cone, Patterns},
+};
 use once_cell::sync::Lazy;
 
 use crate::fixture_path;
@@ -271,6 +275,49 @@ fn allow_or_disallow_symlinks() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn sparse_checkout_in_cone_mode_only_writes_included_entries() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.sparse_checkout = Some(Patterns::Cone(Cone::from_directories(None::<&str>)));
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules")?;
+
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["empty", "executable"]),
+        "only files in the root are included"
+    );
+    assert!(outcome.collisions.is_empty());
+    for entry in index.entries() {
+        assert_eq!(
+            entry.flags.contains(Flags::SKIP_WORKTREE),
+            entry.path(&index).starts_with(b"dir/"),
+            "the skip-worktree flag is set according to the patterns"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn sparse_checkout_in_non_cone_mode_only_writes_included_entries() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.sparse_checkout = Some(Patterns::from_bytes(b"/*\n!/dir/sub-dir/\n", "sparse-checkout", false));
+    let (_source_tree, destination, index, _outcome) =
+        checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules")?;
+
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["dir/content", "empty", "executable"]),
+    );
+    let skipped: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::SKIP_WORKTREE))
+        .map(|e| e.path(&index))
+        .collect();
+    assert_eq!(skipped, ["dir/sub-dir/symlink"]);
+    Ok(())
+}
+
 #[test]
 fn keep_going_collects_results() {
     let mut opts = opts_from_probe();
@@ -0,0 +1,54 @@
+use gix_worktree_state::sparse::{Cone, Patterns};
+
+/// As written by `git sparse-checkout set a/b/c d`.
+const CONE: &[u8] = b"/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n!/a/b/*/\n/a/b/c/\n/d/\n";
+
+#[test]
+fn cone_patterns_roundtrip() -> crate::Result {
+    let cone = Cone::from_bytes(CONE).expect("valid cone patterns");
+    assert_eq!(cone.directories().collect::<Vec<_>>(), ["a/b/c", "d"]);
+    assert_eq!(cone, Cone::from_directories(["a/b/c/", "d", "a/b/c/e"]));
+
+    let mut buf = Vec::new();
+    cone.write_to(&mut buf)?;
+    assert_eq!(buf, CONE, "the output is the same as the one by git");
+    Ok(())
+}
+
+#[test]
+fn cone_mode_includes_directories_recursively_and_files_in_parent_directories() {
+    let patterns = Patterns::from_bytes(CONE, "sparse-checkout", true);
+    assert!(matches!(patterns, Patterns::Cone(_)));
+
+    for (path, is_dir, expected) in [
+        ("file", false, true),
+        ("a", true, true),
+        ("a/file", false, true),
+        ("a/x", true, false),
+        ("a/x/file", false, false),
+        ("a/b/file", false, true),
+        ("a/b/c/file", false, true),
+        ("a/b/c/deep/file", false, true),
+        ("d/e/f", false, true),
+        ("e", true, false),
+        ("e/file", false, false),
+    ] {
+        assert_eq!(patterns.is_included(path.into(), is_dir), expected, "{path}");
+        if is_dir {
+            assert_eq!(patterns.is_directory_outside_cone(path.into()), !expected, "{path}");
+        }
+    }
+}
+
+#[test]
+fn invalid_cone_patterns_fall_back_to_non_cone_mode() {
+    let patterns = Patterns::from_bytes(b"/*\n!/*/\n*.txt\n", "sparse-checkout", true);
+    assert!(matches!(patterns, Patterns::NonCone(_)));
+    assert!(patterns.is_included("a/b/file.txt".into(), false));
+    assert!(!patterns.is_included("a/b/file.rs".into(), false));
+    assert!(patterns.is_included("file.rs".into(), false));
+    assert!(
+        !patterns.is_directory_outside_cone("a".into()),
+        "directories are never considered outside of the cone in non-cone mode"
+    );
+}
@@ -35,6 +35,15 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this a partial clone which receives only the objects that pass `filter`, similar to `git clone --filter`.
+    ///
+    /// The remote is configured as promisor remote to obtain the missing objects from once they are needed,
+    /// and `filter` is used for all future fetches from it.
+    pub fn with_filter(mut self, filter: crate::remote::fetch::Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
@@ -20,6 +20,8 @@ pub mod main_worktree {
             source: gix_traverse::tree::breadthfirst::Error,
         },
         #[error(transparent)]
+        IndexWriteOptions(#[from] crate::config::index_write_options::Error),
+        #[error(transparent)]
         WriteIndex(#[from] gix_index::file::write::Error),
         #[error(transparent)]
         CheckoutOptions(#[from] crate::config::checkout_options::Error),
@@ -31,6 +33,12 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error(transparent)]
+        Hooks(#[from] crate::hooks::init::Error),
+        #[error(transparent)]
+        PostCheckoutHook(#[from] crate::hooks::run::Error),
+        #[error(transparent)]
+        PrefetchObjects(#[from] crate::repository::prefetch_objects::Error),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -60,6 +68,8 @@ pub mod main_worktree {
         ///
         /// Note that this is a no-op if the remote was empty, leaving this repository empty as well. This can be validated by checking
         /// if the `head()` of the returned repository is not unborn.
+        ///
+        /// Just like `git`, the `post-checkout` hook is run once the worktree was checked out, but its exit status is ignored.
         pub fn main_worktree<P>(
             &mut self,
             mut progress: P,
@@ -85,8 +95,11 @@ pub mod main_worktree {
             let workdir = repo.work_dir().ok_or_else(|| Error::BareRepository {
                 git_dir: repo.git_dir().to_owned(),
             })?;
-            let root_tree = match repo.head()?.try_peel_to_id_in_place()? {
-                Some(id) => id.object().expect("downloaded from remote").peel_to_tree()?.id,
+            let (head_id, root_tree) = match repo.head()?.try_peel_to_id_in_place()? {
+                Some(id) => (
+                    id.detach(),
+                    id.object().expect("downloaded from remote").peel_to_tree()?.id,
+                ),
                 None => {
                     return Ok((
                         self.repo.take().expect("still present"),
@@ -99,6 +112,14 @@ pub mod main_worktree {
                 source: err,
             })?;
             let mut index = gix_index::File::from_state(index, repo.index_path());
+            // Obtain all blobs of partial clones at once instead of one at a time.
+            repo.prefetch_objects(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            )?;
 
             let mut opts = repo
                 .config
@@ -124,7 +145,11 @@ pub mod main_worktree {
             files.show_throughput(start);
             bytes.show_throughput(start);
 
-            index.write(Default::default())?;
+            index.write(repo.index_write_options(&index)?)?;
+            match repo.hooks()?.post_checkout(&repo.object_hash().null(), &head_id, true) {
+                Ok(_) | Err(crate::hooks::run::Error::Failed { .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
             Ok((self.repo.take().expect("still present"), outcome))
         }
     }
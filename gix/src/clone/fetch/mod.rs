@@ -35,6 +35,23 @@ pub enum Error {
     },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[error("Failed to configure the repository as partial clone")]
+    PartialCloneConfig(#[from] crate::config::set_value::Error),
+    #[cfg(feature = "bundle")]
+    #[error(transparent)]
+    FetchBundle(#[from] crate::remote::fetch::bundle::Error),
+    #[cfg(feature = "bundle")]
+    #[error("Shallow clones from bundles are not supported")]
+    ShallowBundle,
+    #[cfg(feature = "bundle")]
+    #[error("Partial clones from bundles are not supported")]
+    PartialBundle,
+    #[cfg(feature = "bundle")]
+    #[error("The bundle uses {remote:?} object hashes, which differs from the {local:?} hashes of the repository to clone into")]
+    IncompatibleBundleObjectHash {
+        local: gix_hash::Kind,
+        remote: gix_hash::Kind,
+    },
 }
 
 /// Modification
@@ -125,39 +142,98 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
-            let mut connection = remote.connect(remote::Direction::Fetch).await?;
-            if let Some(f) = self.configure_connection.as_mut() {
-                f(&mut connection).map_err(Error::RemoteConnection)?;
-            }
-            connection
-                .prepare_fetch(&mut *progress, {
-                    let mut opts = self.fetch_options.clone();
-                    if !opts.extra_refspecs.contains(&head_refspec) {
-                        opts.extra_refspecs.push(head_refspec)
-                    }
-                    opts
-                })
-                .await?
-        };
-        if pending_pack.ref_map().object_hash != repo.object_hash() {
-            unimplemented!("configure repository to expect a different object hash as advertised by the server")
-        }
         let reflog_message = {
             let mut b = self.url.to_bstring();
             b.insert_str(0, "clone: from ");
             b
         };
-        let outcome = pending_pack
-            .with_write_packed_refs_only(true)
-            .with_reflog_message(RefLogMessage::Override {
-                message: reflog_message.clone(),
-            })
-            .with_shallow(self.shallow.clone())
-            .receive_inner(progress, should_interrupt)
-            .await?;
+        #[cfg(feature = "bundle")]
+        let bundle_outcome = if let Some(bundle_path) = remote.bundle_path() {
+            let bundle_object_hash = gix_bundle::File::at(bundle_path)
+                .map_err(remote::fetch::bundle::Error::from)?
+                .header()
+                .object_hash;
+            if bundle_object_hash != repo.object_hash() {
+                return Err(Error::IncompatibleBundleObjectHash {
+                    local: repo.object_hash(),
+                    remote: bundle_object_hash,
+                });
+            }
+            if self.shallow != remote::fetch::Shallow::NoChange {
+                return Err(Error::ShallowBundle);
+            }
+            if self.filter.is_some() {
+                return Err(Error::PartialBundle);
+            }
+            let mut extra_refspecs = self.fetch_options.extra_refspecs.clone();
+            if !extra_refspecs.contains(&head_refspec) {
+                extra_refspecs.push(head_refspec.clone())
+            }
+            Some(remote.fetch_bundle(
+                progress,
+                should_interrupt,
+                remote::fetch::bundle::Options {
+                    extra_refspecs,
+                    dry_run: false,
+                    write_packed_refs_only: true,
+                    reflog_message: Some(RefLogMessage::Override {
+                        message: reflog_message.clone(),
+                    }),
+                    write_fetch_head: false,
+                },
+            )?)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "bundle"))]
+        let bundle_outcome = None;
+
+        let outcome = match bundle_outcome {
+            Some(outcome) => outcome,
+            None => {
+                let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
+                    let mut connection = remote.connect(remote::Direction::Fetch).await?;
+                    if let Some(f) = self.configure_connection.as_mut() {
+                        f(&mut connection).map_err(Error::RemoteConnection)?;
+                    }
+                    connection
+                        .prepare_fetch(&mut *progress, {
+                            let mut opts = self.fetch_options.clone();
+                            if !opts.extra_refspecs.contains(&head_refspec) {
+                                opts.extra_refspecs.push(head_refspec)
+                            }
+                            opts
+                        })
+                        .await?
+                };
+                if pending_pack.ref_map().object_hash != repo.object_hash() {
+                    unimplemented!("configure repository to expect a different object hash as advertised by the server")
+                }
+                #[cfg(feature = "bundle")]
+                let pending_pack = match self.bundle_uri.take() {
+                    Some(uri) => pending_pack.with_bundle_uri(uri),
+                    None => pending_pack,
+                };
+                let pending_pack = match self.filter.clone() {
+                    Some(filter) => pending_pack.with_filter(filter),
+                    None => pending_pack,
+                };
+                pending_pack
+                    .with_write_packed_refs_only(true)
+                    .with_write_fetch_head(false)
+                    .with_reflog_message(RefLogMessage::Override {
+                        message: reflog_message.clone(),
+                    })
+                    .with_shallow(self.shallow.clone())
+                    .receive_inner(progress, should_interrupt)
+                    .await?
+            }
+        };
 
         util::append_config_to_repo_config(repo, config);
+        if let Some(filter) = self.filter.as_ref() {
+            util::write_partial_clone_config(repo, remote_name.as_ref(), filter)?;
+        }
         util::update_head(
             repo,
             &outcome.ref_map.remote_refs,
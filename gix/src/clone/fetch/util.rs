@@ -58,6 +58,24 @@ pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::F
     repo_config.append(config);
 }
 
+/// Configure `repo` as partial clone whose missing objects are obtained from the remote named `remote_name`,
+/// and use `filter` for future fetches from it, just like `git clone --filter` does.
+pub fn write_partial_clone_config(
+    repo: &mut Repository,
+    remote_name: &BStr,
+    filter: &crate::remote::fetch::Filter,
+) -> Result<(), Error> {
+    use crate::config::tree::{Core, Extensions, Remote};
+    let mut config = repo.config_snapshot_mut();
+    config.set_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+    config.set_value(&Extensions::PARTIAL_CLONE, remote_name)?;
+    config.set_subsection_value(&Remote::PROMISOR, remote_name, "true")?;
+    config.set_subsection_value(&Remote::PARTIAL_CLONE_FILTER, remote_name, filter.to_string().as_str())?;
+    write_to_local_config(&config, WriteMode::Overwrite)?;
+    config.commit()?;
+    Ok(())
+}
+
 /// HEAD cannot be written by means of refspec by design, so we have to do it manually here. Also create the pointed-to ref
 /// if we have to, as it might not have been naturally included in the ref-specs.
 pub fn update_head(
@@ -103,7 +121,8 @@ pub fn update_head(
                 head_ref_name: referent.to_owned(),
                 source: err,
             })?;
-            repo.refs
+            let transaction = repo
+                .refs
                 .transaction()
                 .packed_refs(gix_ref::file::transaction::PackedRefs::DeletionsAndNonSymbolicUpdates(
                     Box::new(&repo.objects),
@@ -135,13 +154,8 @@ pub fn update_head(
                     gix_lock::acquire::Fail::Immediately,
                     gix_lock::acquire::Fail::Immediately,
                 )
-                .map_err(crate::reference::edit::Error::from)?
-                .commit(
-                    repo.committer()
-                        .transpose()
-                        .map_err(|err| Error::HeadUpdate(crate::reference::edit::Error::ParseCommitterTime(err)))?,
-                )
                 .map_err(crate::reference::edit::Error::from)?;
+            repo.commit_ref_transaction(transaction)?;
 
             if let Some(head_peeled_id) = head_peeled_id {
                 let mut log = reflog_message();
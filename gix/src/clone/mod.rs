@@ -36,6 +36,16 @@ pub struct PrepareFetch {
     /// How to handle shallow clones
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     shallow: remote::fetch::Shallow,
+    /// The object filter to make this a partial clone.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    filter: Option<remote::fetch::Filter>,
+    /// The bundle or bundle list to apply before fetching the remainder from the remote.
+    #[cfg(all(
+        feature = "bundle",
+        any(feature = "async-network-client", feature = "blocking-network-client")
+    ))]
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    bundle_uri: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -134,6 +144,12 @@ impl PrepareFetch {
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
+            filter: None,
+            #[cfg(all(
+                feature = "bundle",
+                any(feature = "async-network-client", feature = "blocking-network-client")
+            ))]
+            bundle_uri: None,
         })
     }
 }
@@ -175,6 +191,16 @@ mod access_feat {
             self.fetch_options = opts;
             self
         }
+
+        /// Download the bundle or bundle list at `uri` and apply it before fetching the remainder from the remote,
+        /// similar to `git clone --bundle-uri`.
+        ///
+        /// See [`Prepare::with_bundle_uri()`](crate::remote::fetch::Prepare::with_bundle_uri()) for details.
+        #[cfg(feature = "bundle")]
+        pub fn with_bundle_uri(mut self, uri: impl Into<crate::bstr::BString>) -> Self {
+            self.bundle_uri = Some(uri.into());
+            self
+        }
     }
 }
 
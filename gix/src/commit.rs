@@ -19,6 +19,10 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error("Could not sign the commit")]
+    Sign(#[from] crate::sign::Error),
 }
 
 ///
@@ -183,6 +183,16 @@ impl Cache {
             })
     }
 
+    /// Return `true` if commits should be signed, as configured by `commit.gpgSign`.
+    pub(crate) fn sign_commits(&self) -> Result<bool, boolean::Error> {
+        boolean(self, "commit.gpgSign", &config::tree::Commit::GPG_SIGN, false)
+    }
+
+    /// Return `true` if annotated tags should be signed, as configured by `tag.gpgSign`.
+    pub(crate) fn sign_tags(&self) -> Result<bool, boolean::Error> {
+        boolean(self, "tag.gpgSign", &config::tree::Tag::GPG_SIGN, false)
+    }
+
     /// Returns (file-timeout, pack-refs timeout)
     pub(crate) fn lock_timeout(
         &self,
@@ -262,9 +272,74 @@ impl Cache {
         })
     }
 
+    #[cfg(feature = "index")]
+    pub(crate) fn index_write_options(
+        &self,
+        existing_version: Option<gix_index::Version>,
+        is_split_index: bool,
+    ) -> Result<gix_index::write::Options, config::index_write_options::Error> {
+        use crate::config::tree::{Feature, Index, SplitIndex};
+        let many_files = boolean(self, "feature.manyFiles", &Feature::MANY_FILES, false)?;
+        let version = match existing_version {
+            Some(version) => Some(version),
+            None => self
+                .resolved
+                .integer_by_key("index.version")
+                .map(|number| Index::VERSION.try_into_index_version(number))
+                .transpose()
+                .with_leniency(self.lenient_config)?
+                .or_else(|| many_files.then_some(gix_index::Version::V4)),
+        };
+        let split_index = self
+            .apply_leniency(
+                self.resolved
+                    .boolean_by_key("core.splitIndex")
+                    .map(|v| Core::SPLIT_INDEX.enrich_error(v)),
+            )?
+            .unwrap_or(is_split_index);
+        let split_index = if split_index {
+            let mut options = gix_index::write::SplitIndex::default();
+            if let Some(max_percent_change) = self
+                .resolved
+                .integer_by_key("splitIndex.maxPercentChange")
+                .map(|number| SplitIndex::MAX_PERCENT_CHANGE.try_into_usize(number))
+                .transpose()
+                .with_leniency(self.lenient_config)?
+            {
+                options.max_percent_change = max_percent_change.min(100) as u8;
+            }
+            if let Some(expiry) = self.apply_leniency(
+                self.resolved
+                    .string_by_key("splitIndex.sharedIndexExpire")
+                    .map(|v| SplitIndex::SHARED_INDEX_EXPIRE.try_into_expiry(v, std::time::SystemTime::now())),
+            )? {
+                options.shared_index_expiry = expiry;
+            }
+            Some(options)
+        } else {
+            None
+        };
+        Ok(gix_index::write::Options {
+            extensions: Default::default(),
+            version,
+            skip_hash: boolean(self, "index.skipHash", &Index::SKIP_HASH, many_files)?,
+            split_index,
+        })
+    }
+
     /// Collect everything needed to checkout files into a worktree.
     /// Note that some of the options being returned will be defaulted so safe settings, the caller might have to override them
     /// depending on the use-case.
+    #[cfg(feature = "index")]
+    pub(crate) fn sparse_checkout_options(&self) -> Result<gix_index::sparse::Options, boolean::Error> {
+        use crate::config::tree::{Core, Index};
+        Ok(gix_index::sparse::Options {
+            sparse_checkout: boolean(self, "core.sparseCheckout", &Core::SPARSE_CHECKOUT, false)?,
+            directory_patterns_only: boolean(self, "core.sparseCheckoutCone", &Core::SPARSE_CHECKOUT_CONE, false)?,
+            write_sparse_index: boolean(self, "index.sparse", &Index::SPARSE, false)?,
+        })
+    }
+
     #[cfg(feature = "worktree-mutation")]
     pub(crate) fn checkout_options(
         &self,
@@ -316,6 +391,7 @@ impl Cache {
                 }
                 config::stat_options::Error::ConfigBoolean(err) => config::checkout_options::Error::ConfigBoolean(err),
             })?,
+            sparse_checkout: repo.sparse_checkout_patterns()?,
         })
     }
 
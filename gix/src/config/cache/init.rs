@@ -304,6 +304,7 @@ impl crate::Repository {
     fn apply_changed_values(&mut self) {
         self.refs.write_reflog = util::reflog_or_default(self.config.reflog, self.work_dir().is_some());
         self.refs.namespace = self.config.refs_namespace.clone();
+        self.objects.promisor = self.promisor();
     }
 }
 
@@ -11,6 +11,8 @@ pub use snapshot::credential_helpers;
 ///
 pub mod overrides;
 
+pub mod url_match;
+
 pub mod tree;
 pub use tree::root::Tree;
 
@@ -167,6 +169,23 @@ pub mod stat_options {
     }
 }
 
+///
+#[cfg(feature = "index")]
+pub mod index_write_options {
+    /// The error produced when collecting the options for writing the index, and returned by
+    /// [Repository::index_write_options()](crate::Repository::index_write_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] super::boolean::Error),
+        #[error(transparent)]
+        ConfigUnsignedInteger(#[from] super::unsigned_integer::Error),
+        #[error(transparent)]
+        ConfigTypedValue(#[from] super::key::GenericErrorWithValue),
+    }
+}
+
 ///
 #[cfg(feature = "attributes")]
 pub mod checkout_options {
@@ -186,6 +205,9 @@ pub mod checkout_options {
         FilterPipelineOptions(#[from] crate::filter::pipeline::options::Error),
         #[error(transparent)]
         CommandContext(#[from] crate::config::command_context::Error),
+        #[cfg(feature = "worktree-mutation")]
+        #[error(transparent)]
+        SparseCheckoutPatterns(#[from] crate::worktree::sparse::patterns::Error),
     }
 }
 
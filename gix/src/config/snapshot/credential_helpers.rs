@@ -42,12 +42,13 @@ impl Snapshot<'_> {
     /// # Deviation
     ///
     /// - Invalid urls can't be used to obtain credential helpers as they are rejected early when creating a valid `url` here.
-    /// - Parsed urls will automatically drop the port if it's the default, i.e. `http://host:80` becomes `http://host` when parsed.
-    ///   This affects the prompt provided to the user, so that git will use the verbatim url, whereas we use `http://host`.
-    /// - Upper-case scheme and host will be lower-cased automatically when parsing into a url, so prompts differ compared to git.
+    /// - Upper-case schemes will be lower-cased automatically when parsing into a url, so prompts differ compared to git.
     /// - A **difference in prompt might affect the matching of getting existing stored credentials**, and it's a question of this being
     ///   a feature or a bug.
-    // TODO: when dealing with `http.*.*` configuration, generalize this algorithm as needed and support precedence.
+    ///
+    /// Like in `git`, all `credential.<url>.*` sections whose `<url>` [matches](crate::config::url_match::match_url()) apply,
+    /// in the order they are defined, and `<url>` may also be a partial url like `example.com` or `example.com/path` which
+    /// has to match the host, and path if given, exactly.
     pub fn credential_helpers(
         &self,
         mut url: gix_url::Url,
@@ -72,29 +73,12 @@ impl Snapshot<'_> {
         {
             for section in credential_sections {
                 let section = match section.header().subsection_name() {
-                    Some(pattern) => gix_url::parse(pattern).ok().and_then(|mut pattern| {
-                        normalize(&mut pattern);
-                        let is_http = matches!(pattern.scheme, gix_url::Scheme::Https | gix_url::Scheme::Http);
-                        let scheme = &pattern.scheme;
-                        let host = pattern.host();
-                        let ports = is_http
-                            .then(|| (pattern.port_or_default(), url.port_or_default()))
-                            .unwrap_or((pattern.port, url.port));
-                        let path = (!(is_http && pattern.path_is_root())).then_some(&pattern.path);
-
-                        if !path.map_or(true, |path| path == &url.path) {
-                            return None;
-                        }
-                        if pattern.user().is_some() && pattern.user() != url.user() {
-                            return None;
-                        }
-                        (scheme == &url.scheme && host_matches(host, url.host()) && ports.0 == ports.1).then_some((
-                            section,
-                            &credential::UrlParameter::HELPER,
-                            &credential::UrlParameter::USERNAME,
-                            &credential::UrlParameter::USE_HTTP_PATH,
-                        ))
-                    }),
+                    Some(pattern) => url_matches(pattern, &url).then_some((
+                        section,
+                        &credential::UrlParameter::HELPER,
+                        &credential::UrlParameter::USERNAME,
+                        &credential::UrlParameter::USE_HTTP_PATH,
+                    )),
                     None => Some((
                         section,
                         &Credential::HELPER,
@@ -175,21 +159,32 @@ impl Snapshot<'_> {
     }
 }
 
-fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
-    match (pattern, host) {
-        (Some(pattern), Some(host)) => {
-            let lfields = pattern.split('.');
-            let rfields = host.split('.');
-            if lfields.clone().count() != rfields.clone().count() {
-                return false;
-            }
-            lfields
-                .zip(rfields)
-                .all(|(pat, value)| gix_glob::wildmatch(pat.into(), value.into(), gix_glob::wildmatch::Mode::empty()))
-        }
-        (None, None) => true,
-        (Some(_), None) | (None, Some(_)) => false,
+/// Return `true` if `pattern` applies to `url`, either as url pattern or as partial url without scheme.
+fn url_matches(pattern: &crate::bstr::BStr, url: &gix_url::Url) -> bool {
+    if pattern.find(b"://").is_none() {
+        return partial_url_matches(pattern, url);
     }
+    gix_url::parse(pattern)
+        .ok()
+        .and_then(|pattern| crate::config::url_match::match_url(&pattern, url))
+        .is_some()
+}
+
+/// Match `host[:port][/path]` against `url` for equality, just like `git` does for partial urls.
+fn partial_url_matches(pattern: &crate::bstr::BStr, url: &gix_url::Url) -> bool {
+    let (host, path) = match pattern.find_byte(b'/') {
+        Some(pos) => (&pattern[..pos], Some(&pattern[pos + 1..])),
+        None => (pattern, None),
+    };
+    let url_host = match (url.host(), url.port) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_owned(),
+        (None, _) => return false,
+    };
+    host.eq_ignore_ascii_case(url_host.as_bytes())
+        && path.map_or(true, |path| {
+            path.trim_end_with(|c| c == '/') == url.path.trim_start_with(|c| c == '/').trim_end_with(|c| c == '/')
+        })
 }
 
 fn normalize(url: &mut gix_url::Url) {
@@ -402,6 +402,8 @@ mod time {
         }
 
         /// Convert the `value` into a date if possible, with `now` as reference time for relative dates.
+        ///
+        /// Pass `None` as `now` to only accept absolute dates, like `git` does for `GIT_AUTHOR_DATE` and `GIT_COMMITTER_DATE`.
         pub fn try_into_time(
             &self,
             value: Cow<'_, BStr>,
@@ -500,13 +502,13 @@ pub mod validate {
         }
     }
 
-    /// Assure that values that parse as git dates are valid.
+    /// Assure that values that parse as absolute git dates are valid.
     #[derive(Default)]
     pub struct Time;
 
     impl Validate for Time {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
-            gix_date::parse(value.to_str()?, std::time::SystemTime::now().into())?;
+            gix_date::parse(value.to_str()?, None)?;
             Ok(())
         }
     }
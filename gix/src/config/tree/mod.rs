@@ -24,6 +24,8 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commit` section.
+        pub const COMMIT: sections::Commit = sections::Commit;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -35,10 +37,18 @@ pub(crate) mod root {
         pub const DIFF: sections::Diff = sections::Diff;
         /// The `extensions` section.
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
+        /// The `feature` section.
+        pub const FEATURE: sections::Feature = sections::Feature;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `fsck` section.
+        pub const FSCK: sections::Fsck = sections::Fsck;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
+        /// The `gpg` section.
+        pub const GPG: sections::Gpg = sections::Gpg;
         /// The `http` section.
         pub const HTTP: sections::Http = sections::Http;
         /// The `index` section.
@@ -55,10 +65,18 @@ pub(crate) mod root {
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `repack` section.
+        pub const REPACK: sections::Repack = sections::Repack;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
+        /// The `splitIndex` section.
+        pub const SPLIT_INDEX: sections::SplitIndex = sections::SplitIndex;
         /// The `ssh` section.
         pub const SSH: sections::Ssh = sections::Ssh;
+        /// The `tag` section.
+        pub const TAG: sections::Tag = sections::Tag;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -71,14 +89,19 @@ pub(crate) mod root {
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COMMIT,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
                 #[cfg(feature = "blob-diff")]
                 &Self::DIFF,
                 &Self::EXTENSIONS,
+                &Self::FEATURE,
                 &Self::FETCH,
+                &Self::FSCK,
+                &Self::GC,
                 &Self::GITOXIDE,
+                &Self::GPG,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
@@ -87,8 +110,12 @@ pub(crate) mod root {
                 &Self::PROTOCOL,
                 &Self::PUSH,
                 &Self::REMOTE,
+                &Self::REPACK,
                 &Self::SAFE,
+                &Self::SPLIT_INDEX,
                 &Self::SSH,
+                &Self::TAG,
+                &Self::TRANSFER,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -98,9 +125,10 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, gpg, http, index, protocol, push, remote,
+    split_index, ssh, Author, Branch, Checkout, Clone, Commit, Committer, Core, Credential, Extensions, Feature, Fetch,
+    Fsck, Gc, Gitoxide, Gpg, Http, Index, Init, Mailmap, Pack, Protocol, Push, Remote, Repack, Safe, SplitIndex, Ssh,
+    Tag, Transfer, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
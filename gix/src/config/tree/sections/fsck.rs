@@ -0,0 +1,21 @@
+use crate::{
+    config,
+    config::tree::{keys, Fsck, Key, Section},
+};
+
+impl Fsck {
+    /// The `fsck.strict` key.
+    pub const STRICT: keys::Boolean = keys::Boolean::new_boolean("strict", &config::Tree::FSCK);
+    /// The `fsck.skipList` key.
+    pub const SKIP_LIST: keys::Path = keys::Path::new_path("skipList", &config::Tree::FSCK);
+}
+
+impl Section for Fsck {
+    fn name(&self) -> &str {
+        "fsck"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::STRICT, &Self::SKIP_LIST]
+    }
+}
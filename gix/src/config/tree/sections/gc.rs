@@ -0,0 +1,68 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry);
+}
+
+/// A key for the time before which objects or reflog entries expire, like `gc.pruneExpire`.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::BStr,
+        config::{key::GenericErrorWithValue, tree::gc::Expiry},
+    };
+
+    impl Expiry {
+        /// Parse `value` into the time before which items expire, relative to `now`, or `None`
+        /// if they should never expire.
+        ///
+        /// Besides dates, `now` or `all` expires all items, and `never` or `false` keeps them forever.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<SystemTime>, GenericErrorWithValue> {
+            crate::config::tree::split_index::parse_expiry(value.as_ref(), now)
+                .ok_or_else(|| GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            crate::config::tree::split_index::parse_expiry(value, std::time::SystemTime::now())
+                .ok_or_else(|| format!("Could not parse '{value}' as expiry date"))?;
+            Ok(())
+        }
+    }
+}
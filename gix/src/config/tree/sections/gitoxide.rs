@@ -345,6 +345,9 @@ mod subsections {
             keys::Executable::new_executable("commandWithoutShellFallback", &Gitoxide::SSH)
                 .with_environment_override("GIT_SSH")
                 .with_note("is always executed without shell and treated as fallback");
+        /// The `gitoxide.ssh.native` key.
+        pub const NATIVE: keys::Boolean = keys::Boolean::new_boolean("native", &Gitoxide::SSH)
+            .with_note("use the built-in ssh client instead of spawning the `ssh` program, if compiled in");
     }
 
     impl Section for Ssh {
@@ -353,7 +356,7 @@ mod subsections {
         }
 
         fn keys(&self) -> &[&dyn Key] {
-            &[&Self::COMMAND_WITHOUT_SHELL_FALLBACK]
+            &[&Self::COMMAND_WITHOUT_SHELL_FALLBACK, &Self::NATIVE]
         }
 
         fn parent(&self) -> Option<&dyn Section> {
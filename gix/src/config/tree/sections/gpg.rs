@@ -0,0 +1,185 @@
+use crate::{
+    config,
+    config::tree::{keys, Gpg, Key, Section},
+};
+
+impl Gpg {
+    /// The `gpg.format` key.
+    pub const FORMAT: Format = Format::new_with_validate("format", &config::Tree::GPG, validate::Format);
+    /// The `gpg.program` key.
+    pub const PROGRAM: keys::Program = keys::Program::new_program("program", &config::Tree::GPG);
+    /// The `gpg.minTrustLevel` key.
+    pub const MIN_TRUST_LEVEL: MinTrustLevel =
+        MinTrustLevel::new_with_validate("minTrustLevel", &config::Tree::GPG, validate::MinTrustLevel);
+
+    /// The `gpg.openpgp` subsection.
+    pub const OPENPGP: OpenPgp = OpenPgp;
+    /// The `gpg.x509` subsection.
+    pub const X509: X509 = X509;
+    /// The `gpg.ssh` subsection.
+    pub const SSH: Ssh = Ssh;
+}
+
+/// The `gpg.format` key.
+pub type Format = keys::Any<validate::Format>;
+
+impl Format {
+    /// Convert `value` into the format of signatures to create.
+    pub fn try_into_format(
+        &'static self,
+        value: std::borrow::Cow<'_, crate::bstr::BStr>,
+    ) -> Result<crate::sign::Format, config::key::GenericErrorWithValue> {
+        use crate::bstr::ByteSlice;
+        use crate::sign::Format;
+        Ok(match value.as_ref().as_bytes() {
+            b"openpgp" => Format::OpenPgp,
+            b"x509" => Format::X509,
+            b"ssh" => Format::Ssh,
+            _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+        })
+    }
+}
+
+/// The `gpg.minTrustLevel` key.
+pub type MinTrustLevel = keys::Any<validate::MinTrustLevel>;
+
+impl MinTrustLevel {
+    /// Convert `value` into the trust level a key must at least have for its signatures to be considered valid.
+    pub fn try_into_trust(
+        &'static self,
+        value: std::borrow::Cow<'_, crate::bstr::BStr>,
+    ) -> Result<crate::sign::Trust, config::key::GenericErrorWithValue> {
+        use crate::bstr::ByteSlice;
+        use crate::sign::Trust;
+        Ok(match value.as_ref().as_bytes() {
+            b"undefined" => Trust::Undefined,
+            b"never" => Trust::Never,
+            b"marginal" => Trust::Marginal,
+            b"fully" => Trust::Fully,
+            b"ultimate" => Trust::Ultimate,
+            _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+        })
+    }
+}
+
+impl Section for Gpg {
+    fn name(&self) -> &str {
+        "gpg"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::FORMAT, &Self::PROGRAM, &Self::MIN_TRUST_LEVEL]
+    }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::OPENPGP, &Self::X509, &Self::SSH]
+    }
+}
+
+mod subsections {
+    use crate::config::{
+        tree::{keys, Gpg, Key, Section},
+        Tree,
+    };
+
+    /// The `gpg.openpgp` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct OpenPgp;
+
+    impl OpenPgp {
+        /// The `gpg.openpgp.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::OPENPGP);
+    }
+
+    impl Section for OpenPgp {
+        fn name(&self) -> &str {
+            "openpgp"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `gpg.x509` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct X509;
+
+    impl X509 {
+        /// The `gpg.x509.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::X509);
+    }
+
+    impl Section for X509 {
+        fn name(&self) -> &str {
+            "x509"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `gpg.ssh` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct Ssh;
+
+    impl Ssh {
+        /// The `gpg.ssh.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::SSH);
+        /// The `gpg.ssh.defaultKeyCommand` key.
+        pub const DEFAULT_KEY_COMMAND: keys::Program = keys::Program::new_program("defaultKeyCommand", &Gpg::SSH);
+        /// The `gpg.ssh.allowedSignersFile` key.
+        pub const ALLOWED_SIGNERS_FILE: keys::Path = keys::Path::new_path("allowedSignersFile", &Gpg::SSH);
+        /// The `gpg.ssh.revocationFile` key.
+        pub const REVOCATION_FILE: keys::Path = keys::Path::new_path("revocationFile", &Gpg::SSH);
+    }
+
+    impl Section for Ssh {
+        fn name(&self) -> &str {
+            "ssh"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[
+                &Self::PROGRAM,
+                &Self::DEFAULT_KEY_COMMAND,
+                &Self::ALLOWED_SIGNERS_FILE,
+                &Self::REVOCATION_FILE,
+            ]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+}
+pub use subsections::{OpenPgp, Ssh, X509};
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Format;
+    impl keys::Validate for Format {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gpg::FORMAT.try_into_format(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct MinTrustLevel;
+    impl keys::Validate for MinTrustLevel {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gpg::MIN_TRUST_LEVEL.try_into_trust(value.into())?;
+            Ok(())
+        }
+    }
+}
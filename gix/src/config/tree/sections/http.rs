@@ -112,7 +112,8 @@ mod key_impls {
 
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "async-http-transport-reqwest"
     ))]
     impl crate::config::tree::http::FollowRedirects {
         /// Convert `value` into the redirect specification, or query the same value as `boolean`
@@ -165,7 +166,8 @@ mod key_impls {
 
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "async-http-transport-reqwest"
     ))]
     impl super::Version {
         pub fn try_into_http_version(
@@ -193,7 +195,8 @@ mod key_impls {
 
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "async-http-transport-reqwest"
     ))]
     impl ProxyAuthMethod {
         pub fn try_into_proxy_auth_method(
@@ -224,7 +227,8 @@ mod key_impls {
 
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "async-http-transport-reqwest"
     ))]
     impl SslVersion {
         pub fn try_into_ssl_version(
@@ -263,7 +267,8 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             #[cfg(any(
                 feature = "blocking-http-transport-reqwest",
-                feature = "blocking-http-transport-curl"
+                feature = "blocking-http-transport-curl",
+                feature = "async-http-transport-reqwest"
             ))]
             super::Http::SSL_VERSION.try_into_ssl_version(std::borrow::Cow::Borrowed(_value))?;
 
@@ -276,7 +281,8 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             #[cfg(any(
                 feature = "blocking-http-transport-reqwest",
-                feature = "blocking-http-transport-curl"
+                feature = "blocking-http-transport-curl",
+                feature = "async-http-transport-reqwest"
             ))]
             super::Http::PROXY_AUTH_METHOD.try_into_proxy_auth_method(std::borrow::Cow::Borrowed(_value))?;
 
@@ -289,7 +295,8 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             #[cfg(any(
                 feature = "blocking-http-transport-reqwest",
-                feature = "blocking-http-transport-curl"
+                feature = "blocking-http-transport-curl",
+                feature = "async-http-transport-reqwest"
             ))]
             super::Http::VERSION.try_into_http_version(std::borrow::Cow::Borrowed(_value))?;
 
@@ -310,7 +317,8 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             #[cfg(any(
                 feature = "blocking-http-transport-reqwest",
-                feature = "blocking-http-transport-curl"
+                feature = "blocking-http-transport-curl",
+                feature = "async-http-transport-reqwest"
             ))]
             super::Http::FOLLOW_REDIRECTS.try_into_follow_redirects(std::borrow::Cow::Borrowed(_value), || {
                 gix_config::Boolean::try_from(_value).map(|b| Some(b.0))
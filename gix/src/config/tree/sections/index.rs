@@ -10,6 +10,48 @@ impl Index {
     /// The `index.skipHash` key.
     pub const SKIP_HASH: keys::Boolean = keys::Boolean::new_boolean("skipHash", &config::Tree::INDEX)
         .with_deviation("also used to skip the hash when reading, even if a hash exists in the index file");
+    /// The `index.sparse` key.
+    pub const SPARSE: keys::Boolean = keys::Boolean::new_boolean("sparse", &config::Tree::INDEX);
+    /// The `index.version` key.
+    pub const VERSION: IndexVersion =
+        IndexVersion::new_with_validate("version", &config::Tree::INDEX, validate::IndexVersion);
+}
+
+/// The `index.version` key.
+pub type IndexVersion = keys::Any<validate::IndexVersion>;
+
+mod index_version {
+    use crate::{
+        config,
+        config::{key::GenericErrorWithValue, tree::index::IndexVersion},
+    };
+
+    impl IndexVersion {
+        /// Convert `value` into the index version to use for new index files, which is 2, 3 or 4.
+        #[cfg(feature = "index")]
+        pub fn try_into_index_version(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<gix_index::Version, config::key::GenericErrorWithValue> {
+            Ok(match self.try_into_version_number(value)? {
+                2 => gix_index::Version::V2,
+                3 => gix_index::Version::V3,
+                _ => gix_index::Version::V4,
+            })
+        }
+
+        pub(super) fn try_into_version_number(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<i64, config::key::GenericErrorWithValue> {
+            let value = value.map_err(|err| GenericErrorWithValue::from(self).with_source(err))?;
+            if (2..=4).contains(&value) {
+                Ok(value)
+            } else {
+                Err(GenericErrorWithValue::from_value(self, value.to_string().into()))
+            }
+        }
+    }
 }
 
 /// The `index.threads` key.
@@ -50,7 +92,7 @@ impl Section for Index {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::SKIP_HASH]
+        &[&Self::THREADS, &Self::SKIP_HASH, &Self::SPARSE, &Self::VERSION]
     }
 }
 
@@ -64,4 +106,15 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct IndexVersion;
+    impl keys::Validate for IndexVersion {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            let value = gix_config::Integer::try_from(value)?
+                .to_decimal()
+                .ok_or_else(|| format!("integer {value} cannot be represented as index version"))?;
+            super::Index::VERSION.try_into_version_number(Ok(value))?;
+            Ok(())
+        }
+    }
 }
@@ -20,6 +20,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commit` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Commit;
+mod commit;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -47,16 +52,36 @@ pub mod diff;
 pub struct Extensions;
 pub mod extensions;
 
+/// The `feature` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Feature;
+mod feature;
+
 /// The `fetch` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Fetch;
 pub mod fetch;
 
+/// The `fsck` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Fsck;
+mod fsck;
+
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gpg;
+pub mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -96,16 +121,36 @@ pub mod push;
 pub struct Remote;
 pub mod remote;
 
+/// The `repack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Repack;
+mod repack;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
 mod safe;
 
+/// The `splitIndex` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct SplitIndex;
+pub mod split_index;
+
 /// The `ssh` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Ssh;
 pub mod ssh;
 
+/// The `tag` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Tag;
+mod tag;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
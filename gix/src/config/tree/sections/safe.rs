@@ -9,10 +9,16 @@ impl Safe {
 }
 
 impl Safe {
-    /// Implements the directory filter to trust only global and system files, for use with `safe.directory`.
+    /// Implements the directory filter to trust only protected configuration, for use with `safe.directory`.
+    ///
+    /// Just like in `git`, these are the installation, system and global files as well as overrides, but never
+    /// repository-local files.
     pub fn directory_filter(meta: &gix_config::file::Metadata) -> bool {
-        let kind = meta.source.kind();
-        kind == gix_config::source::Kind::System || kind == gix_config::source::Kind::Global
+        use gix_config::source::Kind;
+        matches!(
+            meta.source.kind(),
+            Kind::GitInstallation | Kind::System | Kind::Global | Kind::Override
+        )
     }
 }
 
@@ -0,0 +1,80 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, SplitIndex},
+};
+
+impl SplitIndex {
+    /// The `splitIndex.maxPercentChange` key.
+    pub const MAX_PERCENT_CHANGE: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("maxPercentChange", &config::Tree::SPLIT_INDEX);
+    /// The `splitIndex.sharedIndexExpire` key.
+    pub const SHARED_INDEX_EXPIRE: SharedIndexExpire = SharedIndexExpire::new_with_validate(
+        "sharedIndexExpire",
+        &config::Tree::SPLIT_INDEX,
+        validate::SharedIndexExpire,
+    );
+}
+
+/// The `splitIndex.sharedIndexExpire` key.
+pub type SharedIndexExpire = keys::Any<validate::SharedIndexExpire>;
+
+mod shared_index_expire {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config::{key::GenericErrorWithValue, tree::split_index::SharedIndexExpire},
+    };
+
+    impl SharedIndexExpire {
+        /// Parse `value` into the time before which unused shared indices expire, relative to `now`, or `None`
+        /// if they should never expire.
+        ///
+        /// Besides dates, `now` or `all` expires all unused shared indices, and `never` or `false` keeps them forever.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<SystemTime>, GenericErrorWithValue> {
+            parse_expiry(value.as_ref(), now).ok_or_else(|| GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+
+    /// Parse `value` as expiry date relative to `now`, with `None` meaning that there is no expiry,
+    /// or return `None` if it couldn't be parsed.
+    pub(crate) fn parse_expiry(value: &BStr, now: SystemTime) -> Option<Option<SystemTime>> {
+        Some(match value.to_str().ok()? {
+            "never" | "false" => None,
+            "now" | "all" => Some(now),
+            date => {
+                let time = gix_date::parse(date, Some(now)).ok()?;
+                Some(u64::try_from(time.seconds).map_or(SystemTime::UNIX_EPOCH, |secs| {
+                    SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs)
+                }))
+            }
+        })
+    }
+}
+pub(crate) use shared_index_expire::parse_expiry;
+
+impl Section for SplitIndex {
+    fn name(&self) -> &str {
+        "splitIndex"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::MAX_PERCENT_CHANGE, &Self::SHARED_INDEX_EXPIRE]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct SharedIndexExpire;
+    impl keys::Validate for SharedIndexExpire {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::SplitIndex::SHARED_INDEX_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Match URLs against the URL patterns of configuration subsections like `http.<url>.*` or `credential.<url>.*`,
+//! the way `git` does it.
+use std::collections::BTreeMap;
+
+use crate::bstr::BStr;
+
+/// Describes how well a URL pattern matched a URL, with more specific matches comparing greater than less specific ones.
+///
+/// The host is the most significant, followed by the path and finally the user, just like in `git`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match {
+    /// The length of the host of the pattern, which is longer if it contains fewer wildcards or more components.
+    pub host_len: usize,
+    /// The length of the path of the pattern that matched a prefix of the path of the URL, including a trailing slash.
+    pub path_len: usize,
+    /// If `true`, the pattern specified a user which matched the one of the URL.
+    pub user_matched: bool,
+}
+
+/// Match the URL `pattern` against `url` and return `Some(Match)` if it applies to `url`, or `None` if it doesn't.
+///
+/// The scheme and port (with default ports being taken into account) have to be equal, and the host may contain `*`
+/// wildcards to match entire components between dots, as in `https://*.example.com`. The path of `pattern`
+/// has to match a prefix of the path of `url` along component boundaries, and a user has to match if `pattern` specifies one.
+pub fn match_url(pattern: &gix_url::Url, url: &gix_url::Url) -> Option<Match> {
+    if pattern.scheme != url.scheme {
+        return None;
+    }
+    let user_matched = match pattern.user() {
+        Some(user) if url.user() != Some(user) => return None,
+        Some(_) => true,
+        None => false,
+    };
+    if !host_matches(pattern.host(), url.host()) || pattern.port_or_default() != url.port_or_default() {
+        return None;
+    }
+    let path_len = path_prefix_match_len(url.path.as_ref(), pattern.path.as_ref())?;
+    Some(Match {
+        host_len: pattern.host().map_or(0, str::len),
+        path_len,
+        user_matched,
+    })
+}
+
+/// Return a copy of `config` in which all sections named `section_name`, with or without URL subsection, are replaced by
+/// sections without subsection that contain only the values applying to `url`, as selected by [`match_url()`].
+///
+/// For each key, only values of sections that match at least as well as all previous ones for that key are retained,
+/// in order, so that the last value of each key is the one of the best matching section. Sections without subsection
+/// match least well, and those whose subsection isn't a valid URL or whose metadata is rejected by `filter` are ignored.
+///
+/// This way, the returned configuration can be queried like it would be without URL-specific configuration,
+/// with keys like `http.proxy`.
+pub fn scoped_to_url(
+    config: &gix_config::File<'static>,
+    section_name: &str,
+    url: &gix_url::Url,
+    filter: &mut dyn FnMut(&gix_config::file::Metadata) -> bool,
+) -> gix_config::File<'static> {
+    let mut scoped = config.clone();
+    let Some(sections) = config.sections_and_ids_by_name(section_name) else {
+        return scoped;
+    };
+
+    let mut best_match_by_key = BTreeMap::<String, Match>::new();
+    let mut scoped_sections = Vec::new();
+    for (section, id) in sections {
+        scoped.remove_section_by_id(id);
+        if !filter(section.meta()) {
+            continue;
+        }
+        let Some(section_match) = section
+            .header()
+            .subsection_name()
+            .map_or(Some(Match::default()), |pattern| {
+                gix_url::parse(pattern)
+                    .ok()
+                    .and_then(|pattern| match_url(&pattern, url))
+            })
+        else {
+            continue;
+        };
+
+        let mut scoped_section = gix_config::file::Section::new(section_name.to_owned(), None, section.meta().clone())
+            .expect("a section name that exists is valid");
+        let body = section.body();
+        let mut seen = Vec::new();
+        for key in body.keys() {
+            let lowercase_key = key.as_ref().to_ascii_lowercase();
+            if seen.contains(&lowercase_key) {
+                continue;
+            }
+            seen.push(lowercase_key.clone());
+            match best_match_by_key.get(&lowercase_key) {
+                Some(best_match) if section_match < *best_match => continue,
+                _ => best_match_by_key.insert(lowercase_key, section_match),
+            };
+            let mut section_mut = scoped_section.to_mut(Default::default());
+            for value in body.values(key.as_ref()) {
+                section_mut.push(key.to_owned(), Some(value.as_ref()));
+            }
+            if body.value_implicit(key.as_ref()) == Some(None) {
+                section_mut.push(key.to_owned(), None);
+            }
+        }
+        scoped_sections.push(scoped_section);
+    }
+
+    for section in scoped_sections {
+        scoped.push_section(section);
+    }
+    scoped
+}
+
+/// Return true if the `host` matches the `pattern`, which may contain `*` wildcards that don't cross dots.
+fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
+    match (pattern, host) {
+        (Some(pattern), Some(host)) => {
+            let lfields = pattern.split('.');
+            let rfields = host.split('.');
+            if lfields.clone().count() != rfields.clone().count() {
+                return false;
+            }
+            lfields.zip(rfields).all(|(pat, value)| {
+                gix_glob::wildmatch(pat.into(), value.into(), gix_glob::wildmatch::Mode::IGNORE_CASE)
+            })
+        }
+        (None, None) => true,
+        (Some(_), None) | (None, Some(_)) => false,
+    }
+}
+
+/// Return the length of the match if `prefix` is equal to `path` or a prefix of it that ends at a component boundary.
+/// Both are considered to end in a slash, which is also counted, if they don't do so already.
+fn path_prefix_match_len(path: &BStr, prefix: &BStr) -> Option<usize> {
+    let path = path.strip_prefix(b"/").unwrap_or(path);
+    let prefix = prefix.strip_prefix(b"/").unwrap_or(prefix);
+    if prefix.is_empty() {
+        return Some(1);
+    }
+    let prefix = prefix.strip_suffix(b"/").unwrap_or(prefix);
+    let rest = path.strip_prefix(prefix)?;
+    (rest.is_empty() || rest.starts_with(b"/")).then_some(prefix.len() + 2)
+}
@@ -41,11 +41,23 @@ impl ThreadSafeRepository {
         let _span = gix_trace::coarse!("ThreadSafeRepository::discover()");
         let (path, trust) = upwards_opts(directory.as_ref(), options)?;
         let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
-        let mut options = trust_map.into_value_by_level(trust);
+        let trust = match worktree_dir.as_deref() {
+            Some(worktree_dir) if trust == gix_sec::Trust::Full => {
+                crate::open::trust_from_ownership(&git_dir, Some(worktree_dir)).map_err(|err| {
+                    upwards::Error::CheckTrust {
+                        path: worktree_dir.into(),
+                        err,
+                    }
+                })?
+            }
+            _ => trust,
+        };
+        let (mut options, full_trust_options) = crate::open::split_trust_map(trust_map, trust);
         options.git_dir_trust = trust.into();
         // Note that we will adjust the `current_dir` later so it matches the value of `core.precomposeUnicode`.
         options.current_dir = Some(gix_fs::current_dir(false).map_err(upwards::Error::CurrentDir)?);
-        Self::open_from_paths(git_dir, worktree_dir, options).map_err(Into::into)
+        Self::open_from_paths_with_full_trust_options(git_dir, worktree_dir, options, full_trust_options)
+            .map_err(Into::into)
     }
 
     /// Try to open a git repository directly from the environment.
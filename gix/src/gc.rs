@@ -0,0 +1,114 @@
+//! Maintenance of the object database, similar to `git gc`.
+use std::{path::PathBuf, time::SystemTime};
+
+/// Options for use in [`Repository::gc()`](crate::Repository::gc()), typically obtained with
+/// [`Repository::gc_options()`](crate::Repository::gc_options()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Unreachable loose objects that were last modified before this time are deleted, while unreachable objects in packs
+    /// that are replaced are only retained as loose objects if their pack was modified after this time.
+    ///
+    /// If `None`, unreachable objects are never deleted.
+    pub prune_expire: Option<SystemTime>,
+    /// Reflog entries created before this time are removed, or none if `None`.
+    pub reflog_expire: Option<SystemTime>,
+    /// Reflog entries created before this time are removed if they aren't reachable from the current value of their reference,
+    /// or none if `None`.
+    pub reflog_expire_unreachable: Option<SystemTime>,
+    /// If `true`, write all loose references into `packed-refs` and remove them, similar to `git pack-refs --all --prune`.
+    pub pack_refs: bool,
+    /// If `true`, write a `multi-pack-index` file for all remaining packs after repacking.
+    pub write_multi_pack_index: bool,
+    /// If `true`, write a reachability bitmap for the new pack, similar to `git repack --write-bitmap-index`.
+    pub write_bitmap_index: bool,
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The amount of reflog entries that were removed as they expired.
+    pub expired_reflog_entries: usize,
+    /// The amount of loose references that were written into `packed-refs`.
+    pub packed_refs: usize,
+    /// The pack written with all reachable objects, or `None` if there was nothing to pack.
+    pub pack: Option<gix_pack::bundle::write::Outcome>,
+    /// The path to the `multi-pack-index` file that was written, if any.
+    pub multi_pack_index_path: Option<PathBuf>,
+    /// The path to the reachability bitmap of the new pack, if one was written.
+    pub bitmap_path: Option<PathBuf>,
+    /// The paths to the pack data files that were removed after their objects were consolidated.
+    pub removed_packs: Vec<PathBuf>,
+    /// The amount of loose objects that were deleted as they were packed or unreachable and expired.
+    pub removed_loose_objects: usize,
+    /// The amount of unreachable objects from removed packs that were written as loose objects as they weren't expired yet.
+    pub loosened_objects: usize,
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::gc_options()`](crate::Repository::gc_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigExpiry(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+    }
+}
+
+/// The error returned by [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error("Could not open a worktree to learn what it has checked out")]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    ReferencesPlatform(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    ReferencesInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    ReferencesIter(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    ExpireReflog(#[from] gix_ref::file::log::expire::Error),
+    #[error(transparent)]
+    PackRefs(#[from] gix_ref::file::pack_refs::Error),
+    #[error(transparent)]
+    ReflogDecode(#[from] gix_ref::file::log::iter::decode::Error),
+    #[cfg(feature = "index")]
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::Error),
+    #[error("Could not open pack index at '{}'", path.display())]
+    OpenPackIndex {
+        path: PathBuf,
+        source: gix_pack::index::init::Error,
+    },
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    CreateEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+    #[error(transparent)]
+    WritePackData(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    WriteMultiPackIndex(#[from] gix_pack::multi_index::write::Error),
+    #[error(transparent)]
+    WriteBitmap(#[from] gix_pack::index::bitmap::Error),
+    #[error(transparent)]
+    WriteLooseObject(gix_odb::write::Error),
+    #[error(transparent)]
+    RefreshStore(#[from] gix_odb::store::load_index::Error),
+}
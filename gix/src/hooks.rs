@@ -0,0 +1,315 @@
+//! Find and run hooks, the programs `git` invokes at certain points of its operation to allow customizing its behaviour.
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_ref::{transaction::RefEdit, FullName, TargetRef};
+
+use crate::bstr::{BStr, BString, ByteVec};
+
+/// A platform to find and run hooks, obtained with [`Repository::hooks()`](crate::Repository::hooks()).
+///
+/// Hooks are only run if the repository is fully [trusted](gix_sec::Trust::Full), and behave as if they don't exist otherwise.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo crate::Repository,
+    /// The directory containing the hooks.
+    pub(crate) dir: PathBuf,
+    /// If `false`, the repository isn't trusted enough to run hooks.
+    pub(crate) enabled: bool,
+}
+
+/// The state of a reference transaction as passed to the `reference-transaction` hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceTransactionState {
+    /// All references are locked and the transaction is about to be committed.
+    ///
+    /// A failing hook aborts the transaction.
+    Prepared,
+    /// The transaction was committed and all references have their new values.
+    Committed,
+    /// The transaction was aborted and no reference was changed.
+    Aborted,
+}
+
+impl ReferenceTransactionState {
+    /// Return the name of the state as passed to the hook.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceTransactionState::Prepared => "prepared",
+            ReferenceTransactionState::Committed => "committed",
+            ReferenceTransactionState::Aborted => "aborted",
+        }
+    }
+}
+
+/// A reference to be updated on the remote, as passed to the `pre-push` hook.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PushUpdate {
+    /// The name of the local reference to push, or `None` if the remote reference is deleted.
+    pub local_ref: Option<FullName>,
+    /// The id of the object to push, or the null id if the remote reference is deleted.
+    pub local_id: ObjectId,
+    /// The name of the reference to update on the remote.
+    pub remote_ref: FullName,
+    /// The id the remote reference currently points to, or the null id if it doesn't exist yet.
+    pub remote_id: ObjectId,
+}
+
+///
+pub mod init {
+    /// The error returned by [`Repository::hooks()`](crate::Repository::hooks()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not interpolate the path at core.hooksPath")]
+        HooksPath(#[from] gix_config::path::interpolate::Error),
+    }
+}
+
+///
+pub mod run {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Platform::run()`](super::Platform::run()) and the methods running typed hooks.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        CommandContext(#[from] crate::config::command_context::Error),
+        #[error("Could not spawn the '{name}' hook")]
+        Spawn { name: String, source: std::io::Error },
+        #[error("Could not communicate with the '{name}' hook")]
+        Io { name: String, source: std::io::Error },
+        #[error("The '{name}' hook failed with {status}")]
+        Failed {
+            name: String,
+            status: std::process::ExitStatus,
+        },
+        #[error("Could not write or read the commit message at '{}'", path.display())]
+        CommitMessage {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("The argument '{arg}' couldn't be converted into an argument for the hook")]
+        IllformedArgument { arg: BString },
+    }
+}
+
+/// Access
+impl Platform<'_> {
+    /// Return the directory in which hooks are looked up, as configured by `core.hooksPath` or `$GIT_COMMON_DIR/hooks` by default.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the path to the hook named `name` if it exists and can be executed, or `None` if there is no such hook
+    /// or the repository isn't trusted enough to run hooks.
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        if !self.enabled {
+            return None;
+        }
+        let path = self.dir.join(name);
+        let metadata = std::fs::metadata(&path).ok()?;
+        (metadata.is_file() && (cfg!(windows) || gix_fs::is_executable(&metadata))).then_some(path)
+    }
+
+    /// Return `true` if the hook named `name` [exists](Self::find()).
+    pub fn exists(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+}
+
+/// Running
+impl Platform<'_> {
+    /// Run the hook named `name` with `args` and feed `stdin` to it, if present, and wait for it to finish.
+    /// Return `true` if the hook was run successfully, or `false` if it doesn't [exist](Self::find()).
+    ///
+    /// Just like `git`, the hook is run in the root of the worktree, or in the `.git` directory of bare repositories, with
+    /// its [command context](crate::Repository::command_context()) and `GIT_INDEX_FILE` set in its environment.
+    /// An error is returned if the hook exits with a non-zero status.
+    pub fn run(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<std::ffi::OsString>>,
+        stdin: Option<&[u8]>,
+    ) -> Result<bool, run::Error> {
+        use std::io::Write;
+
+        let Some(hook) = self.find(name) else {
+            return Ok(false);
+        };
+        let mut cmd: std::process::Command = gix_command::prepare(hook)
+            .args(args)
+            .env("GIT_INDEX_FILE", self.repo.index_path())
+            .stdin(if stdin.is_some() {
+                std::process::Stdio::piped()
+            } else {
+                std::process::Stdio::null()
+            })
+            .stdout(std::process::Stdio::inherit())
+            .with_context(self.repo.command_context()?)
+            .into();
+        cmd.current_dir(self.repo.work_dir().unwrap_or(self.repo.git_dir()));
+        gix_trace::debug!(cmd = ?cmd);
+
+        let mut child = cmd.spawn().map_err(|err| run::Error::Spawn {
+            name: name.into(),
+            source: err,
+        })?;
+        if let Some((input, mut child_stdin)) = stdin.zip(child.stdin.take()) {
+            match child_stdin.write_all(input) {
+                // The hook may choose not to read its input, just like `git` we don't mind.
+                Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => {
+                    return Err(run::Error::Io {
+                        name: name.into(),
+                        source: err,
+                    })
+                }
+                _ => {}
+            }
+        }
+        let status = child.wait().map_err(|err| run::Error::Io {
+            name: name.into(),
+            source: err,
+        })?;
+        if !status.success() {
+            return Err(run::Error::Failed {
+                name: name.into(),
+                status,
+            });
+        }
+        Ok(true)
+    }
+
+    /// Run the `pre-commit` hook, which is invoked before a commit is created and can prevent it by failing.
+    pub fn pre_commit(&self) -> Result<bool, run::Error> {
+        self.run("pre-commit", None::<&str>, None)
+    }
+
+    /// Run the `commit-msg` hook with `message`, and return the message as possibly edited by the hook.
+    /// If the hook doesn't exist, `message` is returned unchanged.
+    ///
+    /// Just like `git`, the message is passed to the hook by writing it to `$GIT_DIR/COMMIT_EDITMSG`.
+    pub fn commit_msg(&self, message: &BStr) -> Result<BString, run::Error> {
+        if !self.exists("commit-msg") {
+            return Ok(message.to_owned());
+        }
+        let path = self.repo.git_dir().join("COMMIT_EDITMSG");
+        let io_err = |err| run::Error::CommitMessage {
+            path: path.clone(),
+            source: err,
+        };
+        std::fs::write(&path, message).map_err(io_err)?;
+        self.run("commit-msg", Some(&path), None)?;
+        std::fs::read(&path).map(Into::into).map_err(io_err)
+    }
+
+    /// Run the `post-checkout` hook after the worktree was updated from `previous_head` to `new_head`,
+    /// with `is_branch_checkout` being `true` if a branch was checked out, or `false` if only files were checked out.
+    ///
+    /// Note that this hook can't affect the outcome of the checkout, even though its failure is reported.
+    pub fn post_checkout(
+        &self,
+        previous_head: &gix_hash::oid,
+        new_head: &gix_hash::oid,
+        is_branch_checkout: bool,
+    ) -> Result<bool, run::Error> {
+        self.run(
+            "post-checkout",
+            [
+                previous_head.to_string(),
+                new_head.to_string(),
+                usize::from(is_branch_checkout).to_string(),
+            ],
+            None,
+        )
+    }
+
+    /// Run the `reference-transaction` hook for the reference `edits` of a transaction which is in the given `state`.
+    ///
+    /// Each edit is passed on a line of the form `<old-value> SP <new-value> SP <ref-name> LF` with values being
+    /// the null id if unknown, or `ref:<name>` for symbolic references.
+    pub fn reference_transaction(
+        &self,
+        state: ReferenceTransactionState,
+        edits: &[RefEdit],
+    ) -> Result<bool, run::Error> {
+        if !self.exists("reference-transaction") {
+            return Ok(false);
+        }
+        let null = self.repo.object_hash().null();
+        let mut stdin = BString::default();
+        for edit in edits {
+            for target in [edit.change.previous_value(), edit.change.new_value()] {
+                match target {
+                    Some(TargetRef::Peeled(id)) => stdin.push_str(id.to_string()),
+                    Some(TargetRef::Symbolic(name)) => {
+                        stdin.push_str("ref:");
+                        stdin.push_str(name.as_bstr());
+                    }
+                    None => stdin.push_str(null.to_string()),
+                }
+                stdin.push(b' ');
+            }
+            stdin.push_str(edit.name.as_bstr());
+            stdin.push(b'\n');
+        }
+        self.run("reference-transaction", Some(state.as_str()), Some(&stdin))
+    }
+
+    /// Run the `pre-push` hook before pushing `updates` to the remote named `remote_name` at `url`, which can prevent the
+    /// push by failing. Use the `url` as `remote_name` if the remote isn't named.
+    ///
+    /// Each update is passed on a line of the form `<local-ref> SP <local-id> SP <remote-ref> SP <remote-id> LF`.
+    pub fn pre_push(
+        &self,
+        remote_name: &BStr,
+        url: &BStr,
+        updates: impl IntoIterator<Item = PushUpdate>,
+    ) -> Result<bool, run::Error> {
+        if !self.exists("pre-push") {
+            return Ok(false);
+        }
+        let mut stdin = BString::default();
+        for update in updates {
+            stdin.push_str(
+                update
+                    .local_ref
+                    .as_ref()
+                    .map_or("(delete)".into(), gix_ref::FullName::as_bstr),
+            );
+            stdin.push(b' ');
+            stdin.push_str(update.local_id.to_string());
+            stdin.push(b' ');
+            stdin.push_str(update.remote_ref.as_bstr());
+            stdin.push(b' ');
+            stdin.push_str(update.remote_id.to_string());
+            stdin.push(b'\n');
+        }
+        let args = [remote_name, url]
+            .into_iter()
+            .map(|arg| {
+                gix_path::try_from_bstr(arg)
+                    .map(|path| path.into_owned().into_os_string())
+                    .map_err(|_| run::Error::IllformedArgument { arg: arg.into() })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.run("pre-push", args, Some(&stdin))
+    }
+
+    /// Run the `post-rewrite` hook after the `command` (`amend` or `rebase`) rewrote commits, with `rewritten` being
+    /// pairs of the `(original, rewritten)` commit ids.
+    pub fn post_rewrite(
+        &self,
+        command: &str,
+        rewritten: impl IntoIterator<Item = (ObjectId, ObjectId)>,
+    ) -> Result<bool, run::Error> {
+        if !self.exists("post-rewrite") {
+            return Ok(false);
+        }
+        let mut stdin = BString::default();
+        for (original, new) in rewritten {
+            stdin.push_str(format!("{original} {new}\n"));
+        }
+        self.run("post-rewrite", Some(command), Some(&stdin))
+    }
+}
@@ -95,6 +95,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "bundle")]
+pub use gix_bundle as bundle;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -102,6 +104,8 @@ pub use gix_commitgraph as commitgraph;
 pub use gix_credentials as credentials;
 pub use gix_date as date;
 pub use gix_features as features;
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub use gix_fetchhead as fetchhead;
 use gix_features::threading::OwnShared;
 pub use gix_features::{
     parallel,
@@ -303,6 +307,18 @@ pub mod revision;
 #[cfg(feature = "attributes")]
 pub mod filter;
 
+#[cfg(feature = "attributes")]
+pub mod stage;
+
+#[cfg(feature = "attributes")]
+pub mod hooks;
+
+#[cfg(feature = "gc")]
+pub mod gc;
+
+///
+pub mod sign;
+
 ///
 pub mod remote;
 
@@ -158,13 +158,23 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
-    // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
+    ///
+    /// Use [`verify_signature()`](Self::verify_signature()) to verify it.
     pub fn signature(
         &self,
     ) -> Result<Option<(std::borrow::Cow<'_, BStr>, gix_object::commit::SignedData<'_>)>, gix_object::decode::Error>
     {
         gix_object::CommitRefIter::signature(&self.data)
     }
+
+    /// Verify the signature of this commit with the [configured verification program](crate::Repository::verify_signature()),
+    /// or fail if the commit isn't signed, the signature is invalid or the signing key isn't trusted enough.
+    #[cfg(feature = "command")]
+    pub fn verify_signature(&self) -> Result<crate::sign::Verification, crate::sign::verify::Error> {
+        let (signature, signed_data) = self.signature()?.ok_or(crate::sign::verify::Error::Unsigned)?;
+        self.repo
+            .verify_signature(signature.as_ref(), &signed_data.to_bstring())
+    }
 }
 
 impl<'r> std::fmt::Debug for Commit<'r> {
@@ -23,6 +23,19 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Verify the signature of this tag with the [configured verification program](crate::Repository::verify_signature()),
+    /// or fail if the tag isn't signed, the signature is invalid or the signing key isn't trusted enough.
+    #[cfg(feature = "command")]
+    pub fn verify_signature(&self) -> Result<crate::sign::Verification, crate::sign::verify::Error> {
+        let signature = self
+            .decode()?
+            .pgp_signature
+            .ok_or(crate::sign::verify::Error::Unsigned)?;
+        // The signature is always the last part of the tag, and all that comes before it was signed.
+        let payload = &self.data[..self.data.len() - signature.len()];
+        self.repo.verify_signature(signature, payload)
+    }
 }
 
 /// Remove Lifetime
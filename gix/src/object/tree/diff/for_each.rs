@@ -21,6 +21,8 @@ pub enum Error {
     ResourceCache(#[from] crate::repository::diff::resource_cache::Error),
     #[error("Failure during rename tracking")]
     RenameTracking(#[from] tracker::emit::Error),
+    #[error("Could not obtain the blobs needed for rename tracking")]
+    PrefetchObjects(#[from] crate::repository::prefetch_objects::Error),
 }
 
 ///
@@ -86,6 +88,10 @@ impl<'a, 'old> Platform<'a, 'old> {
             visit: for_each,
             location: self.tracking,
             tracked: self.rewrites.map(rewrites::Tracker::new),
+            prefetch: self
+                .rewrites
+                .filter(|rewrites| rewrites.percentage.is_some() && repo.objects.promisor.is_some())
+                .map(|_| Vec::new()),
             err: None,
         };
         match gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
@@ -119,6 +125,8 @@ struct Delegate<'a, 'old, 'new, VisitFn, E> {
     recorder: gix_diff::tree::Recorder,
     visit: VisitFn,
     tracked: Option<rewrites::Tracker<gix_diff::tree::visit::Change>>,
+    /// The blobs that rename tracking will compare, to obtain them in one go if this is a partial clone.
+    prefetch: Option<Vec<gix_hash::ObjectId>>,
     location: Option<gix_diff::tree::recorder::Location>,
     err: Option<E>,
 }
@@ -179,6 +187,9 @@ where
         };
 
         let repo = self.src_tree.repo;
+        if let Some(ids) = self.prefetch.take() {
+            repo.prefetch_objects(ids)?;
+        }
         let mut storage;
         let diff_cache = match diff_cache {
             Some(cache) => cache,
@@ -260,19 +271,25 @@ where
 
     fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
         match self.tracked.as_mut() {
-            Some(tracked) => tracked.try_push_change(change, self.recorder.path()).map_or(
-                gix_diff::tree::visit::Action::Continue,
-                |change| {
-                    Self::emit_change(
+            Some(tracked) => {
+                let id = change.oid_and_entry_mode().0.to_owned();
+                match tracked.try_push_change(change, self.recorder.path()) {
+                    None => {
+                        if let Some(ids) = self.prefetch.as_mut() {
+                            ids.push(id);
+                        }
+                        gix_diff::tree::visit::Action::Continue
+                    }
+                    Some(change) => Self::emit_change(
                         change,
                         self.recorder.path(),
                         &mut self.visit,
                         self.src_tree.repo,
                         self.other_repo,
                         &mut self.err,
-                    )
-                },
-            ),
+                    ),
+                }
+            }
             None => Self::emit_change(
                 change,
                 self.recorder.path(),
@@ -52,7 +52,7 @@ pub enum Error {
     },
     #[error(transparent)]
     Io(#[from] std::io::Error),
-    #[error("The git directory at '{}' is considered unsafe as it's not owned by the current user.", .path.display())]
+    #[error("The repository at '{}' is considered unsafe as it's not owned by the current user and not listed in safe.directory.", .path.display())]
     UnsafeGitDir { path: PathBuf },
     #[error(transparent)]
     EnvironmentAccessDenied(#[from] gix_sec::permission::Error<std::path::PathBuf>),
@@ -61,6 +61,7 @@ pub enum Error {
 mod options;
 pub mod permissions;
 mod repository;
+pub(crate) use repository::{split_trust_map, trust_from_ownership};
 
 #[cfg(test)]
 mod tests {
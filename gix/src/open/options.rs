@@ -86,7 +86,10 @@ impl Options {
     /// are interpreted.
     ///
     /// If not called explicitly, it will be determined by looking at its
-    /// ownership via [`gix_sec::Trust::from_path_ownership()`].
+    /// ownership and the ownership of the worktree via [`gix_sec::Trust::from_path_ownership()`].
+    ///
+    /// Note that repositories listed in `safe.directory` of protected configuration, i.e. of all but
+    /// repository-local configuration files, are always fully trusted, just like in `git`.
     ///
     /// # Security Warning
     ///
@@ -100,7 +103,8 @@ impl Options {
     }
 
     /// If true, default false, and if the repository's trust level is not `Full`
-    /// (see [`with()`][Self::with()] for more), then the open operation will fail.
+    /// (see [`with()`][Self::with()] for more), then the open operation will fail unless
+    /// the repository is listed in `safe.directory`.
     ///
     /// Use this to mimic `git`s way of handling untrusted repositories. Note that `gitoxide` solves
     /// this by not using configuration from untrusted sources and by generally being secured against
@@ -6,6 +6,7 @@ use gix_macros::momo;
 
 use super::{Error, Options};
 use crate::{
+    bstr::ByteSlice,
     config,
     config::{
         cache::interpolate_context,
@@ -94,7 +95,7 @@ impl ThreadSafeRepository {
             .expect("we have sanitized path with is_git()")
             .into_repository_and_work_tree_directories();
         if options.git_dir_trust.is_none() {
-            options.git_dir_trust = gix_sec::Trust::from_path_ownership(&git_dir)?.into();
+            options.git_dir_trust = trust_from_ownership(&git_dir, worktree_dir.as_deref())?.into();
         }
         options.current_dir = Some(cwd);
         ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
@@ -145,16 +146,32 @@ impl ThreadSafeRepository {
             .into_repository_and_work_tree_directories();
         let worktree_dir = worktree_dir.or(overrides.worktree_dir);
 
-        let git_dir_trust = gix_sec::Trust::from_path_ownership(&git_dir)?;
-        let mut options = trust_map.into_value_by_level(git_dir_trust);
+        let git_dir_trust = trust_from_ownership(&git_dir, worktree_dir.as_deref())?;
+        let (mut options, full_trust_options) = split_trust_map(trust_map, git_dir_trust);
         options.current_dir = Some(cwd);
-        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
+        ThreadSafeRepository::open_from_paths_with_full_trust_options(
+            git_dir,
+            worktree_dir,
+            options,
+            full_trust_options,
+        )
     }
 
     pub(crate) fn open_from_paths(
+        git_dir: PathBuf,
+        worktree_dir: Option<PathBuf>,
+        options: Options,
+    ) -> Result<Self, Error> {
+        Self::open_from_paths_with_full_trust_options(git_dir, worktree_dir, options, None)
+    }
+
+    /// Like [`open_from_paths()`](Self::open_from_paths()), but if `options` aren't fully trusted and the repository is listed
+    /// in `safe.directory`, it will be reopened with `full_trust_options` instead, if present.
+    pub(crate) fn open_from_paths_with_full_trust_options(
         mut git_dir: PathBuf,
         mut worktree_dir: Option<PathBuf>,
         mut options: Options,
+        full_trust_options: Option<Options>,
     ) -> Result<Self, Error> {
         let _span = gix_trace::detail!("open_from_paths()");
         let Options {
@@ -247,14 +264,33 @@ impl ThreadSafeRepository {
             cli_config_overrides,
         )?;
 
-        if bail_if_untrusted && git_dir_trust != gix_sec::Trust::Full {
-            check_safe_directories(
-                &git_dir,
+        if git_dir_trust != gix_sec::Trust::Full {
+            let repo_path = worktree_dir.as_deref().unwrap_or(&git_dir);
+            match safe_directory(
+                repo_path,
                 git_install_dir.as_deref(),
                 current_dir,
                 home.as_deref(),
                 &config,
-            )?;
+            ) {
+                Ok(()) => {
+                    // Just like `git`, repositories listed in `safe.directory` are used as if they were owned by us,
+                    // which includes using the permissions of fully trusted repositories.
+                    let mut options = match full_trust_options {
+                        Some(mut full_trust_options) => {
+                            full_trust_options.api_config_overrides = std::mem::take(&mut options.api_config_overrides);
+                            full_trust_options.cli_config_overrides = std::mem::take(&mut options.cli_config_overrides);
+                            full_trust_options.current_dir = options.current_dir.take();
+                            full_trust_options
+                        }
+                        None => options,
+                    };
+                    options.git_dir_trust = Some(gix_sec::Trust::Full);
+                    return ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options);
+                }
+                Err(path) if bail_if_untrusted => return Err(Error::UnsafeGitDir { path }),
+                Err(_) => {}
+            }
         }
 
         // core.worktree might be used to overwrite the worktree directory
@@ -370,18 +406,50 @@ fn replacement_objects_refs_prefix(
     Ok(ref_base.into())
 }
 
-fn check_safe_directories(
+/// Return the options of `trust_map` for `trust` along with the options to use if the repository turns out to be trusted
+/// via `safe.directory` after all.
+pub(crate) fn split_trust_map(
+    trust_map: gix_sec::trust::Mapping<Options>,
+    trust: gix_sec::Trust,
+) -> (Options, Option<Options>) {
+    match trust {
+        gix_sec::Trust::Full => (trust_map.full, None),
+        gix_sec::Trust::Reduced => (trust_map.reduced, Some(trust_map.full)),
+    }
+}
+
+/// Return the trust level of the repository at `git_dir` with `worktree_dir` by the ownership of both, which both must
+/// be owned by the current user for the repository to be trusted fully, just like `git` does.
+pub(crate) fn trust_from_ownership(
     git_dir: &std::path::Path,
+    worktree_dir: Option<&std::path::Path>,
+) -> std::io::Result<gix_sec::Trust> {
+    let git_dir_trust = gix_sec::Trust::from_path_ownership(git_dir)?;
+    Ok(match worktree_dir.filter(|dir| dir.is_dir()) {
+        Some(worktree_dir) if git_dir_trust == gix_sec::Trust::Full => {
+            gix_sec::Trust::from_path_ownership(worktree_dir)?
+        }
+        _ => git_dir_trust,
+    })
+}
+
+/// Return `Ok(())` if the repository at `repo_path`, the worktree directory or the `.git` directory of bare repositories,
+/// is listed in `safe.directory` of protected configuration, or the real path of `repo_path` otherwise.
+///
+/// Values can be `*` to allow all repositories, paths ending in `/*` to allow all repositories within them, and the empty
+/// value to reset all prior values.
+fn safe_directory(
+    repo_path: &std::path::Path,
     git_install_dir: Option<&std::path::Path>,
     current_dir: &std::path::Path,
     home: Option<&std::path::Path>,
     config: &config::Cache,
-) -> Result<(), Error> {
-    let mut is_safe = false;
-    let git_dir = match gix_path::realpath_opts(git_dir, current_dir, gix_path::realpath::MAX_SYMLINKS) {
-        Ok(p) => p,
-        Err(_) => git_dir.to_owned(),
+) -> Result<(), PathBuf> {
+    let realpath = |path: &std::path::Path| {
+        gix_path::realpath_opts(path, current_dir, gix_path::realpath::MAX_SYMLINKS).unwrap_or_else(|_| path.to_owned())
     };
+    let repo_path = realpath(repo_path);
+    let mut is_safe = false;
     for safe_dir in config
         .resolved
         .strings_filter("safe", None, Safe::DIRECTORY.name, &mut Safe::directory_filter)
@@ -395,22 +463,33 @@ fn check_safe_directories(
             is_safe = false;
             continue;
         }
-        if !is_safe {
-            let safe_dir = match gix_config::Path::from(std::borrow::Cow::Borrowed(safe_dir.as_ref()))
-                .interpolate(interpolate_context(git_install_dir, home))
-            {
-                Ok(path) => path,
-                Err(_) => gix_path::from_bstr(safe_dir),
-            };
-            if safe_dir == git_dir {
-                is_safe = true;
-                continue;
-            }
+        if is_safe {
+            continue;
+        }
+        let (safe_dir, is_prefix) = match safe_dir.strip_suffix(b"/*") {
+            Some(prefix) => (prefix.as_bstr(), true),
+            None => (safe_dir.as_ref(), false),
+        };
+        let safe_dir = match gix_config::Path::from(Cow::Borrowed(safe_dir))
+            .interpolate(interpolate_context(git_install_dir, home))
+        {
+            Ok(path) => path,
+            Err(_) => gix_path::from_bstr(safe_dir),
+        };
+        // Relative paths make no sense as they would be relative to the current directory, so git ignores them.
+        if !safe_dir.is_absolute() {
+            continue;
         }
+        let safe_dir = realpath(&safe_dir);
+        is_safe = if is_prefix {
+            repo_path != safe_dir && repo_path.starts_with(&safe_dir)
+        } else {
+            repo_path == safe_dir
+        };
     }
     if is_safe {
         Ok(())
     } else {
-        Err(Error::UnsafeGitDir { path: git_dir })
+        Err(repo_path)
     }
 }
@@ -17,6 +17,12 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[cfg(feature = "attributes")]
+        #[error(transparent)]
+        Hooks(#[from] crate::hooks::init::Error),
+        #[cfg(feature = "attributes")]
+        #[error(transparent)]
+        Hook(#[from] crate::hooks::run::Error),
     }
 }
 
@@ -65,6 +65,8 @@ impl<'repo> Remote<'repo> {
             transport_options: None,
             transport,
             trace,
+            dumb_http: false,
+            remote_shallow_commits: Vec::new(),
         }
     }
 
@@ -0,0 +1,234 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use super::{config, fetch_head};
+use crate::{
+    bstr::BString,
+    remote,
+    remote::{
+        connection::{ref_map, HandshakeWithRefs},
+        fetch,
+        fetch::{outcome, refs, Outcome, RefLogMessage, Status},
+    },
+    Remote,
+};
+
+/// Options for use in [`Remote::fetch_bundle()`].
+#[derive(Default)]
+pub struct Options {
+    /// A list of refspecs to use in addition to the ones of the remote, which won't be saved.
+    ///
+    /// This is the equivalent of [`ref_map::Options::extra_refspecs`](crate::remote::ref_map::Options::extra_refspecs).
+    pub extra_refspecs: Vec<gix_refspec::RefSpec>,
+    /// If `true`, don't make any change to the repository, similar to [`Prepare::with_dry_run()`](fetch::Prepare::with_dry_run()).
+    pub dry_run: bool,
+    /// If `true`, write ref updates to `packed-refs` only, similar to [`Prepare::with_write_packed_refs_only()`](fetch::Prepare::with_write_packed_refs_only()).
+    pub write_packed_refs_only: bool,
+    /// The reflog message to use when updating refs, or `None` to use the default which is prefixed with `fetch`.
+    pub reflog_message: Option<RefLogMessage>,
+    /// If `true`, write `FETCH_HEAD` after updating refs, similar to [`Prepare::with_write_fetch_head()`](fetch::Prepare::with_write_fetch_head()).
+    pub write_fetch_head: bool,
+}
+
+/// The error returned by [`Remote::fetch_bundle()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The {} url was missing - don't know which bundle to fetch from", remote::Direction::Fetch.as_str())]
+    MissingUrl,
+    #[error("The url {url:?} doesn't point to a bundle file")]
+    NotABundle { url: BString },
+    #[error(transparent)]
+    SchemePermission(#[from] crate::config::protocol::allow::Error),
+    #[error("Protocol {scheme:?} of url {url:?} is denied per configuration")]
+    ProtocolDenied { url: BString, scheme: gix_url::Scheme },
+    #[error(transparent)]
+    Open(#[from] gix_bundle::file::init::Error),
+    #[error("Cannot fetch from a bundle that uses {remote} while local repository uses {local} for object hashes")]
+    IncompatibleObjectHash {
+        local: gix_hash::Kind,
+        remote: gix_hash::Kind,
+    },
+    #[error(transparent)]
+    RefMap(#[from] ref_map::Error),
+    #[error(transparent)]
+    Unpack(#[from] gix_bundle::file::unpack::Error),
+    #[error(transparent)]
+    Fetch(#[from] fetch::Error),
+}
+
+/// Fetching from bundles
+impl<'repo> Remote<'repo> {
+    /// Return the path to the bundle file that our fetch url points to, or `None` if it's not a `file://` url or a path
+    /// that points to a bundle.
+    pub fn bundle_path(&self) -> Option<PathBuf> {
+        let url = self.url(remote::Direction::Fetch)?;
+        if url.scheme != gix_url::Scheme::File {
+            return None;
+        }
+        let path = gix_path::from_bstr(url.path.as_ref()).into_owned();
+        let path = if path.is_relative() {
+            self.repo.options.current_dir_or_empty().join(path)
+        } else {
+            path
+        };
+        (path.is_file() && gix_bundle::is_bundle(&path)).then_some(path)
+    }
+
+    /// Fetch from the bundle file that our fetch url points to, similar to [`Prepare::receive()`](fetch::Prepare::receive()),
+    /// but without connecting to a remote and without negotiation, as the bundle contains everything it can provide.
+    ///
+    /// The references of the bundle are matched against the refspecs of this remote along with those in `options`, and if
+    /// any of them points to an object we don't have, the pack of the bundle is written into the object database after
+    /// assuring its prerequisites are present.
+    /// `progress` and `should_interrupt` are used while writing the pack.
+    ///
+    /// If the bundle contains `HEAD`, it will be advertised as symbolic reference to the first branch which points to
+    /// the same commit, similar to what `git` does when cloning from a bundle.
+    #[allow(clippy::result_large_err)]
+    pub fn fetch_bundle(
+        &self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("remote::fetch_bundle()");
+        let repo = self.repo;
+        let url = self.url(remote::Direction::Fetch).ok_or(Error::MissingUrl)?;
+        if !repo.config.url_scheme()?.allow(&url.scheme) {
+            return Err(Error::ProtocolDenied {
+                url: url.to_bstring(),
+                scheme: url.scheme.clone(),
+            });
+        }
+        let path = self
+            .bundle_path()
+            .ok_or_else(|| Error::NotABundle { url: url.to_bstring() })?;
+        let bundle = gix_bundle::File::at(path)?;
+        let header = bundle.header();
+        if header.object_hash != repo.object_hash() {
+            return Err(Error::IncompatibleObjectHash {
+                local: repo.object_hash(),
+                remote: header.object_hash,
+            });
+        }
+
+        let ref_map = ref_map::map_remote_refs(
+            self,
+            HandshakeWithRefs {
+                outcome: gix_protocol::handshake::Outcome {
+                    server_protocol_version: gix_protocol::transport::Protocol::V1,
+                    refs: None,
+                    capabilities: gix_protocol::transport::client::Capabilities::from_bytes(b"\0object-format=sha1")
+                        .expect("valid static capabilities")
+                        .0,
+                },
+                refs: remote_refs(header),
+            },
+            ref_map::with_implied_tag_spec(self, options.extra_refspecs),
+        )?;
+
+        let dry_run = if options.dry_run {
+            fetch::DryRun::Yes
+        } else {
+            fetch::DryRun::No
+        };
+        let needs_pack = ref_map
+            .mappings
+            .iter()
+            .filter_map(|m| m.remote.as_id())
+            .any(|id| !repo.has_object(id));
+        let mut write_pack_bundle = if needs_pack && !options.dry_run {
+            Some(bundle.unpack(
+                Some(&repo.objects.store_ref().path().join("pack")),
+                repo.objects.clone(),
+                progress,
+                should_interrupt,
+                gix_pack::bundle::write::Options {
+                    thread_limit: config::index_threads(repo)?,
+                    index_version: config::pack_index_version(repo)?,
+                    iteration_mode: gix_pack::data::input::Mode::Verify,
+                    object_hash: repo.object_hash(),
+                },
+            )?)
+        } else {
+            None
+        };
+
+        let update_refs = refs::update(
+            repo,
+            options
+                .reflog_message
+                .unwrap_or_else(|| RefLogMessage::Prefixed { action: "fetch".into() }),
+            &ref_map.mappings,
+            self.refspecs(remote::Direction::Fetch),
+            &ref_map.extra_refspecs,
+            self.fetch_tags,
+            dry_run,
+            if options.write_packed_refs_only {
+                fetch::WritePackedRefs::Only
+            } else {
+                fetch::WritePackedRefs::Never
+            },
+        )
+        .map_err(fetch::Error::from)?;
+        if options.write_fetch_head && !options.dry_run {
+            fetch_head::write(self, &ref_map.mappings, &update_refs.updates)?;
+        }
+
+        if let Some(bundle) = write_pack_bundle.as_mut() {
+            if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
+                if let Some(path) = bundle.keep_path.take() {
+                    std::fs::remove_file(&path)
+                        .map_err(|err| fetch::Error::RemovePackKeepFile { path, source: err })?;
+                }
+            }
+        }
+
+        Ok(Outcome {
+            ref_map,
+            status: match write_pack_bundle {
+                Some(write_pack_bundle) => Status::Change {
+                    negotiate: outcome::Negotiate::default(),
+                    write_pack_bundle,
+                    update_refs,
+                },
+                None => Status::NoPackReceived {
+                    dry_run: options.dry_run,
+                    negotiate: None,
+                    update_refs,
+                },
+            },
+            bundle_uri: None,
+        })
+    }
+}
+
+/// Turn the references of a bundle into references as a remote would advertise them.
+fn remote_refs(header: &gix_bundle::Header) -> Vec<gix_protocol::handshake::Ref> {
+    header
+        .refs
+        .iter()
+        .map(|r| {
+            let branch = (r.name == "HEAD")
+                .then(|| {
+                    header
+                        .refs
+                        .iter()
+                        .find(|branch| branch.id == r.id && branch.name.starts_with(b"refs/heads/"))
+                })
+                .flatten();
+            match branch {
+                Some(branch) => gix_protocol::handshake::Ref::Symbolic {
+                    full_ref_name: r.name.clone(),
+                    target: branch.name.clone(),
+                    tag: None,
+                    object: r.id,
+                },
+                None => gix_protocol::handshake::Ref::Direct {
+                    full_ref_name: r.name.clone(),
+                    object: r.id,
+                },
+            }
+        })
+        .collect()
+}
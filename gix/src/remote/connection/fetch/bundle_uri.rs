@@ -0,0 +1,234 @@
+//! Apply bundles advertised by the remote or provided by the user to the object database before negotiating a pack.
+use std::sync::{atomic::AtomicBool, Arc};
+
+use gix_protocol::bundle_uri::{Heuristic, List, Mode};
+use gix_ref::transaction::PreviousValue;
+
+use super::{config, download, outcome};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error recorded for each bundle or bundle list that couldn't be applied.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Download(#[from] download::Error),
+    #[error("Could not write the downloaded bundle to a temporary file")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Open(#[from] gix_bundle::file::init::Error),
+    #[error(transparent)]
+    Unpack(#[from] gix_bundle::file::unpack::Error),
+    #[error("Could not parse bundle list")]
+    ListConfig(#[from] gix_config::file::init::Error),
+    #[error(transparent)]
+    List(#[from] gix_protocol::bundle_uri::decode::Error),
+    #[error("Bundle lists can't refer to other bundle lists")]
+    NestedList,
+    #[error(transparent)]
+    PackOptions(#[from] super::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
+
+/// What we obtained from a bundle URI.
+enum Downloaded {
+    /// A bundle, stored in a temporary file that is removed when dropped.
+    Bundle(gix_bundle::File, gix_tempfile::Handle<gix_tempfile::handle::Writable>),
+    /// A list of bundles which in turn have to be downloaded.
+    List(List),
+}
+
+/// Download the bundle or bundle list at `uri` and apply it to the object database of `repo`, recording the result in `out`.
+pub(crate) fn apply_uri(
+    repo: &crate::Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    out: &mut outcome::BundleUri,
+) {
+    let res = download(repo, remote_name, uri).and_then(|downloaded| match downloaded {
+        Downloaded::Bundle(bundle, _tmp) => apply_bundle(repo, &bundle, progress, should_interrupt),
+        Downloaded::List(list) => {
+            apply_list(repo, remote_name, list, uri, progress, should_interrupt, out);
+            Ok(false)
+        }
+    });
+    record(out, uri, res);
+}
+
+/// Download all bundles of `list` which was obtained from `base`, and apply them to the object database of `repo` as
+/// dictated by the list's mode, recording the result in `out`.
+pub(crate) fn apply_list(
+    repo: &crate::Repository,
+    remote_name: Option<&BStr>,
+    list: List,
+    base: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    out: &mut outcome::BundleUri,
+) {
+    let mut uris: Vec<_> = list.bundles;
+    if list.heuristic == Some(Heuristic::CreationToken) {
+        uris.sort_by_key(|b| b.creation_token.unwrap_or(u64::MAX));
+    }
+    let mut pending: Vec<_> = uris
+        .into_iter()
+        .map(|b| download::resolve(base, b.uri.as_ref()))
+        .collect();
+    match list.mode {
+        Mode::Any => {
+            for uri in pending {
+                let res = download_and_apply(repo, remote_name, uri.as_ref(), progress, should_interrupt);
+                if record(out, uri.as_ref(), res) {
+                    break;
+                }
+            }
+        }
+        Mode::All => {
+            // Without ordering information, bundles may depend on bundles that come later, so retry as long as we make progress.
+            loop {
+                let mut missing_prerequisites = Vec::new();
+                let num_pending = pending.len();
+                for uri in pending {
+                    let res = download_and_apply(repo, remote_name, uri.as_ref(), progress, should_interrupt);
+                    match res {
+                        Err(err @ Error::Unpack(gix_bundle::file::unpack::Error::MissingPrerequisites { .. })) => {
+                            missing_prerequisites.push((uri, err));
+                        }
+                        res => {
+                            record(out, uri.as_ref(), res);
+                        }
+                    }
+                }
+                if missing_prerequisites.is_empty() || missing_prerequisites.len() == num_pending {
+                    for (uri, err) in missing_prerequisites {
+                        record(out, uri.as_ref(), Err(err));
+                    }
+                    break;
+                }
+                pending = missing_prerequisites.into_iter().map(|(uri, _)| uri).collect();
+            }
+        }
+    }
+}
+
+/// Download the bundle at `uri` and apply it, without allowing it to be a bundle list.
+fn download_and_apply(
+    repo: &crate::Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<bool, Error> {
+    match download(repo, remote_name, uri)? {
+        Downloaded::Bundle(bundle, _tmp) => apply_bundle(repo, &bundle, progress, should_interrupt),
+        Downloaded::List(_) => Err(Error::NestedList),
+    }
+}
+
+/// Put the outcome of applying `uri` into `out`, and return `true` if a bundle was applied.
+fn record(out: &mut outcome::BundleUri, uri: &BStr, res: Result<bool, Error>) -> bool {
+    match res {
+        Ok(applied) => {
+            if applied {
+                out.applied.push(uri.to_owned());
+            }
+            applied
+        }
+        Err(err) => {
+            out.failed.push((uri.to_owned(), Arc::new(err)));
+            false
+        }
+    }
+}
+
+fn download(repo: &crate::Repository, remote_name: Option<&BStr>, uri: &BStr) -> Result<Downloaded, Error> {
+    let mut input = download::open(repo, remote_name, uri)?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    std::fs::create_dir_all(&pack_dir)?;
+    let mut tmp = gix_tempfile::new(
+        &pack_dir,
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )?;
+    let path = tmp.with_mut(|file| -> std::io::Result<_> {
+        std::io::copy(&mut input, file)?;
+        Ok(file.path().to_owned())
+    })??;
+    Ok(if gix_bundle::is_bundle(&path) {
+        Downloaded::Bundle(gix_bundle::File::at(path)?, tmp)
+    } else {
+        Downloaded::List(list_from_config(&std::fs::read(path)?)?)
+    })
+}
+
+/// Parse a bundle list as stored in a file with `git-config` syntax.
+fn list_from_config(input: &[u8]) -> Result<List, Error> {
+    let file = gix_config::File::from_bytes_no_includes(input, gix_config::file::Metadata::api(), Default::default())?;
+    let mut lines = Vec::<BString>::new();
+    for section in file.sections_by_name("bundle").into_iter().flatten() {
+        let mut prefix = BString::from("bundle.");
+        if let Some(id) = section.header().subsection_name() {
+            prefix.extend_from_slice(id);
+            prefix.push(b'.');
+        }
+        for key in section.keys() {
+            if let Some(value) = section.value(key.as_ref()) {
+                let mut line = prefix.clone();
+                line.extend_from_slice(key.as_ref().as_bytes());
+                line.push(b'=');
+                line.extend_from_slice(&value);
+                lines.push(line);
+            }
+        }
+    }
+    Ok(List::from_lines(lines.iter().map(AsRef::as_ref))?)
+}
+
+/// Unpack `bundle` into the object database of `repo` and store its branches in `refs/bundles/`.
+/// Return `true` as the bundle was applied, which is also the case if there was nothing to unpack.
+fn apply_bundle(
+    repo: &crate::Repository,
+    bundle: &gix_bundle::File,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<bool, Error> {
+    let header = bundle.header();
+    let needs_pack = header.refs.iter().any(|r| !repo.has_object(r.id));
+    let keep_path = if needs_pack {
+        bundle
+            .unpack(
+                Some(&repo.objects.store_ref().path().join("pack")),
+                repo.objects.clone(),
+                progress,
+                should_interrupt,
+                gix_pack::bundle::write::Options {
+                    thread_limit: config::index_threads(repo)?,
+                    index_version: config::pack_index_version(repo)?,
+                    iteration_mode: gix_pack::data::input::Mode::Verify,
+                    object_hash: repo.object_hash(),
+                },
+            )?
+            .keep_path
+    } else {
+        None
+    };
+    for r in &header.refs {
+        if let Some(name) = r.name.strip_prefix(b"refs/heads/") {
+            let mut bundle_ref = BString::from("refs/bundles/");
+            bundle_ref.extend_from_slice(name);
+            repo.reference(
+                bundle_ref.as_bstr(),
+                r.id,
+                PreviousValue::Any,
+                "bundle-uri: store bundle reference",
+            )?;
+        }
+    }
+    if let Some(keep_path) = keep_path {
+        std::fs::remove_file(keep_path)?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,87 @@
+//! Download packs and bundles from URIs provided by the remote, in addition to what it sends directly.
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned when downloading from a URI advertised by the remote.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error("Cannot download from {uri:?} as the {scheme} protocol isn't supported or disabled at compile time")]
+    UnsupportedScheme { uri: BString, scheme: gix_url::Scheme },
+    #[error("Could not download from {uri:?}")]
+    Io { uri: BString, source: std::io::Error },
+    #[error(transparent)]
+    TransportOptions(#[from] crate::config::transport::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error(transparent)]
+    Http(#[from] gix_protocol::transport::client::http::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error("Could not configure the http client")]
+    ConfigureHttp(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Resolve `uri` relative to `base`, the URI that the list containing `uri` was obtained from, unless it's absolute already.
+#[cfg(feature = "bundle")]
+pub(crate) fn resolve(base: &BStr, uri: &BStr) -> BString {
+    if uri.contains_str("://") || uri.starts_with(b"/") {
+        return uri.to_owned();
+    }
+    let mut out: BString = match base.rfind_byte(b'/') {
+        Some(pos) => base[..=pos].into(),
+        None => BString::default(),
+    };
+    out.extend_from_slice(uri);
+    out
+}
+
+/// Open the content at `uri` for reading, using the http configuration of `repo` for the remote named `remote_name`.
+///
+/// Local paths and `file://` URIs are supported, along with `http(s)://` if a blocking http transport is available.
+#[cfg_attr(
+    not(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    )),
+    allow(unused_variables)
+)]
+pub(crate) fn open(
+    repo: &crate::Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+) -> Result<Box<dyn std::io::BufRead>, Error> {
+    let url = gix_url::parse(uri)?;
+    Ok(match url.scheme {
+        gix_url::Scheme::File => Box::new(std::io::BufReader::new(
+            std::fs::File::open(gix_path::from_bstr(url.path.as_bstr())).map_err(|source| Error::Io {
+                uri: uri.to_owned(),
+                source,
+            })?,
+        )),
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        gix_url::Scheme::Http | gix_url::Scheme::Https => {
+            use gix_protocol::transport::client::http::Http;
+            let mut http = gix_protocol::transport::client::http::Impl::default();
+            if let Some(options) = repo.transport_options(uri, remote_name)? {
+                http.configure(options.as_ref()).map_err(Error::ConfigureHttp)?;
+            }
+            let uri = uri.to_str_lossy();
+            Box::new(http.get(&uri, &uri, std::iter::empty::<&str>())?.body)
+        }
+        scheme => {
+            return Err(Error::UnsupportedScheme {
+                uri: uri.to_owned(),
+                scheme,
+            })
+        }
+    })
+}
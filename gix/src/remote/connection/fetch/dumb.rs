@@ -0,0 +1,628 @@
+//! Fetch from servers which only support the 'dumb' HTTP protocol, which serves a repository as static files.
+//!
+//! The references are read from `info/refs` and `HEAD`, and objects are obtained by walking the commit graph from each tip
+//! that we don't have, downloading loose objects and, if these don't exist, the packs listed in `objects/info/packs` which
+//! contain them.
+#[cfg(feature = "blocking-network-client")]
+use std::io::Read;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::{oid, ObjectId};
+use gix_odb::Write;
+#[cfg(feature = "async-network-client")]
+use gix_protocol::futures_lite::AsyncReadExt;
+use gix_protocol::{handshake, transport::client::Transport};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    remote,
+    remote::{
+        connection::fetch::{config, fetch_head},
+        fetch,
+        fetch::{negotiate, refs, Outcome, Prepare, RefLogMessage, Shallow, Status},
+        Connection,
+    },
+    Repository,
+};
+
+/// The error returned when fetching from a server which only supports the 'dumb' HTTP protocol.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Transport(#[from] gix_protocol::transport::client::Error),
+    #[error("Could not read {path:?} from the remote")]
+    ReadFile { path: String, source: std::io::Error },
+    #[error("The remote doesn't provide 'info/refs', which is created by running 'git update-server-info' in the remote repository")]
+    MissingInfoRefs,
+    #[error("Could not parse line {line:?} of {path:?}")]
+    ParseLine { path: &'static str, line: BString },
+    #[error("The object {id} isn't available on the remote, neither as loose object nor in any of its packs")]
+    ObjectNotFound { id: ObjectId },
+    #[error("The loose object {id} received from the remote could not be decompressed")]
+    Inflate {
+        id: ObjectId,
+        source: gix_features::zlib::inflate::Error,
+    },
+    #[error("The header of the loose object {id} received from the remote could not be decoded")]
+    LooseObjectHeader {
+        id: ObjectId,
+        source: gix_object::decode::LooseHeaderDecodeError,
+    },
+    #[error(
+        "The loose object {id} received from the remote was truncated or didn't have the size stated in its header"
+    )]
+    CorruptLooseObject { id: ObjectId },
+    #[error("The loose object received as {expected} actually has id {actual}")]
+    LooseObjectChecksumMismatch { expected: ObjectId, actual: ObjectId },
+    #[error("The index of pack {name:?} could not be parsed")]
+    PackIndex { name: String },
+    #[error("The pack {name:?} has checksum {actual}, which doesn't match its name")]
+    PackChecksumMismatch { name: String, actual: ObjectId },
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+impl gix_protocol::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Transport(err) => err.is_spurious(),
+            Error::ReadFile { source, .. } => source.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// Return the handshake outcome we pretend to have received from a server which doesn't perform a handshake at all.
+pub(crate) fn handshake_outcome() -> handshake::Outcome {
+    handshake::Outcome {
+        server_protocol_version: gix_protocol::transport::Protocol::V1,
+        refs: None,
+        capabilities: gix_protocol::transport::client::Capabilities::from_bytes(b"\0object-format=sha1")
+            .expect("valid static capabilities")
+            .0,
+    }
+}
+
+/// Obtain all references listed in `info/refs` of the remote behind `transport`, along with `HEAD` if it exists.
+#[gix_protocol::maybe_async::maybe_async]
+pub(crate) async fn refs<T>(transport: &mut T) -> Result<Vec<handshake::Ref>, Error>
+where
+    T: Transport,
+{
+    let info_refs = read_file(transport, "info/refs").await?.ok_or(Error::MissingInfoRefs)?;
+    let mut refs = parse_info_refs(&info_refs)?;
+    if let Some(head) = read_file(transport, "HEAD").await? {
+        let head = parse_head(&head, &refs)?;
+        refs.insert(0, head);
+    }
+    Ok(refs)
+}
+
+impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Like [`receive_inner()`](Self::receive_inner()), but for servers which only support the 'dumb' HTTP protocol
+    /// and thus can't negotiate a pack.
+    #[gix_protocol::maybe_async::maybe_async]
+    #[allow(clippy::drop_non_drop)]
+    pub(crate) async fn receive_dumb(
+        mut self,
+        mut con: Connection<'remote, 'repo, T>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, fetch::Error> {
+        let _span = gix_trace::coarse!("fetch::Prepare::receive_dumb()");
+        let repo = con.remote.repo;
+        if self.ref_map.object_hash != repo.object_hash() {
+            return Err(fetch::Error::IncompatibleObjectHash {
+                local: repo.object_hash(),
+                remote: self.ref_map.object_hash,
+            });
+        }
+        if self.shallow != Shallow::NoChange || repo.is_shallow() {
+            return Err(fetch::Error::MissingServerFeature {
+                feature: "shallow",
+                description: "the 'dumb' HTTP protocol can only provide complete histories",
+            });
+        }
+        if self.filter.is_some() {
+            return Err(fetch::Error::MissingServerFeature {
+                feature: "filter",
+                description: "the 'dumb' HTTP protocol can only provide all objects",
+            });
+        }
+
+        // Objects we don't have must remain missing, instead of being fetched from a promisor remote.
+        let walk_repo = {
+            let mut r = repo.clone();
+            r.objects.promisor = None;
+            r
+        };
+        let is_ignored = negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map);
+        let wants: Vec<_> = self
+            .ref_map
+            .mappings
+            .iter()
+            .filter(|m| !is_ignored(m))
+            .filter_map(|m| m.remote.as_id())
+            .filter(|id| !walk_repo.has_object(id))
+            .map(ToOwned::to_owned)
+            .collect();
+
+        let mut received = None;
+        if !wants.is_empty() && matches!(self.dry_run, fetch::DryRun::No) {
+            let options = gix_pack::bundle::write::Options {
+                thread_limit: config::index_threads(repo)?,
+                index_version: config::pack_index_version(repo)?,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                object_hash: repo.object_hash(),
+            };
+            progress.init(None, gix_features::progress::count("objects"));
+            progress.set_name("receiving objects (dumb http)".into());
+            let mut walk = Walk::default();
+            walk.run(
+                &walk_repo,
+                &mut con.transport,
+                wants,
+                progress,
+                should_interrupt,
+                &options,
+            )
+            .await?;
+
+            // Tags are included if the objects they point to are present, which is what a server would do for us as well.
+            let included_tags: Vec<_> = self
+                .ref_map
+                .mappings
+                .iter()
+                .filter(|m| is_ignored(m))
+                .filter_map(|m| match &m.remote {
+                    fetch::Source::Ref(handshake::Ref::Peeled { tag, object, .. }) => Some((tag, object)),
+                    _ => None,
+                })
+                .filter(|(tag, object)| walk_repo.has_object(object) && !walk_repo.has_object(tag))
+                .map(|(tag, _)| tag.to_owned())
+                .collect();
+            walk.run(
+                &walk_repo,
+                &mut con.transport,
+                included_tags,
+                progress,
+                should_interrupt,
+                &options,
+            )
+            .await?;
+            received = Some(walk);
+        }
+        drop(is_ignored);
+
+        let update_refs = refs::update(
+            repo,
+            self.reflog_message
+                .take()
+                .unwrap_or_else(|| RefLogMessage::Prefixed { action: "fetch".into() }),
+            &self.ref_map.mappings,
+            con.remote.refspecs(remote::Direction::Fetch),
+            &self.ref_map.extra_refspecs,
+            con.remote.fetch_tags,
+            self.dry_run,
+            self.write_packed_refs,
+        )?;
+        if self.write_fetch_head && matches!(self.dry_run, fetch::DryRun::No) {
+            fetch_head::write(con.remote, &self.ref_map.mappings, &update_refs.updates)?;
+        }
+
+        let status = match received {
+            Some(mut walk) => {
+                for bundle in &mut walk.write_pack_bundles {
+                    if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
+                        if let Some(path) = bundle.keep_path.take() {
+                            std::fs::remove_file(&path)
+                                .map_err(|err| fetch::Error::RemovePackKeepFile { path, source: err })?;
+                        }
+                    }
+                }
+                Status::ChangeViaDumbHttp {
+                    write_pack_bundles: walk.write_pack_bundles,
+                    loose_objects: walk.loose.len(),
+                    update_refs,
+                }
+            }
+            None => Status::NoPackReceived {
+                dry_run: matches!(self.dry_run, fetch::DryRun::Yes),
+                negotiate: None,
+                update_refs,
+            },
+        };
+        Ok(Outcome {
+            ref_map: std::mem::take(&mut self.ref_map),
+            status,
+            bundle_uri: None,
+        })
+    }
+}
+
+/// The state of obtaining objects from the remote.
+#[derive(Default)]
+struct Walk {
+    /// The packs listed in `objects/info/packs` that we don't have and didn't download yet,
+    /// or `None` if the list wasn't obtained yet.
+    remote_packs: Option<Vec<RemotePack>>,
+    /// The indices of all packs we downloaded.
+    received_packs: Vec<PackIndex>,
+    /// The loose objects we downloaded.
+    loose: HashSet<ObjectId>,
+    /// Information about each pack that was downloaded, in order.
+    write_pack_bundles: Vec<gix_pack::bundle::write::Outcome>,
+}
+
+/// A pack on the remote.
+struct RemotePack {
+    /// The name of the pack without extension, like `pack-<checksum>`.
+    name: String,
+    /// The objects in the pack, or `None` if the index wasn't downloaded yet.
+    index: Option<PackIndex>,
+}
+
+impl Walk {
+    /// Obtain all objects reachable from `tips` that we don't have, assuming that all objects we already have are complete.
+    #[gix_protocol::maybe_async::maybe_async]
+    async fn run<T>(
+        &mut self,
+        repo: &Repository,
+        transport: &mut T,
+        tips: Vec<ObjectId>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &gix_pack::bundle::write::Options,
+    ) -> Result<(), Error>
+    where
+        T: Transport,
+    {
+        let mut seen: HashSet<_> = tips.iter().copied().collect();
+        let mut queue: VecDeque<_> = tips.into_iter().map(|id| (id, None)).collect();
+        while let Some((id, kind)) = queue.pop_front() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if repo.has_object(id) {
+                if !self.is_received(&id) {
+                    continue;
+                }
+            } else {
+                self.obtain(repo, transport, &id, progress, should_interrupt, options)
+                    .await?;
+            }
+            if kind == Some(gix_object::Kind::Blob) {
+                continue;
+            }
+
+            let object = repo.find_object(id)?;
+            let mut add = |id: ObjectId, kind: gix_object::Kind| {
+                if seen.insert(id) {
+                    queue.push_back((id, Some(kind)));
+                }
+            };
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let mut commit = gix_object::CommitRefIter::from_bytes(&object.data);
+                    add(commit.tree_id()?, gix_object::Kind::Tree);
+                    for parent_id in commit.parent_ids() {
+                        add(parent_id, gix_object::Kind::Commit);
+                    }
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(&object.data) {
+                        let entry = entry?;
+                        if entry.mode.is_commit() {
+                            continue;
+                        }
+                        let kind = if entry.mode.is_tree() {
+                            gix_object::Kind::Tree
+                        } else {
+                            gix_object::Kind::Blob
+                        };
+                        add(entry.oid.to_owned(), kind);
+                    }
+                }
+                gix_object::Kind::Tag => {
+                    let target = gix_object::TagRefIter::from_bytes(&object.data).target_id()?;
+                    if seen.insert(target) {
+                        queue.push_back((target, None));
+                    }
+                }
+                gix_object::Kind::Blob => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the object with `id` was obtained by us, instead of being present beforehand.
+    fn is_received(&self, id: &oid) -> bool {
+        self.loose.contains(id) || self.received_packs.iter().any(|index| index.contains(id))
+    }
+
+    /// Download the object with `id` as loose object, or download the pack that contains it.
+    #[gix_protocol::maybe_async::maybe_async]
+    async fn obtain<T>(
+        &mut self,
+        repo: &Repository,
+        transport: &mut T,
+        id: &oid,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &gix_pack::bundle::write::Options,
+    ) -> Result<(), Error>
+    where
+        T: Transport,
+    {
+        let hex = id.to_hex().to_string();
+        if let Some(compressed) = read_file(transport, &format!("objects/{}/{}", &hex[..2], &hex[2..])).await? {
+            write_loose_object(repo, id, &compressed)?;
+            self.loose.insert(id.to_owned());
+            progress.inc();
+            return Ok(());
+        }
+
+        let pack_dir = repo.objects.store_ref().path().join("pack");
+        if self.remote_packs.is_none() {
+            let packs = match read_file(transport, "objects/info/packs").await? {
+                Some(list) => parse_pack_list(&list)?,
+                None => Vec::new(),
+            };
+            self.remote_packs = Some(
+                packs
+                    .into_iter()
+                    .filter(|name| !pack_dir.join(format!("{name}.pack")).is_file())
+                    .map(|name| RemotePack { name, index: None })
+                    .collect(),
+            );
+        }
+        let remote_packs = self.remote_packs.as_mut().expect("just initialized");
+        for pack_idx in 0..remote_packs.len() {
+            let pack = &mut remote_packs[pack_idx];
+            if pack.index.is_none() {
+                let index = match read_file(transport, &format!("objects/pack/{}.idx", pack.name)).await? {
+                    Some(data) => PackIndex::from_bytes(&data, repo.object_hash()).ok_or_else(|| Error::PackIndex {
+                        name: pack.name.clone(),
+                    })?,
+                    None => PackIndex::default(),
+                };
+                pack.index = Some(index);
+            }
+            if !pack.index.as_ref().map_or(false, |index| index.contains(id)) {
+                continue;
+            }
+
+            let RemotePack { name, index } = remote_packs.remove(pack_idx);
+            let path = format!("objects/pack/{name}.pack");
+            progress.set_name(format!("receiving {path} (dumb http)"));
+            let rd = transport
+                .get_file(&path)
+                .await?
+                .ok_or_else(|| Error::ObjectNotFound { id: id.to_owned() })?;
+            #[cfg(feature = "async-network-client")]
+            let mut rd = gix_protocol::futures_lite::io::BlockOn::new(rd);
+            #[cfg(not(feature = "async-network-client"))]
+            let mut rd = rd;
+            let outcome = gix_pack::Bundle::write_to_directory(
+                &mut rd,
+                Some(&pack_dir),
+                progress,
+                should_interrupt,
+                Some(Box::new({
+                    let repo = repo.clone();
+                    repo.objects
+                })),
+                options.clone(),
+            )?;
+            let checksum = outcome.index.data_hash;
+            if name.strip_prefix("pack-") != Some(checksum.to_hex().to_string().as_str()) {
+                return Err(Error::PackChecksumMismatch { name, actual: checksum });
+            }
+            self.received_packs.push(index.expect("index was obtained"));
+            self.write_pack_bundles.push(outcome);
+            return Ok(());
+        }
+        Err(Error::ObjectNotFound { id: id.to_owned() })
+    }
+}
+
+/// The object ids contained in the index of a pack on the remote.
+#[derive(Default)]
+struct PackIndex {
+    /// All object ids in the pack, sorted.
+    ids: Vec<ObjectId>,
+}
+
+impl PackIndex {
+    /// Parse the ids from `data` in the V1 or V2 pack index format, or return `None` if the data is invalid.
+    fn from_bytes(data: &[u8], object_hash: gix_hash::Kind) -> Option<Self> {
+        const V2_SIGNATURE: &[u8] = b"\xfftOc";
+        const FAN_LEN: usize = 256 * 4;
+        let hash_len = object_hash.len_in_bytes();
+        let (data, entry_len, id_offset) = if let Some(data) = data.strip_prefix(V2_SIGNATURE) {
+            let (version, data) = data.split_at(4.min(data.len()));
+            if version != [0, 0, 0, 2] {
+                return None;
+            }
+            (data, hash_len, 0)
+        } else {
+            (data, 4 + hash_len, 4)
+        };
+        let (fan, entries) = (data.get(..FAN_LEN)?, &data[FAN_LEN..]);
+        let num_objects = u32::from_be_bytes(fan[FAN_LEN - 4..].try_into().ok()?) as usize;
+        let ids = entries
+            .get(..num_objects.checked_mul(entry_len)?)?
+            .chunks_exact(entry_len)
+            .map(|entry| ObjectId::from_bytes_or_panic(&entry[id_offset..]))
+            .collect();
+        Some(PackIndex { ids })
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        self.ids.binary_search_by(|probe| probe.as_ref().cmp(id)).is_ok()
+    }
+}
+
+/// Read the entire file at `path` from `transport`, or return `None` if it doesn't exist.
+#[gix_protocol::maybe_async::maybe_async]
+async fn read_file<T>(transport: &mut T, path: &str) -> Result<Option<Vec<u8>>, Error>
+where
+    T: Transport,
+{
+    let mut rd = match transport.get_file(path).await? {
+        Some(rd) => rd,
+        None => return Ok(None),
+    };
+    let mut buf = Vec::new();
+    rd.read_to_end(&mut buf).await.map_err(|source| Error::ReadFile {
+        path: path.into(),
+        source,
+    })?;
+    Ok(Some(buf))
+}
+
+/// Decompress the loose object with `id` from `compressed` and write it to the object database of `repo`.
+fn write_loose_object(repo: &Repository, id: &oid, compressed: &[u8]) -> Result<(), Error> {
+    let mut inflate = gix_features::zlib::Inflate::default();
+    let mut input = compressed;
+    let mut out = Vec::new();
+    let mut buf = [0; 8192];
+    loop {
+        let (status, consumed, written) = inflate.once(input, &mut buf).map_err(|source| Error::Inflate {
+            id: id.to_owned(),
+            source,
+        })?;
+        out.extend_from_slice(&buf[..written]);
+        input = &input[consumed..];
+        match status {
+            gix_features::zlib::Status::StreamEnd => break,
+            _ if consumed == 0 && written == 0 => return Err(Error::CorruptLooseObject { id: id.to_owned() }),
+            _ => {}
+        }
+    }
+    let (kind, size, header_len) =
+        gix_object::decode::loose_header(&out).map_err(|source| Error::LooseObjectHeader {
+            id: id.to_owned(),
+            source,
+        })?;
+    let data = &out[header_len..];
+    if data.len() as u64 != size {
+        return Err(Error::CorruptLooseObject { id: id.to_owned() });
+    }
+    let actual = gix_object::compute_hash(repo.object_hash(), kind, data);
+    if actual != id {
+        return Err(Error::LooseObjectChecksumMismatch {
+            expected: id.to_owned(),
+            actual,
+        });
+    }
+    repo.objects
+        .write_buf(kind, data)
+        .map_err(crate::object::write::Error::from)?;
+    Ok(())
+}
+
+/// Parse `info/refs`, with lines like `<hex>\t<name>` that are followed by `<hex>\t<name>^{}` for annotated tags.
+fn parse_info_refs(data: &[u8]) -> Result<Vec<handshake::Ref>, Error> {
+    let mut out = Vec::new();
+    for line in data.lines().filter(|line| !line.is_empty()) {
+        let parse_error = || Error::ParseLine {
+            path: "info/refs",
+            line: line.into(),
+        };
+        let (hex, name) = line.split_once_str(b"\t").ok_or_else(parse_error)?;
+        let id = ObjectId::from_hex(hex).map_err(|_| parse_error())?;
+        match name.strip_suffix(b"^{}") {
+            Some(peeled_name) => {
+                let peeled = match out.last_mut() {
+                    Some(handshake::Ref::Direct { full_ref_name, object }) if full_ref_name == peeled_name => {
+                        handshake::Ref::Peeled {
+                            full_ref_name: std::mem::take(full_ref_name),
+                            tag: *object,
+                            object: id,
+                        }
+                    }
+                    _ => return Err(parse_error()),
+                };
+                *out.last_mut().expect("checked above") = peeled;
+            }
+            None => out.push(handshake::Ref::Direct {
+                full_ref_name: name.into(),
+                object: id,
+            }),
+        }
+    }
+    Ok(out)
+}
+
+/// Parse the `HEAD` file, which is either a symbolic ref like `ref: refs/heads/main` or an object id, and resolve it using `refs`.
+fn parse_head(data: &[u8], refs: &[handshake::Ref]) -> Result<handshake::Ref, Error> {
+    let line = data.lines().next().unwrap_or_default();
+    let full_ref_name = BString::from("HEAD");
+    Ok(match line.strip_prefix(b"ref: ") {
+        Some(target) => {
+            let target = target.trim();
+            match refs.iter().find(|r| r.unpack().0 == target) {
+                Some(handshake::Ref::Direct { object, .. }) => handshake::Ref::Symbolic {
+                    full_ref_name,
+                    target: target.into(),
+                    tag: None,
+                    object: *object,
+                },
+                Some(handshake::Ref::Peeled { tag, object, .. }) => handshake::Ref::Symbolic {
+                    full_ref_name,
+                    target: target.into(),
+                    tag: Some(*tag),
+                    object: *object,
+                },
+                _ => handshake::Ref::Unborn {
+                    full_ref_name,
+                    target: target.into(),
+                },
+            }
+        }
+        None => handshake::Ref::Direct {
+            full_ref_name,
+            object: ObjectId::from_hex(line.trim()).map_err(|_| Error::ParseLine {
+                path: "HEAD",
+                line: line.into(),
+            })?,
+        },
+    })
+}
+
+/// Parse `objects/info/packs`, with lines like `P pack-<checksum>.pack`, into pack names without extension.
+fn parse_pack_list(data: &[u8]) -> Result<Vec<String>, Error> {
+    data.lines()
+        .filter_map(|line| line.strip_prefix(b"P "))
+        .map(|name| {
+            name.trim()
+                .strip_suffix(b".pack")
+                .and_then(|name| name.to_str().ok())
+                .filter(|name| {
+                    name.strip_prefix("pack-")
+                        .map_or(false, |hex| ObjectId::from_hex(hex.as_bytes()).is_ok())
+                })
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| Error::ParseLine {
+                    path: "objects/info/packs",
+                    line: name.into(),
+                })
+        })
+        .collect()
+}
@@ -35,6 +35,8 @@ pub enum Error {
         feature: &'static str,
         description: &'static str,
     },
+    #[error("Could not open the index of the received pack to learn which shallow commits of the remote it contains")]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
     #[error("Could not write 'shallow' file to incorporate remote updates after fetching")]
     WriteShallowFile(#[from] crate::shallow::write::Error),
     #[error("'shallow' file could not be locked in preparation for writing changes")]
@@ -44,7 +46,35 @@ pub enum Error {
     #[error("Receiving objects from shallow remotes is prohibited due to the value of `clone.rejectShallow`")]
     RejectShallowRemote,
     #[error(transparent)]
+    Download(#[from] super::download::Error),
+    #[error("The pack downloaded from {uri:?} has checksum {actual}, but {expected} was advertised")]
+    PackfileUriChecksumMismatch {
+        uri: crate::bstr::BString,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("Could not pick up packs added by bundles")]
+    RefreshObjectDatabase(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("Could not learn if the remote is a promisor remote")]
+    PromisorConfig(#[source] config::boolean::Error),
+    #[error(transparent)]
+    PartialCloneFilterConfig(#[from] config::key::GenericErrorWithValue<crate::remote::fetch::filter::Error>),
+    #[error("Could not determine the current branch to learn which fetched objects to merge")]
+    FetchHeadCurrentBranch(#[source] crate::reference::find::existing::Error),
+    #[error("Could not write FETCH_HEAD at \"{}\"", path.display())]
+    WriteFetchHead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not fetch from a remote which only supports the 'dumb' HTTP protocol")]
+    DumbHttp(#[from] super::dumb::Error),
+    #[error("Could not write the .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
@@ -52,6 +82,7 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         match self {
             Error::FetchResponse(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
+            Error::DumbHttp(err) => err.is_spurious(),
             _ => false,
         }
     }
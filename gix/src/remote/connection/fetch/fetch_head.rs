@@ -0,0 +1,108 @@
+//! Write `FETCH_HEAD` to remember what was fetched, and which of it is meant to be merged by `git pull`.
+use gix_fetchhead::{Description, Entry};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::Branch,
+    remote,
+    remote::fetch::{refs, Error, Mapping, SpecIndex, Tags},
+    Remote,
+};
+
+/// Write all `mappings` of `remote` whose remote side points to an object into `FETCH_HEAD` of the repository,
+/// replacing its previous content. `updates` are the ref updates performed for each of the `mappings`, and are used
+/// to skip tags that were already present locally before they were [included](Tags::Included), just like `git` does.
+///
+/// The objects to merge are marked like `git` does it when it isn't passed refspecs on the command-line:
+///
+/// - If the current branch is configured to merge from `remote`, all remote refs that are named in `branch.<name>.merge` are marked.
+/// - Otherwise, if the first refspec of `remote` isn't a pattern, the remote ref it matched is marked.
+/// - If `remote` is anonymous, all mappings produced by its refspecs are marked.
+///
+/// Objects to merge are written first, followed by all others, which is why `FETCH_HEAD` resolves to the first object to merge.
+pub(crate) fn write(remote: &Remote<'_>, mappings: &[Mapping], updates: &[refs::Update]) -> Result<(), Error> {
+    let repo = remote.repo;
+    let url = remote
+        .url(remote::Direction::Fetch)
+        .map(gix_url::Url::to_bstring)
+        .unwrap_or_default();
+    let first_spec_is_pattern = remote
+        .refspecs(remote::Direction::Fetch)
+        .first()
+        .and_then(|spec| spec.to_ref().remote().map(|name| name.contains(&b'*')))
+        .unwrap_or_default();
+    let merge_names = branch_merge_names(remote)?;
+
+    let entries: Vec<_> = mappings
+        .iter()
+        .zip(updates)
+        .filter(|(mapping, update)| {
+            let is_included_tag = remote.fetch_tags == Tags::Included
+                && mapping.spec_index.implicit_index().is_some()
+                && mapping
+                    .remote
+                    .as_name()
+                    .map_or(false, |name| name.starts_with(b"refs/tags/"));
+            !is_included_tag || matches!(update.mode, refs::update::Mode::New)
+        })
+        .filter_map(|(mapping, _)| {
+            let id = mapping.remote.as_id()?.to_owned();
+            let name = mapping
+                .remote
+                .as_name()
+                .map_or_else(|| id.to_string().into(), ToOwned::to_owned);
+            let for_merge = match (remote.name(), &merge_names, mapping.spec_index) {
+                (None, _, SpecIndex::ExplicitInRemote(_)) => true,
+                (None, _, SpecIndex::Implicit(_)) => false,
+                (Some(_), Some(merge_names), _) => merge_names.contains(&name),
+                (Some(_), None, spec_index) => spec_index == SpecIndex::ExplicitInRemote(0) && !first_spec_is_pattern,
+            };
+            Some((id, name, for_merge))
+        })
+        .collect();
+
+    let mut buf = Vec::new();
+    for (id, name, for_merge) in entries
+        .iter()
+        .filter(|(_, _, for_merge)| *for_merge)
+        .chain(entries.iter().filter(|(_, _, for_merge)| !*for_merge))
+    {
+        Entry {
+            id: *id,
+            not_for_merge: !for_merge,
+            description: Description::from_ref_name(name.as_bstr(), url.as_bstr()),
+        }
+        .write_to(&mut buf)
+        .expect("write to memory works");
+    }
+    let path = repo.git_dir().join("FETCH_HEAD");
+    std::fs::write(&path, buf).map_err(|source| Error::WriteFetchHead { path, source })
+}
+
+/// Return the names of the remote refs the current branch merges with if it's configured to merge from `remote`.
+fn branch_merge_names(remote: &Remote<'_>) -> Result<Option<Vec<BString>>, Error> {
+    let Some(remote_name) = remote.name() else {
+        return Ok(None);
+    };
+    let repo = remote.repo;
+    let Some(head_name) = repo.head_name().map_err(Error::FetchHeadCurrentBranch)? else {
+        return Ok(None);
+    };
+    let short_name: &BStr = head_name.as_ref().shorten();
+    if repo
+        .branch_remote_name(short_name, remote::Direction::Fetch)
+        .map_or(true, |name| name.as_bstr() != remote_name.as_bstr())
+    {
+        return Ok(None);
+    }
+    Ok(repo
+        .config
+        .resolved
+        .strings_filter(
+            "branch",
+            Some(short_name),
+            Branch::MERGE.name,
+            &mut repo.filter_config_section(),
+        )
+        .map(|names| names.into_iter().map(std::borrow::Cow::into_owned).collect()))
+}
@@ -65,6 +65,16 @@ pub enum Status {
         /// Information collected while updating references.
         update_refs: refs::update::Outcome,
     },
+    /// The remote only supports the 'dumb' HTTP protocol, and at least one tip was new so we received the objects it needed
+    /// without negotiation, as loose objects or as part of the packs the remote provides.
+    ChangeViaDumbHttp {
+        /// Information collected while writing each of the packs received from the remote, in order.
+        write_pack_bundles: Vec<gix_pack::bundle::write::Outcome>,
+        /// The amount of loose objects that were received from the remote.
+        loose_objects: usize,
+        /// Information collected while updating references.
+        update_refs: refs::update::Outcome,
+    },
 }
 
 /// The outcome of receiving a pack via [`Prepare::receive()`].
@@ -74,10 +84,27 @@ pub struct Outcome {
     pub ref_map: RefMap,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// Information about bundles that were downloaded and applied before negotiating the pack, if bundle URIs were used at all.
+    pub bundle_uri: Option<outcome::BundleUri>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
 pub mod outcome {
+    use std::sync::Arc;
+
+    use crate::bstr::BString;
+
+    /// Information about the bundles obtained through bundle URIs before negotiation.
+    ///
+    /// Failing to obtain or apply bundles isn't fatal, as all missing objects are then received from the remote.
+    #[derive(Default, Debug, Clone)]
+    pub struct BundleUri {
+        /// The URIs of all bundles that were applied to the object database, in order.
+        pub applied: Vec<BString>,
+        /// The URIs of bundles or bundle lists that couldn't be obtained or applied, along with the reason.
+        pub failed: Vec<(BString, Arc<dyn std::error::Error + Send + Sync + 'static>)>,
+    }
+
     /// Information about the negotiation phase of a fetch.
     ///
     /// Note that negotiation can happen even if no pack is ultimately produced.
@@ -190,9 +217,13 @@ where
             con: Some(self),
             ref_map,
             dry_run: DryRun::No,
+            write_fetch_head: true,
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter: None,
+            #[cfg(feature = "bundle")]
+            bundle_uri: None,
         })
     }
 }
@@ -207,7 +238,20 @@ where
     }
 }
 
+///
+#[cfg(feature = "bundle")]
+pub mod bundle;
+///
+#[cfg(feature = "bundle")]
+pub mod bundle_uri;
 mod config;
+///
+pub mod download;
+///
+pub mod dumb;
+mod fetch_head;
+#[cfg(feature = "blocking-network-client")]
+pub(crate) mod promisor;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -221,9 +265,13 @@ where
     con: Option<Connection<'remote, 'repo, T>>,
     ref_map: RefMap,
     dry_run: DryRun,
+    write_fetch_head: bool,
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<remote::fetch::Filter>,
+    #[cfg(feature = "bundle")]
+    bundle_uri: Option<BString>,
 }
 
 /// Builder
@@ -239,6 +287,15 @@ where
         self
     }
 
+    /// If enabled, the default, write `FETCH_HEAD` after updating refs to list all objects that were fetched,
+    /// with those that `git pull` would merge coming first.
+    ///
+    /// This has no effect in dry-run mode.
+    pub fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.write_fetch_head = enabled;
+        self
+    }
+
     /// If enabled, don't write ref updates to loose refs, but put them exclusively to packed-refs.
     ///
     /// This improves performance and allows case-sensitive filesystems to deal with ref names that would otherwise
@@ -265,6 +322,27 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Receive only the objects that pass `filter`, which makes for a *partial clone* whose missing objects are obtained
+    /// from the remote when they are needed.
+    ///
+    /// Without it, the `remote.<name>.partialCloneFilter` of promisor remotes is used.
+    /// Packs received with a filter or from promisor remotes are marked with a `.promisor` file.
+    pub fn with_filter(mut self, filter: remote::fetch::Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Download the bundle or bundle list at `uri` and apply it to the object database before negotiating the pack,
+    /// so that only the objects missing from the bundles have to be received from the remote.
+    ///
+    /// This takes precedence over bundles advertised by the remote, which are only used if `transfer.bundleURI` is `true`.
+    /// Note that this has no effect in dry-run mode.
+    #[cfg(feature = "bundle")]
+    pub fn with_bundle_uri(mut self, uri: impl Into<BString>) -> Self {
+        self.bundle_uri = Some(uri.into());
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
@@ -0,0 +1,52 @@
+//! Obtain objects that are missing in partial clones from their promisor remote as they are needed.
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    remote,
+    remote::fetch::Status,
+};
+
+/// Fetch objects from the promisor remote of a partial clone, similar to how `git` lazily fetches missing objects.
+pub(crate) struct Lazy {
+    /// The repository to fetch into, which is opened anew for each fetch to be independent of the caller.
+    pub git_dir: PathBuf,
+    /// The options to open the repository at `git_dir` with.
+    pub options: crate::open::Options,
+    /// The name of the remote to obtain objects from.
+    pub remote_name: BString,
+}
+
+impl gix_odb::store::Promisor for Lazy {
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let _span = gix_trace::coarse!("remote::fetch::promisor::Lazy::fetch()", num_ids = ids.len());
+        let mut repo = crate::ThreadSafeRepository::open_opts(&self.git_dir, self.options.clone())?.to_thread_local();
+        // Objects that the remote doesn't have must not cause yet another fetch.
+        repo.objects.promisor = None;
+
+        let mut remote = repo
+            .find_remote(self.remote_name.as_bstr())?
+            .with_fetch_tags(remote::fetch::Tags::None);
+        // Only ask for the objects themselves so no reference is updated.
+        remote.fetch_specs = ids
+            .iter()
+            .map(|id| {
+                gix_refspec::parse(id.to_string().as_str().into(), gix_refspec::parse::Operation::Fetch)
+                    .map(|spec| spec.to_owned())
+            })
+            .collect::<Result<_, _>>()?;
+        let outcome = remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(gix_features::progress::Discard, Default::default())?
+            // Like `git`, don't receive the blobs of the trees we ask for, as these are fetched when needed.
+            .with_filter(remote::fetch::Filter::BlobNone)
+            .with_write_fetch_head(false)
+            .receive(gix_features::progress::Discard, &AtomicBool::default())?;
+        if let Status::Change { write_pack_bundle, .. } = outcome.status {
+            if let Some(keep_path) = write_pack_bundle.keep_path {
+                std::fs::remove_file(keep_path)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -5,18 +5,22 @@ use std::{
 
 use gix_odb::store::RefreshMode;
 use gix_protocol::{
-    fetch::Arguments,
+    fetch::{
+        response::{Acknowledgement, ShallowUpdate},
+        Arguments,
+    },
     transport::{client::Transport, packetline::read::ProgressAction},
 };
 
 use crate::{
+    bstr::ByteSlice,
     config::{
         cache::util::ApplyLeniency,
-        tree::{Clone, Fetch, Key},
+        tree::{Clone, Fetch, Key, Remote},
     },
     remote,
     remote::{
-        connection::fetch::config,
+        connection::fetch::{config, download, fetch_head},
         fetch,
         fetch::{
             negotiate, negotiate::Algorithm, outcome, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage,
@@ -25,6 +29,8 @@ use crate::{
     },
     Repository,
 };
+#[cfg(feature = "bundle")]
+use crate::{config::tree::Transfer, remote::connection::fetch::bundle_uri};
 
 impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
 where
@@ -53,6 +59,18 @@ where
     /// A known application for this behaviour is in `remote-helper` implementations which should send this path via `lock <path>` to stdout
     /// to inform git about the file that it will remove once it updated the refs accordingly.
     ///
+    /// ### `FETCH_HEAD`
+    ///
+    /// After updating refs, all remote refs that were fetched are written to `FETCH_HEAD` unless
+    /// [`with_write_fetch_head(false)`](Self::with_write_fetch_head()) is set.
+    /// Those that are to be merged come first, which are the ones named in `branch.<name>.merge` if the current branch merges
+    /// from this remote, or the one matched by the first refspec unless it's a pattern.
+    /// If the remote is anonymous, all remote refs matched by its refspecs are to be merged, as if they were passed on the command-line.
+    ///
+    /// If the remote only supports the 'dumb' HTTP protocol, no negotiation happens and instead all objects we don't have are
+    /// obtained by walking the graph from each new tip, downloading loose objects or the packs that contain them, which
+    /// results in [`Status::ChangeViaDumbHttp`]. Shallow fetches and filters aren't supported in this case.
+    ///
     /// ### Deviation
     ///
     /// When **updating refs**, the `git-fetch` docs state that the following:
@@ -71,6 +89,13 @@ where
     /// ### Configuration
     ///
     /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    /// - `fetch.uriProtocols` is a comma-separated list of protocols like `https`, and if set, the server may send parts of the pack
+    ///    as separate packs to download from URIs with these protocols, which are verified using the checksums advertised with them.
+    /// - `remote.<name>.partialCloneFilter` is the object filter to use when fetching from promisor remotes, unless one
+    ///    was set with [`with_filter()`](Self::with_filter()). Promisor remotes are those with `remote.<name>.promisor`
+    ///    set to `true` or the one named in `extensions.partialClone`.
+    /// - `transfer.bundleURI` can be `true` to download and apply bundles advertised by the server before negotiating the pack,
+    ///    which is useful to offload work from the server. Failures to do so aren't fatal and are listed in [`Outcome::bundle_uri`].
     ///
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn receive<P>(self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
@@ -90,19 +115,33 @@ where
     ) -> Result<Outcome, Error> {
         let _span = gix_trace::coarse!("fetch::Prepare::receive()");
         let mut con = self.con.take().expect("receive() can only be called once");
+        if con.dumb_http {
+            return self.receive_dumb(con, progress, should_interrupt).await;
+        }
+        #[cfg(feature = "bundle")]
+        let bundle_uri = self.bundle_uri.take();
 
         let handshake = &self.ref_map.handshake;
         let protocol_version = handshake.server_protocol_version;
 
         let fetch = gix_protocol::Command::Fetch;
         let repo = con.remote.repo;
-        let fetch_features = {
+        let mut fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
             f.push(repo.config.user_agent_tuple());
             f
         };
 
         gix_protocol::fetch::Response::check_required_features(protocol_version, &fetch_features)?;
+        // In V1, 'deepen-relative' is a feature of the first want which makes all deepening relative,
+        // so it must only be sent if that's what we want.
+        let deepen_relative_as_feature = matches!(
+            protocol_version,
+            gix_protocol::transport::Protocol::V0 | gix_protocol::transport::Protocol::V1
+        ) && fetch_features.iter().any(|(n, _)| *n == "deepen-relative");
+        if deepen_relative_as_feature {
+            fetch_features.retain(|(n, _)| *n != "deepen-relative");
+        }
         let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
         let mut arguments = gix_protocol::fetch::Arguments::new(protocol_version, fetch_features, con.trace);
         if matches!(con.remote.fetch_tags, crate::remote::fetch::Tags::Included) {
@@ -116,7 +155,53 @@ where
             }
             arguments.use_include_tag();
         }
-        let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        let (shallow_commits, mut shallow_lock) =
+            add_shallow_args(&mut arguments, &self.shallow, deepen_relative_as_feature, repo)?;
+        let remote_name = con.remote.name().map(remote::Name::as_bstr);
+        let is_promisor_remote = match remote_name {
+            Some(name) => repo.is_promisor_remote(name).map_err(Error::PromisorConfig)?,
+            None => false,
+        };
+        let filter = match (self.filter.take(), remote_name) {
+            (Some(filter), _) => Some(filter),
+            (None, Some(name)) if is_promisor_remote => repo
+                .config
+                .resolved
+                .string_filter(
+                    "remote",
+                    Some(name),
+                    Remote::PARTIAL_CLONE_FILTER.name,
+                    &mut repo.filter_config_section(),
+                )
+                .map(|spec| Remote::PARTIAL_CLONE_FILTER.try_into_filter(spec))
+                .transpose()
+                .with_leniency(repo.config.lenient_config)?,
+            (None, _) => None,
+        };
+        if let Some(filter) = &filter {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description:
+                        "Object filters need server support to receive only some of the objects for a partial clone",
+                });
+            }
+            arguments.filter(&filter.to_string());
+        }
+        let write_promisor_file = is_promisor_remote || filter.is_some();
+        if let Some(protocols) = repo
+            .config
+            .resolved
+            .string_by_key(Fetch::URI_PROTOCOLS.logical_name().as_str())
+            .filter(|_| arguments.can_use_packfile_uris() && matches!(self.dry_run, fetch::DryRun::No))
+        {
+            let protocols: Vec<_> = protocols
+                .split_str(",")
+                .filter_map(|p| p.trim().to_str().ok())
+                .filter(|p| !p.is_empty())
+                .collect();
+            arguments.use_packfile_uris(&protocols);
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -125,6 +210,74 @@ where
             });
         }
 
+        #[cfg(feature = "bundle")]
+        let bundle_uri = if matches!(self.dry_run, fetch::DryRun::No)
+            && self
+                .ref_map
+                .mappings
+                .iter()
+                .filter_map(|m| m.remote.as_id())
+                .any(|id| !repo.has_object(id))
+        {
+            let out = if let Some(uri) = bundle_uri {
+                let mut out = outcome::BundleUri::default();
+                bundle_uri::apply_uri(repo, remote_name, uri.as_ref(), progress, should_interrupt, &mut out);
+                Some(out)
+            } else if protocol_version == gix_protocol::transport::Protocol::V2
+                && handshake
+                    .capabilities
+                    .contains(gix_protocol::Command::BundleUri.as_str())
+                && repo
+                    .config
+                    .resolved
+                    .boolean_filter_by_key(
+                        Transfer::BUNDLE_URI.logical_name().as_str(),
+                        &mut repo.filter_config_section(),
+                    )
+                    .map(|val| Transfer::BUNDLE_URI.enrich_error(val))
+                    .transpose()?
+                    .unwrap_or(false)
+            {
+                let mut out = outcome::BundleUri::default();
+                let base = con
+                    .remote
+                    .url(remote::Direction::Fetch)
+                    .map(gix_url::Url::to_bstring)
+                    .unwrap_or_default();
+                match gix_protocol::bundle_uri(
+                    &mut con.transport,
+                    &handshake.capabilities,
+                    vec![repo.config.user_agent_tuple()],
+                    &mut &mut *progress,
+                    con.trace,
+                )
+                .await
+                {
+                    Ok(list) => bundle_uri::apply_list(
+                        repo,
+                        remote_name,
+                        list,
+                        base.as_ref(),
+                        progress,
+                        should_interrupt,
+                        &mut out,
+                    ),
+                    Err(err) => out.failed.push((base, std::sync::Arc::new(err))),
+                }
+                Some(out)
+            } else {
+                None
+            };
+            if out.as_ref().map_or(false, |out| !out.applied.is_empty()) {
+                repo.objects.store_ref().refresh()?;
+            }
+            out
+        } else {
+            None
+        };
+        #[cfg(not(feature = "bundle"))]
+        let bundle_uri = None;
+
         let negotiate_span = gix_trace::detail!(
             "negotiate",
             protocol_version = self.ref_map.handshake.server_protocol_version as usize
@@ -144,6 +297,8 @@ where
             r.objects.refresh = RefreshMode::Never;
             // we cache everything of importance in the graph and thus don't need an object cache.
             r.objects.unset_object_cache();
+            // objects we don't have must remain missing, instead of being fetched from a promisor remote.
+            r.objects.promisor = None;
             r
         };
         let mut graph = graph_repo.revision_graph();
@@ -156,6 +311,7 @@ where
             negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
         )?;
         let mut previous_response = None::<gix_protocol::fetch::Response>;
+        let mut packfile_uri_packs = Vec::new();
         let (mut write_pack_bundle, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
                 gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
@@ -181,15 +337,21 @@ where
                 let mut seen_ack = false;
                 let mut in_vain = 0;
                 let mut common = is_stateless.then(Vec::new);
+                let mut shallow_updates = Vec::new();
                 let mut reader = 'negotiation: loop {
                     let _round = gix_trace::detail!("negotiate round", round = rounds.len() + 1);
                     progress.step();
                     progress.set_name(format!("negotiate (round {})", rounds.len() + 1));
 
+                    // Once the server is ready it only waits for `done`, so more haves would be wasted.
+                    let server_is_ready = previous_response
+                        .as_ref()
+                        .map_or(false, |r| r.acknowledgements().contains(&Acknowledgement::Ready));
+                    let haves_this_round = if server_is_ready { 0 } else { haves_to_send };
                     let is_done = match negotiate::one_round(
                         negotiator.deref_mut(),
                         &mut graph,
-                        haves_to_send,
+                        haves_this_round,
                         &mut arguments,
                         previous_response.as_ref(),
                         common.as_mut(),
@@ -203,10 +365,11 @@ where
                             rounds.push(outcome::negotiate::Round {
                                 haves_sent,
                                 in_vain,
-                                haves_to_send,
+                                haves_to_send: haves_this_round,
                                 previous_response_had_at_least_one_in_common: ack_seen,
                             });
-                            let is_done = haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
+                            let is_done =
+                                server_is_ready || haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
                             haves_to_send = gix_negotiate::window_size(is_stateless, Some(haves_to_send));
                             is_done
                         }
@@ -217,6 +380,8 @@ where
                             return Err(err.into());
                         }
                     };
+                    // In V1, stateful connections would block if we tried to read a pack that isn't going to be sent.
+                    let client_expects_pack = is_done || arguments.can_use_no_done();
                     let mut reader = arguments.send(&mut con.transport, is_done).await?;
                     if sideband_all {
                         setup_remote_progress(progress, &mut reader, should_interrupt);
@@ -224,10 +389,12 @@ where
                     let response = gix_protocol::fetch::Response::from_line_reader(
                         protocol_version,
                         &mut reader,
-                        is_done,
+                        client_expects_pack,
                         !is_done,
                     )
                     .await?;
+                    // V1 sends shallow updates only in the first response, which is followed by more rounds of negotiation.
+                    shallow_updates.extend_from_slice(response.shallow_updates());
                     let has_pack = response.has_pack();
                     previous_response = Some(response);
                     if has_pack {
@@ -244,7 +411,9 @@ where
                 drop(negotiate_span);
 
                 let previous_response = previous_response.expect("knowledge of a pack means a response was received");
-                if !previous_response.shallow_updates().is_empty() && shallow_lock.is_none() {
+                // V1 servers advertise that they are shallow only in the handshake, V2 servers tell us in their response.
+                let remote_is_shallow = !con.remote_shallow_commits.is_empty();
+                if (!shallow_updates.is_empty() || remote_is_shallow) && shallow_lock.is_none() {
                     let reject_shallow_remote = repo
                         .config
                         .resolved
@@ -279,7 +448,7 @@ where
                             let repo = repo.clone();
                             repo.objects
                         })),
-                        options,
+                        options.clone(),
                     )?;
                     // Assure the final flush packet is consumed.
                     #[cfg(feature = "async-network-client")]
@@ -298,12 +467,57 @@ where
                     {
                         reader = rd;
                     }
+                    if write_promisor_file {
+                        write_promisor(&res, &self.ref_map.mappings)?;
+                    }
                     Some(res)
                 } else {
                     None
                 };
                 drop(reader);
 
+                if remote_is_shallow && self.shallow == Shallow::NoChange {
+                    // Without a V2 response to tell us, the shallow boundary we received is the one of the remote
+                    // which falls into the pack.
+                    if let Some(index_path) = write_pack_bundle.as_ref().and_then(|b| b.index_path.as_ref()) {
+                        let index = gix_pack::index::File::at(index_path, repo.object_hash())?;
+                        shallow_updates.extend(
+                            con.remote_shallow_commits
+                                .iter()
+                                .filter(|id| index.lookup(id).is_some())
+                                .map(|id| ShallowUpdate::Shallow(*id)),
+                        );
+                    }
+                }
+
+                for packfile_uri in previous_response.packfile_uris() {
+                    let uri = packfile_uri.uri.as_ref();
+                    let mut rd = download::open(repo, remote_name, uri)?;
+                    progress.set_name(format!("receiving pack from {uri}"));
+                    let res = gix_pack::Bundle::write_to_directory(
+                        &mut rd,
+                        Some(&repo.objects.store_ref().path().join("pack")),
+                        progress,
+                        should_interrupt,
+                        Some(Box::new({
+                            let repo = repo.clone();
+                            repo.objects
+                        })),
+                        options.clone(),
+                    )?;
+                    if res.index.data_hash != packfile_uri.pack_hash {
+                        return Err(Error::PackfileUriChecksumMismatch {
+                            uri: uri.to_owned(),
+                            expected: packfile_uri.pack_hash,
+                            actual: res.index.data_hash,
+                        });
+                    }
+                    if write_promisor_file {
+                        write_promisor(&res, &self.ref_map.mappings)?;
+                    }
+                    packfile_uri_packs.push(res);
+                }
+
                 if matches!(protocol_version, gix_protocol::transport::Protocol::V2) {
                     gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
                         .await
@@ -311,8 +525,8 @@ where
                 }
 
                 if let Some(shallow_lock) = shallow_lock {
-                    if !previous_response.shallow_updates().is_empty() {
-                        crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
+                    if !shallow_updates.is_empty() {
+                        crate::shallow::write(shallow_lock, shallow_commits, &shallow_updates)?;
                     }
                 }
                 (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
@@ -331,8 +545,11 @@ where
             self.dry_run,
             self.write_packed_refs,
         )?;
+        if self.write_fetch_head && matches!(self.dry_run, fetch::DryRun::No) {
+            fetch_head::write(con.remote, &self.ref_map.mappings, &update_refs.updates)?;
+        }
 
-        if let Some(bundle) = write_pack_bundle.as_mut() {
+        for bundle in write_pack_bundle.iter_mut().chain(packfile_uri_packs.iter_mut()) {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
                     std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
@@ -354,11 +571,27 @@ where
                     update_refs,
                 },
             },
+            bundle_uri,
         };
         Ok(out)
     }
 }
 
+/// Mark the pack of `bundle` as received from a promisor remote, listing the remote refs of `mappings` like `git` does.
+fn write_promisor(bundle: &gix_pack::bundle::write::Outcome, mappings: &[fetch::Mapping]) -> Result<(), Error> {
+    let Some(path) = bundle.data_path.as_ref().map(|path| path.with_extension("promisor")) else {
+        return Ok(());
+    };
+    let mut buf = Vec::new();
+    for (id, name) in mappings.iter().filter_map(|m| m.remote.as_id().zip(m.remote.as_name())) {
+        buf.extend_from_slice(id.to_hex().to_string().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(name);
+        buf.push(b'\n');
+    }
+    std::fs::write(&path, buf).map_err(|source| Error::WritePromisorFile { path, source })
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -367,6 +600,7 @@ fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
 fn add_shallow_args(
     args: &mut Arguments,
     shallow: &Shallow,
+    deepen_relative_as_feature: bool,
     repo: &Repository,
 ) -> Result<(Option<crate::shallow::Commits>, Option<gix_lock::File>), Error> {
     let expect_change = *shallow != Shallow::NoChange;
@@ -390,7 +624,11 @@ fn add_shallow_args(
         Shallow::DepthAtRemote(commits) => args.deepen(commits.get() as usize),
         Shallow::Deepen(commits) => {
             args.deepen(*commits as usize);
-            args.deepen_relative();
+            if deepen_relative_as_feature {
+                args.add_feature("deepen-relative");
+            } else {
+                args.deepen_relative();
+            }
         }
         Shallow::Since { cutoff } => {
             args.deepen_since(cutoff.seconds);
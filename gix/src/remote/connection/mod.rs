@@ -18,6 +18,10 @@ pub struct Connection<'a, 'repo, T> {
     pub(crate) transport_options: Option<Box<dyn std::any::Any>>,
     pub(crate) transport: T,
     pub(crate) trace: bool,
+    /// If `true`, the remote turned out to only support the 'dumb' HTTP protocol during the handshake.
+    pub(crate) dumb_http: bool,
+    /// The commits at which the remote repository is shallow, as advertised in a V1 handshake.
+    pub(crate) remote_shallow_commits: Vec<gix_hash::ObjectId>,
 }
 
 mod access;
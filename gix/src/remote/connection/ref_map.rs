@@ -30,6 +30,8 @@ pub enum Error {
     Transport(#[from] gix_protocol::transport::client::Error),
     #[error(transparent)]
     ConfigureCredentials(#[from] crate::config::credential_helpers::Error),
+    #[error("Could not list the references of a remote which only supports the 'dumb' HTTP protocol")]
+    DumbHttp(#[from] fetch::dumb::Error),
     #[error(transparent)]
     MappingValidation(#[from] gix_refspec::match_group::validate::Error),
 }
@@ -40,6 +42,7 @@ impl gix_protocol::transport::IsSpuriousError for Error {
             Error::Transport(err) => err.is_spurious(),
             Error::ListRefs(err) => err.is_spurious(),
             Error::Handshake(err) => err.is_spurious(),
+            Error::DumbHttp(err) => err.is_spurious(),
             _ => false,
         }
     }
@@ -109,17 +112,11 @@ where
         Options {
             prefix_from_spec_as_filter_on_remote,
             handshake_parameters,
-            mut extra_refspecs,
+            extra_refspecs,
         }: Options,
     ) -> Result<fetch::RefMap, Error> {
         let _span = gix_trace::coarse!("remote::Connection::ref_map()");
-        let null = gix_hash::ObjectId::null(gix_hash::Kind::Sha1); // OK to hardcode Sha1, it's not supposed to match, ever.
-
-        if let Some(tag_spec) = self.remote.fetch_tags.to_refspec().map(|spec| spec.to_owned()) {
-            if !extra_refspecs.contains(&tag_spec) {
-                extra_refspecs.push(tag_spec);
-            }
-        };
+        let extra_refspecs = with_implied_tag_spec(self.remote, extra_refspecs);
         let specs = {
             let mut s = self.remote.fetch_specs.clone();
             s.extend(extra_refspecs.clone());
@@ -133,49 +130,7 @@ where
                 progress,
             )
             .await?;
-        let num_explicit_specs = self.remote.fetch_specs.len();
-        let group = gix_refspec::MatchGroup::from_fetch_specs(specs.iter().map(gix_refspec::RefSpec::to_ref));
-        let (res, fixes) = group
-            .match_remotes(remote.refs.iter().map(|r| {
-                let (full_ref_name, target, object) = r.unpack();
-                gix_refspec::match_group::Item {
-                    full_ref_name,
-                    target: target.unwrap_or(&null),
-                    object,
-                }
-            }))
-            .validated()?;
-        let mappings = res.mappings;
-        let mappings = mappings
-            .into_iter()
-            .map(|m| fetch::Mapping {
-                remote: m.item_index.map_or_else(
-                    || {
-                        fetch::Source::ObjectId(match m.lhs {
-                            gix_refspec::match_group::SourceRef::ObjectId(id) => id,
-                            _ => unreachable!("no item index implies having an object id"),
-                        })
-                    },
-                    |idx| fetch::Source::Ref(remote.refs[idx].clone()),
-                ),
-                local: m.rhs.map(std::borrow::Cow::into_owned),
-                spec_index: if m.spec_index < num_explicit_specs {
-                    SpecIndex::ExplicitInRemote(m.spec_index)
-                } else {
-                    SpecIndex::Implicit(m.spec_index - num_explicit_specs)
-                },
-            })
-            .collect();
-
-        let object_hash = extract_object_format(self.remote.repo, &remote.outcome)?;
-        Ok(fetch::RefMap {
-            mappings,
-            extra_refspecs,
-            fixes,
-            remote_refs: remote.refs,
-            handshake: remote.outcome,
-            object_hash,
-        })
+        map_remote_refs(self.remote, remote, extra_refspecs)
     }
 
     #[allow(clippy::result_large_err)]
@@ -215,8 +170,29 @@ where
         if let Some(config) = self.transport_options.as_ref() {
             self.transport.configure(&**config)?;
         }
-        let mut outcome =
-            gix_protocol::fetch::handshake(&mut self.transport, authenticate, extra_parameters, &mut progress).await?;
+        let mut outcome = match gix_protocol::handshake_with_shallow_commits(
+            &mut self.transport,
+            gix_protocol::transport::Service::UploadPack,
+            authenticate,
+            extra_parameters,
+            &mut progress,
+        )
+        .await
+        {
+            Ok((outcome, shallow_commits)) => {
+                self.remote_shallow_commits = shallow_commits;
+                outcome
+            }
+            Err(gix_protocol::handshake::Error::Transport(gix_protocol::transport::client::Error::DumbHttpServer)) => {
+                self.dumb_http = true;
+                let refs = fetch::dumb::refs(&mut self.transport).await?;
+                return Ok(HandshakeWithRefs {
+                    outcome: fetch::dumb::handshake_outcome(),
+                    refs,
+                });
+            }
+            Err(err) => return Err(err.into()),
+        };
         let refs = match outcome.refs.take() {
             Some(refs) => refs,
             None => {
@@ -252,6 +228,77 @@ where
     }
 }
 
+/// Add the refspec implied by the tag configuration of `remote` to `extra_refspecs`, if needed.
+pub(crate) fn with_implied_tag_spec(
+    remote: &crate::Remote<'_>,
+    mut extra_refspecs: Vec<gix_refspec::RefSpec>,
+) -> Vec<gix_refspec::RefSpec> {
+    if let Some(tag_spec) = remote.fetch_tags.to_refspec().map(|spec| spec.to_owned()) {
+        if !extra_refspecs.contains(&tag_spec) {
+            extra_refspecs.push(tag_spec);
+        }
+    };
+    extra_refspecs
+}
+
+/// Match the refs of the `remote` side against the fetch refspecs of `local` along with `extra_refspecs` to produce a ref-map.
+#[allow(clippy::result_large_err)]
+pub(crate) fn map_remote_refs(
+    local: &crate::Remote<'_>,
+    remote: HandshakeWithRefs,
+    extra_refspecs: Vec<gix_refspec::RefSpec>,
+) -> Result<fetch::RefMap, Error> {
+    let null = gix_hash::ObjectId::null(gix_hash::Kind::Sha1); // OK to hardcode Sha1, it's not supposed to match, ever.
+    let specs = {
+        let mut s = local.fetch_specs.clone();
+        s.extend(extra_refspecs.clone());
+        s
+    };
+    let num_explicit_specs = local.fetch_specs.len();
+    let group = gix_refspec::MatchGroup::from_fetch_specs(specs.iter().map(gix_refspec::RefSpec::to_ref));
+    let (res, fixes) = group
+        .match_remotes(remote.refs.iter().map(|r| {
+            let (full_ref_name, target, object) = r.unpack();
+            gix_refspec::match_group::Item {
+                full_ref_name,
+                target: target.unwrap_or(&null),
+                object,
+            }
+        }))
+        .validated()?;
+    let mappings = res.mappings;
+    let mappings = mappings
+        .into_iter()
+        .map(|m| fetch::Mapping {
+            remote: m.item_index.map_or_else(
+                || {
+                    fetch::Source::ObjectId(match m.lhs {
+                        gix_refspec::match_group::SourceRef::ObjectId(id) => id,
+                        _ => unreachable!("no item index implies having an object id"),
+                    })
+                },
+                |idx| fetch::Source::Ref(remote.refs[idx].clone()),
+            ),
+            local: m.rhs.map(std::borrow::Cow::into_owned),
+            spec_index: if m.spec_index < num_explicit_specs {
+                SpecIndex::ExplicitInRemote(m.spec_index)
+            } else {
+                SpecIndex::Implicit(m.spec_index - num_explicit_specs)
+            },
+        })
+        .collect();
+
+    let object_hash = extract_object_format(local.repo, &remote.outcome)?;
+    Ok(fetch::RefMap {
+        mappings,
+        extra_refspecs,
+        fixes,
+        remote_refs: remote.refs,
+        handshake: remote.outcome,
+        object_hash,
+    })
+}
+
 /// Assume sha1 if server says nothing, otherwise configure anything beyond sha1 in the local repo configuration
 #[allow(clippy::result_large_err)]
 fn extract_object_format(
@@ -1,3 +1,5 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
 ///
 pub mod negotiate {
     #[cfg(feature = "credentials")]
@@ -11,9 +13,16 @@ pub mod negotiate {
     };
 }
 
+#[cfg(all(
+    feature = "bundle",
+    any(feature = "blocking-network-client", feature = "async-network-client")
+))]
+pub use super::connection::fetch::bundle;
+#[cfg(feature = "blocking-network-client")]
+pub(crate) use super::connection::fetch::promisor;
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use super::connection::fetch::{
-    outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
+    dumb, outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
 };
 
 /// If `Yes`, don't really make changes but do as much as possible to get an idea of what would be done.
@@ -108,6 +117,86 @@ impl Shallow {
     }
 }
 
+/// An object filter to receive only a subset of the objects reachable from the refs to fetch, which makes for a *partial clone*.
+///
+/// Objects that were omitted this way can be obtained later from the *promisor remote* when they are needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Omit all blobs, as in `blob:none`.
+    BlobNone,
+    /// Omit all blobs whose size is at least the given amount of bytes, as in `blob:limit=<n>`.
+    BlobLimit(u64),
+    /// Omit all trees and blobs whose depth from the root tree is at least the given depth, as in `tree:<depth>`.
+    ///
+    /// With a depth of `0`, only commits and tags are received.
+    TreeDepth(u64),
+    /// Omit all blobs that aren't matched by the sparse-checkout patterns in the blob the given revision points to,
+    /// as in `sparse:oid=<rev>`. The revision, like `main:.sparse`, is resolved by the remote.
+    SparseOid(BString),
+}
+
+///
+pub mod filter {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Filter::from_bytes()`](super::Filter::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Object filter {spec:?} is not supported - use 'blob:none', 'blob:limit=<n>', 'tree:<depth>' or 'sparse:oid=<rev>'")]
+        Unsupported { spec: BString },
+        #[error("Object filter {spec:?} needs a number, optionally with a 'k', 'm' or 'g' suffix")]
+        InvalidNumber { spec: BString },
+    }
+}
+
+impl Filter {
+    /// Parse a filter `spec` like `blob:none` as it is passed to `git clone --filter=<spec>`.
+    pub fn from_bytes(spec: &BStr) -> Result<Self, filter::Error> {
+        let invalid_number = || filter::Error::InvalidNumber { spec: spec.to_owned() };
+        Ok(if spec == "blob:none" {
+            Filter::BlobNone
+        } else if let Some(limit) = spec.strip_prefix(b"blob:limit=") {
+            Filter::BlobLimit(parse_size(limit).ok_or_else(invalid_number)?)
+        } else if let Some(depth) = spec.strip_prefix(b"tree:") {
+            Filter::TreeDepth(
+                depth
+                    .to_str()
+                    .ok()
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(invalid_number)?,
+            )
+        } else if let Some(rev) = spec.strip_prefix(b"sparse:oid=").filter(|rev| !rev.is_empty()) {
+            Filter::SparseOid(rev.into())
+        } else {
+            return Err(filter::Error::Unsupported { spec: spec.to_owned() });
+        })
+    }
+}
+
+/// Parse an unsigned integer with an optional unit suffix, like `git` does.
+fn parse_size(value: &[u8]) -> Option<u64> {
+    let value = value.to_str().ok()?;
+    let (number, factor) = match value.chars().last()?.to_ascii_lowercase() {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    number.parse::<u64>().ok()?.checked_mul(factor)
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::BlobNone => f.write_str("blob:none"),
+            Filter::BlobLimit(limit) => write!(f, "blob:limit={limit}"),
+            Filter::TreeDepth(depth) => write!(f, "tree:{depth}"),
+            Filter::SparseOid(rev) => write!(f, "sparse:oid={rev}"),
+        }
+    }
+}
+
 /// Information about the relationship between our refspecs, and remote references with their local counterparts.
 #[derive(Default, Debug, Clone)]
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
@@ -0,0 +1,58 @@
+use std::sync::atomic::AtomicBool;
+
+use crate::{bstr::BStr, repository::create_bundle};
+
+impl crate::Repository {
+    /// Write a bundle to `out` which contains all objects reachable from the references in `specs`, similar to `git bundle create`.
+    ///
+    /// Each spec may be a reference like `main` to include, an exclusion like `^v1.0` or a range like `v1.0..main`.
+    /// All included revisions must start with a reference, which is recorded in the bundle under its full name,
+    /// while excluded commits which are parents of included ones become prerequisites of the bundle.
+    /// Configure the bundle to write with `options`, and use `progress` and `should_interrupt` to observe and stop the operation.
+    pub fn create_bundle<'a>(
+        &self,
+        specs: impl IntoIterator<Item = &'a BStr>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: gix_bundle::write::Options,
+    ) -> Result<gix_bundle::write::Outcome, create_bundle::Error> {
+        let mut refs = Vec::<gix_bundle::Ref>::new();
+        let mut exclude = Vec::new();
+        for spec in specs {
+            let rev = self.rev_parse(spec)?;
+            let (first, second) = (rev.first_reference().cloned(), rev.second_reference().cloned());
+            let (included, reference) = match rev.detach() {
+                gix_revision::Spec::Include(id) => (id, first),
+                gix_revision::Spec::Exclude(id) => {
+                    exclude.push(id);
+                    continue;
+                }
+                gix_revision::Spec::Range { from, to } => {
+                    exclude.push(from);
+                    (to, second)
+                }
+                _ => return Err(create_bundle::Error::UnsupportedSpec { spec: spec.into() }),
+            };
+            let name = reference
+                .ok_or_else(|| create_bundle::Error::NotARef { spec: spec.into() })?
+                .name
+                .into_inner();
+            if !refs.iter().any(|r| r.name == name) {
+                refs.push(gix_bundle::Ref { name, id: included });
+            }
+        }
+
+        let mut objects = self.objects.clone().into_arc()?;
+        objects.prevent_pack_unload();
+        Ok(gix_bundle::write(
+            refs,
+            &exclude,
+            objects,
+            out,
+            progress,
+            should_interrupt,
+            options,
+        )?)
+    }
+}
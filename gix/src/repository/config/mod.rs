@@ -75,6 +75,17 @@ impl crate::Repository {
                 .and_then(|variant| Ssh::VARIANT.try_into_variant(variant).transpose())
                 .transpose()
                 .with_leniency(self.options.lenient_config)?,
+            native: config
+                .boolean_filter("gitoxide", Some("ssh".into()), gitoxide::Ssh::NATIVE.name, &mut trusted)
+                .map(|value| {
+                    value.map_err(|err| {
+                        config::key::GenericErrorWithValue::from_value(&gitoxide::Ssh::NATIVE, err.input.clone())
+                            .with_source(err)
+                    })
+                })
+                .transpose()
+                .with_leniency(self.options.lenient_config)?
+                .unwrap_or_default(),
         };
         Ok(opts)
     }
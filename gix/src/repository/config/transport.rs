@@ -16,10 +16,14 @@ impl crate::Repository {
     /// For transports that support proxy authentication, the
     /// [default authentication method](crate::config::Snapshot::credential_helpers()) will be used with the url of the proxy
     /// if it contains a user name.
+    ///
+    /// Values of `http.<url>.*` sections are used if `<url>` [matches](crate::config::url_match::match_url()) `url`,
+    /// with the most specific match taking precedence, just like in `git`.
     #[cfg_attr(
         not(any(
             feature = "blocking-http-transport-reqwest",
-            feature = "blocking-http-transport-curl"
+            feature = "blocking-http-transport-curl",
+            feature = "async-http-transport-reqwest"
         )),
         allow(unused_variables)
     )]
@@ -36,14 +40,16 @@ impl crate::Repository {
             Http | Https => {
                 #[cfg(not(any(
                     feature = "blocking-http-transport-reqwest",
-                    feature = "blocking-http-transport-curl"
+                    feature = "blocking-http-transport-curl",
+                    feature = "async-http-transport-reqwest"
                 )))]
                 {
                     Ok(None)
                 }
                 #[cfg(any(
                     feature = "blocking-http-transport-reqwest",
-                    feature = "blocking-http-transport-curl"
+                    feature = "blocking-http-transport-curl",
+                    feature = "async-http-transport-reqwest"
                 ))]
                 {
                     use std::{
@@ -60,7 +66,7 @@ impl crate::Repository {
                         config,
                         config::{
                             cache::util::ApplyLeniency,
-                            tree::{gitoxide, Key, Remote},
+                            tree::{gitoxide, Key, Remote, Section},
                         },
                     };
                     fn try_cow_to_string(
@@ -142,8 +148,13 @@ impl crate::Repository {
                     }
 
                     let mut opts = http::Options::default();
-                    let config = &self.config.resolved;
                     let mut trusted_only = self.filter_config_section();
+                    let config = &config::url_match::scoped_to_url(
+                        &self.config.resolved,
+                        config::tree::Http.name(),
+                        &url,
+                        &mut trusted_only,
+                    );
                     let lenient = self.config.lenient_config;
                     opts.extra_headers = {
                         let key = "http.extraHeader";
@@ -0,0 +1,608 @@
+use std::{
+    collections::VecDeque,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use gix_features::progress::Progress;
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::Find;
+use gix_odb::Write;
+use gix_pack::data::output;
+
+use crate::{
+    config,
+    config::tree::{Gc, Key},
+    gc, Repository,
+};
+
+/// Maintenance
+impl Repository {
+    /// Obtain options for [`gc()`](Self::gc()) from the configuration.
+    ///
+    /// `gc.pruneExpire` defaults to two weeks ago, `gc.reflogExpire` to 90 days ago and `gc.reflogExpireUnreachable`
+    /// to 30 days ago. References are always packed, and a `multi-pack-index` is written if one already exists.
+    /// Bitmaps are written if `repack.writeBitmaps` is set, which defaults to `true` in bare repositories.
+    ///
+    /// Note that per-reference configuration like `gc.<pattern>.reflogExpire` isn't supported yet.
+    pub fn gc_options(&self) -> Result<gc::Options, gc::options::Error> {
+        let now = SystemTime::now();
+        let days_ago = |days: u64| now.checked_sub(Duration::from_secs(60 * 60 * 24 * days));
+        let expiry = |key: &'static config::tree::gc::Expiry, default_days: u64| -> Result<_, gc::options::Error> {
+            Ok(self
+                .config
+                .apply_leniency(
+                    self.config
+                        .resolved
+                        .string_by_key(key.logical_name().as_str())
+                        .map(|value| key.try_into_expiry(value, now)),
+                )?
+                .unwrap_or_else(|| days_ago(default_days)))
+        };
+        Ok(gc::Options {
+            prune_expire: expiry(&Gc::PRUNE_EXPIRE, 14)?,
+            reflog_expire: expiry(&Gc::REFLOG_EXPIRE, 90)?,
+            reflog_expire_unreachable: expiry(&Gc::REFLOG_EXPIRE_UNREACHABLE, 30)?,
+            pack_refs: true,
+            write_multi_pack_index: self
+                .objects
+                .store_ref()
+                .path()
+                .join("pack")
+                .join("multi-pack-index")
+                .is_file(),
+            write_bitmap_index: self
+                .config
+                .apply_leniency(
+                    self.config
+                        .resolved
+                        .boolean_by_key("repack.writeBitmaps")
+                        .map(|res| config::tree::Repack::WRITE_BITMAPS.enrich_error(res)),
+                )?
+                .unwrap_or_else(|| self.is_bare()),
+            thread_limit: None,
+        })
+    }
+
+    /// Consolidate all objects reachable from references, their reflogs, and `HEAD`, its reflog and the index of each worktree
+    /// into a single new pack, similar to `git gc`, and remove what's no longer needed, configured by `options`.
+    ///
+    /// Before objects are repacked, expired reflog entries are removed and loose references are packed.
+    /// All packs with a `.keep` or `.promisor` file are retained along with their objects, which are not added to the new pack.
+    /// All other packs are removed once the new pack is in place. Their unreachable objects are written as loose objects, unless
+    /// they expired according to [`prune_expire`](gc::Options::prune_expire).
+    /// Loose objects are removed if they are now packed, or if they are unreachable and expired.
+    /// Objects in alternate object databases are never packed or removed.
+    ///
+    /// A reachability bitmap is written for the new pack if [requested](gc::Options::write_bitmap_index), but only if it
+    /// contains all reachable objects and no kept pack has a bitmap, as `git` would ignore all but one bitmap.
+    ///
+    /// Note that this repository's object database is refreshed so the new state is picked up by all of its handles.
+    pub fn gc(
+        &self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: gc::Options,
+    ) -> Result<gc::Outcome, gc::Error> {
+        if self.objects.promisor.is_some() {
+            // Objects missing in partial clones are expected, and must not be fetched while finding reachable objects.
+            let mut repo = self.clone();
+            repo.objects.promisor = None;
+            return repo.gc(progress, should_interrupt, options);
+        }
+        let mut out = gc::Outcome::default();
+        let (ref_lock_fail_mode, packed_refs_lock_fail_mode) = self.config.lock_timeout()?;
+        out.expired_reflog_entries = self.expire_reflogs(&options, ref_lock_fail_mode, should_interrupt)?;
+        if options.pack_refs {
+            out.packed_refs = self
+                .refs
+                .pack_refs(
+                    &self.objects,
+                    gix_ref::file::pack_refs::Options {
+                        all: true,
+                        prune: true,
+                        lock_fail_mode: packed_refs_lock_fail_mode,
+                    },
+                )?
+                .packed
+                .len();
+        }
+
+        let store = self.objects.store_ref();
+        let object_hash = self.object_hash();
+        let pack_dir = store.path().join("pack");
+        let loose = gix_odb::loose::Store::at(store.path(), object_hash);
+
+        let (replaceable_packs, kept_packs) = packs_in(&pack_dir)?;
+        let mut kept = HashSet::default();
+        for index_path in &kept_packs {
+            kept.extend(open_index(index_path, object_hash)?.iter().map(|entry| entry.oid));
+        }
+        let mut candidates = HashSet::default();
+        for index_path in &replaceable_packs {
+            candidates.extend(
+                open_index(index_path, object_hash)?
+                    .iter()
+                    .map(|entry| entry.oid)
+                    .filter(|id| !kept.contains(id)),
+            );
+        }
+        let loose_ids: Vec<_> = loose.iter().filter_map(Result::ok).collect();
+        candidates.extend(loose_ids.iter().filter(|id| !kept.contains(*id)).copied());
+
+        let reachable = self.reachable_objects(should_interrupt)?;
+        let to_pack: Vec<_> = reachable
+            .keys()
+            .filter(|id| candidates.contains(*id))
+            .copied()
+            .collect();
+        let packs_all_reachable_objects = to_pack.len() == reachable.len();
+        if !to_pack.is_empty() {
+            out.pack = Some(self.write_pack(&pack_dir, to_pack, progress, should_interrupt, options.thread_limit)?);
+        }
+
+        let multi_pack_index_path = pack_dir.join("multi-pack-index");
+        if options.write_multi_pack_index {
+            let mut index_paths = kept_packs.clone();
+            index_paths.extend(out.pack.as_ref().and_then(|pack| pack.index_path.clone()));
+            let mut file = gix_tempfile::new(
+                &pack_dir,
+                gix_tempfile::ContainingDirectory::Exists,
+                gix_tempfile::AutoRemove::Tempfile,
+            )?;
+            gix_pack::multi_index::File::write_from_index_paths(
+                index_paths,
+                &mut file,
+                progress,
+                should_interrupt,
+                gix_pack::multi_index::write::Options { object_hash },
+            )?;
+            file.persist(&multi_pack_index_path).map_err(|err| err.error)?;
+            out.multi_pack_index_path = Some(multi_pack_index_path);
+        } else if multi_pack_index_path.is_file() {
+            std::fs::remove_file(&multi_pack_index_path)?;
+        }
+
+        if let Some(index_path) = out
+            .pack
+            .as_ref()
+            .and_then(|pack| pack.index_path.as_ref())
+            .filter(|_| options.write_bitmap_index && packs_all_reachable_objects)
+            .filter(|_| !kept_packs.iter().any(|path| path.with_extension("bitmap").exists()))
+        {
+            let commits = self.bitmap_commits(&reachable, should_interrupt)?;
+            let mut file = gix_tempfile::new(
+                &pack_dir,
+                gix_tempfile::ContainingDirectory::Exists,
+                gix_tempfile::AutoRemove::Tempfile,
+            )?;
+            open_index(index_path, object_hash)?.write_bitmap_to(
+                &commits,
+                &mut |id| reachable.get(id).copied(),
+                &mut file,
+            )?;
+            let bitmap_path = index_path.with_extension("bitmap");
+            file.persist(&bitmap_path).map_err(|err| err.error)?;
+            out.bitmap_path = Some(bitmap_path);
+        }
+
+        for index_path in &replaceable_packs {
+            let index = open_index(index_path, object_hash)?;
+            let data_path = index_path.with_extension("pack");
+            let is_expired = is_expired(&data_path, options.prune_expire);
+            if !is_expired {
+                let mut buf = Vec::new();
+                for id in index.iter().map(|entry| entry.oid) {
+                    if reachable.contains_key(&id) || kept.contains(&id) || loose.contains(&id) {
+                        continue;
+                    }
+                    let object = self.objects.try_find(&id, &mut buf)?;
+                    if let Some(object) = object {
+                        loose
+                            .write_buf(object.kind, object.data)
+                            .map_err(gc::Error::WriteLooseObject)?;
+                        out.loosened_objects += 1;
+                    }
+                }
+            }
+        }
+
+        store.refresh()?;
+        for index_path in replaceable_packs {
+            let data_path = index_path.with_extension("pack");
+            std::fs::remove_file(&index_path)?;
+            for extension in ["rev", "bitmap", "mtimes"] {
+                std::fs::remove_file(index_path.with_extension(extension)).ok();
+            }
+            std::fs::remove_file(&data_path)?;
+            out.removed_packs.push(data_path);
+        }
+        if let Some(keep_path) = out.pack.as_ref().and_then(|pack| pack.keep_path.as_ref()) {
+            std::fs::remove_file(keep_path)?;
+        }
+
+        let packed: HashSet = out
+            .pack
+            .as_ref()
+            .map(|_| {
+                reachable
+                    .keys()
+                    .filter(|id| candidates.contains(*id))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in loose_ids {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(gc::Error::Interrupted);
+            }
+            let path = loose.object_path(&id);
+            let remove = packed.contains(&id)
+                || kept.contains(&id)
+                || (!reachable.contains_key(&id) && is_expired(&path, options.prune_expire));
+            if remove {
+                std::fs::remove_file(&path)?;
+                if let Some(parent) = path.parent() {
+                    // Only succeeds if the directory is empty.
+                    std::fs::remove_dir(parent).ok();
+                }
+                out.removed_loose_objects += 1;
+            }
+        }
+        store.refresh()?;
+        Ok(out)
+    }
+
+    /// Expire the reflogs of `HEAD` and all references according to `options`, and return the amount of removed entries.
+    fn expire_reflogs(
+        &self,
+        options: &gc::Options,
+        lock_fail_mode: gix_lock::acquire::Fail,
+        should_interrupt: &AtomicBool,
+    ) -> Result<usize, gc::Error> {
+        if options.reflog_expire.is_none() && options.reflog_expire_unreachable.is_none() {
+            return Ok(0);
+        }
+        let seconds = |time: Option<SystemTime>| {
+            time.map(|time| match time.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(duration) => duration.as_secs() as gix_date::SecondsSinceUnixEpoch,
+                Err(err) => -(err.duration().as_secs() as gix_date::SecondsSinceUnixEpoch),
+            })
+        };
+        let expire_options = gix_ref::file::log::expire::Options {
+            expire: seconds(options.reflog_expire),
+            expire_unreachable: seconds(options.reflog_expire_unreachable),
+            lock_fail_mode,
+        };
+
+        let platform = self.references()?;
+        let mut references = vec![self.find_reference("HEAD").ok()];
+        for reference in platform.all()? {
+            references.push(Some(reference.map_err(gc::Error::ReferencesIter)?));
+        }
+        let mut removed = 0;
+        for mut reference in references.into_iter().flatten() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(gc::Error::Interrupted);
+            }
+            let tip = reference.peel_to_id_in_place().ok().map(crate::Id::detach);
+            let mut reachable = None;
+            removed += self
+                .refs
+                .reflog_expire(reference.name(), expire_options, &mut |id| match tip {
+                    Some(tip) => reachable
+                        .get_or_insert_with(|| self.commits_reachable_from(tip))
+                        .contains(id),
+                    None => true,
+                })?
+                .removed;
+        }
+        Ok(removed)
+    }
+
+    /// Return all commits reachable from `tip`, after peeling it to a commit, while skipping missing objects.
+    fn commits_reachable_from(&self, tip: ObjectId) -> HashSet {
+        let mut out = HashSet::default();
+        out.insert(tip);
+        let mut queue = VecDeque::from([tip]);
+        let mut buf = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            let Ok(Some(object)) = self.objects.try_find(&id, &mut buf) else {
+                continue;
+            };
+            let next: Vec<_> = match object.kind {
+                gix_object::Kind::Tag => gix_object::TagRefIter::from_bytes(object.data)
+                    .target_id()
+                    .ok()
+                    .into_iter()
+                    .collect(),
+                gix_object::Kind::Commit => gix_object::CommitRefIter::from_bytes(object.data)
+                    .parent_ids()
+                    .collect(),
+                gix_object::Kind::Tree | gix_object::Kind::Blob => continue,
+            };
+            for id in next {
+                if out.insert(id) {
+                    queue.push_back(id);
+                }
+            }
+        }
+        out
+    }
+
+    /// Find all objects reachable from all references, their reflogs, and `HEAD`, its reflog and the index of all worktrees.
+    /// Missing objects are skipped, which is expected in partial clones.
+    fn reachable_objects(
+        &self,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_hashtable::HashMap<ObjectId, gix_object::Kind>, gc::Error> {
+        let mut tips = Vec::new();
+        let add_reflog =
+            |tips: &mut Vec<ObjectId>, mut log: gix_ref::file::log::iter::Platform<'_, '_>| -> Result<(), gc::Error> {
+                if let Some(lines) = log.all()? {
+                    for line in lines {
+                        let line = line?;
+                        tips.extend(
+                            [line.previous_oid(), line.new_oid()]
+                                .into_iter()
+                                .filter(|id| !id.is_null()),
+                        );
+                    }
+                }
+                Ok(())
+            };
+
+        // Like `git`, consider what's checked out in other worktrees as well, whose `HEAD` and index are their own.
+        let mut other_worktrees = Vec::new();
+        if self.git_dir() != self.common_dir() {
+            other_worktrees.push(self.main_repo()?);
+        }
+        for proxy in self.worktrees()? {
+            if proxy.git_dir() != self.git_dir() {
+                other_worktrees.push(proxy.into_repo_with_possibly_inaccessible_worktree()?);
+            }
+        }
+        for repo in std::iter::once(self).chain(other_worktrees.iter()) {
+            let head = repo.head()?;
+            add_reflog(&mut tips, head.log_iter())?;
+            tips.extend(head.id().map(crate::Id::detach));
+            #[cfg(feature = "index")]
+            if let Some(index) = repo.try_index()? {
+                tips.extend(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                        .map(|entry| entry.id),
+                );
+                let mut trees: Vec<_> = index.tree().into_iter().collect();
+                while let Some(tree) = trees.pop() {
+                    if tree.num_entries.is_some() {
+                        tips.push(tree.id);
+                    }
+                    trees.extend(tree.children.iter());
+                }
+            }
+        }
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(gc::Error::ReferencesIter)?;
+            add_reflog(&mut tips, reference.log_iter())?;
+            tips.extend(reference.try_id().map(crate::Id::detach));
+        }
+
+        let mut reachable = gix_hashtable::HashMap::default();
+        let mut seen = HashSet::default();
+        let mut queue = VecDeque::from(tips);
+        let mut buf = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(gc::Error::Interrupted);
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(object) = self.objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            reachable.insert(id, object.kind);
+            enqueue_edges(&object, &mut queue);
+        }
+        Ok(reachable)
+    }
+
+    /// Return the commits at the tip of all references and `HEAD`, along with all objects reachable from them,
+    /// for writing reachability bitmaps.
+    fn bitmap_commits(
+        &self,
+        reachable_objects: &gix_hashtable::HashMap<ObjectId, gix_object::Kind>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<gix_pack::index::bitmap::Commit>, gc::Error> {
+        let mut tips = Vec::new();
+        let platform = self.references()?;
+        let mut references = vec![self.find_reference("HEAD").ok()];
+        for reference in platform.all()? {
+            references.push(Some(reference.map_err(gc::Error::ReferencesIter)?));
+        }
+        for mut reference in references.into_iter().flatten() {
+            if let Ok(id) = reference.peel_to_id_in_place() {
+                if reachable_objects.get(&id.detach()) == Some(&gix_object::Kind::Commit) {
+                    tips.push(id.detach());
+                }
+            }
+        }
+        tips.sort();
+        tips.dedup();
+
+        let mut out = Vec::<gix_pack::index::bitmap::Commit>::with_capacity(tips.len());
+        let mut buf = Vec::new();
+        for tip in tips {
+            let mut reachable = HashSet::default();
+            let mut queue = VecDeque::from([tip]);
+            while let Some(id) = queue.pop_front() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(gc::Error::Interrupted);
+                }
+                if !reachable.insert(id) {
+                    continue;
+                }
+                if id != tip {
+                    // What's reachable from other tips is known already.
+                    if let Some(other) = out.iter().find(|commit| commit.id == id) {
+                        reachable.extend(other.reachable.iter().copied());
+                        continue;
+                    }
+                }
+                if let Some(object) = self.objects.try_find(&id, &mut buf)? {
+                    enqueue_edges(&object, &mut queue);
+                }
+            }
+            out.push(gix_pack::index::bitmap::Commit {
+                id: tip,
+                reachable: reachable.into_iter().collect(),
+            });
+        }
+        Ok(out)
+    }
+
+    /// Write all objects in `ids` into a new pack in `pack_dir`.
+    fn write_pack(
+        &self,
+        pack_dir: &Path,
+        ids: Vec<ObjectId>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+    ) -> Result<gix_pack::bundle::write::Outcome, gc::Error> {
+        let mut db = self.objects.clone().into_arc()?;
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        let num_objects = ids.len();
+        let (counts, _) = {
+            let mut counting = progress.add_child("counting".into());
+            counting.init(Some(num_objects), gix_features::progress::count("objects"));
+            output::count::objects_unthreaded(
+                &db,
+                &mut ids.into_iter().map(Ok),
+                &counting,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?
+        };
+        let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+
+        let mut pack_file = gix_tempfile::new(
+            pack_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        let mut writer = output::bytes::FromEntriesIter::new(
+            entries,
+            &mut pack_file,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            self.object_hash(),
+        );
+        for written in writer.by_ref() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(gc::Error::Interrupted);
+            }
+            written?;
+        }
+        drop(writer);
+
+        let temporary_pack_path: PathBuf = pack_file
+            .with_mut(|file| file.path().to_owned())
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::NotFound, "temporary pack disappeared"))?;
+        let mut pack = BufReader::new(std::fs::File::open(temporary_pack_path)?);
+        Ok(gix_pack::Bundle::write_to_directory(
+            &mut pack,
+            Some(pack_dir),
+            progress,
+            should_interrupt,
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                thread_limit,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                index_version: Default::default(),
+                object_hash: self.object_hash(),
+            },
+        )?)
+    }
+}
+
+/// Return the paths to the indices of all packs in `pack_dir` which can be replaced, and of those which must be kept
+/// due to a `.keep` or `.promisor` file.
+fn packs_in(pack_dir: &Path) -> std::io::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (mut replaceable, mut kept) = (Vec::new(), Vec::new());
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok((replaceable, kept)),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "idx") || !path.with_extension("pack").is_file() {
+            continue;
+        }
+        if path.with_extension("keep").exists() || path.with_extension("promisor").exists() {
+            kept.push(path);
+        } else {
+            replaceable.push(path);
+        }
+    }
+    replaceable.sort();
+    kept.sort();
+    Ok((replaceable, kept))
+}
+
+/// Add the ids of all objects that `object` refers to, except for submodule commits, to `queue`.
+fn enqueue_edges(object: &gix_object::Data<'_>, queue: &mut VecDeque<ObjectId>) {
+    match object.kind {
+        gix_object::Kind::Commit => {
+            let commit = gix_object::CommitRefIter::from_bytes(object.data);
+            queue.extend(commit.clone().tree_id().ok());
+            queue.extend(commit.parent_ids());
+        }
+        gix_object::Kind::Tag => {
+            queue.extend(gix_object::TagRefIter::from_bytes(object.data).target_id().ok());
+        }
+        gix_object::Kind::Tree => {
+            for entry in gix_object::TreeRefIter::from_bytes(object.data).filter_map(Result::ok) {
+                if !entry.mode.is_commit() {
+                    queue.push_back(entry.oid.to_owned());
+                }
+            }
+        }
+        gix_object::Kind::Blob => {}
+    }
+}
+
+fn open_index(path: &Path, object_hash: gix_hash::Kind) -> Result<gix_pack::index::File, gc::Error> {
+    gix_pack::index::File::at(path, object_hash).map_err(|source| gc::Error::OpenPackIndex {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Return `true` if the file at `path` was last modified before `expire`.
+fn is_expired(path: &Path, expire: Option<SystemTime>) -> bool {
+    expire.map_or(false, |expire| {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .map_or(false, |modified| modified < expire)
+    })
+}
@@ -0,0 +1,28 @@
+use crate::{
+    config::tree::{Core, Key},
+    hooks,
+};
+
+/// Hooks
+impl crate::Repository {
+    /// Return a platform to find and run hooks, located in the directory configured by `core.hooksPath`, or in
+    /// `$GIT_COMMON_DIR/hooks` by default.
+    ///
+    /// Note that `core.hooksPath` is only used if it was set in trusted configuration, and that hooks aren't run
+    /// at all unless the repository is fully [trusted](gix_sec::Trust::Full).
+    pub fn hooks(&self) -> Result<hooks::Platform<'_>, hooks::init::Error> {
+        let dir = match self.config.trusted_file_path("core", None, Core::HOOKS_PATH.name()) {
+            Some(path) => {
+                let path = path?;
+                // Relative paths are relative to where hooks are run.
+                self.work_dir().unwrap_or(self.git_dir()).join(path)
+            }
+            None => self.common_dir().join("hooks"),
+        };
+        Ok(hooks::Platform {
+            repo: self,
+            dir,
+            enabled: self.git_dir_trust() == gix_sec::Trust::Full,
+        })
+    }
+}
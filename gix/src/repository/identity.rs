@@ -1,5 +1,3 @@
-use std::time::SystemTime;
-
 use crate::{
     bstr::BString,
     config,
@@ -124,7 +122,6 @@ impl Personas {
                     .map(std::borrow::Cow::into_owned),
             )
         }
-        let now = SystemTime::now();
         let parse_date = |key: &str, date: &keys::Time| -> Option<Result<gix_date::Time, gix_date::parse::Error>> {
             debug_assert_eq!(
                 key,
@@ -133,7 +130,8 @@ impl Personas {
             );
             config
                 .string_by_key(key)
-                .map(|time| date.try_into_time(time, now.into()))
+                // Like `git`, only accept absolute dates here.
+                .map(|time| date.try_into_time(time, None))
         };
 
         let fallback = (
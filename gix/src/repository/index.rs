@@ -37,6 +37,22 @@ impl crate::Repository {
         Ok(index)
     }
 
+    /// Return the options to use when writing `index` back to disk, as configured by `index.skipHash`, `index.version`,
+    /// `feature.manyFiles`, `core.splitIndex`, `splitIndex.maxPercentChange` and `splitIndex.sharedIndexExpire`.
+    ///
+    /// Like in `git`, `index.version` only affects new indices, i.e. those without a [checksum](gix_index::File::checksum())
+    /// as they weren't read from or written to disk yet, while existing ones keep their version. If `core.splitIndex` isn't set, `index` is written as split index only
+    /// if it was read from one.
+    pub fn index_write_options(
+        &self,
+        index: &gix_index::File,
+    ) -> Result<gix_index::write::Options, crate::config::index_write_options::Error> {
+        self.config.index_write_options(
+            index.checksum().is_some().then(|| index.version()),
+            index.shared_index().is_some(),
+        )
+    }
+
     /// Return a shared worktree index which is updated automatically if the in-memory snapshot has become stale as the underlying file
     /// on disk has changed.
     ///
@@ -14,7 +14,7 @@ impl crate::Repository {
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
-        crate::Repository {
+        let mut repo = crate::Repository {
             bufs: RefCell::new(Vec::with_capacity(4)),
             work_tree,
             common_dir,
@@ -27,7 +27,9 @@ impl crate::Repository {
             shallow_commits,
             #[cfg(feature = "attributes")]
             modules,
-        }
+        };
+        repo.objects.promisor = repo.promisor();
+        repo
     }
 
     /// Convert this instance into a [`ThreadSafeRepository`][crate::ThreadSafeRepository] by dropping all thread-local data.
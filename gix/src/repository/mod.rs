@@ -37,6 +37,8 @@ impl crate::Repository {
 
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+#[cfg(feature = "bundle")]
+mod bundle;
 mod cache;
 mod config;
 ///
@@ -45,7 +47,11 @@ pub mod diff;
 ///
 #[cfg(feature = "attributes")]
 pub mod filter;
+#[cfg(feature = "gc")]
+mod gc;
 mod graph;
+#[cfg(feature = "attributes")]
+mod hooks;
 pub(crate) mod identity;
 mod impls;
 #[cfg(feature = "index")]
@@ -56,6 +62,7 @@ mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
 mod object;
+mod partial_clone;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
@@ -63,6 +70,12 @@ mod remote;
 #[cfg(feature = "revision")]
 mod revision;
 mod shallow;
+#[cfg(feature = "command")]
+mod sign;
+#[cfg(feature = "index")]
+mod sparse;
+#[cfg(feature = "attributes")]
+mod stage;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
@@ -85,6 +98,19 @@ pub mod branch_remote_ref_name {
     }
 }
 
+///
+pub mod prefetch_objects {
+    /// The error returned by [Repository::prefetch_objects()](crate::Repository::prefetch_objects()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not obtain missing objects from the promisor remote")]
+        Fetch(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Could not pick up the objects that were fetched")]
+        RefreshObjectDatabase(#[from] gix_odb::store::load_index::Error),
+    }
+}
+
 ///
 pub mod branch_remote_tracking_ref_name {
 
@@ -169,6 +195,28 @@ pub mod worktree_stream {
     }
 }
 
+///
+#[cfg(feature = "bundle")]
+pub mod create_bundle {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::create_bundle()`][crate::Repository::create_bundle()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        RevParse(#[from] crate::revision::spec::parse::Error),
+        #[error("The revision {spec:?} must start with a reference to be recorded in the bundle")]
+        NotARef { spec: BString },
+        #[error("The revision {spec:?} isn't supported, use references, exclusions like '^a' or ranges like 'a..b'")]
+        UnsupportedSpec { spec: BString },
+        #[error(transparent)]
+        OpenObjects(#[from] std::io::Error),
+        #[error(transparent)]
+        Write(#[from] gix_bundle::write::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-archive")]
 pub mod worktree_archive {
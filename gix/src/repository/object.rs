@@ -201,6 +201,8 @@ impl crate::Repository {
     ///
     /// It will be created with `constraint` which is most commonly to [only create it][PreviousValue::MustNotExist]
     /// or to [force overwriting a possibly existing tag](PreviousValue::Any).
+    ///
+    /// If `tag.gpgSign` is set, the tag object will be [signed](crate::Repository::sign()).
     #[momo]
     pub fn tag(
         &self,
@@ -211,7 +213,7 @@ impl crate::Repository {
         message: impl AsRef<str>,
         constraint: PreviousValue,
     ) -> Result<Reference<'_>, tag::Error> {
-        let tag = gix_object::Tag {
+        let mut tag = gix_object::Tag {
             target: target.as_ref().into(),
             target_kind,
             name: name.as_ref().into(),
@@ -219,6 +221,13 @@ impl crate::Repository {
             message: message.as_ref().into(),
             pgp_signature: None,
         };
+        if self.config.sign_tags()? {
+            // The signature is appended to the tag after a newline, which is part of what's signed.
+            let mut payload = Vec::new();
+            gix_object::WriteTo::write_to(&tag, &mut payload).map_err(|err| object::write::Error(err.into()))?;
+            payload.push(b'\n');
+            tag.pgp_signature = Some(self.sign_payload(&payload)?);
+        }
         let tag_id = self.write_object(&tag)?;
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
@@ -226,6 +235,8 @@ impl crate::Repository {
     /// Similar to [`commit(…)`][crate::Repository::commit()], but allows to create the commit with `committer` and `author` specified.
     ///
     /// This forces setting the commit time and author time by hand. Note that typically, committer and author are the same.
+    ///
+    /// If `commit.gpgSign` is set, the commit object will be [signed](crate::Repository::sign()).
     pub fn commit_as<'a, 'c, Name, E>(
         &self,
         committer: impl Into<gix_actor::SignatureRef<'c>>,
@@ -265,7 +276,7 @@ impl crate::Repository {
 
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
-        let commit = gix_object::Commit {
+        let mut commit = gix_object::Commit {
             message: message.into(),
             tree,
             author: author.into(),
@@ -274,6 +285,18 @@ impl crate::Repository {
             parents,
             extra_headers: Default::default(),
         };
+        if self.config.sign_commits()? {
+            let mut payload = Vec::new();
+            gix_object::WriteTo::write_to(&commit, &mut payload).map_err(|err| object::write::Error(err.into()))?;
+            let mut signature = self.sign_payload(&payload)?;
+            if signature.last() == Some(&b'\n') {
+                signature.pop();
+            }
+            let header = match self.object_hash() {
+                gix_hash::Kind::Sha1 => "gpgsig",
+            };
+            commit.extra_headers.push((header.into(), signature));
+        }
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(RefEdit {
@@ -301,6 +324,19 @@ impl crate::Repository {
         Ok(commit_id)
     }
 
+    /// Sign `payload` if signing programs can be used, or fail otherwise as signing was requested by configuration.
+    fn sign_payload(&self, payload: &[u8]) -> Result<crate::bstr::BString, crate::sign::Error> {
+        #[cfg(feature = "command")]
+        {
+            self.sign(payload)
+        }
+        #[cfg(not(feature = "command"))]
+        {
+            _ = payload;
+            Err(crate::sign::Error::Unsupported)
+        }
+    }
+
     /// Create a new commit object with `message` referring to `tree` with `parents`, and point `reference`
     /// to it. The commit is written without message encoding field, which can be assumed to be UTF-8.
     /// `author` and `committer` fields are pre-set from the configuration, which can be altered
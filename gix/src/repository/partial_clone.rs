@@ -0,0 +1,81 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::BStr,
+    config,
+    config::tree::{Extensions, Key, Remote},
+    repository::prefetch_objects,
+};
+
+/// Partial clones
+impl crate::Repository {
+    /// Return the name of the remote that objects missing in this *partial clone* are obtained from, as configured
+    /// in `extensions.partialClone`, or `None` if this isn't a partial clone.
+    pub fn promisor_remote_name(&self) -> Option<Cow<'_, BStr>> {
+        self.config
+            .resolved
+            .string_filter_by_key(
+                Extensions::PARTIAL_CLONE.logical_name().as_str(),
+                &mut self.filter_config_section(),
+            )
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Return `true` if the remote named `name` can provide the objects that are missing in this *partial clone*,
+    /// either because `remote.<name>.promisor` is `true` or because it's named in `extensions.partialClone`.
+    pub fn is_promisor_remote(&self, name: &BStr) -> Result<bool, config::boolean::Error> {
+        if self.promisor_remote_name().map_or(false, |promisor| promisor.as_ref() == name) {
+            return Ok(true);
+        }
+        Ok(self
+            .config
+            .resolved
+            .boolean_filter("remote", Some(name), Remote::PROMISOR.name, &mut self.filter_config_section())
+            .map(|value| Remote::PROMISOR.enrich_error(value))
+            .transpose()?
+            .unwrap_or(false))
+    }
+
+    /// Obtain all objects in `ids` that aren't present yet from the promisor remote in one go, which is much faster
+    /// than obtaining them one at a time as they are accessed.
+    ///
+    /// Return the amount of objects that were missing. Nothing is done if this isn't a partial clone, or if missing objects
+    /// can't be fetched as the `blocking-network-client` feature isn't enabled.
+    pub fn prefetch_objects(
+        &self,
+        ids: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+    ) -> Result<usize, prefetch_objects::Error> {
+        let promisor = match self.objects.promisor.as_ref() {
+            Some(promisor) => promisor,
+            None => return Ok(0),
+        };
+        let mut missing: Vec<_> = ids
+            .into_iter()
+            .map(Into::into)
+            .filter(|id| !self.has_object(id))
+            .collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        missing.sort();
+        missing.dedup();
+        promisor.fetch(&missing).map_err(prefetch_objects::Error::Fetch)?;
+        self.objects.store_ref().refresh()?;
+        Ok(missing.len())
+    }
+
+    /// Return the promisor to obtain missing objects with if this is a partial clone.
+    pub(crate) fn promisor(&self) -> Option<std::sync::Arc<dyn gix_odb::store::Promisor>> {
+        #[cfg(feature = "blocking-network-client")]
+        {
+            let remote_name = self.promisor_remote_name()?.into_owned();
+            Some(std::sync::Arc::new(crate::remote::fetch::promisor::Lazy {
+                git_dir: self.git_dir().to_owned(),
+                options: self.options.clone(),
+                remote_name,
+            }))
+        }
+        #[cfg(not(feature = "blocking-network-client"))]
+        None
+    }
+}
@@ -148,11 +148,58 @@ impl crate::Repository {
         edits: impl IntoIterator<Item = RefEdit>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
-        self.refs
+        let transaction = self
+            .refs
             .transaction()
-            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
-            .commit(self.committer().transpose()?)
-            .map_err(Into::into)
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?;
+        self.commit_ref_transaction(transaction)
+    }
+
+    /// Commit the prepared `transaction` and run the `reference-transaction` hook for each of its states,
+    /// allowing the hook to abort the transaction.
+    pub(crate) fn commit_ref_transaction(
+        &self,
+        transaction: gix_ref::file::Transaction<'_, '_>,
+    ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        #[cfg(feature = "attributes")]
+        {
+            use crate::hooks::ReferenceTransactionState::*;
+
+            let hooks = self.hooks()?;
+            if !hooks.exists("reference-transaction") {
+                return Ok(transaction.commit(self.committer().transpose()?)?);
+            }
+            let edits: Vec<_> = transaction
+                .edits()
+                .filter(|edit| changes_reference(edit))
+                .cloned()
+                .collect();
+            if let Err(err) = hooks.reference_transaction(Prepared, &edits) {
+                transaction.rollback();
+                hooks.reference_transaction(Aborted, &edits).ok();
+                return Err(err.into());
+            }
+            match transaction.commit(self.committer().transpose()?) {
+                Ok(committed) => {
+                    let edits: Vec<_> = committed
+                        .iter()
+                        .filter(|edit| changes_reference(edit))
+                        .cloned()
+                        .collect();
+                    // Just like in `git`, the outcome of the hook doesn't matter at this point.
+                    hooks.reference_transaction(Committed, &edits).ok();
+                    Ok(committed)
+                }
+                Err(err) => {
+                    hooks.reference_transaction(Aborted, &edits).ok();
+                    Err(err.into())
+                }
+            }
+        }
+        #[cfg(not(feature = "attributes"))]
+        {
+            Ok(transaction.commit(self.committer().transpose()?)?)
+        }
     }
 
     /// Return the repository head, an abstraction to help dealing with the `HEAD` reference.
@@ -263,3 +310,15 @@ impl crate::Repository {
         }
     }
 }
+
+/// Return `true` if `edit` changes the reference itself, and not only its reflog.
+#[cfg(feature = "attributes")]
+fn changes_reference(edit: &RefEdit) -> bool {
+    !matches!(
+        edit.change,
+        Change::Update {
+            log: LogChange { mode: RefLog::Only, .. },
+            ..
+        } | Change::Delete { log: RefLog::Only, .. }
+    )
+}
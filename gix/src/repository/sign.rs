@@ -0,0 +1,450 @@
+use std::{ffi::OsString, io::Write, process::Stdio};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{gpg, Gpg, Key, User},
+    },
+    sign::{verify, Error, Format, Trust, Verification},
+};
+
+/// Signing
+impl crate::Repository {
+    /// Sign `payload`, typically a serialized commit or tag without signature, with the signing program configured
+    /// by `gpg.format` and `gpg.<format>.program` (or `gpg.program` for OpenPGP), and return the signature.
+    ///
+    /// The key to sign with is `user.signingKey`, which defaults to the committer identity for OpenPGP and X.509 signatures.
+    /// For SSH signatures, it can be the path to a private key, the path to a public key whose private key is held by an
+    /// `ssh-agent`, or a literal public key prefixed with `key::` or starting with `ssh-`. If it isn't set, the first line
+    /// printed by `gpg.ssh.defaultKeyCommand` is used as literal key.
+    ///
+    /// Note that programs are only used if they were configured in trusted configuration files.
+    pub fn sign(&self, payload: &[u8]) -> Result<BString, Error> {
+        let format = self.signing_format()?;
+        let program = self.signing_program(format);
+        match format {
+            Format::OpenPgp | Format::X509 => self.sign_with_gpg(program, payload),
+            Format::Ssh => self.sign_with_ssh(program, payload),
+        }
+    }
+
+    fn signing_format(&self) -> Result<Format, Error> {
+        Ok(self
+            .config
+            .resolved
+            .string_by_key(Gpg::FORMAT.logical_name().as_str())
+            .map(|format| Gpg::FORMAT.try_into_format(format))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or_default())
+    }
+
+    fn signing_program(&self, format: Format) -> OsString {
+        let config = &self.config.resolved;
+        let mut trusted = self.filter_config_section();
+        config
+            .string_filter(
+                "gpg",
+                Some(format.as_str().into()),
+                gpg::Ssh::PROGRAM.name(),
+                &mut trusted,
+            )
+            .or_else(|| {
+                (format == Format::OpenPgp)
+                    .then(|| config.string_filter("gpg", None, Gpg::PROGRAM.name(), &mut trusted))
+                    .flatten()
+            })
+            .filter(|program| !program.is_empty())
+            .map_or_else(
+                || format.default_program().into(),
+                |program| gix_path::from_bstr(program).into_owned().into(),
+            )
+    }
+
+    fn signing_key(&self) -> Option<std::borrow::Cow<'_, crate::bstr::BStr>> {
+        self.config
+            .resolved
+            .string_filter(
+                "user",
+                None,
+                User::SIGNING_KEY.name(),
+                &mut self.filter_config_section(),
+            )
+            .filter(|key| !key.is_empty())
+    }
+
+    fn sign_with_gpg(&self, program: OsString, payload: &[u8]) -> Result<BString, Error> {
+        let key = match self.signing_key() {
+            Some(key) => key.into_owned(),
+            None => {
+                let committer = self.committer().ok_or(Error::CommitterMissing)??;
+                format!("{} <{}>", committer.name, committer.email).into()
+            }
+        };
+        let mut cmd: std::process::Command = gix_command::prepare(program.clone())
+            .args(["--status-fd=2", "-bsau"])
+            .arg(gix_path::from_bstring(key))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .into();
+        gix_trace::debug!(cmd = ?cmd);
+        let mut child = cmd.spawn().map_err(|err| Error::Spawn {
+            program: program.clone(),
+            source: err,
+        })?;
+        child.stdin.take().expect("configured").write_all(payload)?;
+        let out = child.wait_with_output()?;
+
+        // Just like `git`, trust the status output more than the exit code.
+        let status = out.stderr.as_bstr();
+        if !out.status.success() || status.find("\n[GNUPG:] SIG_CREATED ").is_none() || out.stdout.is_empty() {
+            return Err(Error::Failed {
+                program,
+                stderr: out.stderr.into(),
+            });
+        }
+        Ok(out.stdout.into())
+    }
+
+    fn sign_with_ssh(&self, program: OsString, payload: &[u8]) -> Result<BString, Error> {
+        let literal_key = |key: &crate::bstr::BStr| -> Option<BString> {
+            key.strip_prefix(b"key::")
+                .or_else(|| key.starts_with(b"ssh-").then_some(key.as_bytes()))
+                .map(Into::into)
+        };
+        let (key_file, is_literal_key) = match self.signing_key() {
+            Some(key) => match literal_key(key.as_ref()) {
+                Some(key) => (TempFile::new(&key)?, true),
+                None => {
+                    let path = self
+                        .config
+                        .trusted_file_path("user", None, User::SIGNING_KEY.name())
+                        .expect("present as the key was found before")?;
+                    (TempFile::Existing(path.into_owned()), false)
+                }
+            },
+            None => {
+                let key = self.ssh_default_key()?;
+                (TempFile::new(&key)?, true)
+            }
+        };
+
+        let buffer = TempFile::new(payload)?;
+        let signature_path = {
+            let mut path = buffer.path().as_os_str().to_owned();
+            path.push(".sig");
+            std::path::PathBuf::from(path)
+        };
+        let mut prepare = gix_command::prepare(program.clone())
+            .args(["-Y", "sign", "-n", "git", "-f"])
+            .arg(key_file.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if is_literal_key {
+            prepare = prepare.arg("-U");
+        }
+        let mut cmd: std::process::Command = prepare.arg(buffer.path()).into();
+        gix_trace::debug!(cmd = ?cmd);
+        let out = cmd.output().map_err(|err| Error::Spawn {
+            program: program.clone(),
+            source: err,
+        })?;
+        let signature = std::fs::read(&signature_path);
+        std::fs::remove_file(&signature_path).ok();
+        if !out.status.success() {
+            return Err(Error::Failed {
+                program,
+                stderr: out.stderr.into(),
+            });
+        }
+        let signature = signature?;
+        if signature.is_empty() {
+            return Err(Error::Failed {
+                program,
+                stderr: out.stderr.into(),
+            });
+        }
+        Ok(signature.into())
+    }
+
+    /// Run `gpg.ssh.defaultKeyCommand` and return the first line of its output that looks like a literal key.
+    fn ssh_default_key(&self) -> Result<BString, Error> {
+        let command = self
+            .config
+            .resolved
+            .string_filter(
+                "gpg",
+                Some("ssh".into()),
+                gpg::Ssh::DEFAULT_KEY_COMMAND.name(),
+                &mut self.filter_config_section(),
+            )
+            .filter(|cmd| !cmd.is_empty())
+            .ok_or(Error::SigningKeyMissing)?;
+        let program = gix_path::from_bstr(command).into_owned().into_os_string();
+        let mut cmd: std::process::Command = gix_command::prepare(program.clone())
+            .with_shell()
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .into();
+        gix_trace::debug!(cmd = ?cmd);
+        let out = cmd.output().map_err(|err| Error::Spawn { program, source: err })?;
+        out.stdout
+            .lines()
+            .find(|line| line.starts_with(b"ssh-"))
+            .filter(|_| out.status.success())
+            .map(Into::into)
+            .ok_or_else(|| Error::DefaultKeyCommand {
+                stderr: out.stderr.into(),
+            })
+    }
+}
+
+/// Verification
+impl crate::Repository {
+    /// Verify that `signature` was made over `payload` with the verification program for its format, configured just like
+    /// the [signing program](Self::sign()), and return information about the signer and the trust placed into their key.
+    ///
+    /// SSH signatures are verified against the keys in `gpg.ssh.allowedSignersFile`, which must be configured, while honoring
+    /// `gpg.ssh.revocationFile`. Keys of valid signatures which aren't listed as allowed signer have [undefined](Trust::Undefined)
+    /// trust, whereas listed ones are [fully](Trust::Fully) trusted.
+    ///
+    /// Just like `git`, an error is returned if the trust in the signing key is below `gpg.minTrustLevel`, which isn't set by default.
+    pub fn verify_signature(&self, signature: &BStr, payload: &[u8]) -> Result<Verification, verify::Error> {
+        let format = Format::from_signature(signature).ok_or(verify::Error::UnknownFormat)?;
+        let program = self.signing_program(format);
+        let verification = match format {
+            Format::OpenPgp | Format::X509 => self.verify_with_gpg(format, program, signature, payload)?,
+            Format::Ssh => self.verify_with_ssh(program, signature, payload)?,
+        };
+
+        let min_trust = self
+            .config
+            .resolved
+            .string_by_key(Gpg::MIN_TRUST_LEVEL.logical_name().as_str())
+            .map(|value| Gpg::MIN_TRUST_LEVEL.try_into_trust(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or_default();
+        if verification.trust < min_trust {
+            return Err(verify::Error::Untrusted {
+                verification: verification.into(),
+                min_trust,
+            });
+        }
+        Ok(verification)
+    }
+
+    fn verify_with_gpg(
+        &self,
+        format: Format,
+        program: OsString,
+        signature: &BStr,
+        payload: &[u8],
+    ) -> Result<Verification, verify::Error> {
+        let signature_file = TempFile::new(signature)?;
+        let mut prepare = gix_command::prepare(program.clone());
+        if format == Format::OpenPgp {
+            prepare = prepare.arg("--keyid-format=long");
+        }
+        let out = run_verification(
+            program,
+            prepare
+                .args(["--status-fd=1", "--verify"])
+                .arg(signature_file.path())
+                .arg("-"),
+            Some(payload),
+        )?;
+
+        let mut verification = Verification {
+            format,
+            trust: Trust::Undefined,
+            signer: None,
+            fingerprint: None,
+            output: out.stderr.into(),
+        };
+        let (mut is_good, mut is_bad) = (false, false);
+        for line in out.stdout.lines() {
+            let Some(line) = line.strip_prefix(b"[GNUPG:] ") else {
+                continue;
+            };
+            let (keyword, args) = line.split_once_str(" ").unwrap_or((line, &[]));
+            match keyword {
+                b"GOODSIG" => {
+                    is_good = true;
+                    verification.signer = args.split_once_str(" ").map(|(_key_id, user_id)| user_id.into());
+                }
+                b"BADSIG" | b"ERRSIG" | b"EXPSIG" | b"EXPKEYSIG" | b"REVKEYSIG" => is_bad = true,
+                b"VALIDSIG" => verification.fingerprint = args.split_str(" ").next().map(Into::into),
+                b"TRUST_UNDEFINED" => verification.trust = Trust::Undefined,
+                b"TRUST_NEVER" => verification.trust = Trust::Never,
+                b"TRUST_MARGINAL" => verification.trust = Trust::Marginal,
+                b"TRUST_FULLY" => verification.trust = Trust::Fully,
+                b"TRUST_ULTIMATE" => verification.trust = Trust::Ultimate,
+                _ => {}
+            }
+        }
+        if !out.status.success() || !is_good || is_bad {
+            return Err(verify::Error::Invalid {
+                output: verification.output,
+            });
+        }
+        Ok(verification)
+    }
+
+    fn verify_with_ssh(
+        &self,
+        program: OsString,
+        signature: &BStr,
+        payload: &[u8],
+    ) -> Result<Verification, verify::Error> {
+        let allowed_signers = self
+            .config
+            .trusted_file_path("gpg", Some("ssh".into()), gpg::Ssh::ALLOWED_SIGNERS_FILE.name())
+            .transpose()?
+            .filter(|path| path.is_file())
+            .ok_or(verify::Error::AllowedSignersFileMissing)?;
+        let revocation_file = self
+            .config
+            .trusted_file_path("gpg", Some("ssh".into()), gpg::Ssh::REVOCATION_FILE.name())
+            .transpose()?;
+        let signature_file = TempFile::new(signature)?;
+
+        let out = run_verification(
+            program.clone(),
+            gix_command::prepare(program.clone())
+                .args(["-Y", "find-principals", "-f"])
+                .arg(allowed_signers.as_ref())
+                .arg("-s")
+                .arg(signature_file.path()),
+            None,
+        )?;
+        let principals: Vec<BString> = if out.status.success() {
+            out.stdout
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(Into::into)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut verification = Verification {
+            format: Format::Ssh,
+            trust: Trust::Undefined,
+            signer: None,
+            fingerprint: None,
+            output: Default::default(),
+        };
+        if principals.is_empty() {
+            // Just like `git`, validate the signature itself even if we don't know who made it.
+            let out = run_verification(
+                program.clone(),
+                gix_command::prepare(program)
+                    .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+                    .arg(signature_file.path()),
+                Some(payload),
+            )?;
+            verification.output = ssh_output(&out);
+            if !out.status.success() {
+                return Err(verify::Error::Invalid {
+                    output: verification.output,
+                });
+            }
+        } else {
+            for principal in principals {
+                let mut prepare = gix_command::prepare(program.clone())
+                    .args(["-Y", "verify", "-n", "git", "-f"])
+                    .arg(allowed_signers.as_ref())
+                    .arg("-I")
+                    .arg(gix_path::from_bstr(principal.as_bstr()).as_ref())
+                    .arg("-s")
+                    .arg(signature_file.path());
+                if let Some(revocation_file) = revocation_file.as_deref() {
+                    prepare = prepare.arg("-r").arg(revocation_file);
+                }
+                let out = run_verification(program.clone(), prepare, Some(payload))?;
+                verification.output = ssh_output(&out);
+                if out.status.success() {
+                    verification.trust = Trust::Fully;
+                    verification.signer = Some(principal);
+                    break;
+                }
+            }
+            if verification.signer.is_none() {
+                return Err(verify::Error::Invalid {
+                    output: verification.output,
+                });
+            }
+        }
+        verification.fingerprint = verification
+            .output
+            .lines()
+            .find(|line| line.starts_with(b"Good "))
+            .and_then(|line| line.rfind(b" key ").map(|pos| line[pos + b" key ".len()..].into()));
+        Ok(verification)
+    }
+}
+
+/// Run the verification `program` prepared with `prepare`, feed `stdin` to it if set, and return its output.
+fn run_verification(
+    program: OsString,
+    prepare: gix_command::Prepare,
+    stdin: Option<&[u8]>,
+) -> Result<std::process::Output, verify::Error> {
+    let mut cmd: std::process::Command = prepare
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .into();
+    gix_trace::debug!(cmd = ?cmd);
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| verify::Error::Spawn { program, source: err })?;
+    if let Some((input, mut child_stdin)) = stdin.zip(child.stdin.take()) {
+        match child_stdin.write_all(input) {
+            // The program may fail before reading all input, which is reported by its output.
+            Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => return Err(err.into()),
+            _ => {}
+        }
+    }
+    Ok(child.wait_with_output()?)
+}
+
+/// `ssh-keygen` prints its results to `stdout` and errors to `stderr`, so use both as output.
+fn ssh_output(out: &std::process::Output) -> BString {
+    let mut output: BString = out.stdout.clone().into();
+    output.extend_from_slice(&out.stderr);
+    output
+}
+
+/// A file to pass to the signing program, which is removed once dropped unless it existed before.
+enum TempFile {
+    Existing(std::path::PathBuf),
+    Temporary {
+        path: std::path::PathBuf,
+        /// Keep the file alive until we are dropped.
+        _file: gix_tempfile::Handle<gix_tempfile::handle::Writable>,
+    },
+}
+
+impl TempFile {
+    fn new(content: &[u8]) -> std::io::Result<Self> {
+        let mut file = gix_tempfile::new(
+            std::env::temp_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        file.write_all(content)?;
+        file.flush()?;
+        let path = file.with_mut(|file| file.path().to_owned())?;
+        Ok(TempFile::Temporary { path, _file: file })
+    }
+
+    fn path(&self) -> &std::path::Path {
+        match self {
+            TempFile::Existing(path) | TempFile::Temporary { path, .. } => path,
+        }
+    }
+}
@@ -0,0 +1,293 @@
+/// Sparse checkouts
+impl crate::Repository {
+    /// Return the configuration that affects sparse checkouts and the sparse index, as configured by `core.sparseCheckout`,
+    /// `core.sparseCheckoutCone` and `index.sparse`.
+    pub fn sparse_checkout_options(&self) -> Result<gix_index::sparse::Options, crate::config::boolean::Error> {
+        self.config.sparse_checkout_options()
+    }
+
+    /// Return the patterns of the `info/sparse-checkout` file in the private git directory of this worktree, interpreted
+    /// in cone-mode if `core.sparseCheckoutCone` is enabled, or `None` if sparse checkouts are disabled
+    /// or if the file doesn't exist.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn sparse_checkout_patterns(
+        &self,
+    ) -> Result<Option<crate::worktree::sparse::Patterns>, crate::worktree::sparse::patterns::Error> {
+        use crate::worktree::sparse::{patterns::Error, Patterns};
+        let options = self.sparse_checkout_options()?;
+        if !options.sparse_checkout {
+            return Ok(None);
+        }
+        let path = self.sparse_checkout_path();
+        Patterns::from_file(&path, options.directory_patterns_only).map_err(|source| Error::Io { path, source })
+    }
+
+    /// Set the `patterns` of the sparse checkout, enable sparse checkouts in the repository configuration
+    /// and update the worktree to match, similar to `git sparse-checkout set`.
+    ///
+    /// In cone-mode, each pattern is a directory whose content is included recursively, with files in the root
+    /// of the worktree always included. Otherwise, each pattern is a line like in a `.gitignore` file.
+    /// `core.sparseCheckout`, `core.sparseCheckoutCone` and `index.sparse` are written to the configuration file of the
+    /// repository according to `options`.
+    ///
+    /// `should_interrupt` is checked while writing newly included files.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn sparse_checkout_set(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<crate::bstr::BStr>>,
+        options: crate::worktree::sparse::set::Options,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<crate::worktree::sparse::reapply::Outcome, crate::worktree::sparse::set::Error> {
+        let patterns = if options.cone {
+            let mut buf = Vec::new();
+            crate::worktree::sparse::Cone::from_directories(patterns)
+                .write_to(&mut buf)
+                .expect("writing to memory works");
+            buf
+        } else {
+            non_cone_patterns(patterns)
+        };
+        self.write_sparse_checkout_patterns(&patterns)?;
+        self.write_sparse_checkout_config(options)?;
+        Ok(self.sparse_checkout_reapply(should_interrupt)?)
+    }
+
+    /// Add `patterns` to the existing patterns of the sparse checkout and update the worktree to match,
+    /// similar to `git sparse-checkout add`.
+    ///
+    /// In cone-mode, each pattern is a directory to include recursively, otherwise each pattern is a line
+    /// like in a `.gitignore` file.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn sparse_checkout_add(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<crate::bstr::BStr>>,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<crate::worktree::sparse::reapply::Outcome, crate::worktree::sparse::set::Error> {
+        use crate::worktree::sparse::{set::Error, Patterns};
+        let new_patterns = match self.sparse_checkout_patterns()?.ok_or(Error::NotEnabled)? {
+            Patterns::Cone(mut cone) => {
+                cone.add_directories(patterns);
+                let mut buf = Vec::new();
+                cone.write_to(&mut buf).expect("writing to memory works");
+                buf
+            }
+            Patterns::NonCone(_) => {
+                let path = self.sparse_checkout_path();
+                let mut buf = std::fs::read(&path).map_err(|source| Error::WritePatterns { path, source })?;
+                if !buf.is_empty() && !buf.ends_with(b"\n") {
+                    buf.push(b'\n');
+                }
+                buf.extend(non_cone_patterns(patterns));
+                buf
+            }
+        };
+        self.write_sparse_checkout_patterns(&new_patterns)?;
+        Ok(self.sparse_checkout_reapply(should_interrupt)?)
+    }
+
+    /// Update the worktree and the index to match the current sparse-checkout patterns, similar to
+    /// `git sparse-checkout reapply`.
+    ///
+    /// Files that are excluded by the patterns are removed from the worktree and marked to skip the worktree in the index,
+    /// unless they are modified. Files that are newly included are checked out, unless a different file already exists
+    /// in their place. If `index.sparse` is enabled in cone-mode, directories outside of the cone are collapsed into
+    /// sparse directory entries, which requires the index to have a valid tree extension.
+    ///
+    /// `should_interrupt` is checked while writing newly included files.
+    ///
+    /// ### Deviation
+    ///
+    /// A file is considered unmodified if its stat information matches, or if its unfiltered content hashes to the
+    /// id in the index, which means files affected by filters may be considered modified and are kept.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn sparse_checkout_reapply(
+        &self,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<crate::worktree::sparse::reapply::Outcome, crate::worktree::sparse::reapply::Error> {
+        use gix_index::entry::Flags;
+
+        use crate::{
+            bstr::ByteSlice,
+            worktree::sparse::{
+                reapply::{Error, Outcome},
+                Patterns,
+            },
+        };
+
+        let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+        let patterns = self.sparse_checkout_patterns()?.ok_or(Error::NotEnabled)?;
+        let stat_options = self.stat_options()?;
+        let mut index = self.open_index()?;
+        index.expand_sparse_directories(&self.objects)?;
+
+        let mut out = Outcome::default();
+        let mut to_checkout = gix_index::State::new(self.object_hash());
+        let mut removed_files = Vec::new();
+        for (entry, rela_path) in index.entries_mut_with_paths() {
+            if entry.stage() != 0 {
+                continue;
+            }
+            let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+            let should_skip = !patterns.is_included(rela_path, false);
+            if is_skipped == should_skip {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(rela_path));
+            let io_err = |source| Error::Io {
+                path: path.clone(),
+                source,
+            };
+            let is_unmodified = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                Ok(md) => Some(is_unmodified(entry, &path, &md, stat_options).map_err(io_err)?),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(err) => return Err(io_err(err)),
+            };
+            match (should_skip, is_unmodified) {
+                (true, Some(false)) => out.modified_files_kept.push(rela_path.to_owned()),
+                (true, unmodified) => {
+                    if unmodified.is_some() {
+                        std::fs::remove_file(&path).map_err(io_err)?;
+                        removed_files.push(path);
+                        out.files_removed += 1;
+                    }
+                    entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+                }
+                (false, Some(false)) => out.existing_files_kept.push(rela_path.to_owned()),
+                (false, Some(true)) => {
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                }
+                (false, None) => {
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    to_checkout.dangerously_push_entry(entry.stat, entry.id, Flags::empty(), entry.mode, rela_path);
+                }
+            }
+        }
+        for mut dir in removed_files.iter().filter_map(|path| path.parent()) {
+            while dir != workdir && std::fs::remove_dir(dir).is_ok() {
+                match dir.parent() {
+                    Some(parent) => dir = parent,
+                    None => break,
+                }
+            }
+        }
+
+        if !to_checkout.entries().is_empty() {
+            let mut opts = self.config.checkout_options(
+                self,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?;
+            opts.sparse_checkout = None;
+            opts.destination_is_initially_empty = false;
+            opts.overwrite_existing = false;
+            to_checkout.sort_entries();
+            let outcome = gix_worktree_state::checkout(
+                &mut to_checkout,
+                workdir,
+                self.objects.clone().into_arc().map_err(Error::ObjectsIntoArc)?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            )?;
+            out.files_checked_out = outcome.files_updated;
+            for entry in to_checkout.entries() {
+                if let Some(idx) = index.entry_index_by_path_and_stage(entry.path(&to_checkout), 0) {
+                    index.entries_mut()[idx].stat = entry.stat;
+                }
+            }
+        }
+        for (entry, _path) in index.entries_mut_with_paths() {
+            if !entry.flags.intersects(Flags::SKIP_WORKTREE | Flags::INTENT_TO_ADD) {
+                entry.flags.remove(Flags::EXTENDED);
+            }
+        }
+
+        let options = self.sparse_checkout_options()?;
+        if let (Patterns::Cone(_), true) = (&patterns, options.write_sparse_index) {
+            index.collapse_sparse_directories(|dir| patterns.is_directory_outside_cone(dir.as_bstr()));
+        }
+        index.write(self.index_write_options(&index)?)?;
+        Ok(out)
+    }
+
+    #[cfg(feature = "worktree-mutation")]
+    fn sparse_checkout_path(&self) -> std::path::PathBuf {
+        self.git_dir().join("info").join("sparse-checkout")
+    }
+
+    #[cfg(feature = "worktree-mutation")]
+    fn write_sparse_checkout_patterns(&self, patterns: &[u8]) -> Result<(), crate::worktree::sparse::set::Error> {
+        let path = self.sparse_checkout_path();
+        let io_err = |source| crate::worktree::sparse::set::Error::WritePatterns {
+            path: path.clone(),
+            source,
+        };
+        std::fs::create_dir_all(path.parent().expect("file in directory")).map_err(io_err)?;
+        std::fs::write(&path, patterns).map_err(io_err)
+    }
+
+    /// Write the sparse-checkout configuration to the configuration file of the repository, and apply it to our
+    /// in-memory configuration as well.
+    #[cfg(feature = "worktree-mutation")]
+    fn write_sparse_checkout_config(
+        &mut self,
+        options: crate::worktree::sparse::set::Options,
+    ) -> Result<(), crate::worktree::sparse::set::Error> {
+        use crate::config::tree::{Core, Index, Key};
+        let values: [(&'static dyn Key, bool); 3] = [
+            (&Core::SPARSE_CHECKOUT, true),
+            (&Core::SPARSE_CHECKOUT_CONE, options.cone),
+            (&Index::SPARSE, options.cone && options.sparse_index),
+        ];
+        let value = |enabled: bool| if enabled { "true" } else { "false" };
+
+        let path = self.common_dir().join("config");
+        let mut config = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+        for (key, enabled) in values {
+            config.set_raw_value(key.section().name(), None, key.name(), value(enabled))?;
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        config.write_to(&mut lock)?;
+        lock.commit().map_err(|err| err.error)?;
+
+        let mut snapshot = self.config_snapshot_mut();
+        for (key, enabled) in values {
+            snapshot.set_value(key, value(enabled))?;
+        }
+        snapshot.commit()?;
+        Ok(())
+    }
+}
+
+/// Turn each non-cone pattern into its own line.
+#[cfg(feature = "worktree-mutation")]
+fn non_cone_patterns(patterns: impl IntoIterator<Item = impl AsRef<crate::bstr::BStr>>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for pattern in patterns {
+        buf.extend_from_slice(pattern.as_ref());
+        buf.push(b'\n');
+    }
+    buf
+}
+
+/// Return `true` if the file at `path` with metadata `md` matches `entry`, comparing its stat information with `options`
+/// first, and its content second.
+#[cfg(feature = "worktree-mutation")]
+fn is_unmodified(
+    entry: &gix_index::Entry,
+    path: &std::path::Path,
+    md: &gix_index::fs::Metadata,
+    options: gix_index::entry::stat::Options,
+) -> std::io::Result<bool> {
+    if gix_index::entry::Stat::from_fs(md).map_or(false, |stat| entry.stat.matches(&stat, options)) {
+        return Ok(true);
+    }
+    let data = if md.is_symlink() {
+        gix_path::into_bstr(std::fs::read_link(path)?).into_owned().into()
+    } else if md.is_file() {
+        std::fs::read(path)?
+    } else {
+        return Ok(false);
+    };
+    Ok(gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, &data) == entry.id)
+}
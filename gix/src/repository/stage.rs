@@ -0,0 +1,364 @@
+use std::{
+    io::Read,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_index::entry::{Flags, Mode, Stat};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    stage::{add, remove, Error},
+};
+
+/// Staging
+impl crate::Repository {
+    /// Update the index with all worktree files matching `patterns` and write it, similar to `git add --all <patterns>`.
+    /// If `patterns` is empty, all files in the worktree are considered.
+    ///
+    /// Untracked files are added unless they are excluded by `.gitignore` files or similar, tracked files are updated
+    /// if their content or mode changed, and entries whose files don't exist in the worktree anymore are removed.
+    /// The content of files is passed through the _clean_ filters configured in `.gitattributes` before it's written
+    /// to the object database, and the stat information of updated entries is refreshed.
+    ///
+    /// `should_interrupt` is checked before each file is processed.
+    ///
+    /// ### Deviation
+    ///
+    /// * Nested repositories are skipped instead of being added as submodules, and submodules aren't updated.
+    /// * Entries marked to [skip the worktree](Flags::SKIP_WORKTREE) are left untouched, and sparse indices are written
+    ///   as full index.
+    pub fn stage_add(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: add::Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<add::Outcome, Error> {
+        use gix_worktree::stack::state::{attributes, ignore};
+
+        let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+        let mut index = self.open_index_or_empty()?;
+        let mut changed = index.expand_sparse_directories(&self.objects)? != 0;
+        let mut pathspec = self.pathspec(patterns, true, &index, attributes::Source::WorktreeThenIdMapping)?;
+        let mut excludes = self.excludes(&index, None, ignore::Source::WorktreeThenIdMappingIfNotSkipped)?;
+        let mut pipeline = crate::filter::Pipeline::new(
+            self,
+            self.attributes_only(&index, attributes::Source::WorktreeThenIdMapping)?
+                .detach(),
+        )?;
+        let capabilities = self.filesystem_options()?;
+        let stat_options = self.stat_options()?;
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+
+        let mut out = add::Outcome::default();
+        let mut deleted = Vec::new();
+        for entry in index.entries() {
+            let rela_path = entry.path(&index);
+            if entry.flags.contains(Flags::SKIP_WORKTREE)
+                || entry.mode.is_submodule()
+                || !pathspec.is_included(rela_path, Some(false))
+            {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(rela_path));
+            match std::fs::symlink_metadata(&path) {
+                Ok(md) if !md.is_dir() => {}
+                Ok(_) => deleted.push(rela_path.to_owned()),
+                Err(err)
+                    if err.kind() == std::io::ErrorKind::NotFound
+                        || !path.parent().map_or(false, std::path::Path::is_dir) =>
+                {
+                    deleted.push(rela_path.to_owned())
+                }
+                Err(err) => return Err(io_err(&path)(err)),
+            }
+        }
+        for rela_path in deleted {
+            out.entries_removed += index.remove_entries_by_path(rela_path.as_ref());
+        }
+
+        let prefix = pathspec.search().common_prefix().to_owned();
+        let mut dirs = vec![BString::default()];
+        let mut buf = Vec::new();
+        while let Some(dir) = dirs.pop() {
+            let dir_path = workdir.join(gix_path::from_bstr(dir.as_bstr()));
+            for dir_entry in std::fs::read_dir(&dir_path).map_err(io_err(&dir_path))? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let dir_entry = dir_entry.map_err(io_err(&dir_path))?;
+                let name = dir_entry.file_name();
+                if name == ".git" {
+                    continue;
+                }
+                let mut rela_path = dir.clone();
+                if !rela_path.is_empty() {
+                    rela_path.push(b'/');
+                }
+                rela_path.extend_from_slice(&gix_path::into_bstr(std::path::PathBuf::from(name)));
+                let path = dir_entry.path();
+                let md = gix_index::fs::Metadata::from_path_no_follow(&path).map_err(io_err(&path))?;
+
+                if md.is_dir() {
+                    let mut dir_prefix = rela_path.clone();
+                    dir_prefix.push(b'/');
+                    let may_match = dir_prefix.starts_with(&prefix) || prefix.starts_with(&dir_prefix);
+                    if !may_match || path.join(".git").exists() {
+                        continue;
+                    }
+                    let is_tracked = index.prefixed_entries(dir_prefix.as_ref()).is_some();
+                    if !is_tracked
+                        && (options.update_only
+                            || (!options.include_ignored
+                                && excludes
+                                    .at_entry(rela_path.as_bstr(), Some(true))
+                                    .map_err(io_err(&path))?
+                                    .is_excluded()))
+                    {
+                        continue;
+                    }
+                    dirs.push(rela_path);
+                    continue;
+                }
+                if !(md.is_file() || md.is_symlink()) || !pathspec.is_included(rela_path.as_bstr(), Some(false)) {
+                    continue;
+                }
+
+                let tracked = index.entry_by_path(rela_path.as_ref()).cloned();
+                match &tracked {
+                    None => {
+                        if options.update_only
+                            || (!options.include_ignored
+                                && excludes
+                                    .at_entry(rela_path.as_bstr(), Some(false))
+                                    .map_err(io_err(&path))?
+                                    .is_excluded())
+                        {
+                            continue;
+                        }
+                    }
+                    Some(entry) if entry.flags.contains(Flags::SKIP_WORKTREE) || entry.mode.is_submodule() => continue,
+                    Some(_) => {}
+                }
+
+                let stat = Stat::from_fs(&md).unwrap_or_default();
+                let mode = match &tracked {
+                    Some(entry) => entry
+                        .mode
+                        .change_to_match_fs(&md, capabilities.symlink, capabilities.executable_bit)
+                        .map_or(entry.mode, |change| change.apply(entry.mode)),
+                    None if md.is_symlink() && capabilities.symlink => Mode::SYMLINK,
+                    None if md.is_executable() && capabilities.executable_bit => Mode::FILE_EXECUTABLE,
+                    None => Mode::FILE,
+                };
+                let is_unchanged_stat = |entry: &gix_index::Entry| {
+                    entry.stage() == 0
+                        && entry.mode == mode
+                        && entry.stat.matches(&stat, stat_options)
+                        && !entry.stat.is_racy(index.timestamp(), stat_options)
+                };
+                if tracked.as_ref().map_or(false, is_unchanged_stat) {
+                    continue;
+                }
+
+                worktree_blob(&mut pipeline, &index, &path, rela_path.as_ref(), &md, mode, &mut buf)?;
+                let id = self.write_blob(&buf)?.detach();
+                match tracked {
+                    Some(entry) if entry.stage() == 0 && entry.id == id && entry.mode == mode => {
+                        let entry = index
+                            .entry_mut_by_path_and_stage(rela_path.as_ref(), 0)
+                            .expect("entry is present");
+                        entry.stat = stat;
+                        changed = true;
+                    }
+                    tracked => {
+                        index.upsert_entry(stat, id, Flags::empty(), mode, rela_path.as_ref());
+                        if tracked.is_some() {
+                            out.entries_updated += 1;
+                        } else {
+                            out.entries_added += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        if changed || out != add::Outcome::default() {
+            index.write(self.index_write_options(&index)?)?;
+        }
+        Ok(out)
+    }
+
+    /// Remove all index entries matching `patterns` along with their files in the worktree and write the index,
+    /// similar to `git rm <patterns>`, with empty directories being removed as well.
+    ///
+    /// Unless [forced](remove::Options::force) or only the index is [affected](remove::Options::cached),
+    /// nothing is removed if one of the files has local modifications.
+    ///
+    /// ### Deviation
+    ///
+    /// * Directories are always removed recursively, as if `-r` was specified.
+    /// * Only worktree files are checked for modifications, but not the index entries against `HEAD`.
+    /// * Submodule entries are removed from the index, but their worktree is left untouched.
+    pub fn stage_remove(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: remove::Options,
+    ) -> Result<remove::Outcome, Error> {
+        use gix_worktree::stack::state::attributes;
+
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        if patterns.is_empty() {
+            return Err(Error::NoPathspec);
+        }
+        let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+        let mut index = self.open_index_or_empty()?;
+        index.expand_sparse_directories(&self.objects)?;
+        let mut pathspec = self.pathspec(&patterns, true, &index, attributes::Source::WorktreeThenIdMapping)?;
+
+        let mut matched = Vec::<BString>::new();
+        for entry in index.entries() {
+            let rela_path = entry.path(&index);
+            if matched.last().map_or(false, |last| last == rela_path) {
+                continue;
+            }
+            if pathspec.is_included(rela_path, Some(false)) {
+                matched.push(rela_path.to_owned());
+            }
+        }
+
+        if !options.cached && !options.force {
+            let mut pipeline = crate::filter::Pipeline::new(
+                self,
+                self.attributes_only(&index, attributes::Source::WorktreeThenIdMapping)?
+                    .detach(),
+            )?;
+            let capabilities = self.filesystem_options()?;
+            let stat_options = self.stat_options()?;
+            let mut buf = Vec::new();
+            let mut modified = Vec::new();
+            for rela_path in &matched {
+                let entry = match index.entry_by_path_and_stage(rela_path.as_ref(), 0) {
+                    Some(entry) if !entry.flags.contains(Flags::SKIP_WORKTREE) && !entry.mode.is_submodule() => entry,
+                    _ => continue,
+                };
+                let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+                let md = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                    Ok(md) => md,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(source) => return Err(Error::Io { path, source }),
+                };
+                if md.is_dir() {
+                    continue;
+                }
+                let is_mode_unchanged = entry
+                    .mode
+                    .change_to_match_fs(&md, capabilities.symlink, capabilities.executable_bit)
+                    .is_none();
+                let is_unmodified = is_mode_unchanged
+                    && (Stat::from_fs(&md).map_or(false, |stat| {
+                        entry.stat.matches(&stat, stat_options) && !entry.stat.is_racy(index.timestamp(), stat_options)
+                    }) || {
+                        worktree_blob(
+                            &mut pipeline,
+                            &index,
+                            &path,
+                            rela_path.as_ref(),
+                            &md,
+                            entry.mode,
+                            &mut buf,
+                        )?;
+                        gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, &buf) == entry.id
+                    });
+                if !is_unmodified {
+                    modified.push(rela_path.clone());
+                }
+            }
+            if !modified.is_empty() {
+                return Err(Error::LocalModifications { paths: modified });
+            }
+        }
+
+        let mut out = remove::Outcome::default();
+        let mut removed_files = Vec::new();
+        for rela_path in &matched {
+            let is_submodule = index
+                .entry_by_path(rela_path.as_ref())
+                .map_or(false, |entry| entry.mode.is_submodule());
+            out.entries_removed += index.remove_entries_by_path(rela_path.as_ref());
+            if options.cached || is_submodule {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            match std::fs::symlink_metadata(&path) {
+                Ok(md) if !md.is_dir() => {
+                    std::fs::remove_file(&path).map_err(|source| Error::Io {
+                        path: path.clone(),
+                        source,
+                    })?;
+                    out.files_removed += 1;
+                    removed_files.push(path);
+                }
+                _ => {}
+            }
+        }
+        for mut dir in removed_files.iter().filter_map(|path| path.parent()) {
+            while dir != workdir && std::fs::remove_dir(dir).is_ok() {
+                match dir.parent() {
+                    Some(parent) => dir = parent,
+                    None => break,
+                }
+            }
+        }
+
+        if out.entries_removed != 0 {
+            index.write(self.index_write_options(&index)?)?;
+        }
+        Ok(out)
+    }
+
+    fn open_index_or_empty(&self) -> Result<gix_index::File, crate::worktree::open_index::Error> {
+        if self.index_path().is_file() {
+            self.open_index()
+        } else {
+            Ok(gix_index::File::from_state(
+                gix_index::State::new(self.object_hash()),
+                self.index_path(),
+            ))
+        }
+    }
+}
+
+/// Read the worktree file at `path` with metadata `md` into `buf` as it should be stored in git, which means
+/// the content of files is passed through the clean filters of `pipeline`, while symlinks are stored as is.
+fn worktree_blob(
+    pipeline: &mut crate::filter::Pipeline<'_>,
+    index: &gix_index::State,
+    path: &Path,
+    rela_path: &BStr,
+    md: &gix_index::fs::Metadata,
+    mode: Mode,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let io_err = |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    };
+    buf.clear();
+    if md.is_symlink() {
+        let target = std::fs::read_link(path).map_err(io_err)?;
+        buf.extend_from_slice(&gix_path::into_bstr(target));
+    } else if mode == Mode::SYMLINK {
+        buf.extend(std::fs::read(path).map_err(io_err)?);
+    } else {
+        let file = std::fs::File::open(path).map_err(io_err)?;
+        pipeline
+            .convert_to_git(file, &gix_path::from_bstr(rela_path), index)?
+            .read_to_end(buf)
+            .map_err(io_err)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,11 @@
-use crate::{worktree, Worktree};
+use std::path::Path;
+
+use crate::{
+    bstr::BString,
+    worktree,
+    worktree::{proxy, Proxy},
+    Worktree,
+};
 
 /// Interact with individual worktrees and their information.
 impl crate::Repository {
@@ -25,6 +32,81 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+    /// Remove the administrative files of all linked worktrees that are [prunable](Proxy::prunable()), configured by `options`,
+    /// similar to `git worktree prune`, and return information about each pruned worktree sorted by id.
+    pub fn worktree_prune(&self, options: worktree::prune::Options) -> std::io::Result<Vec<worktree::prune::Pruned>> {
+        let mut res = Vec::new();
+        let iter = match std::fs::read_dir(self.common_dir().join("worktrees")) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(res),
+            Err(err) => return Err(err),
+        };
+        for entry in iter {
+            let entry = entry?;
+            let proxy = Proxy::new(self, entry.path());
+            let Some(reason) = proxy.prunable(options.expire) else {
+                continue;
+            };
+            if !options.dry_run {
+                if entry.file_type()?.is_dir() {
+                    std::fs::remove_dir_all(proxy.git_dir())?;
+                } else {
+                    std::fs::remove_file(proxy.git_dir())?;
+                }
+            }
+            res.push(worktree::prune::Pruned {
+                id: proxy.id().to_owned(),
+                reason,
+            });
+        }
+        if !options.dry_run {
+            proxy::remove_worktrees_dir_if_empty(self.common_dir());
+        }
+        res.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(res)
+    }
+
+    /// Repair the administrative files of linked worktrees, similar to `git worktree repair [<path>…]`, and return the id of each
+    /// repaired worktree along with what was repaired.
+    ///
+    /// First, the `.git` file of each linked worktree checkout is made to point back to its administrative directory.
+    /// Then each of the given `paths` to linked worktree checkouts, which typically were moved manually, is used to fix
+    /// the `gitdir` file in their administrative directory so it points to the new location.
+    pub fn worktree_repair(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    ) -> Result<Vec<(BString, worktree::proxy::repair::Reason)>, worktree::proxy::repair::Error> {
+        let mut res = Vec::new();
+        for proxy in self.worktrees()? {
+            if let Some(reason) = proxy.repair()? {
+                res.push((proxy.id().to_owned(), reason));
+            }
+        }
+
+        let worktrees_dir = proxy::absolute(&self.common_dir().join("worktrees")).ok();
+        for path in paths {
+            let path = path.as_ref();
+            let not_linked = || worktree::proxy::repair::Error::NotALinkedWorktree { path: path.to_owned() };
+            let git_dir = gix_discover::path::from_gitdir_file(&path.join(gix_discover::DOT_GIT_DIR))
+                .ok()
+                .and_then(|git_dir| proxy::absolute(&git_dir).ok())
+                .ok_or_else(not_linked)?;
+            if worktrees_dir.is_none() || git_dir.parent() != worktrees_dir.as_deref() || !git_dir.is_dir() {
+                return Err(not_linked());
+            }
+            let proxy = Proxy::new(self, git_dir);
+            let is_correct = proxy.base().ok().map_or(false, |base| proxy::same_path(&base, path));
+            if !is_correct {
+                proxy::write_gitdir_file(proxy.git_dir(), path)?;
+                res.push((
+                    proxy.id().to_owned(),
+                    worktree::proxy::repair::Reason::GitDirFileIncorrect,
+                ));
+            }
+        }
+        Ok(res)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
@@ -51,6 +133,317 @@ impl crate::Repository {
         self.config.is_bare && self.work_dir().is_none()
     }
 
+    /// Create a new linked worktree at `path` whose `HEAD` is set according to `head`, and check out its files
+    /// while reporting to `progress` and checking `should_interrupt` for early abort, configured by `options`.
+    /// This is similar to `git worktree add`.
+    ///
+    /// `path` must not exist or be an empty directory. The id of the new worktree, i.e. the name of its administrative
+    /// directory in `.git/worktrees/`, is derived from the last component of `path` and made unique if needed.
+    ///
+    /// Return the repository of the newly added worktree along with the outcome of the checkout.
+    /// If the checkout fails, all files that were created so far will be removed again.
+    /// Just like `git`, the `post-checkout` hook is run after the checkout, but its exit status is ignored.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn worktree_add<P>(
+        &self,
+        path: impl AsRef<Path>,
+        head: worktree::add::Head,
+        mut progress: P,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        options: worktree::add::Options,
+    ) -> Result<(crate::Repository, gix_worktree_state::checkout::Outcome), worktree::add::Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: gix_features::progress::NestedProgress + 'static,
+    {
+        self.worktree_add_inner(path.as_ref(), head, &mut progress, should_interrupt, options)
+    }
+
+    #[cfg(feature = "worktree-mutation")]
+    fn worktree_add_inner(
+        &self,
+        path: &Path,
+        head: worktree::add::Head,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        options: worktree::add::Options,
+    ) -> Result<(crate::Repository, gix_worktree_state::checkout::Outcome), worktree::add::Error> {
+        use gix_ref::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+            Target,
+        };
+        use worktree::add::{Error, Head};
+
+        use crate::bstr::ByteSlice;
+
+        let _span = gix_trace::coarse!("gix::Repository::worktree_add()");
+        let path_existed = match std::fs::read_dir(path) {
+            Ok(mut entries) => {
+                if entries.next().is_some() {
+                    return Err(Error::PathExists { path: path.to_owned() });
+                }
+                true
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                if path.symlink_metadata().is_ok() {
+                    return Err(Error::PathExists { path: path.to_owned() });
+                }
+                false
+            }
+            Err(_) => return Err(Error::PathExists { path: path.to_owned() }),
+        };
+
+        let absolute_path = if path.is_relative() {
+            std::env::current_dir()?.join(path)
+        } else {
+            path.to_owned()
+        };
+        for proxy in self.worktrees()? {
+            let Ok(base) = proxy.base() else { continue };
+            if base.is_dir() || !proxy::same_path(&base, &absolute_path) {
+                continue;
+            }
+            if !options.force || proxy.is_locked() {
+                return Err(Error::PathRegistered {
+                    path: path.to_owned(),
+                    id: proxy.id().to_owned(),
+                });
+            }
+            std::fs::remove_dir_all(proxy.git_dir())?;
+        }
+
+        let mut new_branch = None;
+        let (commit, new_head) = match head {
+            Head::Branch(name) => {
+                let mut branch = self
+                    .try_find_reference(name.as_ref())?
+                    .ok_or_else(|| Error::BranchNotFound {
+                        name: name.as_bstr().to_owned(),
+                    })?;
+                if !options.force {
+                    self.assure_branch_is_not_checked_out(name.as_ref())?;
+                }
+                (branch.peel_to_id_in_place()?.detach(), Target::Symbolic(name))
+            }
+            Head::NewBranch { name, commit, force } => {
+                if force {
+                    self.assure_branch_is_not_checked_out(name.as_ref())?;
+                }
+                new_branch = Some((name.clone(), force));
+                (commit, Target::Symbolic(name))
+            }
+            Head::Detached(commit) => (commit, Target::Peeled(commit)),
+        };
+        let tree = self
+            .find_object(commit)?
+            .peel_to_tree()
+            .map_err(|err| Error::PeelToTree {
+                id: commit,
+                source: err,
+            })?
+            .id;
+
+        std::fs::create_dir_all(path)?;
+        let worktrees_dir = self.common_dir().join("worktrees");
+        std::fs::create_dir_all(&worktrees_dir)?;
+        let id = worktree::add::id_from_path(&absolute_path);
+        let mut git_dir = worktrees_dir.join(gix_path::from_bstr(id.as_bstr()));
+        let mut counter = 0;
+        while let Err(err) = std::fs::create_dir(&git_dir) {
+            if err.kind() != std::io::ErrorKind::AlreadyExists {
+                return Err(err.into());
+            }
+            counter += 1;
+            git_dir = worktrees_dir.join(gix_path::from_bstring(format!("{id}{counter}")));
+        }
+
+        // A new branch is created as late as possible and rolled back on failure to not leave it behind.
+        let mut created_branch = None;
+        let res = (|| -> Result<_, Error> {
+            if let Some((name, force)) = new_branch.take() {
+                let previous_id = if force {
+                    self.try_find_reference(&name)?
+                        .and_then(|r| r.inner.target.try_id().map(ToOwned::to_owned))
+                } else {
+                    None
+                };
+                self.reference(
+                    name.clone(),
+                    commit,
+                    if force {
+                        PreviousValue::Any
+                    } else {
+                        PreviousValue::MustNotExist
+                    },
+                    "branch: Created from worktree add",
+                )?;
+                created_branch = Some((name, previous_id));
+            }
+            std::fs::write(
+                git_dir.join("locked"),
+                options
+                    .lock
+                    .as_ref()
+                    .map_or(&b"initializing"[..], |reason| reason.as_slice()),
+            )?;
+            proxy::write_gitdir_file(&git_dir, path)?;
+            proxy::write_dot_git_file(path, &git_dir)?;
+            std::fs::write(git_dir.join("commondir"), "../..\n")?;
+            std::fs::write(
+                git_dir.join("HEAD"),
+                format!("{}\n", gix_hash::ObjectId::null(self.object_hash())),
+            )?;
+
+            let repo: crate::Repository = crate::ThreadSafeRepository::open_from_paths(
+                git_dir.clone(),
+                Some(proxy::absolute(path)?),
+                self.options.clone(),
+            )?
+            .into();
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: Default::default(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: new_head,
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            })?;
+
+            let outcome = if options.no_checkout {
+                Default::default()
+            } else {
+                let outcome = repo.worktree_add_checkout(tree, progress, should_interrupt)?;
+                match repo.hooks()?.post_checkout(&self.object_hash().null(), &commit, true) {
+                    Ok(_) | Err(crate::hooks::run::Error::Failed { .. }) => {}
+                    Err(err) => return Err(err.into()),
+                }
+                outcome
+            };
+            if options.lock.is_none() {
+                std::fs::remove_file(git_dir.join("locked"))?;
+            }
+            Ok((repo, outcome))
+        })();
+
+        if res.is_err() {
+            std::fs::remove_dir_all(&git_dir).ok();
+            if path_existed {
+                if let Ok(entries) = std::fs::read_dir(path) {
+                    for entry in entries.filter_map(Result::ok) {
+                        let entry_path = entry.path();
+                        if entry.file_type().map_or(false, |ft| ft.is_dir()) {
+                            std::fs::remove_dir_all(entry_path).ok();
+                        } else {
+                            std::fs::remove_file(entry_path).ok();
+                        }
+                    }
+                }
+            } else {
+                std::fs::remove_dir_all(path).ok();
+            }
+            proxy::remove_worktrees_dir_if_empty(self.common_dir());
+            if let Some((name, previous_id)) = created_branch {
+                let expected = PreviousValue::MustExistAndMatch(Target::Peeled(commit));
+                match previous_id {
+                    Some(id) => self
+                        .reference(name, id, expected, "branch: Restored after failed worktree add")
+                        .map(|_| ()),
+                    None => self
+                        .edit_reference(RefEdit {
+                            change: Change::Delete {
+                                expected,
+                                log: RefLog::AndReference,
+                            },
+                            name,
+                            deref: false,
+                        })
+                        .map(|_| ()),
+                }
+                .ok();
+            }
+        }
+        res
+    }
+
+    #[cfg(feature = "worktree-mutation")]
+    fn worktree_add_checkout(
+        &self,
+        tree: gix_hash::ObjectId,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<gix_worktree_state::checkout::Outcome, worktree::add::Error> {
+        use gix_features::progress::Progress;
+        use worktree::add::{Error, ProgressId};
+
+        let workdir = self.work_dir().expect("linked worktrees always have a work dir");
+        let index = gix_index::State::from_tree(&tree, &self.objects)
+            .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+        let mut index = gix_index::File::from_state(index, self.index_path());
+
+        let mut opts = self
+            .config
+            .checkout_options(self, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+        opts.destination_is_initially_empty = true;
+
+        let mut files = progress.add_child_with_id("checkout".to_string(), ProgressId::CheckoutFiles.into());
+        let mut bytes = progress.add_child_with_id("writing".to_string(), ProgressId::BytesWritten.into());
+
+        files.init(Some(index.entries().len()), crate::progress::count("files"));
+        bytes.init(None, crate::progress::bytes());
+
+        let start = std::time::Instant::now();
+        let outcome = gix_worktree_state::checkout(
+            &mut index,
+            workdir,
+            self.objects.clone().into_arc()?,
+            &files,
+            &bytes,
+            should_interrupt,
+            opts,
+        )?;
+        files.show_throughput(start);
+        bytes.show_throughput(start);
+
+        index.write(self.index_write_options(&index)?)?;
+        Ok(outcome)
+    }
+
+    /// Fail if the branch with `name` is checked out in the main worktree or in any linked worktree.
+    #[cfg(feature = "worktree-mutation")]
+    fn assure_branch_is_not_checked_out(&self, name: &gix_ref::FullNameRef) -> Result<(), worktree::add::Error> {
+        use crate::bstr::ByteSlice;
+
+        let main_git_dir = self.common_dir().to_owned();
+        let main_path = gix_discover::path::without_dot_git_dir(main_git_dir.clone());
+        let candidates = std::iter::once((main_git_dir, main_path)).chain(self.worktrees()?.into_iter().map(|proxy| {
+            (
+                proxy.git_dir().to_owned(),
+                proxy.base().unwrap_or_else(|_| proxy.git_dir().to_owned()),
+            )
+        }));
+        for (git_dir, path) in candidates {
+            let Ok(head) = std::fs::read(git_dir.join("HEAD")) else {
+                continue;
+            };
+            if head
+                .trim_end()
+                .strip_prefix(b"ref: ")
+                .map_or(false, |referent| referent.trim() == name.as_bstr())
+            {
+                return Err(worktree::add::Error::BranchCheckedOut {
+                    name: name.as_bstr().to_owned(),
+                    path,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// If `id` points to a tree, produce a stream that yields one worktree entry after the other. The index of the tree at `id`
     /// is returned as well as it is an intermediate byproduct that might be useful to callers.
     ///
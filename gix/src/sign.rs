@@ -0,0 +1,162 @@
+//! Create and verify signatures of commits and tags with external signing programs, similar to what `git` does with `gpg.program`.
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The kind of signatures to create, as configured by `gpg.format`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Create OpenPGP signatures with `gpg`.
+    #[default]
+    OpenPgp,
+    /// Create X.509 signatures with `gpgsm`.
+    X509,
+    /// Create SSH signatures with `ssh-keygen`.
+    Ssh,
+}
+
+impl Format {
+    /// Return the name of the program to use if none is configured.
+    pub fn default_program(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "gpg",
+            Format::X509 => "gpgsm",
+            Format::Ssh => "ssh-keygen",
+        }
+    }
+
+    /// Determine the format of `signature` by its armor, or return `None` if it isn't known.
+    pub fn from_signature(signature: &BStr) -> Option<Self> {
+        let signature = signature.trim_start();
+        [
+            (&b"-----BEGIN PGP SIGNATURE-----"[..], Format::OpenPgp),
+            (b"-----BEGIN PGP MESSAGE-----", Format::OpenPgp),
+            (b"-----BEGIN SIGNED MESSAGE-----", Format::X509),
+            (b"-----BEGIN SSH SIGNATURE-----", Format::Ssh),
+        ]
+        .into_iter()
+        .find_map(|(armor, format)| signature.starts_with(armor).then_some(format))
+    }
+
+    /// Return the name of the configuration subsection for this format, like `ssh` in `gpg.ssh.program`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "openpgp",
+            Format::X509 => "x509",
+            Format::Ssh => "ssh",
+        }
+    }
+}
+
+/// The trust placed into the key that made a signature, in ascending order, as configured by `gpg.minTrustLevel`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Trust {
+    /// Nothing is known about the key, which is also the case for SSH keys that aren't in `gpg.ssh.allowedSignersFile`.
+    #[default]
+    Undefined,
+    /// The key must never be trusted.
+    Never,
+    /// The key is marginally trusted.
+    Marginal,
+    /// The key is fully trusted, which is also the case for SSH keys listed in `gpg.ssh.allowedSignersFile`.
+    Fully,
+    /// The key is ultimately trusted, usually because it's one's own key.
+    Ultimate,
+}
+
+impl Trust {
+    /// Return the name of the trust level as used in `gpg.minTrustLevel`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trust::Undefined => "undefined",
+            Trust::Never => "never",
+            Trust::Marginal => "marginal",
+            Trust::Fully => "fully",
+            Trust::Ultimate => "ultimate",
+        }
+    }
+}
+
+/// The outcome of a successful signature verification.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Verification {
+    /// The format of the verified signature.
+    pub format: Format,
+    /// The trust placed into the key that made the signature.
+    pub trust: Trust,
+    /// The identity of the signer, like the user id of an OpenPGP key or the principal of an SSH key,
+    /// if it is known.
+    pub signer: Option<BString>,
+    /// The fingerprint of the key that made the signature, if it could be determined.
+    pub fingerprint: Option<BString>,
+    /// The human-readable output of the verification program.
+    pub output: BString,
+}
+
+///
+pub mod verify {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::verify_signature()`](crate::Repository::verify_signature()) and the
+    /// `verify_signature()` methods of commits and tags.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The object isn't signed")]
+        Unsigned,
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("The signature format isn't known")]
+        UnknownFormat,
+        #[error(transparent)]
+        MinTrustLevel(#[from] crate::config::key::GenericErrorWithValue),
+        #[error("gpg.ssh.allowedSignersFile needs to be configured and exist to verify SSH signatures")]
+        AllowedSignersFileMissing,
+        #[error("Could not interpolate the path to the allowed signers or revocation file")]
+        Path(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not spawn verification program {program:?}")]
+        Spawn {
+            program: std::ffi::OsString,
+            source: std::io::Error,
+        },
+        #[error("Could not communicate with the verification program")]
+        Io(#[from] std::io::Error),
+        #[error("The signature is invalid: {output}")]
+        Invalid { output: BString },
+        #[error("The signature was made by a key with trust level '{}', but at least '{}' is required", .verification.trust.as_str(), .min_trust.as_str())]
+        Untrusted {
+            verification: Box<super::Verification>,
+            min_trust: super::Trust,
+        },
+    }
+}
+
+/// The error returned by [`Repository::sign()`](crate::Repository::sign()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Format(#[from] crate::config::key::GenericErrorWithValue),
+    #[error("A committer is required to sign with the committer identity if user.signingKey isn't set")]
+    CommitterMissing,
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error("Either user.signingKey or gpg.ssh.defaultKeyCommand must be set to create SSH signatures")]
+    SigningKeyMissing,
+    #[error("gpg.ssh.defaultKeyCommand didn't produce a usable key: {stderr}")]
+    DefaultKeyCommand { stderr: BString },
+    #[error("Could not interpolate the path to the signing key")]
+    KeyPath(#[from] gix_config::path::interpolate::Error),
+    #[error("Could not spawn signing program {program:?}")]
+    Spawn {
+        program: std::ffi::OsString,
+        source: std::io::Error,
+    },
+    #[error("Could not communicate with the signing program or read its signature")]
+    Io(#[from] std::io::Error),
+    #[error("Signing was requested, but the 'command' feature to run signing programs is disabled")]
+    Unsupported,
+    #[error("The signing program {program:?} failed to sign the data: {stderr}")]
+    Failed {
+        program: std::ffi::OsString,
+        stderr: BString,
+    },
+}
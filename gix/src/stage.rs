@@ -0,0 +1,91 @@
+//! Update the index with changes in the worktree, similar to `git add` and `git rm`.
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::stage_add()`](crate::Repository::stage_add())
+/// and [`Repository::stage_remove()`](crate::Repository::stage_remove()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Staging changes needs a worktree")]
+    MissingWorktree,
+    #[error("No pathspec was given, refusing to remove all files")]
+    NoPathspec,
+    #[error("The following files have local modifications and won't be removed: {}", paths.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    LocalModifications { paths: Vec<BString> },
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error("Could not expand sparse directory entries")]
+    ExpandIndex(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    Excludes(#[from] crate::config::exclude_stack::Error),
+    #[error(transparent)]
+    Attributes(#[from] crate::config::attribute_stack::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    IndexWriteOptions(#[from] crate::config::index_write_options::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not access the worktree file at '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+///
+pub mod add {
+    /// Options for use in [`Repository::stage_add()`](crate::Repository::stage_add()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, only update or remove entries that are already tracked, but don't add untracked files,
+        /// like `git add --update`.
+        pub update_only: bool,
+        /// If `true`, add untracked files even if they are excluded by `.gitignore` files and similar, like `git add --force`.
+        pub include_ignored: bool,
+    }
+
+    /// The outcome of [`Repository::stage_add()`](crate::Repository::stage_add()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of untracked files that were added to the index.
+        pub entries_added: usize,
+        /// The amount of tracked files whose index entries were updated as their content or mode changed.
+        pub entries_updated: usize,
+        /// The amount of index entries that were removed as their files don't exist in the worktree anymore.
+        pub entries_removed: usize,
+    }
+}
+
+///
+pub mod remove {
+    /// Options for use in [`Repository::stage_remove()`](crate::Repository::stage_remove()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, only remove entries from the index but leave their files in the worktree, like `git rm --cached`.
+        pub cached: bool,
+        /// If `true`, remove files even if they have local modifications, like `git rm --force`.
+        pub force: bool,
+    }
+
+    /// The outcome of [`Repository::stage_remove()`](crate::Repository::stage_remove()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of index entries that were removed, counting each stage of conflicting entries.
+        pub entries_removed: usize,
+        /// The amount of files that were removed from the worktree.
+        pub files_removed: usize,
+    }
+}
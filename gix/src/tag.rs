@@ -11,6 +11,10 @@ mod error {
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error("Could not sign the tag")]
+        Sign(#[from] crate::sign::Error),
     }
 }
 pub use error::Error;
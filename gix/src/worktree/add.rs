@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+
+/// Determine what the `HEAD` of a newly added worktree should point to, along with what to check out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Head {
+    /// Check out an existing local branch, like `git worktree add <path> <branch>`.
+    ///
+    /// This fails if the branch is already checked out in another worktree, unless [`Options::force`] is set.
+    Branch(gix_ref::FullName),
+    /// Create a new branch named `name` pointing to `commit` and check it out, like `git worktree add -b <name> <path> <commit>`.
+    ///
+    /// If `force` is `true`, an existing branch will be reset to `commit`, like `-B <name>` would.
+    NewBranch {
+        /// The full name of the branch to create, like `refs/heads/feature`.
+        name: gix_ref::FullName,
+        /// The commit the new branch should point to.
+        commit: gix_hash::ObjectId,
+        /// If `true`, overwrite a branch that already exists.
+        force: bool,
+    },
+    /// Detach `HEAD` at the given commit, like `git worktree add --detach <path> <commit>`.
+    Detached(gix_hash::ObjectId),
+}
+
+/// Options for use in [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// If `Some(reason)`, keep the new worktree locked with the given `reason`, which may be empty.
+    pub lock: Option<BString>,
+    /// If `true`, don't write any files into the new worktree and leave the index empty, like `--no-checkout`.
+    pub no_checkout: bool,
+    /// If `true`, allow checking out a branch that is already checked out elsewhere, and allow using a `path`
+    /// that is still registered with a worktree whose checkout is missing.
+    pub force: bool,
+}
+
+/// The error returned by [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Worktree path at '{}' already exists and is not an empty directory", path.display())]
+    PathExists { path: PathBuf },
+    #[error("Worktree path at '{}' is registered as worktree '{id}', but is missing - use force to override", path.display())]
+    PathRegistered { path: PathBuf, id: BString },
+    #[error("Branch '{name}' is already checked out at '{}'", path.display())]
+    BranchCheckedOut { name: BString, path: PathBuf },
+    #[error("Branch '{name}' does not exist")]
+    BranchNotFound { name: BString },
+    #[error("Could not create the worktree administrative files")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Open(#[from] crate::open::Error),
+    #[error("The object at {id} could not be peeled to a tree")]
+    PeelToTree {
+        id: gix_hash::ObjectId,
+        source: crate::object::peel::to_kind::Error,
+    },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error("Could not create index from tree at {id}")]
+    IndexFromTree {
+        id: gix_hash::ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    IndexWriteOptions(#[from] crate::config::index_write_options::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    IndexCheckout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    Hooks(#[from] crate::hooks::init::Error),
+    #[error(transparent)]
+    PostCheckoutHook(#[from] crate::hooks::run::Error),
+}
+
+/// The progress ids used in [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of files checked out thus far.
+    CheckoutFiles,
+    /// The amount of bytes written in total, the aggregate of the size of the content of all files thus far.
+    BytesWritten,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::CheckoutFiles => *b"WACF",
+            ProgressId::BytesWritten => *b"WACB",
+        }
+    }
+}
+
+/// Turn the last component of `path` into a name suitable for use as directory in `.git/worktrees/`, similar to
+/// what git does when sanitizing ref-name components.
+pub(crate) fn id_from_path(path: &std::path::Path) -> BString {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let mut out = BString::default();
+    let mut last = 0u8;
+    for b in name.bytes() {
+        let b = match b {
+            b'.' if out.is_empty() => continue,
+            b'.' if last == b'.' => continue,
+            b'/' if last == b'/' => continue,
+            b'\0'..=b' ' | 0x7f | b'~' | b'^' | b':' | b'?' | b'[' | b'\\' | b'*' | b'/' => b'-',
+            b'{' if last == b'@' => b'-',
+            b => b,
+        };
+        out.push(b);
+        last = b;
+    }
+    while out.ends_with(b".lock") {
+        let new_len = out.len() - ".lock".len();
+        out.truncate(new_len);
+    }
+    while out.last() == Some(&b'.') {
+        out.pop();
+    }
+    if out.is_empty() {
+        out = "worktree".into();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::id_from_path;
+
+    #[test]
+    fn id_is_sanitized_like_git_does() {
+        for (input, expected) in [
+            ("wt", "wt"),
+            ("a/b/wt-a", "wt-a"),
+            (".hidden", "hidden"),
+            ("with space", "with-space"),
+            ("a..b", "a.b"),
+            ("x:y*z", "x-y-z"),
+            ("name.lock", "name"),
+            ("trailing.", "trailing"),
+            ("...", "worktree"),
+        ] {
+            assert_eq!(id_from_path(std::path::Path::new(input)), expected, "{input}");
+        }
+    }
+}
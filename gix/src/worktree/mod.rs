@@ -85,6 +85,17 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod add;
+
+///
+pub mod prune;
+
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod sparse;
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use crate::{
     bstr::{BStr, BString, ByteSlice},
-    worktree::Proxy,
+    worktree::{prune, Proxy},
     Repository, ThreadSafeRepository,
 };
 
@@ -24,6 +24,114 @@ pub mod into_repo {
     }
 }
 
+///
+pub mod lock {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::lock()`][super::Proxy::lock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree is already locked, reason: '{reason}'")]
+        AlreadyLocked { reason: BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::move_to()`][super::Proxy::move_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot move a locked worktree, lock reason: '{reason}'")]
+        Locked { reason: BString },
+        #[error("Worktree at '{}' is inaccessible", .base.display())]
+        MissingWorktree { base: PathBuf },
+        #[error("Target '{}' already exists", .path.display())]
+        DestinationExists { path: PathBuf },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "status", feature = "attributes"))]
+pub mod remove {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// Options for use in [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, remove the worktree even if it has modifications, like `git worktree remove --force`.
+        pub force: bool,
+        /// If `true`, remove the worktree even if it is locked, like `git worktree remove --force --force`.
+        pub force_locked: bool,
+    }
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot remove a locked worktree, lock reason: '{reason}'")]
+        Locked { reason: BString },
+        #[error("Worktree at '{}' contains modified files, use force to delete it", .base.display())]
+        Modified { base: PathBuf },
+        #[error(transparent)]
+        OpenRepo(#[from] crate::open::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Pathspec(#[from] crate::pathspec::init::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        Status(#[from] gix_status::index_as_worktree::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod repair {
+    /// The kind of repair that was performed by [`Proxy::repair()`][super::Proxy::repair()] or
+    /// [`Repository::worktree_repair()`](crate::Repository::worktree_repair()).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Reason {
+        /// The `.git` file in the worktree was missing and was recreated.
+        DotGitFileMissing,
+        /// The `.git` file in the worktree couldn't be parsed and was rewritten.
+        DotGitFileBroken,
+        /// The `.git` file in the worktree pointed to the wrong administrative directory and was rewritten.
+        DotGitFileIncorrect,
+        /// The `gitdir` file in the administrative directory didn't point to the worktree and was rewritten.
+        GitDirFileIncorrect,
+    }
+
+    /// The error returned by [`Repository::worktree_repair()`](crate::Repository::worktree_repair()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'{}' is not a linked worktree of this repository", .path.display())]
+        NotALinkedWorktree { path: std::path::PathBuf },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -74,6 +182,149 @@ impl<'repo> Proxy<'repo> {
             .map(|contents| contents.trim().into())
     }
 
+    /// Return `Some(reason)` if this worktree is stale and should be pruned, or `None` if it is valid or locked.
+    ///
+    /// If `expire` is set, worktrees whose `gitdir` file was modified after this time are never considered prunable
+    /// even if their checkout doesn't exist, to give them a chance to be moved back into place.
+    pub fn prunable(&self, expire: Option<std::time::SystemTime>) -> Option<prune::Reason> {
+        if !self.git_dir.is_dir() {
+            return Some(prune::Reason::NotADirectory);
+        }
+        if self.is_locked() {
+            return None;
+        }
+        let gitdir_file = self.git_dir.join("gitdir");
+        let dot_git = match gix_discover::path::from_plain_file(&gitdir_file) {
+            None => return Some(prune::Reason::GitDirFileMissing),
+            Some(Err(_)) => return Some(prune::Reason::GitDirFileUnreadable),
+            Some(Ok(path)) if path.as_os_str().is_empty() => return Some(prune::Reason::GitDirFileInvalid),
+            Some(Ok(path)) => self.git_dir.join(path),
+        };
+        if dot_git.exists() {
+            return None;
+        }
+        if let Some(expire) = expire {
+            let modified = std::fs::metadata(&gitdir_file).and_then(|md| md.modified());
+            if modified.map_or(false, |modified| modified > expire) {
+                return None;
+            }
+        }
+        Some(prune::Reason::GitDirFilePointsToNonExistentLocation)
+    }
+}
+
+/// Mutation
+impl<'repo> Proxy<'repo> {
+    /// Lock this worktree to prevent it from being pruned, moved or deleted, and record the optional `reason` for it.
+    ///
+    /// Fails if the worktree is already locked.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), lock::Error> {
+        if let Some(reason) = self.lock_reason() {
+            return Err(lock::Error::AlreadyLocked { reason });
+        }
+        std::fs::write(
+            self.git_dir.join("locked"),
+            reason.map(|r| r.as_bytes()).unwrap_or_default(),
+        )?;
+        Ok(())
+    }
+
+    /// Unlock this worktree and return `true` if it was locked, or `false` if it wasn't locked in the first place.
+    pub fn unlock(&self) -> std::io::Result<bool> {
+        match std::fs::remove_file(self.git_dir.join("locked")) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Move the checkout of this worktree to `destination` and update the administrative files to point to it.
+    ///
+    /// If `destination` is an existing directory, the worktree will be moved into it while retaining its current name.
+    /// Note that locked worktrees can't be moved.
+    pub fn move_to(&self, destination: impl AsRef<Path>) -> Result<(), move_to::Error> {
+        if let Some(reason) = self.lock_reason() {
+            return Err(move_to::Error::Locked { reason });
+        }
+        let base = self.base()?;
+        if !base.is_dir() {
+            return Err(move_to::Error::MissingWorktree { base });
+        }
+        let mut destination = destination.as_ref().to_owned();
+        if destination.is_dir() {
+            if let Some(name) = base.file_name() {
+                destination.push(name);
+            }
+        }
+        if destination.symlink_metadata().is_ok() {
+            return Err(move_to::Error::DestinationExists { path: destination });
+        }
+        std::fs::rename(&base, &destination)?;
+        write_gitdir_file(&self.git_dir, &destination)?;
+        Ok(())
+    }
+
+    /// Delete the checkout of this worktree along with its administrative files, configured by `options`.
+    ///
+    /// Unless forced by `options`, worktrees that are locked or have modifications to tracked files can't be removed.
+    /// If the checkout doesn't exist anymore, only the administrative files are removed.
+    ///
+    /// ### Deviation
+    ///
+    /// Untracked files don't prevent the removal of a worktree, whereas `git` would refuse to remove it without force.
+    #[cfg(all(feature = "status", feature = "attributes"))]
+    pub fn remove(self, options: remove::Options) -> Result<(), remove::Error> {
+        if let Some(reason) = self.lock_reason() {
+            if !options.force_locked {
+                return Err(remove::Error::Locked { reason });
+            }
+        }
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = &base {
+            if !options.force {
+                let repo: Repository = ThreadSafeRepository::open_from_paths(
+                    self.git_dir.clone(),
+                    Some(base.clone()),
+                    self.parent.options.clone(),
+                )?
+                .into();
+                if has_modifications(&repo)? {
+                    return Err(remove::Error::Modified { base: base.clone() });
+                }
+            }
+            std::fs::remove_dir_all(base)?;
+        }
+        std::fs::remove_dir_all(&self.git_dir)?;
+        remove_worktrees_dir_if_empty(self.parent.common_dir());
+        Ok(())
+    }
+
+    /// Assure the `.git` file of this worktree's checkout points back to our administrative directory, and rewrite it if
+    /// that's not the case.
+    ///
+    /// Returns `None` if nothing had to be repaired, or if the worktree checkout doesn't exist.
+    pub fn repair(&self) -> std::io::Result<Option<repair::Reason>> {
+        let Some(base) = self.base().ok().filter(|base| base.is_dir()) else {
+            return Ok(None);
+        };
+        let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+        let reason = match gix_discover::path::from_gitdir_file(&dot_git) {
+            Ok(git_dir) => {
+                if same_path(&git_dir, &self.git_dir) {
+                    return Ok(None);
+                }
+                repair::Reason::DotGitFileIncorrect
+            }
+            Err(gix_discover::path::from_gitdir_file::Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                repair::Reason::DotGitFileMissing
+            }
+            Err(gix_discover::path::from_gitdir_file::Error::Io(err)) if dot_git.is_dir() => return Err(err),
+            Err(_) => repair::Reason::DotGitFileBroken,
+        };
+        write_dot_git_file(&base, &self.git_dir)?;
+        Ok(Some(reason))
+    }
+
     /// Transform this proxy into a [`Repository`] while ignoring issues reading `base()` and ignoring that it might not exist.
     ///
     /// Most importantly, the `Repository` might be initialized with a non-existing work tree directory as the checkout
@@ -99,3 +350,96 @@ impl<'repo> Proxy<'repo> {
         Ok(repo.into())
     }
 }
+
+/// Write the `gitdir` file within the administrative `git_dir` of a worktree to point to the `.git` file in `base`.
+pub(crate) fn write_gitdir_file(git_dir: &Path, base: &Path) -> std::io::Result<()> {
+    let dot_git = absolute(base)?.join(gix_discover::DOT_GIT_DIR);
+    let mut buf: Vec<u8> = gix_path::into_bstr(dot_git).into_owned().into();
+    buf.push(b'\n');
+    std::fs::write(git_dir.join("gitdir"), buf)
+}
+
+/// Write the `.git` file into `base` to point to the administrative `git_dir` of the worktree.
+pub(crate) fn write_dot_git_file(base: &Path, git_dir: &Path) -> std::io::Result<()> {
+    let mut buf: Vec<u8> = b"gitdir: ".to_vec();
+    buf.extend_from_slice(&gix_path::into_bstr(absolute(git_dir)?));
+    buf.push(b'\n');
+    std::fs::write(base.join(gix_discover::DOT_GIT_DIR), buf)
+}
+
+/// Return `true` if any tracked file in the worktree of `repo` differs from what's in its index.
+#[cfg(all(feature = "status", feature = "attributes"))]
+fn has_modifications(repo: &Repository) -> Result<bool, remove::Error> {
+    use gix_status::index_as_worktree::{traits::FastEq, EntryStatus, Recorder};
+
+    #[derive(Clone)]
+    struct IgnoreSubmodules;
+
+    impl gix_status::index_as_worktree::traits::SubmoduleStatus for IgnoreSubmodules {
+        type Output = ();
+        type Error = std::convert::Infallible;
+
+        fn status(
+            &mut self,
+            _entry: &gix_index::Entry,
+            _rela_path: &BStr,
+        ) -> Result<Option<Self::Output>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    let Some(work_dir) = repo.work_dir() else {
+        return Ok(false);
+    };
+    let index = repo.index()?;
+    let source = gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping;
+    let pathspec = repo.pathspec(None::<&str>, false, &index, source)?;
+    let attributes = match repo.attributes_only(&index, source)?.detach().state_mut() {
+        gix_worktree::stack::State::AttributesStack(attributes) => std::mem::take(attributes),
+        _ => unreachable!("state must be attributes stack only"),
+    };
+    let filter = gix_filter::Pipeline::new(
+        repo.command_context()
+            .map_err(crate::filter::pipeline::options::Error::from)?,
+        crate::filter::Pipeline::options(repo)?,
+    );
+    let mut recorder = Recorder::default();
+    gix_status::index_as_worktree(
+        &index,
+        work_dir,
+        &mut recorder,
+        FastEq,
+        IgnoreSubmodules,
+        repo.objects.clone().into_arc()?,
+        &mut gix_features::progress::Discard,
+        pathspec.detach()?,
+        filter,
+        &std::sync::atomic::AtomicBool::default(),
+        gix_status::index_as_worktree::Options {
+            fs: repo.filesystem_options()?,
+            thread_limit: None,
+            stat: repo.stat_options()?,
+            attributes,
+        },
+    )?;
+    Ok(recorder
+        .records
+        .iter()
+        .any(|record| !matches!(record.status, EntryStatus::NeedsUpdate(_))))
+}
+
+/// Delete the `worktrees` directory in `common_dir` if it is empty, ignoring all errors.
+pub(crate) fn remove_worktrees_dir_if_empty(common_dir: &Path) {
+    std::fs::remove_dir(common_dir.join("worktrees")).ok();
+}
+
+pub(crate) fn absolute(path: &Path) -> std::io::Result<PathBuf> {
+    gix_path::realpath(path).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+}
+
+pub(crate) fn same_path(a: &Path, b: &Path) -> bool {
+    match (absolute(a), absolute(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -0,0 +1,48 @@
+use crate::bstr::BString;
+
+/// The reason for a linked worktree to be considered prunable, as obtained by [`Proxy::prunable()`](crate::worktree::Proxy::prunable()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The administrative directory in `.git/worktrees/` isn't a directory.
+    NotADirectory,
+    /// The `gitdir` file within the administrative directory does not exist.
+    GitDirFileMissing,
+    /// The `gitdir` file could not be read.
+    GitDirFileUnreadable,
+    /// The `gitdir` file is empty.
+    GitDirFileInvalid,
+    /// The `gitdir` file points to a location that doesn't exist.
+    GitDirFilePointsToNonExistentLocation,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Reason::NotADirectory => "not a valid directory",
+            Reason::GitDirFileMissing => "gitdir file does not exist",
+            Reason::GitDirFileUnreadable => "unable to read gitdir file",
+            Reason::GitDirFileInvalid => "invalid gitdir file",
+            Reason::GitDirFilePointsToNonExistentLocation => "gitdir file points to non-existent location",
+        })
+    }
+}
+
+/// Options for use in [`Repository::worktree_prune()`](crate::Repository::worktree_prune()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Only prune worktrees whose `gitdir` file wasn't modified after the given time.
+    ///
+    /// If `None`, all prunable worktrees are pruned no matter their age, like `git worktree prune` does by default.
+    pub expire: Option<std::time::SystemTime>,
+    /// If `true`, don't actually delete anything but report what would be pruned.
+    pub dry_run: bool,
+}
+
+/// Information about a worktree that was pruned by [`Repository::worktree_prune()`](crate::Repository::worktree_prune()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pruned {
+    /// The id of the worktree, which is the name of its directory in `.git/worktrees/`.
+    pub id: BString,
+    /// The reason for the worktree to be pruned.
+    pub reason: Reason,
+}
@@ -0,0 +1,129 @@
+pub use gix_worktree_state::sparse::{Cone, Patterns};
+
+///
+pub mod patterns {
+    /// The error returned by [`Repository::sparse_checkout_patterns()`](crate::Repository::sparse_checkout_patterns()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error("Could not read sparse-checkout patterns from '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod reapply {
+    use crate::bstr::BString;
+
+    /// The outcome of [`Repository::sparse_checkout_reapply()`](crate::Repository::sparse_checkout_reapply()).
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of files that were excluded by the patterns and thus removed from the worktree.
+        pub files_removed: usize,
+        /// The amount of files that are now included by the patterns and were written to the worktree.
+        pub files_checked_out: usize,
+        /// Paths to files that are excluded by the patterns but were left in the worktree as they are modified.
+        ///
+        /// Their index entries are not marked to skip the worktree.
+        pub modified_files_kept: Vec<BString>,
+        /// Paths to files that are included by the patterns but weren't checked out as a different file is already present
+        /// in the worktree.
+        ///
+        /// Their index entries remain marked to skip the worktree.
+        pub existing_files_kept: Vec<BString>,
+    }
+
+    /// The error returned by [`Repository::sparse_checkout_reapply()`](crate::Repository::sparse_checkout_reapply()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Sparse checkouts need a worktree")]
+        MissingWorktree,
+        #[error("Sparse checkout isn't enabled, set 'core.sparseCheckout' and provide patterns first")]
+        NotEnabled,
+        #[error(transparent)]
+        Patterns(#[from] super::patterns::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error("Could not expand sparse directory entries")]
+        ExpandIndex(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error("Could not obtain a thread-safe handle to the object database")]
+        ObjectsIntoArc(#[source] std::io::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        IndexWriteOptions(#[from] crate::config::index_write_options::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error("Could not update the worktree file at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod set {
+    /// Options for use in [`Repository::sparse_checkout_set()`](crate::Repository::sparse_checkout_set()).
+    #[derive(Debug, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, the default, patterns are directories to include recursively, like `git sparse-checkout set --cone`.
+        ///
+        /// Otherwise, patterns are like the ones found in `.gitignore` files, with matching paths being included.
+        pub cone: bool,
+        /// If `true`, directories outside of the cone are stored as sparse directory entries in the index,
+        /// like `--sparse-index` would. Only effective in cone mode.
+        pub sparse_index: bool,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                cone: true,
+                sparse_index: false,
+            }
+        }
+    }
+
+    /// The error returned by [`Repository::sparse_checkout_set()`](crate::Repository::sparse_checkout_set())
+    /// and [`Repository::sparse_checkout_add()`](crate::Repository::sparse_checkout_add()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Sparse checkout isn't enabled, there are no patterns to add to")]
+        NotEnabled,
+        #[error(transparent)]
+        Patterns(#[from] super::patterns::Error),
+        #[error("Could not write the sparse-checkout file at '{}'", path.display())]
+        WritePatterns {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetConfigValue(#[from] gix_config::file::set_raw_value::Error),
+        #[error(transparent)]
+        SetValue(#[from] crate::config::set_value::Error),
+        #[error(transparent)]
+        ApplyConfig(#[from] crate::config::Error),
+        #[error("Could not write the repository configuration file")]
+        WriteConfig(#[from] std::io::Error),
+        #[error(transparent)]
+        LockConfig(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        Reapply(#[from] super::reapply::Error),
+    }
+}
@@ -582,6 +582,253 @@ mod blocking_io {
         }
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "bundle")]
+    fn fetch_only_from_bundle() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            fixture.join("all.bundle"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        match out.status {
+            gix::remote::fetch::Status::Change { write_pack_bundle, .. } => {
+                assert_eq!(write_pack_bundle.index.num_objects, 11);
+                assert!(write_pack_bundle.keep_path.is_none());
+            }
+            _ => unreachable!("a clone always carries a change"),
+        }
+
+        let base = gix::open_opts(fixture.join("base"), restricted())?;
+        let expected = base.head_id()?.detach();
+        assert_eq!(repo.head_id()?, expected);
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "HEAD in the bundle is resolved to the branch it points to"
+        );
+        assert_eq!(repo.find_reference("refs/remotes/origin/main")?.id(), expected);
+        assert_eq!(
+            repo.find_reference("refs/tags/v1")?.id(),
+            base.find_reference("refs/tags/v1")?.id()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bundle")]
+    fn shallow_from_bundle_is_unsupported() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::prepare_clone_bare(fixture.join("all.bundle"), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(1.try_into().expect("non-zero")))
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+            .unwrap_err();
+        assert!(matches!(err, gix::clone::fetch::Error::ShallowBundle));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bundle")]
+    fn bundle_with_different_object_hash_is_an_error() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::prepare_clone_bare(fixture.join("sha256.bundle"), tmp.path())?
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::FetchBundle(gix::remote::fetch::bundle::Error::Open(_))
+            ),
+            "the object format of the bundle is checked before fetching, and sha256 isn't supported yet: {err:?}"
+        );
+        Ok(())
+    }
+
+    mod partial {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::remote::fetch::Filter;
+
+        use crate::util::restricted;
+
+        /// Return a writable copy of the bundle fixture whose `base` repository can serve partial clones.
+        fn fixture_with_filter_support() -> crate::Result<gix_testtools::tempfile::TempDir> {
+            let fixture = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+            let base = fixture.path().join("base");
+            for (key, value) in [
+                ("uploadpack.allowFilter", "true"),
+                ("uploadpack.allowAnySHA1InWant", "true"),
+            ] {
+                assert!(gix_testtools::run_git(&base, &["config", key, value])?.success());
+            }
+            Ok(fixture)
+        }
+
+        fn url(fixture: &gix_testtools::tempfile::TempDir) -> String {
+            format!("file://{}", fixture.path().join("base").display())
+        }
+
+        fn num_packs(repo: &gix::Repository, extension: &str) -> crate::Result<usize> {
+            Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().map_or(false, |ext| ext == extension))
+                .count())
+        }
+
+        #[test]
+        fn blob_none_then_lazy_fetch_on_access() -> crate::Result {
+            let fixture = fixture_with_filter_support()?;
+            let base = gix::open_opts(fixture.path().join("base"), restricted())?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = gix::clone::PrepareFetch::new(
+                url(&fixture).as_str(),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted(),
+            )?
+            .with_filter(Filter::BlobNone)
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+            match out.status {
+                gix::remote::fetch::Status::Change { write_pack_bundle, .. } => {
+                    assert_eq!(
+                        write_pack_bundle.index.num_objects, 8,
+                        "3 commits, 4 trees and one annotated tag, but no blob"
+                    );
+                    let promisor = write_pack_bundle
+                        .data_path
+                        .expect("pack written")
+                        .with_extension("promisor");
+                    let content = std::fs::read_to_string(promisor)?;
+                    assert!(
+                        content.contains(" refs/heads/main\n"),
+                        "the promisor file lists the refs that were fetched: {content}"
+                    );
+                }
+                _ => unreachable!("a clone always carries a change"),
+            }
+
+            let config = repo.config_snapshot();
+            assert_eq!(
+                config.string("extensions.partialClone").expect("set").as_ref(),
+                "origin"
+            );
+            assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+            assert_eq!(
+                config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+                "blob:none"
+            );
+            assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+            assert_eq!(repo.promisor_remote_name().expect("set").as_ref(), "origin");
+            assert!(repo.is_promisor_remote("origin".into())?);
+
+            let blob = base.rev_parse_single("main:dir/c")?.detach();
+            assert!(!repo.has_object(blob), "blobs weren't received");
+            assert_eq!(num_packs(&repo, "pack")?, 1);
+            assert_eq!(
+                repo.find_object(blob)?.data.as_slice(),
+                b"c\n",
+                "missing objects are fetched from the promisor remote on access"
+            );
+            assert!(repo.has_object(blob));
+            assert_eq!(num_packs(&repo, "pack")?, 2, "each lazy fetch receives a pack");
+            assert_eq!(
+                num_packs(&repo, "promisor")?,
+                2,
+                "which is marked as obtained from the promisor"
+            );
+            assert_eq!(num_packs(&repo, "keep")?, 0);
+
+            assert_eq!(
+                repo.prefetch_objects([blob])?,
+                0,
+                "objects that are present don't need fetching"
+            );
+            let blobs = ["main:a", "main:b"]
+                .iter()
+                .map(|spec| base.rev_parse_single(*spec).map(gix::Id::detach))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(repo.prefetch_objects(blobs.iter().copied())?, 2);
+            assert!(blobs.iter().all(|id| repo.has_object(id)));
+            assert_eq!(num_packs(&repo, "pack")?, 3, "multiple objects are obtained at once");
+            Ok(())
+        }
+
+        #[test]
+        fn blob_none_then_checkout_prefetches_blobs() -> crate::Result {
+            let fixture = fixture_with_filter_support()?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = gix::clone::PrepareFetch::new(
+                url(&fixture).as_str(),
+                tmp.path(),
+                gix::create::Kind::WithWorktree,
+                Default::default(),
+                restricted(),
+            )?
+            .with_filter(Filter::BlobNone);
+            let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+            let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+            let work_dir = repo.work_dir().expect("non-bare");
+            for (path, content) in [("a", "a\n"), ("b", "b\n"), ("dir/c", "c\n")] {
+                assert_eq!(std::fs::read_to_string(work_dir.join(path))?, content);
+            }
+            assert_eq!(
+                num_packs(&repo, "pack")?,
+                2,
+                "all blobs for the checkout were obtained in one go"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn filter_is_rejected_if_unsupported_by_server() -> crate::Result {
+            let fixture = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let err = gix::clone::PrepareFetch::new(
+                fixture.join("base"),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted(),
+            )?
+            .with_filter(Filter::BlobLimit(1))
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())
+            .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::MissingServerFeature {
+                        feature: "filter",
+                        ..
+                    })
+                ),
+                "the server must be configured to allow filters: {err}"
+            );
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "bundle")]
+        fn from_bundle_is_unsupported() -> crate::Result {
+            let fixture = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let err = gix::prepare_clone_bare(fixture.join("all.bundle"), tmp.path())?
+                .with_filter(Filter::BlobNone)
+                .fetch_only(gix::progress::Discard, &AtomicBool::default())
+                .unwrap_err();
+            assert!(matches!(err, gix::clone::fetch::Error::PartialBundle));
+            Ok(())
+        }
+    }
 }
 
 #[test]
@@ -1 +1,2 @@
 mod tree;
+mod url_match;
@@ -145,6 +145,48 @@ mod ssh {
     }
 }
 
+mod gpg {
+    use gix::config::tree::{Gpg, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn format() -> crate::Result {
+        for (actual, expected) in [
+            ("openpgp", gix::sign::Format::OpenPgp),
+            ("x509", gix::sign::Format::X509),
+            ("ssh", gix::sign::Format::Ssh),
+        ] {
+            assert_eq!(Gpg::FORMAT.try_into_format(bcow(actual))?, expected);
+            assert!(Gpg::FORMAT.validate(actual.into()).is_ok());
+        }
+
+        assert_eq!(
+            Gpg::FORMAT.try_into_format(bcow("SSH")).unwrap_err().to_string(),
+            "The key \"gpg.format=SSH\" was invalid",
+            "case-sensitive comparisons"
+        );
+        assert!(Gpg::FORMAT.validate("gpg".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn min_trust_level() -> crate::Result {
+        for (actual, expected) in [
+            ("undefined", gix::sign::Trust::Undefined),
+            ("never", gix::sign::Trust::Never),
+            ("marginal", gix::sign::Trust::Marginal),
+            ("fully", gix::sign::Trust::Fully),
+            ("ultimate", gix::sign::Trust::Ultimate),
+        ] {
+            assert_eq!(Gpg::MIN_TRUST_LEVEL.try_into_trust(bcow(actual))?, expected);
+            assert_eq!(expected.as_str(), actual);
+        }
+        assert!(Gpg::MIN_TRUST_LEVEL.validate("full".into()).is_err());
+        Ok(())
+    }
+}
+
 mod push {
     use crate::config::tree::bcow;
     use gix::config::tree::Push;
@@ -618,6 +660,111 @@ mod index {
             "The key \"index.threads=nothing\" was invalid"
         );
     }
+
+    #[test]
+    #[cfg(feature = "index")]
+    fn version() {
+        for (value, expected) in [
+            (2, gix_index::Version::V2),
+            (3, gix_index::Version::V3),
+            (4, gix_index::Version::V4),
+        ] {
+            assert_eq!(Index::VERSION.try_into_index_version(Ok(value)).unwrap(), expected);
+            assert!(Index::VERSION.validate(value.to_string().as_str().into()).is_ok());
+        }
+        for invalid in [1, 5] {
+            assert_eq!(
+                Index::VERSION
+                    .try_into_index_version(Ok(invalid))
+                    .unwrap_err()
+                    .to_string(),
+                format!("The key \"index.version={invalid}\" was invalid")
+            );
+            assert!(Index::VERSION.validate(invalid.to_string().as_str().into()).is_err());
+        }
+    }
+}
+
+mod split_index {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Key, SplitIndex};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn shared_index_expire() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(60 * 60 * 24 * 365);
+        for (value, expected) in [
+            ("never", None),
+            ("false", None),
+            ("now", Some(now)),
+            ("2.weeks.ago", Some(now - Duration::from_secs(60 * 60 * 24 * 14))),
+            ("1 day ago", Some(now - Duration::from_secs(60 * 60 * 24))),
+        ] {
+            assert_eq!(
+                SplitIndex::SHARED_INDEX_EXPIRE
+                    .try_into_expiry(bcow(value), now)
+                    .unwrap(),
+                expected,
+                "{value}"
+            );
+            assert!(SplitIndex::SHARED_INDEX_EXPIRE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            SplitIndex::SHARED_INDEX_EXPIRE
+                .try_into_expiry(bcow("whenever"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"splitIndex.sharedIndexExpire=whenever\" was invalid"
+        );
+    }
+}
+
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn prune_expire() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(60 * 60 * 24 * 365);
+        for (value, expected) in [
+            ("never", None),
+            ("now", Some(now)),
+            ("2.weeks.ago", Some(now - Duration::from_secs(60 * 60 * 24 * 14))),
+        ] {
+            assert_eq!(
+                Gc::PRUNE_EXPIRE.try_into_expiry(bcow(value), now).unwrap(),
+                expected,
+                "{value}"
+            );
+            assert!(Gc::PRUNE_EXPIRE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expiry(bcow("whenever"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=whenever\" was invalid"
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("whenever".into()).is_err());
+    }
+
+    #[test]
+    fn reflog_expire() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(60 * 60 * 24 * 365);
+        for key in [&Gc::REFLOG_EXPIRE, &Gc::REFLOG_EXPIRE_UNREACHABLE] {
+            assert_eq!(
+                key.try_into_expiry(bcow("90.days.ago"), now).unwrap(),
+                Some(now - Duration::from_secs(60 * 60 * 24 * 90))
+            );
+            assert_eq!(key.try_into_expiry(bcow("never"), now).unwrap(), None);
+            assert!(key.validate("whenever".into()).is_err());
+        }
+    }
 }
 
 mod extensions {
@@ -1079,4 +1226,44 @@ mod remote {
             "The refspec at \"remote.<name>.push=*/*/*\" could not be parsed"
         );
     }
+
+    #[test]
+    fn partial_clone_filter() -> crate::Result {
+        for (spec, expected) in [
+            ("blob:none", remote::fetch::Filter::BlobNone),
+            ("blob:limit=0", remote::fetch::Filter::BlobLimit(0)),
+            ("blob:limit=1k", remote::fetch::Filter::BlobLimit(1024)),
+            ("blob:limit=2M", remote::fetch::Filter::BlobLimit(2 * 1024 * 1024)),
+            ("tree:1", remote::fetch::Filter::TreeDepth(1)),
+            (
+                "sparse:oid=main:.sparse",
+                remote::fetch::Filter::SparseOid("main:.sparse".into()),
+            ),
+        ] {
+            let actual = Remote::PARTIAL_CLONE_FILTER.try_into_filter(bcow(spec))?;
+            assert_eq!(actual, expected);
+            assert!(Remote::PARTIAL_CLONE_FILTER.validate(spec.into()).is_ok());
+        }
+        assert_eq!(
+            remote::fetch::Filter::BlobLimit(1024).to_string(),
+            "blob:limit=1024",
+            "sizes are always written in bytes"
+        );
+        assert_eq!(
+            remote::fetch::Filter::SparseOid("main:.sparse".into()).to_string(),
+            "sparse:oid=main:.sparse"
+        );
+
+        for invalid in ["blob:limit=", "blob:limit=1x", "tree:-1", "object:type=blob", ""] {
+            assert!(Remote::PARTIAL_CLONE_FILTER.validate(invalid.into()).is_err());
+        }
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER
+                .try_into_filter(bcow("combine:blob:none+tree:1"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"remote.<name>.partialCloneFilter=combine:blob:none+tree:1\" was invalid"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,68 @@
+use gix::config::url_match::{match_url, Match};
+
+fn try_match(pattern: &str, url: &str) -> Option<Match> {
+    match_url(
+        &gix::url::parse(pattern.into()).expect("valid pattern"),
+        &gix::url::parse(url.into()).expect("valid url"),
+    )
+}
+
+#[test]
+fn scheme_host_and_port_must_match() {
+    assert!(try_match("https://example.com", "https://example.com/repo").is_some());
+    assert!(try_match("https://example.com", "http://example.com/repo").is_none());
+    assert!(try_match("https://example.com", "https://example.org/repo").is_none());
+    assert!(try_match("https://example.com", "https://example.com:8080/repo").is_none());
+    assert!(try_match("https://example.com:443", "https://example.com/repo").is_some());
+    assert!(try_match("https://EXAMPLE.com", "https://example.COM").is_some());
+}
+
+#[test]
+fn hosts_match_wildcards_per_component() {
+    assert!(try_match("https://*.example.com", "https://a.example.com").is_some());
+    assert!(try_match("https://*.example.com", "https://a.b.example.com").is_none());
+    assert!(try_match("https://*.example.com", "https://example.com").is_none());
+    assert!(try_match("https://*.*.com", "https://a.example.com").is_some());
+    assert!(
+        try_match("https://host.example.com", "https://host.example.com")
+            > try_match("https://*.example.com", "https://host.example.com"),
+        "longer host patterns are more specific"
+    );
+}
+
+#[test]
+fn paths_match_as_prefix_along_component_boundaries() {
+    assert!(try_match("https://example.com/org", "https://example.com/org").is_some());
+    assert!(try_match("https://example.com/org/", "https://example.com/org").is_some());
+    assert!(try_match("https://example.com/org", "https://example.com/org/repo").is_some());
+    assert!(try_match("https://example.com/org", "https://example.com/organization").is_none());
+    assert!(try_match("https://example.com/org/repo", "https://example.com/org").is_none());
+    assert!(try_match("https://example.com/ORG", "https://example.com/org").is_none());
+    assert!(
+        try_match("https://example.com/org", "https://example.com/org/repo")
+            > try_match("https://example.com/", "https://example.com/org/repo"),
+        "longer paths are more specific"
+    );
+}
+
+#[test]
+fn users_must_match_if_specified() {
+    assert!(try_match("https://user@example.com", "https://user@example.com").is_some());
+    assert!(try_match("https://user@example.com", "https://example.com").is_none());
+    assert!(try_match("https://user@example.com", "https://other@example.com").is_none());
+    assert!(try_match("https://example.com", "https://user@example.com").is_some());
+}
+
+#[test]
+fn host_is_more_significant_than_path_which_is_more_significant_than_user() {
+    let url = "https://user@host.example.com/org/repo";
+    let by_host = try_match("https://host.example.com", url);
+    let by_path = try_match("https://*.example.com/org/repo", url);
+    let by_user = try_match("https://user@*.example.com/org", url);
+    assert!(by_host > by_path);
+    assert!(by_path > by_user);
+    assert!(
+        try_match("https://user@*.example.com/org", url) > try_match("https://*.example.com/org", url),
+        "with everything else being equal, the user tips the balance"
+    );
+}
@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  echo a > a
+  git add a && git commit -q -m first
+  git tag -m "first tag" v1
+  echo b > b
+  git add b && git commit -q -m second
+)
+
+git clone -q base local
+(cd local && git bundle create -q ../second.bundle main)
+
+(cd base
+  mkdir dir && echo c > dir/c
+  git add dir && git commit -q -m third
+  git bundle create -q ../all.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main
+
+  mkdir ../served
+  git rev-parse main:dir/c > ../served/blob
+  git pack-objects -q ../served/pack < ../served/blob > ../served/pack-hash
+)
+
+git init -q --object-format=sha256 sha256
+(cd sha256
+  echo a > a
+  git add a && git commit -q -m first
+  git bundle create -q ../sha256.bundle --all
+)
@@ -64,11 +64,28 @@ git init http-proxy-empty
   git config --add http.proxy "" # a value override disabling it later
 )
 
+git init http-url-match
+(cd http-url-match
+  git config http.proxy http://localhost:9090
+  git config http.extraHeader "ExtraHeader: all"
+  git config 'http.https://*.example.com.proxy' http://wildcard-proxy
+  git config 'http.https://host.example.com.proxy' http://host-proxy
+  git config 'http.https://example.com/org.extraHeader' "ExtraHeader: org"
+  git config 'http.https://example.com/org/repo.sslVerify' false
+  git config 'http.https://example.com/organization.lowSpeedTime' 42
+  git config 'http.https://user@example.com.userAgent' agentForUser
+  git config 'http.https://example.com:8080.followRedirects' false
+  git config 'http.https://example.com.followRedirects' initial
+  git config 'http.https://example.com.proxy' http://less-specific-but-later
+  git config 'http.https://example.com/org.proxy' http://org-proxy
+)
+
 git init ssh-all-options
 (cd ssh-all-options
   git config ssh.variant ssh
   git config core.sshCommand "ssh -VVV"
   git config gitoxide.ssh.commandWithoutShellFallback "does not matter as it is a fallback"
+  git config gitoxide.ssh.native true
 )
 
 git init ssh-command-fallback
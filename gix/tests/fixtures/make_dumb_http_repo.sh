@@ -0,0 +1,28 @@
+#!/bin/bash
+set -eu -o pipefail
+
+# A bare repository as served by a 'dumb' HTTP server, with older history in a pack and newer objects being loose.
+git init -q --bare remote.git
+
+git init -q worktree
+(cd worktree
+  git commit -q --allow-empty -m c1
+  echo a > a && git add a && git commit -q -m c2
+  git push -q ../remote.git HEAD:refs/heads/main
+)
+
+(cd remote.git
+  git repack -adq
+)
+
+(cd worktree
+  mkdir dir && echo b > dir/b && git add dir && git commit -q -m c3
+  git tag -m "the first release" v1.0
+  git push -q ../remote.git HEAD:refs/heads/main
+  git push -q ../remote.git v1.0
+)
+
+(cd remote.git
+  git symbolic-ref HEAD refs/heads/main
+  git update-server-info
+)
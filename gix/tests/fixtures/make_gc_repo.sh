@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+git add a && git commit -q -m a
+git repack -adq
+
+echo b > b
+git add b && git commit -q -m b
+
+# an unreachable loose object
+echo loose-garbage | git hash-object -w --stdin >/dev/null
+
+# an unreachable object in a pack that will be replaced
+packed_garbage=$(echo packed-garbage | git hash-object -w --stdin)
+echo "$packed_garbage" | git pack-objects -q .git/objects/pack/pack >/dev/null
+
+# a pack that is kept, along with its object
+kept=$(echo kept | git hash-object -w --stdin)
+kept_pack=$(echo "$kept" | git pack-objects -q .git/objects/pack/pack)
+touch ".git/objects/pack/pack-$kept_pack.keep"
+
+git prune-packed
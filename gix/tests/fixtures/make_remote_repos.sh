@@ -266,6 +266,9 @@ git clone --shared base credential-helpers
     git config credential.ssh://host:21/path.helper ssh://host:21/path
     git config credential.ssh://host/path.helper ssh://host/path
     git config credential.git://host.org.helper git://host.org
+    git config credential.example.org.helper example.org
+    git config credential.example.org/path.helper example.org/path
+    git config credential.example.org:8080.helper example.org:8080
 
     git config credential.https://dev.azure.com.usehttppath true
 
@@ -303,6 +306,9 @@ git clone --shared base credential-helpers
     baseline "ssh://host:21"
     baseline "ssh://host"
     baseline "git://host.org"
+    baseline "https://example.org/path"
+    baseline "http://example.org/other"
+    baseline "https://example.org:8080/path/sub"
 )
 
 git clone --shared base detached-head
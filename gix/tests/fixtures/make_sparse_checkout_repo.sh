@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo a > a
+echo b > b
+mkdir -p c1/c2 c1/c3 d/c4
+echo c1-a > c1/a
+echo c1-b > c1/b
+echo c2-a > c1/c2/a
+echo c2-b > c1/c2/b
+echo c3-a > c1/c3/a
+echo c3-b > c1/c3/b
+echo d-a > d/a
+echo d-b > d/b
+echo c4-a > d/c4/a
+echo c4-b > d/c4/b
+
+git add .
+git commit -q -m "init"
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.autocrlf input
+
+echo "ignored*" > .gitignore
+echo a > a
+echo exe > exe
+chmod +x exe
+mkdir dir
+echo b > dir/b
+
+git add .
+git commit -q -m "init"
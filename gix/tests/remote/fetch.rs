@@ -14,7 +14,10 @@ mod shallow {
 mod blocking_and_async_io {
     use std::sync::atomic::AtomicBool;
 
-    use gix::remote::{fetch, fetch::Status, Direction::Fetch};
+    use gix::{
+        bstr::ByteSlice,
+        remote::{fetch, fetch::Status, Direction::Fetch},
+    };
     use gix_features::progress;
     use gix_protocol::maybe_async;
     use gix_testtools::tempfile::TempDir;
@@ -157,6 +160,83 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_head_is_written_like_git_does_it() -> crate::Result {
+        let other_url = repo_path("clone-as-base-with-changes");
+        let other_url = gix::path::realpath(other_url)?.to_string_lossy().into_owned();
+        for (remote_name, git_args, specs) in [
+            (Some("origin"), vec!["fetch", "origin"], vec![]),
+            (
+                Some("changes-on-top-of-origin"),
+                vec!["fetch", "changes-on-top-of-origin"],
+                vec![],
+            ),
+            (
+                None,
+                vec!["fetch", other_url.as_str(), "refs/heads/main", "HEAD"],
+                vec!["refs/heads/main", "HEAD"],
+            ),
+        ] {
+            let (repo, tmp) = repo_rw("two-origins");
+            let remote = match remote_name {
+                Some(name) => repo.find_remote(name)?,
+                // `git` doesn't follow tags if no refspec has a destination, which we always do unless told otherwise.
+                None => repo
+                    .remote_at(other_url.as_str())?
+                    .with_refspecs(specs.iter().copied(), Fetch)?
+                    .with_fetch_tags(fetch::Tags::None),
+            };
+            remote
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .receive(gix::progress::Discard, &AtomicBool::default())?;
+            let actual = std::fs::read(repo.path().join("FETCH_HEAD"))?;
+            let actual = actual.replace(gix::path::realpath(tmp.path())?.to_string_lossy().as_bytes(), "<tmp>");
+
+            let (git_repo, git_tmp) = repo_rw("two-origins");
+            let out = std::process::Command::new("git")
+                .args(&git_args)
+                .current_dir(git_repo.path())
+                .output()?;
+            assert!(out.status.success(), "{out:?}");
+            let expected = std::fs::read(git_repo.path().join("FETCH_HEAD"))?;
+            let expected = expected.replace(
+                gix::path::realpath(git_tmp.path())?.to_string_lossy().as_bytes(),
+                "<tmp>",
+            );
+            assert_eq!(
+                actual.as_bstr(),
+                expected.as_bstr(),
+                "{remote_name:?}: the order of entries and their descriptions match"
+            );
+
+            let first_entry = gix::fetchhead::parse(&actual).next().expect("at least one entry")?;
+            assert_eq!(
+                repo.rev_parse_single("FETCH_HEAD")?.detach(),
+                first_entry.id,
+                "FETCH_HEAD resolves to the first entry, which is the first object to merge if there is one"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_head_is_not_written_in_dry_run_mode_or_if_disabled() -> crate::Result {
+        for (dry_run, write_fetch_head) in [(true, true), (false, false)] {
+            let (repo, _tmp) = repo_rw("two-origins");
+            repo.find_remote("changes-on-top-of-origin")?
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .with_dry_run(dry_run)
+                .with_write_fetch_head(write_fetch_head)
+                .receive(gix::progress::Discard, &AtomicBool::default())?;
+            assert!(!repo.path().join("FETCH_HEAD").exists());
+        }
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -343,11 +423,6 @@ mod blocking_and_async_io {
             gix::protocol::transport::Protocol::V2,
         ] {
             for (shallow_args, expected) in [(None, [1, 7, 7]), (Some("--depth=2"), [1, 2, 2])] {
-                if version == gix::protocol::transport::Protocol::V1 && shallow_args.is_some() {
-                    // TODO: We cannot yet handle shallow mode for V1 as it will send shallow-info as part of the handshake :/.
-                    //       It's probably not the most important thing to be able to clone from a shallow remote anyway.
-                    continue;
-                }
                 for (fetch_tags, expected_ref_count) in [fetch::Tags::None, fetch::Tags::Included, fetch::Tags::All]
                     .into_iter()
                     .zip(expected)
@@ -596,6 +671,7 @@ mod blocking_and_async_io {
                         );
                         update_refs
                     }
+                    fetch::Status::ChangeViaDumbHttp { .. } => unreachable!("the remote speaks the 'smart' protocol"),
                 };
 
                 assert_eq!(
@@ -653,3 +729,420 @@ mod blocking_and_async_io {
         Ok(())
     }
 }
+
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+mod packfile_uris {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::fetch;
+
+    use crate::util::{restricted, serve_files};
+
+    /// Configure the `base` repository in `fixture` to offer the blob in `served/` as pack via http, advertising `checksum`
+    /// or the actual one, and return the id of the blob.
+    fn offer_blob_pack(fixture: &std::path::Path, checksum: Option<&str>) -> crate::Result<gix::ObjectId> {
+        let served = fixture.join("served");
+        let blob = std::fs::read_to_string(served.join("blob"))?.trim().to_owned();
+        let actual_checksum = std::fs::read_to_string(served.join("pack-hash"))?.trim().to_owned();
+        let url = serve_files(&served)?;
+        let base = fixture.join("base");
+        // `git` only sends packfile URIs if the pack is sent with `sideband-all`.
+        assert!(gix_testtools::run_git(&base, &["config", "uploadpack.allowSidebandAll", "true"])?.success());
+        let status = gix_testtools::run_git(
+            &base,
+            &[
+                "config",
+                "uploadpack.blobpackfileuri",
+                &format!(
+                    "{blob} {} {url}/pack-{actual_checksum}.pack",
+                    checksum.unwrap_or(&actual_checksum)
+                ),
+            ],
+        )?;
+        assert!(status.success());
+        Ok(gix::ObjectId::from_hex(blob.as_bytes())?)
+    }
+
+    fn clone_with_uri_protocols(
+        fixture: &std::path::Path,
+        destination: &std::path::Path,
+    ) -> Result<(gix::Repository, fetch::Outcome), gix::clone::fetch::Error> {
+        let url = format!("file://{}", fixture.join("base").display());
+        gix::clone::PrepareFetch::new(
+            url.as_str(),
+            destination,
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )
+        .expect("valid clone arguments")
+        .with_in_memory_config_overrides(["fetch.uriProtocols=http"])
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())
+    }
+
+    #[test]
+    fn blobs_are_received_from_advertised_packs() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        let blob = offer_blob_pack(fixture.path(), None)?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, outcome) = clone_with_uri_protocols(fixture.path(), tmp.path())?;
+        match outcome.status {
+            fetch::Status::Change { write_pack_bundle, .. } => {
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 10,
+                    "the blob offered via packfile-uri isn't part of the pack sent by the remote"
+                );
+            }
+            _ => unreachable!("a clone always carries a change"),
+        }
+        assert!(repo.has_object(blob), "the blob was received through its own pack");
+        let num_indices = std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "idx"))
+            .count();
+        assert_eq!(num_indices, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn pack_checksums_are_verified() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        offer_blob_pack(fixture.path(), Some(&"1".repeat(40)))?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = clone_with_uri_protocols(fixture.path(), tmp.path()).unwrap_err();
+        assert!(matches!(
+            err,
+            gix::clone::fetch::Error::Fetch(fetch::Error::PackfileUriChecksumMismatch { .. })
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+mod dumb_http {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{fetch, Direction::Fetch};
+
+    use crate::util::{restricted, serve_files};
+
+    #[test]
+    fn objects_are_received_from_packs_and_as_loose_objects() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_read_only("make_dumb_http_repo.sh")?;
+        let remote_repo = gix::open_opts(fixture.join("remote.git"), restricted())?;
+        let url = serve_files(fixture.join("remote.git"))?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, outcome) = gix::clone::PrepareFetch::new(
+            url.as_str(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        match outcome.status {
+            fetch::Status::ChangeViaDumbHttp {
+                write_pack_bundles,
+                loose_objects,
+                ..
+            } => {
+                assert_eq!(
+                    write_pack_bundles.len(),
+                    1,
+                    "the older history is only available as pack"
+                );
+                assert_eq!(write_pack_bundles[0].index.num_objects, 5);
+                assert!(
+                    write_pack_bundles[0].keep_path.is_none(),
+                    "refs were updated so the pack doesn't need to be kept"
+                );
+                assert_eq!(loose_objects, 5, "the newest commit, its trees and blob, and the tag");
+            }
+            _ => unreachable!("a clone with objects to receive always carries a change"),
+        }
+
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "HEAD is obtained from the remote as well"
+        );
+        for name in ["refs/heads/main", "refs/tags/v1.0"] {
+            assert_eq!(
+                repo.find_reference(name)?.id(),
+                remote_repo.find_reference(name)?.id(),
+                "{name} matches the remote"
+            );
+        }
+        let tip = repo.head_id()?;
+        assert_eq!(tip.ancestors().all()?.count(), 3, "the whole history is present");
+        assert!(tip.object()?.peel_to_tree()?.find_entry("dir").is_some());
+
+        let outcome = repo
+            .find_default_remote(Fetch)
+            .expect("origin is set")?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, fetch::Status::NoPackReceived { .. }),
+            "there is nothing to receive if all tips are present"
+        );
+        Ok(())
+    }
+}
+
+/// Conformance of protocol V1 negotiation with `git upload-pack` over `file://`, which keeps a single connection for all rounds.
+/// Reading more or less than what the server sends in any of these rounds would make the fetch hang.
+#[cfg(feature = "blocking-network-client")]
+mod protocol_v1_stateful {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::{
+        config::tree::{Clone, Key},
+        remote::{
+            fetch::{Shallow, Status},
+            Direction::Fetch,
+        },
+    };
+    use gix_testtools::tempfile::TempDir;
+
+    use crate::{remote, util::hex_to_id};
+
+    const V1: gix::protocol::transport::Protocol = gix::protocol::transport::Protocol::V1;
+    const V2: gix::protocol::transport::Protocol = gix::protocol::transport::Protocol::V2;
+
+    fn clone_bare(
+        name: &str,
+        version: gix::protocol::transport::Protocol,
+        shallow: Shallow,
+        extra_config: Option<String>,
+    ) -> Result<(gix::Repository, gix::remote::fetch::Outcome, TempDir), gix::clone::fetch::Error> {
+        let tmp = TempDir::new().expect("can create temp dir");
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            remote::repo_path(name),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            crate::restricted().config_overrides(
+                Some(format!("protocol.version={}", version as u8))
+                    .into_iter()
+                    .chain(extra_config),
+            ),
+        )
+        .expect("valid clone arguments")
+        .with_shallow(shallow)
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        Ok((repo, out, tmp))
+    }
+
+    fn fetch(
+        repo: &gix::Repository,
+        name: &str,
+        refspec: &str,
+        shallow: Shallow,
+    ) -> crate::Result<gix::remote::fetch::Outcome> {
+        let url = format!("file://{}", gix::path::realpath(remote::repo_path(name))?.display());
+        Ok(repo
+            .remote_at(url)?
+            .with_refspecs(Some(refspec), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_shallow(shallow)
+            .receive(gix::progress::Discard, &AtomicBool::default())?)
+    }
+
+    fn set_protocol_version(repo: &mut gix::Repository, version: gix::protocol::transport::Protocol) -> crate::Result {
+        repo.config_snapshot_mut().set_value(
+            &gix::config::tree::Protocol::VERSION,
+            (version as u8).to_string().as_str(),
+        )?;
+        Ok(())
+    }
+
+    fn assert_connectivity(repo: &gix::Repository) -> crate::Result {
+        assert!(
+            gix_testtools::run_git(repo.git_dir(), &["fsck", "--connectivity-only", "--no-dangling"])?.success(),
+            "git considers all objects reachable from refs present"
+        );
+        Ok(())
+    }
+
+    fn negotiation_rounds(outcome: gix::remote::fetch::Outcome) -> Vec<gix::remote::fetch::outcome::negotiate::Round> {
+        match outcome.status {
+            Status::Change { negotiate, .. } => negotiate.rounds,
+            _ => unreachable!("a pack is expected"),
+        }
+    }
+
+    #[test]
+    fn negotiation_sends_done_as_soon_as_the_server_is_ready() -> crate::Result {
+        for (version, expected_rounds) in [(V1, 2), (V2, 1)] {
+            let (mut repo, _, _tmp) = clone_bare("multi_round/client", V2, Shallow::NoChange, None)?;
+            set_protocol_version(&mut repo, version)?;
+            let out = fetch(
+                &repo,
+                "multi_round/server",
+                "refs/heads/b1:refs/remotes/origin/b1",
+                Shallow::NoChange,
+            )?;
+            let rounds = negotiation_rounds(out);
+            assert_eq!(rounds.len(), expected_rounds, "{version:?}");
+            if version == V1 {
+                assert_eq!(
+                    rounds[1].haves_sent, 0,
+                    "after the server said it's ready, it only waits for 'done' without needing more haves"
+                );
+                assert!(rounds[1].previous_response_had_at_least_one_in_common);
+            }
+            assert_eq!(
+                repo.find_reference("refs/remotes/origin/b1")?.id(),
+                remote::repo("multi_round/server").find_reference("refs/heads/b1")?.id(),
+                "{version:?}"
+            );
+            assert_connectivity(&repo)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn negotiation_without_ready_ends_when_haves_run_out() -> crate::Result {
+        for version in [V1, V2] {
+            let (mut repo, _, _tmp) = clone_bare("multi_round/client", V2, Shallow::NoChange, None)?;
+            set_protocol_version(&mut repo, version)?;
+            let out = fetch(
+                &repo,
+                "multi_round/server",
+                "refs/heads/*:refs/remotes/origin/*",
+                Shallow::NoChange,
+            )?;
+            let rounds = negotiation_rounds(out);
+            assert_eq!(
+                rounds.len(),
+                4,
+                "{version:?}: the server can't be ready as 'main' is unrelated, so all haves are sent"
+            );
+            assert!(
+                rounds
+                    .iter()
+                    .skip(1)
+                    .any(|r| r.previous_response_had_at_least_one_in_common),
+                "{version:?}: acknowledgements are associated with the round they belong to"
+            );
+            assert_connectivity(&repo)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn deepen_keeps_shallow_updates_of_the_first_of_multiple_rounds() -> crate::Result {
+        for version in [V1, V2] {
+            let (mut repo, _, _tmp) = clone_bare("multi_round/client", V2, Shallow::NoChange, None)?;
+            set_protocol_version(&mut repo, version)?;
+            let out = fetch(
+                &repo,
+                "multi_round/server",
+                "refs/heads/*:refs/remotes/origin/*",
+                Shallow::DepthAtRemote(1.try_into()?),
+            )?;
+            assert!(negotiation_rounds(out).len() > 1, "{version:?}");
+            assert_eq!(
+                repo.shallow_commits()?.expect("shallow").as_slice(),
+                [
+                    hex_to_id("5721e14c223826f3f876bc758d9c760fe3d872ff"),
+                    hex_to_id("94e36c6f5f0c2d8b8dcf4933ed635e5236695c80"),
+                ],
+                "{version:?}: V1 sends shallow updates only before the acknowledgements of the first round"
+            );
+            assert_connectivity(&repo)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn shallow_clone_then_deepen_relative() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let (repo, _) = gix::clone::PrepareFetch::new(
+            remote::repo_path("base"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            crate::restricted().config_overrides(Some(format!("protocol.version={}", V1 as u8))),
+        )?
+        .with_shallow(Shallow::DepthAtRemote(2.try_into()?))
+        .configure_remote(|mut r| {
+            r.replace_refspecs(Some("refs/heads/main:refs/remotes/origin/main"), Fetch)?;
+            Ok(r)
+        })
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            repo.shallow_commits()?.expect("present").as_slice(),
+            [
+                hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
+                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
+            ]
+        );
+        let shallow_commit_count = repo.head_id()?.ancestors().all()?.count();
+
+        repo.find_default_remote(Fetch)
+            .expect("origin is set")?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_shallow(Shallow::Deepen(1))
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            repo.shallow_commits()?.expect("present").as_slice(),
+            [
+                hex_to_id("27e71576a6335294aa6073ab767f8b36bdba81d0"),
+                hex_to_id("82024b2ef7858273337471cbd1ca1cedbdfd5616"),
+                hex_to_id("b5152869aedeb21e55696bb81de71ea1bb880c85"),
+            ],
+            "the shallow boundary moved by one commit, just like with V2"
+        );
+        assert!(repo.head_id()?.ancestors().all()?.count() > shallow_commit_count);
+        assert_connectivity(&repo)?;
+        Ok(())
+    }
+
+    #[test]
+    fn clone_from_shallow_remote_uses_advertised_shallow_commits() -> crate::Result {
+        let (repo, _, _tmp) = clone_bare("base.shallow", V1, Shallow::NoChange, None)?;
+        assert_eq!(
+            repo.shallow_commits()?.expect("present").as_slice(),
+            [
+                hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
+                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
+            ],
+            "V1 only advertises the shallow boundary of the remote as part of the handshake"
+        );
+        assert_connectivity(&repo)?;
+        Ok(())
+    }
+
+    #[test]
+    fn clone_from_shallow_remote_can_be_rejected() -> crate::Result {
+        let err = clone_bare(
+            "base.shallow",
+            V1,
+            Shallow::NoChange,
+            Some(Clone::REJECT_SHALLOW.validated_assignment_fmt(&true)?.to_string()),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::RejectShallowRemote)
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::bstr::BStr;
+
+use crate::util::restricted;
+
+fn fixture() -> crate::Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")
+}
+
+fn base() -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(fixture()?.join("base"), restricted())?)
+}
+
+fn create_bundle(
+    repo: &gix::Repository,
+    specs: &[&str],
+) -> Result<(gix::bundle::write::Outcome, Vec<u8>), gix::repository::create_bundle::Error> {
+    let mut buf = Vec::new();
+    let outcome = repo.create_bundle(
+        specs.iter().map(BStr::new),
+        &mut buf,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    Ok((outcome, buf))
+}
+
+mod create {
+    use gix::repository::create_bundle;
+
+    use super::{base, create_bundle, fixture};
+
+    #[test]
+    fn from_range() -> crate::Result {
+        let repo = base()?;
+        let (outcome, buf) = create_bundle(&repo, &["main~1..main"])?;
+        assert_eq!(outcome.num_objects, 4, "the commit, two trees and the new blob");
+        assert_eq!(
+            outcome.header.prerequisites.iter().map(|p| p.id).collect::<Vec<_>>(),
+            [repo.rev_parse_single("main~1")?.detach()]
+        );
+        assert_eq!(outcome.header.refs.len(), 1);
+        assert_eq!(outcome.header.refs[0].name, "refs/heads/main");
+        assert_eq!(outcome.header.refs[0].id, repo.rev_parse_single("main")?.detach());
+
+        let expected = gix::bundle::File::at(fixture()?.join("incremental.bundle"))?;
+        assert_eq!(
+            &outcome.header,
+            expected.header(),
+            "the header is the same as the one git writes"
+        );
+        assert!(buf.starts_with(b"# v2 git bundle\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn with_exclusions_and_multiple_refs() -> crate::Result {
+        let repo = base()?;
+        let (outcome, _buf) = create_bundle(&repo, &["main", "v1", "^v1", "main"])?;
+        assert_eq!(
+            outcome
+                .header
+                .refs
+                .iter()
+                .map(|r| r.name.to_string())
+                .collect::<Vec<_>>(),
+            ["refs/heads/main", "refs/tags/v1"],
+            "refs are recorded only once"
+        );
+        assert_eq!(outcome.header.prerequisites.len(), 1);
+        assert_eq!(
+            outcome.num_objects, 8,
+            "two commits, four trees, two blobs, and the tag"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> crate::Result {
+        let repo = base()?;
+        let id = repo.rev_parse_single("main")?.to_string();
+        assert!(matches!(
+            create_bundle(&repo, &[id.as_str()]).unwrap_err(),
+            create_bundle::Error::NotARef { .. }
+        ));
+        assert!(matches!(
+            create_bundle(&repo, &["main^!"]).unwrap_err(),
+            create_bundle::Error::UnsupportedSpec { .. }
+        ));
+        assert!(matches!(
+            create_bundle(&repo, &["does-not-exist"]).unwrap_err(),
+            create_bundle::Error::RevParse(_)
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod fetch {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{fetch, Direction};
+
+    use crate::util::restricted;
+
+    fn local_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        let repo = gix::open_opts(tmp.path().join("local"), restricted())?;
+        Ok((repo, tmp))
+    }
+
+    fn fetch_bundle(
+        repo: &gix::Repository,
+        bundle: &str,
+        dry_run: bool,
+    ) -> Result<fetch::Outcome, fetch::bundle::Error> {
+        let path = repo
+            .work_dir()
+            .expect("non-bare")
+            .parent()
+            .expect("parent")
+            .join(bundle);
+        repo.remote_at(path)
+            .expect("valid path")
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/bundle/*"), Direction::Fetch)
+            .expect("valid refspec")
+            .fetch_bundle(
+                &mut gix::progress::Discard,
+                &AtomicBool::default(),
+                fetch::bundle::Options {
+                    dry_run,
+                    ..Default::default()
+                },
+            )
+    }
+
+    #[test]
+    fn incremental_bundle_into_repository_with_prerequisites() -> crate::Result {
+        let (repo, _tmp) = local_rw()?;
+        let expected = gix::open_opts(repo.work_dir().expect("non-bare").join("../base"), restricted())?
+            .rev_parse_single("main")?
+            .detach();
+
+        let outcome = fetch_bundle(&repo, "incremental.bundle", true)?;
+        assert!(matches!(
+            outcome.status,
+            fetch::Status::NoPackReceived { dry_run: true, .. }
+        ));
+        assert!(repo.try_find_reference("refs/remotes/bundle/main")?.is_none());
+
+        let outcome = fetch_bundle(&repo, "incremental.bundle", false)?;
+        match outcome.status {
+            fetch::Status::Change {
+                write_pack_bundle,
+                update_refs,
+                ..
+            } => {
+                assert_eq!(write_pack_bundle.index.num_objects, 4);
+                assert!(write_pack_bundle.keep_path.is_none(), "keep files are removed");
+                assert_eq!(update_refs.edits.len(), 1);
+            }
+            _ => unreachable!("a pack is written"),
+        }
+        assert_eq!(repo.find_reference("refs/remotes/bundle/main")?.id(), expected);
+        assert!(repo.has_object(expected));
+
+        let outcome = fetch_bundle(&repo, "incremental.bundle", false)?;
+        assert!(
+            matches!(outcome.status, fetch::Status::NoPackReceived { dry_run: false, .. }),
+            "all objects are present now"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_prerequisites_are_an_error() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let repo = gix::init(tmp.path().join("empty"))?;
+        let bundle = gix_testtools::scripted_fixture_read_only("make_bundle_repos.sh")?.join("incremental.bundle");
+        let err = repo
+            .remote_at(bundle)?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/bundle/*"), Direction::Fetch)?
+            .fetch_bundle(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            fetch::bundle::Error::Unpack(gix::bundle::file::unpack::Error::MissingPrerequisites { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn urls_that_are_no_bundles_are_an_error() -> crate::Result {
+        let (repo, _tmp) = local_rw()?;
+        let err = fetch_bundle(&repo, "base", false).unwrap_err();
+        assert!(matches!(err, fetch::bundle::Error::NotABundle { .. }));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod uri {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{fetch, Direction};
+
+    use crate::util::restricted;
+
+    fn fetch_with_bundle_uri(
+        repo: &gix::Repository,
+        uri: impl Into<gix::bstr::BString>,
+    ) -> crate::Result<fetch::Outcome> {
+        let base = repo.work_dir().expect("non-bare").join("../base");
+        Ok(repo
+            .remote_at(base)?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Direction::Fetch)?
+            .connect(Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_bundle_uri(uri)
+            .receive(gix::progress::Discard, &AtomicBool::default())?)
+    }
+
+    fn base_main(repo: &gix::Repository) -> crate::Result<gix::ObjectId> {
+        Ok(
+            gix::open_opts(repo.work_dir().expect("non-bare").join("../base"), restricted())?
+                .rev_parse_single("main")?
+                .detach(),
+        )
+    }
+
+    #[test]
+    fn bundle_from_path_makes_receiving_a_pack_unnecessary() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        let repo = gix::open_opts(tmp.path().join("local"), restricted())?;
+        let bundle = tmp.path().join("incremental.bundle");
+
+        let outcome = fetch_with_bundle_uri(&repo, gix::path::into_bstr(bundle.as_path()).into_owned())?;
+        let bundle_uri = outcome.bundle_uri.expect("bundle uri was used");
+        assert_eq!(bundle_uri.applied.len(), 1);
+        assert!(bundle_uri.failed.is_empty());
+        assert!(
+            matches!(outcome.status, fetch::Status::NoPackReceived { .. }),
+            "all objects are provided by the bundle"
+        );
+
+        let expected = base_main(&repo)?;
+        assert_eq!(repo.find_reference("refs/bundles/main")?.id(), expected);
+        assert_eq!(repo.find_reference("refs/remotes/origin/main")?.id(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn unavailable_bundles_are_not_fatal() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        let repo = gix::open_opts(tmp.path().join("local"), restricted())?;
+
+        let outcome = fetch_with_bundle_uri(&repo, "does-not-exist.bundle")?;
+        let bundle_uri = outcome.bundle_uri.expect("bundle uri was used");
+        assert!(bundle_uri.applied.is_empty());
+        assert_eq!(bundle_uri.failed.len(), 1);
+        assert!(
+            matches!(outcome.status, fetch::Status::Change { write_pack_bundle, .. } if write_pack_bundle.index.num_objects == 4),
+            "the remainder is received from the remote"
+        );
+        assert!(repo.try_find_reference("refs/bundles/main")?.is_none());
+        assert_eq!(repo.find_reference("refs/remotes/origin/main")?.id(), base_main(&repo)?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    fn clone_with_bundle_list_over_http() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_writable("make_bundle_repos.sh")?;
+        std::fs::write(
+            fixture.path().join("bundle-list"),
+            "[bundle]\n\tversion = 1\n\tmode = all\n\
+             [bundle \"incremental\"]\n\turi = incremental.bundle\n\
+             [bundle \"second\"]\n\turi = second.bundle\n",
+        )?;
+        let url = crate::util::serve_files(fixture.path())?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, outcome) = gix::clone::PrepareFetch::new(
+            fixture.path().join("base"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_bundle_uri(format!("{url}/bundle-list"))
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+        let bundle_uri = outcome.bundle_uri.expect("bundle uri was used");
+        assert_eq!(
+            bundle_uri.applied,
+            [format!("{url}/second.bundle"), format!("{url}/incremental.bundle")],
+            "bundles are retried until their prerequisites are met"
+        );
+        assert!(bundle_uri.failed.is_empty(), "{:?}", bundle_uri.failed);
+        assert!(
+            matches!(outcome.status, fetch::Status::Change { write_pack_bundle, .. } if write_pack_bundle.index.num_objects == 1),
+            "only the annotated tag isn't provided by the bundles"
+        );
+
+        let expected = gix::open_opts(fixture.path().join("base"), restricted())?
+            .rev_parse_single("main")?
+            .detach();
+        assert_eq!(repo.find_reference("refs/bundles/main")?.id(), expected);
+        assert_eq!(repo.head_id()?, expected);
+        Ok(())
+    }
+}
@@ -62,7 +62,7 @@ mod baseline {
         );
     }
 
-    fn agrees_with_inner(url: &str, ignore_expected_prompt_port: bool, lowercase_prompt_host: bool) {
+    fn agrees_with_inner(url: &str, lowercase_prompt_scheme: bool) {
         let repo = remote::repo("credential-helpers");
         let (cascade, mut action, prompt_options) = repo
             .config_snapshot()
@@ -94,28 +94,21 @@ mod baseline {
 
         let ctx = action.context_mut().expect("get/fill");
         ctx.destructure_url_in_place(cascade.use_http_path).unwrap();
-        let expected_prompt = lowercase_prompt_host
-            .then(|| expected.prompt_url.to_ascii_lowercase())
-            .unwrap_or_else(|| expected.prompt_url.to_owned());
-        if ignore_expected_prompt_port {
-            assert_eq!(
-                ctx.to_url().expect("parts complete"),
-                expected_prompt.trim_end_matches(|b: char| b == ':' || b.is_numeric())
-            );
+        let expected_prompt = if lowercase_prompt_scheme {
+            let (scheme, rest) = expected.prompt_url.split_once("://").expect("prompts are URLs");
+            format!("{}://{rest}", scheme.to_ascii_lowercase())
         } else {
-            assert_eq!(ctx.to_url().expect("parts complete"), expected_prompt);
-        }
+            expected.prompt_url.to_owned()
+        };
+        assert_eq!(ctx.to_url().expect("parts complete"), expected_prompt);
     }
 
     pub fn agrees_with(url: &str) {
-        agrees_with_inner(url, false, false)
+        agrees_with_inner(url, false)
     }
 
-    pub fn agrees_with_but_drops_default_port_in_prompt(url: &str) {
-        agrees_with_inner(url, true, false)
-    }
-    pub fn agrees_with_but_lowercases_scheme_and_host(url: &str) {
-        agrees_with_inner(url, false, true)
+    pub fn agrees_with_but_lowercases_scheme(url: &str) {
+        agrees_with_inner(url, true)
     }
 }
 
@@ -128,8 +121,8 @@ fn any_url_calls_global() {
 fn http_port_defaulting() {
     baseline::agrees_with("https://example.com");
     baseline::agrees_with("https://example.com/");
-    baseline::agrees_with_but_drops_default_port_in_prompt("https://example.com:443");
-    baseline::agrees_with_but_drops_default_port_in_prompt("https://example.com:443/");
+    baseline::agrees_with("https://example.com:443");
+    baseline::agrees_with("https://example.com:443/");
 }
 
 #[test]
@@ -152,17 +145,17 @@ fn host_globs_match_as_well() {
 
 #[test]
 fn case_sensitive_host_matching() {
-    baseline::agrees_with_but_lowercases_scheme_and_host("https://EXAMPLE.com");
-    baseline::agrees_with_but_lowercases_scheme_and_host("https://example.COM");
-    baseline::agrees_with_but_lowercases_scheme_and_host("HTTPS://example.com");
+    baseline::agrees_with("https://EXAMPLE.com");
+    baseline::agrees_with("https://example.COM");
+    baseline::agrees_with_but_lowercases_scheme("HTTPS://example.com");
 }
 
 #[test]
 fn subdomain_globs_match_on_their_level() {
     baseline::agrees_with("http://a.example.com");
     baseline::agrees_with("http://b.example.com/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://c.example.com:80/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://a.a.example.com:80/path");
+    baseline::agrees_with("http://c.example.com:80/path");
+    baseline::agrees_with("http://a.a.example.com:80/path");
     baseline::agrees_with("http://a.b.example.com/path");
     baseline::agrees_with("http://b.a.example.com/path");
 }
@@ -172,8 +165,8 @@ fn subdomain_globs_match_on_their_level() {
 fn http_urls_match_the_host_without_path_as_well() {
     let _env = Env::new().set("GIT_ASKPASS", "foo");
     baseline::agrees_with("http://example.com:8080/other/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://example.com:80/");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://example.com:80");
+    baseline::agrees_with("http://example.com:80/");
+    baseline::agrees_with("http://example.com:80");
     baseline::agrees_with("http://example.com");
 }
 
@@ -198,6 +191,13 @@ fn ssh_host_and_port_with_path_via_url_match() {
     baseline::agrees_with("ssh://host:21/path");
 }
 
+#[test]
+fn partial_urls_match_host_and_path_exactly() {
+    baseline::agrees_with("https://example.org/path");
+    baseline::agrees_with("http://example.org/other");
+    baseline::agrees_with("https://example.org:8080/path/sub");
+}
+
 #[test]
 fn invalid_urls_are_rejected_early() {
     baseline::works_but_we_dont_parse_invalid_url("ssh://host");
@@ -15,6 +15,7 @@ mod ssh_options {
         assert_eq!(opts.command.as_deref(), Some(OsStr::new("ssh -VVV")));
         assert_eq!(opts.kind, Some(gix::protocol::transport::client::ssh::ProgramKind::Ssh));
         assert!(!opts.disallow_shell, "we can use the shell by default");
+        assert!(opts.native, "the built-in client was requested");
         Ok(())
     }
 
@@ -31,6 +32,7 @@ mod ssh_options {
             opts.disallow_shell,
             "fallbacks won't allow shells, so must be a program or program name"
         );
+        assert!(!opts.native, "the ssh program is used by default");
         Ok(())
     }
 }
@@ -336,4 +336,80 @@ mod http {
             "even with `http.sslVerify` enabled, `gitoxide.http.sslNoVerify` takes precedence`"
         );
     }
+
+    #[test]
+    fn url_specific_values_take_precedence_by_specificity() {
+        let repo = repo("http-url-match");
+
+        let opts = http_options(&repo, None, "https://example.com/org/repo");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://org-proxy"),
+            "a longer path prefix is more specific"
+        );
+        assert_eq!(
+            opts.extra_headers,
+            &["ExtraHeader: all", "ExtraHeader: org"],
+            "multi-values accumulate across all matching sections"
+        );
+        assert!(!opts.ssl_verify);
+        assert_eq!(opts.follow_redirects, FollowRedirects::Initial);
+        assert_ne!(opts.user_agent.as_deref(), Some("agentForUser"), "users must match");
+
+        let opts = http_options(&repo, None, "https://example.com/organization");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://less-specific-but-later"),
+            "paths match only along component boundaries"
+        );
+        assert_eq!(opts.extra_headers, &["ExtraHeader: all"]);
+        assert!(opts.ssl_verify);
+        assert_eq!(opts.low_speed_time_seconds, 42);
+
+        let opts = http_options(&repo, None, "https://user@example.com/org");
+        assert_eq!(opts.user_agent.as_deref(), Some("agentForUser"));
+        assert_eq!(opts.proxy.as_deref(), Some("http://org-proxy"));
+    }
+
+    #[test]
+    fn url_specific_values_match_hosts_with_wildcards_ports_and_schemes() {
+        let repo = repo("http-url-match");
+
+        let opts = http_options(&repo, None, "https://host.example.com/repo");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://host-proxy"),
+            "the longer host is more specific than the one with wildcard"
+        );
+        let opts = http_options(&repo, None, "https://other.example.com");
+        assert_eq!(opts.proxy.as_deref(), Some("http://wildcard-proxy"));
+        let opts = http_options(&repo, None, "https://a.b.example.com");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://localhost:9090"),
+            "wildcards match only a single host component"
+        );
+
+        let opts = http_options(&repo, None, "https://example.com:8080/org");
+        assert_eq!(opts.follow_redirects, FollowRedirects::None);
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://localhost:9090"),
+            "the default port doesn't match other ports"
+        );
+        let opts = http_options(&repo, None, "https://example.com:443/org");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://org-proxy"),
+            "default ports are implied"
+        );
+
+        let opts = http_options(&repo, None, "http://example.com/org");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://localhost:9090"),
+            "schemes have to match"
+        );
+        assert_eq!(opts.extra_headers, &["ExtraHeader: all"]);
+    }
 }
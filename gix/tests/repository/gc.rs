@@ -0,0 +1,286 @@
+use std::{
+    sync::atomic::AtomicBool,
+    time::{Duration, SystemTime},
+};
+
+use crate::util::hex_to_id;
+
+fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let repo_path = gix_testtools::scripted_fixture_writable("make_gc_repo.sh")?;
+    let repo = gix::open_opts(repo_path.path(), crate::restricted())?;
+    Ok((repo, repo_path))
+}
+
+fn loose_ids(repo: &gix::Repository) -> Vec<gix::ObjectId> {
+    let mut ids: Vec<_> = gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .map(Result::unwrap)
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn pack_names(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let mut names: Vec<_> = std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<_, _>>()?;
+    names.sort();
+    Ok(names)
+}
+
+const LOOSE_GARBAGE: &str = "3f543ac979a5ef5b5ecc1d6577351705f4a1538d";
+const PACKED_GARBAGE: &str = "14ed4c4c4687f3d1b39f1267035370b88a1dfc4c";
+const KEPT_PACK: &str = "pack-cd7640331794f6d3b8f0413e1af04bf297afddfc";
+
+fn gc(repo: &gix::Repository, prune_expire: Option<SystemTime>) -> crate::Result<gix::gc::Outcome> {
+    Ok(repo.gc(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::gc::Options {
+            prune_expire,
+            ..repo.gc_options()?
+        },
+    )?)
+}
+
+#[test]
+fn reachable_objects_are_packed_and_unreachable_ones_are_retained_if_not_expired() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let out = gc(&repo, None)?;
+
+    let pack = out.pack.expect("something was packed");
+    assert_eq!(pack.index.num_objects, 6, "two commits with their trees and blobs");
+    assert_eq!(out.removed_packs.len(), 2, "all packs but the kept one are replaced");
+    assert_eq!(out.loosened_objects, 1, "the unreachable object of a removed pack");
+    assert_eq!(out.removed_loose_objects, 3, "all loose objects that were packed");
+    assert_eq!(out.multi_pack_index_path, None, "there was none before");
+
+    assert_eq!(
+        loose_ids(&repo),
+        [hex_to_id(PACKED_GARBAGE), hex_to_id(LOOSE_GARBAGE)],
+        "unreachable objects are kept as they didn't expire yet"
+    );
+    let names = pack_names(&repo)?;
+    assert_eq!(
+        names.len(),
+        5,
+        "the kept pack and the new one, without keep-file: {names:?}"
+    );
+    assert!(names.iter().filter(|name| name.starts_with(KEPT_PACK)).count() == 3);
+
+    let index = gix::odb::pack::index::File::at(pack.index_path.expect("written"), repo.object_hash())?;
+    for entry in index.iter() {
+        assert!(
+            repo.try_find_object(entry.oid)?.is_some(),
+            "all objects of the new pack are accessible"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn expired_unreachable_objects_are_removed() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let out = gc(&repo, SystemTime::now().checked_add(Duration::from_secs(60 * 60)))?;
+
+    assert_eq!(out.pack.expect("written").index.num_objects, 6);
+    assert_eq!(out.loosened_objects, 0, "the replaced pack is expired");
+    assert_eq!(out.removed_loose_objects, 4, "packed and unreachable loose objects");
+    assert!(loose_ids(&repo).is_empty());
+    assert!(repo.try_find_object(hex_to_id(LOOSE_GARBAGE))?.is_none());
+    assert!(repo.try_find_object(hex_to_id(PACKED_GARBAGE))?.is_none());
+    assert!(
+        repo.try_find_object(hex_to_id("bd93009536360a2d96f2b097ac88b28f1fc8cdb4"))?
+            .is_some(),
+        "the object of the kept pack remains"
+    );
+
+    let out = gc(&repo, SystemTime::now().checked_add(Duration::from_secs(60 * 60)))?;
+    assert_eq!(out.pack.expect("written").index.num_objects, 6, "repeated runs repack");
+    assert_eq!(out.removed_packs.len(), 1, "only the previous pack was replaced");
+    assert_eq!(out.removed_loose_objects, 0);
+    Ok(())
+}
+
+#[test]
+fn multi_pack_index_is_written_for_all_remaining_packs() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let out = repo.gc(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::gc::Options {
+            write_multi_pack_index: true,
+            ..repo.gc_options()?
+        },
+    )?;
+    let path = out.multi_pack_index_path.expect("written");
+    let index = gix::odb::pack::multi_index::File::at(&path)?;
+    assert_eq!(index.num_indices(), 2, "the new pack and the kept one");
+    assert_eq!(index.num_objects(), 7);
+
+    assert!(
+        repo.gc_options()?.write_multi_pack_index,
+        "once present, the multi-pack-index will be maintained"
+    );
+    Ok(())
+}
+
+#[test]
+fn bitmap_is_written_if_the_new_pack_contains_all_reachable_objects() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let gc_with_bitmap = |repo: &gix::Repository| -> crate::Result<gix::gc::Outcome> {
+        Ok(repo.gc(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            gix::gc::Options {
+                write_bitmap_index: true,
+                ..repo.gc_options()?
+            },
+        )?)
+    };
+    let out = gc_with_bitmap(&repo)?;
+    let bitmap_path = out
+        .bitmap_path
+        .expect("written as the kept pack only has an unreachable object");
+    assert_eq!(
+        Some(bitmap_path.as_path()),
+        out.pack
+            .as_ref()
+            .and_then(|pack| pack.index_path.as_ref())
+            .map(|path| path.with_extension("bitmap"))
+            .as_deref(),
+        "the bitmap is stored next to the new pack"
+    );
+    assert!(
+        gix_testtools::run_git(tmp.path(), &["rev-list", "--test-bitmap", "main"])?.success(),
+        "git validates the bitmap against a traversal"
+    );
+
+    std::fs::write(bitmap_path.with_extension("keep"), "")?;
+    std::fs::write(tmp.path().join("c"), "c")?;
+    assert!(gix_testtools::run_git(tmp.path(), &["add", "c"])?.success());
+    let out = gc_with_bitmap(&repo)?;
+    assert!(out.pack.is_some(), "the new index entry is packed");
+    assert_eq!(
+        out.bitmap_path, None,
+        "reachable objects are in the kept pack, which also has a bitmap already"
+    );
+    assert!(bitmap_path.is_file(), "the bitmap of the kept pack is retained");
+    Ok(())
+}
+
+#[test]
+fn options_from_configuration() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let expire = repo.gc_options()?.prune_expire.expect("set by default");
+    let two_weeks_ago = SystemTime::now() - Duration::from_secs(60 * 60 * 24 * 14);
+    assert!(
+        two_weeks_ago.duration_since(expire).unwrap_or_default() < Duration::from_secs(60),
+        "defaults to two weeks ago"
+    );
+
+    let mut repo = repo;
+    repo.config_snapshot_mut()
+        .set_raw_value("gc", None, "pruneExpire", "never")?;
+    assert_eq!(repo.gc_options()?.prune_expire, None);
+    repo.config_snapshot_mut()
+        .set_raw_value("gc", None, "pruneExpire", "now")?;
+    assert!(repo.gc_options()?.prune_expire.is_some());
+    assert!(
+        !repo.gc_options()?.write_bitmap_index,
+        "bitmaps are only written by default in bare repositories"
+    );
+    repo.config_snapshot_mut()
+        .set_raw_value("repack", None, "writeBitmaps", "true")?;
+    assert!(repo.gc_options()?.write_bitmap_index);
+    Ok(())
+}
+
+#[test]
+fn references_are_packed_and_reflogs_expire() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let reflog_len = |repo: &gix::Repository| -> crate::Result<usize> {
+        let mut buf = Vec::new();
+        Ok(repo
+            .refs
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("log present")
+            .count())
+    };
+    assert_eq!(reflog_len(&repo)?, 2);
+
+    let out = repo.gc(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::gc::Options {
+            reflog_expire: None,
+            reflog_expire_unreachable: None,
+            pack_refs: false,
+            ..repo.gc_options()?
+        },
+    )?;
+    assert_eq!(out.expired_reflog_entries, 0);
+    assert_eq!(out.packed_refs, 0);
+    assert_eq!(reflog_len(&repo)?, 2);
+    assert_eq!(repo.refs.loose_iter()?.count(), 1, "main is still loose");
+
+    let out = gc(&repo, None)?;
+    assert!(
+        out.expired_reflog_entries >= 2,
+        "fixture entries are older than 90 days, so all of them expire"
+    );
+    assert_eq!(reflog_len(&repo)?, 0);
+    assert_eq!(out.packed_refs, 1);
+    assert_eq!(repo.refs.loose_iter()?.count(), 0, "all refs were packed");
+    assert!(repo.try_find_reference("main")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn objects_checked_out_in_linked_worktrees_are_retained() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let git = |dir: &std::path::Path, args: &[&str]| -> crate::Result {
+        let mut all_args = vec!["-c", "user.name=committer", "-c", "user.email=committer@example.com"];
+        all_args.extend_from_slice(args);
+        assert!(gix_testtools::run_git(dir, &all_args)?.success(), "git {args:?}");
+        Ok(())
+    };
+    let worktree = tmp.path().join("wt-detached");
+    git(
+        tmp.path(),
+        &["worktree", "add", "-q", "--detach", "wt-detached", "main"],
+    )?;
+    git(
+        &worktree,
+        &["commit", "-q", "--allow-empty", "-m", "only in the reflog"],
+    )?;
+    git(
+        &worktree,
+        &["commit", "-q", "--allow-empty", "--amend", "-m", "detached"],
+    )?;
+    std::fs::write(worktree.join("c"), "only in the index of the worktree")?;
+    git(&worktree, &["add", "c"])?;
+
+    gc(&repo, SystemTime::now().checked_add(Duration::from_secs(60 * 60)))?;
+
+    let wt_repo = repo.worktrees()?.pop().expect("one worktree").into_repo()?;
+    let head = wt_repo.head()?;
+    assert!(head.is_detached());
+    assert!(
+        repo.try_find_object(head.id().expect("born"))?.is_some(),
+        "HEAD is kept"
+    );
+    let mut reflog_ids = Vec::new();
+    for line in head.log_iter().all()?.expect("present") {
+        reflog_ids.push(line?.new_oid());
+    }
+    assert_eq!(reflog_ids.len(), 3, "checkout, commit and amend");
+    for id in reflog_ids {
+        assert!(repo.try_find_object(id)?.is_some(), "the HEAD reflog is kept");
+    }
+    for entry in wt_repo.index()?.entries() {
+        assert!(repo.try_find_object(entry.id)?.is_some(), "the index is kept");
+    }
+    assert!(loose_ids(&repo).is_empty(), "everything reachable was packed");
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::path::Path;
+
+use gix::hooks::ReferenceTransactionState;
+
+fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_stage_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+#[cfg(unix)]
+fn write_hook(dir: &Path, name: &str, script: &str) -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}"))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[test]
+fn dir_defaults_to_hooks_in_common_dir_and_can_be_configured() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    assert_eq!(repo.hooks()?.dir(), repo.common_dir().join("hooks"));
+
+    assert!(gix_testtools::run_git(dir.path(), &["config", "core.hooksPath", "my-hooks"])?.success());
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    assert_eq!(
+        repo.hooks()?.dir(),
+        repo.work_dir().expect("non-bare").join("my-hooks"),
+        "relative paths are relative to where hooks run"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_hooks_are_not_run() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    let hooks = repo.hooks()?;
+    assert!(!hooks.exists("pre-commit"), "only samples are installed");
+    assert!(!hooks.pre_commit()?);
+    assert_eq!(hooks.commit_msg("unchanged".into())?, "unchanged");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn non_executable_hooks_are_ignored() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    let hooks = repo.hooks()?;
+    std::fs::write(hooks.dir().join("pre-commit"), "#!/bin/sh\nexit 1")?;
+    assert!(!hooks.exists("pre-commit"));
+    assert!(!hooks.pre_commit()?);
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn hooks_are_not_run_in_untrusted_repositories() -> crate::Result {
+    let (_repo, dir) = repo_rw()?;
+    let repo = gix::open_opts(dir.path(), crate::restricted().with(gix_sec::Trust::Reduced))?;
+    let hooks = repo.hooks()?;
+    write_hook(hooks.dir(), "pre-commit", "exit 1")?;
+    assert!(!hooks.exists("pre-commit"));
+    assert!(!hooks.pre_commit()?);
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn pre_commit_runs_in_the_worktree_with_git_environment() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    let hooks = repo.hooks()?;
+    write_hook(
+        hooks.dir(),
+        "pre-commit",
+        "echo \"$PWD $GIT_DIR $GIT_INDEX_FILE\" > \"$GIT_DIR/hook-env\"",
+    )?;
+    assert!(hooks.pre_commit()?);
+    let env = std::fs::read_to_string(repo.git_dir().join("hook-env"))?;
+    let worktree = gix_path::realpath(dir.path())?;
+    assert_eq!(
+        env.trim_end(),
+        format!(
+            "{} {} {}",
+            worktree.display(),
+            repo.git_dir().display(),
+            repo.index_path().display()
+        )
+    );
+
+    write_hook(hooks.dir(), "pre-commit", "exit 42")?;
+    let err = hooks.pre_commit().unwrap_err();
+    assert!(
+        matches!(&err, gix::hooks::run::Error::Failed { name, status } if name == "pre-commit" && status.code() == Some(42)),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn commit_msg_can_edit_the_message() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    let hooks = repo.hooks()?;
+    write_hook(hooks.dir(), "commit-msg", "echo 'Signed-off-by: hook' >> \"$1\"")?;
+    assert_eq!(hooks.commit_msg("message\n".into())?, "message\nSigned-off-by: hook\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn reference_transaction_receives_all_states_and_edits() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    let log = repo.git_dir().join("transaction-log");
+    write_hook(
+        repo.hooks()?.dir(),
+        "reference-transaction",
+        &format!("echo \"$1\" >> {log:?}\ncat >> {log:?}"),
+    )?;
+    let head = repo.head_id()?.detach();
+    repo.reference(
+        "refs/heads/new",
+        head,
+        gix_ref::transaction::PreviousValue::MustNotExist,
+        "create",
+    )?;
+    let null = gix_hash::Kind::Sha1.null();
+    assert_eq!(
+        std::fs::read_to_string(&log)?,
+        format!("prepared\n{null} {head} refs/heads/new\ncommitted\n{null} {head} refs/heads/new\n")
+    );
+    assert_eq!(ReferenceTransactionState::Prepared.as_str(), "prepared");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn reference_transaction_can_abort_edits() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    let log = repo.git_dir().join("transaction-log");
+    write_hook(
+        repo.hooks()?.dir(),
+        "reference-transaction",
+        &format!("echo \"$1\" >> {log:?}\ntest \"$1\" != prepared"),
+    )?;
+    let err = repo
+        .reference(
+            "refs/heads/new",
+            repo.head_id()?,
+            gix_ref::transaction::PreviousValue::MustNotExist,
+            "create",
+        )
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            gix::reference::edit::Error::Hook(gix::hooks::run::Error::Failed { .. })
+        ),
+        "{err:?}"
+    );
+    assert!(
+        repo.try_find_reference("refs/heads/new")?.is_none(),
+        "nothing was changed"
+    );
+    assert_eq!(std::fs::read_to_string(&log)?, "prepared\naborted\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(all(unix, feature = "worktree-mutation"))]
+fn post_checkout_runs_after_adding_a_worktree() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    let log = repo.git_dir().join("checkout-log");
+    write_hook(
+        repo.hooks()?.dir(),
+        "post-checkout",
+        &format!("echo \"$@\" > {log:?}\nexit 1"),
+    )?;
+    let head = repo.head_id()?.detach();
+    let (worktree, _) = repo.worktree_add(
+        dir.path().join("linked"),
+        gix::worktree::add::Head::Detached(head),
+        gix::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert!(
+        worktree.work_dir().expect("non-bare").join("a").is_file(),
+        "the failing hook doesn't affect the outcome"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&log)?,
+        format!("{} {head} 1\n", gix_hash::Kind::Sha1.null())
+    );
+    Ok(())
+}
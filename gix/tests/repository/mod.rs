@@ -1,8 +1,14 @@
 use gix::Repository;
 
+#[cfg(feature = "bundle")]
+mod bundle;
 mod config;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "gc")]
+mod gc;
+#[cfg(feature = "attributes")]
+mod hooks;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
@@ -10,6 +16,12 @@ mod pathspec;
 mod reference;
 mod remote;
 mod shallow;
+#[cfg(feature = "attributes")]
+mod sign;
+#[cfg(feature = "worktree-mutation")]
+mod sparse;
+#[cfg(feature = "attributes")]
+mod stage;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
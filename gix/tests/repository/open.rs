@@ -187,6 +187,172 @@ mod not_a_repository {
     }
 }
 
+mod safe_directory {
+    use std::path::Path;
+
+    use gix_sec::Trust;
+
+    /// Open the repository at `path` as if it was owned by someone else, and fail if it isn't listed in `safe.directory`.
+    fn open_foreign(path: &Path, safe_directories: &[&str]) -> Result<gix::Repository, gix::open::Error> {
+        gix::open_opts(
+            path,
+            gix::open::Options::isolated()
+                .with(Trust::Reduced)
+                .bail_if_untrusted(true)
+                .config_overrides(safe_directories.iter().map(|dir| format!("safe.directory={dir}"))),
+        )
+    }
+
+    fn repo_dir() -> crate::Result<std::path::PathBuf> {
+        Ok(gix_path::realpath(gix_testtools::scripted_fixture_read_only(
+            "make_basic_repo.sh",
+        )?)?)
+    }
+
+    #[test]
+    fn repositories_of_other_users_are_refused_if_not_listed() -> crate::Result {
+        let dir = repo_dir()?;
+        let err = open_foreign(&dir, &[]).unwrap_err();
+        assert!(
+            matches!(&err, gix::open::Error::UnsafeGitDir { path } if path == &dir),
+            "the worktree is what identifies the repository: {err:?}"
+        );
+
+        let repo = gix::open_opts(&dir, gix::open::Options::isolated().with(Trust::Reduced))?;
+        assert_eq!(
+            repo.git_dir_trust(),
+            Trust::Reduced,
+            "without bailing, the trust is just reduced"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn listed_repositories_are_fully_trusted() -> crate::Result {
+        let dir = repo_dir()?;
+        let repo = open_foreign(&dir, &[dir.to_str().expect("valid UTF-8")])?;
+        assert_eq!(repo.git_dir_trust(), Trust::Full);
+
+        let repo = gix::open_opts(
+            &dir,
+            gix::open::Options::isolated()
+                .with(Trust::Reduced)
+                .config_overrides([format!("safe.directory={}", dir.display())]),
+        )?;
+        assert_eq!(
+            repo.git_dir_trust(),
+            Trust::Full,
+            "listed repositories are trusted even if we wouldn't bail"
+        );
+
+        let err = open_foreign(&dir, &[&format!("{}/.git", dir.display())]).unwrap_err();
+        assert!(
+            matches!(err, gix::open::Error::UnsafeGitDir { .. }),
+            "the git dir doesn't identify non-bare repositories"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bare_repositories_are_identified_by_their_git_dir() -> crate::Result {
+        let dir = repo_dir()?.join("bare.git");
+        let repo = open_foreign(&dir, &[dir.to_str().expect("valid UTF-8")])?;
+        assert_eq!(repo.git_dir_trust(), Trust::Full);
+        Ok(())
+    }
+
+    #[test]
+    fn wildcards_and_prefixes_allow_multiple_repositories() -> crate::Result {
+        let dir = repo_dir()?;
+        assert!(open_foreign(&dir, &["*"]).is_ok());
+        let parent = dir.parent().expect("present").to_str().expect("valid UTF-8");
+        assert!(open_foreign(&dir, &[&format!("{parent}/*")]).is_ok());
+        assert!(
+            open_foreign(&dir, &[&format!("{}/*", dir.display())]).is_err(),
+            "prefixes only match what's within them"
+        );
+        assert!(
+            open_foreign(&dir, &[&format!("{parent}/other")]).is_err(),
+            "other paths don't match"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_values_reset_prior_values() -> crate::Result {
+        let dir = repo_dir()?;
+        let dir_str = dir.to_str().expect("valid UTF-8");
+        assert!(open_foreign(&dir, &["*", ""]).is_err());
+        assert!(open_foreign(&dir, &[dir_str, ""]).is_err());
+        assert!(open_foreign(&dir, &["", dir_str]).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn relative_paths_are_ignored() -> crate::Result {
+        let dir = repo_dir()?;
+        let cwd = std::env::current_dir()?;
+        let relative = gix_path::realpath(&cwd)
+            .ok()
+            .and_then(|cwd| dir.strip_prefix(cwd).ok().map(ToOwned::to_owned));
+        if let Some(relative) = relative {
+            assert!(open_foreign(&dir, &[relative.to_str().expect("valid UTF-8")]).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn repository_local_configuration_is_ignored() -> crate::Result {
+        let (_repo, tmp) = crate::util::repo_rw_opts("make_basic_repo.sh", gix::open::Options::isolated())?;
+        let dir = gix_path::realpath(tmp.path())?;
+        assert!(gix_testtools::run_git(&dir, &["config", "safe.directory", "*"])?.success());
+        let err = open_foreign(&dir, &[]).unwrap_err();
+        assert!(matches!(err, gix::open::Error::UnsafeGitDir { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn listed_repositories_use_the_options_for_full_trust() -> crate::Result {
+        use gix::open::permissions::Attributes;
+
+        let (_repo, tmp) = crate::util::repo_rw_opts("make_basic_repo.sh", gix::open::Options::isolated())?;
+        let dir = gix_path::realpath(tmp.path())?;
+        let owned_by_someone_else = matches!(
+            std::process::Command::new("chown").args(["-R", "65534"]).arg(&dir).status(),
+            Ok(status) if status.success()
+        );
+        if !owned_by_someone_else || gix_sec::Trust::from_path_ownership(&dir)? == Trust::Full {
+            // Only privileged users can hand their files to someone else.
+            return Ok(());
+        }
+
+        let full = gix::open::Options::isolated().permissions(gix::open::Permissions {
+            attributes: Attributes::all(),
+            ..gix::open::Permissions::isolated()
+        });
+        let reduced = gix::open::Options::isolated().config_overrides([format!("safe.directory={}", dir.display())]);
+        let repo = gix::ThreadSafeRepository::discover_opts(
+            &dir,
+            Default::default(),
+            gix_sec::trust::Mapping { full, reduced },
+        )?
+        .to_thread_local();
+        assert_eq!(repo.git_dir_trust(), Trust::Full);
+        assert_eq!(
+            repo.open_options().permissions.attributes,
+            Attributes::all(),
+            "the permissions for fully trusted repositories are used"
+        );
+        assert_eq!(
+            repo.config_snapshot().string("safe.directory").as_deref(),
+            Some(dir.to_str().expect("valid UTF-8").into()),
+            "the configuration overrides of the caller are kept"
+        );
+        Ok(())
+    }
+}
+
 mod open_path_as_is {
 
     use crate::util::{named_subrepo_opts, repo_opts};
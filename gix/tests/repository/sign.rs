@@ -0,0 +1,242 @@
+use std::path::{Path, PathBuf};
+
+use gix::bstr::ByteSlice;
+use gix_testtools::tempfile;
+
+/// Create a throwaway SSH key in `dir` and configure the repository at `repo_dir` to sign everything with it,
+/// and `git` to accept signatures made with it. Return the path to the private key.
+fn configure_ssh_signing(repo_dir: &Path, dir: &Path) -> crate::Result<PathBuf> {
+    let key = dir.join("key");
+    let status = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "test", "-f"])
+        .arg(&key)
+        .status()?;
+    assert!(status.success(), "ssh-keygen must be able to create keys");
+    let public_key = std::fs::read_to_string(key.with_extension("pub"))?;
+    let allowed_signers = dir.join("allowed_signers");
+    std::fs::write(&allowed_signers, format!("gitoxide@localhost {public_key}"))?;
+
+    for (key, value) in [
+        ("user.name", "gitoxide"),
+        ("user.email", "gitoxide@localhost"),
+        ("gpg.format", "ssh"),
+        ("user.signingKey", key.to_str().expect("valid UTF-8")),
+        (
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().expect("valid UTF-8"),
+        ),
+        ("commit.gpgSign", "true"),
+        ("tag.gpgSign", "true"),
+    ] {
+        assert!(gix_testtools::run_git(repo_dir, &["config", key, value])?.success());
+    }
+    Ok(key)
+}
+
+fn signing_repo() -> crate::Result<(gix::Repository, tempfile::TempDir, tempfile::TempDir)> {
+    let (_repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let keys = tempfile::tempdir()?;
+    configure_ssh_signing(tmp.path(), keys.path())?;
+    let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+    Ok((repo, tmp, keys))
+}
+
+#[test]
+fn commits_are_signed_with_ssh_keys_if_configured() -> crate::Result {
+    let (repo, tmp, _keys) = signing_repo()?;
+    let head = repo.head_commit()?;
+    let commit_id = repo.commit("HEAD", "signed", head.tree_id()?, Some(head.id))?;
+    let commit = commit_id.object()?.into_commit();
+    let signature = commit
+        .decode()?
+        .extra_headers()
+        .pgp_signature()
+        .expect("signature is present")
+        .to_owned();
+    assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+    assert!(
+        signature.ends_with(b"-----END SSH SIGNATURE-----"),
+        "trailing newline is removed"
+    );
+
+    assert!(
+        gix_testtools::run_git(tmp.path(), &["verify-commit", &commit_id.to_string()])?.success(),
+        "git can verify the signature"
+    );
+    Ok(())
+}
+
+#[test]
+fn tags_are_signed_with_ssh_keys_if_configured() -> crate::Result {
+    let (repo, tmp, _keys) = signing_repo()?;
+    let tag = repo.tag(
+        "v1.0.0",
+        repo.head_id()?,
+        gix_object::Kind::Commit,
+        Some(repo.committer().expect("present")?),
+        "a message",
+        gix_ref::transaction::PreviousValue::MustNotExist,
+    )?;
+    let data = tag.id().object()?.detach().data;
+    assert!(
+        data.contains_str("\n\na message\n-----BEGIN SSH SIGNATURE-----\n"),
+        "the signature follows the message"
+    );
+    assert!(
+        gix_testtools::run_git(tmp.path(), &["verify-tag", "v1.0.0"])?.success(),
+        "git can verify the signature"
+    );
+    Ok(())
+}
+
+#[test]
+fn signing_with_literal_keys_uses_the_ssh_agent() -> crate::Result {
+    let (repo, tmp, keys) = signing_repo()?;
+    let public_key = std::fs::read_to_string(keys.path().join("key.pub"))?;
+    assert!(gix_testtools::run_git(
+        tmp.path(),
+        &["config", "user.signingKey", &format!("key::{}", public_key.trim())]
+    )?
+    .success());
+    let repo = gix::open_opts(repo.work_dir().expect("non-bare"), crate::restricted())?;
+    let err = repo.sign(b"payload").unwrap_err();
+    assert!(
+        matches!(err, gix::sign::Error::Failed { .. }),
+        "without agent holding the key, signing fails: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn unsigned_unless_configured() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let head = repo.head_commit()?;
+    let commit_id = repo.commit("HEAD", "unsigned", head.tree_id()?, Some(head.id))?;
+    assert!(commit_id
+        .object()?
+        .into_commit()
+        .decode()?
+        .extra_headers()
+        .pgp_signature()
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn programs_must_exist() -> crate::Result {
+    let (repo, tmp, _keys) = signing_repo()?;
+    assert!(gix_testtools::run_git(tmp.path(), &["config", "gpg.ssh.program", "does-not-exist"])?.success());
+    let repo = gix::open_opts(repo.work_dir().expect("non-bare"), crate::restricted())?;
+    let head = repo.head_commit()?;
+    let err = repo
+        .commit("HEAD", "signed", head.tree_id()?, Some(head.id))
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::commit::Error::Sign(gix::sign::Error::Spawn { .. })),
+        "{err:?}"
+    );
+    assert_eq!(repo.head_id()?, head.id, "nothing was committed");
+    Ok(())
+}
+
+mod verify {
+    use gix::sign::{verify, Format, Trust};
+
+    use super::signing_repo;
+
+    #[test]
+    fn commits_signed_by_allowed_signers_are_fully_trusted() -> crate::Result {
+        let (repo, _tmp, _keys) = signing_repo()?;
+        let head = repo.head_commit()?;
+        let commit = repo
+            .commit("HEAD", "signed", head.tree_id()?, Some(head.id))?
+            .object()?
+            .into_commit();
+        let verification = commit.verify_signature()?;
+        assert_eq!(verification.format, Format::Ssh);
+        assert_eq!(verification.trust, Trust::Fully);
+        assert_eq!(verification.signer.as_ref().expect("known"), "gitoxide@localhost");
+        assert!(verification
+            .fingerprint
+            .as_ref()
+            .expect("parsed from output")
+            .starts_with(b"SHA256:"));
+
+        let err = head.verify_signature().unwrap_err();
+        assert!(matches!(err, verify::Error::Unsigned), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn tags_signed_by_git_can_be_verified() -> crate::Result {
+        let (repo, tmp, _keys) = signing_repo()?;
+        assert!(gix_testtools::run_git(tmp.path(), &["tag", "-m", "signed by git", "v1.0.0"])?.success());
+        let tag = repo.find_reference("v1.0.0")?.id().object()?.into_tag();
+        let verification = tag.verify_signature()?;
+        assert_eq!(verification.trust, Trust::Fully);
+        assert_eq!(verification.signer.as_ref().expect("known"), "gitoxide@localhost");
+
+        assert!(gix_testtools::run_git(tmp.path(), &["tag", "--no-sign", "-m", "unsigned", "v2.0.0"])?.success());
+        let err = repo
+            .find_reference("v2.0.0")?
+            .id()
+            .object()?
+            .into_tag()
+            .verify_signature()
+            .unwrap_err();
+        assert!(matches!(err, verify::Error::Unsigned), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_payloads_are_invalid() -> crate::Result {
+        let (repo, _tmp, _keys) = signing_repo()?;
+        let signature = repo.sign(b"payload")?;
+        assert_eq!(
+            repo.verify_signature(signature.as_ref(), b"payload")?.trust,
+            Trust::Fully
+        );
+        let err = repo
+            .verify_signature(signature.as_ref(), b"tampered payload")
+            .unwrap_err();
+        assert!(matches!(err, verify::Error::Invalid { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_signers_have_undefined_trust_which_can_be_rejected() -> crate::Result {
+        let (repo, tmp, keys) = signing_repo()?;
+        let signature = repo.sign(b"payload")?;
+        std::fs::write(keys.path().join("allowed_signers"), "")?;
+        let verification = repo.verify_signature(signature.as_ref(), b"payload")?;
+        assert_eq!(
+            verification.trust,
+            Trust::Undefined,
+            "the signature is valid, but the key is unknown"
+        );
+        assert_eq!(verification.signer, None);
+
+        assert!(gix_testtools::run_git(tmp.path(), &["config", "gpg.minTrustLevel", "fully"])?.success());
+        let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+        let err = repo.verify_signature(signature.as_ref(), b"payload").unwrap_err();
+        assert!(
+            matches!(&err, verify::Error::Untrusted { verification, min_trust: Trust::Fully } if verification.trust == Trust::Undefined),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_signatures_require_allowed_signers() -> crate::Result {
+        let (repo, tmp, _keys) = signing_repo()?;
+        let signature = repo.sign(b"payload")?;
+        assert!(gix_testtools::run_git(tmp.path(), &["config", "--unset", "gpg.ssh.allowedSignersFile"])?.success());
+        let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+        let err = repo.verify_signature(signature.as_ref(), b"payload").unwrap_err();
+        assert!(matches!(err, verify::Error::AllowedSignersFileMissing), "{err:?}");
+
+        let err = repo.verify_signature("not a signature".into(), b"payload").unwrap_err();
+        assert!(matches!(err, verify::Error::UnknownFormat), "{err:?}");
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{
+    index::entry::Flags,
+    worktree::sparse::{reapply, set, Patterns},
+};
+
+fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_sparse_checkout_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn worktree_files(root: &Path) -> Vec<String> {
+    fn recurse(root: &Path, dir: &Path, out: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).expect("readable") {
+            let path = entry.expect("valid entry").path();
+            if path.file_name().map_or(false, |name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                recurse(root, &path, out);
+            } else {
+                out.push(
+                    path.strip_prefix(root)
+                        .expect("within root")
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                );
+            }
+        }
+    }
+    let mut out = Vec::new();
+    recurse(root, root, &mut out);
+    out.sort();
+    out
+}
+
+fn skipped_entries(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::SKIP_WORKTREE))
+        .map(|e| e.path(&index).to_string())
+        .collect())
+}
+
+fn git_considers_clean(worktree: &Path) -> bool {
+    gix_testtools::run_git(worktree, &["diff", "--quiet", "HEAD"])
+        .expect("git can be run")
+        .success()
+        && gix_testtools::run_git(worktree, &["diff", "--quiet", "--cached"])
+            .expect("git can be run")
+            .success()
+}
+
+#[test]
+fn set_and_add_in_cone_mode() -> crate::Result {
+    let (mut repo, dir) = repo_rw()?;
+    assert!(repo.sparse_checkout_patterns()?.is_none(), "disabled by default");
+
+    let out = repo.sparse_checkout_set(["c1/c2"], set::Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        out,
+        reapply::Outcome {
+            files_removed: 6,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        worktree_files(dir.path()),
+        ["a", "b", "c1/a", "c1/b", "c1/c2/a", "c1/c2/b"]
+    );
+    assert_eq!(
+        skipped_entries(&repo)?,
+        ["c1/c3/a", "c1/c3/b", "d/a", "d/b", "d/c4/a", "d/c4/b"]
+    );
+    assert_eq!(
+        std::fs::read(dir.path().join(".git/info/sparse-checkout"))?,
+        b"/*\n!/*/\n/c1/\n!/c1/*/\n/c1/c2/\n",
+        "the same patterns that git would write"
+    );
+    assert!(git_considers_clean(dir.path()));
+
+    let options = repo.sparse_checkout_options()?;
+    assert!(options.sparse_checkout && options.directory_patterns_only && !options.write_sparse_index);
+    let reopened = gix::open_opts(dir.path(), crate::restricted())?;
+    assert_eq!(
+        reopened.sparse_checkout_options()?.sparse_mode(),
+        options.sparse_mode(),
+        "the configuration was persisted"
+    );
+
+    let out = repo.sparse_checkout_add(["d/c4"], &AtomicBool::default())?;
+    assert_eq!(
+        out,
+        reapply::Outcome {
+            files_checked_out: 4,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        worktree_files(dir.path()),
+        ["a", "b", "c1/a", "c1/b", "c1/c2/a", "c1/c2/b", "d/a", "d/b", "d/c4/a", "d/c4/b"],
+        "files in parent directories are included as well"
+    );
+    assert_eq!(skipped_entries(&repo)?, ["c1/c3/a", "c1/c3/b"]);
+    assert!(git_considers_clean(dir.path()));
+    Ok(())
+}
+
+#[test]
+fn set_in_non_cone_mode() -> crate::Result {
+    let (mut repo, dir) = repo_rw()?;
+    repo.sparse_checkout_set(
+        ["/*", "!/d/", "/d/c4/"],
+        set::Options {
+            cone: false,
+            sparse_index: false,
+        },
+        &AtomicBool::default(),
+    )?;
+    assert!(matches!(repo.sparse_checkout_patterns()?, Some(Patterns::NonCone(_))));
+    assert_eq!(
+        worktree_files(dir.path()),
+        ["a", "b", "c1/a", "c1/b", "c1/c2/a", "c1/c2/b", "c1/c3/a", "c1/c3/b", "d/c4/a", "d/c4/b"]
+    );
+    assert_eq!(skipped_entries(&repo)?, ["d/a", "d/b"]);
+    assert!(git_considers_clean(dir.path()));
+    Ok(())
+}
+
+#[test]
+fn modified_and_untracked_files_are_kept() -> crate::Result {
+    let (mut repo, dir) = repo_rw()?;
+    std::fs::write(dir.path().join("d/a"), "modified")?;
+
+    let out = repo.sparse_checkout_set(None::<&str>, set::Options::default(), &AtomicBool::default())?;
+    assert_eq!(out.modified_files_kept, ["d/a"]);
+    assert_eq!(out.files_removed, 9);
+    assert_eq!(worktree_files(dir.path()), ["a", "b", "d/a"]);
+    assert!(
+        !skipped_entries(&repo)?.contains(&"d/a".to_string()),
+        "modified files are not marked to skip the worktree"
+    );
+
+    std::fs::create_dir(dir.path().join("c1"))?;
+    std::fs::write(dir.path().join("c1/a"), "untracked")?;
+    let out = repo.sparse_checkout_add(["c1"], &AtomicBool::default())?;
+    assert_eq!(out.existing_files_kept, ["c1/a"]);
+    assert_eq!(out.files_checked_out, 5);
+    assert_eq!(std::fs::read(dir.path().join("c1/a"))?, b"untracked");
+    assert!(skipped_entries(&repo)?.contains(&"c1/a".to_string()));
+    Ok(())
+}
+
+#[test]
+fn sparse_index_is_written_in_cone_mode() -> crate::Result {
+    let (mut repo, dir) = repo_rw()?;
+    repo.sparse_checkout_set(
+        ["c1/c2"],
+        set::Options {
+            cone: true,
+            sparse_index: true,
+        },
+        &AtomicBool::default(),
+    )?;
+
+    let index = repo.open_index()?;
+    assert!(index.is_sparse());
+    assert_eq!(
+        skipped_entries(&repo)?,
+        ["c1/c3/", "d/"],
+        "these are sparse directories"
+    );
+    assert!(git_considers_clean(dir.path()));
+
+    let out = repo.sparse_checkout_add(["d"], &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 4, "sparse directories are expanded as needed");
+    assert_eq!(skipped_entries(&repo)?, ["c1/c3/"]);
+    assert!(git_considers_clean(dir.path()));
+    Ok(())
+}
+
+#[test]
+fn patterns_written_by_git_are_understood() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    assert!(
+        gix_testtools::run_git(dir.path(), &["update-index", "--refresh", "-q"])?.success(),
+        "git doesn't consider files with outdated stat information unmodified here"
+    );
+    assert!(gix_testtools::run_git(dir.path(), &["sparse-checkout", "set", "c1/c3", "d"])?.success());
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let Some(Patterns::Cone(cone)) = repo.sparse_checkout_patterns()? else {
+        panic!("git uses cone mode by default")
+    };
+    assert_eq!(cone.directories().collect::<Vec<_>>(), ["c1/c3", "d"]);
+
+    let out = repo.sparse_checkout_reapply(&AtomicBool::default())?;
+    assert_eq!(out, reapply::Outcome::default(), "git already applied the patterns");
+    Ok(())
+}
+
+#[test]
+fn reapply_needs_sparse_checkout_to_be_enabled() -> crate::Result {
+    let (repo, _dir) = repo_rw()?;
+    assert!(matches!(
+        repo.sparse_checkout_reapply(&AtomicBool::default()),
+        Err(reapply::Error::NotEnabled)
+    ));
+    Ok(())
+}
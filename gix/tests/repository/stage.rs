@@ -0,0 +1,228 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::stage::{add, remove, Error};
+
+fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_stage_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn git_status(worktree: &Path) -> String {
+    let out = std::process::Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(worktree)
+        .output()
+        .expect("git can be run");
+    assert!(out.status.success());
+    String::from_utf8(out.stdout).expect("valid UTF-8")
+}
+
+#[test]
+fn add_stages_new_modified_and_deleted_files() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    std::fs::write(dir.path().join("a"), "changed")?;
+    std::fs::remove_file(dir.path().join("dir/b"))?;
+    std::fs::write(dir.path().join("new"), "new")?;
+    std::fs::create_dir(dir.path().join("dir2"))?;
+    std::fs::write(dir.path().join("dir2/c"), "c")?;
+    std::fs::write(dir.path().join("ignored-file"), "ignored")?;
+
+    let out = repo.stage_add(None::<&str>, add::Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        out,
+        add::Outcome {
+            entries_added: 2,
+            entries_updated: 1,
+            entries_removed: 1,
+        }
+    );
+    assert_eq!(
+        git_status(dir.path()),
+        "M  a\nD  dir/b\nA  dir2/c\nA  new\n",
+        "git sees all changes as staged, and doesn't see the ignored file"
+    );
+
+    let out = repo.stage_add(None::<&str>, add::Options::default(), &AtomicBool::default())?;
+    assert_eq!(out, add::Outcome::default(), "nothing changed anymore");
+    Ok(())
+}
+
+#[test]
+fn add_respects_pathspecs_and_options() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    std::fs::write(dir.path().join("a"), "changed")?;
+    std::fs::write(dir.path().join("dir/new"), "new")?;
+    std::fs::write(dir.path().join("new"), "new")?;
+    std::fs::write(dir.path().join("ignored-file"), "ignored")?;
+
+    let out = repo.stage_add(Some("dir"), add::Options::default(), &AtomicBool::default())?;
+    assert_eq!(out.entries_added, 1, "only dir/new matches the pathspec");
+    assert_eq!(git_status(dir.path()), " M a\nA  dir/new\n?? new\n");
+
+    let out = repo.stage_add(
+        None::<&str>,
+        add::Options {
+            update_only: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        out,
+        add::Outcome {
+            entries_updated: 1,
+            ..Default::default()
+        },
+        "untracked files aren't added"
+    );
+
+    let out = repo.stage_add(
+        Some("ignored-file"),
+        add::Options {
+            include_ignored: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.entries_added, 1, "ignored files can be forced");
+    assert_eq!(git_status(dir.path()), "M  a\nA  dir/new\nA  ignored-file\n?? new\n");
+    Ok(())
+}
+
+#[test]
+fn add_applies_clean_filters_and_file_modes() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    std::fs::write(dir.path().join("crlf"), "a\r\nb\r\n")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.path().join("a");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    repo.stage_add(None::<&str>, add::Options::default(), &AtomicBool::default())?;
+    let index = repo.open_index()?;
+    let entry = index.entry_by_path("crlf".into()).expect("added");
+    assert_eq!(
+        repo.find_object(entry.id)?.data,
+        b"a\nb\n",
+        "core.autocrlf=input converts line endings"
+    );
+    if cfg!(unix) {
+        assert_eq!(
+            index.entry_by_path("a".into()).expect("present").mode,
+            gix::index::entry::Mode::FILE_EXECUTABLE
+        );
+    }
+    assert_eq!(
+        git_status(dir.path()),
+        if cfg!(unix) { "M  a\nA  crlf\n" } else { "A  crlf\n" }
+    );
+    Ok(())
+}
+
+#[test]
+fn remove_deletes_entries_and_files() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    assert!(matches!(
+        repo.stage_remove(None::<&str>, remove::Options::default()),
+        Err(Error::NoPathspec)
+    ));
+
+    let out = repo.stage_remove(Some("dir"), remove::Options::default())?;
+    assert_eq!(
+        out,
+        remove::Outcome {
+            entries_removed: 1,
+            files_removed: 1,
+        }
+    );
+    assert!(!dir.path().join("dir").exists(), "empty directories are removed");
+
+    let out = repo.stage_remove(
+        Some("a"),
+        remove::Options {
+            cached: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.files_removed, 0);
+    assert!(dir.path().join("a").is_file(), "the file is kept");
+    assert_eq!(git_status(dir.path()), "D  a\nD  dir/b\n?? a\n");
+    Ok(())
+}
+
+#[test]
+fn remove_refuses_to_delete_modified_files_unless_forced() -> crate::Result {
+    let (repo, dir) = repo_rw()?;
+    std::fs::write(dir.path().join("a"), "changed")?;
+
+    let err = repo.stage_remove(["a", "exe"], remove::Options::default()).unwrap_err();
+    assert!(matches!(err, Error::LocalModifications { ref paths } if paths == &["a"]));
+    assert_eq!(git_status(dir.path()), " M a\n", "nothing was changed");
+
+    let out = repo.stage_remove(
+        ["a", "exe"],
+        remove::Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.files_removed, 2);
+    assert_eq!(git_status(dir.path()), "D  a\nD  exe\n");
+    Ok(())
+}
+
+#[test]
+fn add_writes_a_split_index_if_configured() -> crate::Result {
+    let (_repo, dir) = repo_rw()?;
+    assert!(gix_testtools::run_git(dir.path(), &["config", "core.splitIndex", "true"])?.success());
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    std::fs::write(dir.path().join("new"), "new")?;
+
+    repo.stage_add(["new"], add::Options::default(), &AtomicBool::default())?;
+    let index = repo.open_index()?;
+    let shared_index = index.shared_index().expect("index was written as split index");
+    assert!(shared_index.path().is_file());
+    assert_eq!(
+        git_status(dir.path()),
+        "A  new\n",
+        "git can read the split index and sees the staged file"
+    );
+    Ok(())
+}
+
+#[test]
+fn add_writes_new_indices_with_the_configured_version() -> crate::Result {
+    let (_repo, dir) = repo_rw()?;
+    assert!(gix_testtools::run_git(dir.path(), &["config", "index.version", "4"])?.success());
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    std::fs::write(dir.path().join("new"), "new")?;
+
+    repo.stage_add(["new"], add::Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        repo.open_index()?.version(),
+        gix_index::Version::V2,
+        "existing indices keep their version"
+    );
+
+    std::fs::remove_file(repo.index_path())?;
+    repo.stage_add(["new"], add::Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        repo.open_index()?.version(),
+        gix_index::Version::V4,
+        "new indices are written with the configured version"
+    );
+    let out = std::process::Command::new("git")
+        .args(["ls-files"])
+        .current_dir(dir.path())
+        .output()?;
+    assert!(out.status.success(), "git can read the V4 index");
+    assert_eq!(out.stdout.as_slice(), b"new\n");
+    Ok(())
+}
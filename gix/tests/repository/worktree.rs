@@ -336,3 +336,400 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod mutation {
+    use std::path::Path;
+
+    use gix::worktree::prune;
+
+    fn repo_rw() -> crate::Result<Option<(gix::Repository, gix_testtools::tempfile::TempDir)>> {
+        if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+            return Ok(None);
+        }
+        let dir = gix_testtools::scripted_fixture_writable_with_args(
+            "make_worktree_repo.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let repo = gix::open_opts(dir.path().join("repo"), crate::restricted())?;
+        Ok(Some((repo, dir)))
+    }
+
+    fn proxy_by_id<'repo>(repo: &'repo gix::Repository, id: &str) -> gix::worktree::Proxy<'repo> {
+        repo.worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|proxy| proxy.id() == id)
+            .expect("worktree exists")
+    }
+
+    fn git_considers_clean(worktree: &Path) -> bool {
+        gix_testtools::run_git(worktree, &["diff-index", "--quiet", "HEAD"])
+            .expect("git can be run")
+            .success()
+            && gix_testtools::run_git(worktree, &["diff-files", "--quiet"])
+                .expect("git can be run")
+                .success()
+    }
+
+    #[cfg(feature = "worktree-mutation")]
+    mod add {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::worktree::add::{Error, Head, Options};
+
+        use super::{git_considers_clean, repo_rw};
+
+        #[test]
+        fn new_branch_with_checkout() -> crate::Result {
+            let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+            let path = tmp.path().join("wt-new");
+            let head_id = repo.head_id()?.detach();
+            let (wt_repo, outcome) = repo.worktree_add(
+                &path,
+                Head::NewBranch {
+                    name: "refs/heads/new".try_into()?,
+                    commit: head_id,
+                    force: false,
+                },
+                gix::progress::Discard,
+                &AtomicBool::default(),
+                Options::default(),
+            )?;
+            assert_eq!(outcome.files_updated, 3, "all files of the tree were checked out");
+            assert!(path.join("dir").join("c").is_file());
+            assert_eq!(wt_repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/new");
+            assert_eq!(wt_repo.head_id()?, head_id);
+            assert_eq!(wt_repo.index()?.entries().len(), 3, "the index was written");
+            assert_eq!(repo.find_reference("new")?.id(), head_id, "the branch was created");
+
+            let proxy = super::proxy_by_id(&repo, "wt-new");
+            assert!(!proxy.is_locked(), "the initialization lock is removed");
+            assert_eq!(proxy.base()?, gix_path::realpath(&path)?);
+            assert_eq!(wt_repo.worktree().expect("linked").id(), Some("wt-new".into()));
+            assert!(git_considers_clean(&path), "git sees the worktree as clean");
+            Ok(())
+        }
+
+        #[test]
+        fn detached_without_checkout_and_locked() -> crate::Result {
+            let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+            let path = tmp.path().join("other").join("wt-a");
+            let head_id = repo.head_id()?.detach();
+            let (wt_repo, outcome) = repo.worktree_add(
+                &path,
+                Head::Detached(head_id),
+                gix::progress::Discard,
+                &AtomicBool::default(),
+                Options {
+                    lock: Some("on usb stick".into()),
+                    no_checkout: true,
+                    force: false,
+                },
+            )?;
+            assert_eq!(outcome.files_updated, 0);
+            assert!(!path.join("a").exists(), "nothing was checked out");
+            assert!(wt_repo.head()?.is_detached());
+            assert_eq!(wt_repo.head_id()?, head_id);
+
+            let proxy = super::proxy_by_id(&repo, "wt-a2");
+            assert_eq!(
+                proxy.lock_reason().expect("locked"),
+                "on usb stick",
+                "the id is made unique as 'wt-a' and 'wt-a1' already exist"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn refusals_and_force() -> crate::Result {
+            let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+            let head_id = repo.head_id()?.detach();
+            let add = |path: &std::path::Path, head: Head, force: bool| {
+                repo.worktree_add(
+                    path,
+                    head,
+                    gix::progress::Discard,
+                    &AtomicBool::default(),
+                    Options {
+                        force,
+                        ..Default::default()
+                    },
+                )
+                .map(|_| ())
+            };
+
+            assert!(matches!(
+                add(&tmp.path().join("wt-a"), Head::Detached(head_id), false),
+                Err(Error::PathExists { .. })
+            ));
+            assert!(matches!(
+                add(
+                    &tmp.path().join("wt-x"),
+                    Head::Branch("refs/heads/wt-a".try_into()?),
+                    false
+                ),
+                Err(Error::BranchCheckedOut { .. })
+            ));
+            assert!(matches!(
+                add(
+                    &tmp.path().join("wt-x"),
+                    Head::Branch("refs/heads/does-not-exist".try_into()?),
+                    false
+                ),
+                Err(Error::BranchNotFound { .. })
+            ));
+            assert!(!tmp.path().join("wt-x").exists(), "nothing is left on failure");
+
+            let deleted = tmp.path().join("wt-deleted");
+            assert!(matches!(
+                add(&deleted, Head::Detached(head_id), false),
+                Err(Error::PathRegistered { .. })
+            ));
+            add(&deleted, Head::Detached(head_id), true)?;
+            assert!(deleted.join("a").is_file());
+            assert!(super::git_considers_clean(&deleted));
+            Ok(())
+        }
+
+        #[test]
+        fn new_branch_is_not_left_behind_on_failure() -> crate::Result {
+            let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+            let head_id = repo.head_id()?.detach();
+            let blob_id = repo
+                .head_commit()?
+                .tree()?
+                .find_entry("a")
+                .expect("present")
+                .object_id();
+            let path = tmp.path().join("wt-x");
+            let err = repo
+                .worktree_add(
+                    &path,
+                    Head::NewBranch {
+                        name: "refs/heads/new".try_into()?,
+                        commit: blob_id,
+                        force: false,
+                    },
+                    gix::progress::Discard,
+                    &AtomicBool::default(),
+                    Options::default(),
+                )
+                .unwrap_err();
+            assert!(matches!(err, Error::PeelToTree { .. }));
+            assert!(repo.try_find_reference("new")?.is_none(), "the branch isn't created");
+            assert!(!path.exists(), "nothing is left on failure");
+
+            let broken_commit_id = repo.write_object(&gix::objs::Commit {
+                tree: repo
+                    .write_object(&gix::objs::Tree {
+                        entries: vec![gix::objs::tree::Entry {
+                            mode: gix::objs::tree::EntryKind::Blob.into(),
+                            filename: "missing".into(),
+                            oid: gix::hash::ObjectId::from_hex(b"1111111111111111111111111111111111111111")?,
+                        }],
+                    })?
+                    .detach(),
+                parents: Default::default(),
+                author: Default::default(),
+                committer: Default::default(),
+                encoding: None,
+                message: "the blob of this tree is missing".into(),
+                extra_headers: Vec::new(),
+            })?;
+            repo.reference(
+                "refs/heads/spare",
+                head_id,
+                gix::refs::transaction::PreviousValue::MustNotExist,
+                "create spare",
+            )?;
+            for (name, force) in [("refs/heads/new", false), ("refs/heads/spare", true)] {
+                let err = repo
+                    .worktree_add(
+                        &path,
+                        Head::NewBranch {
+                            name: name.try_into()?,
+                            commit: broken_commit_id.detach(),
+                            force,
+                        },
+                        gix::progress::Discard,
+                        &AtomicBool::default(),
+                        Options::default(),
+                    )
+                    .unwrap_err();
+                assert!(matches!(err, Error::IndexCheckout(_)), "{err:?}");
+                assert!(!path.exists(), "nothing is left on failure");
+            }
+            assert!(
+                repo.try_find_reference("new")?.is_none(),
+                "the new branch was deleted after the checkout failed"
+            );
+            assert_eq!(
+                repo.find_reference("spare")?.id(),
+                head_id,
+                "the forcefully reset branch was restored"
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn lock_and_unlock() -> crate::Result {
+        let Some((repo, _tmp)) = repo_rw()? else { return Ok(()) };
+        let proxy = proxy_by_id(&repo, "wt-b");
+        assert!(!proxy.is_locked());
+        proxy.lock(Some("reason".into()))?;
+        assert_eq!(proxy.lock_reason().expect("locked"), "reason");
+        assert!(matches!(
+            proxy.lock(None),
+            Err(gix::worktree::proxy::lock::Error::AlreadyLocked { reason }) if reason == "reason"
+        ));
+        assert!(proxy.unlock()?);
+        assert!(!proxy.is_locked());
+        assert!(!proxy.unlock()?, "it's not an error to unlock an unlocked worktree");
+        Ok(())
+    }
+
+    #[test]
+    fn prune() -> crate::Result {
+        let Some((repo, _tmp)) = repo_rw()? else { return Ok(()) };
+        let num_worktrees = repo.worktrees()?.len();
+        assert_eq!(
+            proxy_by_id(&repo, "wt-deleted").prunable(None),
+            Some(prune::Reason::GitDirFilePointsToNonExistentLocation)
+        );
+        assert_eq!(proxy_by_id(&repo, "wt-c-locked").prunable(None), None);
+        assert_eq!(proxy_by_id(&repo, "wt-a").prunable(None), None);
+
+        let pruned = repo.worktree_prune(prune::Options {
+            expire: Some(std::time::SystemTime::UNIX_EPOCH),
+            dry_run: false,
+        })?;
+        assert!(pruned.is_empty(), "the gitdir file is too new to be pruned");
+
+        let pruned = repo.worktree_prune(prune::Options {
+            expire: None,
+            dry_run: true,
+        })?;
+        assert_eq!(
+            pruned,
+            vec![prune::Pruned {
+                id: "wt-deleted".into(),
+                reason: prune::Reason::GitDirFilePointsToNonExistentLocation
+            }]
+        );
+        assert_eq!(repo.worktrees()?.len(), num_worktrees, "dry-runs don't change anything");
+
+        let pruned_for_real = repo.worktree_prune(Default::default())?;
+        assert_eq!(pruned_for_real, pruned);
+        assert_eq!(repo.worktrees()?.len(), num_worktrees - 1);
+        assert!(!repo.common_dir().join("worktrees").join("wt-deleted").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn move_to() -> crate::Result {
+        let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+        let proxy = proxy_by_id(&repo, "wt-c-locked");
+        assert!(matches!(
+            proxy.move_to(tmp.path().join("somewhere")),
+            Err(gix::worktree::proxy::move_to::Error::Locked { .. })
+        ));
+
+        let proxy = proxy_by_id(&repo, "wt-b");
+        assert!(matches!(
+            proxy.move_to(tmp.path().join("wt-a").join("a")),
+            Err(gix::worktree::proxy::move_to::Error::DestinationExists { .. })
+        ));
+        let target_dir = tmp.path().join("moved");
+        std::fs::create_dir(&target_dir)?;
+        proxy.move_to(&target_dir)?;
+        let new_base = target_dir.join("wt-b");
+        assert_eq!(proxy.base()?, gix_path::realpath(&new_base)?);
+        assert!(new_base.join("a").is_file());
+        assert!(git_considers_clean(&new_base));
+        Ok(())
+    }
+
+    #[test]
+    fn repair() -> crate::Result {
+        use gix::worktree::proxy::repair::Reason;
+        let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+        let proxy = proxy_by_id(&repo, "wt-b");
+        assert_eq!(proxy.repair()?, None, "nothing to repair");
+
+        let dot_git = tmp.path().join("wt-b").join(".git");
+        std::fs::write(&dot_git, "garbage")?;
+        assert_eq!(proxy.repair()?, Some(Reason::DotGitFileBroken));
+        std::fs::remove_file(&dot_git)?;
+        assert_eq!(
+            repo.worktree_repair(None::<&Path>)?,
+            vec![("wt-b".into(), Reason::DotGitFileMissing)]
+        );
+        assert!(git_considers_clean(&tmp.path().join("wt-b")));
+
+        let moved = tmp.path().join("wt-b-moved-manually");
+        std::fs::rename(tmp.path().join("wt-b"), &moved)?;
+        assert!(
+            proxy.base()?.file_name().expect("present") == "wt-b",
+            "still points to the old location"
+        );
+        assert_eq!(
+            repo.worktree_repair(Some(&moved))?,
+            vec![("wt-b".into(), Reason::GitDirFileIncorrect)]
+        );
+        assert_eq!(proxy.base()?, gix_path::realpath(&moved)?);
+        assert!(git_considers_clean(&moved));
+
+        assert!(matches!(
+            repo.worktree_repair(Some(tmp.path().join("repo"))),
+            Err(gix::worktree::proxy::repair::Error::NotALinkedWorktree { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "status", feature = "attributes"))]
+    fn remove() -> crate::Result {
+        use gix::worktree::proxy::remove::{Error, Options};
+        let Some((repo, tmp)) = repo_rw()? else { return Ok(()) };
+
+        let base = tmp.path().join("wt-b");
+        proxy_by_id(&repo, "wt-b").remove(Options::default())?;
+        assert!(!base.exists(), "the checkout was removed");
+        assert!(!repo.common_dir().join("worktrees").join("wt-b").exists());
+
+        let base = tmp.path().join("prev").join("wt-a");
+        std::fs::write(base.join("a"), "modified")?;
+        assert!(matches!(
+            proxy_by_id(&repo, "wt-a1").remove(Options::default()),
+            Err(Error::Modified { .. })
+        ));
+        proxy_by_id(&repo, "wt-a1").remove(Options {
+            force: true,
+            ..Default::default()
+        })?;
+        assert!(!base.exists());
+
+        assert!(matches!(
+            proxy_by_id(&repo, "wt-c-locked").remove(Options {
+                force: true,
+                ..Default::default()
+            }),
+            Err(Error::Locked { .. })
+        ));
+        proxy_by_id(&repo, "wt-c-locked").remove(Options {
+            force_locked: true,
+            ..Default::default()
+        })?;
+        assert!(!tmp.path().join("wt-c-locked").exists());
+
+        proxy_by_id(&repo, "wt-deleted").remove(Options::default())?;
+        assert_eq!(
+            repo.worktrees()?.len(),
+            2,
+            "only wt-a and nested-wt-b are left, missing checkouts can be removed"
+        );
+        Ok(())
+    }
+}
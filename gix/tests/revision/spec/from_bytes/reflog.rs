@@ -1,7 +1,4 @@
-use gix::{
-    prelude::ObjectIdExt,
-    revision::{spec::parse::Error, Spec},
-};
+use gix::{prelude::ObjectIdExt, revision::Spec};
 
 use crate::{
     revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo},
@@ -78,10 +75,29 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
+fn by_date() {
     let repo = repo("complex_graph").unwrap();
-    assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
-    ));
+
+    for (spec, expected) in [
+        ("main@{2.weeks.ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        (
+            "main@{2005-04-07 22:20:00 +0000}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        (
+            "main@{2005-04-07 22:15:13 +0000}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        (
+            "main@{1979-02-26 18:30:00 +0000}",
+            "9f9eac6bd1cd4b4cc6a494f044b28c985a22972b",
+        ),
+    ] {
+        let spec = parse_spec_no_baseline(spec, &repo).unwrap_or_else(|err| panic!("{spec} to parse: {err}"));
+        assert_eq!(spec, Spec::from_id(hex_to_id(expected).attach(&repo)));
+        assert_eq!(
+            spec.first_reference().map(|r| r.name.to_string()),
+            Some("refs/heads/main".into())
+        );
+    }
 }
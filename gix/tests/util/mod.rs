@@ -80,3 +80,46 @@ pub fn basic_repo() -> Result<Repository> {
 pub fn basic_rw_repo() -> Result<(Repository, tempfile::TempDir)> {
     repo_rw("make_basic_repo.sh")
 }
+
+/// Serve the files in `root` over HTTP on a random local port in a background thread, and return the base URL to reach them.
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+pub fn serve_files(root: impl Into<std::path::PathBuf>) -> std::io::Result<String> {
+    use std::io::{BufRead, Write};
+    let root = root.into();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let mut stream = std::io::BufReader::new(stream);
+            let request = (&mut stream)
+                .lines()
+                .map_while(std::result::Result::ok)
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            let path = request
+                .first()
+                .and_then(|line| line.split(' ').nth(1))
+                .and_then(|path| path.split('?').next())
+                .unwrap_or_default()
+                .trim_start_matches('/');
+            let response = match std::fs::read(root.join(path)) {
+                Ok(data) => [
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        data.len()
+                    )
+                    .into_bytes(),
+                    data,
+                ]
+                .concat(),
+                Err(_) => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+            };
+            stream.get_mut().write_all(&response).ok();
+        }
+    });
+    Ok(url)
+}
@@ -3,6 +3,11 @@
 mod porcelain;
 
 fn main() -> anyhow::Result<()> {
+    // The credential cache helper starts this binary again to keep credentials in memory.
+    #[cfg(unix)]
+    if let Some(res) = gix::credentials::program::cache::daemon::serve_if_requested() {
+        return Ok(res?);
+    }
     porcelain::main()
 }
 
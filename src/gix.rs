@@ -6,6 +6,11 @@ use anyhow::Result;
 
 #[cfg(feature = "pretty-cli")]
 fn main() -> Result<()> {
+    // The credential cache helper starts this binary again to keep credentials in memory.
+    #[cfg(unix)]
+    if let Some(res) = gix::credentials::program::cache::daemon::serve_if_requested() {
+        return Ok(res?);
+    }
     plumbing::main()
 }
 
@@ -1100,14 +1100,31 @@ pub fn main() -> Result<()> {
                 move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
             ),
         },
-        Subcommands::Fsck(fsck::Platform { spec }) => prepare_and_run(
+        Subcommands::Fsck(fsck::Platform {
+            spec,
+            unreachable,
+            no_dangling,
+            lost_found,
+        }) => prepare_and_run(
             "fsck",
             trace,
             auto_verbose,
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
+            move |_progress, out, _err| {
+                core::repository::fsck(
+                    repository(Mode::Strict)?,
+                    core::repository::fsck::Options {
+                        spec,
+                        unreachable,
+                        dangling: !no_dangling,
+                        lost_found,
+                    },
+                    out,
+                    &should_interrupt,
+                )
+            },
         ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
@@ -494,8 +494,17 @@ pub mod odb {
 pub mod fsck {
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
-        /// A revspec to start the connectivity check from.
+        /// A revspec to start the connectivity check from, instead of all references, their reflogs and the index.
         pub spec: Option<String>,
+        /// Print objects that exist but aren't reachable from any starting point.
+        #[clap(long)]
+        pub unreachable: bool,
+        /// Don't print dangling objects, i.e. unreachable objects not referenced by any other object.
+        #[clap(long)]
+        pub no_dangling: bool,
+        /// Write dangling objects into `.git/lost-found/commit` or `.git/lost-found/other`, depending on their kind.
+        #[clap(long)]
+        pub lost_found: bool,
     }
 }
 
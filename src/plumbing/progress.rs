@@ -142,14 +142,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.protectNTFS",
         usage: NotPlanned { reason: "lack of demand"},
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned { note: Some("we want to support huge repos and be the fastest in doing so") },
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned { note: Some("this is a nice improvement over spareCheckout alone and should one day be available too") },
-    },
     Record {
         config: "core.gitProxy",
         usage: NotPlanned { reason: "the transport mechanism works differently enough to not support it for now, but of course it's possible to add support if there is demand" },
@@ -170,10 +162,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "checkout.thresholdForParallelism",
         usage: NotApplicable {reason: "parallelism is efficient enough to always run with benefit"},
     },
-    Record {
-        config: "feature.manyFiles",
-        usage: Planned {note: Some("big repositories are on the roadmap")},
-    },
     Record {
         config: "core.preloadIndex",
         usage: Planned {note: Some("it's enabled by default and allows parallel stat checks - it's using a lot of CPU for just minor performance boosts though")},
@@ -190,10 +178,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "commitGraph.readChangedPaths",
         usage: NotPlanned { reason: "can be considered when the underlying feature is actually used or needed" },
     },
-    Record {
-        config: "index.sparse",
-        usage: Planned {note: Some("we can read sparse indices and support for it will be added early on")},
-    },
     Record {
         config: "merge.renormalize",
         usage: Planned {note: Some("once merging is being implemented, renormalization should be respected")},
@@ -259,7 +243,7 @@ static GIT_CONFIG: &[Record] = &[
     Record {
     config: "clone.filterSubmodules,",
         usage: Planned {
-            note: Some("currently object filtering isn't support, a prerequisite for this, see --filter=blob:none for more"),
+            note: Some("object filtering is supported, but it's not yet passed on to submodules as they aren't cloned yet"),
         },
     },
     Record {
@@ -330,34 +314,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "advice.updateSparsePath",
         usage: NotApplicable { reason: "gitoxide does not yet have an 'advice' system" },
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned { note: Some("together with 'index.sparse' and 'core.sparseCheckoutCone', configures if the index should be written sparse or not") },
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned { note: Some("non-cone mode is deprecated but should still fail gracefully if encountered") },
-    },
-    Record {
-        config: "core.splitIndex",
-        usage: NotPlanned { reason: "we don't want to be able to create split indices, but we will read them. It's (somewhat) superseded by sparse indices" },
-    },
-    Record {
-        config: "splitIndex.maxPercentageChange",
-        usage: NotPlanned { reason: "seems like it's superseded by sparse indices" },
-    },
-    Record {
-        config: "splitIndex.sharedIndexExpire",
-        usage: NotPlanned { reason: "seems like it's superseded by sparse indices" },
-    },
-    Record {
-        config: "index.sparse",
-        usage: Planned { note: Some("together with 'core.sparseCheckout' and 'core.sparseCheckoutCone', configures if the index should be written sparse or not") },
-    },
-    Record {
-        config: "index.version",
-        usage: Planned { note: Some("once V4 indices can be written, we need to be able to set a desired version. For now we write the smallest possible index version only.") },
-    },
     Record {
         config: "http.<url>.*",
         usage: Planned { note: Some("definitely needed for correctness, testing against baseline is a must") }
@@ -466,18 +422,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "sparse.expectFilesOutsideOfPatterns",
         usage: NotPlanned { reason: "todo" },
     },
-    Record {
-        config: "remote.<name>.promisor",
-        usage: Planned {
-            note: Some("required for big monorepos, and typically used in conjunction with sparse indices")
-        }
-    },
-    Record {
-        config: "remote.<name>.partialCloneFilter",
-        usage: Planned {
-            note: Some("required for big monorepos, and typically used in conjunction with sparse indices")
-        }
-    },
     Record {
         config: "merge.renameLimit",
         usage: Planned { note: Some("The same as diff.renameLimit") }